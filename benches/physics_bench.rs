@@ -1,5 +1,10 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
-use particle_accelerator::{core::mesh::TriangleMesh, *};
+use particle_accelerator::{
+    collision::broadphase::SpeculativeExpansion,
+    core::{mesh::TriangleMesh, soa::BodiesSoA, types::MaterialPairProperties},
+    dynamics::{BlockSolverMode, PGSSolver},
+    *,
+};
 use std::hint::black_box;
 
 const DT: f32 = 1.0 / 60.0;
@@ -14,6 +19,54 @@ fn prepare_world(body_count: usize) -> PhysicsEngine {
     engine
 }
 
+fn prepare_broadphase_scene(count: usize) -> (BodiesSoA, Arena<Collider>) {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let side = (count as f32).cbrt().ceil() as usize;
+    for i in 0..count {
+        let mut body = RigidBody::new(EntityId::from_index(i as u32));
+        body.transform.position = Vec3::new(
+            (i % side) as f32 * 1.1,
+            ((i / side) % side) as f32 * 1.1,
+            (i / (side * side)) as f32 * 1.1,
+        );
+        let body_id = bodies.insert(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = body_id;
+        let collider_id = colliders.insert(collider);
+        colliders.get_mut(collider_id).unwrap().id = collider_id;
+    }
+    (bodies, colliders)
+}
+
+fn bench_broadphase(c: &mut Criterion) {
+    let mut group = c.benchmark_group("broadphase");
+    for &count in &[512usize, 2048] {
+        for (name, backend) in [
+            ("grid", BroadPhaseBackend::Grid),
+            ("sweep_and_prune", BroadPhaseBackend::SweepAndPrune),
+            ("incremental", BroadPhaseBackend::Incremental),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, count), &count, |b, &count| {
+                let (mut bodies, colliders) = prepare_broadphase_scene(count);
+                let mut broadphase = BroadPhase::with_backend(2.0, backend);
+                broadphase.get_potential_pairs(&colliders, &bodies);
+                let mut frame = 0u32;
+                b.iter(|| {
+                    // Small coherent drift so the sweep only needs an incremental re-sort.
+                    frame = frame.wrapping_add(1);
+                    let offset = Vec3::X * ((frame % 8) as f32 * 0.01 - 0.04);
+                    for body in bodies.iter_mut() {
+                        body.transform.position += offset;
+                    }
+                    black_box(broadphase.get_potential_pairs(&colliders, &bodies));
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
 fn bench_world_step(c: &mut Criterion) {
     let mut group = c.benchmark_group("world_step");
     for &count in &[128usize, 512, 2048] {
@@ -39,6 +92,289 @@ fn bench_world_step(c: &mut Criterion) {
     group.finish();
 }
 
+/// Rays fanned from outside the lattice of `prepare_broadphase_scene` across its front face.
+fn prepare_rays(count: usize, side: f32) -> Vec<Ray> {
+    let columns = (count as f32).sqrt().ceil() as usize;
+    (0..count)
+        .map(|i| {
+            let target = Vec3::new(
+                (i % columns) as f32 / columns as f32 * side,
+                (i / columns) as f32 / columns as f32 * side,
+                side * 0.5,
+            );
+            let origin = Vec3::new(side * 0.5, side * 0.5, -10.0);
+            Ray::new(origin, target - origin, 100.0)
+        })
+        .collect()
+}
+
+fn bench_raycast_batch(c: &mut Criterion) {
+    let mut group = c.benchmark_group("raycast_batch");
+    let (bodies, colliders) = prepare_broadphase_scene(4096);
+    let rays = prepare_rays(1024, 16.0 * 1.1);
+    let query = RaycastQuery::default();
+
+    group.bench_function("single_casts", |b| {
+        b.iter(|| {
+            for ray in &rays {
+                let single = RaycastQuery::new(ray.origin, ray.direction, ray.max_distance);
+                black_box(Raycast::cast(&single, &colliders, &bodies));
+            }
+        })
+    });
+    group.bench_function("cast_batch", |b| {
+        b.iter(|| black_box(query.cast_batch(&rays, &colliders, &bodies)))
+    });
+    let mut pipeline = QueryPipeline::new();
+    pipeline.update(&colliders, &bodies);
+    group.bench_function("pipeline_cast_batch", |b| {
+        b.iter(|| black_box(pipeline.cast_batch(&query, &rays, &colliders)))
+    });
+    group.finish();
+}
+
+/// A lattice of touching spheres without gravity, so every step finds the same pairs.
+fn prepare_sphere_lattice(count: usize) -> PhysicsWorld {
+    let mut world = PhysicsWorld::new(DT);
+    world.gravity = Vec3::ZERO;
+    let side = (count as f32).cbrt().ceil() as usize;
+    for i in 0..count {
+        let mut body = RigidBody::new(EntityId::from_index(i as u32));
+        body.transform.position = Vec3::new(
+            (i % side) as f32,
+            ((i / side) % side) as f32,
+            (i / (side * side)) as f32,
+        );
+        let id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+    }
+    world
+}
+
+/// Stands in for a GPU broad phase that has already finished: hands back the body pairs
+/// the AABB kernels would report, without any CPU work inside the step.
+struct PrecomputedPairs(Vec<(u32, u32)>);
+
+impl PrecomputedPairs {
+    fn for_state(state: &GpuWorldState) -> Self {
+        let mut pairs = Vec::new();
+        for (i, a) in state.bodies.iter().enumerate() {
+            for (j, b) in state.bodies.iter().enumerate().skip(i + 1) {
+                let reach = Vec3::splat(a.radius + b.radius);
+                if (a.position - b.position).abs().cmple(reach).all() {
+                    pairs.push((i as u32, j as u32));
+                }
+            }
+        }
+        Self(pairs)
+    }
+}
+
+impl ComputeBackend for PrecomputedPairs {
+    fn name(&self) -> &str {
+        "precomputed-pairs"
+    }
+
+    fn dispatch_broadphase(&self, _state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        Some(GpuReadback::ready(self.0.clone()))
+    }
+}
+
+/// World steps with the CPU broad phase against steps fed by a GPU backend's pairs. The
+/// sweep finds the same pairs as the AABB kernels, so both run the same narrowphase and
+/// the gap is the CPU broad phase the GPU path takes off the step, less the mapping of
+/// body pairs to collider pairs.
+fn bench_gpu_broadphase_step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("gpu_broadphase_step");
+    for &count in &[512usize, 2048] {
+        group.bench_with_input(
+            BenchmarkId::new("cpu_sweep_and_prune", count),
+            &count,
+            |b, &count| {
+                let mut world = prepare_sphere_lattice(count);
+                world.set_broadphase_backend(BroadPhaseBackend::SweepAndPrune);
+                b.iter(|| world.step(black_box(DT)))
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("gpu_pairs", count), &count, |b, &count| {
+            let mut world = prepare_sphere_lattice(count);
+            let mut state = GpuWorldState::new();
+            state.sync_bodies(
+                &world.bodies,
+                &world.colliders,
+                DT,
+                SpeculativeExpansion::default(),
+            );
+            world.set_gpu_backend(PrecomputedPairs::for_state(&state));
+            b.iter(|| world.step(black_box(DT)))
+        });
+        #[cfg(feature = "wgpu")]
+        if let Some(mut backend) = particle_accelerator::gpu::WgpuBackend::new() {
+            backend.set_broadphase_mode(particle_accelerator::gpu::BroadphaseMode::Lbvh);
+            let mut world = prepare_sphere_lattice(count);
+            world.set_gpu_backend(backend);
+            group.bench_function(BenchmarkId::new("wgpu_lbvh", count), |b| {
+                b.iter(|| world.step(black_box(DT)))
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Boxes resting on the ground with a four-point manifold each, as in a settled pile.
+fn prepare_contact_pile(count: usize) -> (BodiesSoA, Vec<Contact>) {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let material =
+        MaterialPairProperties::from_materials(&Material::default(), &Material::default());
+
+    let mut contacts = Vec::with_capacity(count * 4);
+    for i in 0..count {
+        let mut body = RigidBody::new(EntityId::default());
+        let center = Vec3::new((i % 32) as f32 * 1.1, 0.5, (i / 32) as f32 * 1.1);
+        body.transform.position = center;
+        body.velocity.linear = Vec3::new(0.0, -0.2, 0.0);
+        body.recompute_inverses();
+        let body_id = bodies.insert(body);
+        for corner in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+            contacts.push(Contact {
+                body_a: ground_id,
+                body_b: body_id,
+                point: center + Vec3::new(corner.0, -0.5, corner.1),
+                normal: Vec3::Y,
+                depth: 0.005,
+                relative_velocity: 0.0,
+                feature_id: 0,
+                material_index_a: None,
+                material_index_b: None,
+                accumulated_normal_impulse: 0.0,
+                accumulated_tangent_impulse: Vec3::ZERO,
+                accumulated_rolling_impulse: Vec3::ZERO,
+                accumulated_torsional_impulse: 0.0,
+                material,
+            });
+        }
+    }
+    (bodies, contacts)
+}
+
+/// Columns of boxes stacked `height` high, every box carrying a four-point manifold on
+/// the one below.
+fn prepare_box_stacks(columns: usize, height: usize) -> (BodiesSoA, Vec<Contact>) {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let material =
+        MaterialPairProperties::from_materials(&Material::default(), &Material::default());
+
+    let mut contacts = Vec::with_capacity(columns * height * 4);
+    for column in 0..columns {
+        let mut support = ground_id;
+        for level in 0..height {
+            let mut body = RigidBody::new(EntityId::default());
+            let center = Vec3::new(
+                (column % 32) as f32 * 1.1,
+                0.5 + level as f32,
+                (column / 32) as f32 * 1.1,
+            );
+            body.transform.position = center;
+            // Falling as after one step of gravity, with the column settling from the top.
+            body.velocity.linear = Vec3::new(0.0, -0.2 - 0.05 * level as f32, 0.0);
+            body.recompute_inverses();
+            let body_id = bodies.insert(body);
+            for corner in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+                contacts.push(Contact {
+                    body_a: support,
+                    body_b: body_id,
+                    point: center + Vec3::new(corner.0, -0.5, corner.1),
+                    normal: Vec3::Y,
+                    depth: 0.005,
+                    relative_velocity: 0.0,
+                    feature_id: 0,
+                    material_index_a: None,
+                    material_index_b: None,
+                    accumulated_normal_impulse: 0.0,
+                    accumulated_tangent_impulse: Vec3::ZERO,
+                    accumulated_rolling_impulse: Vec3::ZERO,
+                    accumulated_torsional_impulse: 0.0,
+                    material,
+                });
+            }
+            support = body_id;
+        }
+    }
+    (bodies, contacts)
+}
+
+fn bench_contact_solver(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contact_solver");
+    let solver = PGSSolver {
+        velocity_iterations: 10,
+        ..PGSSolver::new()
+    };
+    // Point-by-point sequential impulses, on one contact at a time and on four SIMD lanes.
+    let sequential = PGSSolver {
+        block_solver: BlockSolverMode::Off,
+        ..solver.clone()
+    };
+    let simd = PGSSolver {
+        simd_contacts: true,
+        ..sequential.clone()
+    };
+    for &count in &[256usize, 1024] {
+        for (name, solver) in [
+            ("pile", &solver),
+            ("pile_sequential", &sequential),
+            ("pile_simd", &simd),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, count), &count, |b, &count| {
+                let (bodies, contacts) = prepare_contact_pile(count);
+                b.iter(|| {
+                    let mut bodies = bodies.clone();
+                    let mut contacts = contacts.clone();
+                    black_box(solver.solve(&mut bodies, &[], &mut contacts, DT));
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Point-by-point sequential impulses against the four-lane SIMD path on stacked piles,
+/// where contacts between boxes stay active over the iterations.
+fn bench_contact_stacks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contact_stacks");
+    for &iterations in &[4u32, 10] {
+        let sequential = PGSSolver {
+            velocity_iterations: iterations,
+            block_solver: BlockSolverMode::Off,
+            ..PGSSolver::new()
+        };
+        let simd = PGSSolver {
+            simd_contacts: true,
+            ..sequential.clone()
+        };
+        for (name, solver) in [("sequential", &sequential), ("simd", &simd)] {
+            let (bodies, contacts) = prepare_box_stacks(128, 8);
+            group.bench_function(BenchmarkId::new(name, iterations), |b| {
+                b.iter(|| {
+                    let mut bodies = bodies.clone();
+                    let mut contacts = contacts.clone();
+                    black_box(solver.solve(&mut bodies, &[], &mut contacts, DT));
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
 fn generate_grid_mesh(resolution: usize) -> (Vec<Vec3>, Vec<[u32; 3]>) {
     let mut vertices = Vec::new();
     let mut indices = Vec::new();
@@ -134,5 +470,15 @@ fn bench_gjk(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_world_step, bench_mesh_builder, bench_gjk);
+criterion_group!(
+    benches,
+    bench_world_step,
+    bench_broadphase,
+    bench_gpu_broadphase_step,
+    bench_raycast_batch,
+    bench_mesh_builder,
+    bench_gjk,
+    bench_contact_solver,
+    bench_contact_stacks
+);
 criterion_main!(benches);
@@ -20,6 +20,8 @@ fn main() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     engine.add_collider(collider);
 
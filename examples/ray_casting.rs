@@ -10,6 +10,8 @@ fn main() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(collider);
 
@@ -0,0 +1,209 @@
+//! bevy ECS integration. Enabled with the `bevy` feature.
+//!
+//! [`PhysicsPlugin`] keeps a [`PhysicsWorld`] resource in sync with the app's entities
+//! every fixed update: bodies and colliders whose components were removed or whose
+//! entities were despawned leave the world, entities gaining a [`RigidBodyComponent`] or
+//! [`ColliderComponent`] are added to it, the world is stepped, and body poses are written
+//! back to the entities' `Transform`s. See `docs/BEVY.md`.
+
+use std::collections::HashMap;
+
+use bevy_app::{App, FixedUpdate, Plugin};
+use bevy_ecs::prelude::*;
+use bevy_time::Time;
+use bevy_transform::components::Transform;
+
+use crate::{
+    core::{collider::Collider, rigidbody::RigidBody},
+    utils::allocator::EntityId,
+    world::PhysicsWorld,
+};
+
+impl Resource for PhysicsWorld {}
+
+/// A rigid body simulated for this entity.
+///
+/// The body is copied into the [`PhysicsWorld`] when the entity is registered, posed by
+/// the entity's `Transform` if it has one. Change it afterwards through the world, using
+/// [`Self::handle`].
+#[derive(Component, Clone)]
+pub struct RigidBodyComponent {
+    body: RigidBody,
+    handle: Option<EntityId>,
+}
+
+impl RigidBodyComponent {
+    pub fn new(body: RigidBody) -> Self {
+        Self { body, handle: None }
+    }
+
+    /// Id of the body in the [`PhysicsWorld`], once [`register_bodies`] has added it.
+    pub fn handle(&self) -> Option<EntityId> {
+        self.handle
+    }
+}
+
+/// A collider attached to the rigid body of the same entity.
+#[derive(Component, Clone)]
+pub struct ColliderComponent {
+    collider: Collider,
+    handle: Option<EntityId>,
+}
+
+impl ColliderComponent {
+    pub fn new(collider: Collider) -> Self {
+        Self {
+            collider,
+            handle: None,
+        }
+    }
+
+    /// Id of the collider in the [`PhysicsWorld`], once [`register_colliders`] has added it.
+    pub fn handle(&self) -> Option<EntityId> {
+        self.handle
+    }
+}
+
+/// Bodies and colliders the plugin has added to the world, by the entity they belong to.
+#[derive(Resource, Default)]
+pub struct PhysicsEntities {
+    bodies: HashMap<Entity, EntityId>,
+    colliders: HashMap<Entity, EntityId>,
+}
+
+impl PhysicsEntities {
+    /// Id of the body added for `entity`'s [`RigidBodyComponent`].
+    pub fn body(&self, entity: Entity) -> Option<EntityId> {
+        self.bodies.get(&entity).copied()
+    }
+
+    /// Id of the collider added for `entity`'s [`ColliderComponent`].
+    pub fn collider(&self, entity: Entity) -> Option<EntityId> {
+        self.colliders.get(&entity).copied()
+    }
+}
+
+/// Adds a [`PhysicsWorld`] resource, unless the app already has one, and a
+/// [`PhysicsEntities`] resource, and runs [`sync_removals`], [`register_bodies`],
+/// [`register_colliders`], [`step_world`] and [`write_back_transforms`] in that order every
+/// `FixedUpdate`.
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<PhysicsWorld>() {
+            app.insert_resource(PhysicsWorld::builder().build());
+        }
+        app.init_resource::<PhysicsEntities>();
+        app.add_systems(
+            FixedUpdate,
+            (
+                sync_removals,
+                register_bodies,
+                register_colliders,
+                step_world,
+                write_back_transforms,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Removes the bodies and colliders of entities that lost their [`RigidBodyComponent`] or
+/// [`ColliderComponent`], including despawned ones. Removing a body removes its colliders
+/// and joints too.
+pub fn sync_removals(
+    mut world: ResMut<PhysicsWorld>,
+    mut entities: ResMut<PhysicsEntities>,
+    mut removed_bodies: RemovedComponents<RigidBodyComponent>,
+    mut removed_colliders: RemovedComponents<ColliderComponent>,
+) {
+    for entity in removed_colliders.read() {
+        if let Some(collider) = entities.colliders.remove(&entity) {
+            world.remove_collider(collider);
+        }
+    }
+    for entity in removed_bodies.read() {
+        if let Some(body) = entities.bodies.remove(&entity) {
+            world.remove_rigidbody(body);
+            // The body's colliders went with it.
+            entities.colliders.remove(&entity);
+        }
+    }
+}
+
+/// Adds the bodies of newly added [`RigidBodyComponent`]s to the world.
+pub fn register_bodies(
+    mut world: ResMut<PhysicsWorld>,
+    mut entities: ResMut<PhysicsEntities>,
+    mut bodies: Query<
+        (Entity, &mut RigidBodyComponent, Option<&Transform>),
+        Added<RigidBodyComponent>,
+    >,
+) {
+    for (entity, mut component, transform) in &mut bodies {
+        if component.handle.is_some() {
+            continue;
+        }
+        let mut body = component.body.clone();
+        if let Some(transform) = transform {
+            body.transform.position = transform.translation;
+            body.transform.rotation = transform.rotation;
+        }
+        let handle = world.add_rigidbody(body);
+        component.handle = Some(handle);
+        entities.bodies.insert(entity, handle);
+    }
+}
+
+type ColliderOrBodyAdded = Or<(Added<ColliderComponent>, Added<RigidBodyComponent>)>;
+
+/// Attaches the colliders of newly added [`ColliderComponent`]s to their entity's body,
+/// once that body is in the world.
+pub fn register_colliders(
+    mut world: ResMut<PhysicsWorld>,
+    mut entities: ResMut<PhysicsEntities>,
+    mut colliders: Query<
+        (Entity, &mut ColliderComponent, &RigidBodyComponent),
+        ColliderOrBodyAdded,
+    >,
+) {
+    for (entity, mut component, body) in &mut colliders {
+        let Some(body) = body.handle else {
+            continue;
+        };
+        if component.handle.is_some() {
+            continue;
+        }
+        let collider = Collider {
+            rigidbody_id: body,
+            ..component.collider.clone()
+        };
+        let handle = world.add_collider(collider);
+        component.handle = Some(handle);
+        entities.colliders.insert(entity, handle);
+    }
+}
+
+/// Advances the world by the fixed update's delta time.
+pub fn step_world(mut world: ResMut<PhysicsWorld>, time: Res<Time>) {
+    world.step(time.delta_secs());
+}
+
+/// Copies body poses onto the `Transform`s of their entities, leaving those of bodies
+/// that did not move unchanged.
+pub fn write_back_transforms(
+    world: Res<PhysicsWorld>,
+    mut bodies: Query<(&RigidBodyComponent, &mut Transform)>,
+) {
+    for (component, mut transform) in &mut bodies {
+        let Some(body) = component.handle.and_then(|handle| world.body(handle)) else {
+            continue;
+        };
+        let pose = body.transform();
+        if transform.translation != pose.position || transform.rotation != pose.rotation {
+            transform.translation = pose.position;
+            transform.rotation = pose.rotation;
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use glam::{Quat, Vec3};
+
+use crate::{
+    collision::contact::{ContactManifold, RawContactPoint},
+    core::{
+        collider::{Collider, ColliderShape},
+        rigidbody::RigidBody,
+        types::Transform,
+    },
+    utils::math::{closest_point_on_segment, closest_points_segments},
+};
+
+/// Golden-section steps used to find the deepest point of a segment against a box.
+const SEGMENT_SEARCH_STEPS: usize = 32;
+/// Segments closer than this to parallel get a two-point manifold.
+const PARALLEL_COSINE: f32 = 0.999;
+/// End caps whose normals agree this closely with the deepest point share its manifold.
+const SHARED_NORMAL_COSINE: f32 = 0.95;
+
+/// Whether the pair has a closed-form routine. Box/box stays on SAT.
+pub fn handles(shape_a: &ColliderShape, shape_b: &ColliderShape) -> bool {
+    let rounded = |shape: &ColliderShape| {
+        matches!(
+            shape,
+            ColliderShape::Sphere { .. } | ColliderShape::Capsule { .. }
+        )
+    };
+    let primitive =
+        |shape: &ColliderShape| rounded(shape) || matches!(shape, ColliderShape::Box { .. });
+    (rounded(shape_a) && primitive(shape_b)) || (primitive(shape_a) && rounded(shape_b))
+}
+
+/// Generates a manifold for sphere, capsule, and box pairs (except box/box). Normal points
+/// from A to B. Returns `None` for separated or unsupported pairs.
+///
+/// Spheres are capsules with a zero-length segment, so every pair reduces to
+/// segment/segment or segment/box. Parallel capsules and capsules lying on a box face
+/// report both end caps.
+pub fn collide(
+    collider_a: &Collider,
+    body_a: &RigidBody,
+    collider_b: &Collider,
+    body_b: &RigidBody,
+) -> Option<ContactManifold> {
+    let a = Primitive::from_collider(collider_a, body_a)?;
+    let b = Primitive::from_collider(collider_b, body_b)?;
+    match (a, b) {
+        (Primitive::Segment(a), Primitive::Segment(b)) => segment_segment(&a, &b),
+        (Primitive::Box(a), Primitive::Segment(b)) => segment_box(&b, &a),
+        (Primitive::Segment(a), Primitive::Box(b)) => {
+            let mut manifold = segment_box(&a, &b)?;
+            manifold.normal = -manifold.normal;
+            Some(manifold)
+        }
+        (Primitive::Box(_), Primitive::Box(_)) => None,
+    }
+}
+
+/// Swept sphere: a sphere when `start == end`, otherwise a capsule.
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    start: Vec3,
+    end: Vec3,
+    radius: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OrientedBox {
+    center: Vec3,
+    rotation: Quat,
+    half_extents: Vec3,
+}
+
+enum Primitive {
+    Segment(Segment),
+    Box(OrientedBox),
+}
+
+impl Primitive {
+    fn from_collider(collider: &Collider, body: &RigidBody) -> Option<Self> {
+        let world: Transform = collider.world_transform(&body.transform);
+        match &collider.shape {
+            ColliderShape::Sphere { radius } => Some(Self::Segment(Segment {
+                start: world.position,
+                end: world.position,
+                radius: radius.max(0.0) * world.scale.abs().max_element(),
+            })),
+            ColliderShape::Capsule { radius, height } => {
+                let cap_offset = world.rotation * (Vec3::Y * 0.5 * height * world.scale.y);
+                Some(Self::Segment(Segment {
+                    start: world.position + cap_offset,
+                    end: world.position - cap_offset,
+                    radius: radius.max(0.0) * world.scale.x.abs().max(world.scale.z.abs()),
+                }))
+            }
+            ColliderShape::Box { half_extents } => Some(Self::Box(OrientedBox {
+                center: world.position,
+                rotation: world.rotation,
+                half_extents: *half_extents * world.scale.abs(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// Contact point halfway between the two surfaces.
+fn raw_point(surface_a: Vec3, normal: Vec3, depth: f32, feature_id: u64) -> RawContactPoint {
+    RawContactPoint {
+        point: surface_a - normal * (depth * 0.5),
+        depth,
+        feature_id,
+        material_index_a: None,
+        material_index_b: None,
+    }
+}
+
+fn segment_segment(a: &Segment, b: &Segment) -> Option<ContactManifold> {
+    let (closest_a, closest_b) = closest_points_segments(a.start, a.end, b.start, b.end);
+    let offset = closest_b - closest_a;
+    let distance = offset.length();
+    let depth = a.radius + b.radius - distance;
+    if depth <= 0.0 {
+        return None;
+    }
+
+    let normal = if distance > 1e-6 {
+        offset / distance
+    } else {
+        fallback_normal(a, b)
+    };
+    let mut points = vec![raw_point(closest_a + normal * a.radius, normal, depth, 0)];
+
+    // Parallel capsules touch along a line; keep both ends of the overlap.
+    let axis_a = a.end - a.start;
+    let axis_b = b.end - b.start;
+    let length_a = axis_a.length();
+    if length_a > 1e-6 && axis_b.length() > 1e-6 {
+        let direction = axis_a / length_a;
+        if direction.dot(axis_b.normalize()).abs() > PARALLEL_COSINE {
+            let project =
+                |point: Vec3| ((point - a.start).dot(direction) / length_a).clamp(0.0, 1.0);
+            let (s0, s1) = (project(b.start), project(b.end));
+            let (s0, s1) = (s0.min(s1), s0.max(s1));
+            if (s1 - s0) * length_a > 1e-4 {
+                points.clear();
+                for (feature_id, s) in [(0, s0), (1, s1)] {
+                    let on_a = a.start + axis_a * s;
+                    let on_b = closest_point_on_segment(on_a, b.start, b.end);
+                    let depth = a.radius + b.radius - (on_b - on_a).dot(normal);
+                    if depth > 0.0 {
+                        points.push(raw_point(
+                            on_a + normal * a.radius,
+                            normal,
+                            depth,
+                            feature_id,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if points.is_empty() {
+        return None;
+    }
+    Some(ContactManifold {
+        normal,
+        points,
+        simplex: None,
+    })
+}
+
+/// Direction used when the core segments intersect and no separating offset exists.
+fn fallback_normal(a: &Segment, b: &Segment) -> Vec3 {
+    let axis_a = a.end - a.start;
+    let axis_b = b.end - b.start;
+    ((b.start + b.end - a.start - a.end) * 0.5)
+        .try_normalize()
+        .or_else(|| axis_a.cross(axis_b).try_normalize())
+        .or_else(|| {
+            axis_a
+                .try_normalize()
+                .map(|axis| axis.any_orthonormal_vector())
+        })
+        .unwrap_or(Vec3::Y)
+}
+
+/// Box surface contact for a sphere at `center` (box-local), with the normal pointing from
+/// the box towards the sphere.
+struct BoxContact {
+    surface: Vec3,
+    normal: Vec3,
+    depth: f32,
+}
+
+fn point_box_contact(center: Vec3, radius: f32, half_extents: Vec3) -> BoxContact {
+    let clamped = center.clamp(-half_extents, half_extents);
+    let offset = center - clamped;
+    let distance = offset.length();
+    if distance > 1e-6 {
+        return BoxContact {
+            surface: clamped,
+            normal: offset / distance,
+            depth: radius - distance,
+        };
+    }
+
+    // Centre inside the box: push out through the nearest face.
+    let gaps = half_extents - center.abs();
+    let axis = if gaps.x <= gaps.y && gaps.x <= gaps.z {
+        0
+    } else if gaps.y <= gaps.z {
+        1
+    } else {
+        2
+    };
+    let sign = if center[axis] >= 0.0 { 1.0 } else { -1.0 };
+    let mut normal = Vec3::ZERO;
+    normal[axis] = sign;
+    let mut surface = center;
+    surface[axis] = sign * half_extents[axis];
+    BoxContact {
+        surface,
+        normal,
+        depth: radius + gaps[axis],
+    }
+}
+
+/// Signed distance from a box-local point to the box surface.
+fn box_distance(point: Vec3, half_extents: Vec3) -> f32 {
+    let q = point.abs() - half_extents;
+    q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+}
+
+/// Manifold with the normal pointing from the box towards the segment.
+fn segment_box(segment: &Segment, obb: &OrientedBox) -> Option<ContactManifold> {
+    let inverse = obb.rotation.conjugate();
+    let start = inverse * (segment.start - obb.center);
+    let end = inverse * (segment.end - obb.center);
+    let at = |t: f32| start + (end - start) * t;
+
+    // The box distance field is convex along the segment, so golden-section search finds
+    // the deepest (or closest) point.
+    let (mut low, mut high) = (0.0f32, 1.0f32);
+    if (end - start).length_squared() > 1e-12 {
+        let ratio = 0.5 * (5.0f32.sqrt() - 1.0);
+        for _ in 0..SEGMENT_SEARCH_STEPS {
+            let t0 = high - (high - low) * ratio;
+            let t1 = low + (high - low) * ratio;
+            if box_distance(at(t0), obb.half_extents) < box_distance(at(t1), obb.half_extents) {
+                high = t1;
+            } else {
+                low = t0;
+            }
+        }
+    }
+    let deepest = point_box_contact(at(0.5 * (low + high)), segment.radius, obb.half_extents);
+    if deepest.depth <= 0.0 {
+        return None;
+    }
+
+    let to_world = |contact: &BoxContact, feature_id: u64| {
+        let normal = obb.rotation * contact.normal;
+        raw_point(
+            obb.center + obb.rotation * contact.surface,
+            normal,
+            contact.depth,
+            feature_id,
+        )
+    };
+
+    // A capsule lying on a face rests on both end caps.
+    if (end - start).length_squared() > 1e-12 {
+        let caps = [
+            point_box_contact(start, segment.radius, obb.half_extents),
+            point_box_contact(end, segment.radius, obb.half_extents),
+        ];
+        if caps
+            .iter()
+            .all(|cap| cap.depth > 0.0 && cap.normal.dot(deepest.normal) > SHARED_NORMAL_COSINE)
+        {
+            return Some(ContactManifold {
+                normal: obb.rotation * deepest.normal,
+                points: vec![to_world(&caps[0], 0), to_world(&caps[1], 1)],
+                simplex: None,
+            });
+        }
+    }
+
+    Some(ContactManifold {
+        normal: obb.rotation * deepest.normal,
+        points: vec![to_world(&deepest, 0)],
+        simplex: None,
+    })
+}
@@ -1,11 +1,16 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+type PairFilter = dyn Fn(&Collider, &Collider) -> bool + Send + Sync;
 
 use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::{DEFAULT_FAT_MARGIN, DEFAULT_SAP_MARGIN},
     core::{
         collider::{Collider, ColliderShape},
-        soa::BodiesSoA,
+        soa::{BodiesSoA, BodyRef},
     },
     utils::{
         allocator::{Arena, EntityId},
@@ -13,6 +18,24 @@ use crate::{
     },
 };
 
+/// Bounds padding for bodies with CCD enabled, so pairs they can reach within one step
+/// still reach speculative contact generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SpeculativeExpansion {
+    pub margin: f32,
+    pub time_step: f32,
+}
+
+impl SpeculativeExpansion {
+    /// Speculative margin plus one step of linear travel; zero for bodies without CCD.
+    pub fn padding(&self, body: &BodyRef) -> f32 {
+        if !body.ccd_enabled() || body.is_static() {
+            return 0.0;
+        }
+        self.margin + body.velocity().linear.length() * self.time_step
+    }
+}
+
 /// Uniform grid spatial partitioning used by the broad-phase.
 pub struct SpatialGrid {
     cell_size: f32,
@@ -27,6 +50,10 @@ impl SpatialGrid {
         }
     }
 
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
     fn world_to_grid(&self, pos: Vec3) -> (i32, i32, i32) {
         (
             (pos.x / self.cell_size).floor() as i32,
@@ -68,46 +95,786 @@ impl SpatialGrid {
         results
     }
 
-    pub fn update(&mut self, colliders: &Arena<Collider>, bodies: &BodiesSoA) {
+    pub fn update(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        expansion: SpeculativeExpansion,
+        frozen: &HashSet<EntityId>,
+    ) {
         self.grid.clear();
 
         for collider_id in colliders.ids() {
             let collider = match colliders.get(collider_id) {
-                Some(c) => c,
-                None => continue,
+                Some(c) if !c.is_unbounded() => c,
+                _ => continue,
             };
             let body = match bodies.get(collider.rigidbody_id) {
-                Some(b) => b,
-                None => continue,
+                Some(b) if !frozen.contains(&b.id()) => b,
+                _ => continue,
             };
 
             let transform = collider.world_transform(body.transform());
-            let radius = BroadPhase::get_collider_radius(&collider.shape);
+            let radius = BroadPhase::get_collider_radius(&collider.shape)
+                + collider.margin
+                + expansion.padding(&body);
             self.insert(collider.id, transform.position, radius);
         }
     }
 }
 
+/// Incremental sort-and-sweep broad-phase along a single axis.
+///
+/// Proxies stay sorted between steps, so coherent motion only needs a cheap
+/// insertion-sort pass instead of a full rebuild.
+pub struct SweepAndPrune {
+    axis: usize,
+    proxies: Vec<SapProxy>,
+    lookup: HashMap<EntityId, usize>,
+    stamp: u32,
+    /// Padding added to every proxy so nearly-touching pairs still reach the narrow-phase.
+    pub margin: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SapProxy {
+    collider: EntityId,
+    min: Vec3,
+    max: Vec3,
+    stamp: u32,
+}
+
+impl SapProxy {
+    fn overlaps(&self, other: &SapProxy) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+}
+
+impl Default for SweepAndPrune {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SweepAndPrune {
+    pub fn new() -> Self {
+        Self {
+            axis: 0,
+            proxies: Vec::new(),
+            lookup: HashMap::new(),
+            stamp: 0,
+            margin: DEFAULT_SAP_MARGIN,
+        }
+    }
+
+    /// Selects the sweep axis (0 = X, 1 = Y, 2 = Z) and re-sorts the proxies along it.
+    pub fn set_axis(&mut self, axis: usize) {
+        let axis = axis.min(2);
+        if axis != self.axis {
+            self.axis = axis;
+            self.sort(true);
+        }
+    }
+
+    pub fn axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn proxy_count(&self) -> usize {
+        self.proxies.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.proxies.clear();
+        self.lookup.clear();
+    }
+
+    /// Drops the proxy of `collider`, if it has one.
+    pub fn remove(&mut self, collider: EntityId) {
+        if self.lookup.remove(&collider).is_some() {
+            self.proxies.retain(|proxy| proxy.collider != collider);
+            self.reindex();
+        }
+    }
+
+    /// Refreshes proxy bounds, drops proxies for removed colliders, and re-sorts incrementally.
+    pub fn update(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        expansion: SpeculativeExpansion,
+        frozen: &HashSet<EntityId>,
+    ) {
+        self.stamp = self.stamp.wrapping_add(1);
+        let stamp = self.stamp;
+        let known = self.proxies.len();
+
+        for collider_id in colliders.ids() {
+            let collider = match colliders.get(collider_id) {
+                Some(c) if !c.is_unbounded() => c,
+                _ => continue,
+            };
+            let body = match bodies.get(collider.rigidbody_id) {
+                Some(b) if !frozen.contains(&b.id()) => b,
+                _ => continue,
+            };
+
+            let transform = collider.world_transform(body.transform());
+            let extent = Vec3::splat(
+                BroadPhase::get_collider_radius(&collider.shape)
+                    + collider.margin
+                    + self.margin
+                    + expansion.padding(&body),
+            );
+            let min = transform.position - extent;
+            let max = transform.position + extent;
+
+            match self.lookup.get(&collider.id) {
+                Some(&slot) => {
+                    let proxy = &mut self.proxies[slot];
+                    proxy.min = min;
+                    proxy.max = max;
+                    proxy.stamp = stamp;
+                }
+                None => self.proxies.push(SapProxy {
+                    collider: collider.id,
+                    min,
+                    max,
+                    stamp,
+                }),
+            }
+        }
+
+        let added = self.proxies.len() > known;
+        self.proxies.retain(|proxy| proxy.stamp == stamp);
+        self.sort(added);
+    }
+
+    /// Sweeps the sorted proxies and returns overlapping pairs with the lower index first.
+    pub fn find_pairs(&self) -> Vec<(EntityId, EntityId)> {
+        let axis = self.axis;
+        let mut pairs = Vec::new();
+
+        for (i, proxy) in self.proxies.iter().enumerate() {
+            for other in &self.proxies[i + 1..] {
+                if other.min[axis] > proxy.max[axis] {
+                    break;
+                }
+                if !proxy.overlaps(other) {
+                    continue;
+                }
+                let pair = if proxy.collider.index() < other.collider.index() {
+                    (proxy.collider, other.collider)
+                } else {
+                    (other.collider, proxy.collider)
+                };
+                pairs.push(pair);
+            }
+        }
+
+        pairs
+    }
+
+    /// Colliders whose proxies overlap the box `min..max`, as of the last update.
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<EntityId> {
+        let axis = self.axis;
+        let end = self
+            .proxies
+            .partition_point(|proxy| proxy.min[axis] <= max[axis]);
+        self.proxies[..end]
+            .iter()
+            .filter(|proxy| proxy.min.cmple(max).all() && min.cmple(proxy.max).all())
+            .map(|proxy| proxy.collider)
+            .collect()
+    }
+
+    fn sort(&mut self, added: bool) {
+        let axis = self.axis;
+        if added {
+            // New proxies sit unsorted at the end; a batch of them would make insertion
+            // sort quadratic.
+            self.proxies
+                .sort_by(|a, b| a.min[axis].total_cmp(&b.min[axis]));
+        } else {
+            // Insertion sort is close to linear when the previous order is still mostly valid.
+            for i in 1..self.proxies.len() {
+                let mut j = i;
+                while j > 0 && self.proxies[j - 1].min[axis] > self.proxies[j].min[axis] {
+                    self.proxies.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+        }
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.lookup.clear();
+        for (slot, proxy) in self.proxies.iter().enumerate() {
+            self.lookup.insert(proxy.collider, slot);
+        }
+    }
+}
+
+/// Persistent pair cache over fat bounds.
+///
+/// Each proxy stores its bounds padded by `fat_margin`. Colliders whose tight bounds stay
+/// inside their fat box are not re-tested, so pairs between resting colliders carry over
+/// from step to step. Pairs that appeared or disappeared in the last update are kept for
+/// callers that react to changes.
+pub struct PairManager {
+    proxies: Vec<FatProxy>,
+    lookup: HashMap<EntityId, usize>,
+    pairs: BTreeSet<(EntityId, EntityId)>,
+    added: Vec<(EntityId, EntityId)>,
+    removed: Vec<(EntityId, EntityId)>,
+    stamp: u32,
+    /// Padding around each proxy; larger values re-test less often but report more pairs.
+    pub fat_margin: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FatProxy {
+    collider: EntityId,
+    min: Vec3,
+    max: Vec3,
+    stamp: u32,
+    moved: bool,
+}
+
+impl FatProxy {
+    fn overlaps(&self, other: &FatProxy) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+}
+
+impl Default for PairManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PairManager {
+    pub fn new() -> Self {
+        Self {
+            proxies: Vec::new(),
+            lookup: HashMap::new(),
+            pairs: BTreeSet::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            stamp: 0,
+            fat_margin: DEFAULT_FAT_MARGIN,
+        }
+    }
+
+    pub fn proxy_count(&self) -> usize {
+        self.proxies.len()
+    }
+
+    pub fn pair_count(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Colliders whose fat bounds were rebuilt (or created) in the last update.
+    pub fn moved_count(&self) -> usize {
+        self.proxies.iter().filter(|proxy| proxy.moved).count()
+    }
+
+    /// Pairs that started overlapping in the last update, sorted. The world updates once per
+    /// fixed step, so after [`PhysicsWorld::step`](crate::world::PhysicsWorld::step) these
+    /// are the changes of its last fixed step.
+    pub fn added_pairs(&self) -> &[(EntityId, EntityId)] {
+        &self.added
+    }
+
+    /// Pairs that stopped overlapping (or lost a collider) in the last update, sorted.
+    pub fn removed_pairs(&self) -> &[(EntityId, EntityId)] {
+        &self.removed
+    }
+
+    pub fn clear(&mut self) {
+        self.proxies.clear();
+        self.lookup.clear();
+        self.pairs.clear();
+        self.added.clear();
+        self.removed.clear();
+    }
+
+    /// Drops the proxy of `collider` and every cached pair it was part of.
+    pub fn remove(&mut self, collider: EntityId) {
+        if self.lookup.remove(&collider).is_some() {
+            self.proxies.retain(|proxy| proxy.collider != collider);
+            self.reindex();
+        }
+        self.pairs.retain(|&(a, b)| a != collider && b != collider);
+    }
+
+    /// Refits proxies that left their fat bounds, drops removed colliders, and updates the
+    /// cached pair set for moved proxies only.
+    pub fn update(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        expansion: SpeculativeExpansion,
+        frozen: &HashSet<EntityId>,
+    ) {
+        self.stamp = self.stamp.wrapping_add(1);
+        let stamp = self.stamp;
+        self.added.clear();
+        self.removed.clear();
+        let known = self.proxies.len();
+
+        for collider_id in colliders.ids() {
+            let collider = match colliders.get(collider_id) {
+                Some(c) if !c.is_unbounded() => c,
+                _ => continue,
+            };
+            let body = match bodies.get(collider.rigidbody_id) {
+                Some(b) if !frozen.contains(&b.id()) => b,
+                _ => continue,
+            };
+
+            let transform = collider.world_transform(body.transform());
+            let extent = Vec3::splat(
+                BroadPhase::get_collider_radius(&collider.shape)
+                    + collider.margin
+                    + expansion.padding(&body),
+            );
+            let min = transform.position - extent;
+            let max = transform.position + extent;
+            let fat = Vec3::splat(self.fat_margin.max(0.0));
+
+            match self.lookup.get(&collider.id) {
+                Some(&slot) => {
+                    let proxy = &mut self.proxies[slot];
+                    proxy.stamp = stamp;
+                    proxy.moved = !(proxy.min.cmple(min).all() && max.cmple(proxy.max).all());
+                    if proxy.moved {
+                        proxy.min = min - fat;
+                        proxy.max = max + fat;
+                    }
+                }
+                None => self.proxies.push(FatProxy {
+                    collider: collider.id,
+                    min: min - fat,
+                    max: max + fat,
+                    stamp,
+                    moved: true,
+                }),
+            }
+        }
+
+        let added = self.proxies.len() > known;
+        self.proxies.retain(|proxy| proxy.stamp == stamp);
+        self.sort(added);
+
+        // Drop pairs whose colliders are gone or whose moved bounds no longer overlap.
+        let (proxies, lookup, removed) = (&self.proxies, &self.lookup, &mut self.removed);
+        self.pairs.retain(|&(a, b)| {
+            let keep = match (lookup.get(&a), lookup.get(&b)) {
+                (Some(&a), Some(&b)) => {
+                    let (a, b) = (&proxies[a], &proxies[b]);
+                    !(a.moved || b.moved) || a.overlaps(b)
+                }
+                _ => false,
+            };
+            if !keep {
+                removed.push((a, b));
+            }
+            keep
+        });
+
+        // Only pairs with at least one moved proxy can be new.
+        for (i, proxy) in self.proxies.iter().enumerate() {
+            for other in &self.proxies[i + 1..] {
+                if other.min.x > proxy.max.x {
+                    break;
+                }
+                if !(proxy.moved || other.moved) || !proxy.overlaps(other) {
+                    continue;
+                }
+                let pair = if proxy.collider.index() < other.collider.index() {
+                    (proxy.collider, other.collider)
+                } else {
+                    (other.collider, proxy.collider)
+                };
+                if self.pairs.insert(pair) {
+                    self.added.push(pair);
+                }
+            }
+        }
+        self.added.sort();
+    }
+
+    /// Cached pairs with the lower index first, sorted.
+    pub fn find_pairs(&self) -> Vec<(EntityId, EntityId)> {
+        self.pairs.iter().copied().collect()
+    }
+
+    /// Colliders whose fat proxies overlap the box `min..max`, as of the last update.
+    pub fn query_aabb(&self, min: Vec3, max: Vec3) -> Vec<EntityId> {
+        let end = self.proxies.partition_point(|proxy| proxy.min.x <= max.x);
+        self.proxies[..end]
+            .iter()
+            .filter(|proxy| proxy.min.cmple(max).all() && min.cmple(proxy.max).all())
+            .map(|proxy| proxy.collider)
+            .collect()
+    }
+
+    fn sort(&mut self, added: bool) {
+        if added {
+            // Appended proxies are unsorted; see `SweepAndPrune::sort`.
+            self.proxies.sort_by(|a, b| a.min.x.total_cmp(&b.min.x));
+        } else {
+            // Fat bounds rarely change, so the previous order is almost always still valid.
+            for i in 1..self.proxies.len() {
+                let mut j = i;
+                while j > 0 && self.proxies[j - 1].min.x > self.proxies[j].min.x {
+                    self.proxies.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+        }
+        self.reindex();
+    }
+
+    fn reindex(&mut self) {
+        self.lookup.clear();
+        for (slot, proxy) in self.proxies.iter().enumerate() {
+            self.lookup.insert(proxy.collider, slot);
+        }
+    }
+}
+
+/// Acceleration structure used by [`BroadPhase`] to find candidate pairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BroadPhaseBackend {
+    /// Uniform hash grid, rebuilt every step. Good default for scattered scenes.
+    #[default]
+    Grid,
+    /// Persistent sort-and-sweep, best for scenes with mostly coherent motion.
+    SweepAndPrune,
+    /// Persistent pair cache over fat bounds, best for mostly static scenes.
+    Incremental,
+}
+
 /// Broad phase driver returning potential collider pairs.
+///
+/// Pairs whose [`CollisionFilter`](crate::core::collider::CollisionFilter)s do not interact,
+/// or that the optional pair filter rejects, are dropped before narrowphase and CCD see them.
 pub struct BroadPhase {
     grid: SpatialGrid,
+    sap: SweepAndPrune,
+    pair_manager: PairManager,
+    backend: BroadPhaseBackend,
     pub min_separation: f32,
+    pair_filter: Option<Box<PairFilter>>,
+    speculative: SpeculativeExpansion,
+    /// Bodies whose colliders get no proxies; see [`Self::set_body_frozen`].
+    frozen: HashSet<EntityId>,
+    /// Whether the active backend holds bounds from [`Self::get_potential_pairs`].
+    bounds_current: bool,
 }
 
 impl BroadPhase {
     pub fn new(cell_size: f32) -> Self {
+        Self::with_backend(cell_size, BroadPhaseBackend::Grid)
+    }
+
+    pub fn with_backend(cell_size: f32, backend: BroadPhaseBackend) -> Self {
         Self {
             grid: SpatialGrid::new(cell_size),
+            sap: SweepAndPrune::new(),
+            pair_manager: PairManager::new(),
+            backend,
             min_separation: 0.01,
+            pair_filter: None,
+            speculative: SpeculativeExpansion::default(),
+            frozen: HashSet::new(),
+            bounds_current: false,
+        }
+    }
+
+    pub fn backend(&self) -> BroadPhaseBackend {
+        self.backend
+    }
+
+    pub fn set_backend(&mut self, backend: BroadPhaseBackend) {
+        if self.backend != backend {
+            self.sap.clear();
+            self.pair_manager.clear();
+            self.bounds_current = false;
+        }
+        self.backend = backend;
+    }
+
+    pub fn grid_cell_size(&self) -> f32 {
+        self.grid.cell_size()
+    }
+
+    /// Resizes the grid cells; the grid is rebuilt every step, so this takes effect on the
+    /// next update.
+    pub fn set_grid_cell_size(&mut self, cell_size: f32) {
+        self.grid = SpatialGrid::new(cell_size);
+        if self.backend == BroadPhaseBackend::Grid {
+            self.bounds_current = false;
+        }
+    }
+
+    pub fn sweep_and_prune(&self) -> &SweepAndPrune {
+        &self.sap
+    }
+
+    pub fn sweep_and_prune_mut(&mut self) -> &mut SweepAndPrune {
+        &mut self.sap
+    }
+
+    pub fn pair_manager(&self) -> &PairManager {
+        &self.pair_manager
+    }
+
+    pub fn pair_manager_mut(&mut self) -> &mut PairManager {
+        &mut self.pair_manager
+    }
+
+    /// Drops `collider` from the persistent backends; the grid is rebuilt every step anyway.
+    pub fn remove_collider(&mut self, collider: EntityId) {
+        self.sap.remove(collider);
+        self.pair_manager.remove(collider);
+    }
+
+    /// Takes the colliders of `body` out of the broad phase, or puts them back. Frozen
+    /// colliders lose their proxies on the next update and pair with nothing until thawed.
+    pub fn set_body_frozen(&mut self, body: EntityId, frozen: bool) {
+        if frozen {
+            self.frozen.insert(body);
+        } else {
+            self.frozen.remove(&body);
         }
     }
 
+    pub fn is_body_frozen(&self, body: EntityId) -> bool {
+        self.frozen.contains(&body)
+    }
+
+    /// Installs a callback that can veto collider pairs. Return `false` to skip a pair.
+    pub fn set_pair_filter<F>(&mut self, filter: Option<F>)
+    where
+        F: Fn(&Collider, &Collider) -> bool + Send + Sync + 'static,
+    {
+        self.pair_filter = filter.map(|f| Box::new(f) as Box<_>);
+    }
+
+    /// Pads the bounds of CCD-enabled bodies by `margin` plus their travel over `time_step`.
+    pub fn set_speculative_expansion(&mut self, margin: f32, time_step: f32) {
+        self.speculative = SpeculativeExpansion {
+            margin: margin.max(0.0),
+            time_step: time_step.max(0.0),
+        };
+    }
+
+    pub fn speculative_expansion(&self) -> SpeculativeExpansion {
+        self.speculative
+    }
+
     pub fn get_potential_pairs(
         &mut self,
         colliders: &Arena<Collider>,
         bodies: &BodiesSoA,
     ) -> Vec<(EntityId, EntityId)> {
-        self.grid.update(colliders, bodies);
+        let mut pairs = match self.backend {
+            BroadPhaseBackend::Grid => self.grid_pairs(colliders, bodies),
+            BroadPhaseBackend::SweepAndPrune => {
+                self.sap
+                    .update(colliders, bodies, self.speculative, &self.frozen);
+                self.sap.find_pairs()
+            }
+            BroadPhaseBackend::Incremental => {
+                self.pair_manager
+                    .update(colliders, bodies, self.speculative, &self.frozen);
+                self.pair_manager.find_pairs()
+            }
+        };
+        self.bounds_current = true;
+        self.plane_pairs(colliders, bodies, &mut pairs);
+        pairs.retain(|&(a, b)| match (colliders.get(a), colliders.get(b)) {
+            (Some(a), Some(b)) => self.accepts_pair(a, b),
+            _ => false,
+        });
+        pairs
+    }
+
+    /// Collider pairs for body pairs found by another broad phase, such as a GPU backend's,
+    /// in place of [`Self::get_potential_pairs`]. Pairs every bounded collider of one body
+    /// with every bounded collider of the other, skips frozen bodies, adds the plane pairs,
+    /// and applies the same filters. The backends are left untouched, so their bounds no
+    /// longer serve [`Self::query_sphere`].
+    pub fn pairs_from_bodies(
+        &mut self,
+        body_pairs: &[(EntityId, EntityId)],
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) -> Vec<(EntityId, EntityId)> {
+        let mut owned: HashMap<EntityId, Vec<&Collider>> = HashMap::new();
+        for collider in colliders.iter().filter(|collider| !collider.is_unbounded()) {
+            owned
+                .entry(collider.rigidbody_id)
+                .or_default()
+                .push(collider);
+        }
+        self.bounds_current = false;
+        let mut pairs = Vec::new();
+        for (body_a, body_b) in body_pairs {
+            if self.frozen.contains(body_a) || self.frozen.contains(body_b) {
+                continue;
+            }
+            let (Some(colliders_a), Some(colliders_b)) = (owned.get(body_a), owned.get(body_b))
+            else {
+                continue;
+            };
+            for a in colliders_a {
+                for b in colliders_b {
+                    if self.accepts_pair(a, b) {
+                        pairs.push(if a.id.index() < b.id.index() {
+                            (a.id, b.id)
+                        } else {
+                            (b.id, a.id)
+                        });
+                    }
+                }
+            }
+        }
+        let mut planes = Vec::new();
+        self.plane_pairs(colliders, bodies, &mut planes);
+        pairs.extend(planes.into_iter().filter(|&(a, b)| {
+            match (colliders.get(a), colliders.get(b)) {
+                (Some(a), Some(b)) => self.accepts_pair(a, b),
+                _ => false,
+            }
+        }));
+        pairs
+    }
+
+    /// Bounded, non-trigger colliders of unfrozen bodies whose bounding spheres reach the
+    /// sphere at `center`, sorted.
+    ///
+    /// Candidates come from the active backend's bounds over the sphere's box, as of the last
+    /// [`Self::get_potential_pairs`]; colliders added or moved out of their bounds since then
+    /// can be missed. Until the backend holds bounds, every collider is a candidate. The
+    /// sphere test itself uses the bodies' current poses.
+    pub fn query_sphere(
+        &self,
+        center: Vec3,
+        radius: f32,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) -> Vec<EntityId> {
+        let extent = Vec3::splat(radius.max(0.0));
+        let mut candidates = if !self.bounds_current {
+            colliders.ids().collect()
+        } else {
+            match self.backend {
+                BroadPhaseBackend::Grid => self.grid.query(center, radius.max(0.0)),
+                BroadPhaseBackend::SweepAndPrune => {
+                    self.sap.query_aabb(center - extent, center + extent)
+                }
+                BroadPhaseBackend::Incremental => self
+                    .pair_manager
+                    .query_aabb(center - extent, center + extent),
+            }
+        };
+        candidates.sort();
+        candidates.retain(|&collider_id| {
+            let Some(collider) = colliders.get(collider_id) else {
+                return false;
+            };
+            if collider.is_trigger || collider.is_unbounded() {
+                return false;
+            }
+            let body = match bodies.get(collider.rigidbody_id) {
+                Some(b) if !self.frozen.contains(&b.id()) => b,
+                _ => return false,
+            };
+            let transform = collider.world_transform(body.transform());
+            let reach = Self::get_collider_radius(&collider.shape)
+                * transform.scale.abs().max_element()
+                + collider.margin
+                + radius;
+            transform.position.distance_squared(center) <= reach * reach
+        });
+        candidates
+    }
+
+    /// Layer filters plus the user pair filter, if any.
+    pub fn accepts_pair(&self, collider_a: &Collider, collider_b: &Collider) -> bool {
+        collider_a
+            .collision_filter
+            .interacts_with(&collider_b.collision_filter)
+            && self
+                .pair_filter
+                .as_ref()
+                .is_none_or(|filter| filter(collider_a, collider_b))
+    }
+
+    /// Planes stay out of the grid and sweep; they pair with every collider whose bounding
+    /// sphere reaches the plane surface.
+    fn plane_pairs(
+        &self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        pairs: &mut Vec<(EntityId, EntityId)>,
+    ) {
+        let mut planes = Vec::new();
+        for collider in colliders.iter() {
+            if let ColliderShape::Plane { normal } = &collider.shape {
+                let body = match bodies.get(collider.rigidbody_id) {
+                    Some(b) if !self.frozen.contains(&b.id()) => b,
+                    _ => continue,
+                };
+                let transform = collider.world_transform(body.transform());
+                let normal = (transform.rotation * (*normal / transform.scale)).normalize_or_zero();
+                planes.push((collider.id, transform.position, normal));
+            }
+        }
+        if planes.is_empty() {
+            return;
+        }
+
+        for collider in colliders.iter() {
+            if collider.is_unbounded() {
+                continue;
+            }
+            let body = match bodies.get(collider.rigidbody_id) {
+                Some(b) if !self.frozen.contains(&b.id()) => b,
+                _ => continue,
+            };
+            let transform = collider.world_transform(body.transform());
+            let reach = Self::get_collider_radius(&collider.shape)
+                * transform.scale.abs().max_element()
+                + collider.margin
+                + self.sap.margin
+                + self.speculative.padding(&body);
+            for (plane_id, origin, normal) in &planes {
+                if (transform.position - *origin).dot(*normal) > reach {
+                    continue;
+                }
+                pairs.push(if plane_id.index() < collider.id.index() {
+                    (*plane_id, collider.id)
+                } else {
+                    (collider.id, *plane_id)
+                });
+            }
+        }
+    }
+
+    fn grid_pairs(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) -> Vec<(EntityId, EntityId)> {
+        self.grid
+            .update(colliders, bodies, self.speculative, &self.frozen);
 
         // println!("DEBUG: Broadphase grid updated. {} potential pairs.", 0); // placeholder
 
@@ -116,16 +883,18 @@ impl BroadPhase {
 
         for collider_id in colliders.ids() {
             let collider = match colliders.get(collider_id) {
-                Some(c) => c,
-                None => continue,
+                Some(c) if !c.is_unbounded() => c,
+                _ => continue,
             };
             let body = match bodies.get(collider.rigidbody_id) {
-                Some(b) => b,
-                None => continue,
+                Some(b) if !self.frozen.contains(&b.id()) => b,
+                _ => continue,
             };
 
             let transform = collider.world_transform(body.transform());
-            let radius = Self::get_collider_radius(&collider.shape);
+            let radius = Self::get_collider_radius(&collider.shape)
+                + collider.margin
+                + self.speculative.padding(&body);
             let nearby = self.grid.query(transform.position, radius);
 
             for other_id in nearby {
@@ -155,9 +924,12 @@ impl BroadPhase {
             ColliderShape::Capsule { radius, height } => {
                 (radius * radius + (height / 2.0) * (height / 2.0)).sqrt()
             }
-            ColliderShape::Cylinder { radius, height } => {
+            ColliderShape::Cylinder { radius, height }
+            | ColliderShape::Cone { radius, height }
+            | ColliderShape::RoundedCylinder { radius, height, .. } => {
                 (radius * radius + (height / 2.0) * (height / 2.0)).sqrt()
             }
+            ColliderShape::RoundedBox { half_extents, .. } => half_extents.length(),
             ColliderShape::ConvexHull { vertices } => simd::max_length(vertices),
             ColliderShape::Compound { shapes } => shapes
                 .iter()
@@ -166,6 +938,8 @@ impl BroadPhase {
                 })
                 .fold(0.0, f32::max),
             ColliderShape::Mesh { mesh } => mesh.bounding_radius(),
+            ColliderShape::Heightfield { heightfield } => heightfield.bounding_radius(),
+            ColliderShape::Plane { .. } => f32::INFINITY,
         }
     }
 }
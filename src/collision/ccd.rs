@@ -1,7 +1,7 @@
 use glam::{Quat, Vec3};
 
 use crate::{
-    collision::narrowphase::NarrowPhase,
+    collision::{narrowphase::NarrowPhase, shapes::ShapeUtil},
     core::{
         collider::{Collider, ColliderShape},
         rigidbody::RigidBody,
@@ -18,6 +18,18 @@ pub struct CCDResult {
     pub time_of_impact: f32,
 }
 
+/// How the contact at the time of impact is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CcdQuality {
+    /// When narrowphase misses the shapes at the TOI, fall back to a contact along the
+    /// relative velocity. Cheap, but the normal can be far off for glancing hits.
+    #[default]
+    Approximate,
+    /// Bisects forward from the TOI until the shapes touch and uses the GJK/EPA contact
+    /// there, falling back to the approximate contact only if they never do.
+    Iterative,
+}
+
 /// Continuous collision detector placeholder.
 pub struct CCDDetector {
     pub enabled: bool,
@@ -25,6 +37,10 @@ pub struct CCDDetector {
     pub angular_padding: f32,
     pub max_toi_iterations: usize,
     pub speculative_margin: f32,
+    pub quality: CcdQuality,
+    /// After solving, bodies with a CCD contact are slowed to at most this multiple of their
+    /// pre-impact speed. `None` disables the clamp.
+    pub velocity_clamp: Option<f32>,
 }
 
 impl Default for CCDDetector {
@@ -41,6 +57,8 @@ impl CCDDetector {
             angular_padding: 0.5,
             max_toi_iterations: 8,
             speculative_margin: 0.05,
+            quality: CcdQuality::default(),
+            velocity_clamp: Some(1.0),
         }
     }
 
@@ -56,6 +74,14 @@ impl CCDDetector {
         self.angular_padding = padding.max(0.0);
     }
 
+    pub fn set_quality(&mut self, quality: CcdQuality) {
+        self.quality = quality;
+    }
+
+    pub fn set_velocity_clamp(&mut self, clamp: Option<f32>) {
+        self.velocity_clamp = clamp.map(|scale| scale.max(0.0));
+    }
+
     pub fn detect_ccd(
         &self,
         body_a: &RigidBody,
@@ -64,7 +90,7 @@ impl CCDDetector {
         collider_b: &Collider,
         dt: f32,
     ) -> Option<CCDResult> {
-        if !self.enabled {
+        if !self.enabled || involves_terrain(collider_a, collider_b) {
             return None;
         }
 
@@ -86,18 +112,26 @@ impl CCDDetector {
         )?;
 
         // Use NarrowPhase to get a high-quality contact at the TOI.
-        let contact = self
+        let sampled = self
             .sample_contact(body_a, collider_a, body_b, collider_b, toi)
-            .unwrap_or_else(|| {
-                self.build_fallback_contact(
-                    body_a,
-                    collider_a,
-                    body_b,
-                    collider_b,
-                    toi,
-                    relative_velocity,
-                )
+            .map(|contact| (contact, toi))
+            .or_else(|| match self.quality {
+                CcdQuality::Iterative => {
+                    self.refine_contact(body_a, collider_a, body_b, collider_b, toi..dt)
+                }
+                CcdQuality::Approximate => None,
             });
+        let (contact, toi) = sampled.unwrap_or_else(|| {
+            let contact = self.build_fallback_contact(
+                body_a,
+                collider_a,
+                body_b,
+                collider_b,
+                toi,
+                relative_velocity,
+            );
+            (contact, toi)
+        });
 
         Some(CCDResult {
             contact,
@@ -105,6 +139,33 @@ impl CCDDetector {
         })
     }
 
+    /// Earliest sampled contact in `time_range`, bisecting towards the first touching time.
+    fn refine_contact(
+        &self,
+        body_a: &RigidBody,
+        collider_a: &Collider,
+        body_b: &RigidBody,
+        collider_b: &Collider,
+        time_range: std::ops::Range<f32>,
+    ) -> Option<(Contact, f32)> {
+        let (mut t_lo, mut t_hi) = (time_range.start, time_range.end);
+        let mut best = (
+            self.sample_contact(body_a, collider_a, body_b, collider_b, t_hi)?,
+            t_hi,
+        );
+        for _ in 0..self.max_toi_iterations {
+            let t_mid = (t_lo + t_hi) * 0.5;
+            match self.sample_contact(body_a, collider_a, body_b, collider_b, t_mid) {
+                Some(contact) => {
+                    best = (contact, t_mid);
+                    t_hi = t_mid;
+                }
+                None => t_lo = t_mid,
+            }
+        }
+        Some(best)
+    }
+
     pub fn generate_speculative_contact(
         &self,
         body_a: &RigidBody,
@@ -113,7 +174,7 @@ impl CCDDetector {
         collider_b: &Collider,
         dt: f32,
     ) -> Option<Contact> {
-        if self.speculative_margin <= f32::EPSILON {
+        if self.speculative_margin <= f32::EPSILON || involves_terrain(collider_a, collider_b) {
             return None;
         }
 
@@ -167,6 +228,8 @@ impl CCDDetector {
             depth: current_depth,
             relative_velocity: (body_b.velocity.linear - body_a.velocity.linear).dot(direction),
             feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
             accumulated_normal_impulse: 0.0,
             accumulated_tangent_impulse: Vec3::ZERO,
             accumulated_rolling_impulse: Vec3::ZERO,
@@ -370,6 +433,8 @@ impl CCDDetector {
             depth,
             relative_velocity: relative_velocity.dot(normal),
             feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
             accumulated_normal_impulse: 0.0,
             accumulated_tangent_impulse: Vec3::ZERO,
             accumulated_rolling_impulse: Vec3::ZERO,
@@ -475,7 +540,8 @@ fn support_radius(collider: &Collider, body: &RigidBody, direction: Vec3) -> f32
         return 0.0;
     }
     let world = collider.world_transform(&body.transform);
-    shape_support_radius(&collider.shape, dir, &world)
+    let (core, margin) = ShapeUtil::margin_core(&collider.shape, collider.margin, world.scale);
+    shape_support_radius(&core, dir, &world) + margin
 }
 
 fn support_point_world(collider: &Collider, body: &RigidBody, direction: Vec3) -> Vec3 {
@@ -484,7 +550,8 @@ fn support_point_world(collider: &Collider, body: &RigidBody, direction: Vec3) -
         return collider.world_transform(&body.transform).position;
     }
     let world = collider.world_transform(&body.transform);
-    shape_support_point(&collider.shape, dir, &world)
+    let (core, margin) = ShapeUtil::margin_core(&collider.shape, collider.margin, world.scale);
+    shape_support_point(&core, dir, &world) + dir * margin
 }
 
 fn shape_support_radius(shape: &ColliderShape, dir_world: Vec3, world: &Transform) -> f32 {
@@ -520,6 +587,20 @@ fn shape_support_radius(shape: &ColliderShape, dir_world: Vec3, world: &Transfor
             scaled.extend(mesh.vertices.iter().map(|vertex| (*vertex) * world.scale));
             simd::max_dot(&scaled, dir_local)
         }
+        ColliderShape::Heightfield { heightfield } => {
+            let scaled: Vec<Vec3> = heightfield
+                .vertices()
+                .map(|vertex| vertex * world.scale)
+                .collect();
+            simd::max_dot(&scaled, dir_local)
+        }
+        ColliderShape::Cone { .. }
+        | ColliderShape::RoundedBox { .. }
+        | ColliderShape::RoundedCylinder { .. } => {
+            let support = shape_support_point(shape, dir_world, world);
+            (support - world.position).dot(dir_world.normalize_or_zero())
+        }
+        ColliderShape::Plane { .. } => 0.0,
         ColliderShape::Compound { shapes } => {
             let mut max_proj = 0.0f32;
             for (local_transform, shape) in shapes {
@@ -612,6 +693,26 @@ fn shape_support_point(shape: &ColliderShape, dir_world: Vec3, world: &Transform
             }
             best
         }
+        ColliderShape::Heightfield { heightfield } => {
+            let mut best = world.position;
+            let mut best_dot = f32::MIN;
+            for vertex in heightfield.vertices() {
+                let world_vertex = world.position + world.rotation * (vertex * world.scale);
+                let dot = world_vertex.dot(dir_world);
+                if dot > best_dot {
+                    best_dot = dot;
+                    best = world_vertex;
+                }
+            }
+            best
+        }
+        ColliderShape::Cone { .. }
+        | ColliderShape::RoundedBox { .. }
+        | ColliderShape::RoundedCylinder { .. } => {
+            let local = ShapeUtil::support(shape, dir_local * world.scale);
+            world.position + world.rotation * (local * world.scale)
+        }
+        ColliderShape::Plane { .. } => world.position,
         ColliderShape::Compound { shapes } => {
             let mut best_point = world.position;
             let mut best_dot = f32::MIN;
@@ -629,6 +730,18 @@ fn shape_support_point(shape: &ColliderShape, dir_world: Vec3, world: &Transform
     }
 }
 
+/// Support-mapping estimates treat terrain as its convex hull, so heightfields opt out.
+/// Planes have no finite support and are solid all the way down, so nothing tunnels them.
+fn involves_terrain(collider_a: &Collider, collider_b: &Collider) -> bool {
+    let is_terrain = |shape: &ColliderShape| {
+        matches!(
+            shape,
+            ColliderShape::Heightfield { .. } | ColliderShape::Plane { .. }
+        )
+    };
+    is_terrain(&collider_a.shape) || is_terrain(&collider_b.shape)
+}
+
 fn radial_scale(scale: Vec3) -> f32 {
     scale.x.abs().max(scale.z.abs())
 }
@@ -693,6 +806,8 @@ mod tests {
             offset: Transform::default(),
             is_trigger: false,
             collision_filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         };
 
         (body, collider)
@@ -732,6 +847,8 @@ mod tests {
             offset: Transform::default(),
             is_trigger: false,
             collision_filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         };
 
         let (mut body_b, collider_b) = make_sphere(
@@ -92,3 +92,19 @@ pub fn rectangle_planes(center: Vec3, tangent_u: Vec3, tangent_v: Vec3, half_u:
         Plane::from_point_normal(center - tangent_v * half_v, -tangent_v),
     ]
 }
+
+/// Builds outward-facing side planes for a convex polygon lying in the plane with `normal`.
+pub fn polygon_side_planes(vertices: &[Vec3], normal: Vec3) -> Vec<Plane> {
+    let centroid = vertices.iter().copied().sum::<Vec3>() / vertices.len().max(1) as f32;
+    let mut planes = Vec::with_capacity(vertices.len());
+    for i in 0..vertices.len() {
+        let start = vertices[i];
+        let end = vertices[(i + 1) % vertices.len()];
+        let mut side = (end - start).cross(normal);
+        if side.dot(centroid - start) > 0.0 {
+            side = -side;
+        }
+        planes.push(Plane::from_point_normal(start, side));
+    }
+    planes
+}
@@ -1,18 +1,26 @@
-type ManifoldDebugHook = dyn Fn(&ManifoldDebugInfo) + Send + Sync;
+/// Callback receiving manifold snapshots; see [`ManifoldCache::set_debug_hook`].
+pub type ManifoldDebugHook = dyn Fn(&ManifoldDebugInfo) + Send + Sync;
+/// Body-pair predicate limiting which manifolds are snapshotted for the debug hook.
+pub type ManifoldDebugFilter = dyn Fn(EntityId, EntityId) -> bool + Send + Sync;
 
 use std::{cmp::Ordering, collections::HashMap, fmt};
 
 use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
     collision::{
+        analytic,
         clipping::{clip_polygon, rectangle_planes},
+        heightfield, mesh,
         narrowphase::NarrowPhase,
+        plane,
     },
     core::{
         collider::{Collider, ColliderShape},
         rigidbody::RigidBody,
-        types::{Material, MaterialPairProperties},
+        types::{MaterialPairProperties, MaterialPairTable},
     },
     dynamics::solver::Contact,
     utils::allocator::EntityId,
@@ -29,6 +37,8 @@ pub struct RawContactPoint {
     pub point: Vec3,
     pub depth: f32,
     pub feature_id: u64,
+    pub material_index_a: Option<u16>,
+    pub material_index_b: Option<u16>,
 }
 
 /// Raw manifold description generated per frame by the narrow phase.
@@ -48,6 +58,24 @@ impl ContactManifold {
     ) -> Option<Self> {
         use crate::collision::narrowphase::NarrowPhase;
 
+        if matches!(collider_a.shape, ColliderShape::Plane { .. })
+            || matches!(collider_b.shape, ColliderShape::Plane { .. })
+        {
+            return plane::collide(collider_a, body_a, collider_b, body_b);
+        }
+
+        if matches!(collider_a.shape, ColliderShape::Heightfield { .. })
+            || matches!(collider_b.shape, ColliderShape::Heightfield { .. })
+        {
+            return heightfield::collide(collider_a, body_a, collider_b, body_b);
+        }
+
+        if let (ColliderShape::Mesh { .. }, ColliderShape::Mesh { .. }) =
+            (&collider_a.shape, &collider_b.shape)
+        {
+            return mesh::collide(collider_a, body_a, collider_b, body_b);
+        }
+
         if let (
             ColliderShape::Box { half_extents: he_a },
             ColliderShape::Box { half_extents: he_b },
@@ -60,6 +88,10 @@ impl ContactManifold {
             }
         }
 
+        if analytic::handles(&collider_a.shape, &collider_b.shape) {
+            return analytic::collide(collider_a, body_a, collider_b, body_b);
+        }
+
         let (contact, simplex) =
             NarrowPhase::collide(collider_a, body_a, collider_b, body_b, None)?;
 
@@ -69,6 +101,8 @@ impl ContactManifold {
                 point: contact.point,
                 depth: contact.depth,
                 feature_id: contact.feature_id,
+                material_index_a: contact.material_index_a,
+                material_index_b: contact.material_index_b,
             }],
             simplex: Some(simplex),
         })
@@ -76,8 +110,11 @@ impl ContactManifold {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ContactPoint {
     pub feature_id: u64,
+    pub material_index_a: Option<u16>,
+    pub material_index_b: Option<u16>,
     pub world_point: Vec3,
     pub local_a: Vec3,
     pub local_b: Vec3,
@@ -113,7 +150,50 @@ pub struct ManifoldDebugInfo {
     pub material: MaterialPairProperties,
 }
 
-#[derive(Debug)]
+impl ManifoldDebugInfo {
+    /// Line segments from each contact point along the normal, scaled by `length`, for
+    /// debug drawing.
+    pub fn normal_segments(&self, length: f32) -> impl Iterator<Item = (Vec3, Vec3)> + '_ {
+        self.points
+            .iter()
+            .map(move |point| (point.world_point, point.world_point + self.normal * length))
+    }
+}
+
+/// Summary of a body pair's manifold carried by [`ContactEvent`]s.
+#[derive(Debug, Clone, Copy)]
+pub struct ContactEventInfo {
+    pub body_a: EntityId,
+    pub body_b: EntityId,
+    pub normal: Vec3,
+    /// Average of the manifold's contact points.
+    pub point: Vec3,
+    /// Sum of the accumulated normal impulses; zero for [`ContactEvent::Ended`].
+    pub normal_impulse: f32,
+    /// Sum of the accumulated friction impulse magnitudes; zero for [`ContactEvent::Ended`].
+    pub tangent_impulse: f32,
+}
+
+/// Change in the touching state of a body pair, derived from the persistent manifolds.
+#[derive(Debug, Clone, Copy)]
+pub enum ContactEvent {
+    Started(ContactEventInfo),
+    Persisted(ContactEventInfo),
+    Ended(ContactEventInfo),
+}
+
+impl ContactEvent {
+    pub fn info(&self) -> &ContactEventInfo {
+        match self {
+            ContactEvent::Started(info)
+            | ContactEvent::Persisted(info)
+            | ContactEvent::Ended(info) => info,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct PersistentManifold {
     body_a: EntityId,
     body_b: EntityId,
@@ -122,6 +202,7 @@ pub struct PersistentManifold {
     pub last_frame: u32,
     material: MaterialPairProperties,
     pub simplex: Option<Vec<Vec3>>,
+    touching: bool,
 }
 
 impl PersistentManifold {
@@ -134,6 +215,7 @@ impl PersistentManifold {
             last_frame: 0,
             material: MaterialPairProperties::default(),
             simplex: None,
+            touching: false,
         }
     }
 
@@ -149,7 +231,6 @@ impl PersistentManifold {
         self.normal = normal;
         self.last_frame = frame;
         self.simplex = simplex;
-        self.material = Material::combine_pair(&body_a.material, &body_b.material);
 
         let mut updated_points = Vec::with_capacity(raw_points.len());
         for raw in raw_points {
@@ -159,10 +240,14 @@ impl PersistentManifold {
                 point.local_a = world_to_local(body_a, raw.point);
                 point.local_b = world_to_local(body_b, raw.point);
                 point.depth = raw.depth;
+                point.material_index_a = raw.material_index_a;
+                point.material_index_b = raw.material_index_b;
                 updated_points.push(point);
             } else {
                 updated_points.push(ContactPoint {
                     feature_id: raw.feature_id,
+                    material_index_a: raw.material_index_a,
+                    material_index_b: raw.material_index_b,
                     world_point: raw.point,
                     local_a: world_to_local(body_a, raw.point),
                     local_b: world_to_local(body_b, raw.point),
@@ -193,6 +278,8 @@ impl PersistentManifold {
                 depth: point.depth,
                 relative_velocity: 0.0,
                 feature_id: point.feature_id,
+                material_index_a: point.material_index_a,
+                material_index_b: point.material_index_b,
                 accumulated_normal_impulse: point.normal_impulse,
                 accumulated_tangent_impulse: point.tangent_impulse,
                 accumulated_rolling_impulse: point.rolling_impulse,
@@ -216,6 +303,26 @@ impl PersistentManifold {
         }
     }
 
+    fn event_info(&self, with_impulses: bool) -> ContactEventInfo {
+        let count = self.points.len().max(1) as f32;
+        let point = self.points.iter().map(|p| p.world_point).sum::<Vec3>() / count;
+        let (normal_impulse, tangent_impulse) = if with_impulses {
+            self.points.iter().fold((0.0, 0.0), |(n, t), p| {
+                (n + p.normal_impulse, t + p.tangent_impulse.length())
+            })
+        } else {
+            (0.0, 0.0)
+        };
+        ContactEventInfo {
+            body_a: self.body_a,
+            body_b: self.body_b,
+            normal: self.normal,
+            point,
+            normal_impulse,
+            tangent_impulse,
+        }
+    }
+
     fn debug_snapshot(&self, frame: u32) -> ManifoldDebugInfo {
         ManifoldDebugInfo {
             body_a: self.body_a,
@@ -322,6 +429,8 @@ fn generate_box_box_manifold(
             point,
             depth,
             feature_id,
+            material_index_a: None,
+            material_index_b: None,
         });
     }
 
@@ -590,7 +699,7 @@ fn tangent_distance(candidate: &ContactPoint, selected: &[ContactPoint], normal:
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 struct ManifoldKey {
     a: EntityId,
     b: EntityId,
@@ -610,6 +719,7 @@ pub struct ManifoldCache {
     manifolds: HashMap<ManifoldKey, PersistentManifold>,
     frame: u32,
     debug_hook: Option<Box<ManifoldDebugHook>>,
+    debug_filter: Option<Box<ManifoldDebugFilter>>,
 }
 
 impl ManifoldCache {
@@ -618,6 +728,7 @@ impl ManifoldCache {
             manifolds: HashMap::new(),
             frame: 0,
             debug_hook: None,
+            debug_filter: None,
         }
     }
 
@@ -632,10 +743,11 @@ impl ManifoldCache {
         collider_b: &Collider,
         rigid_a: &RigidBody,
         rigid_b: &RigidBody,
+        material_pairs: &MaterialPairTable,
     ) -> Vec<Contact> {
         let (body_a, body_b) = (rigid_a.id, rigid_b.id);
         let key = ManifoldKey::new(body_a, body_b);
-        let wants_debug = self.debug_hook.is_some();
+        let wants_debug = self.wants_debug(body_a, body_b);
 
         // If this manifold doesn't have a simplex yet (e.g. it was just created by generate()),
         // try to get one from the existing persistent manifold.
@@ -653,6 +765,8 @@ impl ManifoldCache {
                         point: contact.point,
                         depth: contact.depth,
                         feature_id: contact.feature_id,
+                        material_index_a: contact.material_index_a,
+                        material_index_b: contact.material_index_b,
                     }];
                     manifold.simplex = Some(simplex);
                 }
@@ -671,6 +785,7 @@ impl ManifoldCache {
                 self.frame,
                 manifold.simplex,
             );
+            entry.material = material_pairs.combine(&rigid_a.material, &rigid_b.material);
             let contacts = entry.to_contacts();
             let snapshot = if wants_debug {
                 Some(entry.debug_snapshot(self.frame))
@@ -702,6 +817,8 @@ impl ManifoldCache {
         entry.material = contact.material;
         entry.points.push(ContactPoint {
             feature_id: contact.feature_id,
+            material_index_a: contact.material_index_a,
+            material_index_b: contact.material_index_b,
             world_point: contact.point,
             local_a: Vec3::ZERO,
             local_b: Vec3::ZERO,
@@ -712,13 +829,11 @@ impl ManifoldCache {
             torsional_impulse: contact.accumulated_torsional_impulse,
         });
 
-        if let Some(snapshot) = self.debug_hook.as_ref().and_then(|_| {
-            self.manifolds
-                .get(&key)
-                .map(|m| m.debug_snapshot(self.frame))
-        }) {
-            if let Some(hook) = self.debug_hook.as_mut() {
-                hook(&snapshot);
+        if self.wants_debug(contact.body_a, contact.body_b) {
+            if let (Some(hook), Some(manifold)) =
+                (self.debug_hook.as_ref(), self.manifolds.get(&key))
+            {
+                hook(&manifold.debug_snapshot(self.frame));
             }
         }
     }
@@ -733,7 +848,12 @@ impl ManifoldCache {
             let key = ManifoldKey::new(contact.body_a, contact.body_b);
             if let Some(manifold) = self.manifolds.get_mut(&key) {
                 manifold.apply_impulses(contact);
-                if wants_debug {
+                if wants_debug
+                    && self
+                        .debug_filter
+                        .as_ref()
+                        .is_none_or(|filter| filter(key.a, key.b))
+                {
                     updated_keys.push(key);
                 }
             }
@@ -754,12 +874,60 @@ impl ManifoldCache {
         }
     }
 
+    /// Appends events for pairs that started, kept, or stopped touching this frame.
+    ///
+    /// A pair touches when its manifold was refreshed this frame with at least one
+    /// non-separated point, so speculative contacts do not count. Call after impulses have
+    /// been written back and before [`ManifoldCache::prune_stale`].
+    pub fn collect_events(&mut self, events: &mut Vec<ContactEvent>) {
+        let frame = self.frame;
+        let first = events.len();
+        for manifold in self.manifolds.values_mut() {
+            let touching =
+                manifold.last_frame == frame && manifold.points.iter().any(|p| p.depth >= 0.0);
+            match (manifold.touching, touching) {
+                (false, true) => events.push(ContactEvent::Started(manifold.event_info(true))),
+                (true, true) => events.push(ContactEvent::Persisted(manifold.event_info(true))),
+                (true, false) => events.push(ContactEvent::Ended(manifold.event_info(false))),
+                (false, false) => {}
+            }
+            manifold.touching = touching;
+        }
+        // Manifolds live in a hash map; sort so event order is deterministic.
+        events[first..].sort_by_key(|event| {
+            let info = event.info();
+            (info.body_a.index(), info.body_b.index())
+        });
+    }
+
     pub fn prune_stale(&mut self) {
         let frame = self.frame;
         self.manifolds
             .retain(|_, manifold| frame.saturating_sub(manifold.last_frame) <= MANIFOLD_MAX_AGE);
     }
 
+    /// Drops every manifold involving `body` and returns the bodies it was touching.
+    pub fn remove_body(&mut self, body: EntityId) -> Vec<EntityId> {
+        let mut touching = Vec::new();
+        self.manifolds.retain(|key, _| {
+            let other = if key.a == body {
+                key.b
+            } else if key.b == body {
+                key.a
+            } else {
+                return true;
+            };
+            touching.push(other);
+            false
+        });
+        touching.sort();
+        touching
+    }
+
+    /// Installs a callback receiving a snapshot each time a manifold changes: after
+    /// narrowphase refreshes it, when a CCD contact is recorded, and after the solver writes
+    /// impulses back. Snapshots allocate, so they are only built while a hook is installed
+    /// and only for pairs accepted by the debug filter.
     pub fn set_debug_hook<F>(&mut self, hook: Option<F>)
     where
         F: Fn(&ManifoldDebugInfo) + Send + Sync + 'static,
@@ -767,12 +935,73 @@ impl ManifoldCache {
         self.debug_hook = hook.map(|f| Box::new(f) as Box<_>);
     }
 
+    /// Limits debug snapshots to body pairs for which `filter` returns `true`. The pair is
+    /// passed with the lower index first. `None` snapshots every pair.
+    pub fn set_debug_filter<F>(&mut self, filter: Option<F>)
+    where
+        F: Fn(EntityId, EntityId) -> bool + Send + Sync + 'static,
+    {
+        self.debug_filter = filter.map(|f| Box::new(f) as Box<_>);
+    }
+
+    fn wants_debug(&self, body_a: EntityId, body_b: EntityId) -> bool {
+        if self.debug_hook.is_none() {
+            return false;
+        }
+        let key = ManifoldKey::new(body_a, body_b);
+        self.debug_filter
+            .as_ref()
+            .is_none_or(|filter| filter(key.a, key.b))
+    }
+
+    /// Snapshots of manifolds refreshed this frame that pass the debug filter, ordered by
+    /// body indices.
+    pub fn frame_snapshots(&self) -> Vec<ManifoldDebugInfo> {
+        let mut keys: Vec<&ManifoldKey> = self
+            .manifolds
+            .iter()
+            .filter(|(key, manifold)| {
+                manifold.last_frame == self.frame
+                    && self
+                        .debug_filter
+                        .as_ref()
+                        .is_none_or(|filter| filter(key.a, key.b))
+            })
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort_unstable_by_key(|key| (key.a.index(), key.b.index()));
+        keys.into_iter()
+            .map(|key| self.manifolds[key].debug_snapshot(self.frame))
+            .collect()
+    }
+
     pub fn debug_snapshots(&self) -> Vec<ManifoldDebugInfo> {
         self.manifolds
             .values()
             .map(|manifold| manifold.debug_snapshot(self.frame))
             .collect()
     }
+
+    /// Every persisted manifold, ordered by body pair.
+    pub(crate) fn persisted(&self) -> Vec<PersistentManifold> {
+        let mut manifolds: Vec<PersistentManifold> = self.manifolds.values().cloned().collect();
+        manifolds.sort_by_key(|manifold| ManifoldKey::new(manifold.body_a, manifold.body_b));
+        manifolds
+    }
+
+    /// Replaces the cache's manifolds, keeping its debug hooks.
+    pub(crate) fn restore(&mut self, manifolds: &[PersistentManifold], frame: u32) {
+        self.frame = frame;
+        self.manifolds = manifolds
+            .iter()
+            .map(|manifold| {
+                (
+                    ManifoldKey::new(manifold.body_a, manifold.body_b),
+                    manifold.clone(),
+                )
+            })
+            .collect();
+    }
 }
 
 impl Default for ManifoldCache {
@@ -0,0 +1,259 @@
+use glam::Vec3;
+
+use crate::{
+    collision::contact::{ContactManifold, RawContactPoint},
+    core::{
+        collider::{Collider, ColliderShape},
+        heightfield::Heightfield,
+        rigidbody::RigidBody,
+        types::Transform,
+    },
+    utils::math::closest_points_segment_triangle,
+};
+
+const CONTACT_MERGE_DISTANCE: f32 = 1e-3;
+
+/// Generates a manifold when either collider is a heightfield. Normal points from A to B.
+///
+/// Contact points carry the material index of the touched heightfield triangle.
+///
+/// Spheres and capsules are tested against the triangles under their bounds; boxes and
+/// convex hulls sample their vertices against the surface. Other pairings return `None`.
+pub fn collide(
+    collider_a: &Collider,
+    body_a: &RigidBody,
+    collider_b: &Collider,
+    body_b: &RigidBody,
+) -> Option<ContactManifold> {
+    if let ColliderShape::Heightfield { heightfield } = &collider_a.shape {
+        return collide_against(heightfield, collider_a, body_a, collider_b, body_b);
+    }
+    if let ColliderShape::Heightfield { heightfield } = &collider_b.shape {
+        let mut manifold = collide_against(heightfield, collider_b, body_b, collider_a, body_a)?;
+        manifold.normal = -manifold.normal;
+        for point in &mut manifold.points {
+            std::mem::swap(&mut point.material_index_a, &mut point.material_index_b);
+        }
+        return Some(manifold);
+    }
+    None
+}
+
+fn collide_against(
+    heightfield: &Heightfield,
+    field_collider: &Collider,
+    field_body: &RigidBody,
+    other_collider: &Collider,
+    other_body: &RigidBody,
+) -> Option<ContactManifold> {
+    let field = field_collider.world_transform(&field_body.transform);
+    let other = other_collider.world_transform(&other_body.transform);
+
+    match &other_collider.shape {
+        ColliderShape::Sphere { radius } => {
+            let radius = radius.max(0.0) * other.scale.abs().max_element();
+            let contact = deepest_segment_contact(
+                heightfield,
+                &field,
+                other.position,
+                other.position,
+                radius,
+            )?;
+            Some(ContactManifold {
+                normal: contact.normal,
+                points: vec![RawContactPoint {
+                    point: contact.point,
+                    depth: contact.depth,
+                    feature_id: contact.triangle as u64,
+                    material_index_a: heightfield.material_index(contact.triangle),
+                    material_index_b: None,
+                }],
+                simplex: None,
+            })
+        }
+        ColliderShape::Capsule { radius, height } => {
+            let radius = radius.max(0.0) * other.scale.x.abs().max(other.scale.z.abs());
+            let cap_offset = other.rotation * (Vec3::Y * 0.5 * height * other.scale.y);
+            let top = other.position + cap_offset;
+            let bottom = other.position - cap_offset;
+            capsule_manifold(heightfield, &field, top, bottom, radius)
+        }
+        ColliderShape::Box { half_extents } => {
+            let corners = (0..8).map(|i| {
+                let sign = Vec3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                );
+                other.position + other.rotation * (*half_extents * sign * other.scale)
+            });
+            vertex_manifold(heightfield, &field, corners)
+        }
+        ColliderShape::ConvexHull { vertices } => {
+            let points = vertices
+                .iter()
+                .map(|v| other.position + other.rotation * (*v * other.scale));
+            vertex_manifold(heightfield, &field, points)
+        }
+        _ => None,
+    }
+}
+
+struct SurfaceContact {
+    point: Vec3,
+    normal: Vec3,
+    depth: f32,
+    triangle: u32,
+}
+
+fn capsule_manifold(
+    heightfield: &Heightfield,
+    field: &Transform,
+    top: Vec3,
+    bottom: Vec3,
+    radius: f32,
+) -> Option<ContactManifold> {
+    let deepest = deepest_segment_contact(heightfield, field, top, bottom, radius)?;
+    let normal = deepest.normal;
+
+    // Resting capsules need both end caps in the manifold to stay stable.
+    let mut points = vec![RawContactPoint {
+        point: deepest.point,
+        depth: deepest.depth,
+        feature_id: (deepest.triangle as u64) << 2,
+        material_index_a: heightfield.material_index(deepest.triangle),
+        material_index_b: None,
+    }];
+    for (tag, end) in [(1u64, top), (2u64, bottom)] {
+        if let Some(contact) = deepest_segment_contact(heightfield, field, end, end, radius) {
+            let duplicate = points
+                .iter()
+                .any(|existing| (existing.point - contact.point).length() < CONTACT_MERGE_DISTANCE);
+            if !duplicate {
+                points.push(RawContactPoint {
+                    point: contact.point,
+                    depth: contact.depth,
+                    feature_id: ((contact.triangle as u64) << 2) | tag,
+                    material_index_a: heightfield.material_index(contact.triangle),
+                    material_index_b: None,
+                });
+            }
+        }
+    }
+
+    Some(ContactManifold {
+        normal,
+        points,
+        simplex: None,
+    })
+}
+
+fn deepest_segment_contact(
+    heightfield: &Heightfield,
+    field: &Transform,
+    start: Vec3,
+    end: Vec3,
+    radius: f32,
+) -> Option<SurfaceContact> {
+    let world_min = start.min(end) - Vec3::splat(radius);
+    let world_max = start.max(end) + Vec3::splat(radius);
+    let (local_min, local_max) = local_bounds(field, world_min, world_max);
+
+    let mut best: Option<SurfaceContact> = None;
+    for (triangle, [a, b, c]) in heightfield.triangles_in_aabb(local_min, local_max) {
+        let a = to_world(field, a);
+        let b = to_world(field, b);
+        let c = to_world(field, c);
+        let (on_segment, on_triangle) = closest_points_segment_triangle(start, end, a, b, c);
+        let offset = on_segment - on_triangle;
+        let distance = offset.length();
+        if distance >= radius {
+            continue;
+        }
+
+        let face_normal = (b - a).cross(c - a).normalize_or_zero();
+        let normal = if distance > 1e-6 {
+            offset / distance
+        } else {
+            face_normal
+        };
+        let depth = radius - distance;
+        if best.as_ref().is_none_or(|current| depth > current.depth) {
+            best = Some(SurfaceContact {
+                point: on_triangle,
+                normal,
+                depth,
+                triangle,
+            });
+        }
+    }
+    best
+}
+
+fn vertex_manifold(
+    heightfield: &Heightfield,
+    field: &Transform,
+    vertices: impl Iterator<Item = Vec3>,
+) -> Option<ContactManifold> {
+    let mut points = Vec::new();
+    let mut normal_sum = Vec3::ZERO;
+
+    for (index, vertex) in vertices.enumerate() {
+        let local = to_local(field, vertex);
+        let (height, normal, triangle) = match heightfield.surface_at(local.x, local.z) {
+            Some(surface) => surface,
+            None => continue,
+        };
+        let vertical = height - local.y;
+        if vertical <= 0.0 {
+            continue;
+        }
+        let depth = vertical * normal.y;
+        normal_sum += normal * depth;
+        points.push(RawContactPoint {
+            point: vertex,
+            depth,
+            feature_id: ((triangle as u64) << 8) | index as u64,
+            material_index_a: heightfield.material_index(triangle),
+            material_index_b: None,
+        });
+    }
+
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(ContactManifold {
+        normal: normal_to_world(field, normal_sum.normalize_or_zero()),
+        points,
+        simplex: None,
+    })
+}
+
+fn local_bounds(field: &Transform, world_min: Vec3, world_max: Vec3) -> (Vec3, Vec3) {
+    let mut min = Vec3::splat(f32::INFINITY);
+    let mut max = Vec3::splat(f32::NEG_INFINITY);
+    for i in 0..8 {
+        let corner = Vec3::new(
+            if i & 1 == 0 { world_min.x } else { world_max.x },
+            if i & 2 == 0 { world_min.y } else { world_max.y },
+            if i & 4 == 0 { world_min.z } else { world_max.z },
+        );
+        let local = to_local(field, corner);
+        min = min.min(local);
+        max = max.max(local);
+    }
+    (min, max)
+}
+
+fn to_local(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.rotation.conjugate() * (point - transform.position) / transform.scale
+}
+
+fn to_world(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.position + transform.rotation * (point * transform.scale)
+}
+
+fn normal_to_world(transform: &Transform, normal: Vec3) -> Vec3 {
+    (transform.rotation * (normal / transform.scale)).normalize_or_zero()
+}
@@ -0,0 +1,255 @@
+use glam::{Mat3, Vec3};
+
+use crate::{
+    collision::{
+        clipping::{clip_polygon, polygon_side_planes},
+        contact::{ContactManifold, RawContactPoint},
+    },
+    core::{
+        collider::{Collider, ColliderShape},
+        mesh::{MeshBvhNode, TriangleMesh},
+        rigidbody::RigidBody,
+        types::Transform,
+    },
+};
+
+const AXIS_EPSILON: f32 = 1e-6;
+const CONTACT_MERGE_DISTANCE: f32 = 1e-3;
+/// Triangle contacts whose normal deviates further than this from the manifold normal are dropped.
+const NORMAL_AGREEMENT: f32 = 0.7;
+const CLUSTER_AGREEMENT: f32 = 0.95;
+
+/// Generates a manifold between two triangle meshes. Normal points from A to B.
+///
+/// Candidate triangle pairs come from a simultaneous walk of both meshes' BVHs; each pair is
+/// tested with SAT and face contacts are clipped to produce multiple points. Meshes are
+/// expected to use counter-clockwise winding so face normals point outward.
+pub fn collide(
+    collider_a: &Collider,
+    body_a: &RigidBody,
+    collider_b: &Collider,
+    body_b: &RigidBody,
+) -> Option<ContactManifold> {
+    let (mesh_a, mesh_b) = match (&collider_a.shape, &collider_b.shape) {
+        (ColliderShape::Mesh { mesh: a }, ColliderShape::Mesh { mesh: b }) => (a, b),
+        _ => return None,
+    };
+
+    let world_a = collider_a.world_transform(&body_a.transform);
+    let world_b = collider_b.world_transform(&body_b.transform);
+
+    let mut contacts = Vec::new();
+    for (tri_a, tri_b) in overlapping_triangle_pairs(mesh_a, &world_a, mesh_b, &world_b) {
+        let a = mesh_a.triangle(tri_a).map(|v| to_world(&world_a, v));
+        let b = mesh_b.triangle(tri_b).map(|v| to_world(&world_b, v));
+        if let Some(contact) = triangle_contact(a, b) {
+            contacts.push((tri_a, tri_b, contact));
+        }
+    }
+
+    let normal = manifold_normal(&contacts)?;
+
+    let mut points: Vec<RawContactPoint> = Vec::new();
+    for (tri_a, tri_b, contact) in &contacts {
+        if contact.normal.dot(normal) < NORMAL_AGREEMENT {
+            continue;
+        }
+        for (k, point) in contact.points.iter().enumerate() {
+            let feature_id = ((*tri_a as u64) << 34) | ((*tri_b as u64) << 4) | k as u64;
+            match points
+                .iter_mut()
+                .find(|existing| (existing.point - point.0).length() < CONTACT_MERGE_DISTANCE)
+            {
+                Some(existing) => existing.depth = existing.depth.max(point.1),
+                None => points.push(RawContactPoint {
+                    point: point.0,
+                    depth: point.1,
+                    feature_id,
+                    material_index_a: mesh_a.material_index(*tri_a),
+                    material_index_b: mesh_b.material_index(*tri_b),
+                }),
+            }
+        }
+    }
+
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(ContactManifold {
+        normal,
+        points,
+        simplex: None,
+    })
+}
+
+/// Picks the contact normal whose cluster has the shallowest maximum depth.
+///
+/// Side faces of closed meshes cross near corners and report deep, sideways contacts; the
+/// shallowest consistent direction approximates the minimum translation instead.
+fn manifold_normal(contacts: &[(usize, usize, TriangleContact)]) -> Option<Vec3> {
+    let mut clusters: Vec<(Vec3, f32)> = Vec::new();
+    for (_, _, contact) in contacts {
+        match clusters
+            .iter_mut()
+            .find(|(normal, _)| normal.dot(contact.normal) > CLUSTER_AGREEMENT)
+        {
+            Some(cluster) => cluster.1 = cluster.1.max(contact.depth),
+            None => clusters.push((contact.normal, contact.depth)),
+        }
+    }
+
+    clusters
+        .into_iter()
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(normal, _)| normal)
+}
+
+/// Walks both BVHs together, expressing B's nodes in A's local space.
+fn overlapping_triangle_pairs(
+    mesh_a: &TriangleMesh,
+    world_a: &Transform,
+    mesh_b: &TriangleMesh,
+    world_b: &Transform,
+) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    if mesh_a.bvh.nodes.is_empty() || mesh_b.bvh.nodes.is_empty() {
+        return pairs;
+    }
+
+    let inv_rotation_a = world_a.rotation.conjugate();
+    let inv_scale_a = Mat3::from_diagonal(world_a.scale.recip());
+    let matrix = inv_scale_a
+        * Mat3::from_quat(inv_rotation_a * world_b.rotation)
+        * Mat3::from_diagonal(world_b.scale);
+    let translation = inv_scale_a * (inv_rotation_a * (world_b.position - world_a.position));
+
+    let mut stack = vec![(0usize, 0usize)];
+    while let Some((index_a, index_b)) = stack.pop() {
+        let node_a = &mesh_a.bvh.nodes[index_a];
+        let node_b = &mesh_b.bvh.nodes[index_b];
+        if !node_a
+            .bounds
+            .overlaps(&node_b.bounds.transformed(matrix, translation))
+        {
+            continue;
+        }
+
+        match (node_a.is_leaf(), node_b.is_leaf()) {
+            (true, true) => {
+                for tri_a in node_a.start..node_a.start + node_a.count {
+                    for tri_b in node_b.start..node_b.start + node_b.count {
+                        pairs.push((tri_a, tri_b));
+                    }
+                }
+            }
+            (false, true) => push_children(&mut stack, node_a, |child| (child, index_b)),
+            (true, false) => push_children(&mut stack, node_b, |child| (index_a, child)),
+            (false, false) => {
+                // Descend the larger volume first to keep the pair count balanced.
+                if node_a.bounds.radius() >= node_b.bounds.radius() {
+                    push_children(&mut stack, node_a, |child| (child, index_b));
+                } else {
+                    push_children(&mut stack, node_b, |child| (index_a, child));
+                }
+            }
+        }
+    }
+    pairs
+}
+
+fn push_children<F>(stack: &mut Vec<(usize, usize)>, node: &MeshBvhNode, pair: F)
+where
+    F: Fn(usize) -> (usize, usize),
+{
+    stack.extend(node.left.map(&pair));
+    stack.extend(node.right.map(&pair));
+}
+
+struct TriangleContact {
+    normal: Vec3,
+    depth: f32,
+    points: Vec<(Vec3, f32)>,
+}
+
+/// Contact between two world-space triangles. The returned normal points from A to B.
+///
+/// SAT over face and edge axes rejects separated pairs. Contact normals always come from the
+/// outward (counter-clockwise) face normals, which keeps them consistent across a closed mesh.
+fn triangle_contact(a: [Vec3; 3], b: [Vec3; 3]) -> Option<TriangleContact> {
+    let normal_a = (a[1] - a[0]).cross(a[2] - a[0]).try_normalize()?;
+    let normal_b = (b[1] - b[0]).cross(b[2] - b[0]).try_normalize()?;
+
+    if separated_on(&a, &b, normal_a) || separated_on(&a, &b, normal_b) {
+        return None;
+    }
+    for i in 0..3 {
+        let edge_a = a[(i + 1) % 3] - a[i];
+        for j in 0..3 {
+            let axis = edge_a.cross(b[(j + 1) % 3] - b[j]);
+            if axis.length_squared() >= AXIS_EPSILON && separated_on(&a, &b, axis.normalize()) {
+                return None;
+            }
+        }
+    }
+
+    // B sinking below A's face, or A sinking below B's face.
+    let depth_a = b
+        .iter()
+        .map(|p| (a[0] - *p).dot(normal_a))
+        .fold(0.0f32, f32::max);
+    let depth_b = a
+        .iter()
+        .map(|p| (b[0] - *p).dot(normal_b))
+        .fold(0.0f32, f32::max);
+
+    let (normal, depth, points) = if depth_a <= depth_b {
+        (normal_a, depth_a, clip_face(&a, normal_a, &b))
+    } else {
+        (-normal_b, depth_b, clip_face(&b, normal_b, &a))
+    };
+
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(TriangleContact {
+        normal,
+        depth,
+        points,
+    })
+}
+
+fn separated_on(a: &[Vec3; 3], b: &[Vec3; 3], axis: Vec3) -> bool {
+    let (min_a, max_a) = project(a, axis);
+    let (min_b, max_b) = project(b, axis);
+    max_a < min_b || max_b < min_a
+}
+
+/// Clips the incident triangle against the reference triangle's sides and keeps points
+/// lying behind the reference face.
+fn clip_face(
+    reference: &[Vec3; 3],
+    reference_normal: Vec3,
+    incident: &[Vec3; 3],
+) -> Vec<(Vec3, f32)> {
+    let planes = polygon_side_planes(reference, reference_normal);
+    clip_polygon(incident, &planes)
+        .into_iter()
+        .filter_map(|point| {
+            let depth = (reference[0] - point).dot(reference_normal);
+            (depth > 0.0).then_some((point, depth))
+        })
+        .collect()
+}
+
+fn project(triangle: &[Vec3; 3], axis: Vec3) -> (f32, f32) {
+    let d0 = triangle[0].dot(axis);
+    let d1 = triangle[1].dot(axis);
+    let d2 = triangle[2].dot(axis);
+    (d0.min(d1).min(d2), d0.max(d1).max(d2))
+}
+
+fn to_world(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.position + transform.rotation * (point * transform.scale)
+}
@@ -1,14 +1,30 @@
 //! Collision detection modules: broad-phase, narrow-phase, contact manifolds, queries, CCD.
 
 pub mod shapes;
+pub mod analytic;
 pub mod broadphase;
 pub mod narrowphase;
 pub mod contact;
 pub mod queries;
+pub mod query_pipeline;
 pub mod ccd;
 pub mod clipping;
+pub mod heightfield;
+pub mod mesh;
+pub mod plane;
+pub mod recorder;
+pub mod trigger;
 
-pub use broadphase::{BroadPhase, SpatialGrid};
-pub use contact::ContactManifold;
-pub use queries::{Raycast, RaycastHit, RaycastQuery};
-pub use ccd::CCDDetector;
+pub use broadphase::{
+    BroadPhase, BroadPhaseBackend, PairManager, SpatialGrid, SpeculativeExpansion,
+    SweepAndPrune,
+};
+pub use contact::{
+    ContactEvent, ContactEventInfo, ContactManifold, ManifoldDebugFilter, ManifoldDebugHook,
+    ManifoldDebugInfo, ManifoldPointDebugInfo,
+};
+pub use recorder::{ManifoldRecorder, RecordedFrame};
+pub use queries::{closest_points, ClosestPoints, Ray, Raycast, RaycastHit, RaycastQuery};
+pub use query_pipeline::QueryPipeline;
+pub use ccd::{CCDDetector, CcdQuality};
+pub use trigger::{TriggerEvent, TriggerOverlap, TriggerTracker};
@@ -3,6 +3,7 @@ use glam::Quat;
 use glam::Vec3;
 
 use crate::{
+    collision::{analytic, contact::ContactManifold, heightfield, mesh, plane, shapes::ShapeUtil},
     core::{
         collider::{Collider, ColliderShape},
         rigidbody::RigidBody,
@@ -17,6 +18,8 @@ pub struct GJKAlgorithm;
 
 impl GJKAlgorithm {
     const MAX_ITERATIONS: usize = 20;
+    const MAX_DISTANCE_ITERATIONS: usize = 32;
+    const DISTANCE_TOLERANCE: f32 = 1e-5;
     const EPSILON: f32 = 1e-6;
 
     pub fn intersect(
@@ -28,6 +31,83 @@ impl GJKAlgorithm {
         body_b: EntityId,
         initial_simplex: Option<&[Vec3]>,
     ) -> Option<(Contact, Vec<Vec3>)> {
+        let shapes = (
+            MarginShape::new(shape_a, transform_a, 0.0),
+            MarginShape::new(shape_b, transform_b, 0.0),
+        );
+        Self::intersect_margins(shapes, body_a, body_b, initial_simplex)
+    }
+
+    /// Collides two colliders as shrunk cores plus their collision margins.
+    ///
+    /// While the cores are apart, the contact comes straight from their closest points,
+    /// which is stable from frame to frame; EPA only runs once the cores themselves
+    /// overlap. See [`ShapeUtil::margin_core`].
+    pub fn intersect_colliders(
+        collider_a: &Collider,
+        transform_a: &Transform,
+        collider_b: &Collider,
+        transform_b: &Transform,
+        body_a: EntityId,
+        body_b: EntityId,
+        initial_simplex: Option<&[Vec3]>,
+    ) -> Option<(Contact, Vec<Vec3>)> {
+        let (core_a, margin_a) =
+            ShapeUtil::margin_core(&collider_a.shape, collider_a.margin, transform_a.scale);
+        let (core_b, margin_b) =
+            ShapeUtil::margin_core(&collider_b.shape, collider_b.margin, transform_b.scale);
+        let a = MarginShape::new(&core_a, transform_a, margin_a);
+        let b = MarginShape::new(&core_b, transform_b, margin_b);
+        let margins = margin_a + margin_b;
+
+        if margins > 0.0 {
+            if let Some((point_a, point_b)) =
+                Self::closest_points(&core_a, transform_a, &core_b, transform_b)
+            {
+                let offset = point_b - point_a;
+                let distance = offset.length();
+                if distance >= margins {
+                    return None;
+                }
+                if distance > Self::EPSILON {
+                    let normal = offset / distance;
+                    let depth = margins - distance;
+                    let surface_a = point_a + normal * margin_a;
+                    let mut contact = Self::contact(body_a, body_b, normal, depth);
+                    contact.point = surface_a - normal * depth * 0.5;
+                    return Some((contact, Vec::new()));
+                }
+            }
+        }
+        Self::intersect_margins((a, b), body_a, body_b, initial_simplex)
+    }
+
+    fn contact(body_a: EntityId, body_b: EntityId, normal: Vec3, depth: f32) -> Contact {
+        Contact {
+            body_a,
+            body_b,
+            point: Vec3::ZERO,
+            normal,
+            depth,
+            relative_velocity: 0.0,
+            feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
+            accumulated_normal_impulse: 0.0,
+            accumulated_tangent_impulse: Vec3::ZERO,
+            accumulated_rolling_impulse: Vec3::ZERO,
+            accumulated_torsional_impulse: 0.0,
+            material: MaterialPairProperties::default(),
+        }
+    }
+
+    fn intersect_margins(
+        (a, b): (MarginShape, MarginShape),
+        body_a: EntityId,
+        body_b: EntityId,
+        initial_simplex: Option<&[Vec3]>,
+    ) -> Option<(Contact, Vec<Vec3>)> {
+        let (transform_a, transform_b) = (a.transform, b.transform);
         let mut simplex: Vec<Vec3> = if let Some(cached) = initial_simplex {
             cached.to_vec()
         } else {
@@ -47,9 +127,7 @@ impl GJKAlgorithm {
         };
 
         for _ in 0..Self::MAX_ITERATIONS {
-            let support_a = Self::support(shape_a, transform_a, direction);
-            let support_b = Self::support(shape_b, transform_b, -direction);
-            let point = support_a - support_b;
+            let point = a.support(direction) - b.support(-direction);
 
             if point.dot(direction) < -1e-6 {
                 return None;
@@ -58,13 +136,7 @@ impl GJKAlgorithm {
             simplex.push(point);
             if Self::contains_origin(&mut simplex, &mut direction) {
                 // GJK confirmed intersection, now compute penetration with EPA
-                let (depth, mut normal) = EPAAlgorithm::compute_penetration(
-                    &simplex,
-                    shape_a,
-                    transform_a,
-                    shape_b,
-                    transform_b,
-                );
+                let (depth, mut normal) = EPAAlgorithm::compute_penetration(&simplex, &a, &b);
 
                 // Enforce A→B convention: normal must point from A toward B
                 let relative_pos = transform_b.position - transform_a.position;
@@ -86,23 +158,8 @@ impl GJKAlgorithm {
                     }
                 }
 
-                let contact_point =
-                    Self::support(shape_a, transform_a, normal) - normal * depth * 0.5;
-
-                let contact = Contact {
-                    body_a,
-                    body_b,
-                    point: contact_point,
-                    normal,
-                    depth,
-                    relative_velocity: 0.0,
-                    feature_id: 0,
-                    accumulated_normal_impulse: 0.0,
-                    accumulated_tangent_impulse: Vec3::ZERO,
-                    accumulated_rolling_impulse: Vec3::ZERO,
-                    accumulated_torsional_impulse: 0.0,
-                    material: MaterialPairProperties::default(),
-                };
+                let mut contact = Self::contact(body_a, body_b, normal, depth);
+                contact.point = a.support(normal) - normal * depth * 0.5;
 
                 return Some((contact, simplex));
             }
@@ -111,6 +168,63 @@ impl GJKAlgorithm {
         None
     }
 
+    /// Closest points between two separated convex shapes, as `(point_on_a, point_on_b)`.
+    ///
+    /// Runs the GJK distance iteration, tracking the support points of both shapes so the
+    /// witness points can be recovered from the final simplex. Returns `None` when the shapes
+    /// overlap.
+    pub fn closest_points(
+        shape_a: &ColliderShape,
+        transform_a: &Transform,
+        shape_b: &ColliderShape,
+        transform_b: &Transform,
+    ) -> Option<(Vec3, Vec3)> {
+        let support = |direction: Vec3| {
+            let a = Self::support(shape_a, transform_a, direction);
+            let b = Self::support(shape_b, transform_b, -direction);
+            SupportVertex { w: a - b, a, b }
+        };
+
+        let mut initial = transform_b.position - transform_a.position;
+        if initial.length_squared() < Self::EPSILON {
+            initial = Vec3::X;
+        }
+        let mut simplex = vec![support(-initial)];
+        let mut weights = vec![1.0];
+        let mut closest = simplex[0].w;
+
+        for _ in 0..Self::MAX_DISTANCE_ITERATIONS {
+            let distance_sq = closest.length_squared();
+            if distance_sq < Self::EPSILON {
+                return None;
+            }
+
+            let vertex = support(-closest);
+            // No support point gets meaningfully closer: the current point is the minimum.
+            if distance_sq - closest.dot(vertex.w) <= Self::DISTANCE_TOLERANCE * distance_sq {
+                break;
+            }
+            if simplex
+                .iter()
+                .any(|v| (v.w - vertex.w).length_squared() < Self::EPSILON)
+            {
+                break;
+            }
+
+            simplex.push(vertex);
+            (simplex, weights) = closest_on_simplex(&simplex)?;
+            closest = simplex
+                .iter()
+                .zip(&weights)
+                .map(|(v, weight)| v.w * *weight)
+                .sum();
+        }
+
+        let point_a = simplex.iter().zip(&weights).map(|(v, w)| v.a * *w).sum();
+        let point_b = simplex.iter().zip(&weights).map(|(v, w)| v.b * *w).sum();
+        Some((point_a, point_b))
+    }
+
     fn support(shape: &ColliderShape, transform: &Transform, direction: Vec3) -> Vec3 {
         match shape {
             ColliderShape::Sphere { radius } => {
@@ -186,6 +300,28 @@ impl GJKAlgorithm {
                 }
                 best_point
             }
+            ColliderShape::Heightfield { heightfield } => {
+                let mut best_point = transform.position;
+                let mut best_dot = f32::MIN;
+                for v in heightfield.vertices() {
+                    let world_v = transform.position + transform.rotation * (v * transform.scale);
+                    let dot = world_v.dot(direction);
+                    if dot > best_dot {
+                        best_dot = dot;
+                        best_point = world_v;
+                    }
+                }
+                best_point
+            }
+            ColliderShape::Cone { .. }
+            | ColliderShape::RoundedBox { .. }
+            | ColliderShape::RoundedCylinder { .. } => {
+                let dir_local = transform.rotation.conjugate() * direction;
+                let local = ShapeUtil::support(shape, dir_local * transform.scale);
+                transform.position + transform.rotation * (local * transform.scale)
+            }
+            // Planes are routed to `plane::collide` before GJK runs.
+            ColliderShape::Plane { .. } => transform.position,
             ColliderShape::Compound { shapes } => {
                 let mut best_point = transform.position;
                 let mut best_dot = f32::MIN;
@@ -288,6 +424,145 @@ impl GJKAlgorithm {
     }
 }
 
+/// Convex core in world space, inflated by a margin along every support direction.
+#[derive(Clone, Copy)]
+struct MarginShape<'a> {
+    shape: &'a ColliderShape,
+    transform: &'a Transform,
+    margin: f32,
+}
+
+impl<'a> MarginShape<'a> {
+    fn new(shape: &'a ColliderShape, transform: &'a Transform, margin: f32) -> Self {
+        Self {
+            shape,
+            transform,
+            margin,
+        }
+    }
+
+    fn support(&self, direction: Vec3) -> Vec3 {
+        let point = GJKAlgorithm::support(self.shape, self.transform, direction);
+        if self.margin > 0.0 {
+            point + direction.normalize_or_zero() * self.margin
+        } else {
+            point
+        }
+    }
+}
+
+/// Minkowski difference vertex remembering the support points that produced it.
+#[derive(Clone, Copy)]
+struct SupportVertex {
+    w: Vec3,
+    a: Vec3,
+    b: Vec3,
+}
+
+/// Reduces `simplex` to the sub-simplex nearest the origin, with barycentric weights.
+/// Returns `None` when a tetrahedron contains the origin.
+fn closest_on_simplex(simplex: &[SupportVertex]) -> Option<(Vec<SupportVertex>, Vec<f32>)> {
+    match simplex {
+        [a] => Some((vec![*a], vec![1.0])),
+        [a, b] => Some(closest_on_segment(*a, *b)),
+        [a, b, c] => Some(closest_on_triangle(*a, *b, *c)),
+        [a, b, c, d] => {
+            let faces = [
+                (*a, *b, *c, *d),
+                (*a, *c, *d, *b),
+                (*a, *d, *b, *c),
+                (*b, *d, *c, *a),
+            ];
+            let mut best: Option<(Vec<SupportVertex>, Vec<f32>)> = None;
+            let mut best_distance = f32::INFINITY;
+            for (p, q, r, opposite) in faces {
+                let normal = (q.w - p.w).cross(r.w - p.w);
+                // Only faces separating the origin from the opposite vertex can hold the minimum.
+                if normal.dot(-p.w) * normal.dot(opposite.w - p.w) >= 0.0 {
+                    continue;
+                }
+                let (vertices, weights) = closest_on_triangle(p, q, r);
+                let point: Vec3 = vertices.iter().zip(&weights).map(|(v, w)| v.w * *w).sum();
+                if point.length_squared() < best_distance {
+                    best_distance = point.length_squared();
+                    best = Some((vertices, weights));
+                }
+            }
+            best
+        }
+        _ => None,
+    }
+}
+
+fn closest_on_segment(a: SupportVertex, b: SupportVertex) -> (Vec<SupportVertex>, Vec<f32>) {
+    let ab = b.w - a.w;
+    let length_sq = ab.length_squared();
+    if length_sq < GJKAlgorithm::EPSILON {
+        return (vec![a], vec![1.0]);
+    }
+    let t = (-a.w).dot(ab) / length_sq;
+    if t <= 0.0 {
+        (vec![a], vec![1.0])
+    } else if t >= 1.0 {
+        (vec![b], vec![1.0])
+    } else {
+        (vec![a, b], vec![1.0 - t, t])
+    }
+}
+
+/// Voronoi-region search for the point of triangle `abc` nearest the origin.
+fn closest_on_triangle(
+    a: SupportVertex,
+    b: SupportVertex,
+    c: SupportVertex,
+) -> (Vec<SupportVertex>, Vec<f32>) {
+    let ab = b.w - a.w;
+    let ac = c.w - a.w;
+    let d1 = ab.dot(-a.w);
+    let d2 = ac.dot(-a.w);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return (vec![a], vec![1.0]);
+    }
+
+    let d3 = ab.dot(-b.w);
+    let d4 = ac.dot(-b.w);
+    if d3 >= 0.0 && d4 <= d3 {
+        return (vec![b], vec![1.0]);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        let t = d1 / (d1 - d3);
+        return (vec![a, b], vec![1.0 - t, t]);
+    }
+
+    let d5 = ab.dot(-c.w);
+    let d6 = ac.dot(-c.w);
+    if d6 >= 0.0 && d5 <= d6 {
+        return (vec![c], vec![1.0]);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        let t = d2 / (d2 - d6);
+        return (vec![a, c], vec![1.0 - t, t]);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        let t = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return (vec![b, c], vec![1.0 - t, t]);
+    }
+
+    let denom = va + vb + vc;
+    if denom.abs() < GJKAlgorithm::EPSILON {
+        return closest_on_segment(a, b);
+    }
+    let v = vb / denom;
+    let w = vc / denom;
+    (vec![a, b, c], vec![1.0 - v - w, v, w])
+}
+
 /// Expanding Polytope Algorithm for penetration depth calculation.
 struct EPAAlgorithm;
 
@@ -295,13 +570,8 @@ impl EPAAlgorithm {
     const MAX_ITERATIONS: usize = 32;
     const EPSILON: f32 = 1e-6;
 
-    fn compute_penetration(
-        simplex: &[Vec3],
-        shape_a: &ColliderShape,
-        transform_a: &Transform,
-        shape_b: &ColliderShape,
-        transform_b: &Transform,
-    ) -> (f32, Vec3) {
+    fn compute_penetration(simplex: &[Vec3], a: &MarginShape, b: &MarginShape) -> (f32, Vec3) {
+        let (transform_a, transform_b) = (a.transform, b.transform);
         if simplex.len() < 4 {
             // Degenerate simplex, fall back to center-to-center
             let normal = (transform_b.position - transform_a.position).normalize_or_zero();
@@ -350,9 +620,7 @@ impl EPAAlgorithm {
                 return (Self::EPSILON, normal);
             }
 
-            let support_a = GJKAlgorithm::support(shape_a, transform_a, normal);
-            let support_b = GJKAlgorithm::support(shape_b, transform_b, -normal);
-            let support = support_a - support_b;
+            let support = a.support(normal) - b.support(-normal);
             let distance = support.dot(normal);
 
             // Convergence check: if new support point doesn't expand polytope significantly
@@ -548,6 +816,8 @@ impl SATAlgorithm {
             depth: min_overlap,
             relative_velocity: 0.0,
             feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
             accumulated_normal_impulse: 0.0,
             accumulated_tangent_impulse: Vec3::ZERO,
             accumulated_rolling_impulse: Vec3::ZERO,
@@ -557,7 +827,9 @@ impl SATAlgorithm {
     }
 }
 
-/// Narrow phase dispatcher (placeholder hooking algorithms to colliders).
+/// Narrow phase dispatcher: SAT for box pairs, closed-form routines for the remaining
+/// sphere/capsule/box pairs, dedicated paths for planes, terrain, and meshes, and GJK/EPA
+/// for everything else.
 pub struct NarrowPhase;
 
 impl NarrowPhase {
@@ -586,10 +858,39 @@ impl NarrowPhase {
                 )?;
                 (c, Vec::new())
             }
-            _ => GJKAlgorithm::intersect(
-                &collider_a.shape,
+            (ColliderShape::Plane { .. }, _) | (_, ColliderShape::Plane { .. }) => {
+                let manifold = plane::collide(collider_a, body_a, collider_b, body_b)?;
+                (
+                    Self::deepest_contact(&manifold, body_a.id, body_b.id)?,
+                    Vec::new(),
+                )
+            }
+            (ColliderShape::Heightfield { .. }, _) | (_, ColliderShape::Heightfield { .. }) => {
+                let manifold = heightfield::collide(collider_a, body_a, collider_b, body_b)?;
+                (
+                    Self::deepest_contact(&manifold, body_a.id, body_b.id)?,
+                    Vec::new(),
+                )
+            }
+            (ColliderShape::Mesh { .. }, ColliderShape::Mesh { .. }) => {
+                let manifold = mesh::collide(collider_a, body_a, collider_b, body_b)?;
+                (
+                    Self::deepest_contact(&manifold, body_a.id, body_b.id)?,
+                    Vec::new(),
+                )
+            }
+            (shape_a, shape_b) if analytic::handles(shape_a, shape_b) => {
+                let manifold = analytic::collide(collider_a, body_a, collider_b, body_b)?;
+                (
+                    Self::deepest_contact(&manifold, body_a.id, body_b.id)?,
+                    Vec::new(),
+                )
+            }
+            // General convex pairs.
+            _ => GJKAlgorithm::intersect_colliders(
+                collider_a,
                 &transform_a,
-                &collider_b.shape,
+                collider_b,
                 &transform_b,
                 body_a.id,
                 body_b.id,
@@ -601,6 +902,34 @@ impl NarrowPhase {
             MaterialPairProperties::from_materials(&body_a.material, &body_b.material);
         Some((contact, simplex))
     }
+
+    /// Collapses a multi-point manifold into its deepest point.
+    fn deepest_contact(
+        manifold: &ContactManifold,
+        body_a: EntityId,
+        body_b: EntityId,
+    ) -> Option<Contact> {
+        let deepest = manifold
+            .points
+            .iter()
+            .max_by(|a, b| a.depth.partial_cmp(&b.depth).unwrap())?;
+        Some(Contact {
+            body_a,
+            body_b,
+            point: deepest.point,
+            normal: manifold.normal,
+            depth: deepest.depth,
+            relative_velocity: 0.0,
+            feature_id: deepest.feature_id,
+            material_index_a: deepest.material_index_a,
+            material_index_b: deepest.material_index_b,
+            accumulated_normal_impulse: 0.0,
+            accumulated_tangent_impulse: Vec3::ZERO,
+            accumulated_rolling_impulse: Vec3::ZERO,
+            accumulated_torsional_impulse: 0.0,
+            material: MaterialPairProperties::default(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -625,6 +954,8 @@ mod tests {
             offset: Transform::default(),
             is_trigger: false,
             collision_filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         };
 
         (body, collider)
@@ -701,6 +1032,8 @@ mod tests {
             offset: Transform::default(),
             is_trigger: false,
             collision_filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         };
 
         let mut body_b = RigidBody::new(EntityId::from_index(1));
@@ -716,6 +1049,8 @@ mod tests {
             offset: Transform::default(),
             is_trigger: false,
             collision_filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         };
 
         // Rotate A by 45 degrees around Z.
@@ -0,0 +1,227 @@
+use glam::Vec3;
+
+use crate::{
+    collision::{
+        contact::{ContactManifold, RawContactPoint},
+        shapes::ShapeUtil,
+    },
+    core::{
+        collider::{Collider, ColliderShape},
+        rigidbody::RigidBody,
+        types::Transform,
+    },
+};
+
+/// Rim samples per cylinder cap.
+const CYLINDER_RIM_SAMPLES: usize = 8;
+
+/// Generates a manifold when either collider is a plane. Normal points from A to B.
+///
+/// Spheres and capsules touch at their centre and end caps; boxes, convex hulls, meshes,
+/// cylinders, and cones test their vertices (rim samples for round edges) against the
+/// half-space. Rounded shapes test their core's vertices inflated by the border radius.
+/// Compound children are tested one by one. Plane/plane and plane/heightfield pairs return
+/// `None`.
+pub fn collide(
+    collider_a: &Collider,
+    body_a: &RigidBody,
+    collider_b: &Collider,
+    body_b: &RigidBody,
+) -> Option<ContactManifold> {
+    if let ColliderShape::Plane { normal } = &collider_a.shape {
+        return collide_against(*normal, collider_a, body_a, collider_b, body_b);
+    }
+    if let ColliderShape::Plane { normal } = &collider_b.shape {
+        let mut manifold = collide_against(*normal, collider_b, body_b, collider_a, body_a)?;
+        manifold.normal = -manifold.normal;
+        return Some(manifold);
+    }
+    None
+}
+
+/// World-space plane through `origin` whose solid side lies opposite `normal`.
+#[derive(Debug, Clone, Copy)]
+struct HalfSpace {
+    origin: Vec3,
+    normal: Vec3,
+}
+
+impl HalfSpace {
+    fn depth(&self, point: Vec3) -> f32 {
+        -(point - self.origin).dot(self.normal)
+    }
+
+    fn project(&self, point: Vec3) -> Vec3 {
+        point + self.normal * self.depth(point)
+    }
+}
+
+fn collide_against(
+    local_normal: Vec3,
+    plane_collider: &Collider,
+    plane_body: &RigidBody,
+    other_collider: &Collider,
+    other_body: &RigidBody,
+) -> Option<ContactManifold> {
+    let plane = plane_collider.world_transform(&plane_body.transform);
+    let normal = (plane.rotation * (local_normal / plane.scale)).normalize_or_zero();
+    if normal == Vec3::ZERO {
+        return None;
+    }
+    let half_space = HalfSpace {
+        origin: plane.position,
+        normal,
+    };
+
+    let other = other_collider.world_transform(&other_body.transform);
+    let mut points = Vec::new();
+    shape_points(&half_space, &other_collider.shape, &other, 0, &mut points);
+    if points.is_empty() {
+        return None;
+    }
+
+    Some(ContactManifold {
+        normal,
+        points,
+        simplex: None,
+    })
+}
+
+fn shape_points(
+    plane: &HalfSpace,
+    shape: &ColliderShape,
+    world: &Transform,
+    feature_base: u64,
+    points: &mut Vec<RawContactPoint>,
+) {
+    match shape {
+        ColliderShape::Sphere { radius } => {
+            let radius = radius.max(0.0) * world.scale.abs().max_element();
+            push_rounded(plane, world.position, radius, feature_base, points);
+        }
+        ColliderShape::Capsule { radius, height } => {
+            let radius = radius.max(0.0) * world.scale.x.abs().max(world.scale.z.abs());
+            let cap_offset = world.rotation * (Vec3::Y * 0.5 * height * world.scale.y);
+            // Resting capsules need both end caps in the manifold to stay stable.
+            push_rounded(
+                plane,
+                world.position + cap_offset,
+                radius,
+                feature_base,
+                points,
+            );
+            push_rounded(
+                plane,
+                world.position - cap_offset,
+                radius,
+                feature_base | 1,
+                points,
+            );
+        }
+        ColliderShape::Box { half_extents } => {
+            let corners = (0..8).map(|i| {
+                Vec3::new(
+                    if i & 1 == 0 { -1.0 } else { 1.0 },
+                    if i & 2 == 0 { -1.0 } else { 1.0 },
+                    if i & 4 == 0 { -1.0 } else { 1.0 },
+                ) * *half_extents
+            });
+            push_vertices(plane, world, corners, feature_base, points);
+        }
+        ColliderShape::Cylinder { radius, height } => {
+            let rim = (0..2 * CYLINDER_RIM_SAMPLES).map(|i| {
+                let angle = (i % CYLINDER_RIM_SAMPLES) as f32 * std::f32::consts::TAU
+                    / CYLINDER_RIM_SAMPLES as f32;
+                let y = if i < CYLINDER_RIM_SAMPLES { 0.5 } else { -0.5 } * height;
+                Vec3::new(radius * angle.cos(), y, radius * angle.sin())
+            });
+            push_vertices(plane, world, rim, feature_base, points);
+        }
+        ColliderShape::Cone { radius, height } => {
+            let apex = std::iter::once(Vec3::Y * 0.5 * height);
+            let rim = (0..CYLINDER_RIM_SAMPLES).map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / CYLINDER_RIM_SAMPLES as f32;
+                Vec3::new(radius * angle.cos(), -0.5 * height, radius * angle.sin())
+            });
+            push_vertices(plane, world, apex.chain(rim), feature_base, points);
+        }
+        ColliderShape::RoundedBox { .. } | ColliderShape::RoundedCylinder { .. } => {
+            let (core, border_radius) = match ShapeUtil::rounded_core(shape) {
+                Some(rounded) => rounded,
+                None => return,
+            };
+            // Test the core against the plane pushed out by the border, then project back.
+            let radius = border_radius * world.scale.abs().max_element();
+            let raised = HalfSpace {
+                origin: plane.origin + plane.normal * radius,
+                normal: plane.normal,
+            };
+            let first = points.len();
+            shape_points(&raised, &core, world, feature_base, points);
+            for contact in &mut points[first..] {
+                contact.point = plane.project(contact.point);
+            }
+        }
+        ColliderShape::ConvexHull { vertices } => {
+            push_vertices(plane, world, vertices.iter().copied(), feature_base, points);
+        }
+        ColliderShape::Mesh { mesh } => {
+            push_vertices(
+                plane,
+                world,
+                mesh.vertices.iter().copied(),
+                feature_base,
+                points,
+            );
+        }
+        ColliderShape::Compound { shapes } => {
+            for (child, (local_transform, shape)) in shapes.iter().enumerate() {
+                let child_world = world.combine(local_transform);
+                let base = feature_base | ((child as u64 + 1) << 32);
+                shape_points(plane, shape, &child_world, base, points);
+            }
+        }
+        ColliderShape::Heightfield { .. } | ColliderShape::Plane { .. } => {}
+    }
+}
+
+fn push_rounded(
+    plane: &HalfSpace,
+    center: Vec3,
+    radius: f32,
+    feature_id: u64,
+    points: &mut Vec<RawContactPoint>,
+) {
+    let depth = plane.depth(center) + radius;
+    if depth > 0.0 {
+        points.push(RawContactPoint {
+            point: plane.project(center),
+            depth,
+            feature_id,
+            material_index_a: None,
+            material_index_b: None,
+        });
+    }
+}
+
+fn push_vertices(
+    plane: &HalfSpace,
+    world: &Transform,
+    local_vertices: impl Iterator<Item = Vec3>,
+    feature_base: u64,
+    points: &mut Vec<RawContactPoint>,
+) {
+    for (index, local) in local_vertices.enumerate() {
+        let vertex = world.position + world.rotation * (local * world.scale);
+        let depth = plane.depth(vertex);
+        if depth > 0.0 {
+            points.push(RawContactPoint {
+                point: vertex,
+                depth,
+                feature_id: feature_base | index as u64,
+                material_index_a: None,
+                material_index_b: None,
+            });
+        }
+    }
+}
@@ -1,8 +1,16 @@
-use glam::Vec3;
+use glam::{Mat3, Vec3};
 
 use crate::{
+    collision::{
+        broadphase::BroadPhase,
+        narrowphase::{GJKAlgorithm, NarrowPhase},
+        query_pipeline::QueryPipeline,
+        shapes::ShapeUtil,
+    },
     core::{
         collider::{Collider, ColliderShape},
+        heightfield::Heightfield,
+        rigidbody::RigidBody,
         soa::BodiesSoA,
         types::Transform,
     },
@@ -17,6 +25,8 @@ pub struct RaycastHit {
     pub point: Vec3,
     pub normal: Vec3,
     pub distance: f32,
+    /// Material table index of the hit triangle for meshes and heightfields that carry one.
+    pub material_index: Option<u16>,
 }
 
 #[derive(Debug, Clone)]
@@ -54,6 +64,73 @@ impl RaycastQuery {
             ..Self::default()
         }
     }
+
+    /// Casts many rays sharing this query's layer and trigger filters and returns the
+    /// closest hit for each, in input order. The query's own ray and `closest_only` are
+    /// ignored.
+    ///
+    /// Builds a [`QueryPipeline`] over the colliders once, then walks it with each packet of
+    /// four rays; see [`QueryPipeline::cast_batch`]. Batches cast against an unchanged
+    /// scene should reuse a pipeline, such as the world's.
+    pub fn cast_batch(
+        &self,
+        rays: &[Ray],
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) -> Vec<Option<RaycastHit>> {
+        let mut pipeline = QueryPipeline::new();
+        pipeline.update(colliders, bodies);
+        pipeline.cast_batch(self, rays, colliders)
+    }
+
+    pub(crate) fn accepts(&self, collider: &Collider) -> bool {
+        !(self.ignore_triggers && collider.is_trigger)
+            && collider.collision_filter.layer & self.layer_mask != 0
+            && collider.collision_filter.mask & self.query_layer != 0
+    }
+}
+
+/// A single ray for [`RaycastQuery::cast_batch`].
+#[derive(Debug, Clone, Copy)]
+pub struct Ray {
+    pub origin: Vec3,
+    pub direction: Vec3,
+    pub max_distance: f32,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3, direction: Vec3, max_distance: f32) -> Self {
+        Self {
+            origin,
+            direction,
+            max_distance,
+        }
+    }
+}
+
+/// Conservative world-space bounds of a collider shape.
+pub(crate) fn world_bounds(shape: &ColliderShape, transform: &Transform) -> (Vec3, Vec3) {
+    let local = match shape {
+        ColliderShape::Mesh { mesh } => Some(mesh.bounds),
+        ColliderShape::Heightfield { heightfield } => Some(heightfield.bounds),
+        ColliderShape::Plane { .. } => {
+            return (Vec3::splat(f32::MIN), Vec3::splat(f32::MAX));
+        }
+        _ => None,
+    };
+    match local {
+        Some(bounds) => {
+            let matrix = Mat3::from_quat(transform.rotation) * Mat3::from_diagonal(transform.scale);
+            let bounds = bounds.transformed(matrix, transform.position);
+            (bounds.min, bounds.max)
+        }
+        None => {
+            let radius =
+                BroadPhase::get_collider_radius(shape) * transform.scale.abs().max_element();
+            let extent = Vec3::splat(radius);
+            (transform.position - extent, transform.position + extent)
+        }
+    }
 }
 
 pub struct Raycast;
@@ -67,6 +144,34 @@ impl Raycast {
         Self::cast_with_filter(query, colliders, bodies, |_, _| true)
     }
 
+    /// Returns every hit along the ray sorted by distance, regardless of `closest_only`.
+    pub fn cast_all(
+        query: &RaycastQuery,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) -> Vec<RaycastHit> {
+        Self::cast_all_with_filter(query, colliders, bodies, |_| true)
+    }
+
+    /// Like [`Raycast::cast_all`], skipping colliders whose owning body fails `filter`.
+    pub fn cast_all_with_filter<F>(
+        query: &RaycastQuery,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        filter: F,
+    ) -> Vec<RaycastHit>
+    where
+        F: Fn(EntityId) -> bool,
+    {
+        let query = RaycastQuery {
+            closest_only: false,
+            ..query.clone()
+        };
+        Self::cast_with_filter(&query, colliders, bodies, |_, collider| {
+            filter(collider.rigidbody_id)
+        })
+    }
+
     pub fn cast_with_filter<F>(
         query: &RaycastQuery,
         colliders: &Arena<Collider>,
@@ -84,13 +189,7 @@ impl Raycast {
                 None => continue,
             };
 
-            if query.ignore_triggers && collider.is_trigger {
-                continue;
-            }
-            if collider.collision_filter.layer & query.layer_mask == 0 {
-                continue;
-            }
-            if collider.collision_filter.mask & query.query_layer == 0 {
+            if !query.accepts(collider) {
                 continue;
             }
             if !filter(collider.id, collider) {
@@ -122,7 +221,7 @@ impl Raycast {
         }
     }
 
-    fn ray_shape_test(
+    pub(crate) fn ray_shape_test(
         query: &RaycastQuery,
         shape: &ColliderShape,
         transform: &Transform,
@@ -138,6 +237,7 @@ impl Raycast {
                         point,
                         normal: (point - transform.position).normalize(),
                         distance,
+                        material_index: None,
                     }
                 })
             }
@@ -149,6 +249,7 @@ impl Raycast {
                         point,
                         normal,
                         distance,
+                        material_index: None,
                     },
                 )
             }
@@ -160,6 +261,7 @@ impl Raycast {
                         point,
                         normal,
                         distance,
+                        material_index: None,
                     },
                 )
             }
@@ -171,9 +273,36 @@ impl Raycast {
                         point,
                         normal,
                         distance,
+                        material_index: None,
                     },
                 )
             }
+            ColliderShape::Cone { .. }
+            | ColliderShape::RoundedBox { .. }
+            | ColliderShape::RoundedCylinder { .. } => Self::ray_distance_field(
+                query, shape, transform,
+            )
+            .map(|(point, distance, normal)| RaycastHit {
+                body_id,
+                collider_id,
+                point,
+                normal,
+                distance,
+                material_index: None,
+            }),
+            ColliderShape::Plane { normal } => {
+                let normal = (transform.rotation * (*normal / transform.scale)).normalize_or_zero();
+                Self::ray_plane(query, transform.position, normal).map(|(point, distance)| {
+                    RaycastHit {
+                        body_id,
+                        collider_id,
+                        point,
+                        normal,
+                        distance,
+                        material_index: None,
+                    }
+                })
+            }
             ColliderShape::Compound { shapes } => shapes
                 .iter()
                 .filter_map(|(local_transform, shape)| {
@@ -184,6 +313,9 @@ impl Raycast {
             ColliderShape::Mesh { mesh } => {
                 Self::ray_mesh(query, mesh, transform, collider_id, body_id)
             }
+            ColliderShape::Heightfield { heightfield } => {
+                Self::ray_heightfield(query, heightfield, transform, collider_id, body_id)
+            }
             _ => None,
         }
     }
@@ -207,6 +339,79 @@ impl Raycast {
         Some((point, t))
     }
 
+    /// Sphere-traces the shape's signed distance field. Rays starting inside miss, like the
+    /// analytic shapes.
+    fn ray_distance_field(
+        query: &RaycastQuery,
+        shape: &ColliderShape,
+        transform: &Transform,
+    ) -> Option<(Vec3, f32, Vec3)> {
+        const MAX_STEPS: usize = 128;
+        const SURFACE_EPSILON: f32 = 1e-4;
+        const GRADIENT_STEP: f32 = 1e-3;
+
+        let dir = query.direction.normalize_or_zero();
+        if dir == Vec3::ZERO || transform.scale.min_element().abs() < 1e-6 {
+            return None;
+        }
+        let inverse_rotation = transform.rotation.conjugate();
+        let to_local =
+            |point: Vec3| inverse_rotation * (point - transform.position) / transform.scale;
+        let local_speed = (inverse_rotation * dir / transform.scale).length();
+
+        // Skip straight to the bounding sphere.
+        let bound = shape.bounding_radius() * transform.scale.abs().max_element();
+        let along = (transform.position - query.origin).dot(dir);
+        let closest = query.origin + dir * along;
+        if (closest - transform.position).length_squared() > bound * bound {
+            return None;
+        }
+        let mut t = (along - bound).max(0.0);
+
+        for _ in 0..MAX_STEPS {
+            if t > query.max_distance {
+                return None;
+            }
+            let local = to_local(query.origin + dir * t);
+            let distance = ShapeUtil::signed_distance(shape, local)?;
+            if distance < SURFACE_EPSILON {
+                if t == 0.0 && distance < 0.0 {
+                    return None;
+                }
+                let gradient = Vec3::new(
+                    Self::distance_difference(shape, local, Vec3::X * GRADIENT_STEP),
+                    Self::distance_difference(shape, local, Vec3::Y * GRADIENT_STEP),
+                    Self::distance_difference(shape, local, Vec3::Z * GRADIENT_STEP),
+                );
+                let normal =
+                    (transform.rotation * (gradient / transform.scale)).normalize_or_zero();
+                return Some((query.origin + dir * t, t, normal));
+            }
+            t += distance / local_speed;
+        }
+        None
+    }
+
+    fn distance_difference(shape: &ColliderShape, point: Vec3, offset: Vec3) -> f32 {
+        let ahead = ShapeUtil::signed_distance(shape, point + offset).unwrap_or(0.0);
+        let behind = ShapeUtil::signed_distance(shape, point - offset).unwrap_or(0.0);
+        ahead - behind
+    }
+
+    /// Hits only from the front side of the plane.
+    fn ray_plane(query: &RaycastQuery, origin: Vec3, normal: Vec3) -> Option<(Vec3, f32)> {
+        let dir = query.direction.normalize_or_zero();
+        let denom = dir.dot(normal);
+        if denom >= -1e-6 {
+            return None;
+        }
+        let t = (origin - query.origin).dot(normal) / denom;
+        if t < 0.0 || t > query.max_distance {
+            return None;
+        }
+        Some((query.origin + dir * t, t))
+    }
+
     fn ray_aabb(
         query: &RaycastQuery,
         center: Vec3,
@@ -231,12 +436,12 @@ impl Raycast {
                 let inv_dir = 1.0 / dir_component;
                 let mut t1 = (min - origin_component) * inv_dir;
                 let mut t2 = (max - origin_component) * inv_dir;
+                // The face a ray enters through always faces against it.
                 let mut axis_normal = Vec3::ZERO;
                 axis_normal[i] = -dir_component.signum();
 
                 if t1 > t2 {
                     std::mem::swap(&mut t1, &mut t2);
-                    axis_normal = -axis_normal;
                 }
 
                 if t1 > t_min {
@@ -292,16 +497,18 @@ impl Raycast {
 
         let matrix = transform.to_matrix();
         let mut best: Option<(Vec3, f32, Vec3)> = None;
+        let mut best_triangle = 0;
 
-        for tri in &mesh.indices {
+        for (index, tri) in mesh.indices.iter().enumerate() {
             let v0 = matrix.transform_point3(mesh.vertices[tri[0] as usize]);
             let v1 = matrix.transform_point3(mesh.vertices[tri[1] as usize]);
             let v2 = matrix.transform_point3(mesh.vertices[tri[2] as usize]);
 
             if let Some((distance, normal)) = Self::ray_triangle(query.origin, dir, v0, v1, v2) {
-                if distance <= query.max_distance {
-                    let point = query.origin + dir * distance;
-                    best = Self::closer_hit(best, Some((point, distance, normal)));
+                let closer = best.is_none_or(|(_, best_distance, _)| distance < best_distance);
+                if distance <= query.max_distance && closer {
+                    best = Some((query.origin + dir * distance, distance, normal));
+                    best_triangle = index;
                 }
             }
         }
@@ -312,9 +519,144 @@ impl Raycast {
             point,
             normal,
             distance,
+            material_index: mesh.material_index(best_triangle),
         })
     }
 
+    /// Walks the grid cells under the ray (2D DDA) and stops at the first cell with a hit.
+    /// Hole cells are stepped over.
+    fn ray_heightfield(
+        query: &RaycastQuery,
+        heightfield: &Heightfield,
+        transform: &Transform,
+        collider_id: EntityId,
+        body_id: EntityId,
+    ) -> Option<RaycastHit> {
+        let dir = query.direction.normalize_or_zero();
+        if dir == Vec3::ZERO {
+            return None;
+        }
+
+        // Ray parameters are shared between spaces, so `t` stays a world distance.
+        let inv_rotation = transform.rotation.conjugate();
+        let origin = inv_rotation * (query.origin - transform.position) / transform.scale;
+        let local_dir = inv_rotation * dir / transform.scale;
+
+        let (t_enter, t_exit) = Self::ray_slab(
+            origin,
+            local_dir,
+            heightfield.bounds.min,
+            heightfield.bounds.max,
+            query.max_distance,
+        )?;
+
+        let entry = origin + local_dir * t_enter;
+        let (gu, gv) = heightfield.grid_coords(entry);
+        let last_col = heightfield.cell_cols() as i64 - 1;
+        let last_row = heightfield.cell_rows() as i64 - 1;
+        let mut col = (gu.floor() as i64).clamp(0, last_col);
+        let mut row = (gv.floor() as i64).clamp(0, last_row);
+
+        let du = local_dir.x / heightfield.scale.x;
+        let dv = local_dir.z / heightfield.scale.z;
+        let step_col: i64 = if du > 0.0 { 1 } else { -1 };
+        let step_row: i64 = if dv > 0.0 { 1 } else { -1 };
+        let boundary = |cell: i64, step: i64| (cell + if step > 0 { 1 } else { 0 }) as f32;
+        let mut t_next_col = if du.abs() > 1e-9 {
+            t_enter + (boundary(col, step_col) - gu) / du
+        } else {
+            f32::INFINITY
+        };
+        let mut t_next_row = if dv.abs() > 1e-9 {
+            t_enter + (boundary(row, step_row) - gv) / dv
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_col = if du.abs() > 1e-9 {
+            1.0 / du.abs()
+        } else {
+            f32::INFINITY
+        };
+        let t_delta_row = if dv.abs() > 1e-9 {
+            1.0 / dv.abs()
+        } else {
+            f32::INFINITY
+        };
+
+        loop {
+            let mut best: Option<(f32, Vec3, u32)> = None;
+            let (r, c) = (row as usize, col as usize);
+            // Holes skip the triangle tests but still step the walk on to the next cell.
+            if !heightfield.is_hole(r, c) {
+                let triangles = heightfield.cell_triangles(r, c);
+                for (index, [v0, v1, v2]) in triangles.into_iter().enumerate() {
+                    if let Some((t, normal)) = Self::ray_triangle(origin, local_dir, v0, v1, v2) {
+                        if t <= query.max_distance && best.is_none_or(|(best_t, _, _)| t < best_t) {
+                            best = Some((t, normal, heightfield.triangle_id(r, c, index)));
+                        }
+                    }
+                }
+            }
+
+            if let Some((distance, normal, triangle)) = best {
+                return Some(RaycastHit {
+                    body_id,
+                    collider_id,
+                    point: query.origin + dir * distance,
+                    normal: (transform.rotation * (normal / transform.scale)).normalize_or_zero(),
+                    distance,
+                    material_index: heightfield.material_index(triangle),
+                });
+            }
+
+            if t_next_col.min(t_next_row) > t_exit {
+                return None;
+            }
+            if t_next_col < t_next_row {
+                col += step_col;
+                t_next_col += t_delta_col;
+            } else {
+                row += step_row;
+                t_next_row += t_delta_row;
+            }
+            if col < 0 || col > last_col || row < 0 || row > last_row {
+                return None;
+            }
+        }
+    }
+
+    /// Entry/exit parameters of a ray against an AABB, clamped to `[0, max_distance]`.
+    fn ray_slab(
+        origin: Vec3,
+        dir: Vec3,
+        min: Vec3,
+        max: Vec3,
+        max_distance: f32,
+    ) -> Option<(f32, f32)> {
+        let mut t_min = 0.0f32;
+        let mut t_max = max_distance;
+        for i in 0..3 {
+            if dir[i].abs() < 1e-9 {
+                if origin[i] < min[i] || origin[i] > max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / dir[i];
+            let mut t1 = (min[i] - origin[i]) * inv;
+            let mut t2 = (max[i] - origin[i]) * inv;
+            if t1 > t2 {
+                std::mem::swap(&mut t1, &mut t2);
+            }
+            t_min = t_min.max(t1);
+            t_max = t_max.min(t2);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some((t_min, t_max))
+    }
+
     fn ray_triangle(origin: Vec3, dir: Vec3, v0: Vec3, v1: Vec3, v2: Vec3) -> Option<(f32, Vec3)> {
         let edge1 = v1 - v0;
         let edge2 = v2 - v0;
@@ -424,3 +766,53 @@ impl Raycast {
         }
     }
 }
+
+/// Closest features of two colliders.
+#[derive(Debug, Clone, Copy)]
+pub struct ClosestPoints {
+    /// Gap between the shapes; negative values are penetration depths.
+    pub distance: f32,
+    pub point_a: Vec3,
+    pub point_b: Vec3,
+    /// Unit direction from A toward B.
+    pub normal: Vec3,
+}
+
+/// Computes the separation and witness points between two colliders.
+///
+/// Separated shapes use GJK distance; overlapping shapes report the narrow-phase penetration
+/// as a negative distance. Meshes, heightfields, and compounds are treated as their convex
+/// hulls when separated. Returns `None` if the overlap could not be resolved.
+pub fn closest_points(
+    collider_a: &Collider,
+    body_a: &RigidBody,
+    collider_b: &Collider,
+    body_b: &RigidBody,
+) -> Option<ClosestPoints> {
+    let transform_a = collider_a.world_transform(&body_a.transform);
+    let transform_b = collider_b.world_transform(&body_b.transform);
+
+    if let Some((point_a, point_b)) = GJKAlgorithm::closest_points(
+        &collider_a.shape,
+        &transform_a,
+        &collider_b.shape,
+        &transform_b,
+    ) {
+        let offset = point_b - point_a;
+        return Some(ClosestPoints {
+            distance: offset.length(),
+            point_a,
+            point_b,
+            normal: offset.normalize_or_zero(),
+        });
+    }
+
+    let (contact, _) = NarrowPhase::collide(collider_a, body_a, collider_b, body_b, None)?;
+    let half_depth = contact.normal * contact.depth * 0.5;
+    Some(ClosestPoints {
+        distance: -contact.depth,
+        point_a: contact.point + half_depth,
+        point_b: contact.point - half_depth,
+        normal: contact.normal,
+    })
+}
@@ -0,0 +1,337 @@
+use glam::{Vec3, Vec4};
+
+use crate::{
+    collision::queries::{world_bounds, Ray, Raycast, RaycastHit, RaycastQuery},
+    core::{
+        collider::{Collider, ColliderShape},
+        mesh::Aabb,
+        soa::BodiesSoA,
+        types::Transform,
+    },
+    utils::{
+        allocator::{Arena, EntityId},
+        simd::RayPacket,
+    },
+};
+
+/// Collider as it was posed at the last [`QueryPipeline::update`].
+#[derive(Debug, Clone)]
+struct Entry {
+    collider_id: EntityId,
+    body_id: EntityId,
+    transform: Transform,
+    bounds: Aabb,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Aabb,
+    /// Indices of the two children, or `None` for leaves.
+    children: Option<(usize, usize)>,
+    start: usize,
+    count: usize,
+}
+
+/// Bounding volume hierarchy over the world bounds of every collider, for answering many
+/// ray casts and overlap tests without scanning the collider arena for each.
+///
+/// The hierarchy is a snapshot: [`PhysicsWorld`](crate::world::PhysicsWorld) rebuilds its
+/// pipeline after every step, and colliders moved or added since are not seen until the
+/// next [`Self::update`]. Shapes are still read from the arena passed to each query, so
+/// colliders removed since the update are skipped.
+#[derive(Debug, Clone, Default)]
+pub struct QueryPipeline {
+    nodes: Vec<Node>,
+    entries: Vec<Entry>,
+    /// Planes, whose bounds are infinite, are tested on every query instead.
+    planes: Vec<Entry>,
+}
+
+impl QueryPipeline {
+    const MAX_LEAF_COLLIDERS: usize = 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rebuilds the hierarchy from the colliders' current poses.
+    pub fn update(&mut self, colliders: &Arena<Collider>, bodies: &BodiesSoA) {
+        self.nodes.clear();
+        self.entries.clear();
+        self.planes.clear();
+        for collider in colliders.iter() {
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let transform = collider.world_transform(body.transform());
+            let (min, max) = world_bounds(&collider.shape, &transform);
+            let entry = Entry {
+                collider_id: collider.id,
+                body_id: body.id(),
+                transform,
+                bounds: Aabb::new(min, max),
+            };
+            if matches!(collider.shape, ColliderShape::Plane { .. }) {
+                self.planes.push(entry);
+            } else {
+                self.entries.push(entry);
+            }
+        }
+        if !self.entries.is_empty() {
+            let count = self.entries.len();
+            self.build_node(0, count);
+        }
+    }
+
+    /// Number of colliders in the snapshot.
+    pub fn len(&self) -> usize {
+        self.entries.len() + self.planes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn build_node(&mut self, start: usize, count: usize) -> usize {
+        let entries = &mut self.entries[start..start + count];
+        let mut bounds = Aabb::empty();
+        let mut centroid_bounds = Aabb::empty();
+        for entry in entries.iter() {
+            bounds = bounds.union(&entry.bounds);
+            centroid_bounds.extend(entry.bounds.center());
+        }
+
+        let index = self.nodes.len();
+        self.nodes.push(Node {
+            bounds,
+            children: None,
+            start,
+            count,
+        });
+        if count <= Self::MAX_LEAF_COLLIDERS {
+            return index;
+        }
+
+        // Median split along the axis the centroids spread furthest on.
+        let axis = (centroid_bounds.max - centroid_bounds.min).max_position();
+        let mid = count / 2;
+        entries.select_nth_unstable_by(mid, |a, b| {
+            a.bounds.center()[axis].total_cmp(&b.bounds.center()[axis])
+        });
+        let left = self.build_node(start, mid);
+        let right = self.build_node(start + mid, count - mid);
+        self.nodes[index].children = Some((left, right));
+        index
+    }
+
+    /// Ray cast against the snapshot, with the same results as [`Raycast::cast`] would give
+    /// for the colliders' poses at the last update.
+    pub fn cast_ray(&self, query: &RaycastQuery, colliders: &Arena<Collider>) -> Vec<RaycastHit> {
+        self.cast_ray_with_filter(query, colliders, |_, _| true)
+    }
+
+    /// Like [`Self::cast_ray`], skipping colliders rejected by `filter`.
+    pub fn cast_ray_with_filter<F>(
+        &self,
+        query: &RaycastQuery,
+        colliders: &Arena<Collider>,
+        mut filter: F,
+    ) -> Vec<RaycastHit>
+    where
+        F: FnMut(EntityId, &Collider) -> bool,
+    {
+        let direction = query.direction.normalize_or_zero();
+        let inverse_direction = direction.recip();
+        let mut hits = Vec::new();
+        let mut test = |entry: &Entry| {
+            let Some(collider) = colliders.get(entry.collider_id) else {
+                return;
+            };
+            if !query.accepts(collider) || !filter(collider.id, collider) {
+                return;
+            }
+            if let Some(hit) = Raycast::ray_shape_test(
+                query,
+                &collider.shape,
+                &entry.transform,
+                entry.collider_id,
+                entry.body_id,
+            ) {
+                hits.push(hit);
+            }
+        };
+
+        self.planes.iter().for_each(&mut test);
+        if direction != Vec3::ZERO {
+            self.visit(
+                |bounds| ray_enters(query.origin, inverse_direction, query.max_distance, bounds),
+                &mut test,
+            );
+        }
+
+        hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+        if query.closest_only {
+            hits.truncate(1);
+        }
+        hits
+    }
+
+    /// Closest hit for each ray of a batch sharing `query`'s filters, in input order, like
+    /// [`RaycastQuery::cast_batch`] for the colliders' poses at the last update.
+    ///
+    /// Each packet of four rays walks the tree once with a SIMD slab test, carrying down only
+    /// the lanes that entered the node. A lane's reach shrinks to its closest hit so far, so
+    /// nodes behind it are skipped.
+    pub fn cast_batch(
+        &self,
+        query: &RaycastQuery,
+        rays: &[Ray],
+        colliders: &Arena<Collider>,
+    ) -> Vec<Option<RaycastHit>> {
+        let mut results = Vec::with_capacity(rays.len());
+        let mut stack = Vec::new();
+        for packet in rays.chunks(4) {
+            let queries: Vec<RaycastQuery> = packet
+                .iter()
+                .map(|ray| RaycastQuery {
+                    origin: ray.origin,
+                    direction: ray.direction.normalize_or_zero(),
+                    max_distance: ray.max_distance,
+                    ..query.clone()
+                })
+                .collect();
+            let lanes: Vec<(Vec3, Vec3, f32)> = queries
+                .iter()
+                .map(|query| (query.origin, query.direction, query.max_distance))
+                .collect();
+            let mut simd_packet = RayPacket::new(&lanes);
+            let mut best: [Option<RaycastHit>; 4] = Default::default();
+
+            let mut test = |entry: &Entry, mask: u32, simd_packet: &mut RayPacket| {
+                let Some(collider) = colliders.get(entry.collider_id) else {
+                    return;
+                };
+                if !query.accepts(collider) {
+                    return;
+                }
+                for (lane, query) in queries.iter().enumerate() {
+                    if mask & (1 << lane) == 0 {
+                        continue;
+                    }
+                    let Some(hit) = Raycast::ray_shape_test(
+                        query,
+                        &collider.shape,
+                        &entry.transform,
+                        entry.collider_id,
+                        entry.body_id,
+                    ) else {
+                        continue;
+                    };
+                    if best[lane]
+                        .as_ref()
+                        .is_none_or(|current| hit.distance < current.distance)
+                    {
+                        let mut reach = simd_packet.max_distance.to_array();
+                        reach[lane] = hit.distance;
+                        simd_packet.max_distance = Vec4::from_array(reach);
+                        best[lane] = Some(hit);
+                    }
+                }
+            };
+
+            let all_lanes = (1 << packet.len()) - 1;
+            for plane in &self.planes {
+                test(plane, all_lanes, &mut simd_packet);
+            }
+            if !self.nodes.is_empty() {
+                stack.push((0, all_lanes));
+            }
+            while let Some((index, mask)) = stack.pop() {
+                let node = &self.nodes[index];
+                let mask = mask & simd_packet.slab_test(node.bounds.min, node.bounds.max);
+                if mask == 0 {
+                    continue;
+                }
+                match node.children {
+                    Some((left, right)) => {
+                        stack.push((right, mask));
+                        stack.push((left, mask));
+                    }
+                    None => {
+                        for entry in &self.entries[node.start..node.start + node.count] {
+                            let mask =
+                                mask & simd_packet.slab_test(entry.bounds.min, entry.bounds.max);
+                            if mask != 0 {
+                                test(entry, mask, &mut simd_packet);
+                            }
+                        }
+                    }
+                }
+            }
+            results.extend(best.into_iter().take(packet.len()));
+        }
+        results
+    }
+
+    /// Ids of the colliders whose bounds overlap `bounds`, and of the planes with part of
+    /// `bounds` behind them. Collider bounds are conservative; follow up with a narrow-phase
+    /// test where exact contact matters.
+    pub fn intersect_aabb(&self, bounds: &Aabb, colliders: &Arena<Collider>) -> Vec<EntityId> {
+        let mut found = Vec::new();
+        for plane in &self.planes {
+            let Some(ColliderShape::Plane { normal }) = colliders
+                .get(plane.collider_id)
+                .map(|collider| &collider.shape)
+            else {
+                continue;
+            };
+            let transform = &plane.transform;
+            let normal = (transform.rotation * (*normal / transform.scale)).normalize_or_zero();
+            let distance = (bounds.center() - transform.position).dot(normal);
+            if distance <= bounds.extent().dot(normal.abs()) {
+                found.push(plane.collider_id);
+            }
+        }
+        self.visit(
+            |node| node.overlaps(bounds),
+            |entry| {
+                if entry.bounds.overlaps(bounds) && colliders.get(entry.collider_id).is_some() {
+                    found.push(entry.collider_id);
+                }
+            },
+        );
+        found
+    }
+
+    /// Calls `leaf` for every entry in the nodes whose bounds pass `enter`.
+    fn visit(&self, mut enter: impl FnMut(&Aabb) -> bool, mut leaf: impl FnMut(&Entry)) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if !enter(&node.bounds) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => {
+                    stack.push(right);
+                    stack.push(left);
+                }
+                None => self.entries[node.start..node.start + node.count]
+                    .iter()
+                    .for_each(&mut leaf),
+            }
+        }
+    }
+}
+
+/// Slab test: whether the ray reaches `bounds` within `max_distance`.
+fn ray_enters(origin: Vec3, inverse_direction: Vec3, max_distance: f32, bounds: &Aabb) -> bool {
+    let t1 = (bounds.min - origin) * inverse_direction;
+    let t2 = (bounds.max - origin) * inverse_direction;
+    let near = t1.min(t2).max_element().max(0.0);
+    let far = t1.max(t2).min_element().min(max_distance);
+    near <= far
+}
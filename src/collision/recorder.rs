@@ -0,0 +1,178 @@
+use std::{collections::VecDeque, fmt::Write as _, io};
+
+use glam::Vec3;
+
+use crate::collision::contact::ManifoldDebugInfo;
+
+/// Manifolds captured at the end of one fixed step.
+#[derive(Debug, Clone)]
+pub struct RecordedFrame {
+    pub frame: u32,
+    pub manifolds: Vec<ManifoldDebugInfo>,
+}
+
+/// Ring buffer of per-frame manifold snapshots for offline contact debugging.
+///
+/// Attach one with
+/// [`PhysicsWorld::set_manifold_recorder`](crate::world::PhysicsWorld::set_manifold_recorder).
+/// Each step records the manifolds refreshed that frame, after the solver has written its
+/// impulses back and subject to the world's manifold debug filter. Once `capacity` frames
+/// are stored, the oldest is dropped.
+#[derive(Debug, Clone)]
+pub struct ManifoldRecorder {
+    capacity: usize,
+    frames: VecDeque<RecordedFrame>,
+    paused: bool,
+}
+
+impl ManifoldRecorder {
+    /// Keeps the last `capacity` frames (at least one).
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            frames: VecDeque::with_capacity(capacity),
+            paused: false,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Stops recording without dropping captured frames.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn record(&mut self, frame: u32, manifolds: Vec<ManifoldDebugInfo>) {
+        if self.paused {
+            return;
+        }
+        if self.frames.len() == self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(RecordedFrame { frame, manifolds });
+    }
+
+    /// Recorded frames, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &RecordedFrame> {
+        self.frames.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Serializes the recorded frames as JSON. Body ids are written as
+    /// [`EntityId::to_bits`](crate::utils::allocator::EntityId::to_bits) values and
+    /// non-finite numbers as `null`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::from("{\"frames\":[");
+        for (i, frame) in self.frames.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let _ = write!(out, "{{\"frame\":{},\"manifolds\":[", frame.frame);
+            for (j, manifold) in frame.manifolds.iter().enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write_manifold(&mut out, manifold);
+            }
+            out.push_str("]}");
+        }
+        out.push_str("]}");
+        out
+    }
+
+    /// Writes [`ManifoldRecorder::to_json`] to `writer`.
+    pub fn write_json<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_json().as_bytes())
+    }
+}
+
+impl Default for ManifoldRecorder {
+    fn default() -> Self {
+        Self::new(120)
+    }
+}
+
+fn write_manifold(out: &mut String, manifold: &ManifoldDebugInfo) {
+    let material = &manifold.material;
+    let _ = write!(
+        out,
+        "{{\"body_a\":{},\"body_b\":{},\"normal\":",
+        manifold.body_a.to_bits(),
+        manifold.body_b.to_bits()
+    );
+    write_vec3(out, manifold.normal);
+    out.push_str(",\"material\":{\"static_friction\":");
+    write_vec3(out, material.static_friction);
+    out.push_str(",\"dynamic_friction\":");
+    write_vec3(out, material.dynamic_friction);
+    out.push_str(",\"restitution\":");
+    write_f32(out, material.restitution);
+    out.push_str(",\"rolling_friction\":");
+    write_f32(out, material.rolling_friction);
+    out.push_str(",\"torsional_friction\":");
+    write_f32(out, material.torsional_friction);
+    out.push_str("},\"points\":[");
+    for (i, point) in manifold.points.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        let _ = write!(
+            out,
+            "{{\"feature_id\":{},\"world_point\":",
+            point.feature_id
+        );
+        write_vec3(out, point.world_point);
+        out.push_str(",\"local_a\":");
+        write_vec3(out, point.local_a);
+        out.push_str(",\"local_b\":");
+        write_vec3(out, point.local_b);
+        out.push_str(",\"depth\":");
+        write_f32(out, point.depth);
+        out.push_str(",\"normal_impulse\":");
+        write_f32(out, point.normal_impulse);
+        out.push_str(",\"tangent_impulse\":");
+        write_vec3(out, point.tangent_impulse);
+        out.push_str(",\"rolling_impulse\":");
+        write_vec3(out, point.rolling_impulse);
+        out.push_str(",\"torsional_impulse\":");
+        write_f32(out, point.torsional_impulse);
+        out.push('}');
+    }
+    out.push_str("]}");
+}
+
+fn write_vec3(out: &mut String, value: Vec3) {
+    out.push('[');
+    write_f32(out, value.x);
+    out.push(',');
+    write_f32(out, value.y);
+    out.push(',');
+    write_f32(out, value.z);
+    out.push(']');
+}
+
+fn write_f32(out: &mut String, value: f32) {
+    if value.is_finite() {
+        let _ = write!(out, "{value}");
+    } else {
+        out.push_str("null");
+    }
+}
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use glam::Vec3;
 
 use crate::core::collider::ColliderShape;
@@ -20,12 +22,20 @@ impl ShapeUtil {
                 point.y += half_height * direction.y.signum();
                 point
             }
-            ColliderShape::Cylinder { radius, height } => {
-                Vec3::new(
-                    radius * direction.x.signum(),
-                    (height / 2.0) * direction.y.signum(),
-                    radius * direction.z.signum(),
-                )
+            ColliderShape::Cylinder { radius, height } => cylinder_support(*radius, *height, direction),
+            ColliderShape::Cone { radius, height } => {
+                let half_height = height / 2.0;
+                let lateral = Vec3::new(direction.x, 0.0, direction.z);
+                let sin_apex = radius / (radius * radius + height * height).sqrt();
+                if direction.y > direction.length() * sin_apex {
+                    Vec3::new(0.0, half_height, 0.0)
+                } else {
+                    lateral.normalize_or_zero() * *radius - Vec3::Y * half_height
+                }
+            }
+            ColliderShape::RoundedBox { .. } | ColliderShape::RoundedCylinder { .. } => {
+                let (core, border_radius) = Self::rounded_core(shape).unwrap_or((shape.clone(), 0.0));
+                Self::support(&core, direction) + direction.normalize_or_zero() * border_radius
             }
             ColliderShape::ConvexHull { vertices } => vertices
                 .iter()
@@ -38,6 +48,8 @@ impl ShapeUtil {
                 .max_by(|a, b| a.dot(direction).partial_cmp(&b.dot(direction)).unwrap())
                 .unwrap_or(Vec3::ZERO),
             ColliderShape::Mesh { mesh } => mesh.support_point(direction),
+            ColliderShape::Heightfield { heightfield } => heightfield.support_point(direction),
+            ColliderShape::Plane { .. } => Vec3::ZERO,
         }
     }
 
@@ -46,13 +58,123 @@ impl ShapeUtil {
             ColliderShape::Sphere { radius } => *radius,
             ColliderShape::Box { half_extents } => half_extents.length(),
             ColliderShape::Capsule { radius, height } => (*radius * *radius + (height / 2.0).powi(2)).sqrt(),
-            ColliderShape::Cylinder { radius, height } => (*radius * *radius + (height / 2.0).powi(2)).sqrt(),
+            ColliderShape::Cylinder { radius, height }
+            | ColliderShape::Cone { radius, height }
+            | ColliderShape::RoundedCylinder { radius, height, .. } => (*radius * *radius + (height / 2.0).powi(2)).sqrt(),
+            ColliderShape::RoundedBox { half_extents, .. } => half_extents.length(),
             ColliderShape::ConvexHull { vertices } => vertices.iter().map(|v| v.length()).fold(0.0, f32::max),
             ColliderShape::Compound { shapes } => shapes
                 .iter()
                 .map(|(transform, shape)| transform.position.length() + Self::bounding_radius(shape))
                 .fold(0.0, f32::max),
             ColliderShape::Mesh { mesh } => mesh.bounding_radius(),
+            ColliderShape::Heightfield { heightfield } => heightfield.bounding_radius(),
+            ColliderShape::Plane { .. } => f32::INFINITY,
+        }
+    }
+    /// Splits a rounded shape into its sharp core and border radius; `None` for other shapes.
+    pub fn rounded_core(shape: &ColliderShape) -> Option<(ColliderShape, f32)> {
+        match shape {
+            ColliderShape::RoundedBox { half_extents, border_radius } => {
+                let r = border_radius.clamp(0.0, half_extents.min_element().max(0.0));
+                Some((ColliderShape::Box { half_extents: *half_extents - Vec3::splat(r) }, r))
+            }
+            ColliderShape::RoundedCylinder { radius, height, border_radius } => {
+                let r = border_radius.clamp(0.0, radius.min(height / 2.0).max(0.0));
+                Some((ColliderShape::Cylinder { radius: radius - r, height: height - 2.0 * r }, r))
+            }
+            _ => None,
         }
     }
+
+    /// Core for a world-space collision `margin` on a collider with the given `scale`.
+    ///
+    /// Boxes and cylinders shrink by the margin, so core plus margin keeps their outer faces
+    /// and only rounds edges. Hulls, cones, meshes, and compounds keep their geometry and grow
+    /// by the margin. Spheres, capsules, rounded shapes, planes, and heightfields ignore it.
+    /// Returns the core and the margin actually applied.
+    pub fn margin_core(shape: &ColliderShape, margin: f32, scale: Vec3) -> (Cow<'_, ColliderShape>, f32) {
+        let margin = margin.max(0.0);
+        if margin == 0.0 {
+            return (Cow::Borrowed(shape), 0.0);
+        }
+        let scale = scale.abs().max(Vec3::splat(f32::EPSILON));
+        match shape {
+            ColliderShape::Box { half_extents } => {
+                let m = margin.min((*half_extents * scale).min_element().max(0.0));
+                let core = ColliderShape::Box { half_extents: *half_extents - Vec3::splat(m) / scale };
+                (Cow::Owned(core), m)
+            }
+            ColliderShape::Cylinder { radius, height } => {
+                let radial = scale.x.max(scale.z);
+                let m = margin.min((radius * radial).min(0.5 * height * scale.y).max(0.0));
+                let core = ColliderShape::Cylinder {
+                    radius: radius - m / radial,
+                    height: height - 2.0 * m / scale.y,
+                };
+                (Cow::Owned(core), m)
+            }
+            ColliderShape::ConvexHull { .. }
+            | ColliderShape::Cone { .. }
+            | ColliderShape::Mesh { .. }
+            | ColliderShape::Compound { .. } => (Cow::Borrowed(shape), margin),
+            _ => (Cow::Borrowed(shape), 0.0),
+        }
+    }
+
+    /// Exact signed distance from a local-space point to a primitive's surface, negative
+    /// inside. `None` for hulls, meshes, heightfields, planes, and compounds.
+    pub fn signed_distance(shape: &ColliderShape, point: Vec3) -> Option<f32> {
+        let distance = match shape {
+            ColliderShape::Sphere { radius } => point.length() - radius,
+            ColliderShape::Box { half_extents } => box_distance(point, *half_extents),
+            ColliderShape::Capsule { radius, height } => {
+                let half_height = height / 2.0;
+                (point - Vec3::Y * point.y.clamp(-half_height, half_height)).length() - radius
+            }
+            ColliderShape::Cylinder { radius, height } => cylinder_distance(point, *radius, *height),
+            ColliderShape::Cone { radius, height } => cone_distance(point, *radius, *height),
+            ColliderShape::RoundedBox { .. } | ColliderShape::RoundedCylinder { .. } => {
+                let (core, border_radius) = Self::rounded_core(shape)?;
+                Self::signed_distance(&core, point)? - border_radius
+            }
+            _ => return None,
+        };
+        Some(distance)
+    }
+}
+
+fn cylinder_support(radius: f32, height: f32, direction: Vec3) -> Vec3 {
+    let lateral = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero() * radius;
+    let axial = if direction.y >= 0.0 { height / 2.0 } else { -height / 2.0 };
+    lateral + Vec3::Y * axial
+}
+
+fn box_distance(point: Vec3, half_extents: Vec3) -> f32 {
+    let q = point.abs() - half_extents;
+    q.max(Vec3::ZERO).length() + q.max_element().min(0.0)
+}
+
+fn cylinder_distance(point: Vec3, radius: f32, height: f32) -> f32 {
+    let radial = Vec3::new(point.x, 0.0, point.z).length() - radius;
+    let axial = point.y.abs() - height / 2.0;
+    Vec3::new(radial.max(0.0), axial.max(0.0), 0.0).length() + radial.max(axial).min(0.0)
+}
+
+/// Distance in the (radial, y) half-plane to the cone's profile triangle.
+fn cone_distance(point: Vec3, radius: f32, height: f32) -> f32 {
+    let half_height = height / 2.0;
+    let p = (Vec3::new(point.x, 0.0, point.z).length(), point.y);
+    let base = (p.0.min(radius), -half_height);
+    let edge = (radius, -half_height);
+    let slant = (-radius, height);
+    let t = (((p.0 - edge.0) * slant.0 + (p.1 - edge.1) * slant.1)
+        / (slant.0 * slant.0 + slant.1 * slant.1).max(1e-12))
+    .clamp(0.0, 1.0);
+    let on_slant = (edge.0 + slant.0 * t, edge.1 + slant.1 * t);
+    let to_base = ((p.0 - base.0).powi(2) + (p.1 - base.1).powi(2)).sqrt();
+    let to_slant = ((p.0 - on_slant.0).powi(2) + (p.1 - on_slant.1).powi(2)).sqrt();
+    let inside = p.1 >= -half_height && p.0 * height <= radius * (half_height - p.1);
+    let distance = to_base.min(to_slant);
+    if inside { -distance } else { distance }
 }
@@ -0,0 +1,117 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{core::collider::Collider, utils::allocator::EntityId};
+
+/// A trigger collider overlapping another collider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TriggerOverlap {
+    pub trigger: EntityId,
+    pub other: EntityId,
+    pub trigger_body: EntityId,
+    pub other_body: EntityId,
+}
+
+impl TriggerOverlap {
+    fn key(&self) -> (EntityId, EntityId) {
+        (self.trigger, self.other)
+    }
+}
+
+/// Change in a trigger's overlap set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerEvent {
+    Enter(TriggerOverlap),
+    Exit(TriggerOverlap),
+}
+
+impl TriggerEvent {
+    pub fn overlap(&self) -> &TriggerOverlap {
+        match self {
+            TriggerEvent::Enter(overlap) | TriggerEvent::Exit(overlap) => overlap,
+        }
+    }
+}
+
+/// Tracks trigger overlaps between frames and reports enter/exit transitions.
+#[derive(Debug, Default)]
+pub struct TriggerTracker {
+    overlaps: Vec<TriggerOverlap>,
+    current: Vec<TriggerOverlap>,
+}
+
+impl TriggerTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.current.clear();
+    }
+
+    /// Records an overlap found this frame. When both colliders are triggers, `trigger` is
+    /// reported as the trigger.
+    pub fn record(&mut self, trigger: &Collider, other: &Collider) {
+        self.current.push(TriggerOverlap {
+            trigger: trigger.id,
+            other: other.id,
+            trigger_body: trigger.rigidbody_id,
+            other_body: other.rigidbody_id,
+        });
+    }
+
+    /// Diffs this frame's overlaps against the previous frame's and appends `Exit` then
+    /// `Enter` events, each ordered by collider ids.
+    pub fn end_frame(&mut self, events: &mut Vec<TriggerEvent>) {
+        self.current.sort_by_key(TriggerOverlap::key);
+        self.current.dedup_by_key(|overlap| overlap.key());
+
+        for previous in &self.overlaps {
+            if self
+                .current
+                .binary_search_by_key(&previous.key(), TriggerOverlap::key)
+                .is_err()
+            {
+                events.push(TriggerEvent::Exit(*previous));
+            }
+        }
+        for overlap in &self.current {
+            if self
+                .overlaps
+                .binary_search_by_key(&overlap.key(), TriggerOverlap::key)
+                .is_err()
+            {
+                events.push(TriggerEvent::Enter(*overlap));
+            }
+        }
+
+        std::mem::swap(&mut self.overlaps, &mut self.current);
+    }
+
+    /// Forgets every overlap involving `collider` and returns them, ordered by collider ids.
+    pub fn remove_collider(&mut self, collider: EntityId) -> Vec<TriggerOverlap> {
+        let involves =
+            |overlap: &TriggerOverlap| overlap.trigger == collider || overlap.other == collider;
+        self.current.retain(|overlap| !involves(overlap));
+        let removed = self.overlaps.iter().copied().filter(involves).collect();
+        self.overlaps.retain(|overlap| !involves(overlap));
+        removed
+    }
+
+    pub(crate) fn restore(&mut self, overlaps: &[TriggerOverlap]) {
+        self.overlaps = overlaps.to_vec();
+        self.current.clear();
+    }
+
+    /// Overlaps found in the most recent frame, ordered by collider ids.
+    pub fn overlaps(&self) -> &[TriggerOverlap] {
+        &self.overlaps
+    }
+
+    pub fn is_overlapping(&self, trigger: EntityId, other: EntityId) -> bool {
+        self.overlaps
+            .binary_search_by_key(&(trigger, other), TriggerOverlap::key)
+            .is_ok()
+    }
+}
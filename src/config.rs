@@ -1,4 +1,17 @@
-//! Global configuration constants for the Particle Accelerator engine.
+//! Global configuration constants for the Particle Accelerator engine, and the
+//! [`WorldConfig`] a world can load its tuning from.
+
+use std::fmt;
+use std::path::Path;
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::broadphase::BroadPhaseBackend,
+    dynamics::{BlockSolverMode, PositionCorrection},
+};
 
 /// Default gravity vector applied in the physics world (Y-up).
 pub const DEFAULT_GRAVITY: [f32; 3] = [0.0, -9.81, 0.0];
@@ -9,11 +22,290 @@ pub const DEFAULT_TIME_STEP: f32 = 1.0 / 60.0;
 /// Number of constraint solver iterations performed per step.
 pub const DEFAULT_SOLVER_ITERATIONS: u32 = 4;
 
+/// Number of position correction passes run after the velocity iterations.
+pub const DEFAULT_POSITION_ITERATIONS: u32 = 1;
+
+/// Fraction of contact penetration the solver corrects per step.
+pub const DEFAULT_SOLVER_BIAS: f32 = 0.2;
+
+/// Penetration depth the solver leaves alone, so resting contacts don't jitter.
+pub const DEFAULT_SOLVER_SLOP: f32 = 0.01;
+
 /// Default damping applied to linear velocity.
 pub const DEFAULT_LINEAR_DAMPING: f32 = 0.02;
 
 /// Default damping applied to angular velocity.
 pub const DEFAULT_ANGULAR_DAMPING: f32 = 0.02;
 
+/// Default cap on a body's linear speed (m/s); keeps an exploding stack from flinging
+/// bodies out of the world.
+pub const DEFAULT_MAX_LINEAR_VELOCITY: f32 = 1000.0;
+
+/// Default cap on a body's angular speed (rad/s).
+pub const DEFAULT_MAX_ANGULAR_VELOCITY: f32 = 100.0;
+
 /// Default cell size for the broad-phase uniform grid.
 pub const DEFAULT_BROADPHASE_CELL_SIZE: f32 = 5.0;
+
+/// Default padding on sweep-and-prune proxies.
+pub const DEFAULT_SAP_MARGIN: f32 = 0.05;
+
+/// Default padding on the fat bounds of the incremental broad-phase.
+pub const DEFAULT_FAT_MARGIN: f32 = 0.1;
+
+/// Default distance outside every active simulation region beyond which bodies freeze.
+pub const DEFAULT_REGION_MARGIN: f32 = 10.0;
+
+/// Solver settings that can change between steps; see
+/// [`PhysicsWorld::set_solver_config`](crate::world::PhysicsWorld::set_solver_config).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct SolverConfig {
+    pub velocity_iterations: u32,
+    pub position_iterations: u32,
+    /// Fraction of contact penetration corrected per step.
+    pub bias: f32,
+    /// Penetration depth left uncorrected.
+    pub slop: f32,
+    /// Whether contacts and joints start each step from the impulses they ended the last
+    /// one with.
+    pub warm_starting: bool,
+    /// Whether bodies that opt into CCD get swept against the world.
+    pub ccd_enabled: bool,
+    /// How penetration is resolved; `bias` and `slop` apply to either correction.
+    pub position_correction: PositionCorrection,
+    /// Which contact manifolds have their normal impulses solved as one block.
+    pub block_solver: BlockSolverMode,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            velocity_iterations: DEFAULT_SOLVER_ITERATIONS,
+            position_iterations: DEFAULT_POSITION_ITERATIONS,
+            bias: DEFAULT_SOLVER_BIAS,
+            slop: DEFAULT_SOLVER_SLOP,
+            warm_starting: true,
+            ccd_enabled: true,
+            position_correction: PositionCorrection::default(),
+            block_solver: BlockSolverMode::default(),
+        }
+    }
+}
+
+/// Broad-phase settings of a [`WorldConfig`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct BroadPhaseConfig {
+    pub backend: BroadPhaseBackend,
+    /// Cell size of the grid backend.
+    pub cell_size: f32,
+    /// Padding on sweep-and-prune proxies.
+    pub sap_margin: f32,
+    /// Padding on the fat bounds of the incremental backend.
+    pub fat_margin: f32,
+}
+
+impl Default for BroadPhaseConfig {
+    fn default() -> Self {
+        Self {
+            backend: BroadPhaseBackend::default(),
+            cell_size: DEFAULT_BROADPHASE_CELL_SIZE,
+            sap_margin: DEFAULT_SAP_MARGIN,
+            fat_margin: DEFAULT_FAT_MARGIN,
+        }
+    }
+}
+
+/// World, solver and broad-phase tuning, loadable from a TOML or RON file; see
+/// [`PhysicsWorld::from_config`](crate::world::PhysicsWorld::from_config).
+///
+/// Fields missing from a file keep their defaults, so a file only lists what it changes.
+/// Unknown fields are rejected rather than ignored, so a typo doesn't go unnoticed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct WorldConfig {
+    /// Fixed step length in seconds.
+    pub time_step: f32,
+    pub gravity: Vec3,
+    pub parallel: bool,
+    pub solver: SolverConfig,
+    pub broadphase: BroadPhaseConfig,
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            time_step: DEFAULT_TIME_STEP,
+            gravity: Vec3::from_slice(&DEFAULT_GRAVITY),
+            parallel: false,
+            solver: SolverConfig::default(),
+            broadphase: BroadPhaseConfig::default(),
+        }
+    }
+}
+
+type ConfigParser = fn(&str) -> Result<WorldConfig, ConfigError>;
+
+impl WorldConfig {
+    /// Reads and validates the config at `path`, picking the format from its extension:
+    /// `.toml` needs the `toml` feature and `.ron` the `ron` feature.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let format = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let parse = Self::parser(&format).ok_or(ConfigError::UnsupportedFormat(format))?;
+        let config = parse(&std::fs::read_to_string(path)?)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn parser(format: &str) -> Option<ConfigParser> {
+        match format {
+            #[cfg(feature = "toml")]
+            "toml" => Some(Self::parse_toml),
+            #[cfg(feature = "ron")]
+            "ron" => Some(Self::parse_ron),
+            _ => None,
+        }
+    }
+
+    /// Parses and validates a TOML config.
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(text: &str) -> Result<Self, ConfigError> {
+        let config = Self::parse_toml(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses and validates a RON config.
+    #[cfg(feature = "ron")]
+    pub fn from_ron_str(text: &str) -> Result<Self, ConfigError> {
+        let config = Self::parse_ron(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    #[cfg(feature = "toml")]
+    fn parse_toml(text: &str) -> Result<Self, ConfigError> {
+        toml::from_str(text).map_err(|err| ConfigError::Parse(err.to_string()))
+    }
+
+    #[cfg(feature = "ron")]
+    fn parse_ron(text: &str) -> Result<Self, ConfigError> {
+        ron::from_str(text).map_err(|err| ConfigError::Parse(err.to_string()))
+    }
+
+    /// Checks every value the world can't run with, reporting all of them at once.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+        if !(self.time_step.is_finite() && self.time_step > 0.0) {
+            problems.push(format!(
+                "time_step must be positive, got {}",
+                self.time_step
+            ));
+        }
+        if !self.gravity.is_finite() {
+            problems.push(format!("gravity must be finite, got {}", self.gravity));
+        }
+        let solver = &self.solver;
+        if solver.velocity_iterations == 0 {
+            problems.push("solver.velocity_iterations must be at least 1".to_string());
+        }
+        if !(0.0..=1.0).contains(&solver.bias) {
+            problems.push(format!(
+                "solver.bias must be in [0, 1], got {}",
+                solver.bias
+            ));
+        }
+        if !(solver.slop.is_finite() && solver.slop >= 0.0) {
+            problems.push(format!(
+                "solver.slop must not be negative, got {}",
+                solver.slop
+            ));
+        }
+        let broadphase = &self.broadphase;
+        if !(broadphase.cell_size.is_finite() && broadphase.cell_size > 0.0) {
+            problems.push(format!(
+                "broadphase.cell_size must be positive, got {}",
+                broadphase.cell_size
+            ));
+        }
+        for (name, margin) in [
+            ("sap_margin", broadphase.sap_margin),
+            ("fat_margin", broadphase.fat_margin),
+        ] {
+            if !(margin.is_finite() && margin >= 0.0) {
+                problems.push(format!(
+                    "broadphase.{name} must not be negative, got {margin}"
+                ));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(problems))
+        }
+    }
+}
+
+/// Why a [`WorldConfig`] could not be loaded or applied.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is malformed or doesn't match the layout of [`WorldConfig`].
+    Parse(String),
+    /// The file extension names a format this build can't read.
+    UnsupportedFormat(String),
+    /// The config parsed but holds values the world can't run with, one message each.
+    Invalid(Vec<String>),
+    /// The world was not loaded from a file, so there is nothing to reload.
+    NoSource,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read config: {err}"),
+            Self::Parse(message) => write!(f, "failed to parse config: {message}"),
+            Self::UnsupportedFormat(format) => write!(
+                f,
+                "unsupported config format `{format}` (expected `toml` or `ron` with the \
+                 matching feature enabled)"
+            ),
+            Self::Invalid(problems) => write!(f, "invalid config: {}", problems.join("; ")),
+            Self::NoSource => write!(f, "world was not loaded from a config file"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
@@ -1,9 +1,12 @@
-use super::types::Transform;
+use super::{constraints::MotorMode, types::Transform};
 use crate::utils::allocator::EntityId;
 use glam::{Mat3, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// Type of joint connecting a link to its parent in reduced coordinates.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum JointType {
     /// 1-DOF Rotational joint.
     Revolute { axis: Vec3 },
@@ -65,8 +68,20 @@ impl JointType {
     }
 }
 
+/// Motor on a 1-DOF link joint, driven like the Revolute and Prismatic joint motors.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinkMotor {
+    /// Target speed in [`MotorMode::Velocity`], feed-forward speed in [`MotorMode::Position`].
+    pub speed: f32,
+    /// Largest torque (revolute) or force (prismatic) the motor applies.
+    pub max_effort: f32,
+    pub mode: MotorMode,
+}
+
 /// A single node in the articulated body tree.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Link {
     pub name: String,
     /// Index of the parent link. None if this is the root link.
@@ -87,7 +102,14 @@ pub struct Link {
     pub inertia: Mat3,
 
     /// Optional mapping to a maximal coordinate body for collision detection.
+    ///
+    /// The world moves the body with the link as a kinematic proxy, and contact impulses on
+    /// it push back on the multibody's revolute and prismatic joints.
     pub collision_body: Option<EntityId>,
+
+    /// Lower and upper joint coordinate for 1-DOF joints.
+    pub limit: Option<(f32, f32)>,
+    pub motor: Option<LinkMotor>,
 }
 
 impl Link {
@@ -102,12 +124,15 @@ impl Link {
             com_offset: Vec3::ZERO,
             inertia: Mat3::IDENTITY,
             collision_body: None,
+            limit: None,
+            motor: None,
         }
     }
 }
 
 /// A collection of links forming a tree structure for reduced-coordinate dynamics.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Multibody {
     pub id: EntityId,
     /// Links ordered such that a parent always appears before its children.
@@ -124,6 +149,10 @@ pub struct Multibody {
     pub ddq: Vec<f32>,
     /// Generalized forces (torques applied at joints).
     pub tau: Vec<f32>,
+    /// Articulated inertia of each 1-DOF joint from the last ABA pass.
+    pub joint_inertia: Vec<f32>,
+    /// Generalized impulses from contacts on collision links, applied by the next step.
+    pub contact_impulse: Vec<f32>,
 
     // Computed states
     pub world_transforms: Vec<Transform>,
@@ -140,6 +169,8 @@ impl Multibody {
             dq: Vec::new(),
             ddq: Vec::new(),
             tau: Vec::new(),
+            joint_inertia: Vec::new(),
+            contact_impulse: Vec::new(),
             world_transforms: Vec::new(),
             spatial_velocities: Vec::new(),
         }
@@ -157,6 +188,8 @@ impl Multibody {
         self.dq.resize(self.total_dofs, 0.0);
         self.ddq.resize(self.total_dofs, 0.0);
         self.tau.resize(self.total_dofs, 0.0);
+        self.joint_inertia.resize(self.total_dofs, 0.0);
+        self.contact_impulse.resize(self.total_dofs, 0.0);
 
         self.world_transforms.push(Transform::default());
         self.spatial_velocities
@@ -166,6 +199,94 @@ impl Multibody {
         idx
     }
 
+    /// Whether any link has a motor.
+    pub fn has_motors(&self) -> bool {
+        self.links.iter().any(|link| link.motor.is_some())
+    }
+
+    /// Generalized forces the link motors apply this step.
+    ///
+    /// Uses `ddq` and `joint_inertia` from an ABA pass without the motors, so a velocity
+    /// motor cancels the passive acceleration on its joint and servos are solved implicitly.
+    pub fn motor_forces(&self, dt: f32) -> Vec<f32> {
+        let mut forces = vec![0.0; self.total_dofs];
+        for link in &self.links {
+            let Some(motor) = link.motor else {
+                continue;
+            };
+            let i = link.q_offset;
+            let inertia = self.joint_inertia.get(i).copied().unwrap_or(0.0);
+            if link.joint_type.dofs() != 1 || inertia <= f32::EPSILON {
+                continue;
+            }
+            let passive_speed = self.dq[i] + self.ddq[i] * dt;
+            let impulse = match motor.mode {
+                MotorMode::Velocity => (motor.speed - passive_speed) * inertia,
+                MotorMode::Position {
+                    target,
+                    stiffness,
+                    damping,
+                } => crate::dynamics::solver::servo_impulse(
+                    target - self.q[i],
+                    motor.speed - passive_speed,
+                    passive_speed,
+                    stiffness,
+                    damping,
+                    1.0 / inertia,
+                    dt,
+                ),
+            };
+            let max_impulse = motor.max_effort.max(0.0) * dt;
+            forces[i] = impulse.clamp(-max_impulse, max_impulse) / dt;
+        }
+        forces
+    }
+
+    /// Clamps 1-DOF joints to their limits, removing velocity that points further out.
+    pub fn enforce_limits(&mut self) {
+        for link in &self.links {
+            let Some((lower, upper)) = link.limit else {
+                continue;
+            };
+            if link.joint_type.dofs() != 1 {
+                continue;
+            }
+            let i = link.q_offset;
+            if self.q[i] < lower {
+                self.q[i] = lower;
+                self.dq[i] = self.dq[i].max(0.0);
+            } else if self.q[i] > upper {
+                self.q[i] = upper;
+                self.dq[i] = self.dq[i].min(0.0);
+            }
+        }
+    }
+
+    /// Adds the generalized impulse of a world-space impulse at `point` on link `link`.
+    ///
+    /// Every revolute and prismatic joint between the link and the root takes its share;
+    /// spherical joints are skipped. Uses the world transforms of the last kinematics update.
+    pub fn apply_link_impulse(&mut self, link: usize, point: Vec3, impulse: Vec3) {
+        let mut current = Some(link);
+        while let Some(i) = current {
+            let Some(node) = self.links.get(i) else {
+                return;
+            };
+            let frame = self.world_transforms[i];
+            let share = match node.joint_type {
+                JointType::Revolute { axis } => {
+                    (frame.rotation * axis).dot((point - frame.position).cross(impulse))
+                }
+                JointType::Prismatic { axis } => (frame.rotation * axis).dot(impulse),
+                JointType::Spherical | JointType::Fixed => 0.0,
+            };
+            if node.joint_type.dofs() == 1 {
+                self.contact_impulse[node.q_offset] += share;
+            }
+            current = node.parent_idx;
+        }
+    }
+
     /// Updates world transforms and spatial velocities based on current q and dq.
     pub fn update_kinematics(&mut self) {
         use crate::utils::spatial::SpatialVec;
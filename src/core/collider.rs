@@ -1,10 +1,20 @@
-use super::{mesh::TriangleMesh, types::Transform};
-use crate::utils::allocator::EntityId;
-use glam::{Quat, Vec3};
+use super::{
+    heightfield::Heightfield,
+    mesh::TriangleMesh,
+    types::{MassProperties, Transform},
+};
+use crate::utils::{
+    allocator::EntityId,
+    math::{inertia_capsule, rotate_inertia, translate_inertia},
+};
+use glam::{Mat3, Quat, Vec3};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use std::{f32::consts::PI, sync::Arc};
 
 /// Enumeration of supported collider geometries.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ColliderShape {
     Sphere {
         radius: f32,
@@ -20,19 +30,45 @@ pub enum ColliderShape {
         radius: f32,
         height: f32,
     },
+    /// Y-aligned cone with its apex at `+height / 2` and base at `-height / 2`.
+    Cone {
+        radius: f32,
+        height: f32,
+    },
+    /// Box of outer size `half_extents` with edges and corners rounded by `border_radius`.
+    RoundedBox {
+        half_extents: Vec3,
+        border_radius: f32,
+    },
+    /// Y-aligned cylinder of outer size `radius`/`height` with rims rounded by `border_radius`.
+    RoundedCylinder {
+        radius: f32,
+        height: f32,
+        border_radius: f32,
+    },
     ConvexHull {
         vertices: Vec<Vec3>,
     },
     Compound {
         shapes: Vec<(Transform, ColliderShape)>,
     },
+    /// Shared, so cloning the collider or a world snapshot does not copy the triangles.
     Mesh {
-        mesh: TriangleMesh,
+        mesh: Arc<TriangleMesh>,
+    },
+    /// Shared, so cloning the collider or a world snapshot does not copy the samples.
+    Heightfield {
+        heightfield: Arc<Heightfield>,
+    },
+    /// Infinite plane through the collider origin. The half-space behind `normal` is solid.
+    Plane {
+        normal: Vec3,
     },
 }
 
 /// Simple collision filtering mask.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CollisionFilter {
     pub layer: u32,
     pub mask: u32,
@@ -47,6 +83,94 @@ impl Default for CollisionFilter {
     }
 }
 
+impl CollisionFilter {
+    /// Two filters interact only when each one's mask contains the other's layer.
+    pub fn interacts_with(&self, other: &CollisionFilter) -> bool {
+        (self.mask & other.layer) != 0 && (other.mask & self.layer) != 0
+    }
+}
+
+/// Set of collision layer bits, combined with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CollisionGroups(pub u32);
+
+impl CollisionGroups {
+    pub const NONE: Self = Self(0);
+    pub const ALL: Self = Self(u32::MAX);
+
+    /// The group for layer `index` (0..32).
+    pub const fn layer(index: u32) -> Self {
+        Self(1 << index)
+    }
+
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for CollisionGroups {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for CollisionGroups {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Typed builder for [`CollisionFilter`]:
+/// `CollisionLayers::group(PLAYER).collides_with(ENEMY | TERRAIN)`.
+///
+/// A new value collides with every group until narrowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionLayers {
+    group: CollisionGroups,
+    mask: CollisionGroups,
+}
+
+impl CollisionLayers {
+    pub const fn group(group: CollisionGroups) -> Self {
+        Self {
+            group,
+            mask: CollisionGroups::ALL,
+        }
+    }
+
+    /// Restricts collisions to `groups`.
+    pub const fn collides_with(self, groups: CollisionGroups) -> Self {
+        Self {
+            mask: groups,
+            ..self
+        }
+    }
+
+    /// Removes `groups` from the collision mask.
+    pub const fn ignores(self, groups: CollisionGroups) -> Self {
+        Self {
+            mask: CollisionGroups(self.mask.0 & !groups.0),
+            ..self
+        }
+    }
+
+    pub const fn filter(self) -> CollisionFilter {
+        CollisionFilter {
+            layer: self.group.0,
+            mask: self.mask.0,
+        }
+    }
+}
+
+impl From<CollisionLayers> for CollisionFilter {
+    fn from(layers: CollisionLayers) -> Self {
+        layers.filter()
+    }
+}
+
 impl Default for Collider {
     fn default() -> Self {
         Self::builder().build()
@@ -54,7 +178,8 @@ impl Default for Collider {
 }
 
 /// Collider component referencing a rigid body.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Collider {
     pub id: EntityId,
     pub rigidbody_id: EntityId,
@@ -62,6 +187,16 @@ pub struct Collider {
     pub offset: Transform,
     pub is_trigger: bool,
     pub collision_filter: CollisionFilter,
+    /// World-space collision margin used by GJK/EPA and CCD; see
+    /// [`ShapeUtil::margin_core`](crate::collision::shapes::ShapeUtil::margin_core).
+    /// Zero disables it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub margin: f32,
+    /// Density the collider contributes mass with; `None` leaves the body's mass alone.
+    ///
+    /// See [`PhysicsWorld::update_mass_properties`](crate::world::PhysicsWorld::update_mass_properties).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub density: Option<f32>,
 }
 
 impl Collider {
@@ -73,9 +208,42 @@ impl Collider {
         ColliderShape::Box { half_extents }
     }
 
+    pub fn cone(radius: f32, height: f32) -> ColliderShape {
+        ColliderShape::Cone { radius, height }
+    }
+
+    /// `border_radius` is clamped to the smallest half extent.
+    pub fn rounded_box(half_extents: Vec3, border_radius: f32) -> ColliderShape {
+        ColliderShape::RoundedBox {
+            half_extents,
+            border_radius: border_radius.clamp(0.0, half_extents.min_element().max(0.0)),
+        }
+    }
+
+    /// `border_radius` is clamped to the radius and half height.
+    pub fn rounded_cylinder(radius: f32, height: f32, border_radius: f32) -> ColliderShape {
+        ColliderShape::RoundedCylinder {
+            radius,
+            height,
+            border_radius: border_radius.clamp(0.0, radius.min(height * 0.5).max(0.0)),
+        }
+    }
+
     pub fn mesh(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> ColliderShape {
         ColliderShape::Mesh {
-            mesh: TriangleMesh::builder(vertices, indices).build(),
+            mesh: Arc::new(TriangleMesh::builder(vertices, indices).build()),
+        }
+    }
+
+    pub fn heightfield(rows: usize, cols: usize, heights: Vec<f32>, scale: Vec3) -> ColliderShape {
+        ColliderShape::Heightfield {
+            heightfield: Arc::new(Heightfield::new(rows, cols, heights, scale)),
+        }
+    }
+
+    pub fn plane(normal: Vec3) -> ColliderShape {
+        ColliderShape::Plane {
+            normal: normal.normalize_or(Vec3::Y),
         }
     }
 
@@ -87,6 +255,11 @@ impl Collider {
         self.shape.bounding_radius()
     }
 
+    /// Whether the shape has no finite bounds (planes).
+    pub fn is_unbounded(&self) -> bool {
+        matches!(self.shape, ColliderShape::Plane { .. })
+    }
+
     pub fn builder() -> ColliderBuilder {
         ColliderBuilder::new()
     }
@@ -97,6 +270,8 @@ pub struct ColliderBuilder {
     offset: Transform,
     is_trigger: bool,
     filter: CollisionFilter,
+    margin: f32,
+    density: Option<f32>,
 }
 
 impl Default for ColliderBuilder {
@@ -112,9 +287,16 @@ impl ColliderBuilder {
             offset: Transform::default(),
             is_trigger: false,
             filter: CollisionFilter::default(),
+            margin: 0.0,
+            density: None,
         }
     }
 
+    pub fn shape(mut self, shape: ColliderShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
     pub fn sphere(mut self, radius: f32) -> Self {
         self.shape = ColliderShape::Sphere { radius };
         self
@@ -130,6 +312,26 @@ impl ColliderBuilder {
         self
     }
 
+    pub fn plane(mut self, normal: Vec3) -> Self {
+        self.shape = Collider::plane(normal);
+        self
+    }
+
+    pub fn cone(mut self, radius: f32, height: f32) -> Self {
+        self.shape = Collider::cone(radius, height);
+        self
+    }
+
+    pub fn rounded_box(mut self, half_extents: Vec3, border_radius: f32) -> Self {
+        self.shape = Collider::rounded_box(half_extents, border_radius);
+        self
+    }
+
+    pub fn rounded_cylinder(mut self, radius: f32, height: f32, border_radius: f32) -> Self {
+        self.shape = Collider::rounded_cylinder(radius, height, border_radius);
+        self
+    }
+
     pub fn offset(mut self, offset: Transform) -> Self {
         self.offset = offset;
         self
@@ -140,6 +342,21 @@ impl ColliderBuilder {
         self
     }
 
+    pub fn margin(mut self, margin: f32) -> Self {
+        self.margin = margin.max(0.0);
+        self
+    }
+
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    pub fn layers(mut self, layers: CollisionLayers) -> Self {
+        self.filter = layers.filter();
+        self
+    }
+
     pub fn filter(mut self, layer: u32, mask: u32) -> Self {
         self.filter = CollisionFilter { layer, mask };
         self
@@ -153,6 +370,8 @@ impl ColliderBuilder {
             offset: self.offset,
             is_trigger: self.is_trigger,
             collision_filter: self.filter,
+            margin: self.margin,
+            density: self.density,
         }
     }
 }
@@ -163,9 +382,12 @@ impl ColliderShape {
             ColliderShape::Sphere { radius } => *radius,
             ColliderShape::Box { half_extents } => half_extents.length(),
             ColliderShape::Capsule { radius, height } => radius + height * 0.5,
-            ColliderShape::Cylinder { radius, height } => {
+            ColliderShape::Cylinder { radius, height }
+            | ColliderShape::Cone { radius, height }
+            | ColliderShape::RoundedCylinder { radius, height, .. } => {
                 (radius.powi(2) + (height * 0.5).powi(2)).sqrt()
             }
+            ColliderShape::RoundedBox { half_extents, .. } => half_extents.length(),
             ColliderShape::ConvexHull { vertices } => {
                 vertices.iter().map(|v| v.length()).fold(0.0, f32::max)
             }
@@ -174,8 +396,350 @@ impl ColliderShape {
                 .map(|(transform, shape)| transform.position.length() + shape.bounding_radius())
                 .fold(0.0, f32::max),
             ColliderShape::Mesh { mesh } => mesh.bounding_radius(),
+            ColliderShape::Heightfield { heightfield } => heightfield.bounding_radius(),
+            ColliderShape::Plane { .. } => f32::INFINITY,
         }
     }
+
+    /// Mass and inertia about the shape origin for a uniform `density`.
+    ///
+    /// Rounded shapes use their exact volume with the inertia of the unrounded outer shape.
+    /// Meshes fall back to [`TriangleMesh::approximate_mass_properties`]. Returns `None`
+    /// for heightfields, planes, flat hulls, and compounds of only such shapes.
+    pub fn mass_properties(&self, density: f32) -> Option<MassProperties> {
+        self.mass_distribution(density).map(|(props, _)| props)
+    }
+
+    /// Centre of mass in the shape's frame, assuming uniform density.
+    pub fn center_of_mass(&self) -> Option<Vec3> {
+        self.mass_distribution(1.0).map(|(_, center)| center)
+    }
+
+    /// Mass properties about the shape origin, and the centre of mass.
+    fn mass_distribution(&self, density: f32) -> Option<(MassProperties, Vec3)> {
+        let density = density.max(0.0001);
+        let mut center = Vec3::ZERO;
+        let (mass, inertia) = match self {
+            ColliderShape::Sphere { radius } => {
+                let mass = density * 4.0 / 3.0 * PI * radius.powi(3);
+                (
+                    mass,
+                    Mat3::from_diagonal(Vec3::splat(0.4 * mass * radius * radius)),
+                )
+            }
+            ColliderShape::Box { half_extents } => {
+                let mass = density * 8.0 * half_extents.x * half_extents.y * half_extents.z;
+                (mass, box_inertia(*half_extents, mass))
+            }
+            ColliderShape::Capsule { radius, height } => {
+                let volume = PI * radius * radius * (height + 4.0 / 3.0 * radius);
+                let mass = density * volume;
+                (mass, inertia_capsule(*radius, *height, mass))
+            }
+            ColliderShape::Cylinder { radius, height } => {
+                let mass = density * PI * radius * radius * height;
+                (mass, cylinder_inertia(*radius, *height, mass))
+            }
+            ColliderShape::Cone { radius, height } => {
+                let mass = density * PI * radius * radius * height / 3.0;
+                // About the mid-height origin; the centroid sits a quarter height lower.
+                let lateral = mass * (0.15 * radius * radius + 0.1 * height * height);
+                let axial = 0.3 * mass * radius * radius;
+                center = Vec3::new(0.0, -0.25 * height, 0.0);
+                (
+                    mass,
+                    Mat3::from_diagonal(Vec3::new(lateral, axial, lateral)),
+                )
+            }
+            ColliderShape::RoundedBox {
+                half_extents,
+                border_radius,
+            } => {
+                let r = border_radius.clamp(0.0, half_extents.min_element().max(0.0));
+                let core = (*half_extents - Vec3::splat(r)) * 2.0;
+                // Steiner formula for a box grown by a sphere of radius `r`.
+                let volume = core.x * core.y * core.z
+                    + 2.0 * (core.x * core.y + core.y * core.z + core.z * core.x) * r
+                    + PI * (core.x + core.y + core.z) * r * r
+                    + 4.0 / 3.0 * PI * r.powi(3);
+                let mass = density * volume;
+                (mass, box_inertia(*half_extents, mass))
+            }
+            ColliderShape::RoundedCylinder {
+                radius,
+                height,
+                border_radius,
+            } => {
+                let r = border_radius.clamp(0.0, radius.min(height * 0.5).max(0.0));
+                let core_radius = radius - r;
+                let core_height = height - 2.0 * r;
+                // Steiner formula for a cylinder grown by a sphere of radius `r`.
+                let volume = PI * core_radius * core_radius * core_height
+                    + 2.0 * PI * core_radius * (core_radius + core_height) * r
+                    + PI * (PI * core_radius + core_height) * r * r
+                    + 4.0 / 3.0 * PI * r.powi(3);
+                let mass = density * volume;
+                (mass, cylinder_inertia(*radius, *height, mass))
+            }
+            ColliderShape::Mesh { mesh } => {
+                // The approximation is about the bounds' centre.
+                let props = mesh.approximate_mass_properties(density);
+                center = mesh.bounds.center();
+                (
+                    props.mass,
+                    translate_inertia(props.inertia, props.mass, center),
+                )
+            }
+            ColliderShape::ConvexHull { vertices } => {
+                let triangles = hull_triangles(vertices)?;
+                let (volume, center_of_volume, covariance) =
+                    closed_surface_moments(vertices, &triangles);
+                center = center_of_volume;
+                let mass = density * volume;
+                (mass, inertia_from_covariance(covariance * density))
+            }
+            ColliderShape::Compound { shapes } => {
+                return combine_mass(
+                    shapes
+                        .iter()
+                        .map(|(transform, shape)| (shape, transform, density)),
+                )
+            }
+            ColliderShape::Heightfield { .. } | ColliderShape::Plane { .. } => return None,
+        };
+        Some((
+            MassProperties {
+                mass: mass.max(0.0001),
+                inertia,
+            },
+            center,
+        ))
+    }
+}
+
+impl Collider {
+    /// Mass properties of the colliders with a [`Collider::density`], combined about their
+    /// common centre of mass, and that centre in the body's frame.
+    ///
+    /// Collider offsets, including their scale, place each shape on the body. Triggers and
+    /// shapes without a volume are skipped; returns `None` if nothing has mass.
+    pub fn combined_mass_properties<'a>(
+        colliders: impl IntoIterator<Item = &'a Collider>,
+    ) -> Option<(MassProperties, Vec3)> {
+        let (props, center) = combine_mass(colliders.into_iter().filter_map(|collider| {
+            let density = collider.density.filter(|_| !collider.is_trigger)?;
+            Some((&collider.shape, &collider.offset, density))
+        }))?;
+        Some((
+            MassProperties {
+                mass: props.mass,
+                // Undo the parallel-axis shift from the origin to the centre of mass.
+                inertia: translate_inertia(props.inertia, -props.mass, center),
+            },
+            center,
+        ))
+    }
+}
+
+/// Sums shapes placed by transforms into mass properties about their common origin.
+fn combine_mass<'a>(
+    parts: impl Iterator<Item = (&'a ColliderShape, &'a Transform, f32)>,
+) -> Option<(MassProperties, Vec3)> {
+    let mut mass = 0.0;
+    let mut moment = Vec3::ZERO;
+    let mut inertia = Mat3::ZERO;
+    for (shape, transform, density) in parts {
+        let Some((props, center)) = shape.mass_distribution(density) else {
+            continue;
+        };
+        let (props, center) = place_mass(props, center, transform);
+        mass += props.mass;
+        moment += center * props.mass;
+        inertia += props.inertia;
+    }
+    (mass > 0.0).then(|| (MassProperties { mass, inertia }, moment / mass))
+}
+
+/// Moves mass properties about a shape's origin into the frame `transform` places it in.
+///
+/// Works on the second moment `∫ x xᵀ dm`, which scales, rotates and translates simply.
+fn place_mass(
+    props: MassProperties,
+    center: Vec3,
+    transform: &Transform,
+) -> (MassProperties, Vec3) {
+    let scale = transform.scale;
+    let volume_scale = (scale.x * scale.y * scale.z).abs();
+    let mass = props.mass * volume_scale;
+    let stretch = Mat3::from_diagonal(scale);
+    let covariance = rotate_inertia(
+        stretch * covariance_from_inertia(props.inertia) * stretch,
+        transform.rotation,
+    ) * volume_scale;
+    let center = transform.rotation * (scale * center);
+    let offset = transform.position;
+    let first = center * mass;
+    let covariance =
+        covariance + outer(offset, first) + outer(first, offset) + outer(offset, offset) * mass;
+    (
+        MassProperties {
+            mass,
+            inertia: inertia_from_covariance(covariance),
+        },
+        center + offset,
+    )
+}
+
+fn outer(a: Vec3, b: Vec3) -> Mat3 {
+    Mat3::from_cols(a * b.x, a * b.y, a * b.z)
+}
+
+fn covariance_from_inertia(inertia: Mat3) -> Mat3 {
+    let trace = inertia.x_axis.x + inertia.y_axis.y + inertia.z_axis.z;
+    Mat3::from_diagonal(Vec3::splat(0.5 * trace)) - inertia
+}
+
+fn inertia_from_covariance(covariance: Mat3) -> Mat3 {
+    let trace = covariance.x_axis.x + covariance.y_axis.y + covariance.z_axis.z;
+    Mat3::from_diagonal(Vec3::splat(trace)) - covariance
+}
+
+/// Volume, centroid and unit-density second moment of a closed, outward-wound surface.
+///
+/// Sums signed tetrahedra from the origin to each triangle.
+fn closed_surface_moments(vertices: &[Vec3], triangles: &[[usize; 3]]) -> (f32, Vec3, Mat3) {
+    // Second moment of the canonical tetrahedron with unit legs along the axes.
+    let canonical = Mat3::from_cols(
+        Vec3::new(2.0, 1.0, 1.0),
+        Vec3::new(1.0, 2.0, 1.0),
+        Vec3::new(1.0, 1.0, 2.0),
+    ) / 120.0;
+    let mut volume = 0.0;
+    let mut moment = Vec3::ZERO;
+    let mut covariance = Mat3::ZERO;
+    for &[a, b, c] in triangles {
+        let legs = Mat3::from_cols(vertices[a], vertices[b], vertices[c]);
+        let det = legs.determinant();
+        volume += det / 6.0;
+        moment += (vertices[a] + vertices[b] + vertices[c]) * det / 24.0;
+        covariance += legs * canonical * legs.transpose() * det;
+    }
+    let center = if volume.abs() > f32::EPSILON {
+        moment / volume
+    } else {
+        Vec3::ZERO
+    };
+    (volume, center, covariance)
+}
+
+/// Outward-wound triangles of the convex hull of `points`, by incremental construction.
+///
+/// Returns `None` when the points are flat or too few to enclose a volume.
+pub(crate) fn hull_triangles(points: &[Vec3]) -> Option<Vec<[usize; 3]>> {
+    let scale = points
+        .iter()
+        .map(|p| p.abs().max_element())
+        .fold(0.0, f32::max);
+    let epsilon = scale.max(f32::EPSILON) * 1e-5;
+
+    // Seed with the largest tetrahedron found greedily.
+    let first = 0;
+    let second = farthest(points, |p| p.distance_squared(points[first]))?;
+    let axis = (points[second] - points[first]).normalize_or_zero();
+    let third = farthest(points, |p| {
+        let offset = p - points[first];
+        (offset - axis * offset.dot(axis)).length_squared()
+    })?;
+    let normal = (points[second] - points[first])
+        .cross(points[third] - points[first])
+        .normalize_or_zero();
+    let fourth = farthest(points, |p| (p - points[first]).dot(normal).abs())?;
+    if (points[fourth] - points[first]).dot(normal).abs() <= epsilon {
+        return None;
+    }
+
+    let interior = (points[first] + points[second] + points[third] + points[fourth]) * 0.25;
+    let outward = |[a, b, c]: [usize; 3]| {
+        let normal = (points[b] - points[a]).cross(points[c] - points[a]);
+        if normal.dot(points[a] - interior) < 0.0 {
+            [a, c, b]
+        } else {
+            [a, b, c]
+        }
+    };
+    let mut faces: Vec<[usize; 3]> = [
+        [first, second, third],
+        [first, second, fourth],
+        [first, third, fourth],
+        [second, third, fourth],
+    ]
+    .into_iter()
+    .map(outward)
+    .collect();
+
+    for (index, &point) in points.iter().enumerate() {
+        let visible: Vec<bool> = faces
+            .iter()
+            .map(|&[a, b, c]| {
+                let normal = (points[b] - points[a])
+                    .cross(points[c] - points[a])
+                    .normalize_or_zero();
+                normal.dot(point - points[a]) > epsilon
+            })
+            .collect();
+        if !visible.contains(&true) {
+            continue;
+        }
+        // Edges of visible faces that no other visible face shares form the horizon.
+        let mut horizon = Vec::new();
+        for (face, _) in faces.iter().zip(&visible).filter(|(_, &v)| v) {
+            for edge in [(face[0], face[1]), (face[1], face[2]), (face[2], face[0])] {
+                let shared = faces
+                    .iter()
+                    .zip(&visible)
+                    .filter(|(_, &v)| v)
+                    .any(|(other, _)| {
+                        [
+                            (other[0], other[1]),
+                            (other[1], other[2]),
+                            (other[2], other[0]),
+                        ]
+                        .contains(&(edge.1, edge.0))
+                    });
+                if !shared {
+                    horizon.push(edge);
+                }
+            }
+        }
+        let mut keep = visible.iter().map(|v| !v);
+        faces.retain(|_| keep.next().unwrap_or(true));
+        faces.extend(horizon.into_iter().map(|(a, b)| [a, b, index]));
+    }
+    Some(faces)
+}
+
+fn farthest(points: &[Vec3], metric: impl Fn(Vec3) -> f32) -> Option<usize> {
+    points
+        .iter()
+        .map(|&point| metric(point))
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+fn box_inertia(half_extents: Vec3, mass: f32) -> Mat3 {
+    let size = half_extents * 2.0;
+    let factor = mass / 12.0;
+    Mat3::from_diagonal(Vec3::new(
+        factor * (size.y * size.y + size.z * size.z),
+        factor * (size.x * size.x + size.z * size.z),
+        factor * (size.x * size.x + size.y * size.y),
+    ))
+}
+
+fn cylinder_inertia(radius: f32, height: f32, mass: f32) -> Mat3 {
+    let lateral = mass * (3.0 * radius * radius + height * height) / 12.0;
+    Mat3::from_diagonal(Vec3::new(lateral, 0.5 * mass * radius * radius, lateral))
 }
 
 /// Convenience constructors for transforms.
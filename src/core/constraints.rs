@@ -1,9 +1,11 @@
-use crate::utils::allocator::EntityId;
+use crate::utils::allocator::{EntityId, GenerationalId};
 use glam::{Quat, Vec3};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Supported joint types for phase 4+ development.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Joint {
     /// Fully locks two bodies together at a specific relative transform.
     Fixed {
@@ -27,8 +29,11 @@ pub enum Joint {
         local_basis_b: Vec3,
 
         enable_motor: bool,
+        /// Target speed in [`MotorMode::Velocity`], feed-forward speed in [`MotorMode::Position`].
         motor_speed: f32,
         max_motor_torque: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        motor_mode: MotorMode,
 
         enable_limit: bool,
         lower_angle: f32,
@@ -51,8 +56,11 @@ pub enum Joint {
         upper_limit: f32,
 
         enable_motor: bool,
+        /// Target speed in [`MotorMode::Velocity`], feed-forward speed in [`MotorMode::Position`].
         motor_speed: f32,
         max_motor_force: f32,
+        #[cfg_attr(feature = "serde", serde(default))]
+        motor_mode: MotorMode,
     },
     Spring {
         body_a: EntityId,
@@ -69,6 +77,15 @@ pub enum Joint {
 }
 
 impl Joint {
+    /// Body id that anchors a joint to the static world instead of a second body.
+    ///
+    /// The world body sits at the origin with no rotation, so the pivot, axis, and frame on
+    /// its side of the joint are given in world space.
+    pub const WORLD: EntityId = EntityId(GenerationalId {
+        index: usize::MAX - 1,
+        generation: 0,
+    });
+
     pub fn bodies(&self) -> (EntityId, EntityId) {
         match self {
             Joint::Fixed { body_a, body_b, .. }
@@ -78,4 +95,79 @@ impl Joint {
             | Joint::Distance { body_a, body_b, .. } => (*body_a, *body_b),
         }
     }
+
+    /// Whether the joint has an enabled motor in [`MotorMode::Position`].
+    pub fn is_servo(&self) -> bool {
+        match self {
+            Joint::Revolute {
+                enable_motor,
+                motor_mode,
+                ..
+            }
+            | Joint::Prismatic {
+                enable_motor,
+                motor_mode,
+                ..
+            } => *enable_motor && matches!(motor_mode, MotorMode::Position { .. }),
+            _ => false,
+        }
+    }
+
+    /// Whether either side of the joint is [`Joint::WORLD`].
+    pub fn is_grounded(&self) -> bool {
+        let (body_a, body_b) = self.bodies();
+        body_a == Self::WORLD || body_b == Self::WORLD
+    }
+}
+
+/// What a Revolute or Prismatic motor drives towards.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MotorMode {
+    /// Drive the joint speed to `motor_speed`.
+    #[default]
+    Velocity,
+    /// Servo to a joint angle (radians) or offset along the axis, as a spring-damper.
+    ///
+    /// The drive is solved implicitly, so stiff servos stay stable; `max_motor_torque` or
+    /// `max_motor_force` still caps the effort.
+    Position {
+        target: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+}
+
+/// Load at which a breakable joint is removed from the world.
+///
+/// Force and torque are the joint's constraint impulse over one step divided by the step.
+/// Use `f32::INFINITY` to never break on one of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JointBreakLimit {
+    pub max_force: f32,
+    pub max_torque: f32,
+}
+
+impl JointBreakLimit {
+    pub fn new(max_force: f32, max_torque: f32) -> Self {
+        Self {
+            max_force,
+            max_torque,
+        }
+    }
+
+    pub fn is_exceeded(&self, force: f32, torque: f32) -> bool {
+        force > self.max_force || torque > self.max_torque
+    }
+}
+
+/// A breakable joint exceeded its [`JointBreakLimit`] and was removed.
+#[derive(Debug, Clone)]
+pub struct JointBroken {
+    pub joint: Joint,
+    /// Constraint force in the step it broke.
+    pub force: f32,
+    /// Constraint torque in the step it broke.
+    pub torque: f32,
 }
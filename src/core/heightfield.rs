@@ -0,0 +1,261 @@
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::mesh::Aabb;
+
+/// Regular grid of height samples used for terrain colliders.
+///
+/// Samples are stored row-major: columns run along local X, rows along local Z.
+/// The grid is centred on the local origin in XZ and heights are scaled by `scale.y`.
+/// Every cell is split into two triangles whose normals face +Y. Cells flagged as holes
+/// produce no triangles, so bodies and rays pass straight through them.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Heightfield {
+    pub rows: usize,
+    pub cols: usize,
+    pub heights: Vec<f32>,
+    pub scale: Vec3,
+    pub bounds: Aabb,
+    /// Per-cell hole flags, row-major over cells; empty when the field has no holes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub holes: Vec<bool>,
+    /// Per-triangle material table indices keyed by [`Heightfield::triangle_id`]; empty when unused.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub material_indices: Vec<u16>,
+}
+
+impl Heightfield {
+    /// Builds a heightfield from `rows * cols` samples. Panics on fewer than 2x2 samples.
+    pub fn new(rows: usize, cols: usize, heights: Vec<f32>, scale: Vec3) -> Self {
+        assert!(
+            rows >= 2 && cols >= 2,
+            "heightfield needs at least 2x2 samples"
+        );
+        assert_eq!(
+            heights.len(),
+            rows * cols,
+            "heightfield sample count must equal rows * cols"
+        );
+
+        let mut field = Self {
+            rows,
+            cols,
+            heights,
+            scale,
+            bounds: Aabb::empty(),
+            holes: Vec::new(),
+            material_indices: Vec::new(),
+        };
+        field.bounds = field.compute_bounds();
+        field
+    }
+
+    pub fn flat(rows: usize, cols: usize, scale: Vec3) -> Self {
+        Self::new(rows, cols, vec![0.0; rows * cols], scale)
+    }
+
+    pub fn cell_rows(&self) -> usize {
+        self.rows - 1
+    }
+
+    pub fn cell_cols(&self) -> usize {
+        self.cols - 1
+    }
+
+    /// Marks cells as holes. `holes` holds one flag per cell, row-major.
+    pub fn with_holes(mut self, holes: Vec<bool>) -> Self {
+        assert_eq!(
+            holes.len(),
+            self.cell_rows() * self.cell_cols(),
+            "heightfield needs one hole flag per cell"
+        );
+        self.holes = holes;
+        self
+    }
+
+    /// Assigns one material table index per triangle, ordered by [`Heightfield::triangle_id`].
+    pub fn with_material_indices(mut self, material_indices: Vec<u16>) -> Self {
+        assert_eq!(
+            material_indices.len(),
+            self.cell_rows() * self.cell_cols() * 2,
+            "heightfield needs one material index per triangle"
+        );
+        self.material_indices = material_indices;
+        self
+    }
+
+    pub fn set_hole(&mut self, row: usize, col: usize, hole: bool) {
+        if self.holes.is_empty() {
+            if !hole {
+                return;
+            }
+            self.holes = vec![false; self.cell_rows() * self.cell_cols()];
+        }
+        let index = row * self.cell_cols() + col;
+        self.holes[index] = hole;
+    }
+
+    pub fn is_hole(&self, row: usize, col: usize) -> bool {
+        self.holes
+            .get(row * self.cell_cols() + col)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Material table index of the triangle with the given [`Heightfield::triangle_id`].
+    pub fn material_index(&self, triangle: u32) -> Option<u16> {
+        self.material_indices.get(triangle as usize).copied()
+    }
+
+    fn origin(&self) -> Vec3 {
+        Vec3::new(
+            -0.5 * self.cell_cols() as f32 * self.scale.x,
+            0.0,
+            -0.5 * self.cell_rows() as f32 * self.scale.z,
+        )
+    }
+
+    /// Scaled height of the sample at `(row, col)`.
+    pub fn height(&self, row: usize, col: usize) -> f32 {
+        self.heights[row * self.cols + col] * self.scale.y
+    }
+
+    /// Local-space position of the sample at `(row, col)`.
+    pub fn vertex(&self, row: usize, col: usize) -> Vec3 {
+        let origin = self.origin();
+        Vec3::new(
+            origin.x + col as f32 * self.scale.x,
+            self.height(row, col),
+            origin.z + row as f32 * self.scale.z,
+        )
+    }
+
+    pub fn vertices(&self) -> impl Iterator<Item = Vec3> + '_ {
+        (0..self.rows).flat_map(move |row| (0..self.cols).map(move |col| self.vertex(row, col)))
+    }
+
+    /// Stable identifier for triangle `index` (0 or 1) of cell `(row, col)`.
+    pub fn triangle_id(&self, row: usize, col: usize, index: usize) -> u32 {
+        ((row * self.cell_cols() + col) * 2 + index) as u32
+    }
+
+    /// Both triangles of cell `(row, col)` in local space.
+    pub fn cell_triangles(&self, row: usize, col: usize) -> [[Vec3; 3]; 2] {
+        let v00 = self.vertex(row, col);
+        let v01 = self.vertex(row, col + 1);
+        let v10 = self.vertex(row + 1, col);
+        let v11 = self.vertex(row + 1, col + 1);
+        [[v00, v10, v01], [v01, v10, v11]]
+    }
+
+    /// Continuous grid coordinates `(col, row)` for a local-space point.
+    pub fn grid_coords(&self, local: Vec3) -> (f32, f32) {
+        let origin = self.origin();
+        (
+            (local.x - origin.x) / self.scale.x,
+            (local.z - origin.z) / self.scale.z,
+        )
+    }
+
+    /// Inclusive cell range `(row_min, row_max, col_min, col_max)` overlapping a local AABB.
+    pub fn cell_range(&self, min: Vec3, max: Vec3) -> Option<(usize, usize, usize, usize)> {
+        if max.y < self.bounds.min.y || min.y > self.bounds.max.y {
+            return None;
+        }
+        let (c0, r0) = self.grid_coords(min);
+        let (c1, r1) = self.grid_coords(max);
+        let (c0, c1) = (c0.min(c1), c0.max(c1));
+        let (r0, r1) = (r0.min(r1), r0.max(r1));
+        let max_col = self.cell_cols() as f32;
+        let max_row = self.cell_rows() as f32;
+        if c1 < 0.0 || r1 < 0.0 || c0 > max_col || r0 > max_row {
+            return None;
+        }
+        let clamp_col = |v: f32| (v.floor().max(0.0) as usize).min(self.cell_cols() - 1);
+        let clamp_row = |v: f32| (v.floor().max(0.0) as usize).min(self.cell_rows() - 1);
+        Some((clamp_row(r0), clamp_row(r1), clamp_col(c0), clamp_col(c1)))
+    }
+
+    /// Triangles overlapping a local AABB, tagged with their [`Heightfield::triangle_id`].
+    pub fn triangles_in_aabb(&self, min: Vec3, max: Vec3) -> Vec<(u32, [Vec3; 3])> {
+        let mut triangles = Vec::new();
+        let (r0, r1, c0, c1) = match self.cell_range(min, max) {
+            Some(range) => range,
+            None => return triangles,
+        };
+        for row in r0..=r1 {
+            for col in c0..=c1 {
+                if self.is_hole(row, col) {
+                    continue;
+                }
+                for (index, tri) in self.cell_triangles(row, col).into_iter().enumerate() {
+                    triangles.push((self.triangle_id(row, col, index), tri));
+                }
+            }
+        }
+        triangles
+    }
+
+    /// Interpolated surface height and triangle normal under a local XZ position.
+    ///
+    /// Returns `None` outside the grid and over holes.
+    pub fn surface_at(&self, x: f32, z: f32) -> Option<(f32, Vec3, u32)> {
+        let (u, v) = self.grid_coords(Vec3::new(x, 0.0, z));
+        let max_col = self.cell_cols() as f32;
+        let max_row = self.cell_rows() as f32;
+        if !(0.0..=max_col).contains(&u) || !(0.0..=max_row).contains(&v) {
+            return None;
+        }
+
+        let col = (u.floor() as usize).min(self.cell_cols() - 1);
+        let row = (v.floor() as usize).min(self.cell_rows() - 1);
+        if self.is_hole(row, col) {
+            return None;
+        }
+        let fu = u - col as f32;
+        let fv = v - row as f32;
+
+        let h00 = self.height(row, col);
+        let h01 = self.height(row, col + 1);
+        let h10 = self.height(row + 1, col);
+        let h11 = self.height(row + 1, col + 1);
+
+        let (height, index) = if fu + fv <= 1.0 {
+            (h00 + fu * (h01 - h00) + fv * (h10 - h00), 0)
+        } else {
+            (h11 + (1.0 - fu) * (h10 - h11) + (1.0 - fv) * (h01 - h11), 1)
+        };
+
+        let [a, b, c] = self.cell_triangles(row, col)[index];
+        let normal = (b - a).cross(c - a).normalize_or_zero();
+        Some((height, normal, self.triangle_id(row, col, index)))
+    }
+
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        self.surface_at(x, z).map(|(height, _, _)| height)
+    }
+
+    pub fn support_point(&self, direction: Vec3) -> Vec3 {
+        let dir = direction.normalize_or_zero();
+        if dir == Vec3::ZERO {
+            return Vec3::ZERO;
+        }
+        self.vertices()
+            .max_by(|a, b| a.dot(dir).partial_cmp(&b.dot(dir)).unwrap())
+            .unwrap_or(Vec3::ZERO)
+    }
+
+    pub fn bounding_radius(&self) -> f32 {
+        self.bounds.min.abs().max(self.bounds.max.abs()).length()
+    }
+
+    fn compute_bounds(&self) -> Aabb {
+        let mut bounds = Aabb::empty();
+        for vertex in self.vertices() {
+            bounds.extend(vertex);
+        }
+        bounds
+    }
+}
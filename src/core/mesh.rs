@@ -1,12 +1,14 @@
 use std::collections::HashMap;
 
 use glam::{Mat3, Vec3};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use super::types::MassProperties;
 
 /// Axis-aligned bounding box used for mesh bounds and BVH nodes.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Aabb {
     pub min: Vec3,
     pub max: Vec3,
@@ -48,10 +50,48 @@ impl Aabb {
     pub fn radius(&self) -> f32 {
         self.extent().length()
     }
+
+    /// Smallest box containing both boxes.
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb::new(self.min.min(other.min), self.max.max(other.max))
+    }
+
+    /// Surface area of the box; zero for empty boxes.
+    pub fn surface_area(&self) -> f32 {
+        let size = (self.max - self.min).max(Vec3::ZERO);
+        2.0 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    pub fn overlaps(&self, other: &Aabb) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+
+    /// Conservative bounds of this box after applying `matrix` and then `translation`.
+    pub fn transformed(&self, matrix: Mat3, translation: Vec3) -> Aabb {
+        let center = matrix * self.center() + translation;
+        let abs = Mat3::from_cols(
+            matrix.x_axis.abs(),
+            matrix.y_axis.abs(),
+            matrix.z_axis.abs(),
+        );
+        let extent = abs * self.extent();
+        Aabb::new(center - extent, center + extent)
+    }
+}
+
+/// How [`MeshBvh`] chooses where to split a node while building.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BvhSplit {
+    /// Halves the triangles along the longest centroid axis. Fast to build.
+    Median,
+    /// Binned surface-area heuristic. Slower to build, cheaper to query.
+    #[default]
+    Sah,
 }
 
 /// Simple BVH node representation for triangle meshes.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MeshBvhNode {
     pub bounds: Aabb,
     pub left: Option<usize>,
@@ -60,25 +100,332 @@ pub struct MeshBvhNode {
     pub count: usize,
 }
 
-/// Placeholder BVH storing a single node per mesh for now.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl MeshBvhNode {
+    pub fn is_leaf(&self) -> bool {
+        self.left.is_none() && self.right.is_none()
+    }
+}
+
+/// Bounding volume hierarchy over mesh triangles; `nodes[0]` is the root.
+///
+/// Leaves reference a contiguous `start..start + count` range of the mesh index buffer.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MeshBvh {
     pub nodes: Vec<MeshBvhNode>,
 }
 
 impl MeshBvh {
+    const MAX_LEAF_TRIANGLES: usize = 4;
+    const SAH_BINS: usize = 12;
+
     pub fn new(nodes: Vec<MeshBvhNode>) -> Self {
         Self { nodes }
     }
+
+    /// Builds a hierarchy over `indices` with the surface-area heuristic.
+    ///
+    /// Returns the hierarchy and the triangle order it expects: leaf ranges refer to slots in
+    /// `order`, where `order[slot]` is the original triangle index.
+    pub fn build(vertices: &[Vec3], indices: &[[u32; 3]]) -> (Self, Vec<usize>) {
+        Self::build_with(vertices, indices, BvhSplit::default())
+    }
+
+    /// Like [`MeshBvh::build`], with an explicit split strategy.
+    pub fn build_with(
+        vertices: &[Vec3],
+        indices: &[[u32; 3]],
+        split: BvhSplit,
+    ) -> (Self, Vec<usize>) {
+        let mut order: Vec<usize> = (0..indices.len()).collect();
+        let mut nodes = Vec::new();
+        if indices.is_empty() {
+            nodes.push(MeshBvhNode {
+                bounds: Aabb::from_points(vertices),
+                left: None,
+                right: None,
+                start: 0,
+                count: 0,
+            });
+        } else {
+            Self::build_node(&mut nodes, vertices, indices, &mut order, 0, split);
+        }
+        (Self { nodes }, order)
+    }
+
+    fn build_node(
+        nodes: &mut Vec<MeshBvhNode>,
+        vertices: &[Vec3],
+        indices: &[[u32; 3]],
+        order: &mut [usize],
+        start: usize,
+        split: BvhSplit,
+    ) -> usize {
+        let corners = |tri: usize| indices[tri].map(|i| vertices[i as usize]);
+        let mut bounds = Aabb::empty();
+        let mut centroid_bounds = Aabb::empty();
+        for &tri in order.iter() {
+            let [a, b, c] = corners(tri);
+            bounds.extend(a);
+            bounds.extend(b);
+            bounds.extend(c);
+            centroid_bounds.extend((a + b + c) / 3.0);
+        }
+
+        let index = nodes.len();
+        nodes.push(MeshBvhNode {
+            bounds,
+            left: None,
+            right: None,
+            start,
+            count: order.len(),
+        });
+
+        if order.len() <= Self::MAX_LEAF_TRIANGLES {
+            return index;
+        }
+
+        let mid = match split {
+            BvhSplit::Sah => Self::sah_partition(vertices, indices, order, &centroid_bounds),
+            BvhSplit::Median => None,
+        }
+        .unwrap_or_else(|| Self::median_partition(vertices, indices, order, &centroid_bounds));
+
+        let (left_half, right_half) = order.split_at_mut(mid);
+        let left = Self::build_node(nodes, vertices, indices, left_half, start, split);
+        let right = Self::build_node(nodes, vertices, indices, right_half, start + mid, split);
+        nodes[index].left = Some(left);
+        nodes[index].right = Some(right);
+        index
+    }
+
+    fn median_partition(
+        vertices: &[Vec3],
+        indices: &[[u32; 3]],
+        order: &mut [usize],
+        centroid_bounds: &Aabb,
+    ) -> usize {
+        let size = centroid_bounds.max - centroid_bounds.min;
+        let axis = if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        };
+        let centroid = |tri: usize| {
+            indices[tri]
+                .iter()
+                .map(|&i| vertices[i as usize][axis])
+                .sum::<f32>()
+        };
+        order.sort_by(|&a, &b| {
+            centroid(a)
+                .partial_cmp(&centroid(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        order.len() / 2
+    }
+
+    /// Partitions `order` at the cheapest binned split over all three axes. Returns `None`
+    /// when every centroid falls in one bin.
+    fn sah_partition(
+        vertices: &[Vec3],
+        indices: &[[u32; 3]],
+        order: &mut [usize],
+        centroid_bounds: &Aabb,
+    ) -> Option<usize> {
+        let corners = |tri: usize| indices[tri].map(|i| vertices[i as usize]);
+        let centroid = |tri: usize| {
+            let [a, b, c] = corners(tri);
+            (a + b + c) / 3.0
+        };
+        let size = centroid_bounds.max - centroid_bounds.min;
+        let bin_of = |tri: usize, axis: usize| {
+            let t = (centroid(tri)[axis] - centroid_bounds.min[axis]) / size[axis];
+            ((t * Self::SAH_BINS as f32) as usize).min(Self::SAH_BINS - 1)
+        };
+
+        // (cost, axis, first bin of the right half)
+        let mut best: Option<(f32, usize, usize)> = None;
+        for axis in 0..3 {
+            if size[axis] <= f32::EPSILON {
+                continue;
+            }
+            let mut bins = [(Aabb::empty(), 0usize); Self::SAH_BINS];
+            for &tri in order.iter() {
+                let bin = &mut bins[bin_of(tri, axis)];
+                for corner in corners(tri) {
+                    bin.0.extend(corner);
+                }
+                bin.1 += 1;
+            }
+
+            // Sweep from the right to get the cost of every right half, then from the left.
+            let mut right_costs = [0.0f32; Self::SAH_BINS];
+            let (mut bounds, mut count) = (Aabb::empty(), 0);
+            for split in (1..Self::SAH_BINS).rev() {
+                bounds = bounds.union(&bins[split].0);
+                count += bins[split].1;
+                right_costs[split] = bounds.surface_area() * count as f32;
+            }
+            let (mut bounds, mut count) = (Aabb::empty(), 0);
+            for split in 1..Self::SAH_BINS {
+                bounds = bounds.union(&bins[split - 1].0);
+                count += bins[split - 1].1;
+                if count == 0 || count == order.len() {
+                    continue;
+                }
+                let cost = bounds.surface_area() * count as f32 + right_costs[split];
+                if best.is_none_or(|(best_cost, _, _)| cost < best_cost) {
+                    best = Some((cost, axis, split));
+                }
+            }
+        }
+
+        let (_, axis, split) = best?;
+        let mut mid = 0;
+        for i in 0..order.len() {
+            if bin_of(order[i], axis) < split {
+                order.swap(i, mid);
+                mid += 1;
+            }
+        }
+        Some(mid)
+    }
+
+    /// Recomputes node bounds bottom-up for moved vertices. The tree shape and triangle order
+    /// are kept, so queries stay correct but may slow down if the mesh deforms a lot.
+    pub fn refit(&mut self, vertices: &[Vec3], indices: &[[u32; 3]]) {
+        // Children are always stored after their parent.
+        for index in (0..self.nodes.len()).rev() {
+            let node = &self.nodes[index];
+            let bounds = match (node.left, node.right) {
+                (None, None) if node.count == 0 => Aabb::from_points(vertices),
+                (None, None) => {
+                    let mut bounds = Aabb::empty();
+                    for tri in &indices[node.start..node.start + node.count] {
+                        for &i in tri {
+                            bounds.extend(vertices[i as usize]);
+                        }
+                    }
+                    bounds
+                }
+                (left, right) => left
+                    .into_iter()
+                    .chain(right)
+                    .fold(Aabb::empty(), |bounds, child| {
+                        bounds.union(&self.nodes[child].bounds)
+                    }),
+            };
+            self.nodes[index].bounds = bounds;
+        }
+    }
+
+    /// Encodes the hierarchy in a compact little-endian format for offline cooking.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = ByteWriter::new(BVH_MAGIC);
+        self.write(&mut writer);
+        writer.bytes
+    }
+
+    /// Decodes bytes produced by [`MeshBvh::to_bytes`]. Returns `None` for malformed data.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader::new(bytes, BVH_MAGIC)?;
+        let bvh = Self::read(&mut reader)?;
+        reader.finish()?;
+        Some(bvh)
+    }
+
+    fn write(&self, writer: &mut ByteWriter) {
+        writer.u32(self.nodes.len() as u32);
+        for node in &self.nodes {
+            writer.vec3(node.bounds.min);
+            writer.vec3(node.bounds.max);
+            writer.u32(node.left.map_or(u32::MAX, |child| child as u32));
+            writer.u32(node.right.map_or(u32::MAX, |child| child as u32));
+            writer.u32(node.start as u32);
+            writer.u32(node.count as u32);
+        }
+    }
+
+    fn read(reader: &mut ByteReader) -> Option<Self> {
+        let count = reader.u32()? as usize;
+        let mut nodes = Vec::with_capacity(count.min(reader.remaining() / NODE_BYTES));
+        for index in 0..count {
+            let bounds = Aabb::new(reader.vec3()?, reader.vec3()?);
+            // Children must follow their parent; this also rules out cycles.
+            let child = |raw: u32| match raw {
+                u32::MAX => Some(None),
+                raw if (raw as usize) > index && (raw as usize) < count => Some(Some(raw as usize)),
+                _ => None,
+            };
+            let left = child(reader.u32()?)?;
+            let right = child(reader.u32()?)?;
+            let start = reader.u32()? as usize;
+            let node_count = reader.u32()? as usize;
+            nodes.push(MeshBvhNode {
+                bounds,
+                left,
+                right,
+                start,
+                count: node_count,
+            });
+        }
+        if nodes.is_empty() {
+            return None;
+        }
+        Some(Self { nodes })
+    }
+
+    /// Whether every leaf range fits in a mesh with `triangles` triangles.
+    fn fits(&self, triangles: usize) -> bool {
+        self.nodes.iter().all(|node| {
+            !node.is_leaf()
+                || node
+                    .start
+                    .checked_add(node.count)
+                    .is_some_and(|end| end <= triangles)
+        })
+    }
+
+    /// Collects indices of triangles whose leaf bounds overlap `bounds`.
+    pub fn query(&self, bounds: &Aabb, out: &mut Vec<usize>) {
+        if self.nodes.is_empty() {
+            return;
+        }
+        let mut stack = vec![0usize];
+        while let Some(node_index) = stack.pop() {
+            let node = &self.nodes[node_index];
+            if !node.bounds.overlaps(bounds) {
+                continue;
+            }
+            if node.is_leaf() {
+                out.extend(node.start..node.start + node.count);
+                continue;
+            }
+            stack.extend(node.left);
+            stack.extend(node.right);
+        }
+    }
 }
 
 /// Triangle mesh collider data used for advanced shapes.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TriangleMesh {
     pub vertices: Vec<Vec3>,
+    /// Triangles in BVH leaf order, which may differ from the order given to
+    /// [`MeshBuilder`]; see [`TriangleMesh::source_triangle`].
     pub indices: Vec<[u32; 3]>,
     pub bounds: Aabb,
     pub bvh: MeshBvh,
+    /// Optional per-triangle index into a user material table; empty when unused.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub material_indices: Vec<u16>,
+    /// Builder index of each triangle in `indices`; empty when they are in builder order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub source_triangles: Vec<u32>,
 }
 
 impl TriangleMesh {
@@ -86,6 +433,23 @@ impl TriangleMesh {
         MeshBuilder::new(vertices, indices)
     }
 
+    /// Material table index assigned to triangle `index`, if any.
+    pub fn material_index(&self, index: usize) -> Option<u16> {
+        self.material_indices.get(index).copied()
+    }
+
+    /// Index triangle `index` had in the buffer given to [`MeshBuilder`].
+    pub fn source_triangle(&self, index: usize) -> usize {
+        self.source_triangles
+            .get(index)
+            .map_or(index, |&source| source as usize)
+    }
+
+    /// Local-space corners of triangle `index`.
+    pub fn triangle(&self, index: usize) -> [Vec3; 3] {
+        self.indices[index].map(|i| self.vertices[i as usize])
+    }
+
     pub fn support_point(&self, direction: Vec3) -> Vec3 {
         let dir = direction.normalize_or_zero();
         if dir == Vec3::ZERO {
@@ -114,6 +478,91 @@ impl TriangleMesh {
         self.bounds.radius()
     }
 
+    /// Refreshes `bounds` and refits the BVH after editing `vertices` in place.
+    pub fn refit(&mut self) {
+        self.bounds = Aabb::from_points(&self.vertices);
+        self.bvh.refit(&self.vertices, &self.indices);
+    }
+
+    /// Encodes the cooked mesh, BVH included, so loading skips the build.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = ByteWriter::new(MESH_MAGIC);
+        writer.u32(self.vertices.len() as u32);
+        for &vertex in &self.vertices {
+            writer.vec3(vertex);
+        }
+        writer.u32(self.indices.len() as u32);
+        for tri in &self.indices {
+            for &i in tri {
+                writer.u32(i);
+            }
+        }
+        writer.u32(self.material_indices.len() as u32);
+        for &material in &self.material_indices {
+            writer.u16(material);
+        }
+        writer.u32(self.source_triangles.len() as u32);
+        for &source in &self.source_triangles {
+            writer.u32(source);
+        }
+        self.bvh.write(&mut writer);
+        writer.bytes
+    }
+
+    /// Decodes bytes produced by [`TriangleMesh::to_bytes`]. Returns `None` for malformed
+    /// data, including out-of-range vertex indices and BVH leaves.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = ByteReader::new(bytes, MESH_MAGIC)?;
+        let vertex_count = reader.u32()? as usize;
+        let mut vertices = Vec::with_capacity(vertex_count.min(reader.remaining() / 12));
+        for _ in 0..vertex_count {
+            vertices.push(reader.vec3()?);
+        }
+        let triangle_count = reader.u32()? as usize;
+        let mut indices = Vec::with_capacity(triangle_count.min(reader.remaining() / 12));
+        for _ in 0..triangle_count {
+            let tri = [reader.u32()?, reader.u32()?, reader.u32()?];
+            if tri.iter().any(|&i| i as usize >= vertex_count) {
+                return None;
+            }
+            indices.push(tri);
+        }
+        let material_count = reader.u32()? as usize;
+        if material_count != 0 && material_count != triangle_count {
+            return None;
+        }
+        let mut material_indices = Vec::with_capacity(material_count);
+        for _ in 0..material_count {
+            material_indices.push(reader.u16()?);
+        }
+        let source_count = reader.u32()? as usize;
+        if source_count != 0 && source_count != triangle_count {
+            return None;
+        }
+        let mut source_triangles = Vec::with_capacity(source_count);
+        for _ in 0..source_count {
+            let source = reader.u32()?;
+            if source as usize >= triangle_count {
+                return None;
+            }
+            source_triangles.push(source);
+        }
+        let bvh = MeshBvh::read(&mut reader)?;
+        reader.finish()?;
+        if !bvh.fits(triangle_count) {
+            return None;
+        }
+
+        Some(Self {
+            bounds: Aabb::from_points(&vertices),
+            vertices,
+            indices,
+            bvh,
+            material_indices,
+            source_triangles,
+        })
+    }
+
     /// Approximates mass & inertia by treating the mesh bounds as a solid box.
     pub fn approximate_mass_properties(&self, density: f32) -> MassProperties {
         let extents = self.bounds.extent();
@@ -137,11 +586,30 @@ impl TriangleMesh {
 pub struct MeshBuilder {
     vertices: Vec<Vec3>,
     indices: Vec<[u32; 3]>,
+    material_indices: Vec<u16>,
+    bvh_split: BvhSplit,
 }
 
 impl MeshBuilder {
     pub fn new(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> Self {
-        Self { vertices, indices }
+        Self {
+            vertices,
+            indices,
+            material_indices: Vec::new(),
+            bvh_split: BvhSplit::default(),
+        }
+    }
+
+    /// Split strategy for the BVH built by [`MeshBuilder::build`].
+    pub fn bvh_split(mut self, split: BvhSplit) -> Self {
+        self.bvh_split = split;
+        self
+    }
+
+    /// Assigns one material table index per triangle, in the same order as `indices`.
+    pub fn material_indices(mut self, material_indices: Vec<u16>) -> Self {
+        self.material_indices = material_indices;
+        self
     }
 
     /// Deduplicates vertices using a quantized grid for stability.
@@ -192,20 +660,113 @@ impl MeshBuilder {
         self
     }
 
+    /// Cooks the mesh and its BVH.
+    ///
+    /// Triangles, and their material indices, are stored in BVH leaf order;
+    /// [`TriangleMesh::source_triangle`] maps a stored triangle back to its index here.
     pub fn build(self) -> TriangleMesh {
+        assert!(
+            self.material_indices.is_empty() || self.material_indices.len() == self.indices.len(),
+            "mesh needs one material index per triangle"
+        );
         let bounds = Aabb::from_points(&self.vertices);
-        let node = MeshBvhNode {
-            bounds,
-            left: None,
-            right: None,
-            start: 0,
-            count: self.indices.len(),
+        let (bvh, order) = MeshBvh::build_with(&self.vertices, &self.indices, self.bvh_split);
+        let indices = order.iter().map(|&tri| self.indices[tri]).collect();
+        let material_indices = if self.material_indices.is_empty() {
+            Vec::new()
+        } else {
+            order
+                .iter()
+                .map(|&tri| self.material_indices[tri])
+                .collect()
         };
         TriangleMesh {
             vertices: self.vertices,
-            indices: self.indices,
+            indices,
             bounds,
-            bvh: MeshBvh::new(vec![node]),
+            bvh,
+            material_indices,
+            source_triangles: order.iter().map(|&tri| tri as u32).collect(),
+        }
+    }
+}
+
+const BVH_MAGIC: [u8; 4] = *b"PABV";
+const MESH_MAGIC: [u8; 4] = *b"PAMS";
+const FORMAT_VERSION: u32 = 2;
+const NODE_BYTES: usize = 40;
+
+/// Little-endian encoder for cooked mesh data.
+struct ByteWriter {
+    bytes: Vec<u8>,
+}
+
+impl ByteWriter {
+    fn new(magic: [u8; 4]) -> Self {
+        let mut writer = Self {
+            bytes: magic.to_vec(),
+        };
+        writer.u32(FORMAT_VERSION);
+        writer
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn vec3(&mut self, value: Vec3) {
+        for component in value.to_array() {
+            self.bytes.extend_from_slice(&component.to_le_bytes());
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    /// Checks the magic and version header.
+    fn new(bytes: &'a [u8], magic: [u8; 4]) -> Option<Self> {
+        let mut reader = Self { bytes };
+        if reader.take::<4>()? != magic || reader.u32()? != FORMAT_VERSION {
+            return None;
         }
+        Some(reader)
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let (head, rest) = self.bytes.split_first_chunk::<N>()?;
+        self.bytes = rest;
+        Some(*head)
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    fn vec3(&mut self) -> Option<Vec3> {
+        let mut value = [0.0; 3];
+        for component in &mut value {
+            *component = f32::from_le_bytes(self.take()?);
+        }
+        Some(Vec3::from_array(value))
+    }
+
+    /// Rejects trailing bytes.
+    fn finish(&self) -> Option<()> {
+        self.bytes.is_empty().then_some(())
     }
 }
@@ -3,14 +3,19 @@
 pub mod articulations;
 pub mod collider;
 pub mod constraints;
+pub mod heightfield;
 pub mod mesh;
 pub mod rigidbody;
 pub mod soa;
 pub mod types;
 
-pub use articulations::{JointType as ArticulatedJointType, Link, Multibody};
-pub use collider::{Collider, ColliderShape, CollisionFilter};
-pub use constraints::Joint;
-pub use mesh::{Aabb, MeshBuilder, MeshBvh, TriangleMesh};
-pub use rigidbody::RigidBody;
-pub use types::{MassProperties, Material, Transform, Velocity};
+pub use articulations::{JointType as ArticulatedJointType, Link, LinkMotor, Multibody};
+pub use collider::{Collider, ColliderShape, CollisionFilter, CollisionGroups, CollisionLayers};
+pub use constraints::{Joint, JointBreakLimit, JointBroken, MotorMode};
+pub use heightfield::Heightfield;
+pub use mesh::{Aabb, BvhSplit, MeshBuilder, MeshBvh, TriangleMesh};
+pub use rigidbody::{BodyType, RigidBody};
+pub use types::{
+    AnisotropicFriction, MassIssue, MassProperties, MassValidation, Material, StribeckCurve,
+    Transform, Velocity,
+};
@@ -1,8 +1,28 @@
-use crate::utils::allocator::EntityId;
+use crate::{
+    config::{
+        DEFAULT_ANGULAR_DAMPING, DEFAULT_LINEAR_DAMPING, DEFAULT_MAX_ANGULAR_VELOCITY,
+        DEFAULT_MAX_LINEAR_VELOCITY,
+    },
+    utils::allocator::EntityId,
+};
 
 use super::types::{MassProperties, Material, Transform, Velocity};
 use glam::{Mat3, Vec3};
 
+/// How a body takes part in the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BodyType {
+    /// Moved by gravity, forces and contacts.
+    #[default]
+    Dynamic,
+    /// Moved only by its own velocity or by
+    /// [`PhysicsWorld::set_next_kinematic_position`](crate::world::PhysicsWorld::set_next_kinematic_position);
+    /// the solver treats it as infinitely heavy, so it pushes dynamic bodies aside.
+    Kinematic,
+    /// Never moves.
+    Static,
+}
+
 /// Core rigid body description storing kinematic state and properties.
 #[derive(Debug, Clone)]
 pub struct RigidBody {
@@ -13,12 +33,20 @@ pub struct RigidBody {
     pub mass_properties: MassProperties,
     pub material: Material,
     pub gravity_scale: f32,
+    /// Gravity this body falls with instead of the world's; still scaled by `gravity_scale`.
+    pub gravity_override: Option<Vec3>,
     pub is_static: bool,
     pub is_kinematic: bool,
     pub is_awake: bool,
     pub is_enabled: bool,
+    /// Opts the body into swept CCD and speculative contacts.
+    pub ccd_enabled: bool,
     pub linear_velocity_damping: f32,
     pub angular_velocity_damping: f32,
+    /// Speed limit (m/s) enforced after each integration substep.
+    pub max_linear_velocity: f32,
+    /// Angular speed limit (rad/s) enforced after each integration substep.
+    pub max_angular_velocity: f32,
     pub inverse_mass: f32,
     pub inverse_inertia: Mat3,
 }
@@ -33,12 +61,16 @@ impl Default for RigidBody {
             mass_properties: MassProperties::default(),
             material: Material::default(),
             gravity_scale: 1.0,
+            gravity_override: None,
             is_static: false,
             is_kinematic: false,
             is_awake: true,
             is_enabled: true,
-            linear_velocity_damping: 0.02,
-            angular_velocity_damping: 0.02,
+            ccd_enabled: false,
+            linear_velocity_damping: DEFAULT_LINEAR_DAMPING,
+            angular_velocity_damping: DEFAULT_ANGULAR_DAMPING,
+            max_linear_velocity: DEFAULT_MAX_LINEAR_VELOCITY,
+            max_angular_velocity: DEFAULT_MAX_ANGULAR_VELOCITY,
             inverse_mass: 1.0,
             inverse_inertia: Mat3::IDENTITY,
         };
@@ -91,25 +123,28 @@ impl RigidBody {
         self.recompute_inverses();
     }
 
-    pub fn recompute_inverses(&mut self) {
+    pub fn body_type(&self) -> BodyType {
         if self.is_static {
-            self.inverse_mass = 0.0;
-            self.inverse_inertia = Mat3::ZERO;
-            return;
-        }
-        self.inverse_mass = if self.mass_properties.mass.abs() < f32::EPSILON {
-            0.0
+            BodyType::Static
+        } else if self.is_kinematic {
+            BodyType::Kinematic
         } else {
-            1.0 / self.mass_properties.mass
-        };
-        let det = self.mass_properties.inertia.determinant();
-        if det.abs() < f32::EPSILON {
-            self.inverse_inertia = Mat3::ZERO;
-        } else {
-            self.inverse_inertia = self.mass_properties.inertia.inverse();
+            BodyType::Dynamic
         }
     }
 
+    pub fn set_body_type(&mut self, body_type: BodyType) {
+        self.is_static = body_type == BodyType::Static;
+        self.is_kinematic = body_type == BodyType::Kinematic;
+        self.recompute_inverses();
+    }
+
+    /// Static and kinematic bodies get zero inverses, so contacts cannot move them.
+    pub fn recompute_inverses(&mut self) {
+        (self.inverse_mass, self.inverse_inertia) =
+            inverse_mass_properties(&self.mass_properties, self.body_type());
+    }
+
     pub fn builder() -> RigidBodyBuilder {
         RigidBodyBuilder::new()
     }
@@ -160,12 +195,62 @@ impl RigidBodyBuilder {
         self
     }
 
+    pub fn body_type(mut self, body_type: BodyType) -> Self {
+        self.body.set_body_type(body_type);
+        self
+    }
+
     pub fn velocity(mut self, linear: Vec3, angular: Vec3) -> Self {
         self.body.set_velocity(linear, angular);
         self
     }
 
+    pub fn ccd_enabled(mut self, enabled: bool) -> Self {
+        self.body.ccd_enabled = enabled;
+        self
+    }
+
+    pub fn damping(mut self, linear: f32, angular: f32) -> Self {
+        self.body.linear_velocity_damping = linear;
+        self.body.angular_velocity_damping = angular;
+        self
+    }
+
+    pub fn max_velocity(mut self, linear: f32, angular: f32) -> Self {
+        self.body.max_linear_velocity = linear;
+        self.body.max_angular_velocity = angular;
+        self
+    }
+
+    pub fn gravity_scale(mut self, scale: f32) -> Self {
+        self.body.gravity_scale = scale;
+        self
+    }
+
+    pub fn gravity(mut self, gravity: Vec3) -> Self {
+        self.body.gravity_override = Some(gravity);
+        self
+    }
+
     pub fn build(self) -> RigidBody {
         self.body
     }
 }
+
+/// Inverse mass and inertia a body of `body_type` with `props` presents to the solver.
+pub(crate) fn inverse_mass_properties(props: &MassProperties, body_type: BodyType) -> (f32, Mat3) {
+    if body_type != BodyType::Dynamic {
+        return (0.0, Mat3::ZERO);
+    }
+    let inverse_mass = if props.mass.abs() < f32::EPSILON {
+        0.0
+    } else {
+        1.0 / props.mass
+    };
+    let inverse_inertia = if props.inertia.determinant().abs() < f32::EPSILON {
+        Mat3::ZERO
+    } else {
+        props.inertia.inverse()
+    };
+    (inverse_mass, inverse_inertia)
+}
@@ -1,15 +1,19 @@
-use crate::core::rigidbody::RigidBody;
+use crate::core::rigidbody::{inverse_mass_properties, BodyType, RigidBody};
 use crate::core::types::{MassProperties, Material, Transform, Velocity};
 use crate::utils::allocator::EntityId;
 use glam::{Mat3, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BodyFlags {
     pub is_static: bool,
     pub is_kinematic: bool,
     pub is_awake: bool,
     pub is_enabled: bool,
+    pub ccd_enabled: bool,
 }
 
 impl Default for BodyFlags {
@@ -19,13 +23,39 @@ impl Default for BodyFlags {
             is_kinematic: false,
             is_awake: true,
             is_enabled: true,
+            ccd_enabled: false,
+        }
+    }
+}
+
+impl BodyFlags {
+    pub fn body_type(&self) -> BodyType {
+        if self.is_static {
+            BodyType::Static
+        } else if self.is_kinematic {
+            BodyType::Kinematic
+        } else {
+            BodyType::Dynamic
+        }
+    }
+}
+
+impl From<&RigidBody> for BodyFlags {
+    fn from(body: &RigidBody) -> Self {
+        Self {
+            is_static: body.is_static,
+            is_kinematic: body.is_kinematic,
+            is_awake: body.is_awake,
+            is_enabled: body.is_enabled,
+            ccd_enabled: body.ccd_enabled,
         }
     }
 }
 
 /// Structure-of-Arrays storage for Rigid Bodies.
 /// Replaces Arena<RigidBody> for better cache locality.
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BodiesSoA {
     // Generational memory management
     pub generations: Vec<u32>,
@@ -48,8 +78,11 @@ pub struct BodiesSoA {
     pub materials: Vec<Material>,
     pub flags: Vec<BodyFlags>,
     pub gravity_scales: Vec<f32>,
+    pub gravity_overrides: Vec<Option<Vec3>>,
     pub linear_dampings: Vec<f32>,
     pub angular_dampings: Vec<f32>,
+    pub max_linear_velocities: Vec<f32>,
+    pub max_angular_velocities: Vec<f32>,
 }
 
 impl BodiesSoA {
@@ -72,6 +105,27 @@ impl BodiesSoA {
         }
     }
 
+    /// Makes room for `additional` more bodies, beyond the free slots already available.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = additional.saturating_sub(self.free_list.len());
+        self.generations.reserve(needed);
+        self.ids.reserve(needed);
+        self.transforms.reserve(needed);
+        self.velocities.reserve(needed);
+        self.accelerations.reserve(needed);
+        self.inverse_masses.reserve(needed);
+        self.inverse_inertias.reserve(needed);
+        self.mass_properties.reserve(needed);
+        self.materials.reserve(needed);
+        self.flags.reserve(needed);
+        self.gravity_scales.reserve(needed);
+        self.gravity_overrides.reserve(needed);
+        self.linear_dampings.reserve(needed);
+        self.angular_dampings.reserve(needed);
+        self.max_linear_velocities.reserve(needed);
+        self.max_angular_velocities.reserve(needed);
+    }
+
     pub fn remove(&mut self, id: EntityId) -> Option<()> {
         if !self.is_valid(id) {
             return None;
@@ -113,8 +167,11 @@ impl BodiesSoA {
             material: &mut self.materials[i],
             flags: &mut self.flags[i],
             gravity_scale: &mut self.gravity_scales[i],
+            gravity_override: &mut self.gravity_overrides[i],
             linear_damping: &mut self.linear_dampings[i],
             angular_damping: &mut self.angular_dampings[i],
+            max_linear_velocity: &mut self.max_linear_velocities[i],
+            max_angular_velocity: &mut self.max_angular_velocities[i],
         })
     }
 
@@ -141,8 +198,11 @@ impl BodiesSoA {
             let ptr_mat = self.materials.as_mut_ptr();
             let ptr_flags = self.flags.as_mut_ptr();
             let ptr_grav = self.gravity_scales.as_mut_ptr();
+            let ptr_grav_override = self.gravity_overrides.as_mut_ptr();
             let ptr_ld = self.linear_dampings.as_mut_ptr();
             let ptr_ad = self.angular_dampings.as_mut_ptr();
+            let ptr_max_lin = self.max_linear_velocities.as_mut_ptr();
+            let ptr_max_ang = self.max_angular_velocities.as_mut_ptr();
 
             let a = BodyMut {
                 id: id_a,
@@ -155,8 +215,11 @@ impl BodiesSoA {
                 material: &mut *ptr_mat.add(i_a),
                 flags: &mut *ptr_flags.add(i_a),
                 gravity_scale: &mut *ptr_grav.add(i_a),
+                gravity_override: &mut *ptr_grav_override.add(i_a),
                 linear_damping: &mut *ptr_ld.add(i_a),
                 angular_damping: &mut *ptr_ad.add(i_a),
+                max_linear_velocity: &mut *ptr_max_lin.add(i_a),
+                max_angular_velocity: &mut *ptr_max_ang.add(i_a),
             };
 
             let b = BodyMut {
@@ -170,8 +233,11 @@ impl BodiesSoA {
                 material: &mut *ptr_mat.add(i_b),
                 flags: &mut *ptr_flags.add(i_b),
                 gravity_scale: &mut *ptr_grav.add(i_b),
+                gravity_override: &mut *ptr_grav_override.add(i_b),
                 linear_damping: &mut *ptr_ld.add(i_b),
                 angular_damping: &mut *ptr_ad.add(i_b),
+                max_linear_velocity: &mut *ptr_max_lin.add(i_b),
+                max_angular_velocity: &mut *ptr_max_ang.add(i_b),
             };
 
             Some((a, b))
@@ -226,11 +292,37 @@ pub struct BodyProxyMut<'a> {
     pub material: &'a mut Material,
     pub flags: &'a mut BodyFlags,
     pub gravity_scale: &'a mut f32,
+    pub gravity_override: &'a mut Option<Vec3>,
     pub linear_damping: &'a mut f32,
     pub angular_damping: &'a mut f32,
+    pub max_linear_velocity: &'a mut f32,
+    pub max_angular_velocity: &'a mut f32,
 }
 
 impl<'a> BodyProxyMut<'a> {
+    /// Borrows a standalone body as a proxy.
+    ///
+    /// `flags` stands in for the body's flag fields; copy any changes back afterwards.
+    pub(crate) fn from_rigid_body(body: &'a mut RigidBody, flags: &'a mut BodyFlags) -> Self {
+        Self {
+            id: body.id,
+            transform: &mut body.transform,
+            velocity: &mut body.velocity,
+            acceleration: &mut body.acceleration,
+            inverse_mass: &mut body.inverse_mass,
+            inverse_inertia: &mut body.inverse_inertia,
+            mass_properties: &mut body.mass_properties,
+            material: &mut body.material,
+            flags,
+            gravity_scale: &mut body.gravity_scale,
+            gravity_override: &mut body.gravity_override,
+            linear_damping: &mut body.linear_velocity_damping,
+            angular_damping: &mut body.angular_velocity_damping,
+            max_linear_velocity: &mut body.max_linear_velocity,
+            max_angular_velocity: &mut body.max_angular_velocity,
+        }
+    }
+
     pub fn apply_impulse(&mut self, impulse: Vec3, position: Vec3) {
         if self.flags.is_static {
             return;
@@ -254,6 +346,11 @@ impl<'a> BodyProxyMut<'a> {
         self.velocity.angular = angular;
     }
 
+    /// Gravitational acceleration this body feels when the world's is `world_gravity`.
+    pub fn gravity(&self, world_gravity: Vec3) -> Vec3 {
+        self.gravity_override.unwrap_or(world_gravity) * (*self.gravity_scale)
+    }
+
     // Accessors to match RigidBody API
     pub fn is_static(&self) -> bool {
         self.flags.is_static
@@ -261,17 +358,38 @@ impl<'a> BodyProxyMut<'a> {
     pub fn is_kinematic(&self) -> bool {
         self.flags.is_kinematic
     }
+    pub fn body_type(&self) -> BodyType {
+        self.flags.body_type()
+    }
     pub fn is_awake(&self) -> bool {
         self.flags.is_awake
     }
     pub fn is_enabled(&self) -> bool {
         self.flags.is_enabled
     }
+    pub fn ccd_enabled(&self) -> bool {
+        self.flags.ccd_enabled
+    }
 
     pub fn set_awake(&mut self, awake: bool) {
         self.flags.is_awake = awake;
     }
 
+    pub fn set_mass_properties(&mut self, props: MassProperties) {
+        *self.mass_properties = props;
+        (*self.inverse_mass, *self.inverse_inertia) =
+            inverse_mass_properties(self.mass_properties, self.flags.body_type());
+    }
+
+    /// Switches the body's type, recomputing the inverses the solver sees.
+    pub fn set_body_type(&mut self, body_type: BodyType) {
+        self.flags.is_static = body_type == BodyType::Static;
+        self.flags.is_kinematic = body_type == BodyType::Kinematic;
+        (*self.inverse_mass, *self.inverse_inertia) =
+            inverse_mass_properties(self.mass_properties, body_type);
+        self.flags.is_awake = true;
+    }
+
     pub fn to_rigid_body(&self) -> RigidBody {
         let mut body = RigidBody::new(self.id);
         body.transform = *self.transform;
@@ -286,10 +404,14 @@ impl<'a> BodyProxyMut<'a> {
         body.is_kinematic = self.flags.is_kinematic;
         body.is_awake = self.flags.is_awake;
         body.is_enabled = self.flags.is_enabled;
+        body.ccd_enabled = self.flags.ccd_enabled;
 
         body.gravity_scale = *self.gravity_scale;
+        body.gravity_override = *self.gravity_override;
         body.linear_velocity_damping = *self.linear_damping;
         body.angular_velocity_damping = *self.angular_damping;
+        body.max_linear_velocity = *self.max_linear_velocity;
+        body.max_angular_velocity = *self.max_angular_velocity;
 
         body
     }
@@ -306,9 +428,13 @@ impl<'a> BodyProxyMut<'a> {
         self.flags.is_kinematic = other.is_kinematic;
         self.flags.is_awake = other.is_awake;
         self.flags.is_enabled = other.is_enabled;
+        self.flags.ccd_enabled = other.ccd_enabled;
         *self.gravity_scale = other.gravity_scale;
+        *self.gravity_override = other.gravity_override;
         *self.linear_damping = other.linear_velocity_damping;
         *self.angular_damping = other.angular_velocity_damping;
+        *self.max_linear_velocity = other.max_linear_velocity;
+        *self.max_angular_velocity = other.max_angular_velocity;
     }
 }
 
@@ -329,8 +455,11 @@ pub struct SoAIterMut<'a> {
     ptr_materials: *mut Material,
     ptr_flags: *mut BodyFlags,
     ptr_gravity: *mut f32,
+    ptr_gravity_override: *mut Option<Vec3>,
     ptr_linear_damping: *mut f32,
     ptr_angular_damping: *mut f32,
+    ptr_max_linear_velocity: *mut f32,
+    ptr_max_angular_velocity: *mut f32,
 
     _marker: std::marker::PhantomData<&'a mut BodiesSoA>,
 }
@@ -352,8 +481,11 @@ impl<'a> SoAIterMut<'a> {
             ptr_materials: soa.materials.as_mut_ptr(),
             ptr_flags: soa.flags.as_mut_ptr(),
             ptr_gravity: soa.gravity_scales.as_mut_ptr(),
+            ptr_gravity_override: soa.gravity_overrides.as_mut_ptr(),
             ptr_linear_damping: soa.linear_dampings.as_mut_ptr(),
             ptr_angular_damping: soa.angular_dampings.as_mut_ptr(),
+            ptr_max_linear_velocity: soa.max_linear_velocities.as_mut_ptr(),
+            ptr_max_angular_velocity: soa.max_angular_velocities.as_mut_ptr(),
 
             _marker: std::marker::PhantomData,
         }
@@ -387,8 +519,11 @@ impl<'a> Iterator for SoAIterMut<'a> {
                     material: &mut *self.ptr_materials.add(i),
                     flags: &mut *self.ptr_flags.add(i),
                     gravity_scale: &mut *self.ptr_gravity.add(i),
+                    gravity_override: &mut *self.ptr_gravity_override.add(i),
                     linear_damping: &mut *self.ptr_linear_damping.add(i),
                     angular_damping: &mut *self.ptr_angular_damping.add(i),
+                    max_linear_velocity: &mut *self.ptr_max_linear_velocity.add(i),
+                    max_angular_velocity: &mut *self.ptr_max_angular_velocity.add(i),
                 });
             }
         }
@@ -416,15 +551,13 @@ impl BodiesSoA {
         self.inverse_inertias[index] = body.inverse_inertia;
         self.mass_properties[index] = body.mass_properties;
         self.materials[index] = body.material;
-        self.flags[index] = BodyFlags {
-            is_static: body.is_static,
-            is_kinematic: body.is_kinematic,
-            is_awake: body.is_awake,
-            is_enabled: body.is_enabled,
-        };
+        self.flags[index] = BodyFlags::from(&body);
         self.gravity_scales[index] = body.gravity_scale;
+        self.gravity_overrides[index] = body.gravity_override;
         self.linear_dampings[index] = body.linear_velocity_damping;
         self.angular_dampings[index] = body.angular_velocity_damping;
+        self.max_linear_velocities[index] = body.max_linear_velocity;
+        self.max_angular_velocities[index] = body.max_angular_velocity;
     }
 
     pub fn push(&mut self, id: EntityId, body: RigidBody) {
@@ -436,15 +569,13 @@ impl BodiesSoA {
         self.inverse_inertias.push(body.inverse_inertia);
         self.mass_properties.push(body.mass_properties);
         self.materials.push(body.material);
-        self.flags.push(BodyFlags {
-            is_static: body.is_static,
-            is_kinematic: body.is_kinematic,
-            is_awake: body.is_awake,
-            is_enabled: body.is_enabled,
-        });
+        self.flags.push(BodyFlags::from(&body));
         self.gravity_scales.push(body.gravity_scale);
+        self.gravity_overrides.push(body.gravity_override);
         self.linear_dampings.push(body.linear_velocity_damping);
         self.angular_dampings.push(body.angular_velocity_damping);
+        self.max_linear_velocities.push(body.max_linear_velocity);
+        self.max_angular_velocities.push(body.max_angular_velocity);
     }
 }
 
@@ -475,12 +606,18 @@ impl<'a> BodyRef<'a> {
     pub fn is_kinematic(&self) -> bool {
         self.soa.flags[self.index].is_kinematic
     }
+    pub fn body_type(&self) -> BodyType {
+        self.soa.flags[self.index].body_type()
+    }
     pub fn is_awake(&self) -> bool {
         self.soa.flags[self.index].is_awake
     }
     pub fn is_enabled(&self) -> bool {
         self.soa.flags[self.index].is_enabled
     }
+    pub fn ccd_enabled(&self) -> bool {
+        self.soa.flags[self.index].ccd_enabled
+    }
     pub fn material(&self) -> &Material {
         &self.soa.materials[self.index]
     }
@@ -500,10 +637,14 @@ impl<'a> BodyRef<'a> {
         body.is_kinematic = flags.is_kinematic;
         body.is_awake = flags.is_awake;
         body.is_enabled = flags.is_enabled;
+        body.ccd_enabled = flags.ccd_enabled;
 
         body.gravity_scale = self.soa.gravity_scales[self.index];
+        body.gravity_override = self.soa.gravity_overrides[self.index];
         body.linear_velocity_damping = self.soa.linear_dampings[self.index];
         body.angular_velocity_damping = self.soa.angular_dampings[self.index];
+        body.max_linear_velocity = self.soa.max_linear_velocities[self.index];
+        body.max_angular_velocity = self.soa.max_angular_velocities[self.index];
 
         body
     }
@@ -1,11 +1,15 @@
+use std::collections::HashMap;
+
 use glam::{Mat3, Mat4, Quat, Vec3};
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Common math types re-exported for convenience.
 pub use glam::{Mat2, Vec2};
 
 /// Position, orientation, and non-uniform scale of an entity.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transform {
     pub position: Vec3,
     pub rotation: Quat,
@@ -39,7 +43,8 @@ impl Transform {
 }
 
 /// Linear and angular velocity of a rigid body.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Velocity {
     pub linear: Vec3,
     pub angular: Vec3,
@@ -55,7 +60,8 @@ impl Default for Velocity {
 }
 
 /// Mass and inertia tensor data.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MassProperties {
     pub mass: f32,
     pub inertia: Mat3,
@@ -70,10 +76,180 @@ impl Default for MassProperties {
     }
 }
 
+impl MassProperties {
+    /// Largest ratio between principal moments the solver copes with in `f32`.
+    pub const MAX_INERTIA_RATIO: f32 = 1.0e4;
+
+    /// Lists the problems that would make a dynamic body misbehave in the solver.
+    pub fn validate(&self) -> Vec<MassIssue> {
+        let mut issues = Vec::new();
+        if !self.mass.is_finite() || self.mass <= 0.0 {
+            issues.push(MassIssue::InvalidMass(self.mass));
+        }
+        if !self.inertia.is_finite() {
+            issues.push(MassIssue::NonFiniteInertia);
+            return issues;
+        }
+        let asymmetry = (self.inertia - self.inertia.transpose()).abs();
+        let scale = self
+            .inertia
+            .abs()
+            .to_cols_array()
+            .into_iter()
+            .fold(0.0, f32::max);
+        if asymmetry
+            .to_cols_array()
+            .into_iter()
+            .any(|v| v > scale * 1e-4)
+        {
+            issues.push(MassIssue::AsymmetricInertia);
+        }
+        let (moments, _) = symmetric_eigen(symmetrize(self.inertia));
+        let (min, max) = (moments.min_element(), moments.max_element());
+        if min <= 0.0 {
+            issues.push(MassIssue::SingularInertia);
+        } else if max / min > Self::MAX_INERTIA_RATIO {
+            issues.push(MassIssue::IllConditionedInertia(max / min));
+        }
+        issues
+    }
+
+    /// Returns a copy with the problems from [`Self::validate`] repaired.
+    ///
+    /// Invalid masses become 1, unusable inertia becomes `mass * I`, and principal moments
+    /// are raised to at least `1 / MAX_INERTIA_RATIO` of the largest one.
+    pub fn conditioned(&self) -> Self {
+        let mass = if self.mass.is_finite() && self.mass > 0.0 {
+            self.mass
+        } else {
+            1.0
+        };
+        let fallback = Mat3::from_diagonal(Vec3::splat(mass));
+        if !self.inertia.is_finite() {
+            return Self {
+                mass,
+                inertia: fallback,
+            };
+        }
+        let (moments, axes) = symmetric_eigen(symmetrize(self.inertia));
+        let largest = moments.max_element();
+        if largest <= 0.0 {
+            return Self {
+                mass,
+                inertia: fallback,
+            };
+        }
+        let moments = moments.max(Vec3::splat(largest / Self::MAX_INERTIA_RATIO));
+        Self {
+            mass,
+            inertia: axes * Mat3::from_diagonal(moments) * axes.transpose(),
+        }
+    }
+}
+
+/// Problem found by [`MassProperties::validate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MassIssue {
+    /// Mass is zero, negative, or not finite.
+    InvalidMass(f32),
+    /// The inertia tensor has a NaN or infinite entry.
+    NonFiniteInertia,
+    /// The inertia tensor is not symmetric.
+    AsymmetricInertia,
+    /// A principal moment is zero or negative, so the tensor cannot be inverted.
+    SingularInertia,
+    /// Largest over smallest principal moment exceeds [`MassProperties::MAX_INERTIA_RATIO`].
+    IllConditionedInertia(f32),
+}
+
+impl std::fmt::Display for MassIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMass(mass) => write!(f, "invalid mass {mass}"),
+            Self::NonFiniteInertia => write!(f, "non-finite inertia tensor"),
+            Self::AsymmetricInertia => write!(f, "asymmetric inertia tensor"),
+            Self::SingularInertia => write!(f, "singular inertia tensor"),
+            Self::IllConditionedInertia(ratio) => {
+                write!(f, "inertia tensor condition ratio {ratio:.0}")
+            }
+        }
+    }
+}
+
+/// What [`PhysicsWorld::add_rigidbody`](crate::PhysicsWorld::add_rigidbody) does with dynamic
+/// bodies that fail [`MassProperties::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MassValidation {
+    /// Insert bodies unchecked.
+    Off,
+    /// Log a warning per problem and insert the body as is.
+    Warn,
+    /// Log a warning and insert [`MassProperties::conditioned`] instead.
+    #[default]
+    Correct,
+}
+
+fn symmetrize(m: Mat3) -> Mat3 {
+    (m + m.transpose()) * 0.5
+}
+
+/// Eigenvalues and eigenvectors (as columns) of a symmetric matrix, by cyclic Jacobi sweeps.
+fn symmetric_eigen(m: Mat3) -> (Vec3, Mat3) {
+    let mut a = m.to_cols_array_2d();
+    let mut v = Mat3::IDENTITY.to_cols_array_2d();
+    for _ in 0..16 {
+        let off = a[0][1].powi(2) + a[0][2].powi(2) + a[1][2].powi(2);
+        let scale = a[0][0].abs() + a[1][1].abs() + a[2][2].abs();
+        if off <= (f32::EPSILON * scale).powi(2) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() <= f32::MIN_POSITIVE {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            let rotate = |xp: f32, xq: f32| (c * xp - s * xq, s * xp + c * xq);
+            for col in &mut a {
+                (col[p], col[q]) = rotate(col[p], col[q]);
+            }
+            // Rows p and q, then eigenvector columns p and q (`v` is column-major).
+            for m in [&mut a, &mut v] {
+                let (mut mp, mut mq) = (m[p], m[q]);
+                for (xp, xq) in mp.iter_mut().zip(mq.iter_mut()) {
+                    (*xp, *xq) = rotate(*xp, *xq);
+                }
+                (m[p], m[q]) = (mp, mq);
+            }
+        }
+    }
+    (
+        Vec3::new(a[0][0], a[1][1], a[2][2]),
+        Mat3::from_cols_array_2d(&v),
+    )
+}
+
+/// Identifies a material in a [`MaterialPairTable`]. Materials are untagged by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MaterialId(pub u32);
+
+impl MaterialId {
+    pub const DEFAULT: Self = Self(0);
+    pub const RUBBER: Self = Self(1);
+    pub const STEEL: Self = Self(2);
+    pub const ICE: Self = Self(3);
+}
+
 /// Material coefficients that affect interactions.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(default)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Material {
+    /// Key for pair-specific overrides in the world's [`MaterialPairTable`].
+    pub id: MaterialId,
     pub density: f32,
     pub restitution: f32,
     pub static_friction: f32,
@@ -86,11 +262,16 @@ pub struct Material {
     pub friction_anisotropy: Vec3,
     /// How this material mixes its coefficients with another material.
     pub mixing: MaterialMixing,
+    /// Optional velocity-dependent friction; `None` keeps the static/dynamic Coulomb switch.
+    pub stribeck: Option<StribeckCurve>,
+    /// Friction that follows the body's orientation, for skis, sleds and treads.
+    pub anisotropic_friction: Option<AnisotropicFriction>,
 }
 
 impl Default for Material {
     fn default() -> Self {
         Self {
+            id: MaterialId::DEFAULT,
             density: 1.0,
             restitution: 0.1,
             static_friction: 0.5,
@@ -99,6 +280,8 @@ impl Default for Material {
             torsional_friction: 0.01,
             friction_anisotropy: Vec3::ONE,
             mixing: MaterialMixing::default(),
+            stribeck: None,
+            anisotropic_friction: None,
         }
     }
 }
@@ -106,6 +289,7 @@ impl Default for Material {
 impl Material {
     pub fn rubber() -> Self {
         Self {
+            id: MaterialId::RUBBER,
             density: 1.4,
             restitution: 0.8,
             static_friction: 1.2,
@@ -114,11 +298,14 @@ impl Material {
             torsional_friction: 0.03,
             friction_anisotropy: Vec3::ONE,
             mixing: MaterialMixing::default(),
+            stribeck: None,
+            anisotropic_friction: None,
         }
     }
 
     pub fn steel() -> Self {
         Self {
+            id: MaterialId::STEEL,
             density: 7.8,
             restitution: 0.4,
             static_friction: 0.58,
@@ -127,11 +314,14 @@ impl Material {
             torsional_friction: 0.012,
             friction_anisotropy: Vec3::splat(0.95),
             mixing: MaterialMixing::default(),
+            stribeck: None,
+            anisotropic_friction: None,
         }
     }
 
     pub fn ice() -> Self {
         Self {
+            id: MaterialId::ICE,
             density: 0.9,
             restitution: 0.05,
             static_friction: 0.05,
@@ -140,9 +330,16 @@ impl Material {
             torsional_friction: 0.003,
             friction_anisotropy: Vec3::splat(0.8),
             mixing: MaterialMixing::default(),
+            stribeck: None,
+            anisotropic_friction: None,
         }
     }
 
+    pub fn with_id(mut self, id: MaterialId) -> Self {
+        self.id = id;
+        self
+    }
+
     pub fn combine_with(&self, other: &Self) -> MaterialPairProperties {
         let friction_mode = self.mixing.friction.resolve(other.mixing.friction);
         let restitution_mode = self.mixing.restitution.resolve(other.mixing.restitution);
@@ -161,6 +358,8 @@ impl Material {
             rolling_friction: rolling,
             torsional_friction: torsional,
             restitution,
+            stribeck: StribeckCurve::combine(self.stribeck, other.stribeck),
+            anisotropic_friction: [self.anisotropic_friction, other.anisotropic_friction],
         }
     }
 
@@ -173,11 +372,14 @@ impl Material {
             rolling_friction: 0.5 * (ab.rolling_friction + ba.rolling_friction),
             torsional_friction: 0.5 * (ab.torsional_friction + ba.torsional_friction),
             restitution: 0.5 * (ab.restitution + ba.restitution),
+            stribeck: ab.stribeck,
+            anisotropic_friction: ab.anisotropic_friction,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MaterialMixing {
     pub friction: MixingMode,
     pub restitution: MixingMode,
@@ -204,7 +406,8 @@ impl MaterialMixing {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MixingMode {
     #[default]
     Average,
@@ -235,12 +438,102 @@ impl MixingMode {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct MaterialPairProperties {
     pub static_friction: Vec3,
     pub dynamic_friction: Vec3,
     pub rolling_friction: f32,
     pub torsional_friction: f32,
     pub restitution: f32,
+    pub stribeck: Option<StribeckCurve>,
+    /// Body-space anisotropic friction of the first and second material, in pair order.
+    pub anisotropic_friction: [Option<AnisotropicFriction>; 2],
+}
+
+/// Friction scaled differently along and across a direction fixed to the body.
+///
+/// A ski uses a small `along` and a large `across` so it glides forward but not sideways.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnisotropicFriction {
+    /// Body-space direction, projected onto the contact plane at each contact.
+    pub axis: Vec3,
+    /// Friction multiplier for sliding along `axis`.
+    pub along: f32,
+    /// Friction multiplier for sliding across `axis` within the contact plane.
+    pub across: f32,
+}
+
+impl AnisotropicFriction {
+    pub fn new(axis: Vec3, along: f32, across: f32) -> Self {
+        Self {
+            axis: axis.normalize_or_zero(),
+            along,
+            across,
+        }
+    }
+
+    fn ratio(&self) -> f32 {
+        let (low, high) = (self.along.abs(), self.across.abs());
+        high.max(low) / high.min(low).max(1e-6)
+    }
+}
+
+/// Stribeck friction curve: the coefficient falls from static to dynamic friction as the
+/// slip speed grows, plus an optional viscous term.
+///
+/// `mu(v) = mu_d + (mu_s - mu_d) * exp(-(v / velocity)^exponent) + viscous * v`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StribeckCurve {
+    /// Slip speed (m/s) around which static friction gives way to dynamic friction.
+    pub velocity: f32,
+    /// Shape of the transition; 2.0 gives the common Gaussian curve.
+    pub exponent: f32,
+    /// Extra coefficient per m/s of slip.
+    pub viscous: f32,
+}
+
+impl StribeckCurve {
+    pub fn new(velocity: f32) -> Self {
+        Self {
+            velocity,
+            exponent: 2.0,
+            viscous: 0.0,
+        }
+    }
+
+    pub fn with_exponent(mut self, exponent: f32) -> Self {
+        self.exponent = exponent;
+        self
+    }
+
+    pub fn with_viscous(mut self, viscous: f32) -> Self {
+        self.viscous = viscous;
+        self
+    }
+
+    /// Effective friction coefficient at `slip_speed`.
+    pub fn coefficient(&self, static_friction: f32, dynamic_friction: f32, slip_speed: f32) -> f32 {
+        let slip = slip_speed.abs();
+        let ratio = slip / self.velocity.max(1e-4);
+        let blend = (-ratio.powf(self.exponent.max(1e-3))).exp();
+        dynamic_friction
+            + (static_friction - dynamic_friction) * blend
+            + self.viscous.max(0.0) * slip
+    }
+
+    /// Averages two curves; a single curve applies to the whole pair.
+    pub fn combine(a: Option<Self>, b: Option<Self>) -> Option<Self> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(Self {
+                velocity: 0.5 * (a.velocity + b.velocity),
+                exponent: 0.5 * (a.exponent + b.exponent),
+                viscous: 0.5 * (a.viscous + b.viscous),
+            }),
+            (a, b) => a.or(b),
+        }
+    }
 }
 
 impl Default for MaterialPairProperties {
@@ -253,6 +546,141 @@ impl MaterialPairProperties {
     pub fn from_materials(a: &Material, b: &Material) -> Self {
         Material::combine_pair(a, b)
     }
+
+    /// World-space tangent axes and their friction scales for a contact with `normal`,
+    /// taken from whichever material is more anisotropic.
+    ///
+    /// Returns `None` for isotropic pairs or when the axis is parallel to the normal.
+    pub fn anisotropic_axes(
+        &self,
+        rotation_a: Quat,
+        rotation_b: Quat,
+        normal: Vec3,
+    ) -> Option<[(Vec3, f32); 2]> {
+        let (friction, rotation) = match self.anisotropic_friction {
+            [Some(a), Some(b)] if b.ratio() > a.ratio() => (b, rotation_b),
+            [Some(a), _] => (a, rotation_a),
+            [None, Some(b)] => (b, rotation_b),
+            [None, None] => return None,
+        };
+        let axis = rotation * friction.axis;
+        let along = (axis - normal * axis.dot(normal)).try_normalize()?;
+        let across = normal.cross(along).normalize_or_zero();
+        Some([(along, friction.along), (across, friction.across)])
+    }
+}
+
+/// Replacement coefficients for one material pair. `None` keeps the mixed value.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MaterialPairOverride {
+    pub static_friction: Option<f32>,
+    pub dynamic_friction: Option<f32>,
+    pub restitution: Option<f32>,
+    pub rolling_friction: Option<f32>,
+    pub torsional_friction: Option<f32>,
+}
+
+impl MaterialPairOverride {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_friction(mut self, static_friction: f32, dynamic_friction: f32) -> Self {
+        self.static_friction = Some(static_friction);
+        self.dynamic_friction = Some(dynamic_friction);
+        self
+    }
+
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = Some(restitution);
+        self
+    }
+
+    pub fn with_rolling_friction(mut self, rolling_friction: f32) -> Self {
+        self.rolling_friction = Some(rolling_friction);
+        self
+    }
+
+    pub fn with_torsional_friction(mut self, torsional_friction: f32) -> Self {
+        self.torsional_friction = Some(torsional_friction);
+        self
+    }
+
+    /// Friction overrides keep the pair's averaged anisotropy.
+    fn apply(&self, pair: &mut MaterialPairProperties, anisotropy: Vec3) {
+        if let Some(value) = self.static_friction {
+            pair.static_friction = anisotropy * value;
+        }
+        if let Some(value) = self.dynamic_friction {
+            pair.dynamic_friction = anisotropy * value;
+        }
+        if let Some(value) = self.restitution {
+            pair.restitution = value;
+        }
+        if let Some(value) = self.rolling_friction {
+            pair.rolling_friction = value;
+        }
+        if let Some(value) = self.torsional_friction {
+            pair.torsional_friction = value;
+        }
+    }
+}
+
+/// Overrides keyed by unordered [`MaterialId`] pairs, applied after two materials mix.
+#[derive(Debug, Clone, Default)]
+pub struct MaterialPairTable {
+    overrides: HashMap<(MaterialId, MaterialId), MaterialPairOverride>,
+}
+
+impl MaterialPairTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(a: MaterialId, b: MaterialId) -> (MaterialId, MaterialId) {
+        (a.min(b), a.max(b))
+    }
+
+    /// Registers an override for `a` touching `b`, in either order. Returns the replaced one.
+    pub fn insert(
+        &mut self,
+        a: MaterialId,
+        b: MaterialId,
+        properties: MaterialPairOverride,
+    ) -> Option<MaterialPairOverride> {
+        self.overrides.insert(Self::key(a, b), properties)
+    }
+
+    pub fn remove(&mut self, a: MaterialId, b: MaterialId) -> Option<MaterialPairOverride> {
+        self.overrides.remove(&Self::key(a, b))
+    }
+
+    pub fn get(&self, a: MaterialId, b: MaterialId) -> Option<&MaterialPairOverride> {
+        self.overrides.get(&Self::key(a, b))
+    }
+
+    pub fn len(&self) -> usize {
+        self.overrides.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.overrides.clear();
+    }
+
+    /// Mixes `a` and `b`, then applies any override registered for their ids.
+    pub fn combine(&self, a: &Material, b: &Material) -> MaterialPairProperties {
+        let mut pair = Material::combine_pair(a, b);
+        if let Some(properties) = self.get(a.id, b.id) {
+            let anisotropy = (a.friction_anisotropy + b.friction_anisotropy) * 0.5;
+            properties.apply(&mut pair, anisotropy);
+        }
+        pair
+    }
 }
 
 /// Helper methods for inertia calculations.
@@ -284,6 +712,43 @@ impl InertiaTensorExt for Mat3 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn conditioning_repairs_degenerate_inertia() {
+        let rotation = Mat3::from_quat(Quat::from_rotation_z(0.7));
+        let thin_rod = MassProperties {
+            mass: 2.0,
+            inertia: rotation
+                * Mat3::from_diagonal(Vec3::new(1.0, 1.0, 1e-7))
+                * rotation.transpose(),
+        };
+        assert!(matches!(
+            thin_rod.validate().as_slice(),
+            [MassIssue::IllConditionedInertia(_)]
+        ));
+        let fixed = thin_rod.conditioned();
+        assert!(fixed.validate().is_empty());
+        // The well-conditioned axes keep their moments.
+        let x_axis = rotation * Vec3::X;
+        assert!((x_axis.dot(fixed.inertia * x_axis) - 1.0).abs() < 1e-4);
+
+        let broken = MassProperties {
+            mass: 0.0,
+            inertia: Mat3::from_cols(Vec3::X, Vec3::new(0.5, 1.0, 0.0), Vec3::Z),
+        };
+        let issues = broken.validate();
+        assert!(issues.contains(&MassIssue::InvalidMass(0.0)));
+        assert!(issues.contains(&MassIssue::AsymmetricInertia));
+        let fixed = broken.conditioned();
+        assert_eq!(fixed.mass, 1.0);
+        assert!(fixed.validate().is_empty());
+
+        let nan = MassProperties {
+            mass: f32::NAN,
+            inertia: Mat3::ZERO,
+        };
+        assert!(nan.conditioned().validate().is_empty());
+    }
+
     #[test]
     fn mixing_modes_combine_expected_values() {
         let mode = MixingMode::Average;
@@ -321,5 +786,59 @@ mod tests {
         assert!(pair.static_friction.x > pair.static_friction.y);
         assert!(pair.dynamic_friction.z > pair.dynamic_friction.y);
         assert!(pair.restitution > 0.0);
+        assert!(pair.stribeck.is_none());
+    }
+
+    #[test]
+    fn stribeck_curve_falls_from_static_to_dynamic() {
+        let curve = StribeckCurve::new(0.5);
+        assert!((curve.coefficient(0.8, 0.4, 0.0) - 0.8).abs() < 1e-5);
+        assert!((curve.coefficient(0.8, 0.4, 5.0) - 0.4).abs() < 1e-5);
+
+        let samples: Vec<f32> = (0..10)
+            .map(|i| curve.coefficient(0.8, 0.4, i as f32 * 0.1))
+            .collect();
+        assert!(samples.windows(2).all(|w| w[1] <= w[0]));
+
+        let viscous = curve.with_viscous(0.1);
+        assert!((viscous.coefficient(0.8, 0.4, 5.0) - 0.9).abs() < 1e-5);
+
+        let tire = Material {
+            stribeck: Some(curve),
+            ..Default::default()
+        };
+        let pair = Material::combine_pair(&tire, &Material::default());
+        assert_eq!(pair.stribeck, Some(curve));
+    }
+
+    #[test]
+    fn material_pair_table_overrides_only_matching_pairs() {
+        let mut table = MaterialPairTable::new();
+        table.insert(
+            MaterialId::RUBBER,
+            MaterialId::ICE,
+            MaterialPairOverride::new()
+                .with_friction(0.15, 0.1)
+                .with_restitution(0.0),
+        );
+
+        let rubber = Material::rubber();
+        let ice = Material::ice();
+        let pair = table.combine(&ice, &rubber);
+        let anisotropy = (rubber.friction_anisotropy + ice.friction_anisotropy) * 0.5;
+        assert!((pair.static_friction - anisotropy * 0.15).length() < 1e-5);
+        assert!((pair.dynamic_friction - anisotropy * 0.1).length() < 1e-5);
+        assert_eq!(pair.restitution, 0.0);
+        let mixed = Material::combine_pair(&rubber, &ice);
+        assert_eq!(pair.rolling_friction, mixed.rolling_friction);
+
+        let steel = Material::steel();
+        let untouched = table.combine(&rubber, &steel);
+        let expected = Material::combine_pair(&rubber, &steel);
+        assert_eq!(untouched.static_friction, expected.static_friction);
+        assert_eq!(untouched.restitution, expected.restitution);
+
+        assert!(table.remove(MaterialId::ICE, MaterialId::RUBBER).is_some());
+        assert!(table.is_empty());
     }
 }
@@ -6,6 +6,14 @@ pub struct ABASolver;
 impl ABASolver {
     /// Solves for generalized accelerations (ddq) using Featherstone's ABA.
     pub fn solve(mb: &mut Multibody, gravity: glam::Vec3) {
+        let tau = mb.tau.clone();
+        Self::solve_with_forces(mb, gravity, &tau);
+    }
+
+    /// [`Self::solve`] with generalized forces `tau` in place of `mb.tau`.
+    ///
+    /// Also records each 1-DOF joint's articulated inertia in `mb.joint_inertia`.
+    pub fn solve_with_forces(mb: &mut Multibody, gravity: glam::Vec3, tau: &[f32]) {
         let n = mb.links.len();
         if n == 0 {
             return;
@@ -72,7 +80,8 @@ impl ABASolver {
                 d_inv[i] = di;
 
                 let q_offset = link.q_offset;
-                force_u[i] = mb.tau[q_offset] - s.dot(&p_a[i]);
+                mb.joint_inertia[q_offset] = d;
+                force_u[i] = tau[q_offset] - s.dot(&p_a[i]);
 
                 if let Some(p_idx) = link.parent_idx {
                     // Propagate to parent
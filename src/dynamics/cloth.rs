@@ -0,0 +1,389 @@
+//! Position-based cloth: triangle meshes held together by distance and bending constraints.
+//!
+//! Each world step is split into substeps that predict vertex positions, project every
+//! constraint once (XPBD), and then resolve contacts. Vertices collide with the world's
+//! colliders and push dynamic bodies back in proportion to [`ClothParams::coupling`], so a
+//! flag tugs on its pole. Collision is tested per vertex, so colliders thinner than the
+//! cloth's edges can slip between vertices. Self-collision keeps vertices apart, using
+//! candidate pairs found once per step with a spatial hash.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::pbd::{apply_reactions, gather_obstacles, DistanceConstraint, Vertices};
+use crate::{
+    core::{collider::Collider, mesh::TriangleMesh, rigidbody::BodyType, soa::BodiesSoA},
+    utils::{
+        allocator::{Arena, EntityId},
+        spatial_hash::SpatialHash,
+    },
+};
+
+/// Material and solver settings shared by every vertex of a [`Cloth`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClothParams {
+    /// Mass per square metre of cloth (kg/m²).
+    pub area_density: f32,
+    /// XPBD compliance of the edges (m/N); zero makes them inextensible.
+    pub stretch_compliance: f32,
+    /// XPBD compliance of the links across each shared edge; larger values fold more easily.
+    pub bend_compliance: f32,
+    /// Collision radius of each vertex (m).
+    pub thickness: f32,
+    /// Coulomb friction coefficient against colliders.
+    pub friction: f32,
+    /// Linear velocity damping per second.
+    pub damping: f32,
+    pub substeps: u32,
+    /// Whether vertices push apart other vertices of the same cloth.
+    pub self_collision: bool,
+    /// Share of each collision and attachment correction handed back to dynamic bodies as
+    /// a reaction impulse, from 0 (one-way) to 1 (momentum-conserving).
+    pub coupling: f32,
+}
+
+impl Default for ClothParams {
+    fn default() -> Self {
+        Self {
+            area_density: 0.2,
+            stretch_compliance: 0.0,
+            bend_compliance: 0.1,
+            thickness: 0.01,
+            friction: 0.3,
+            damping: 0.1,
+            substeps: 10,
+            self_collision: true,
+            coupling: 1.0,
+        }
+    }
+}
+
+/// A cloth vertex held to a point that moves with a rigid body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ClothAttachment {
+    pub vertex: usize,
+    pub body: EntityId,
+    /// Attachment point in the body's local frame.
+    pub local_point: Vec3,
+}
+
+/// Triangle-mesh cloth registered with
+/// [`PhysicsWorld::add_cloth`](crate::world::PhysicsWorld::add_cloth).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cloth {
+    pub params: ClothParams,
+    vertices: Vertices,
+    /// Positions the cloth was built with; vertices closer than this at rest are never
+    /// pushed further apart by self-collision.
+    rest_positions: Vec<Vec3>,
+    triangles: Vec<[u32; 3]>,
+    stretch: Vec<DistanceConstraint>,
+    bend: Vec<DistanceConstraint>,
+    attachments: Vec<ClothAttachment>,
+}
+
+impl Cloth {
+    /// Builds a cloth from a triangle soup. Each edge becomes a distance constraint and
+    /// each pair of triangles sharing an edge links its two far vertices against bending.
+    /// Triangles with out-of-range or repeated indices are dropped.
+    pub fn new(vertices: Vec<Vec3>, triangles: Vec<[u32; 3]>, params: ClothParams) -> Self {
+        let count = vertices.len();
+        let triangles: Vec<[u32; 3]> = triangles
+            .into_iter()
+            .filter(|&[a, b, c]| {
+                [a, b, c].iter().all(|&index| (index as usize) < count)
+                    && a != b
+                    && b != c
+                    && c != a
+            })
+            .collect();
+
+        let mut masses = vec![0.0; count];
+        let mut stretch = Vec::new();
+        let mut bend = Vec::new();
+        // Vertex opposite each edge in the first triangle that used it.
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for triangle in &triangles {
+            let [a, b, c] = triangle.map(|index| index as usize);
+            let area = 0.5
+                * (vertices[b] - vertices[a])
+                    .cross(vertices[c] - vertices[a])
+                    .length();
+            for vertex in [a, b, c] {
+                masses[vertex] += params.area_density * area / 3.0;
+            }
+            for (p, q, opposite) in [(a, b, c), (b, c, a), (c, a, b)] {
+                match edges.get(&(p.min(q), p.max(q))) {
+                    None => {
+                        edges.insert((p.min(q), p.max(q)), opposite);
+                        stretch.push(DistanceConstraint::new(&vertices, p, q));
+                    }
+                    Some(&other) if other != opposite => {
+                        bend.push(DistanceConstraint::new(&vertices, other, opposite));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        Self {
+            params,
+            rest_positions: vertices.clone(),
+            vertices: Vertices::new(vertices, masses),
+            triangles,
+            stretch,
+            bend,
+            attachments: Vec::new(),
+        }
+    }
+
+    pub fn from_mesh(mesh: &TriangleMesh, params: ClothParams) -> Self {
+        Self::new(mesh.vertices.clone(), mesh.indices.clone(), params)
+    }
+
+    /// A flat sheet of `columns` × `rows` quads with corners at `origin`, `origin + u`,
+    /// `origin + v` and `origin + u + v`. Vertex `(i, j)` has index `j * (columns + 1) + i`.
+    pub fn grid(
+        origin: Vec3,
+        u: Vec3,
+        v: Vec3,
+        columns: usize,
+        rows: usize,
+        params: ClothParams,
+    ) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let mut vertices = Vec::with_capacity((columns + 1) * (rows + 1));
+        for j in 0..=rows {
+            for i in 0..=columns {
+                vertices
+                    .push(origin + u * (i as f32 / columns as f32) + v * (j as f32 / rows as f32));
+            }
+        }
+        let index = |i: usize, j: usize| (j * (columns + 1) + i) as u32;
+        let mut triangles = Vec::with_capacity(columns * rows * 2);
+        for j in 0..rows {
+            for i in 0..columns {
+                let (a, b, c, d) = (
+                    index(i, j),
+                    index(i + 1, j),
+                    index(i + 1, j + 1),
+                    index(i, j + 1),
+                );
+                // Alternate the diagonals so the sheet drapes symmetrically.
+                if (i + j) % 2 == 0 {
+                    triangles.push([a, b, c]);
+                    triangles.push([a, c, d]);
+                } else {
+                    triangles.push([a, b, d]);
+                    triangles.push([b, c, d]);
+                }
+            }
+        }
+        Self::new(vertices, triangles, params)
+    }
+
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.len() == 0
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.vertices.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.vertices.velocities
+    }
+
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    /// Mass lumped at each vertex from its triangles' areas.
+    pub fn masses(&self) -> &[f32] {
+        &self.vertices.masses
+    }
+
+    /// Total mass of the cloth's vertices.
+    pub fn mass(&self) -> f32 {
+        self.vertices.masses.iter().sum()
+    }
+
+    /// Fixes a vertex where it is; it still pulls on its neighbours.
+    pub fn pin(&mut self, vertex: usize) -> bool {
+        self.vertices.pin(vertex)
+    }
+
+    pub fn unpin(&mut self, vertex: usize) -> bool {
+        self.vertices.unpin(vertex)
+    }
+
+    pub fn is_pinned(&self, vertex: usize) -> bool {
+        self.vertices.is_pinned(vertex)
+    }
+
+    /// Teleports a vertex, typically a pinned one being dragged around.
+    pub fn set_position(&mut self, vertex: usize, position: Vec3) -> bool {
+        self.vertices.set_position(vertex, position)
+    }
+
+    /// Holds `vertex` to `local_point` on `body`. When the body is dynamic it feels the
+    /// cloth's pull, scaled by [`ClothParams::coupling`].
+    pub fn attach(&mut self, vertex: usize, body: EntityId, local_point: Vec3) -> bool {
+        if vertex >= self.len() {
+            return false;
+        }
+        self.attachments.push(ClothAttachment {
+            vertex,
+            body,
+            local_point,
+        });
+        true
+    }
+
+    /// Removes every attachment of `vertex`.
+    pub fn detach(&mut self, vertex: usize) -> bool {
+        let before = self.attachments.len();
+        self.attachments
+            .retain(|attachment| attachment.vertex != vertex);
+        self.attachments.len() != before
+    }
+
+    pub fn attachments(&self) -> &[ClothAttachment] {
+        &self.attachments
+    }
+
+    /// Advances the cloth by `dt`, then applies the accumulated reaction impulses to the
+    /// dynamic bodies it touched or hangs from.
+    pub fn step(
+        &mut self,
+        dt: f32,
+        gravity: Vec3,
+        colliders: &Arena<Collider>,
+        bodies: &mut BodiesSoA,
+    ) {
+        if self.is_empty() || dt <= 0.0 {
+            return;
+        }
+        self.attachments
+            .retain(|attachment| bodies.get(attachment.body).is_some());
+
+        let obstacles = gather_obstacles(colliders, bodies);
+        // End-of-step position, velocity and dynamic flag of each attachment point.
+        let anchors: Vec<(Vec3, Vec3, bool)> = self
+            .attachments
+            .iter()
+            .filter_map(|attachment| {
+                let body = bodies.get(attachment.body)?;
+                let transform = body.transform();
+                let offset = transform.rotation * (attachment.local_point * transform.scale);
+                let velocity = body.velocity().linear + body.velocity().angular.cross(offset);
+                Some((
+                    transform.position + offset,
+                    velocity,
+                    body.body_type() == BodyType::Dynamic,
+                ))
+            })
+            .collect();
+
+        let substeps = self.params.substeps.max(1);
+        let h = dt / substeps as f32;
+        let pairs = self.self_collision_pairs(dt, gravity);
+        let mut reactions = Vec::new();
+        for substep in 1..=substeps {
+            let vertices = &mut self.vertices;
+            vertices.predict(h, gravity, self.params.damping);
+            vertices.solve_distances(&self.stretch, self.params.stretch_compliance / (h * h));
+            vertices.solve_distances(&self.bend, self.params.bend_compliance / (h * h));
+            // The bodies have already moved this step, so walk each anchor back along its
+            // velocity to where it was partway through.
+            let remaining = dt - h * substep as f32;
+            for (attachment, &(end, velocity, dynamic)) in self.attachments.iter().zip(&anchors) {
+                let vertex = attachment.vertex;
+                if vertices.inverse_masses[vertex] == 0.0 {
+                    continue;
+                }
+                let anchor = end - velocity * remaining;
+                let correction = anchor - vertices.positions[vertex];
+                vertices.positions[vertex] = anchor;
+                if dynamic {
+                    reactions.push((
+                        attachment.body,
+                        -correction * vertices.masses[vertex] / h,
+                        anchor,
+                    ));
+                }
+            }
+            self.collide_self(&pairs);
+            self.vertices.collide(
+                &obstacles,
+                self.params.thickness.max(0.0),
+                self.params.friction,
+                h,
+                &mut reactions,
+            );
+            self.vertices.update_velocities(h);
+        }
+        apply_reactions(bodies, reactions, self.params.coupling);
+    }
+
+    /// Vertex pairs that could touch during a step of `dt`, with the distance each pair
+    /// is kept apart: two thicknesses, or the rest distance if that is smaller.
+    fn self_collision_pairs(&self, dt: f32, gravity: Vec3) -> Vec<(usize, usize, f32)> {
+        let reach = 2.0 * self.params.thickness;
+        if !self.params.self_collision || reach <= 0.0 {
+            return Vec::new();
+        }
+        let vertices = &self.vertices;
+        let max_speed = vertices
+            .velocities
+            .iter()
+            .fold(0.0_f32, |max, velocity| max.max(velocity.length()))
+            + gravity.length() * dt;
+        // Both vertices of a pair may close in on each other over the whole step.
+        let search = reach + 2.0 * max_speed * dt;
+        let hash = SpatialHash::build(search, vertices.positions.iter().copied());
+        let mut pairs = Vec::new();
+        for a in 0..vertices.len() {
+            hash.for_each_near(vertices.positions[a], |b| {
+                if b <= a
+                    || vertices.inverse_masses[a] + vertices.inverse_masses[b] == 0.0
+                    || vertices.positions[a].distance_squared(vertices.positions[b])
+                        >= search * search
+                {
+                    return;
+                }
+                let target = reach.min(self.rest_positions[a].distance(self.rest_positions[b]));
+                if target > 0.0 {
+                    pairs.push((a, b, target));
+                }
+            });
+        }
+        pairs
+    }
+
+    fn collide_self(&mut self, pairs: &[(usize, usize, f32)]) {
+        let vertices = &mut self.vertices;
+        for &(a, b, target) in pairs {
+            let offset = vertices.positions[b] - vertices.positions[a];
+            let distance_squared = offset.length_squared();
+            if distance_squared >= target * target || distance_squared < f32::EPSILON {
+                continue;
+            }
+            let weight = vertices.inverse_masses[a] + vertices.inverse_masses[b];
+            let distance = distance_squared.sqrt();
+            let push = offset / distance * (target - distance) / weight;
+            vertices.positions[a] -= push * vertices.inverse_masses[a];
+            vertices.positions[b] += push * vertices.inverse_masses[b];
+        }
+    }
+}
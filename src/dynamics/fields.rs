@@ -0,0 +1,203 @@
+//! Region-based force fields applied by the world to every body inside them.
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::mesh::Aabb;
+
+/// World-space region a [`ForceField`] acts in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FieldVolume {
+    Sphere {
+        center: Vec3,
+        radius: f32,
+    },
+    /// Axis-aligned box.
+    Box {
+        center: Vec3,
+        half_extents: Vec3,
+    },
+}
+
+impl FieldVolume {
+    pub fn center(&self) -> Vec3 {
+        match *self {
+            Self::Sphere { center, .. } | Self::Box { center, .. } => center,
+        }
+    }
+
+    /// Whether `bounds` reaches into the volume.
+    pub fn overlaps(&self, bounds: &Aabb) -> bool {
+        match *self {
+            Self::Sphere { center, radius } => {
+                let closest = center.clamp(bounds.min, bounds.max);
+                closest.distance_squared(center) <= radius * radius
+            }
+            Self::Box {
+                center,
+                half_extents,
+            } => bounds.overlaps(&Aabb::new(center - half_extents, center + half_extents)),
+        }
+    }
+}
+
+/// What a [`ForceField`] does to the bodies inside it.
+///
+/// Attractors and vortices accelerate every body alike regardless of mass; wind drags
+/// bodies toward the air speed, so heavy bodies respond more slowly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FieldKind {
+    /// Accelerates bodies toward the volume's centre; a negative strength repels them.
+    Attractor { strength: f32 },
+    /// Swirls bodies around `axis` through the volume's centre following the right-hand
+    /// rule, while `pull` draws them in toward the axis.
+    Vortex {
+        axis: Vec3,
+        strength: f32,
+        pull: f32,
+    },
+    /// Drags bodies toward `velocity`, perturbed by smooth noise of amplitude
+    /// `turbulence` that varies over space and time at `frequency`.
+    Wind {
+        velocity: Vec3,
+        drag: f32,
+        turbulence: f32,
+        frequency: f32,
+    },
+}
+
+/// A force field volume registered with
+/// [`PhysicsWorld::add_force_field`](crate::world::PhysicsWorld::add_force_field).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ForceField {
+    pub volume: FieldVolume,
+    pub kind: FieldKind,
+    pub enabled: bool,
+}
+
+impl ForceField {
+    pub fn new(volume: FieldVolume, kind: FieldKind) -> Self {
+        Self {
+            volume,
+            kind,
+            enabled: true,
+        }
+    }
+
+    pub fn attractor(volume: FieldVolume, strength: f32) -> Self {
+        Self::new(volume, FieldKind::Attractor { strength })
+    }
+
+    pub fn vortex(volume: FieldVolume, axis: Vec3, strength: f32, pull: f32) -> Self {
+        Self::new(
+            volume,
+            FieldKind::Vortex {
+                axis: axis.normalize_or_zero(),
+                strength,
+                pull,
+            },
+        )
+    }
+
+    pub fn wind(volume: FieldVolume, velocity: Vec3, drag: f32) -> Self {
+        Self::new(
+            volume,
+            FieldKind::Wind {
+                velocity,
+                drag,
+                turbulence: 0.0,
+                frequency: 1.0,
+            },
+        )
+    }
+
+    /// Adds gusts to a wind field; other kinds are unchanged.
+    pub fn turbulence(mut self, amplitude: f32, frequency: f32) -> Self {
+        if let FieldKind::Wind {
+            turbulence,
+            frequency: wind_frequency,
+            ..
+        } = &mut self.kind
+        {
+            *turbulence = amplitude;
+            *wind_frequency = frequency;
+        }
+        self
+    }
+
+    /// Force on a body of `mass` at `position` moving at `velocity`, `time` seconds into
+    /// the simulation.
+    pub fn force(&self, position: Vec3, velocity: Vec3, mass: f32, time: f32) -> Vec3 {
+        let center = self.volume.center();
+        match self.kind {
+            FieldKind::Attractor { strength } => {
+                (center - position).normalize_or_zero() * strength * mass
+            }
+            FieldKind::Vortex {
+                axis,
+                strength,
+                pull,
+            } => {
+                let offset = position - center;
+                let radial = offset - axis * offset.dot(axis);
+                let inward = -radial.normalize_or_zero();
+                let tangent = axis.cross(-inward);
+                (tangent * strength + inward * pull) * mass
+            }
+            FieldKind::Wind {
+                velocity: wind,
+                drag,
+                turbulence,
+                frequency,
+            } => {
+                let gust = if turbulence > 0.0 {
+                    let sample = position * frequency + Vec3::splat(time * frequency);
+                    Vec3::new(
+                        value_noise(sample),
+                        value_noise(sample + Vec3::new(17.3, 0.0, 0.0)),
+                        value_noise(sample + Vec3::new(0.0, 31.7, 0.0)),
+                    ) * turbulence
+                } else {
+                    Vec3::ZERO
+                };
+                (wind + gust - velocity) * drag
+            }
+        }
+    }
+}
+
+/// Smooth, deterministic lattice noise in `[-1, 1]`.
+fn value_noise(point: Vec3) -> f32 {
+    let cell = point.floor();
+    let t = point - cell;
+    let t = t * t * (Vec3::splat(3.0) - 2.0 * t);
+    let (x, y, z) = (cell.x as i32, cell.y as i32, cell.z as i32);
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let edge = |dy: i32, dz: i32| {
+        lerp(
+            lattice_value(x, y + dy, z + dz),
+            lattice_value(x + 1, y + dy, z + dz),
+            t.x,
+        )
+    };
+    lerp(
+        lerp(edge(0, 0), edge(1, 0), t.y),
+        lerp(edge(0, 1), edge(1, 1), t.y),
+        t.z,
+    )
+}
+
+fn lattice_value(x: i32, y: i32, z: i32) -> f32 {
+    let mut hash = (x as u32).wrapping_mul(73_856_093)
+        ^ (y as u32).wrapping_mul(19_349_663)
+        ^ (z as u32).wrapping_mul(83_492_791);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0x5bd1_e995);
+    hash ^= hash >> 15;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}
@@ -0,0 +1,557 @@
+//! Weakly compressible SPH fluid coupled one way with the world's colliders.
+//!
+//! Solid colliders are sampled with boundary particles that add to the fluid's density
+//! and pressure, so fluid piles up against walls instead of leaking through them; the
+//! bodies themselves are never pushed by the fluid. Boundary particles are generated for
+//! spheres, boxes, and the other primitives with a signed distance; planes, hulls,
+//! meshes, heightfields and compounds only push penetrating fluid particles back out.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{narrowphase::NarrowPhase, shapes::ShapeUtil},
+    core::{
+        collider::{Collider, ColliderShape},
+        rigidbody::RigidBody,
+        soa::BodiesSoA,
+    },
+    utils::{
+        allocator::{Arena, EntityId},
+        spatial_hash::SpatialHash,
+    },
+};
+
+/// Material and solver settings of a [`Fluid`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FluidParams {
+    /// Spacing between particles at rest (m); the smoothing radius is twice this.
+    pub particle_spacing: f32,
+    pub rest_density: f32,
+    /// Squared speed of sound (m²/s²); pressure is `stiffness * (ρ - ρ₀)`.
+    pub stiffness: f32,
+    /// Dynamic viscosity (Pa·s). The default is far thicker than water, which keeps
+    /// coarse particle fluids from jittering.
+    pub viscosity: f32,
+    /// Upper bound on the CFL-limited substeps per world step.
+    pub max_substeps: u32,
+    /// Emitters stop once the fluid holds this many particles.
+    pub max_particles: usize,
+}
+
+impl Default for FluidParams {
+    fn default() -> Self {
+        Self {
+            particle_spacing: 0.05,
+            rest_density: 1000.0,
+            stiffness: 100.0,
+            viscosity: 10.0,
+            max_substeps: 16,
+            max_particles: 20_000,
+        }
+    }
+}
+
+/// Nozzle that spawns fluid particles, registered with
+/// [`PhysicsWorld::add_fluid_emitter`](crate::world::PhysicsWorld::add_fluid_emitter).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FluidEmitter {
+    pub position: Vec3,
+    /// Velocity of the emitted particles; they leave a disc facing this direction.
+    pub velocity: Vec3,
+    /// Radius of the nozzle disc.
+    pub radius: f32,
+    /// Particles emitted per second.
+    pub rate: f32,
+    pub enabled: bool,
+    /// Particles still owed from fractional emission.
+    pending: f32,
+    emitted: u32,
+}
+
+impl FluidEmitter {
+    pub fn new(position: Vec3, velocity: Vec3, radius: f32, rate: f32) -> Self {
+        Self {
+            position,
+            velocity,
+            radius: radius.max(0.0),
+            rate: rate.max(0.0),
+            enabled: true,
+            pending: 0.0,
+            emitted: 0,
+        }
+    }
+
+    /// Total particles this emitter has spawned.
+    pub fn emitted(&self) -> u32 {
+        self.emitted
+    }
+
+    /// Next spawn point on the nozzle disc, spread out along a golden-angle spiral.
+    fn next_point(&mut self) -> Vec3 {
+        const GOLDEN_ANGLE: f32 = 2.399_963;
+        let normal = self.velocity.normalize_or(Vec3::NEG_Y);
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+        let k = self.emitted as f32;
+        let radius = self.radius * (k * 0.618_034).fract().sqrt();
+        let angle = k * GOLDEN_ANGLE;
+        self.emitted += 1;
+        self.position + (tangent * angle.cos() + bitangent * angle.sin()) * radius
+    }
+}
+
+/// SPH fluid stepped by the world after its rigid bodies.
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Fluid {
+    pub params: FluidParams,
+    pub emitters: Arena<FluidEmitter>,
+    positions: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    densities: Vec<f32>,
+    pressures: Vec<f32>,
+    accelerations: Vec<Vec3>,
+    /// Fluid and boundary particles within the kernel support of each particle.
+    neighbors: Vec<Vec<usize>>,
+    boundary_neighbors: Vec<Vec<usize>>,
+    /// Boundary particles of the current step with the velocity of their surface.
+    boundary: Vec<(Vec3, Vec3)>,
+    /// Local-space boundary samples per collider, keyed by its bounding radius.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    boundary_samples: HashMap<EntityId, (f32, Vec<Vec3>)>,
+}
+
+impl Fluid {
+    pub fn new(params: FluidParams) -> Self {
+        Self {
+            params,
+            ..Self::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.velocities
+    }
+
+    /// Densities from the last substep, in the order of [`Self::positions`].
+    pub fn densities(&self) -> &[f32] {
+        &self.densities
+    }
+
+    /// Adds a particle directly, bypassing the emitters and the particle limit.
+    pub fn add_particle(&mut self, position: Vec3, velocity: Vec3) {
+        self.positions.push(position);
+        self.velocities.push(velocity);
+        self.densities.push(self.params.rest_density);
+        self.pressures.push(0.0);
+        self.accelerations.push(Vec3::ZERO);
+    }
+
+    /// Removes the particles `keep` rejects, e.g. those that fell out of the level.
+    pub fn retain(&mut self, mut keep: impl FnMut(Vec3) -> bool) {
+        let mut index = 0;
+        while index < self.positions.len() {
+            if keep(self.positions[index]) {
+                index += 1;
+                continue;
+            }
+            self.positions.swap_remove(index);
+            self.velocities.swap_remove(index);
+            self.densities.swap_remove(index);
+            self.pressures.swap_remove(index);
+            self.accelerations.swap_remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.retain(|_| false);
+    }
+
+    /// Emits new particles, then advances the fluid by `dt` in CFL-limited substeps.
+    pub fn step(
+        &mut self,
+        dt: f32,
+        gravity: Vec3,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) {
+        self.emit(dt);
+        if self.positions.is_empty() {
+            return;
+        }
+
+        let spacing = self.params.particle_spacing.max(1e-4);
+        let h = 2.0 * spacing;
+        let kernels = Kernels::new(h);
+        let mass = self.particle_mass(&kernels);
+        self.sample_boundary(colliders, bodies, spacing, h);
+        let boundary_grid = SpatialHash::build(h, self.boundary.iter().map(|(p, _)| *p));
+
+        let max_speed = self
+            .velocities
+            .iter()
+            .map(|v| v.length())
+            .fold(0.0, f32::max);
+        let signal_speed = self.params.stiffness.max(0.0).sqrt().max(max_speed);
+        let stable_dt = 0.4 * h / signal_speed.max(1e-3);
+        let substeps = ((dt / stable_dt).ceil() as u32).clamp(1, self.params.max_substeps.max(1));
+        let sub_dt = dt / substeps as f32;
+
+        for _ in 0..substeps {
+            let grid = SpatialHash::build(h, self.positions.iter().copied());
+            self.find_neighbors(&grid, &boundary_grid, h);
+            self.compute_pressures(&kernels, mass);
+            self.compute_accelerations(&kernels, mass, gravity);
+            for ((position, velocity), acceleration) in self
+                .positions
+                .iter_mut()
+                .zip(&mut self.velocities)
+                .zip(&self.accelerations)
+            {
+                *velocity += *acceleration * sub_dt;
+                *position += *velocity * sub_dt;
+            }
+            self.resolve_penetrations(colliders, bodies, spacing * 0.5);
+        }
+    }
+
+    fn emit(&mut self, dt: f32) {
+        let max_particles = self.params.max_particles;
+        let mut spawned = Vec::new();
+        for emitter in self.emitters.iter_mut() {
+            if !emitter.enabled {
+                continue;
+            }
+            emitter.pending += emitter.rate * dt;
+            while emitter.pending >= 1.0 && self.positions.len() + spawned.len() < max_particles {
+                emitter.pending -= 1.0;
+                spawned.push((emitter.next_point(), emitter.velocity));
+            }
+            // A full fluid does not bank emission for later.
+            emitter.pending = emitter.pending.min(1.0);
+        }
+        for (position, velocity) in spawned {
+            self.add_particle(position, velocity);
+        }
+    }
+
+    /// Mass that gives a particle in a rest lattice exactly the rest density.
+    fn particle_mass(&self, kernels: &Kernels) -> f32 {
+        let spacing = self.params.particle_spacing.max(1e-4);
+        let reach = (kernels.h / spacing).ceil() as i32;
+        let mut sum = 0.0;
+        for x in -reach..=reach {
+            for y in -reach..=reach {
+                for z in -reach..=reach {
+                    let offset = Vec3::new(x as f32, y as f32, z as f32) * spacing;
+                    sum += kernels.poly6(offset.length_squared());
+                }
+            }
+        }
+        self.params.rest_density / sum.max(f32::EPSILON)
+    }
+
+    /// Places boundary particles on the colliders near the fluid.
+    fn sample_boundary(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        spacing: f32,
+        h: f32,
+    ) {
+        self.boundary.clear();
+        let (min, max) = self.positions.iter().fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(lo, hi), p| (lo.min(*p), hi.max(*p)),
+        );
+        let (min, max) = (min - Vec3::splat(h), max + Vec3::splat(h));
+
+        for collider in colliders.iter() {
+            if collider.is_trigger || collider.is_unbounded() {
+                continue;
+            }
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let transform = collider.world_transform(body.transform());
+            let reach = collider.bounding_radius() * transform.scale.abs().max_element();
+            let nearest = transform.position.clamp(min, max);
+            if nearest.distance_squared(transform.position) > reach * reach {
+                continue;
+            }
+
+            let signature = collider.bounding_radius();
+            if self
+                .boundary_samples
+                .get(&collider.id)
+                .is_none_or(|(cached, _)| *cached != signature)
+            {
+                let samples = surface_samples(&collider.shape, spacing);
+                self.boundary_samples
+                    .insert(collider.id, (signature, samples));
+            }
+            let samples = &self.boundary_samples[&collider.id].1;
+            let body_position = body.transform().position;
+            let velocity = *body.velocity();
+            for local in samples {
+                let point = transform.position + transform.rotation * (transform.scale * *local);
+                if point.cmplt(min).any() || point.cmpgt(max).any() {
+                    continue;
+                }
+                let surface_velocity =
+                    velocity.linear + velocity.angular.cross(point - body_position);
+                self.boundary.push((point, surface_velocity));
+            }
+        }
+    }
+
+    fn find_neighbors(&mut self, grid: &SpatialHash, boundary_grid: &SpatialHash, h: f32) {
+        let count = self.positions.len();
+        self.neighbors.resize_with(count, Vec::new);
+        self.boundary_neighbors.resize_with(count, Vec::new);
+        let h2 = h * h;
+        for i in 0..count {
+            let position = self.positions[i];
+            let (positions, boundary) = (&self.positions, &self.boundary);
+            let near = &mut self.neighbors[i];
+            near.clear();
+            grid.for_each_near(position, |j| {
+                if position.distance_squared(positions[j]) < h2 {
+                    near.push(j);
+                }
+            });
+            let near = &mut self.boundary_neighbors[i];
+            near.clear();
+            boundary_grid.for_each_near(position, |b| {
+                if position.distance_squared(boundary[b].0) < h2 {
+                    near.push(b);
+                }
+            });
+        }
+    }
+
+    fn compute_pressures(&mut self, kernels: &Kernels, mass: f32) {
+        let rest_density = self.params.rest_density;
+        for i in 0..self.positions.len() {
+            let position = self.positions[i];
+            let mut density = 0.0;
+            for &j in &self.neighbors[i] {
+                density += mass * kernels.poly6(position.distance_squared(self.positions[j]));
+            }
+            for &b in &self.boundary_neighbors[i] {
+                density += mass * kernels.poly6(position.distance_squared(self.boundary[b].0));
+            }
+            self.densities[i] = density.max(rest_density * 1e-3);
+            // Clamped at zero so sparse spray does not clump together.
+            self.pressures[i] = (self.params.stiffness * (density - rest_density)).max(0.0);
+        }
+    }
+
+    fn compute_accelerations(&mut self, kernels: &Kernels, mass: f32, gravity: Vec3) {
+        let viscosity = self.params.viscosity;
+        for i in 0..self.positions.len() {
+            let (position, velocity) = (self.positions[i], self.velocities[i]);
+            let density = self.densities[i];
+            let pressure_term = self.pressures[i] / (density * density);
+            let mut acceleration = gravity;
+            for &j in &self.neighbors[i] {
+                let offset = position - self.positions[j];
+                let distance = offset.length();
+                if j == i || distance <= 1e-6 {
+                    continue;
+                }
+                let other = self.densities[j];
+                let gradient = kernels.spiky_gradient(offset, distance);
+                acceleration -=
+                    gradient * mass * (pressure_term + self.pressures[j] / (other * other));
+                acceleration += (self.velocities[j] - velocity)
+                    * (viscosity * mass * kernels.viscosity_laplacian(distance)
+                        / (other * density));
+            }
+            for &b in &self.boundary_neighbors[i] {
+                let (point, surface_velocity) = self.boundary[b];
+                let offset = position - point;
+                let distance = offset.length();
+                if distance <= 1e-6 {
+                    continue;
+                }
+                let gradient = kernels.spiky_gradient(offset, distance);
+                acceleration -= gradient * mass * pressure_term;
+                acceleration += (surface_velocity - velocity)
+                    * (viscosity * mass * kernels.viscosity_laplacian(distance)
+                        / (density * density));
+            }
+            self.accelerations[i] = acceleration;
+        }
+    }
+
+    /// Pushes particles out of colliders and removes their approach velocity.
+    fn resolve_penetrations(
+        &mut self,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        radius: f32,
+    ) {
+        let probe = Collider::builder().sphere(radius).build();
+        let mut probe_body = RigidBody::new(EntityId::default());
+        for collider in colliders.iter() {
+            if collider.is_trigger {
+                continue;
+            }
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let body = body.to_rigid_body();
+            let transform = collider.world_transform(&body.transform);
+            let reach = collider.bounding_radius() * transform.scale.abs().max_element()
+                + collider.margin
+                + radius;
+            for (position, velocity) in self.positions.iter_mut().zip(&mut self.velocities) {
+                if position.distance_squared(transform.position) > reach * reach {
+                    continue;
+                }
+                // Primitives rule most particles out with a cheap distance check.
+                let local = transform.rotation.inverse() * (*position - transform.position)
+                    / transform.scale;
+                if ShapeUtil::signed_distance(&collider.shape, local).is_some_and(|distance| {
+                    distance * transform.scale.abs().min_element() > radius + collider.margin
+                }) {
+                    continue;
+                }
+                probe_body.transform.position = *position;
+                let Some((contact, _)) =
+                    NarrowPhase::collide(&probe, &probe_body, collider, &body, None)
+                else {
+                    continue;
+                };
+                let normal = -contact.normal;
+                *position += normal * contact.depth;
+                let surface_velocity = body.velocity.linear
+                    + body
+                        .velocity
+                        .angular
+                        .cross(contact.point - body.transform.position);
+                let approach = (*velocity - surface_velocity).dot(normal);
+                if approach < 0.0 {
+                    *velocity -= normal * approach;
+                }
+            }
+        }
+    }
+}
+
+/// Müller et al. smoothing kernels for support radius `h`.
+#[derive(Debug, Clone, Copy)]
+struct Kernels {
+    h: f32,
+    poly6: f32,
+    spiky: f32,
+}
+
+impl Kernels {
+    fn new(h: f32) -> Self {
+        Self {
+            h,
+            poly6: 315.0 / (64.0 * PI * h.powi(9)),
+            spiky: 45.0 / (PI * h.powi(6)),
+        }
+    }
+
+    fn poly6(&self, distance_squared: f32) -> f32 {
+        let h2 = self.h * self.h;
+        if distance_squared >= h2 {
+            return 0.0;
+        }
+        self.poly6 * (h2 - distance_squared).powi(3)
+    }
+
+    fn spiky_gradient(&self, offset: Vec3, distance: f32) -> Vec3 {
+        -offset / distance * self.spiky * (self.h - distance).powi(2)
+    }
+
+    fn viscosity_laplacian(&self, distance: f32) -> f32 {
+        self.spiky * (self.h - distance)
+    }
+}
+
+/// Boundary particles on the surface of `shape`, about `spacing` apart, in its local frame.
+fn surface_samples(shape: &ColliderShape, spacing: f32) -> Vec<Vec3> {
+    match shape {
+        ColliderShape::Sphere { radius } => {
+            let count = ((4.0 * PI * radius * radius) / (spacing * spacing))
+                .ceil()
+                .max(1.0) as usize;
+            (0..count)
+                .map(|i| {
+                    // Fibonacci sphere.
+                    let y = 1.0 - 2.0 * (i as f32 + 0.5) / count as f32;
+                    let ring = (1.0 - y * y).max(0.0).sqrt();
+                    let angle = i as f32 * 2.399_963;
+                    Vec3::new(ring * angle.cos(), y, ring * angle.sin()) * *radius
+                })
+                .collect()
+        }
+        ColliderShape::Box { half_extents } => box_surface(*half_extents, spacing),
+        _ => {
+            // Lattice points within half a spacing of the surface.
+            let reach = ShapeUtil::bounding_radius(shape);
+            if !reach.is_finite() || ShapeUtil::signed_distance(shape, Vec3::ZERO).is_none() {
+                return Vec::new();
+            }
+            let steps = (reach / spacing).ceil() as i32;
+            let mut samples = Vec::new();
+            for x in -steps..=steps {
+                for y in -steps..=steps {
+                    for z in -steps..=steps {
+                        let point = Vec3::new(x as f32, y as f32, z as f32) * spacing;
+                        if ShapeUtil::signed_distance(shape, point)
+                            .is_some_and(|distance| distance.abs() <= spacing * 0.5)
+                        {
+                            samples.push(point);
+                        }
+                    }
+                }
+            }
+            samples
+        }
+    }
+}
+
+/// Lattice points on the faces of a box, each included once.
+fn box_surface(half_extents: Vec3, spacing: f32) -> Vec<Vec3> {
+    let counts = (half_extents * 2.0 / spacing).ceil().max(Vec3::ONE);
+    let (nx, ny, nz) = (counts.x as u32, counts.y as u32, counts.z as u32);
+    let step = half_extents * 2.0 / counts;
+    let point =
+        |i: u32, j: u32, k: u32| Vec3::new(i as f32, j as f32, k as f32) * step - half_extents;
+    let mut samples = Vec::new();
+    for i in 0..=nx {
+        for j in 0..=ny {
+            if i == 0 || i == nx || j == 0 || j == ny {
+                samples.extend((0..=nz).map(|k| point(i, j, k)));
+            } else {
+                samples.push(point(i, j, 0));
+                samples.push(point(i, j, nz));
+            }
+        }
+    }
+    samples
+}
@@ -7,7 +7,7 @@ pub trait ForceGenerator: Send + Sync {
     fn apply(&self, body: &mut BodyMut, dt: f32);
 }
 
-/// Constant gravity force scaled per body.
+/// Constant gravity force scaled per body; bodies with a gravity override use theirs.
 pub struct GravityForce {
     pub gravity: Vec3,
 }
@@ -23,7 +23,7 @@ impl ForceGenerator for GravityForce {
         if body.is_static() {
             return;
         }
-        let force = self.gravity * body.mass_properties.mass * (*body.gravity_scale);
+        let force = body.gravity(self.gravity) * body.mass_properties.mass;
         body.apply_force(force);
     }
 }
@@ -73,6 +73,154 @@ impl ForceGenerator for SpringForce {
     }
 }
 
+/// Thin lifting surface (wing, fin, flat plate) producing lift and drag from the
+/// airflow over it.
+///
+/// The angle of attack is measured between the relative airflow and the surface plane.
+/// Below `stall_angle` the lift coefficient grows linearly with it; past the stall the
+/// surface behaves like a flat plate. A surface with a zero `normal` has no orientation
+/// and only produces `drag_coefficient` drag, which suits projectiles.
+pub struct AeroSurface {
+    /// Only this body is affected; `None` applies the surface to every body.
+    pub body: Option<EntityId>,
+    /// Surface normal in body space.
+    pub normal: Vec3,
+    /// Centre of pressure in body space; lift and drag applied off the centre of mass
+    /// also produce torque.
+    pub offset: Vec3,
+    pub area: f32,
+    pub air_density: f32,
+    /// World-space wind velocity.
+    pub wind: Vec3,
+    /// Lift coefficient per radian of angle of attack before the stall.
+    pub lift_slope: f32,
+    /// Stall angle in radians.
+    pub stall_angle: f32,
+    /// Drag coefficient at zero angle of attack.
+    pub drag_coefficient: f32,
+}
+
+impl AeroSurface {
+    /// Sea-level air density in kg/m³.
+    pub const AIR_DENSITY: f32 = 1.225;
+
+    /// Thin-airfoil wing with the given body-space normal and planform area.
+    pub fn wing(normal: Vec3, area: f32) -> Self {
+        Self {
+            body: None,
+            normal: normal.normalize_or_zero(),
+            offset: Vec3::ZERO,
+            area,
+            air_density: Self::AIR_DENSITY,
+            wind: Vec3::ZERO,
+            lift_slope: std::f32::consts::TAU,
+            stall_angle: 15f32.to_radians(),
+            drag_coefficient: 0.02,
+        }
+    }
+
+    /// Orientation-free drag for a projectile with the given frontal area.
+    pub fn projectile(area: f32, drag_coefficient: f32) -> Self {
+        Self {
+            normal: Vec3::ZERO,
+            drag_coefficient,
+            lift_slope: 0.0,
+            ..Self::wing(Vec3::ZERO, area)
+        }
+    }
+
+    pub fn body(mut self, body: EntityId) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    pub fn offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn wind(mut self, wind: Vec3) -> Self {
+        self.wind = wind;
+        self
+    }
+
+    /// Lift and drag for a surface with world-space normal `normal` in the given
+    /// relative airflow.
+    pub fn force(&self, normal: Vec3, airflow: Vec3) -> Vec3 {
+        let speed = airflow.length();
+        if speed < 1e-6 {
+            return Vec3::ZERO;
+        }
+        let flow = airflow / speed;
+        let dynamic_pressure = 0.5 * self.air_density * speed * speed * self.area;
+        if normal == Vec3::ZERO {
+            return flow * dynamic_pressure * self.drag_coefficient;
+        }
+
+        // Positive when the air strikes the underside of the surface.
+        let sin_alpha = flow.dot(normal).clamp(-1.0, 1.0);
+        let alpha = sin_alpha.asin();
+        let lift_coefficient = if alpha.abs() <= self.stall_angle {
+            self.lift_slope * alpha
+        } else {
+            (2.0 * alpha).sin()
+        };
+        let drag_coefficient = self.drag_coefficient + 2.0 * sin_alpha * sin_alpha;
+        let lift_direction = (normal - flow * sin_alpha).normalize_or_zero();
+
+        (lift_direction * lift_coefficient + flow * drag_coefficient) * dynamic_pressure
+    }
+}
+
+impl ForceGenerator for AeroSurface {
+    fn apply(&self, body: &mut BodyMut, dt: f32) {
+        if body.is_static() || self.body.is_some_and(|id| id != body.id) {
+            return;
+        }
+
+        let arm = body.transform.rotation * self.offset;
+        let point_velocity = body.velocity.linear + body.velocity.angular.cross(arm);
+        let normal = body.transform.rotation * self.normal;
+        let force = self.force(normal, self.wind - point_velocity);
+        if force == Vec3::ZERO {
+            return;
+        }
+
+        body.apply_force(force);
+        // Bodies only accumulate linear acceleration, so the torque goes straight
+        // into angular velocity.
+        body.velocity.angular += *body.inverse_inertia * arm.cross(force) * dt;
+    }
+}
+
+/// How an explosion's impulse weakens with distance from its centre.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExplosionFalloff {
+    /// Full impulse everywhere inside the radius.
+    Constant,
+    /// Fades linearly to zero at the radius.
+    #[default]
+    Linear,
+    /// Fades with the square of the remaining distance, concentrating the blast near
+    /// the centre.
+    Quadratic,
+}
+
+impl ExplosionFalloff {
+    /// Fraction of the full impulse felt at `distance` from an explosion of `radius`.
+    pub fn scale(self, distance: f32, radius: f32) -> f32 {
+        if distance > radius {
+            return 0.0;
+        }
+        let remaining = 1.0 - distance / radius.max(f32::EPSILON);
+        match self {
+            Self::Constant => 1.0,
+            Self::Linear => remaining,
+            Self::Quadratic => remaining * remaining,
+        }
+    }
+}
+
 /// Collection of forces that can be applied each frame.
 pub struct ForceRegistry {
     forces: Vec<Box<dyn ForceGenerator>>,
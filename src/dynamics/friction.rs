@@ -54,21 +54,45 @@ pub fn apply_friction_slice(
     let tangent_vel = relative_vel - contact.normal * relative_vel.dot(contact.normal);
 
     if tangent_vel.length_squared() > f32::EPSILON {
-        let tangent = tangent_vel.normalize();
-
         let material_pair =
             crate::core::types::Material::combine_pair(&body_a.material, &body_b.material);
-        let mu_static =
-            friction_coefficient(material_pair.static_friction, contact.normal, tangent);
-        let max_friction = mu_static * normal_impulse;
-
-        let impulse_mag =
-            -tangent_vel.dot(tangent) / (body_a.inverse_mass + body_b.inverse_mass + 1e-6);
-        let impulse_mag = impulse_mag.clamp(-max_friction, max_friction);
+        let friction_limit = |direction: Vec3| {
+            let mu_static =
+                friction_coefficient(material_pair.static_friction, contact.normal, direction);
+            let mu = match material_pair.stribeck {
+                Some(curve) => {
+                    let mu_dynamic = friction_coefficient(
+                        material_pair.dynamic_friction,
+                        contact.normal,
+                        direction,
+                    );
+                    curve.coefficient(mu_static, mu_dynamic, tangent_vel.length())
+                }
+                None => mu_static,
+            };
+            mu * normal_impulse
+        };
+        let inv_mass_sum = body_a.inverse_mass + body_b.inverse_mass + 1e-6;
 
-        let impulse = tangent * impulse_mag;
-        body_a.apply_impulse(-impulse, contact.point);
-        body_b.apply_impulse(impulse, contact.point);
+        let tangent = tangent_vel.normalize();
+        // Isotropic friction works along the slip direction alone.
+        let axes = material_pair
+            .anisotropic_axes(
+                body_a.transform.rotation,
+                body_b.transform.rotation,
+                contact.normal,
+            )
+            .unwrap_or([(tangent, 1.0), (Vec3::ZERO, 0.0)]);
+        for (axis, scale) in axes {
+            if axis == Vec3::ZERO {
+                continue;
+            }
+            let max_friction = friction_limit(axis) * scale;
+            let impulse_mag = -tangent_vel.dot(axis) / inv_mass_sum;
+            let impulse = axis * impulse_mag.clamp(-max_friction, max_friction);
+            body_a.apply_impulse(-impulse, contact.point);
+            body_b.apply_impulse(impulse, contact.point);
+        }
     }
 }
 
@@ -120,19 +144,47 @@ fn apply_tangential_friction(
         mu_dynamic = mu_static;
     }
 
-    let max_static = mu_static * normal_impulse;
-    let max_dynamic = mu_dynamic * normal_impulse;
-
-    let mut clamped_impulse = new_impulse;
-    let length = clamped_impulse.length();
+    let slip = tangent_velocity.length();
+    let limit = |magnitude: f32, mu_static: f32, mu_dynamic: f32| {
+        match contact.material.stribeck {
+            // The friction cone shrinks smoothly with slip speed instead of switching.
+            Some(curve) => {
+                magnitude.min(curve.coefficient(mu_static, mu_dynamic, slip) * normal_impulse)
+            }
+            None if magnitude > mu_static * normal_impulse => mu_dynamic.max(0.0) * normal_impulse,
+            None => magnitude,
+        }
+    };
 
-    if length > max_static && length > 0.0 {
-        if max_dynamic > 0.0 {
-            clamped_impulse = clamped_impulse.normalize() * max_dynamic;
-        } else {
-            clamped_impulse = Vec3::ZERO;
+    let clamped_impulse = match contact.material.anisotropic_axes(
+        body_a.transform.rotation,
+        body_b.transform.rotation,
+        contact.normal,
+    ) {
+        // Each axis gets its own limit, so a ski can glide along its length while its
+        // edge still grips sideways.
+        Some(axes) => axes
+            .iter()
+            .map(|&(axis, scale)| {
+                let component = new_impulse.dot(axis);
+                let mu_static =
+                    friction_coefficient(contact.material.static_friction, contact.normal, axis);
+                let mu_dynamic =
+                    friction_coefficient(contact.material.dynamic_friction, contact.normal, axis)
+                        .min(mu_static);
+                axis * component.signum()
+                    * limit(component.abs(), mu_static * scale, mu_dynamic * scale)
+            })
+            .sum(),
+        None => {
+            let length = new_impulse.length();
+            if length > 0.0 {
+                new_impulse * (limit(length, mu_static, mu_dynamic) / length)
+            } else {
+                new_impulse
+            }
         }
-    }
+    };
 
     let impulse_delta = clamped_impulse - contact.accumulated_tangent_impulse;
     if impulse_delta.length_squared() <= 1e-12 {
@@ -1,13 +1,32 @@
-use glam::{Quat, Vec3};
+use glam::{Mat3, Quat, Vec3};
 
 use crate::core::soa::{BodiesSoA, BodyMut};
 
+/// How [`Integrator`] advances bodies over a substep, from cheapest to most accurate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IntegrationScheme {
+    /// Velocity first, then position from the new velocity. Cheap and stable.
+    #[default]
+    SymplecticEuler,
+    /// Velocity Verlet: positions see half of this substep's acceleration and rotations
+    /// the mean of the old and new spin.
+    Verlet,
+    /// Classic fourth-order Runge-Kutta over position, velocity, rotation and spin, so
+    /// damping and gyroscopic torque are followed through the substep.
+    RK4,
+    /// Symplectic Euler, with the world nudging contact velocities beforehand so next
+    /// frame's predicted overlap stays within the slop.
+    PredictiveCorrective,
+}
+
 /// Integrator responsible for stepping rigid bodies forward in time.
 #[derive(Debug, Clone)]
 pub struct Integrator {
     pub dt: f32,
     pub substeps: u32,
     parallel: bool,
+    gyroscopic: bool,
+    scheme: IntegrationScheme,
 }
 
 impl Integrator {
@@ -17,6 +36,8 @@ impl Integrator {
             dt: substep_dt,
             substeps: substeps.max(1),
             parallel: false,
+            gyroscopic: false,
+            scheme: IntegrationScheme::default(),
         }
     }
 
@@ -24,57 +45,259 @@ impl Integrator {
         self.parallel = enabled;
     }
 
+    /// Adds the gyroscopic torque `-ω × Iω`, so spinning bodies precess and tumble.
+    ///
+    /// The term is integrated implicitly, which keeps fast spins from gaining energy.
+    /// Off by default.
+    pub fn set_gyroscopic(&mut self, enabled: bool) {
+        self.gyroscopic = enabled;
+    }
+
+    pub fn gyroscopic(&self) -> bool {
+        self.gyroscopic
+    }
+
+    pub fn set_scheme(&mut self, scheme: IntegrationScheme) {
+        self.scheme = scheme;
+    }
+
+    pub fn scheme(&self) -> IntegrationScheme {
+        self.scheme
+    }
+
     pub fn integrate_position(&self, body: &mut BodyMut, dt: f32) {
-        if body.flags.is_static {
+        if body.flags.is_static || !body.flags.is_enabled {
             return;
         }
 
         body.transform.position += body.velocity.linear * dt;
-
-        let omega_mag = body.velocity.angular.length();
-        if omega_mag > 1e-6 {
-            let axis = body.velocity.angular / omega_mag;
-            let angle = omega_mag * dt;
-            let delta = Quat::from_axis_angle(axis, angle);
-            body.transform.rotation = (delta * body.transform.rotation).normalize();
-        }
+        let angular = body.velocity.angular;
+        Self::rotate(body, angular, dt);
     }
 
     pub fn integrate_velocity(&self, body: &mut BodyMut, dt: f32) {
-        if body.flags.is_static {
+        if body.flags.is_static || !body.flags.is_enabled {
+            return;
+        }
+        // Kinematic bodies keep the velocity they were given.
+        if body.flags.is_kinematic {
+            *body.acceleration = Vec3::ZERO;
             return;
         }
 
         body.velocity.linear += (*body.acceleration) * dt;
+        if self.gyroscopic && *body.inverse_inertia != Mat3::ZERO {
+            body.velocity.angular = Self::solve_gyroscopic(
+                body.mass_properties.inertia,
+                body.transform.rotation,
+                body.velocity.angular,
+                dt,
+            );
+        }
 
         body.velocity.linear *= (1.0 - (*body.linear_damping) * dt).max(0.0);
         body.velocity.angular *= (1.0 - (*body.angular_damping) * dt).max(0.0);
+        Self::finish_velocity(body);
+    }
+
+    /// Moves the body with velocity Verlet.
+    pub fn integrate_verlet(&self, body: &mut BodyMut, dt: f32) {
+        if body.flags.is_static || !body.flags.is_enabled {
+            return;
+        }
+        let acceleration = if body.flags.is_kinematic {
+            Vec3::ZERO
+        } else {
+            *body.acceleration
+        };
+        let spin = body.velocity.angular;
+        body.transform.position += (body.velocity.linear + acceleration * (0.5 * dt)) * dt;
+        self.integrate_velocity(body, dt);
+        Self::rotate(body, (spin + body.velocity.angular) * 0.5, dt);
+    }
+
+    /// Moves the body with fourth-order Runge-Kutta.
+    pub fn integrate_rk4(&self, body: &mut BodyMut, dt: f32) {
+        if body.flags.is_static || !body.flags.is_enabled {
+            return;
+        }
+        // Kinematic bodies move at constant velocity, which Euler already gets exactly.
+        if body.flags.is_kinematic {
+            self.integrate_velocity(body, dt);
+            self.integrate_position(body, dt);
+            return;
+        }
+
+        let rates = Rates {
+            acceleration: *body.acceleration,
+            linear_damping: *body.linear_damping,
+            angular_damping: *body.angular_damping,
+            inertia: (self.gyroscopic && *body.inverse_inertia != Mat3::ZERO)
+                .then_some(body.mass_properties.inertia)
+                .filter(|inertia| inertia.determinant().abs() >= f32::EPSILON),
+        };
+        let start = State {
+            position: body.transform.position,
+            rotation: body.transform.rotation,
+            linear: body.velocity.linear,
+            angular: body.velocity.angular,
+        };
+        let k1 = rates.derivative(&start);
+        let k2 = rates.derivative(&start.advance(&k1, dt * 0.5));
+        let k3 = rates.derivative(&start.advance(&k2, dt * 0.5));
+        let k4 = rates.derivative(&start.advance(&k3, dt));
+        let end = start.advance(&k1.blend(&k2, &k3, &k4), dt);
+
+        body.transform.position = end.position;
+        body.transform.rotation = end.rotation;
+        body.velocity.linear = end.linear;
+        body.velocity.angular = end.angular;
+        Self::finish_velocity(body);
+    }
+
+    /// Applies the speed limits and clears the consumed acceleration.
+    fn finish_velocity(body: &mut BodyMut) {
+        body.velocity.linear = body
+            .velocity
+            .linear
+            .clamp_length_max(body.max_linear_velocity.max(0.0));
+        body.velocity.angular = body
+            .velocity
+            .angular
+            .clamp_length_max(body.max_angular_velocity.max(0.0));
 
         *body.acceleration = Vec3::ZERO;
     }
 
+    fn rotate(body: &mut BodyMut, angular: Vec3, dt: f32) {
+        let omega_mag = angular.length();
+        if omega_mag > 1e-6 {
+            let delta = Quat::from_axis_angle(angular / omega_mag, omega_mag * dt);
+            body.transform.rotation = (delta * body.transform.rotation).normalize();
+        }
+    }
+
+    /// Backward Euler step of `I ω' = -ω × Iω` in body space, linearised with one Newton
+    /// iteration.
+    fn solve_gyroscopic(inertia: Mat3, rotation: Quat, angular: Vec3, dt: f32) -> Vec3 {
+        let omega = rotation.inverse() * angular;
+        let momentum = inertia * omega;
+        let residual = omega.cross(momentum) * dt;
+        let jacobian = inertia + (skew(omega) * inertia - skew(momentum)) * dt;
+        if jacobian.determinant().abs() < f32::EPSILON {
+            return angular;
+        }
+        rotation * (omega - jacobian.inverse() * residual)
+    }
+
     pub fn step(&self, bodies: &mut BodiesSoA) {
         for _ in 0..self.substeps {
             // Parallel disabled for SoA initial implementation
-            /*
-            if self.parallel {
-                bodies.par_for_each_mut(|body| self.integrate_velocity(body, self.dt));
-            } else {
-            */
-            for mut body in bodies.iter_mut() {
-                self.integrate_velocity(&mut body, self.dt);
-            }
-            //}
-
-            /*
-            if self.parallel {
-                bodies.par_for_each_mut(|body| self.integrate_position(body, self.dt));
-            } else {
-            */
-            for mut body in bodies.iter_mut() {
-                self.integrate_position(&mut body, self.dt);
+            match self.scheme {
+                IntegrationScheme::SymplecticEuler | IntegrationScheme::PredictiveCorrective => {
+                    for mut body in bodies.iter_mut() {
+                        self.integrate_velocity(&mut body, self.dt);
+                    }
+                    for mut body in bodies.iter_mut() {
+                        self.integrate_position(&mut body, self.dt);
+                    }
+                }
+                IntegrationScheme::Verlet => {
+                    for mut body in bodies.iter_mut() {
+                        self.integrate_verlet(&mut body, self.dt);
+                    }
+                }
+                IntegrationScheme::RK4 => {
+                    for mut body in bodies.iter_mut() {
+                        self.integrate_rk4(&mut body, self.dt);
+                    }
+                }
             }
-            //}
         }
     }
 }
+
+/// Body state carried through the Runge-Kutta stages.
+#[derive(Clone, Copy)]
+struct State {
+    position: Vec3,
+    rotation: Quat,
+    linear: Vec3,
+    angular: Vec3,
+}
+
+impl State {
+    fn advance(&self, rate: &Derivative, dt: f32) -> Self {
+        Self {
+            position: self.position + rate.linear * dt,
+            rotation: (self.rotation + rate.rotation * dt).normalize(),
+            linear: self.linear + rate.linear_acceleration * dt,
+            angular: self.angular + rate.angular_acceleration * dt,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Derivative {
+    linear: Vec3,
+    rotation: Quat,
+    linear_acceleration: Vec3,
+    angular_acceleration: Vec3,
+}
+
+impl Derivative {
+    /// The Runge-Kutta weighted average of four stage derivatives.
+    fn blend(&self, k2: &Self, k3: &Self, k4: &Self) -> Self {
+        let weigh = |a: Vec3, b: Vec3, c: Vec3, d: Vec3| (a + (b + c) * 2.0 + d) / 6.0;
+        Self {
+            linear: weigh(self.linear, k2.linear, k3.linear, k4.linear),
+            rotation: (self.rotation + (k2.rotation + k3.rotation) * 2.0 + k4.rotation) / 6.0,
+            linear_acceleration: weigh(
+                self.linear_acceleration,
+                k2.linear_acceleration,
+                k3.linear_acceleration,
+                k4.linear_acceleration,
+            ),
+            angular_acceleration: weigh(
+                self.angular_acceleration,
+                k2.angular_acceleration,
+                k3.angular_acceleration,
+                k4.angular_acceleration,
+            ),
+        }
+    }
+}
+
+/// What drives a body through one substep; only the state changes between stages.
+struct Rates {
+    acceleration: Vec3,
+    linear_damping: f32,
+    angular_damping: f32,
+    /// Body-space inertia when gyroscopic torque is on.
+    inertia: Option<Mat3>,
+}
+
+impl Rates {
+    fn derivative(&self, state: &State) -> Derivative {
+        let mut angular_acceleration = -state.angular * self.angular_damping;
+        if let Some(inertia) = self.inertia {
+            let omega = state.rotation.inverse() * state.angular;
+            let torque = -omega.cross(inertia * omega);
+            angular_acceleration += state.rotation * (inertia.inverse() * torque);
+        }
+        Derivative {
+            linear: state.linear,
+            rotation: Quat::from_vec4(state.angular.extend(0.0)) * state.rotation * 0.5,
+            linear_acceleration: self.acceleration - state.linear * self.linear_damping,
+            angular_acceleration,
+        }
+    }
+}
+
+fn skew(v: Vec3) -> Mat3 {
+    Mat3::from_cols(
+        Vec3::new(0.0, v.z, -v.y),
+        Vec3::new(-v.z, 0.0, v.x),
+        Vec3::new(v.y, -v.x, 0.0),
+    )
+}
@@ -1,5 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
+use glam::Vec3;
+
 use crate::{
     core::{constraints::Joint, soa::BodiesSoA},
     dynamics::solver::Contact,
@@ -7,17 +9,104 @@ use crate::{
 };
 
 /// Represents a connected set of bodies/contacts that can be solved independently.
+#[derive(Clone)]
 pub struct Island {
     pub bodies: Vec<EntityId>,
     pub contacts: Vec<Contact>,
     pub joints: Vec<Joint>,
+    /// Position of each of `joints` in the list the islands were built from.
+    pub joint_indices: Vec<usize>,
     pub is_awake: bool,
+    /// Bodies owned by a neighbouring partition but referenced by a boundary constraint.
+    /// Empty unless the island came from [`Island::split`].
+    pub ghosts: Vec<EntityId>,
+}
+
+impl Island {
+    /// Splits the island into slabs of at most `max_bodies` bodies along the axis where the
+    /// bodies are most spread out.
+    ///
+    /// Constraints that cross a slab boundary are copied into both partitions, and the body on
+    /// the far side is listed in [`Island::ghosts`]. Solving partitions independently means
+    /// each side only sees the other's velocities from the start of the solve, so stacks that
+    /// straddle a boundary converge more slowly than they would in one island.
+    pub fn split(&self, max_bodies: usize, bodies: &BodiesSoA) -> Vec<Island> {
+        let max_bodies = max_bodies.max(1);
+        if self.bodies.len() <= max_bodies {
+            return vec![self.clone()];
+        }
+
+        let position = |id: &EntityId| {
+            bodies
+                .get(*id)
+                .map(|body| body.transform().position)
+                .unwrap_or(Vec3::ZERO)
+        };
+        let (min, max) = self.bodies.iter().map(position).fold(
+            (Vec3::splat(f32::MAX), Vec3::splat(f32::MIN)),
+            |(min, max), p| (min.min(p), max.max(p)),
+        );
+        let extent = max - min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+
+        let mut sorted = self.bodies.clone();
+        sorted.sort_by(|a, b| position(a)[axis].total_cmp(&position(b)[axis]));
+
+        sorted
+            .chunks(max_bodies)
+            .map(|chunk| {
+                let owned: HashSet<_> = chunk.iter().copied().collect();
+                let mut ghosts = Vec::new();
+                let mut touches = |a: EntityId, b: EntityId| {
+                    let (has_a, has_b) = (owned.contains(&a), owned.contains(&b));
+                    if has_a != has_b {
+                        let other = if has_a { b } else { a };
+                        if other != Joint::WORLD && !ghosts.contains(&other) {
+                            ghosts.push(other);
+                        }
+                    }
+                    has_a || has_b
+                };
+                let contacts = self
+                    .contacts
+                    .iter()
+                    .filter(|c| touches(c.body_a, c.body_b))
+                    .cloned()
+                    .collect();
+                let (joints, joint_indices) = self
+                    .joints
+                    .iter()
+                    .zip(&self.joint_indices)
+                    .filter(|(j, _)| {
+                        let (a, b) = j.bodies();
+                        touches(a, b)
+                    })
+                    .map(|(j, index)| (j.clone(), *index))
+                    .unzip();
+                Island {
+                    bodies: chunk.to_vec(),
+                    contacts,
+                    joints,
+                    joint_indices,
+                    is_awake: self.is_awake,
+                    ghosts,
+                }
+            })
+            .collect()
+    }
 }
 
 /// Builds islands each step and manages sleeping state.
 pub struct IslandManager {
     islands: Vec<Island>,
     adjacency: HashMap<EntityId, Vec<EntityId>>,
+    max_island_size: Option<usize>,
 }
 
 impl Default for IslandManager {
@@ -31,9 +120,20 @@ impl IslandManager {
         Self {
             islands: Vec::new(),
             adjacency: HashMap::new(),
+            max_island_size: None,
         }
     }
 
+    /// Caps how many bodies the parallel solver handles in one job; larger islands are
+    /// split with [`Island::split`]. `None` (the default) never splits.
+    pub fn set_max_island_size(&mut self, max_bodies: Option<usize>) {
+        self.max_island_size = max_bodies.map(|max| max.max(1));
+    }
+
+    pub fn max_island_size(&self) -> Option<usize> {
+        self.max_island_size
+    }
+
     pub fn build_islands(&mut self, bodies: &BodiesSoA, contacts: &[Contact], joints: &[Joint]) {
         self.islands.clear();
         self.adjacency.clear();
@@ -50,6 +150,10 @@ impl IslandManager {
         }
 
         for joint in joints {
+            // The world anchor is not a body and must not merge islands.
+            if joint.is_grounded() {
+                continue;
+            }
             let (body_a, body_b) = joint.bodies();
             self.adjacency.entry(body_a).or_default().push(body_b);
             self.adjacency.entry(body_b).or_default().push(body_a);
@@ -73,14 +177,15 @@ impl IslandManager {
                 .filter(|c| id_set.contains(&c.body_a) || id_set.contains(&c.body_b))
                 .cloned()
                 .collect();
-            let island_joints = joints
+            let (island_joints, joint_indices) = joints
                 .iter()
-                .filter(|j| {
+                .enumerate()
+                .filter(|(_, j)| {
                     let (a, b) = j.bodies();
                     id_set.contains(&a) || id_set.contains(&b)
                 })
-                .cloned()
-                .collect();
+                .map(|(index, j)| (j.clone(), index))
+                .unzip();
             let is_awake = bodies_in_island.iter().any(|id| {
                 bodies
                     .get(*id)
@@ -91,7 +196,9 @@ impl IslandManager {
                 bodies: bodies_in_island,
                 contacts: island_contacts,
                 joints: island_joints,
+                joint_indices,
                 is_awake,
+                ghosts: Vec::new(),
             });
         }
     }
@@ -126,7 +233,8 @@ impl IslandManager {
                 }
             }
             avg_velocity /= island.bodies.len().max(1) as f32;
-            if avg_velocity < 0.01 {
+            // A servo may still be closing on its target below the sleep threshold.
+            if avg_velocity < 0.01 && !island.joints.iter().any(Joint::is_servo) {
                 island.is_awake = false;
                 for body_id in &island.bodies {
                     if let Some(mut body) = bodies.get_mut(*body_id) {
@@ -1,19 +1,39 @@
 //! Simulation dynamics modules: integration, forces, constraint solvers, and islands.
 
 pub mod aba;
+pub mod cloth;
+pub mod fields;
+pub mod fluid;
 pub mod forces;
 pub mod friction;
 pub mod integrator;
 pub mod island;
+#[cfg(feature = "parallel")]
 pub mod parallel;
+pub mod particles;
+mod pbd;
 pub mod pci;
+mod simd_contacts;
+pub mod soft_body;
 pub mod solver;
 
 pub use aba::ABASolver;
 
-pub use forces::{DragForce, ForceGenerator, ForceRegistry, GravityForce, SpringForce};
-pub use integrator::Integrator;
+pub use cloth::{Cloth, ClothAttachment, ClothParams};
+pub use fields::{FieldKind, FieldVolume, ForceField};
+pub use fluid::{Fluid, FluidEmitter, FluidParams};
+pub use forces::{
+    AeroSurface, DragForce, ExplosionFalloff, ForceGenerator, ForceRegistry, GravityForce,
+    SpringForce,
+};
+pub use integrator::{IntegrationScheme, Integrator};
 pub use island::{Island, IslandManager};
+#[cfg(feature = "parallel")]
 pub use parallel::ParallelIntegrator;
+pub use particles::{Particle, ParticleLink, ParticleSystem};
 pub use pci::PredictiveCorrectiveIntegrator;
-pub use solver::{ConstraintSolver, Contact, PGSSolver, SolverStepMetrics};
+pub use soft_body::{SoftBody, SoftBodyParams};
+pub use solver::{
+    BlockSolverMode, ConstraintSolver, Contact, JointImpulse, PGSSolver, PositionCorrection,
+    SolverStepMetrics,
+};
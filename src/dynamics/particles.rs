@@ -0,0 +1,476 @@
+//! Point-mass particles for debris and effects, stored as structure-of-arrays.
+//!
+//! Particles have no rotation and no collision shape beyond a radius. They collide with
+//! the world's colliders one way: bodies push particles, but particles never push back.
+//!
+//! With [`ParticleSystem::gpu`] set, a compute backend that supports it steps the particles
+//! instead and keeps them in device memory. Only changes made on the CPU (spawns, despawns,
+//! new velocities) are uploaded, and positions are never read back.
+
+use std::collections::VecDeque;
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::narrowphase::NarrowPhase,
+    core::{
+        collider::{Collider, ColliderShape},
+        rigidbody::RigidBody,
+        soa::BodiesSoA,
+    },
+    gpu::{GpuParticle, GpuParticleState},
+    utils::allocator::{Arena, EntityId},
+};
+
+/// Spawn description for [`ParticleSystem::spawn`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub mass: f32,
+    pub radius: f32,
+    /// Seconds until the particle is despawned; `None` keeps it forever.
+    pub lifetime: Option<f32>,
+}
+
+impl Particle {
+    pub fn new(position: Vec3) -> Self {
+        Self {
+            position,
+            velocity: Vec3::ZERO,
+            mass: 1.0,
+            radius: 0.05,
+            lifetime: None,
+        }
+    }
+
+    pub fn velocity(mut self, velocity: Vec3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn radius(mut self, radius: f32) -> Self {
+        self.radius = radius.max(0.0);
+        self
+    }
+
+    pub fn lifetime(mut self, seconds: f32) -> Self {
+        self.lifetime = Some(seconds);
+        self
+    }
+}
+
+/// Damped spring between two particles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParticleLink {
+    pub a: EntityId,
+    pub b: EntityId,
+    pub rest_length: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+/// Pool of point masses stepped by the world after its rigid bodies.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParticleSystem {
+    generations: Vec<u32>,
+    free_list: VecDeque<usize>,
+    alive: Vec<bool>,
+    count: usize,
+
+    positions: Vec<Vec3>,
+    velocities: Vec<Vec3>,
+    inverse_masses: Vec<f32>,
+    radii: Vec<f32>,
+    ages: Vec<f32>,
+    lifetimes: Vec<Option<f32>>,
+    links: Vec<ParticleLink>,
+
+    /// Fraction of the approach speed kept after hitting a collider.
+    pub restitution: f32,
+    /// Fraction of the sliding speed removed by each collider contact.
+    pub friction: f32,
+    /// Linear velocity damping per second.
+    pub damping: f32,
+    /// Whether particles collide with the world's colliders. GPU-stepped particles also
+    /// collide with each other.
+    pub collisions: bool,
+    /// Step on the world's compute backend if it simulates particles and the system has no
+    /// links. [`Self::position`] and [`Self::velocity`] then keep the values the particle
+    /// was spawned or last given on the CPU.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gpu: bool,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gpu_resident: bool,
+    /// Slots spawned or killed since the backend last synced.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gpu_writes: Vec<usize>,
+    /// Slots whose velocity was set since the backend last synced.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gpu_velocity_writes: Vec<usize>,
+    /// Largest radius spawned since the particles moved to the GPU.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    gpu_max_radius: f32,
+}
+
+impl Default for ParticleSystem {
+    fn default() -> Self {
+        Self {
+            generations: Vec::new(),
+            free_list: VecDeque::new(),
+            alive: Vec::new(),
+            count: 0,
+            positions: Vec::new(),
+            velocities: Vec::new(),
+            inverse_masses: Vec::new(),
+            radii: Vec::new(),
+            ages: Vec::new(),
+            lifetimes: Vec::new(),
+            links: Vec::new(),
+            restitution: 0.3,
+            friction: 0.2,
+            damping: 0.0,
+            collisions: true,
+            gpu: false,
+            gpu_resident: false,
+            gpu_writes: Vec::new(),
+            gpu_velocity_writes: Vec::new(),
+            gpu_max_radius: 0.0,
+        }
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self, particle: Particle) -> EntityId {
+        let inverse_mass = if particle.mass > 0.0 {
+            1.0 / particle.mass
+        } else {
+            0.0
+        };
+        self.count += 1;
+        if self.gpu_resident {
+            self.gpu_max_radius = self.gpu_max_radius.max(particle.radius);
+        }
+        let id = if let Some(index) = self.free_list.pop_front() {
+            self.alive[index] = true;
+            self.positions[index] = particle.position;
+            self.velocities[index] = particle.velocity;
+            self.inverse_masses[index] = inverse_mass;
+            self.radii[index] = particle.radius;
+            self.ages[index] = 0.0;
+            self.lifetimes[index] = particle.lifetime;
+            EntityId::new(index, self.generations[index])
+        } else {
+            let index = self.generations.len();
+            self.generations.push(0);
+            self.alive.push(true);
+            self.positions.push(particle.position);
+            self.velocities.push(particle.velocity);
+            self.inverse_masses.push(inverse_mass);
+            self.radii.push(particle.radius);
+            self.ages.push(0.0);
+            self.lifetimes.push(particle.lifetime);
+            EntityId::new(index, 0)
+        };
+        if self.gpu_resident {
+            self.gpu_writes.push(id.index());
+        }
+        id
+    }
+
+    /// Removes a particle and any links to it.
+    pub fn despawn(&mut self, id: EntityId) -> bool {
+        if !self.is_alive(id) {
+            return false;
+        }
+        self.kill(id.index());
+        self.links.retain(|link| link.a != id && link.b != id);
+        true
+    }
+
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        let index = id.index();
+        index < self.alive.len() && self.alive[index] && self.generations[index] == id.generation()
+    }
+
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn position(&self, id: EntityId) -> Option<Vec3> {
+        self.is_alive(id).then(|| self.positions[id.index()])
+    }
+
+    pub fn velocity(&self, id: EntityId) -> Option<Vec3> {
+        self.is_alive(id).then(|| self.velocities[id.index()])
+    }
+
+    pub fn set_velocity(&mut self, id: EntityId, velocity: Vec3) -> bool {
+        if !self.is_alive(id) {
+            return false;
+        }
+        self.velocities[id.index()] = velocity;
+        if self.gpu_resident {
+            self.gpu_velocity_writes.push(id.index());
+        }
+        true
+    }
+
+    /// Seconds since the particle was spawned.
+    pub fn age(&self, id: EntityId) -> Option<f32> {
+        self.is_alive(id).then(|| self.ages[id.index()])
+    }
+
+    /// Ids and positions of the live particles.
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, Vec3)> + '_ {
+        (0..self.alive.len())
+            .filter(|&index| self.alive[index])
+            .map(|index| {
+                (
+                    EntityId::new(index, self.generations[index]),
+                    self.positions[index],
+                )
+            })
+    }
+
+    /// Links two live particles with a spring at their current distance.
+    pub fn add_link(&mut self, a: EntityId, b: EntityId, stiffness: f32, damping: f32) -> bool {
+        if a == b || !self.is_alive(a) || !self.is_alive(b) {
+            return false;
+        }
+        self.links.push(ParticleLink {
+            a,
+            b,
+            rest_length: self.positions[a.index()].distance(self.positions[b.index()]),
+            stiffness,
+            damping,
+        });
+        true
+    }
+
+    pub fn links(&self) -> &[ParticleLink] {
+        &self.links
+    }
+
+    pub fn clear(&mut self) {
+        for index in 0..self.alive.len() {
+            if self.alive[index] {
+                self.kill(index);
+            }
+        }
+        self.links.clear();
+    }
+
+    /// Whether the world's compute backend currently steps the particles.
+    pub fn is_gpu_resident(&self) -> bool {
+        self.gpu_resident
+    }
+
+    /// Moving onto the GPU queues every slot for upload. Moving off leaves the CPU copies
+    /// as they were last set on the CPU.
+    pub(crate) fn set_gpu_resident(&mut self, resident: bool) {
+        if resident == self.gpu_resident {
+            return;
+        }
+        if resident {
+            self.gpu_writes = (0..self.alive.len()).collect();
+            self.gpu_max_radius = (0..self.alive.len())
+                .filter(|&index| self.alive[index])
+                .map(|index| self.radii[index])
+                .fold(0.0, f32::max);
+        } else {
+            self.gpu_writes.clear();
+        }
+        self.gpu_velocity_writes.clear();
+        self.gpu_resident = resident;
+    }
+
+    /// Moves the slots changed since the last call into `state`.
+    pub(crate) fn take_gpu_writes(&mut self, state: &mut GpuParticleState) {
+        state.slot_count = self.alive.len() as u32;
+        state.cell_size = if self.collisions {
+            2.0 * self.gpu_max_radius
+        } else {
+            0.0
+        };
+
+        self.gpu_writes.sort_unstable();
+        self.gpu_writes.dedup();
+        state.writes.clear();
+        state.writes.extend(self.gpu_writes.drain(..).map(|index| {
+            let particle = if self.alive[index] {
+                GpuParticle {
+                    position: self.positions[index],
+                    radius: self.radii[index],
+                    velocity: self.velocities[index],
+                    inverse_mass: self.inverse_masses[index],
+                }
+            } else {
+                GpuParticle::DEAD
+            };
+            (index as u32, particle)
+        }));
+
+        self.gpu_velocity_writes.sort_unstable();
+        self.gpu_velocity_writes.dedup();
+        state.velocity_writes.clear();
+        state.velocity_writes.extend(
+            self.gpu_velocity_writes
+                .drain(..)
+                .filter(|&index| self.alive[index])
+                .map(|index| (index as u32, self.velocities[index])),
+        );
+    }
+
+    /// Ages, accelerates and moves every particle, then resolves collider contacts.
+    pub fn step(
+        &mut self,
+        dt: f32,
+        gravity: Vec3,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+    ) {
+        if self.count == 0 {
+            return;
+        }
+        self.advance_ages(dt);
+
+        for link in &self.links {
+            let (a, b) = (link.a.index(), link.b.index());
+            let offset = self.positions[b] - self.positions[a];
+            let Some(direction) = offset.try_normalize() else {
+                continue;
+            };
+            let stretch_speed = (self.velocities[b] - self.velocities[a]).dot(direction);
+            let tension = link.stiffness * (offset.length() - link.rest_length)
+                + link.damping * stretch_speed;
+            let impulse = direction * tension * dt;
+            self.velocities[a] += impulse * self.inverse_masses[a];
+            self.velocities[b] -= impulse * self.inverse_masses[b];
+        }
+
+        let damping = (1.0 - self.damping * dt).max(0.0);
+        for index in 0..self.alive.len() {
+            if !self.alive[index] {
+                continue;
+            }
+            if self.inverse_masses[index] > 0.0 {
+                self.velocities[index] = (self.velocities[index] + gravity * dt) * damping;
+            }
+            self.positions[index] += self.velocities[index] * dt;
+        }
+
+        if self.collisions {
+            self.collide(colliders, bodies);
+        }
+    }
+
+    /// Ages every particle and despawns those past their lifetime, with their links.
+    pub(crate) fn advance_ages(&mut self, dt: f32) {
+        let mut expired = false;
+        for index in 0..self.alive.len() {
+            if !self.alive[index] {
+                continue;
+            }
+            self.ages[index] += dt;
+            if self.lifetimes[index].is_some_and(|lifetime| self.ages[index] >= lifetime) {
+                self.kill(index);
+                expired = true;
+            }
+        }
+        if expired {
+            let (alive, generations) = (&self.alive, &self.generations);
+            let live =
+                |id: EntityId| alive[id.index()] && generations[id.index()] == id.generation();
+            self.links.retain(|link| live(link.a) && live(link.b));
+        }
+    }
+
+    /// Pushes particles out of colliders and reflects their velocity off the surface.
+    fn collide(&mut self, colliders: &Arena<Collider>, bodies: &BodiesSoA) {
+        // Solid colliders with their bodies and bounding spheres, gathered once.
+        let mut obstacles = Vec::new();
+        for collider in colliders.iter() {
+            if collider.is_trigger {
+                continue;
+            }
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let transform = collider.world_transform(body.transform());
+            let reach =
+                collider.bounding_radius() * transform.scale.abs().max_element() + collider.margin;
+            obstacles.push((collider, body.to_rigid_body(), transform.position, reach));
+        }
+        if obstacles.is_empty() {
+            return;
+        }
+
+        let mut probe = Collider::builder().sphere(0.0).build();
+        let mut probe_body = RigidBody::new(EntityId::default());
+        for index in 0..self.alive.len() {
+            if !self.alive[index] {
+                continue;
+            }
+            let radius = self.radii[index];
+            probe.shape = ColliderShape::Sphere { radius };
+            for (collider, body, center, reach) in &obstacles {
+                let position = self.positions[index];
+                if position.distance_squared(*center) > (reach + radius).powi(2) {
+                    continue;
+                }
+                probe_body.transform.position = position;
+                let Some((contact, _)) =
+                    NarrowPhase::collide(&probe, &probe_body, collider, body, None)
+                else {
+                    continue;
+                };
+                // The contact normal points from the particle into the collider.
+                let normal = -contact.normal;
+                self.positions[index] += normal * contact.depth;
+
+                let surface_velocity = body.velocity.linear
+                    + body
+                        .velocity
+                        .angular
+                        .cross(contact.point - body.transform.position);
+                let relative = self.velocities[index] - surface_velocity;
+                let approach = relative.dot(normal);
+                if approach < 0.0 {
+                    let tangential = relative - normal * approach;
+                    self.velocities[index] = surface_velocity
+                        + tangential * (1.0 - self.friction).clamp(0.0, 1.0)
+                        - normal * approach * self.restitution;
+                }
+            }
+        }
+    }
+
+    fn kill(&mut self, index: usize) {
+        self.alive[index] = false;
+        self.generations[index] = self.generations[index].wrapping_add(1);
+        self.free_list.push_back(index);
+        self.count -= 1;
+        if self.gpu_resident {
+            self.gpu_writes.push(index);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Building blocks shared by the position-based cloth and soft body solvers.
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{narrowphase::NarrowPhase, shapes::ShapeUtil},
+    core::{
+        collider::{Collider, ColliderShape},
+        rigidbody::{BodyType, RigidBody},
+        soa::BodiesSoA,
+        types::Transform,
+    },
+    utils::allocator::{Arena, EntityId},
+};
+
+/// Impulse owed to a dynamic body, and the world point it acts at.
+pub(crate) type Reaction = (EntityId, Vec3, Vec3);
+
+/// Point masses moved by position projection, stored as structure-of-arrays.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct Vertices {
+    pub(crate) positions: Vec<Vec3>,
+    /// Positions at the start of the current substep.
+    pub(crate) previous: Vec<Vec3>,
+    pub(crate) velocities: Vec<Vec3>,
+    pub(crate) masses: Vec<f32>,
+    /// Zero for pinned vertices.
+    pub(crate) inverse_masses: Vec<f32>,
+}
+
+impl Vertices {
+    pub(crate) fn new(positions: Vec<Vec3>, masses: Vec<f32>) -> Self {
+        let inverse_masses = masses.iter().map(|&mass| inverse(mass)).collect();
+        Self {
+            previous: positions.clone(),
+            velocities: vec![Vec3::ZERO; positions.len()],
+            positions,
+            masses,
+            inverse_masses,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub(crate) fn pin(&mut self, vertex: usize) -> bool {
+        if vertex >= self.len() {
+            return false;
+        }
+        self.inverse_masses[vertex] = 0.0;
+        self.velocities[vertex] = Vec3::ZERO;
+        true
+    }
+
+    pub(crate) fn unpin(&mut self, vertex: usize) -> bool {
+        let Some(&mass) = self.masses.get(vertex) else {
+            return false;
+        };
+        self.inverse_masses[vertex] = inverse(mass);
+        true
+    }
+
+    pub(crate) fn is_pinned(&self, vertex: usize) -> bool {
+        self.inverse_masses
+            .get(vertex)
+            .is_some_and(|&inverse_mass| inverse_mass == 0.0)
+    }
+
+    pub(crate) fn set_position(&mut self, vertex: usize, position: Vec3) -> bool {
+        if vertex >= self.len() {
+            return false;
+        }
+        self.positions[vertex] = position;
+        self.previous[vertex] = position;
+        self.velocities[vertex] = Vec3::ZERO;
+        true
+    }
+
+    /// Accelerates the free vertices and moves every vertex along its velocity.
+    pub(crate) fn predict(&mut self, h: f32, gravity: Vec3, damping: f32) {
+        let damping = (1.0 - damping * h).max(0.0);
+        for index in 0..self.len() {
+            if self.inverse_masses[index] > 0.0 {
+                self.velocities[index] = (self.velocities[index] + gravity * h) * damping;
+            }
+            self.previous[index] = self.positions[index];
+            self.positions[index] += self.velocities[index] * h;
+        }
+    }
+
+    /// Derives velocities from how far each vertex moved this substep.
+    pub(crate) fn update_velocities(&mut self, h: f32) {
+        for index in 0..self.len() {
+            self.velocities[index] = (self.positions[index] - self.previous[index]) / h;
+        }
+    }
+
+    /// One XPBD pass over `constraints` with `alpha` = compliance / h².
+    pub(crate) fn solve_distances(&mut self, constraints: &[DistanceConstraint], alpha: f32) {
+        for constraint in constraints {
+            let (a, b) = (constraint.a, constraint.b);
+            let weight = self.inverse_masses[a] + self.inverse_masses[b];
+            if weight == 0.0 {
+                continue;
+            }
+            let offset = self.positions[b] - self.positions[a];
+            let length = offset.length();
+            if length < f32::EPSILON {
+                continue;
+            }
+            let lambda = (length - constraint.rest_length) / (weight + alpha);
+            let correction = offset / length * lambda;
+            self.positions[a] += correction * self.inverse_masses[a];
+            self.positions[b] -= correction * self.inverse_masses[b];
+        }
+    }
+
+    /// Pushes vertices of `radius` out of colliders with Coulomb friction against the
+    /// surface motion, recording the reaction on each dynamic body touched.
+    pub(crate) fn collide(
+        &mut self,
+        obstacles: &[Obstacle<'_>],
+        radius: f32,
+        friction: f32,
+        h: f32,
+        reactions: &mut Vec<Reaction>,
+    ) {
+        if obstacles.is_empty() {
+            return;
+        }
+        let probe = Collider::builder()
+            .shape(ColliderShape::Sphere { radius })
+            .build();
+        let mut probe_body = RigidBody::new(EntityId::default());
+        for index in 0..self.len() {
+            if self.inverse_masses[index] == 0.0 {
+                continue;
+            }
+            for obstacle in obstacles {
+                let position = self.positions[index];
+                let transform = &obstacle.transform;
+                if position.distance_squared(transform.position) > (obstacle.reach + radius).powi(2)
+                {
+                    continue;
+                }
+                // Primitives rule most vertices out with a cheap distance check.
+                let local = transform.rotation.inverse() * (position - transform.position)
+                    / transform.scale;
+                if ShapeUtil::signed_distance(&obstacle.collider.shape, local).is_some_and(
+                    |distance| {
+                        distance * transform.scale.abs().min_element()
+                            > radius + obstacle.collider.margin
+                    },
+                ) {
+                    continue;
+                }
+                probe_body.transform.position = position;
+                let Some((contact, _)) = NarrowPhase::collide(
+                    &probe,
+                    &probe_body,
+                    obstacle.collider,
+                    &obstacle.body,
+                    None,
+                ) else {
+                    continue;
+                };
+                // The contact normal points from the vertex into the collider.
+                let normal = -contact.normal;
+                let mut corrected = position + normal * contact.depth;
+
+                let body = &obstacle.body;
+                let surface_velocity = body.velocity.linear
+                    + body
+                        .velocity
+                        .angular
+                        .cross(contact.point - body.transform.position);
+                let slip = corrected - self.previous[index] - surface_velocity * h;
+                let tangential = slip - normal * slip.dot(normal);
+                let limit = friction * contact.depth;
+                let length = tangential.length();
+                corrected -= if length <= limit {
+                    tangential
+                } else {
+                    tangential * (limit / length)
+                };
+
+                self.positions[index] = corrected;
+                if obstacle.dynamic {
+                    reactions.push((
+                        body.id,
+                        -(corrected - position) * self.masses[index] / h,
+                        contact.point,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct DistanceConstraint {
+    pub(crate) a: usize,
+    pub(crate) b: usize,
+    pub(crate) rest_length: f32,
+}
+
+impl DistanceConstraint {
+    pub(crate) fn new(positions: &[Vec3], a: usize, b: usize) -> Self {
+        Self {
+            a,
+            b,
+            rest_length: positions[a].distance(positions[b]),
+        }
+    }
+}
+
+/// A solid collider with a snapshot of its body, gathered once per step.
+pub(crate) struct Obstacle<'a> {
+    collider: &'a Collider,
+    body: RigidBody,
+    transform: Transform,
+    reach: f32,
+    dynamic: bool,
+}
+
+pub(crate) fn gather_obstacles<'a>(
+    colliders: &'a Arena<Collider>,
+    bodies: &BodiesSoA,
+) -> Vec<Obstacle<'a>> {
+    colliders
+        .iter()
+        .filter(|collider| !collider.is_trigger)
+        .filter_map(|collider| {
+            let body = bodies.get(collider.rigidbody_id)?;
+            let transform = collider.world_transform(body.transform());
+            Some(Obstacle {
+                collider,
+                transform,
+                reach: collider.bounding_radius() * transform.scale.abs().max_element()
+                    + collider.margin,
+                dynamic: body.body_type() == BodyType::Dynamic,
+                body: body.to_rigid_body(),
+            })
+        })
+        .collect()
+}
+
+/// Applies the recorded reactions, scaled by `coupling` in `[0, 1]`.
+pub(crate) fn apply_reactions(bodies: &mut BodiesSoA, reactions: Vec<Reaction>, coupling: f32) {
+    let coupling = coupling.clamp(0.0, 1.0);
+    if coupling == 0.0 {
+        return;
+    }
+    for (body, impulse, point) in reactions {
+        if let Some(mut body) = bodies.get_mut(body) {
+            body.apply_impulse(impulse * coupling, point);
+        }
+    }
+}
+
+fn inverse(mass: f32) -> f32 {
+    if mass > 0.0 {
+        1.0 / mass
+    } else {
+        0.0
+    }
+}
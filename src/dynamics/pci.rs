@@ -1,3 +1,5 @@
+use glam::Vec3;
+
 use crate::{core::soa::BodiesSoA, dynamics::solver::Contact};
 
 /// Predictive-corrective integrator that damps future interpenetrations.
@@ -9,7 +11,7 @@ pub struct PredictiveCorrectiveIntegrator {
 
 impl Default for PredictiveCorrectiveIntegrator {
     fn default() -> Self {
-        Self::new(0)
+        Self::new(4)
     }
 }
 
@@ -25,6 +27,8 @@ impl PredictiveCorrectiveIntegrator {
         self.correction_iterations = iterations;
     }
 
+    /// Adds separating velocity wherever a contact would still overlap by more than the
+    /// slop after moving for `dt` at the current velocities.
     pub fn apply(&self, bodies: &mut BodiesSoA, contacts: &[Contact], dt: f32) {
         if self.correction_iterations == 0 {
             return;
@@ -34,9 +38,11 @@ impl PredictiveCorrectiveIntegrator {
         }
         let dt = dt.max(1e-4);
 
-        // Loop over iterations
+        // Impulses accumulate per contact and never pull, so later iterations can take
+        // back what an earlier one overshot.
+        let mut accumulated = vec![0.0f32; contacts.len()];
         for _ in 0..self.correction_iterations {
-            for contact in contacts {
+            for (contact, total) in contacts.iter().zip(&mut accumulated) {
                 let (body_a, body_b) = match bodies.get2_mut(contact.body_a, contact.body_b) {
                     Some(pair) => pair,
                     None => continue,
@@ -47,63 +53,36 @@ impl PredictiveCorrectiveIntegrator {
                 }
 
                 let normal = contact.normal;
-
-                // 1. Calculate relative velocity at contact point
                 let r_a = contact.point - body_a.transform.position;
                 let r_b = contact.point - body_b.transform.position;
 
                 let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(r_a);
                 let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(r_b);
-                let rel_vel = v_b - v_a;
-                let vel_along_normal = rel_vel.dot(normal);
+                let vel_along_normal = (v_b - v_a).dot(normal);
 
-                // 2. Predict penetration at end of frame
-                // depth is positive for penetration. contact.depth is current penetration.
-                // If velocity is separating (vel_along_normal > 0), penetration decreases.
-                // predicted_penetration = current_penetration - vel_along_normal * dt
-                // Note: Speculative contacts might have negative depth (separation).
+                // Speculative contacts have negative depth and only count once closing.
                 let predicted_penetration = contact.depth - vel_along_normal * dt;
+                let velocity_error = (predicted_penetration - self.penetration_slop) / dt;
 
-                if predicted_penetration <= self.penetration_slop {
-                    continue;
-                }
-
-                // 3. Solve for correction impulse
-                // The goal is to reduce predicted_penetration to penetration_slop.
-                // delta_penetration = predicted_penetration - slop
-                // required_vel_change = delta_penetration / dt
-
-                let penetration_error = predicted_penetration - self.penetration_slop;
-                let velocity_bias = penetration_error / dt;
-
-                // Compute effective mass (Inverse K matrix diagonal)
-                // Compute effective mass (Inverse K matrix diagonal)
                 let mut k_mass = *body_a.inverse_mass + *body_b.inverse_mass;
-
                 if !body_a.is_static() {
-                    let rn_a = r_a.cross(normal);
-                    let inertia_a = *body_a.inverse_inertia * rn_a;
+                    let inertia_a = *body_a.inverse_inertia * r_a.cross(normal);
                     k_mass += inertia_a.cross(r_a).dot(normal);
                 }
-
                 if !body_b.is_static() {
-                    let rn_b = r_b.cross(normal);
-                    let inertia_b = *body_b.inverse_inertia * rn_b;
+                    let inertia_b = *body_b.inverse_inertia * r_b.cross(normal);
                     k_mass += inertia_b.cross(r_b).dot(normal);
                 }
-
                 if k_mass <= 1e-6 {
                     continue;
                 }
 
-                let impulse_mag = velocity_bias / k_mass;
-
-                // Clamp impulse??
-                // PCI usually accumulates or applies greedily?
-                // Standard implementation applies immediately.
-                // Impulse should be positive (pushing apart).
-                // If velocity_bias is positive (need to separate), impulse is positive.
-                let impulse = normal * impulse_mag;
+                let new_total = (*total + velocity_error / k_mass).max(0.0);
+                let impulse = normal * (new_total - *total);
+                *total = new_total;
+                if impulse == Vec3::ZERO {
+                    continue;
+                }
 
                 if !body_a.is_static() {
                     body_a.velocity.linear -= impulse * (*body_a.inverse_mass);
@@ -0,0 +1,458 @@
+//! Four-lane contact solver.
+//!
+//! Contacts are packed into batches of four that share no dynamic body, so every lane of
+//! a batch can read and write its bodies' velocities at once. Each batch then runs the
+//! same sequential impulse steps as [`PGSSolver`](super::PGSSolver) does point by point:
+//! normal impulse, Coulomb friction, rolling and torsional resistance.
+
+use std::collections::HashMap;
+
+use glam::{Mat3, Vec3, Vec4};
+
+use crate::{
+    dynamics::solver::{BlockBody, Contact, ContactPrep},
+    utils::{
+        allocator::EntityId,
+        simd::{Mat3x4, Vec3x4},
+    },
+};
+
+pub(super) const LANES: usize = 4;
+
+/// Body slot every padding lane points at. It never moves, so padding lanes apply nothing.
+const PADDING: usize = 0;
+
+/// Contacts of one solve, batched for the four-lane solver.
+pub(super) struct WideContacts {
+    /// Velocity state of every body the batches touch, with [`PADDING`] first.
+    bodies: Vec<BlockBody>,
+    /// Id of each entry of `bodies`.
+    ids: Vec<EntityId>,
+    /// Whether each entry of `bodies` is fixed, see [`is_fixed`].
+    fixed: Vec<bool>,
+    batches: Vec<ContactBatch>,
+    /// Contacts left to the scalar solver, in `contacts` order: those whose friction
+    /// depends on body orientation or slip speed, and those between two fixed bodies.
+    scalar: Vec<usize>,
+}
+
+/// A contact and the slots of its bodies.
+#[derive(Clone, Copy)]
+struct Lane {
+    contact: usize,
+    a: usize,
+    b: usize,
+}
+
+/// Terms of one lane, read from its contact before being spread over the batch.
+#[derive(Clone, Copy, Default)]
+struct LaneTerms {
+    normal: Vec3,
+    prep: ContactPrep,
+    static_friction: Vec3,
+    dynamic_friction: Vec3,
+    rolling_friction: f32,
+    torsional_friction: f32,
+    normal_impulse: f32,
+    tangent_impulse: Vec3,
+    rolling_impulse: Vec3,
+    torsional_impulse: f32,
+}
+
+impl LaneTerms {
+    fn new(contact: &Contact, prep: &ContactPrep) -> Self {
+        Self {
+            normal: contact.normal,
+            prep: *prep,
+            static_friction: contact.material.static_friction,
+            dynamic_friction: contact.material.dynamic_friction,
+            rolling_friction: contact.material.rolling_friction,
+            torsional_friction: contact.material.torsional_friction,
+            normal_impulse: contact.accumulated_normal_impulse,
+            tangent_impulse: contact.accumulated_tangent_impulse,
+            rolling_impulse: contact.accumulated_rolling_impulse,
+            torsional_impulse: contact.accumulated_torsional_impulse,
+        }
+    }
+}
+
+/// Four contacts with their solver terms laid out one lane each.
+struct ContactBatch {
+    /// Index of each lane's contact; `None` for padding.
+    contacts: [Option<usize>; LANES],
+    body_a: [usize; LANES],
+    body_b: [usize; LANES],
+    normal: Vec3x4,
+    r_a: Vec3x4,
+    r_b: Vec3x4,
+    /// Angular velocity change per unit normal impulse, `I⁻¹ (r × n)`.
+    normal_angular_a: Vec3x4,
+    normal_angular_b: Vec3x4,
+    inverse_mass_a: Vec4,
+    inverse_mass_b: Vec4,
+    inverse_inertia_a: Mat3x4,
+    inverse_inertia_b: Mat3x4,
+    normal_mass: Vec4,
+    restitution: Vec4,
+    bias: Vec4,
+    static_friction: Vec3x4,
+    dynamic_friction: Vec3x4,
+    rolling_friction: Vec4,
+    torsional_friction: Vec4,
+    /// Effective mass of a twist about the normal, inverted.
+    twist_mass: Vec4,
+    normal_impulse: Vec4,
+    tangent_impulse: Vec3x4,
+    rolling_impulse: Vec3x4,
+    torsional_impulse: Vec4,
+}
+
+impl WideContacts {
+    /// Batches `contacts`, reading each body once through `body`.
+    ///
+    /// `preps` holds the terms of each contact in `contacts` order. Contacts with a missing
+    /// body are left out, as the scalar solver skips them too.
+    pub(super) fn new(
+        contacts: &[Contact],
+        preps: &[ContactPrep],
+        mut body: impl FnMut(EntityId) -> Option<BlockBody>,
+    ) -> Self {
+        let mut wide = Self {
+            bodies: vec![BlockBody::fixed(Vec3::ZERO)],
+            ids: vec![EntityId::default()],
+            fixed: vec![true],
+            batches: Vec::new(),
+            scalar: Vec::new(),
+        };
+        let mut slots: HashMap<EntityId, usize> = HashMap::with_capacity(contacts.len());
+        let mut slot = |id: EntityId, wide: &mut Self| -> Option<usize> {
+            if let Some(&index) = slots.get(&id) {
+                return Some(index);
+            }
+            let state = body(id)?;
+            wide.fixed.push(is_fixed(&state));
+            wide.bodies.push(state);
+            wide.ids.push(id);
+            slots.insert(id, wide.bodies.len() - 1);
+            Some(wide.bodies.len() - 1)
+        };
+
+        // Lanes of each batch, and the first batch each dynamic body is free in again.
+        let mut lanes: Vec<[Option<Lane>; LANES]> = Vec::new();
+        let mut filled: Vec<usize> = Vec::new();
+        let mut next_free: Vec<usize> = Vec::new();
+        let mut first_open = 0;
+        // Manifolds are contiguous, so most contacts reuse the previous pair's slots.
+        let mut previous = None;
+        for (index, contact) in contacts.iter().enumerate() {
+            let pair = match previous {
+                Some((a, b, pair)) if a == contact.body_a && b == contact.body_b => pair,
+                _ => slot(contact.body_a, &mut wide).zip(slot(contact.body_b, &mut wide)),
+            };
+            previous = Some((contact.body_a, contact.body_b, pair));
+            let Some((a, b)) = pair else {
+                continue;
+            };
+            let (fixed_a, fixed_b) = (wide.fixed[a], wide.fixed[b]);
+            let material = &contact.material;
+            if (fixed_a && fixed_b)
+                || material.stribeck.is_some()
+                || material.anisotropic_friction.iter().any(Option::is_some)
+            {
+                wide.scalar.push(index);
+                continue;
+            }
+            next_free.resize(wide.bodies.len(), 0);
+            // Later batches run later, so a body's contacts keep their order.
+            let earliest = match (fixed_a, fixed_b) {
+                (false, false) => next_free[a].max(next_free[b]),
+                (false, true) => next_free[a],
+                _ => next_free[b],
+            };
+            let mut batch = earliest.max(first_open);
+            while batch < lanes.len() && filled[batch] == LANES {
+                batch += 1;
+            }
+            if batch == lanes.len() {
+                lanes.push([None; LANES]);
+                filled.push(0);
+            }
+            lanes[batch][filled[batch]] = Some(Lane {
+                contact: index,
+                a,
+                b,
+            });
+            filled[batch] += 1;
+            for (slot, fixed) in [(a, fixed_a), (b, fixed_b)] {
+                if !fixed {
+                    next_free[slot] = batch + 1;
+                }
+            }
+            while first_open < lanes.len() && filled[first_open] == LANES {
+                first_open += 1;
+            }
+        }
+
+        wide.batches = lanes
+            .iter()
+            .map(|lanes| wide.pack(lanes, contacts, preps))
+            .collect();
+        wide
+    }
+
+    fn pack(
+        &self,
+        lanes: &[Option<Lane>; LANES],
+        contacts: &[Contact],
+        preps: &[ContactPrep],
+    ) -> ContactBatch {
+        let terms = lanes.map(|lane| {
+            lane.map_or(LaneTerms::default(), |lane| {
+                LaneTerms::new(&contacts[lane.contact], &preps[lane.contact])
+            })
+        });
+        let body_a = lanes.map(|lane| lane.map_or(PADDING, |lane| lane.a));
+        let body_b = lanes.map(|lane| lane.map_or(PADDING, |lane| lane.b));
+
+        let normal = vectors(|i| terms[i].normal);
+        let r_a = vectors(|i| terms[i].prep.r_a);
+        let r_b = vectors(|i| terms[i].prep.r_b);
+        let inverse_inertia_a =
+            Mat3x4::from_array(body_a.map(|slot| self.bodies[slot].inverse_inertia));
+        let inverse_inertia_b =
+            Mat3x4::from_array(body_b.map(|slot| self.bodies[slot].inverse_inertia));
+        let twist = inverse_inertia_a.mul_vec3(normal).dot(normal)
+            + inverse_inertia_b.mul_vec3(normal).dot(normal)
+            + Vec4::splat(1e-6);
+        ContactBatch {
+            contacts: lanes.map(|lane| lane.map(|lane| lane.contact)),
+            body_a,
+            body_b,
+            normal,
+            r_a,
+            r_b,
+            normal_angular_a: inverse_inertia_a.mul_vec3(r_a.cross(normal)),
+            normal_angular_b: inverse_inertia_b.mul_vec3(r_b.cross(normal)),
+            inverse_mass_a: Vec4::from_array(body_a.map(|slot| self.bodies[slot].inverse_mass)),
+            inverse_mass_b: Vec4::from_array(body_b.map(|slot| self.bodies[slot].inverse_mass)),
+            inverse_inertia_a,
+            inverse_inertia_b,
+            normal_mass: scalars(|i| terms[i].prep.normal_mass),
+            restitution: scalars(|i| terms[i].prep.restitution),
+            bias: scalars(|i| terms[i].prep.bias),
+            static_friction: vectors(|i| terms[i].static_friction),
+            dynamic_friction: vectors(|i| terms[i].dynamic_friction),
+            rolling_friction: scalars(|i| terms[i].rolling_friction),
+            torsional_friction: scalars(|i| terms[i].torsional_friction),
+            twist_mass: twist.recip(),
+            normal_impulse: scalars(|i| terms[i].normal_impulse),
+            tangent_impulse: vectors(|i| terms[i].tangent_impulse),
+            rolling_impulse: vectors(|i| terms[i].rolling_impulse),
+            torsional_impulse: scalars(|i| terms[i].torsional_impulse),
+        }
+    }
+
+    /// Contacts the batches leave to the scalar solver, by index into `contacts`.
+    pub(super) fn scalar_contacts(&self) -> &[usize] {
+        &self.scalar
+    }
+
+    /// Runs one velocity iteration over every batch.
+    pub(super) fn resolve(&mut self) {
+        for batch in &mut self.batches {
+            batch.resolve(&mut self.bodies);
+        }
+    }
+
+    /// Writes the velocities of the dynamic bodies back through `write`.
+    pub(super) fn scatter(&self, mut write: impl FnMut(EntityId, Vec3, Vec3)) {
+        for ((id, body), fixed) in self.ids.iter().zip(&self.bodies).zip(&self.fixed) {
+            if !fixed {
+                write(*id, body.linear, body.angular);
+            }
+        }
+    }
+
+    /// Reloads the velocities of the dynamic bodies, after joints or scalar contacts moved them.
+    pub(super) fn gather(&mut self, mut read: impl FnMut(EntityId) -> Option<(Vec3, Vec3)>) {
+        for ((id, body), fixed) in self.ids.iter().zip(&mut self.bodies).zip(&self.fixed) {
+            if *fixed {
+                continue;
+            }
+            if let Some((linear, angular)) = read(*id) {
+                body.linear = linear;
+                body.angular = angular;
+            }
+        }
+    }
+
+    /// Stores the accumulated impulses in their contacts for warm starting.
+    pub(super) fn store_impulses(&self, contacts: &mut [Contact]) {
+        for batch in &self.batches {
+            let normal = batch.normal_impulse.to_array();
+            let tangent = batch.tangent_impulse.to_array();
+            let rolling = batch.rolling_impulse.to_array();
+            let torsional = batch.torsional_impulse.to_array();
+            for (lane, index) in batch.contacts.iter().enumerate() {
+                if let Some(contact) = index.map(|index| &mut contacts[index]) {
+                    contact.accumulated_normal_impulse = normal[lane];
+                    contact.accumulated_tangent_impulse = tangent[lane];
+                    contact.accumulated_rolling_impulse = rolling[lane];
+                    contact.accumulated_torsional_impulse = torsional[lane];
+                }
+            }
+        }
+    }
+}
+
+impl ContactBatch {
+    fn resolve(&mut self, bodies: &mut [BlockBody]) {
+        let a = self
+            .body_a
+            .map(|slot| (bodies[slot].linear, bodies[slot].angular));
+        let b = self
+            .body_b
+            .map(|slot| (bodies[slot].linear, bodies[slot].angular));
+        let mut linear_a = Vec3x4::from_array(a.map(|(linear, _)| linear));
+        let mut angular_a = Vec3x4::from_array(a.map(|(_, angular)| angular));
+        let mut linear_b = Vec3x4::from_array(b.map(|(linear, _)| linear));
+        let mut angular_b = Vec3x4::from_array(b.map(|(_, angular)| angular));
+        let zero = Vec4::ZERO;
+        let n = self.normal;
+
+        // Normal impulse; separating lanes skip friction as well.
+        let relative = linear_b
+            .add(angular_b.cross(self.r_b))
+            .sub(linear_a.add(angular_a.cross(self.r_a)));
+        let approach = relative.dot(n);
+        let active = approach.cmplt(zero);
+        if !active.any() {
+            return;
+        }
+        let lambda = -(approach * (Vec4::ONE + self.restitution) - self.bias) * self.normal_mass;
+        let total = (self.normal_impulse + lambda).max(zero);
+        let delta = Vec4::select(active, total - self.normal_impulse, zero);
+        self.normal_impulse = Vec4::select(active, total, self.normal_impulse);
+        linear_a = linear_a.sub(n.scale(delta * self.inverse_mass_a));
+        angular_a = angular_a.sub(self.normal_angular_a.scale(delta));
+        linear_b = linear_b.add(n.scale(delta * self.inverse_mass_b));
+        angular_b = angular_b.add(self.normal_angular_b.scale(delta));
+        let normal_impulse = self.normal_impulse;
+        let loaded = active & normal_impulse.cmpgt(Vec4::splat(f32::EPSILON));
+
+        // Coulomb friction, clamped to the static cone and dropped to dynamic friction
+        // once it slips.
+        self.tangent_impulse =
+            Vec3x4::select(active & !loaded, Vec3x4::zero(), self.tangent_impulse);
+        let relative = linear_b
+            .add(angular_b.cross(self.r_b))
+            .sub(linear_a.add(angular_a.cross(self.r_a)));
+        let slip = relative.sub(n.scale(relative.dot(n)));
+        let mut wanted = self.tangent_impulse.sub(slip.scale(self.normal_mass));
+        wanted = wanted.sub(n.scale(wanted.dot(n)));
+        let length = wanted.length();
+        let direction = wanted.scale(Vec4::ONE / length.max(Vec4::splat(1e-6)));
+        let coefficient = |friction: Vec3x4| {
+            let along = Vec3x4 {
+                x: friction.x.abs(),
+                y: friction.y.abs(),
+                z: friction.z.abs(),
+            }
+            .dot(Vec3x4 {
+                x: direction.x.abs(),
+                y: direction.y.abs(),
+                z: direction.z.abs(),
+            });
+            let largest = friction.x.abs().max(friction.y.abs()).max(friction.z.abs());
+            Vec4::select(length.cmpgt(Vec4::splat(1e-6)), along, largest)
+        };
+        let mu_static = coefficient(self.static_friction);
+        let mu_dynamic = coefficient(self.dynamic_friction).min(mu_static);
+        let limit = Vec4::select(
+            length.cmpgt(mu_static * normal_impulse),
+            mu_dynamic.max(zero) * normal_impulse,
+            length,
+        );
+        let clamped = Vec3x4::select(
+            length.cmpgt(zero),
+            wanted.scale(limit / length.max(Vec4::splat(f32::MIN_POSITIVE))),
+            wanted,
+        );
+        let change = clamped.sub(self.tangent_impulse);
+        let apply = loaded & change.dot(change).cmpgt(Vec4::splat(1e-12));
+        let change = Vec3x4::select(apply, change, Vec3x4::zero());
+        self.tangent_impulse = Vec3x4::select(loaded, clamped, self.tangent_impulse);
+        linear_a = linear_a.sub(change.scale(self.inverse_mass_a));
+        angular_a = angular_a.sub(self.inverse_inertia_a.mul_vec3(self.r_a.cross(change)));
+        linear_b = linear_b.add(change.scale(self.inverse_mass_b));
+        angular_b = angular_b.add(self.inverse_inertia_b.mul_vec3(self.r_b.cross(change)));
+
+        // Rolling resistance about the axis the bodies roll over each other on.
+        let limit = self.rolling_friction.max(zero) * normal_impulse;
+        let resists = limit.cmpgt(Vec4::splat(f32::EPSILON));
+        self.rolling_impulse =
+            Vec3x4::select(active & !resists, Vec3x4::zero(), self.rolling_impulse);
+        let spin = angular_b.sub(angular_a);
+        let axis = spin.sub(n.scale(spin.dot(n))).normalize_or_zero();
+        let effective = self.inverse_inertia_a.mul_vec3(axis).dot(axis)
+            + self.inverse_inertia_b.mul_vec3(axis).dot(axis)
+            + Vec4::splat(1e-6);
+        let desired = self
+            .rolling_impulse
+            .sub(axis.scale(axis.dot(spin) / effective));
+        let length = desired.length();
+        let clamped = Vec3x4::select(
+            length.cmpgt(limit),
+            desired.scale(limit / length.max(Vec4::splat(f32::MIN_POSITIVE))),
+            desired,
+        );
+        let change = clamped.sub(self.rolling_impulse);
+        let apply = active
+            & resists
+            & axis.dot(axis).cmpgt(zero)
+            & change.dot(change).cmpgt(Vec4::splat(1e-12));
+        let change = Vec3x4::select(apply, change, Vec3x4::zero());
+        self.rolling_impulse = Vec3x4::select(apply, clamped, self.rolling_impulse);
+        angular_a = angular_a.sub(self.inverse_inertia_a.mul_vec3(change));
+        angular_b = angular_b.add(self.inverse_inertia_b.mul_vec3(change));
+
+        // Torsional resistance about the normal.
+        let limit = self.torsional_friction.max(zero) * normal_impulse;
+        let resists = limit.cmpgt(Vec4::splat(f32::EPSILON));
+        self.torsional_impulse = Vec4::select(active & !resists, zero, self.torsional_impulse);
+        let twist = angular_b.sub(angular_a).dot(n);
+        let desired = (self.torsional_impulse - twist * self.twist_mass).clamp(-limit, limit);
+        let change = desired - self.torsional_impulse;
+        let apply = active & resists & change.abs().cmpgt(Vec4::splat(1e-10));
+        let change = Vec4::select(apply, change, zero);
+        self.torsional_impulse = Vec4::select(apply, desired, self.torsional_impulse);
+        angular_a = angular_a.sub(self.inverse_inertia_a.mul_vec3(n.scale(change)));
+        angular_b = angular_b.add(self.inverse_inertia_b.mul_vec3(n.scale(change)));
+
+        // Fixed bodies get back exactly what they had, as nothing moves them.
+        let store =
+            |bodies: &mut [BlockBody], slots: &[usize; LANES], linear: Vec3x4, angular: Vec3x4| {
+                for ((slot, linear), angular) in
+                    slots.iter().zip(linear.to_array()).zip(angular.to_array())
+                {
+                    bodies[*slot].linear = linear;
+                    bodies[*slot].angular = angular;
+                }
+            };
+        store(bodies, &self.body_a, linear_a, angular_a);
+        store(bodies, &self.body_b, linear_b, angular_b);
+    }
+}
+
+pub(super) fn vectors(lane: impl Fn(usize) -> Vec3) -> Vec3x4 {
+    Vec3x4::from_array(std::array::from_fn(lane))
+}
+
+pub(super) fn scalars(lane: impl Fn(usize) -> f32) -> Vec4 {
+    Vec4::from_array(std::array::from_fn(lane))
+}
+
+/// Whether no impulse can move the body, so any number of lanes may share it.
+fn is_fixed(body: &BlockBody) -> bool {
+    body.inverse_mass == 0.0 && body.inverse_inertia == Mat3::ZERO
+}
@@ -0,0 +1,425 @@
+//! Volumetric soft bodies: tetrahedral meshes solved with XPBD edge and volume constraints.
+//!
+//! Soft bodies share the cloth's contact model. Vertices are pushed out of the world's
+//! colliders, and dynamic bodies receive the reaction scaled by
+//! [`SoftBodyParams::coupling`], so a jelly block dropped on a plank pushes it down.
+//! Contacts are tested per vertex, so colliders smaller than the tetrahedra can slip
+//! between vertices.
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use glam::{IVec3, Mat3, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::pbd::{apply_reactions, gather_obstacles, DistanceConstraint, Vertices};
+use crate::{
+    core::{collider::Collider, mesh::TriangleMesh, soa::BodiesSoA},
+    utils::allocator::Arena,
+};
+
+/// Material and solver settings shared by every tetrahedron of a [`SoftBody`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SoftBodyParams {
+    /// Mass per cubic metre (kg/m³).
+    pub density: f32,
+    /// XPBD compliance of the edges (m/N); zero makes them rigid.
+    pub edge_compliance: f32,
+    /// XPBD compliance of each tetrahedron's volume; zero makes the body incompressible.
+    pub volume_compliance: f32,
+    /// Collision radius of each vertex (m).
+    pub thickness: f32,
+    /// Coulomb friction coefficient against colliders.
+    pub friction: f32,
+    /// Linear velocity damping per second.
+    pub damping: f32,
+    pub substeps: u32,
+    /// Share of each contact correction handed back to dynamic bodies as a reaction
+    /// impulse, from 0 (one-way) to 1 (momentum-conserving).
+    pub coupling: f32,
+}
+
+impl Default for SoftBodyParams {
+    fn default() -> Self {
+        Self {
+            density: 1000.0,
+            edge_compliance: 1e-3,
+            volume_compliance: 0.0,
+            thickness: 0.005,
+            friction: 0.5,
+            damping: 0.1,
+            substeps: 10,
+            coupling: 1.0,
+        }
+    }
+}
+
+/// Tetrahedral soft body registered with
+/// [`PhysicsWorld::add_soft_body`](crate::world::PhysicsWorld::add_soft_body).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SoftBody {
+    pub params: SoftBodyParams,
+    vertices: Vertices,
+    tetrahedra: Vec<[u32; 4]>,
+    rest_volumes: Vec<f32>,
+    edges: Vec<DistanceConstraint>,
+    /// Faces used by a single tetrahedron, wound to face outwards.
+    surface: Vec<[u32; 3]>,
+    /// Tetrahedron and barycentric weights carrying each vertex of the source mesh.
+    embedding: Vec<(usize, [f32; 4])>,
+}
+
+impl SoftBody {
+    /// Builds a soft body from a tetrahedral mesh. Every tetrahedron keeps its volume and
+    /// each of its edges keeps its length. Tetrahedra with out-of-range or repeated
+    /// indices are dropped, and inverted ones are flipped.
+    pub fn new(vertices: Vec<Vec3>, tetrahedra: Vec<[u32; 4]>, params: SoftBodyParams) -> Self {
+        let count = vertices.len();
+        let tetrahedra: Vec<[u32; 4]> = tetrahedra
+            .into_iter()
+            .filter(|tet| {
+                tet.iter().all(|&index| (index as usize) < count)
+                    && (0..4).all(|i| (i + 1..4).all(|j| tet[i] != tet[j]))
+            })
+            .map(|[a, b, c, d]| {
+                if signed_volume(&vertices, [a, b, c, d]) < 0.0 {
+                    [a, b, d, c]
+                } else {
+                    [a, b, c, d]
+                }
+            })
+            .collect();
+
+        let mut masses = vec![0.0; count];
+        let mut rest_volumes = Vec::with_capacity(tetrahedra.len());
+        let mut edges = Vec::new();
+        let mut faces: HashMap<[u32; 3], usize> = HashMap::new();
+        for &tet in &tetrahedra {
+            let volume = signed_volume(&vertices, tet);
+            rest_volumes.push(volume);
+            for index in tet {
+                masses[index as usize] += params.density * volume / 4.0;
+            }
+            for i in 0..4 {
+                for j in i + 1..4 {
+                    let (a, b) = (tet[i].min(tet[j]), tet[i].max(tet[j]));
+                    edges.push((a as usize, b as usize));
+                }
+            }
+            for face in outward_faces(tet) {
+                *faces.entry(sorted(face)).or_default() += 1;
+            }
+        }
+        edges.sort_unstable();
+        edges.dedup();
+        let surface = tetrahedra
+            .iter()
+            .flat_map(|&tet| outward_faces(tet))
+            .filter(|&face| faces[&sorted(face)] == 1)
+            .collect();
+
+        Self {
+            params,
+            edges: edges
+                .into_iter()
+                .map(|(a, b)| DistanceConstraint::new(&vertices, a, b))
+                .collect(),
+            vertices: Vertices::new(vertices, masses),
+            tetrahedra,
+            rest_volumes,
+            surface,
+            embedding: Vec::new(),
+        }
+    }
+
+    /// Fills a closed triangle mesh with a lattice of cubes `cell_size` wide, each split
+    /// into six tetrahedra. Cells are kept when their centre lies inside the mesh or they
+    /// hold one of its vertices; the mesh's vertices then ride along with the tetrahedra
+    /// around them, see [`Self::embedded_positions`].
+    pub fn from_mesh(mesh: &TriangleMesh, cell_size: f32, params: SoftBodyParams) -> Self {
+        if mesh.vertices.is_empty() || cell_size <= 0.0 {
+            return Self::new(Vec::new(), Vec::new(), params);
+        }
+        let origin = mesh.bounds.min;
+        // Shave off round-off so a mesh a whole number of cells wide gets no extra layer.
+        let cells = ((mesh.bounds.max - origin) / cell_size - 1e-3)
+            .ceil()
+            .max(Vec3::ONE)
+            .as_ivec3();
+        let cell_of = |point: Vec3| {
+            let cell = ((point - origin) / cell_size).floor().as_ivec3();
+            cell.clamp(IVec3::ZERO, cells - 1)
+        };
+
+        let mut filled = Vec::new();
+        for x in 0..cells.x {
+            for y in 0..cells.y {
+                for z in 0..cells.z {
+                    let center =
+                        origin + (Vec3::new(x as f32, y as f32, z as f32) + 0.5) * cell_size;
+                    if winding_number(mesh, center).abs() > 0.5 {
+                        filled.push(IVec3::new(x, y, z));
+                    }
+                }
+            }
+        }
+        for &vertex in &mesh.vertices {
+            filled.push(cell_of(vertex));
+        }
+        filled.sort_unstable_by_key(|cell| (cell.x, cell.y, cell.z));
+        filled.dedup();
+
+        let mut vertices = Vec::new();
+        let mut lattice: HashMap<IVec3, u32> = HashMap::new();
+        let mut first_tet: HashMap<IVec3, usize> = HashMap::new();
+        let mut tetrahedra = Vec::with_capacity(filled.len() * 6);
+        for &cell in &filled {
+            let corners: [u32; 8] = std::array::from_fn(|bits| {
+                let corner = cell
+                    + IVec3::new(
+                        bits as i32 & 1,
+                        (bits as i32 >> 1) & 1,
+                        (bits as i32 >> 2) & 1,
+                    );
+                *lattice.entry(corner).or_insert_with(|| {
+                    vertices.push(origin + corner.as_vec3() * cell_size);
+                    vertices.len() as u32 - 1
+                })
+            });
+            first_tet.insert(cell, tetrahedra.len());
+            // Kuhn split: one tetrahedron per monotone path from corner 0 to corner 7,
+            // which matches up with the neighbouring cubes' faces.
+            for [first, second] in [[1, 2], [1, 4], [2, 1], [2, 4], [4, 1], [4, 2]] {
+                let mut tet = [
+                    corners[0],
+                    corners[first],
+                    corners[first | second],
+                    corners[7],
+                ];
+                if signed_volume(&vertices, tet) < 0.0 {
+                    tet.swap(2, 3);
+                }
+                tetrahedra.push(tet);
+            }
+        }
+
+        let embedding = mesh
+            .vertices
+            .iter()
+            .map(|&point| {
+                let first = first_tet[&cell_of(point)];
+                (first..first + 6)
+                    .map(|tet| (tet, barycentric(&vertices, tetrahedra[tet], point)))
+                    .max_by(|(_, a), (_, b)| {
+                        let (a, b) = (min_weight(a), min_weight(b));
+                        a.total_cmp(&b)
+                    })
+                    .expect("every filled cell has six tetrahedra")
+            })
+            .collect();
+
+        let mut body = Self::new(vertices, tetrahedra, params);
+        body.embedding = embedding;
+        body
+    }
+
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.len() == 0
+    }
+
+    pub fn positions(&self) -> &[Vec3] {
+        &self.vertices.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec3] {
+        &self.vertices.velocities
+    }
+
+    /// Gives every vertex the same velocity, e.g. to throw the body.
+    pub fn set_velocity(&mut self, velocity: Vec3) {
+        for (vertex, v) in self.vertices.velocities.iter_mut().enumerate() {
+            if self.vertices.inverse_masses[vertex] > 0.0 {
+                *v = velocity;
+            }
+        }
+    }
+
+    pub fn tetrahedra(&self) -> &[[u32; 4]] {
+        &self.tetrahedra
+    }
+
+    /// Boundary faces of the tetrahedral mesh, wound to face outwards.
+    pub fn surface_triangles(&self) -> &[[u32; 3]] {
+        &self.surface
+    }
+
+    /// Current positions of the source mesh's vertices, in the same order. Empty unless
+    /// the body was built with [`Self::from_mesh`].
+    pub fn embedded_positions(&self) -> Vec<Vec3> {
+        let positions = &self.vertices.positions;
+        self.embedding
+            .iter()
+            .map(|&(tet, weights)| {
+                self.tetrahedra[tet]
+                    .iter()
+                    .zip(weights)
+                    .map(|(&vertex, weight)| positions[vertex as usize] * weight)
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Mass lumped at each vertex from its tetrahedra's volumes.
+    pub fn masses(&self) -> &[f32] {
+        &self.vertices.masses
+    }
+
+    pub fn mass(&self) -> f32 {
+        self.vertices.masses.iter().sum()
+    }
+
+    /// Current volume enclosed by the tetrahedra.
+    pub fn volume(&self) -> f32 {
+        self.tetrahedra
+            .iter()
+            .map(|&tet| signed_volume(&self.vertices.positions, tet))
+            .sum()
+    }
+
+    pub fn rest_volume(&self) -> f32 {
+        self.rest_volumes.iter().sum()
+    }
+
+    /// Fixes a vertex where it is; it still pulls on its neighbours.
+    pub fn pin(&mut self, vertex: usize) -> bool {
+        self.vertices.pin(vertex)
+    }
+
+    pub fn unpin(&mut self, vertex: usize) -> bool {
+        self.vertices.unpin(vertex)
+    }
+
+    pub fn is_pinned(&self, vertex: usize) -> bool {
+        self.vertices.is_pinned(vertex)
+    }
+
+    /// Teleports a vertex, typically a pinned one being dragged around.
+    pub fn set_position(&mut self, vertex: usize, position: Vec3) -> bool {
+        self.vertices.set_position(vertex, position)
+    }
+
+    /// Advances the body by `dt`, then applies the accumulated reaction impulses to the
+    /// dynamic bodies it touched.
+    pub fn step(
+        &mut self,
+        dt: f32,
+        gravity: Vec3,
+        colliders: &Arena<Collider>,
+        bodies: &mut BodiesSoA,
+    ) {
+        if self.is_empty() || dt <= 0.0 {
+            return;
+        }
+        let obstacles = gather_obstacles(colliders, bodies);
+        let substeps = self.params.substeps.max(1);
+        let h = dt / substeps as f32;
+        let mut reactions = Vec::new();
+        for _ in 0..substeps {
+            self.vertices.predict(h, gravity, self.params.damping);
+            self.vertices
+                .solve_distances(&self.edges, self.params.edge_compliance / (h * h));
+            self.solve_volumes(self.params.volume_compliance / (h * h));
+            self.vertices.collide(
+                &obstacles,
+                self.params.thickness.max(0.0),
+                self.params.friction,
+                h,
+                &mut reactions,
+            );
+            self.vertices.update_velocities(h);
+        }
+        apply_reactions(bodies, reactions, self.params.coupling);
+    }
+
+    /// One XPBD pass restoring each tetrahedron's rest volume.
+    fn solve_volumes(&mut self, alpha: f32) {
+        let (positions, inverse_masses) =
+            (&mut self.vertices.positions, &self.vertices.inverse_masses);
+        for (&tet, &rest_volume) in self.tetrahedra.iter().zip(&self.rest_volumes) {
+            let [a, b, c, d] = tet.map(|index| index as usize);
+            let (ab, ac, ad) = (
+                positions[b] - positions[a],
+                positions[c] - positions[a],
+                positions[d] - positions[a],
+            );
+            let mut gradients = [Vec3::ZERO, ac.cross(ad), ad.cross(ab), ab.cross(ac)];
+            gradients[0] = -(gradients[1] + gradients[2] + gradients[3]);
+
+            let weight: f32 = [a, b, c, d]
+                .iter()
+                .zip(&gradients)
+                .map(|(&vertex, gradient)| inverse_masses[vertex] * gradient.length_squared())
+                .sum();
+            if weight < f32::EPSILON {
+                continue;
+            }
+            let error = ab.cross(ac).dot(ad) - 6.0 * rest_volume;
+            let lambda = -error / (weight + alpha);
+            for (vertex, gradient) in [a, b, c, d].into_iter().zip(gradients) {
+                positions[vertex] += gradient * lambda * inverse_masses[vertex];
+            }
+        }
+    }
+}
+
+fn signed_volume(positions: &[Vec3], tet: [u32; 4]) -> f32 {
+    let [a, b, c, d] = tet.map(|index| positions[index as usize]);
+    (b - a).cross(c - a).dot(d - a) / 6.0
+}
+
+/// Faces of a positively oriented tetrahedron, wound so their normals point out of it.
+fn outward_faces([a, b, c, d]: [u32; 4]) -> [[u32; 3]; 4] {
+    [[a, c, b], [a, b, d], [a, d, c], [b, c, d]]
+}
+
+fn sorted(mut face: [u32; 3]) -> [u32; 3] {
+    face.sort_unstable();
+    face
+}
+
+fn barycentric(positions: &[Vec3], tet: [u32; 4], point: Vec3) -> [f32; 4] {
+    let [a, b, c, d] = tet.map(|index| positions[index as usize]);
+    let [u, v, w] = Mat3::from_cols(b - a, c - a, d - a)
+        .inverse()
+        .mul_vec3(point - a)
+        .to_array();
+    [1.0 - u - v - w, u, v, w]
+}
+
+fn min_weight(weights: &[f32; 4]) -> f32 {
+    weights.iter().copied().fold(f32::INFINITY, f32::min)
+}
+
+/// Generalized winding number of `mesh` around `point`: ±1 inside a closed mesh
+/// (depending on its winding), 0 outside.
+fn winding_number(mesh: &TriangleMesh, point: Vec3) -> f32 {
+    let solid_angle: f32 = mesh
+        .indices
+        .iter()
+        .map(|triangle| {
+            let [a, b, c] = triangle.map(|index| mesh.vertices[index as usize] - point);
+            let (la, lb, lc) = (a.length(), b.length(), c.length());
+            let numerator = a.dot(b.cross(c));
+            let denominator = la * lb * lc + a.dot(b) * lc + a.dot(c) * lb + b.dot(c) * la;
+            2.0 * numerator.atan2(denominator)
+        })
+        .sum();
+    solid_angle / (4.0 * PI)
+}
@@ -1,18 +1,26 @@
-// use std::collections::HashMap;
+use std::collections::HashMap;
 
-use glam::{Mat3, Vec3};
+use glam::{Mat3, Quat, Vec3, Vec4};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::{
+        DEFAULT_POSITION_ITERATIONS, DEFAULT_SOLVER_BIAS, DEFAULT_SOLVER_ITERATIONS,
+        DEFAULT_SOLVER_SLOP,
+    },
     core::{
-        constraints::Joint,
+        constraints::{Joint, MotorMode},
         rigidbody::RigidBody,
-        soa::{BodiesSoA, BodyMut},
-        types::MaterialPairProperties,
+        soa::{BodiesSoA, BodyFlags, BodyMut},
+        types::{MaterialPairProperties, Transform},
     },
     // use crate::dynamics::friction::apply_friction;
-    utils::allocator::EntityId,
+    utils::{allocator::EntityId, math::orthonormal_basis},
 };
 
+use super::simd_contacts::{scalars, vectors, WideContacts, LANES};
+
 /// Contact info shared between broad/narrow phase and solver.
 #[derive(Debug, Clone)]
 pub struct Contact {
@@ -23,6 +31,9 @@ pub struct Contact {
     pub depth: f32,
     pub relative_velocity: f32,
     pub feature_id: u64,
+    /// Material table indices of the touched triangles on mesh and heightfield colliders.
+    pub material_index_a: Option<u16>,
+    pub material_index_b: Option<u16>,
     pub accumulated_normal_impulse: f32,
     pub accumulated_tangent_impulse: Vec3,
     pub accumulated_rolling_impulse: Vec3,
@@ -30,6 +41,24 @@ pub struct Contact {
     pub material: MaterialPairProperties,
 }
 
+/// Net impulse a joint applied to its second body over one solve.
+///
+/// Divided by the time step this is the constraint force and torque, which is what
+/// breakable joints compare against their limits.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct JointImpulse {
+    pub linear: Vec3,
+    pub angular: Vec3,
+}
+
+impl JointImpulse {
+    pub fn accumulate(&mut self, other: JointImpulse) {
+        self.linear += other.linear;
+        self.angular += other.angular;
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct SolverStepMetrics {
     pub islands_solved: usize,
@@ -39,6 +68,10 @@ pub struct SolverStepMetrics {
     pub tangent_impulse_sum: f32,
     pub rolling_impulse_sum: f32,
     pub torsional_impulse_sum: f32,
+    /// Body count of the largest awake island this step.
+    pub largest_island: usize,
+    /// Awake islands larger than the configured maximum island size.
+    pub oversized_islands: usize,
 }
 
 impl SolverStepMetrics {
@@ -54,6 +87,13 @@ impl SolverStepMetrics {
         }
     }
 
+    pub fn record_island_size(&mut self, body_count: usize, max_island_size: Option<usize>) {
+        self.largest_island = self.largest_island.max(body_count);
+        if max_island_size.is_some_and(|max| body_count > max) {
+            self.oversized_islands += 1;
+        }
+    }
+
     pub fn merge(&mut self, other: &Self) {
         self.islands_solved += other.islands_solved;
         self.contacts_solved += other.contacts_solved;
@@ -62,6 +102,18 @@ impl SolverStepMetrics {
         self.tangent_impulse_sum += other.tangent_impulse_sum;
         self.rolling_impulse_sum += other.rolling_impulse_sum;
         self.torsional_impulse_sum += other.torsional_impulse_sum;
+        self.largest_island = self.largest_island.max(other.largest_island);
+        self.oversized_islands += other.oversized_islands;
+    }
+}
+
+/// Drops the impulses contacts carried over from their manifolds, so they solve from rest.
+fn clear_cached_impulses(contacts: &mut [Contact]) {
+    for contact in contacts {
+        contact.accumulated_normal_impulse = 0.0;
+        contact.accumulated_tangent_impulse = Vec3::ZERO;
+        contact.accumulated_rolling_impulse = Vec3::ZERO;
+        contact.accumulated_torsional_impulse = 0.0;
     }
 }
 
@@ -98,6 +150,76 @@ mod tests {
          ...
     }
     */
+
+    use super::*;
+
+    fn contact(point: Vec3, depth: f32, restitution: f32) -> Contact {
+        Contact {
+            body_a: EntityId::default(),
+            body_b: EntityId::default(),
+            point,
+            normal: Vec3::Y,
+            depth,
+            relative_velocity: 0.0,
+            feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
+            accumulated_normal_impulse: 0.0,
+            accumulated_tangent_impulse: Vec3::ZERO,
+            accumulated_rolling_impulse: Vec3::ZERO,
+            accumulated_torsional_impulse: 0.0,
+            material: MaterialPairProperties {
+                restitution,
+                ..MaterialPairProperties::default()
+            },
+        }
+    }
+
+    #[test]
+    fn lanes_match_the_per_contact_terms() {
+        let ground = BlockBody::fixed(Vec3::ZERO);
+        let falling = BlockBody {
+            position: Vec3::new(0.0, 1.0, 0.0),
+            inverse_mass: 0.5,
+            inverse_inertia: Mat3::IDENTITY,
+            linear: Vec3::new(0.0, -2.0, 0.0),
+            angular: Vec3::new(0.0, 0.0, 1.0),
+        };
+        let pair = (ground, falling);
+        let contacts = [
+            contact(Vec3::new(0.5, 0.0, 0.0), 0.1, 0.5),
+            contact(Vec3::new(-0.5, 0.0, 0.0), -0.02, 0.5),
+            contact(Vec3::ZERO, 0.1, 0.5),
+        ];
+        let dt = 1.0 / 120.0;
+        let preps = prepare_lanes(
+            &contacts,
+            &[Some(pair), Some(pair), None, Some(pair)],
+            0.2,
+            dt,
+        );
+
+        let first = preps[0];
+        assert_eq!(first.r_a, Vec3::new(0.5, 0.0, 0.0));
+        assert_eq!(first.r_b, Vec3::new(0.5, -1.0, 0.0));
+        assert!((first.normal_mass - 2.0).abs() < 1e-4);
+        assert_eq!(first.restitution, 0.5);
+        assert!((first.bias - 0.2 * 0.1 / dt).abs() < 1e-4);
+        // The spin about Z slows the approach at the contact to 1.5 m/s.
+        assert!((first.bounce - 0.5 * 1.5).abs() < 1e-4);
+
+        // Speculative contacts close the gap without bouncing.
+        let second = preps[1];
+        assert_eq!(second.restitution, 0.0);
+        assert_eq!(second.bounce, 0.0);
+        assert!((second.bias + 0.02 / dt).abs() < 1e-3);
+
+        // Lanes without bodies, and padding past the contacts, are left empty.
+        for prep in &preps[2..] {
+            assert_eq!(prep.normal_mass, 0.0);
+            assert_eq!(prep.bias, 0.0);
+        }
+    }
 }
 
 /// Basic constraint solver placeholder (Phase 2).
@@ -105,55 +227,208 @@ mod tests {
 pub struct ConstraintSolver {
     pub iterations: u32,
     pub bias_factor: f32,
+    /// Which manifolds have their normal impulses solved as one block.
+    pub block_solver: BlockSolverMode,
 }
 
 impl ConstraintSolver {
     pub fn new(iterations: u32) -> Self {
         Self {
             iterations,
-            bias_factor: 0.2,
+            bias_factor: DEFAULT_SOLVER_BIAS,
+            block_solver: BlockSolverMode::default(),
         }
     }
 
     pub fn solve(&self, bodies: &mut BodiesSoA, joints: &[Joint], contacts: &mut [Contact]) {
         Self::warm_start_contacts(bodies, contacts);
+        let prepared = Self::prepare_contacts(
+            bodies,
+            contacts,
+            self.bias_factor,
+            self.block_solver,
+            1.0 / 60.0,
+        );
 
         // Warm start joints? (Not implemented yet/optional)
+        let mut anchor = world_anchor(joints);
 
         for _ in 0..self.iterations {
             // Solve joints
             for joint in joints {
-                let (id_a, id_b) = joint.bodies();
-                if let Some((mut body_a, mut body_b)) = bodies.get2_mut(id_a, id_b) {
+                if let Some((mut body_a, mut body_b)) = joint_bodies_mut(bodies, &mut anchor, joint)
+                {
                     Self::resolve_velocity_joint(
                         &mut body_a,
                         &mut body_b,
                         joint,
                         1.0 / 60.0,
                         1.0 / self.iterations as f32,
+                        None,
                     );
                     // Pass dt! Or remove dt from resolve?
                 }
             }
 
             // Solve contacts
-            for contact in contacts.iter_mut() {
-                if let Some((mut body_a, mut body_b)) =
-                    bodies.get2_mut(contact.body_a, contact.body_b)
-                {
-                    Self::resolve_contact(&mut body_a, &mut body_b, contact, self.bias_factor);
+            Self::resolve_contacts(bodies, contacts, &prepared, self.block_solver);
+        }
+    }
+
+    /// Precomputes the contact terms that stay fixed over the velocity iterations.
+    fn prepare_contacts(
+        bodies: &mut BodiesSoA,
+        contacts: &[Contact],
+        bias_factor: f32,
+        block_solver: BlockSolverMode,
+        dt: f32,
+    ) -> PreparedContacts {
+        PreparedContacts::new(contacts, bias_factor, block_solver, dt, |a, b| {
+            let (body_a, body_b) = bodies.get2_mut(a, b)?;
+            Some((
+                BlockBody::from_proxy(&body_a),
+                BlockBody::from_proxy(&body_b),
+            ))
+        })
+    }
+
+    /// Resolves each manifold, solving its normal impulses as a block when enabled.
+    fn resolve_contacts(
+        bodies: &mut BodiesSoA,
+        contacts: &mut [Contact],
+        prepared: &PreparedContacts,
+        block_solver: BlockSolverMode,
+    ) {
+        let manifolds = manifold_groups(contacts, block_solver.max_points());
+        for (manifold, (preps, block)) in manifolds.zip(prepared.manifolds()) {
+            let Some((mut body_a, mut body_b)) =
+                bodies.get2_mut(manifold[0].body_a, manifold[0].body_b)
+            else {
+                continue;
+            };
+            if let Some(block) = block {
+                if Self::resolve_block(&mut body_a, &mut body_b, manifold, preps, block) {
+                    continue;
+                }
+            }
+            for (contact, prep) in manifold.iter_mut().zip(preps) {
+                Self::resolve_contact(&mut body_a, &mut body_b, contact, prep);
+            }
+        }
+    }
+
+    /// Block path of [`Self::resolve_contact`] for a whole manifold.
+    ///
+    /// Ill-conditioned manifolds of more than two points are retried as 2x2 blocks, with
+    /// sequential impulses for any pair that still cannot be solved together.
+    fn resolve_block(
+        body_a: &mut BodyMut,
+        body_b: &mut BodyMut,
+        manifold: &mut [Contact],
+        preps: &[ContactPrep],
+        block: &BlockPrep,
+    ) -> bool {
+        if block.well_conditioned {
+            let a = BlockBody::from_proxy(body_a);
+            let b = BlockBody::from_proxy(body_b);
+            if let Some(impulses) = solve_normal_block(&a, &b, manifold, preps, &block.k) {
+                for (contact, impulse) in manifold.iter_mut().zip(impulses) {
+                    Self::apply_block_impulse(body_a, body_b, contact, impulse);
+                }
+                return true;
+            }
+        }
+        if manifold.len() <= 2 {
+            return false;
+        }
+        let pairs = manifold.chunks_mut(2).zip(preps.chunks(2));
+        for (index, (pair, pair_preps)) in pairs.enumerate() {
+            if let Some(k) = block.pair(index, pair.len()) {
+                let a = BlockBody::from_proxy(body_a);
+                let b = BlockBody::from_proxy(body_b);
+                if let Some(impulses) = solve_normal_block(&a, &b, pair, pair_preps, &k) {
+                    for (contact, impulse) in pair.iter_mut().zip(impulses) {
+                        Self::apply_block_impulse(body_a, body_b, contact, impulse);
+                    }
+                    continue;
                 }
             }
+            for (contact, prep) in pair.iter_mut().zip(pair_preps) {
+                Self::resolve_contact(body_a, body_b, contact, prep);
+            }
         }
+        true
+    }
+
+    fn apply_block_impulse(
+        body_a: &mut BodyMut,
+        body_b: &mut BodyMut,
+        contact: &mut Contact,
+        impulse: f32,
+    ) {
+        let impulse_delta = impulse - contact.accumulated_normal_impulse;
+        contact.accumulated_normal_impulse = impulse;
+        let impulse = contact.normal * impulse_delta;
+        body_a.apply_impulse(-impulse, contact.point);
+        body_b.apply_impulse(impulse, contact.point);
+        crate::dynamics::friction::apply_friction(
+            body_a,
+            body_b,
+            contact,
+            contact.accumulated_normal_impulse,
+        );
+    }
+
+    /// Reapplies the lock impulse a joint ended its previous solve with.
+    fn warm_start_joint(
+        body_a: &mut BodyMut,
+        body_b: &mut BodyMut,
+        joint: &Joint,
+        impulse: JointImpulse,
+    ) {
+        let (local_pivot_a, local_pivot_b) = match joint {
+            Joint::Fixed {
+                local_pivot_a,
+                local_pivot_b,
+                ..
+            }
+            | Joint::Revolute {
+                local_pivot_a,
+                local_pivot_b,
+                ..
+            }
+            | Joint::Prismatic {
+                local_pivot_a,
+                local_pivot_b,
+                ..
+            } => (*local_pivot_a, *local_pivot_b),
+            _ => return,
+        };
+        let r_a = body_a.transform.rotation.mul_vec3(local_pivot_a);
+        let r_b = body_b.transform.rotation.mul_vec3(local_pivot_b);
+        let i_a_inv = *body_a.inverse_inertia;
+        let i_b_inv = *body_b.inverse_inertia;
+        body_a.velocity.linear -= impulse.linear * *body_a.inverse_mass;
+        body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse.linear) + impulse.angular);
+        body_b.velocity.linear += impulse.linear * *body_b.inverse_mass;
+        body_b.velocity.angular += i_b_inv.mul_vec3(r_b.cross(impulse.linear) + impulse.angular);
     }
 
+    /// Applies one velocity iteration of `joint` and returns the impulse it applied.
+    ///
+    /// With `lock`, the locked axes only hold their relative velocity and add their
+    /// impulses to it for warm starting the next solve. Drift correction is left out of
+    /// those iterations: a cached correction impulse would push again every step.
     fn resolve_velocity_joint(
         body_a: &mut BodyMut,
         body_b: &mut BodyMut,
         joint: &Joint,
         dt: f32,
         inv_iterations: f32,
-    ) {
+        mut lock: Option<&mut JointImpulse>,
+    ) -> JointImpulse {
+        let drift = if lock.is_some() { 0.0 } else { 1.0 };
+        let mut applied = JointImpulse::default();
         match joint {
             Joint::Fixed {
                 local_pivot_a,
@@ -172,7 +447,7 @@ impl ConstraintSolver {
                 {
                     let delta =
                         (body_b.transform.position + r_b) - (body_a.transform.position + r_a);
-                    let bias_raw = delta * (0.3 / dt);
+                    let bias_raw = delta * (0.3 * drift / dt);
                     let max_bias = 20.0;
                     let bias = if bias_raw.length_squared() > max_bias * max_bias {
                         bias_raw.normalize() * max_bias
@@ -205,6 +480,10 @@ impl ConstraintSolver {
                         - r_b_skew.mul_mat3(&i_b_inv).mul_mat3(&r_b_skew);
 
                     let impulse_p2p = k.inverse().mul_vec3(-(relative_vel + bias));
+                    applied.linear += impulse_p2p;
+                    if let Some(lock) = lock.as_deref_mut() {
+                        lock.linear += impulse_p2p;
+                    }
 
                     body_a.velocity.linear -= impulse_p2p * m_a_inv;
                     body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse_p2p));
@@ -228,7 +507,7 @@ impl ConstraintSolver {
                     };
                     let rotation_error = axis * angle;
 
-                    let bias_raw = rotation_error * (0.3 / dt);
+                    let bias_raw = rotation_error * (0.3 * drift / dt);
                     let max_ang_bias = 10.0;
                     let bias = if bias_raw.length_squared() > max_ang_bias * max_ang_bias {
                         bias_raw.normalize() * max_ang_bias
@@ -243,6 +522,10 @@ impl ConstraintSolver {
                     let k = i_a_inv + i_b_inv;
                     if k.determinant().abs() > f32::EPSILON {
                         let impulse_ang = k.inverse().mul_vec3(-(rel_ang_vel + bias));
+                        applied.angular += impulse_ang;
+                        if let Some(lock) = lock.as_deref_mut() {
+                            lock.angular += impulse_ang;
+                        }
                         body_a.velocity.angular -= i_a_inv.mul_vec3(impulse_ang);
                         body_b.velocity.angular += i_b_inv.mul_vec3(impulse_ang);
                     }
@@ -258,6 +541,7 @@ impl ConstraintSolver {
                 enable_motor,
                 motor_speed,
                 max_motor_torque,
+                motor_mode,
                 enable_limit,
                 lower_angle,
                 upper_angle,
@@ -275,7 +559,7 @@ impl ConstraintSolver {
 
                 // 1. Point-to-Point (Pivot)
                 {
-                    let bias_raw = delta * (0.2 / dt);
+                    let bias_raw = delta * (0.2 * drift / dt);
                     let max_bias = 5.0; // m/s
                     let bias = if bias_raw.length_squared() > max_bias * max_bias {
                         bias_raw.normalize() * max_bias
@@ -309,6 +593,10 @@ impl ConstraintSolver {
                         - r_b_skew.mul_mat3(&i_b_inv).mul_mat3(&r_b_skew);
 
                     let impulse_p2p = k.inverse().mul_vec3(-(relative_vel + bias));
+                    applied.linear += impulse_p2p;
+                    if let Some(lock) = lock.as_deref_mut() {
+                        lock.linear += impulse_p2p;
+                    }
 
                     body_a.velocity.linear -= impulse_p2p * m_a_inv;
                     body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse_p2p));
@@ -324,19 +612,40 @@ impl ConstraintSolver {
                 let inv_mass = axis_world.dot(i_a_inv.mul_vec3(axis_world))
                     + axis_world.dot(i_b_inv.mul_vec3(axis_world));
 
+                let basis_a = q_a.mul_vec3(*local_basis_a);
+                let basis_b = q_b.mul_vec3(*local_basis_b);
+                let angle =
+                    f32::atan2(basis_a.cross(basis_b).dot(axis_world), basis_a.dot(basis_b));
+
                 if inv_mass > f32::EPSILON {
                     // Motor
                     if *enable_motor {
                         let rel_ang_vel = body_b.velocity.angular - body_a.velocity.angular;
                         let speed = rel_ang_vel.dot(axis_world);
-                        let target = *motor_speed;
-                        let error = target - speed;
-
-                        let impulse_mag = error / inv_mass;
+                        let impulse_mag = match *motor_mode {
+                            MotorMode::Velocity => (*motor_speed - speed) / inv_mass,
+                            MotorMode::Position {
+                                target,
+                                stiffness,
+                                damping,
+                            } => {
+                                let error = wrap_angle(target - angle);
+                                servo_impulse(
+                                    error,
+                                    *motor_speed - speed,
+                                    speed,
+                                    stiffness,
+                                    damping,
+                                    inv_mass,
+                                    dt,
+                                ) * inv_iterations
+                            }
+                        };
                         let max_impulse = (*max_motor_torque * dt) * inv_iterations;
                         let clamped_impulse = impulse_mag.clamp(-max_impulse, max_impulse);
 
                         let impulse = axis_world * clamped_impulse;
+                        applied.angular += impulse;
 
                         body_a.velocity.angular -= i_a_inv.mul_vec3(impulse);
                         body_b.velocity.angular += i_b_inv.mul_vec3(impulse);
@@ -344,14 +653,6 @@ impl ConstraintSolver {
 
                     // Limits
                     if *enable_limit {
-                        let basis_a = q_a.mul_vec3(*local_basis_a);
-                        let basis_b = q_b.mul_vec3(*local_basis_b);
-
-                        let angle = f32::atan2(
-                            basis_a.cross(basis_b).dot(axis_world),
-                            basis_a.dot(basis_b),
-                        );
-
                         let mut limit_impulse = 0.0;
                         let max_ang_bias = 2.0; // rad/s
                         if angle <= *lower_angle {
@@ -374,6 +675,7 @@ impl ConstraintSolver {
 
                         if limit_impulse.abs() > 0.0 {
                             let impulse = axis_world * limit_impulse;
+                            applied.angular += impulse;
                             body_a.velocity.angular -= i_a_inv.mul_vec3(impulse);
                             body_b.velocity.angular += i_b_inv.mul_vec3(impulse);
                         }
@@ -392,6 +694,7 @@ impl ConstraintSolver {
                 enable_motor,
                 motor_speed,
                 max_motor_force,
+                motor_mode,
                 ..
             } => {
                 let q_a = body_a.transform.rotation;
@@ -412,7 +715,7 @@ impl ConstraintSolver {
                     };
                     let rotation_error = axis * angle;
 
-                    let bias_raw = rotation_error * (0.3 / dt);
+                    let bias_raw = rotation_error * (0.3 * drift / dt);
                     let max_ang_bias = 10.0;
                     let bias = if bias_raw.length_squared() > max_ang_bias * max_ang_bias {
                         bias_raw.normalize() * max_ang_bias
@@ -426,6 +729,10 @@ impl ConstraintSolver {
                     let k = i_a_inv + i_b_inv;
                     if k.determinant().abs() > 1e-6 {
                         let impulse_ang = k.inverse().mul_vec3(-(rel_ang_vel + bias));
+                        applied.angular += impulse_ang;
+                        if let Some(lock) = lock.as_deref_mut() {
+                            lock.angular += impulse_ang;
+                        }
                         body_a.velocity.angular -= i_a_inv.mul_vec3(impulse_ang);
                         body_b.velocity.angular += i_b_inv.mul_vec3(impulse_ang);
                     }
@@ -435,11 +742,11 @@ impl ConstraintSolver {
                 {
                     let delta =
                         (body_b.transform.position + r_b) - (body_a.transform.position + r_a);
-                    let (v_world, w_world) = u_world.any_orthonormal_pair();
+                    let (v_world, w_world) = orthonormal_basis(u_world);
 
                     for axis in [v_world, w_world] {
                         let error = delta.dot(axis);
-                        let bias = (error * 0.3 / dt).clamp(-20.0, 20.0);
+                        let bias = (error * 0.3 * drift / dt).clamp(-20.0, 20.0);
 
                         let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(r_a);
                         let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(r_b);
@@ -458,6 +765,10 @@ impl ConstraintSolver {
                         if k > 1e-6 {
                             let impulse_mag = -(rel_v + bias) / k;
                             let impulse = axis * impulse_mag;
+                            applied.linear += impulse;
+                            if let Some(lock) = lock.as_deref_mut() {
+                                lock.linear += impulse;
+                            }
                             body_a.velocity.linear -= impulse * m_a_inv;
                             body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse));
                             body_b.velocity.linear += impulse * m_b_inv;
@@ -488,12 +799,29 @@ impl ConstraintSolver {
                             let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(r_b);
                             let rel_v = (v_b - v_a).dot(u_world);
 
-                            let error = *motor_speed - rel_v;
-                            let impulse_mag = error / k;
+                            let impulse_mag = match *motor_mode {
+                                MotorMode::Velocity => (*motor_speed - rel_v) / k,
+                                MotorMode::Position {
+                                    target,
+                                    stiffness,
+                                    damping,
+                                } => {
+                                    servo_impulse(
+                                        target - dist,
+                                        *motor_speed - rel_v,
+                                        rel_v,
+                                        stiffness,
+                                        damping,
+                                        k,
+                                        dt,
+                                    ) * inv_iterations
+                                }
+                            };
                             let max_impulse = (*max_motor_force * dt) * inv_iterations;
                             let clamped_impulse = impulse_mag.clamp(-max_impulse, max_impulse);
 
                             let impulse = u_world * clamped_impulse;
+                            applied.linear += impulse;
                             body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse));
                             body_a.velocity.linear -= impulse * m_a_inv;
                             body_b.velocity.angular += i_b_inv.mul_vec3(r_b.cross(impulse));
@@ -519,6 +847,7 @@ impl ConstraintSolver {
                                 let bias = -0.2 * error / dt;
                                 let impulse_mag = (-(rel_v) + bias) / k;
                                 let impulse = u_world * impulse_mag.max(0.0);
+                                applied.linear += impulse;
                                 body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse));
                                 body_a.velocity.linear -= impulse * m_a_inv;
                                 body_b.velocity.angular += i_b_inv.mul_vec3(r_b.cross(impulse));
@@ -528,6 +857,7 @@ impl ConstraintSolver {
                                 let bias = -0.2 * error / dt;
                                 let impulse_mag = (-(rel_v) + bias) / k;
                                 let impulse = u_world * impulse_mag.min(0.0);
+                                applied.linear += impulse;
                                 body_a.velocity.angular -= i_a_inv.mul_vec3(r_a.cross(impulse));
                                 body_a.velocity.linear -= impulse * m_a_inv;
                                 body_b.velocity.angular += i_b_inv.mul_vec3(r_b.cross(impulse));
@@ -539,23 +869,21 @@ impl ConstraintSolver {
             }
             _ => {}
         }
+        applied
     }
 
     fn resolve_contact(
         body_a: &mut BodyMut,
         body_b: &mut BodyMut,
         contact: &mut Contact,
-        bias_factor: f32,
+        prep: &ContactPrep,
     ) {
         if body_a.is_static() && body_b.is_static() {
             return;
         }
 
-        let r_a = contact.point - body_a.transform.position;
-        let r_b = contact.point - body_b.transform.position;
-
-        let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(r_a);
-        let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(r_b);
+        let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(prep.r_a);
+        let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(prep.r_b);
         let relative_vel = v_b - v_a;
 
         let vel_along_normal = relative_vel.dot(contact.normal);
@@ -563,17 +891,12 @@ impl ConstraintSolver {
             return;
         }
 
-        let restitution = (body_a.material.restitution * body_b.material.restitution).sqrt();
-        // Baumgarte stabilization uses the full depth (positive for penetration, negative for separation).
-        // The scaling factor (beta) is typically 0.2.
-        let bias = bias_factor * contact.depth / (1.0 / 60.0);
-
         // Corrected restitution formula: J = -(v_rel * (1 + e) - bias).
         // Bias is subtracted from the relative velocity.
         // During overlap (bias > 0), the target velocity v_new is positive (separation).
         // During gaps (bias < 0), the target velocity v_new is greater than the bias.
-        let impulse_mag = -(vel_along_normal * (1.0 + restitution) - bias)
-            / (*body_a.inverse_mass + *body_b.inverse_mass + 1e-6);
+        let impulse_mag =
+            -(vel_along_normal * (1.0 + prep.restitution) - prep.bias) * prep.normal_mass;
 
         let impulse_mag = (contact.accumulated_normal_impulse + impulse_mag).max(0.0);
         let impulse_delta = impulse_mag - contact.accumulated_normal_impulse;
@@ -664,13 +987,10 @@ impl ConstraintSolver {
         body_a: &mut RigidBody,
         body_b: &mut RigidBody,
         contact: &mut Contact,
-        bias_factor: f32,
+        prep: &ContactPrep,
     ) {
-        let r_a = contact.point - body_a.transform.position;
-        let r_b = contact.point - body_b.transform.position;
-
-        let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(r_a);
-        let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(r_b);
+        let v_a = body_a.velocity.linear + body_a.velocity.angular.cross(prep.r_a);
+        let v_b = body_b.velocity.linear + body_b.velocity.angular.cross(prep.r_b);
         let relative_vel = v_b - v_a;
 
         let vel_along_normal = relative_vel.dot(contact.normal);
@@ -678,11 +998,8 @@ impl ConstraintSolver {
             return;
         }
 
-        let restitution = (body_a.material.restitution * body_b.material.restitution).sqrt();
-        let bias = bias_factor * contact.depth / (1.0 / 60.0);
-
-        let impulse_mag = -(vel_along_normal * (1.0 + restitution) - bias)
-            / (body_a.inverse_mass + body_b.inverse_mass + 1e-6);
+        let impulse_mag =
+            -(vel_along_normal * (1.0 + prep.restitution) - prep.bias) * prep.normal_mass;
 
         let impulse_mag = (contact.accumulated_normal_impulse + impulse_mag).max(0.0);
         let impulse_delta = impulse_mag - contact.accumulated_normal_impulse;
@@ -700,6 +1017,106 @@ impl ConstraintSolver {
             contact.accumulated_normal_impulse,
         );
     }
+
+    /// Slice counterpart of [`Self::prepare_contacts`].
+    fn prepare_contacts_slice(
+        bodies: &mut [RigidBody],
+        id_map: &std::collections::HashMap<EntityId, usize>,
+        contacts: &[Contact],
+        bias_factor: f32,
+        block_solver: BlockSolverMode,
+        dt: f32,
+    ) -> PreparedContacts {
+        PreparedContacts::new(contacts, bias_factor, block_solver, dt, |a, b| {
+            let (body_a, body_b) = get_pair_mut_from_slice(bodies, id_map, a, b)?;
+            Some((BlockBody::from_body(body_a), BlockBody::from_body(body_b)))
+        })
+    }
+
+    /// Slice counterpart of [`Self::resolve_contacts`].
+    fn resolve_contacts_slice(
+        bodies: &mut [RigidBody],
+        id_map: &std::collections::HashMap<EntityId, usize>,
+        contacts: &mut [Contact],
+        prepared: &PreparedContacts,
+        block_solver: BlockSolverMode,
+    ) {
+        let manifolds = manifold_groups(contacts, block_solver.max_points());
+        for (manifold, (preps, block)) in manifolds.zip(prepared.manifolds()) {
+            let Some((body_a, body_b)) =
+                get_pair_mut_from_slice(bodies, id_map, manifold[0].body_a, manifold[0].body_b)
+            else {
+                continue;
+            };
+            if let Some(block) = block {
+                if Self::resolve_block_slice(body_a, body_b, manifold, preps, block) {
+                    continue;
+                }
+            }
+            for (contact, prep) in manifold.iter_mut().zip(preps) {
+                Self::resolve_contact_slice(body_a, body_b, contact, prep);
+            }
+        }
+    }
+
+    /// Slice counterpart of [`Self::resolve_block`].
+    fn resolve_block_slice(
+        body_a: &mut RigidBody,
+        body_b: &mut RigidBody,
+        manifold: &mut [Contact],
+        preps: &[ContactPrep],
+        block: &BlockPrep,
+    ) -> bool {
+        if block.well_conditioned {
+            let a = BlockBody::from_body(body_a);
+            let b = BlockBody::from_body(body_b);
+            if let Some(impulses) = solve_normal_block(&a, &b, manifold, preps, &block.k) {
+                for (contact, impulse) in manifold.iter_mut().zip(impulses) {
+                    Self::apply_block_impulse_slice(body_a, body_b, contact, impulse);
+                }
+                return true;
+            }
+        }
+        if manifold.len() <= 2 {
+            return false;
+        }
+        let pairs = manifold.chunks_mut(2).zip(preps.chunks(2));
+        for (index, (pair, pair_preps)) in pairs.enumerate() {
+            if let Some(k) = block.pair(index, pair.len()) {
+                let a = BlockBody::from_body(body_a);
+                let b = BlockBody::from_body(body_b);
+                if let Some(impulses) = solve_normal_block(&a, &b, pair, pair_preps, &k) {
+                    for (contact, impulse) in pair.iter_mut().zip(impulses) {
+                        Self::apply_block_impulse_slice(body_a, body_b, contact, impulse);
+                    }
+                    continue;
+                }
+            }
+            for (contact, prep) in pair.iter_mut().zip(pair_preps) {
+                Self::resolve_contact_slice(body_a, body_b, contact, prep);
+            }
+        }
+        true
+    }
+
+    fn apply_block_impulse_slice(
+        body_a: &mut RigidBody,
+        body_b: &mut RigidBody,
+        contact: &mut Contact,
+        impulse: f32,
+    ) {
+        let impulse_delta = impulse - contact.accumulated_normal_impulse;
+        contact.accumulated_normal_impulse = impulse;
+        let impulse = contact.normal * impulse_delta;
+        body_a.apply_impulse(-impulse, contact.point);
+        body_b.apply_impulse(impulse, contact.point);
+        crate::dynamics::friction::apply_friction_slice(
+            body_a,
+            body_b,
+            contact,
+            contact.accumulated_normal_impulse,
+        );
+    }
 }
 
 /// Phase 4 solver placeholder (PGS).
@@ -709,6 +1126,19 @@ pub struct PGSSolver {
     pub position_iterations: u32,
     pub bias_factor: f32,
     pub slop: f32,
+    /// Which manifolds have their normal impulses solved as one block.
+    pub block_solver: BlockSolverMode,
+    /// Whether contacts start each solve from the impulses cached in their manifolds
+    /// instead of from zero.
+    pub contact_warm_starting: bool,
+    /// Whether joints start each solve from the lock impulses they ended the last one
+    /// with, as contacts do, instead of from rest.
+    pub joint_warm_starting: bool,
+    /// How contacts push overlapping bodies back apart.
+    pub position_correction: PositionCorrection,
+    /// Whether contacts are solved four at a time on SIMD lanes, in batches that share no
+    /// dynamic body. Manifolds are then solved point by point, whatever `block_solver` says.
+    pub simd_contacts: bool,
 }
 
 impl Default for PGSSolver {
@@ -720,64 +1150,189 @@ impl Default for PGSSolver {
 impl PGSSolver {
     pub fn new() -> Self {
         Self {
-            velocity_iterations: 4,
-            position_iterations: 1,
-            bias_factor: 0.2,
-            slop: 0.01,
+            velocity_iterations: DEFAULT_SOLVER_ITERATIONS,
+            position_iterations: DEFAULT_POSITION_ITERATIONS,
+            bias_factor: DEFAULT_SOLVER_BIAS,
+            slop: DEFAULT_SOLVER_SLOP,
+            block_solver: BlockSolverMode::default(),
+            contact_warm_starting: true,
+            joint_warm_starting: true,
+            position_correction: PositionCorrection::default(),
+            simd_contacts: false,
+        }
+    }
+
+    /// Block mode the velocity iterations use; the SIMD path has no blocks.
+    fn contact_block_mode(&self) -> BlockSolverMode {
+        if self.simd_contacts {
+            BlockSolverMode::Off
+        } else {
+            self.block_solver
         }
     }
 
+    /// Baumgarte factor of the contact velocity bias; only Baumgarte correction uses one.
+    fn velocity_bias_factor(&self) -> f32 {
+        match self.position_correction {
+            PositionCorrection::Baumgarte => self.bias_factor,
+            PositionCorrection::SplitImpulse | PositionCorrection::None => 0.0,
+        }
+    }
+
+    /// Solves one island and returns the impulse each joint applied, in `joints` order.
+    ///
+    /// Joints start cold; see [`Self::solve_warm_started`] to carry their impulses over.
     pub fn solve(
         &self,
         bodies: &mut BodiesSoA,
         joints: &[Joint],
         contacts: &mut [Contact],
         dt: f32,
-    ) {
-        ConstraintSolver::warm_start_contacts(bodies, contacts);
-        for _iter in 0..self.velocity_iterations {
-            for contact in contacts.iter_mut() {
-                if let Some((mut body_a, mut body_b)) =
-                    bodies.get2_mut(contact.body_a, contact.body_b)
+    ) -> Vec<JointImpulse> {
+        let mut joint_warm_start = vec![JointImpulse::default(); joints.len()];
+        self.solve_warm_started(bodies, joints, &mut joint_warm_start, contacts, dt)
+    }
+
+    /// Like [`Self::solve`], but first reapplies each joint's lock impulse from
+    /// `joint_warm_start` (in `joints` order) and leaves this solve's there for the next.
+    pub fn solve_warm_started(
+        &self,
+        bodies: &mut BodiesSoA,
+        joints: &[Joint],
+        joint_warm_start: &mut [JointImpulse],
+        contacts: &mut [Contact],
+        dt: f32,
+    ) -> Vec<JointImpulse> {
+        if self.contact_warm_starting {
+            ConstraintSolver::warm_start_contacts(bodies, contacts);
+        } else {
+            clear_cached_impulses(contacts);
+        }
+        let block_solver = self.contact_block_mode();
+        let prepared = ConstraintSolver::prepare_contacts(
+            bodies,
+            contacts,
+            self.velocity_bias_factor(),
+            block_solver,
+            dt,
+        );
+        let mut wide = self.simd_contacts.then(|| {
+            WideContacts::new(contacts, &prepared.contacts, |id| {
+                bodies.get_mut(id).map(|body| BlockBody::from_proxy(&body))
+            })
+        });
+        // Joints and scalar contacts work on the bodies themselves, so the batches hand their
+        // velocities over around them.
+        let sync = !joints.is_empty()
+            || wide
+                .as_ref()
+                .is_some_and(|w| !w.scalar_contacts().is_empty());
+        let mut anchor = world_anchor(joints);
+        let mut joint_impulses = vec![JointImpulse::default(); joints.len()];
+        let mut locks = vec![JointImpulse::default(); joints.len()];
+        if self.joint_warm_starting {
+            for (index, (joint, warm)) in joints.iter().zip(joint_warm_start.iter()).enumerate() {
+                if let Some((mut body_a, mut body_b)) = joint_bodies_mut(bodies, &mut anchor, joint)
                 {
-                    ConstraintSolver::resolve_contact(
-                        &mut body_a,
-                        &mut body_b,
-                        contact,
-                        self.bias_factor,
-                    );
+                    ConstraintSolver::warm_start_joint(&mut body_a, &mut body_b, joint, *warm);
+                    joint_impulses[index] = *warm;
+                    locks[index] = *warm;
+                }
+            }
+        }
+        for iter in 0..self.velocity_iterations {
+            match wide.as_mut() {
+                Some(wide) => {
+                    wide.resolve();
+                    if sync || iter + 1 == self.velocity_iterations {
+                        wide.scatter(|id, linear, angular| {
+                            if let Some(body) = bodies.get_mut(id) {
+                                body.velocity.linear = linear;
+                                body.velocity.angular = angular;
+                                body.flags.is_awake = true;
+                            }
+                        });
+                    }
+                    for &index in wide.scalar_contacts() {
+                        let contact = &mut contacts[index];
+                        if let Some((mut body_a, mut body_b)) =
+                            bodies.get2_mut(contact.body_a, contact.body_b)
+                        {
+                            ConstraintSolver::resolve_contact(
+                                &mut body_a,
+                                &mut body_b,
+                                contact,
+                                &prepared.contacts[index],
+                            );
+                        }
+                    }
+                }
+                None => {
+                    ConstraintSolver::resolve_contacts(bodies, contacts, &prepared, block_solver)
                 }
             }
 
-            for joint in joints {
-                let (id_a, id_b) = joint.bodies();
-                if let Some((mut body_a, mut body_b)) = bodies.get2_mut(id_a, id_b) {
-                    ConstraintSolver::resolve_velocity_joint(
+            // The first iteration refines the cached impulses; the rest also correct drift,
+            // which is not carried over.
+            let warm = self.joint_warm_starting && iter == 0 && self.velocity_iterations > 1;
+            for ((joint, total), lock) in joints.iter().zip(&mut joint_impulses).zip(&mut locks) {
+                if let Some((mut body_a, mut body_b)) = joint_bodies_mut(bodies, &mut anchor, joint)
+                {
+                    total.accumulate(ConstraintSolver::resolve_velocity_joint(
                         &mut body_a,
                         &mut body_b,
                         joint,
                         dt,
                         1.0 / self.velocity_iterations as f32,
-                    );
+                        warm.then_some(lock),
+                    ));
                 }
             }
+            if let Some(wide) = wide.as_mut().filter(|_| sync) {
+                wide.gather(|id| {
+                    let body = bodies.get_mut(id)?;
+                    Some((body.velocity.linear, body.velocity.angular))
+                });
+            }
+        }
+        if let Some(wide) = &wide {
+            wide.store_impulses(contacts);
+        }
+        for (warm, lock) in joint_warm_start.iter_mut().zip(locks) {
+            *warm = lock;
         }
 
-        for _ in 0..self.position_iterations {
-            for contact in contacts.iter() {
-                if let Some((mut body_a, mut body_b)) =
-                    bodies.get2_mut(contact.body_a, contact.body_b)
-                {
-                    Self::correct_position(
-                        &mut body_a,
-                        &mut body_b,
-                        contact,
-                        self.bias_factor,
-                        self.slop,
-                    );
+        match self.position_correction {
+            PositionCorrection::Baumgarte => {
+                for _ in 0..self.position_iterations {
+                    for contact in contacts.iter() {
+                        if let Some((mut body_a, mut body_b)) =
+                            bodies.get2_mut(contact.body_a, contact.body_b)
+                        {
+                            Self::correct_position(
+                                &mut body_a,
+                                &mut body_b,
+                                contact,
+                                self.bias_factor,
+                                self.slop,
+                            );
+                        }
+                    }
+                }
+            }
+            PositionCorrection::SplitImpulse => {
+                let pseudo = self.split_impulses(contacts, &prepared, dt, |id| {
+                    bodies.get_mut(id).map(|body| BlockBody::from_proxy(&body))
+                });
+                for (id, velocity) in pseudo {
+                    if let Some(body) = bodies.get_mut(id) {
+                        displace(body.transform, &velocity, dt);
+                    }
                 }
             }
+            PositionCorrection::None => {}
         }
+        joint_impulses
     }
 
     // Parallel-friendly solver path operating on a dense slice of rigid bodies.
@@ -788,49 +1343,216 @@ impl PGSSolver {
         id_map: &std::collections::HashMap<EntityId, usize>,
         contacts: &mut [Contact],
         joints: &[Joint],
+        joint_warm_start: &mut [JointImpulse],
         dt: f32,
-    ) {
-        warm_start_slice(bodies, id_map, contacts);
-        for _ in 0..self.velocity_iterations {
-            for contact in contacts.iter_mut() {
-                if let Some((body_a, body_b)) =
-                    get_pair_mut_from_slice(bodies, id_map, contact.body_a, contact.body_b)
-                {
-                    ConstraintSolver::resolve_contact_slice(
-                        body_a,
-                        body_b,
-                        contact,
-                        self.bias_factor,
-                    );
+    ) -> Vec<JointImpulse> {
+        if self.contact_warm_starting {
+            warm_start_slice(bodies, id_map, contacts);
+        } else {
+            clear_cached_impulses(contacts);
+        }
+        let block_solver = self.contact_block_mode();
+        let prepared = ConstraintSolver::prepare_contacts_slice(
+            bodies,
+            id_map,
+            contacts,
+            self.velocity_bias_factor(),
+            block_solver,
+            dt,
+        );
+        let mut wide = self.simd_contacts.then(|| {
+            WideContacts::new(contacts, &prepared.contacts, |id| {
+                id_map
+                    .get(&id)
+                    .map(|&index| BlockBody::from_body(&bodies[index]))
+            })
+        });
+        let sync = !joints.is_empty()
+            || wide
+                .as_ref()
+                .is_some_and(|w| !w.scalar_contacts().is_empty());
+        let mut anchor = world_anchor_body();
+        let mut joint_impulses = vec![JointImpulse::default(); joints.len()];
+        let mut locks = vec![JointImpulse::default(); joints.len()];
+        if self.joint_warm_starting {
+            for (index, (joint, warm)) in joints.iter().zip(joint_warm_start.iter()).enumerate() {
+                if warm_start_joint_slice(bodies, id_map, &mut anchor, joint, *warm) {
+                    joint_impulses[index] = *warm;
+                    locks[index] = *warm;
                 }
             }
+        }
+        for iter in 0..self.velocity_iterations {
+            match wide.as_mut() {
+                Some(wide) => {
+                    wide.resolve();
+                    if sync || iter + 1 == self.velocity_iterations {
+                        wide.scatter(|id, linear, angular| {
+                            if let Some(&index) = id_map.get(&id) {
+                                let body = &mut bodies[index];
+                                body.velocity.linear = linear;
+                                body.velocity.angular = angular;
+                                body.is_awake = true;
+                            }
+                        });
+                    }
+                    for &index in wide.scalar_contacts() {
+                        let contact = &mut contacts[index];
+                        if let Some((body_a, body_b)) =
+                            get_pair_mut_from_slice(bodies, id_map, contact.body_a, contact.body_b)
+                        {
+                            ConstraintSolver::resolve_contact_slice(
+                                body_a,
+                                body_b,
+                                contact,
+                                &prepared.contacts[index],
+                            );
+                        }
+                    }
+                }
+                None => ConstraintSolver::resolve_contacts_slice(
+                    bodies,
+                    id_map,
+                    contacts,
+                    &prepared,
+                    block_solver,
+                ),
+            }
 
-            for joint in joints {
-                resolve_velocity_joint_slice(
+            let warm = self.joint_warm_starting && iter == 0 && self.velocity_iterations > 1;
+            for ((joint, total), lock) in joints.iter().zip(&mut joint_impulses).zip(&mut locks) {
+                total.accumulate(resolve_velocity_joint_slice(
                     bodies,
                     id_map,
+                    &mut anchor,
                     joint,
                     dt,
                     1.0 / self.velocity_iterations as f32,
-                );
+                    warm.then_some(lock),
+                ));
+            }
+            if let Some(wide) = wide.as_mut().filter(|_| sync) {
+                wide.gather(|id| {
+                    let body = &bodies[*id_map.get(&id)?];
+                    Some((body.velocity.linear, body.velocity.angular))
+                });
+            }
+        }
+        if let Some(wide) = &wide {
+            wide.store_impulses(contacts);
+        }
+        for (warm, lock) in joint_warm_start.iter_mut().zip(locks) {
+            *warm = lock;
+        }
+
+        match self.position_correction {
+            PositionCorrection::Baumgarte => {
+                for _ in 0..self.position_iterations {
+                    for contact in contacts.iter() {
+                        if let Some((body_a, body_b)) =
+                            get_pair_mut_from_slice(bodies, id_map, contact.body_a, contact.body_b)
+                        {
+                            Self::correct_position_slice(
+                                body_a,
+                                body_b,
+                                contact,
+                                self.bias_factor,
+                                self.slop,
+                            );
+                        }
+                    }
+                }
             }
+            PositionCorrection::SplitImpulse => {
+                let pseudo = self.split_impulses(contacts, &prepared, dt, |id| {
+                    id_map
+                        .get(&id)
+                        .map(|&index| BlockBody::from_body(&bodies[index]))
+                });
+                for (id, velocity) in pseudo {
+                    if let Some(&index) = id_map.get(&id) {
+                        displace(&mut bodies[index].transform, &velocity, dt);
+                    }
+                }
+            }
+            PositionCorrection::None => {}
         }
+        joint_impulses
+    }
 
-        for _ in 0..self.position_iterations {
-            for contact in contacts.iter() {
-                if let Some((body_a, body_b)) =
-                    get_pair_mut_from_slice(bodies, id_map, contact.body_a, contact.body_b)
-                {
-                    Self::correct_position_slice(
-                        body_a,
-                        body_b,
-                        contact,
-                        self.bias_factor,
-                        self.slop,
+    /// Solves penetration on pseudo velocities and returns those of every body touched.
+    ///
+    /// Pseudo velocities start from rest each solve and only ever move the bodies, so
+    /// pushing them apart adds no momentum and leaves bounces to restitution alone.
+    fn split_impulses(
+        &self,
+        contacts: &[Contact],
+        prepared: &PreparedContacts,
+        dt: f32,
+        mut body: impl FnMut(EntityId) -> Option<BlockBody>,
+    ) -> HashMap<EntityId, BlockBody> {
+        let mut pseudo: HashMap<EntityId, BlockBody> = HashMap::new();
+        for contact in contacts {
+            for id in [contact.body_a, contact.body_b] {
+                if pseudo.contains_key(&id) {
+                    continue;
+                }
+                if let Some(state) = body(id) {
+                    pseudo.insert(
+                        id,
+                        BlockBody {
+                            linear: Vec3::ZERO,
+                            angular: Vec3::ZERO,
+                            ..state
+                        },
                     );
                 }
             }
         }
+
+        let mut accumulated = vec![0.0f32; contacts.len()];
+        for _ in 0..self.velocity_iterations {
+            for ((contact, prep), total) in contacts
+                .iter()
+                .zip(&prepared.contacts)
+                .zip(&mut accumulated)
+            {
+                let target = self.bias_factor * (contact.depth - self.slop) / dt;
+                if target <= 0.0 {
+                    continue;
+                }
+                let (Some(&a), Some(&b)) =
+                    (pseudo.get(&contact.body_a), pseudo.get(&contact.body_b))
+                else {
+                    continue;
+                };
+                let v_a = a.linear + a.angular.cross(prep.r_a);
+                let v_b = b.linear + b.angular.cross(prep.r_b);
+                let closing = (v_b - v_a).dot(contact.normal);
+                // Pseudo impulses are pure position fixes, so the lever arms count too.
+                let n = contact.normal;
+                let effective = a.inverse_mass
+                    + b.inverse_mass
+                    + n.dot((a.inverse_inertia * prep.r_a.cross(n)).cross(prep.r_a))
+                    + n.dot((b.inverse_inertia * prep.r_b.cross(n)).cross(prep.r_b));
+                if effective <= f32::EPSILON {
+                    continue;
+                }
+                let new_total = (*total + (target - closing) / effective).max(0.0);
+                let impulse = contact.normal * (new_total - *total);
+                *total = new_total;
+
+                if let Some(a) = pseudo.get_mut(&contact.body_a) {
+                    a.linear -= impulse * a.inverse_mass;
+                    a.angular -= a.inverse_inertia * prep.r_a.cross(impulse);
+                }
+                if let Some(b) = pseudo.get_mut(&contact.body_b) {
+                    b.linear += impulse * b.inverse_mass;
+                    b.angular += b.inverse_inertia * prep.r_b.cross(impulse);
+                }
+            }
+        }
+        pseudo
     }
 
     fn correct_position(
@@ -888,44 +1610,616 @@ impl PGSSolver {
     }
 }
 
+/// Warm-starts a joint between slice bodies; false when either body is missing.
+fn warm_start_joint_slice(
+    bodies: &mut [RigidBody],
+    id_map: &std::collections::HashMap<EntityId, usize>,
+    anchor: &mut RigidBody,
+    joint: &Joint,
+    impulse: JointImpulse,
+) -> bool {
+    let (id_a, id_b) = joint.bodies();
+    let Some((a, b)) = get_joint_pair_mut_from_slice(bodies, id_map, anchor, id_a, id_b) else {
+        return false;
+    };
+    let mut flags_a = BodyFlags::from(&*a);
+    let mut flags_b = BodyFlags::from(&*b);
+    ConstraintSolver::warm_start_joint(
+        &mut BodyMut::from_rigid_body(a, &mut flags_a),
+        &mut BodyMut::from_rigid_body(b, &mut flags_b),
+        joint,
+        impulse,
+    );
+    true
+}
+
 fn resolve_velocity_joint_slice(
     bodies: &mut [RigidBody],
     id_map: &std::collections::HashMap<EntityId, usize>,
+    anchor: &mut RigidBody,
     joint: &Joint,
     dt: f32,
-    _inv_iterations: f32,
-) {
-    match joint {
-        Joint::Fixed { body_a, body_b, .. } => {
-            if let Some((_a, _b)) = get_pair_mut_from_slice(bodies, id_map, *body_a, *body_b) {
-                // Fixed joint logic in resolve_velocity_joint is complex,
-                // for MVP we can use a simpler approach or replicate.
-                // Replicating only necessary parts.
+    inv_iterations: f32,
+    lock: Option<&mut JointImpulse>,
+) -> JointImpulse {
+    let (id_a, id_b) = joint.bodies();
+    let Some((a, b)) = get_joint_pair_mut_from_slice(bodies, id_map, anchor, id_a, id_b) else {
+        return JointImpulse::default();
+    };
+    if let Joint::Distance { distance, .. } = joint {
+        // Simplified distance joint for slice
+        let rel_pos = b.transform.position - a.transform.position;
+        let current_dist = rel_pos.length();
+        let error = current_dist - *distance;
+        let normal = rel_pos.normalize_or_zero();
+        let bias = (error * 0.2) / dt;
+
+        let v_a = a.velocity.linear;
+        let v_b = b.velocity.linear;
+        let rel_vel = (v_b - v_a).dot(normal);
+
+        let impulse = -(rel_vel - bias) / (a.inverse_mass + b.inverse_mass + 1e-6);
+        a.apply_impulse(-impulse * normal, a.transform.position);
+        b.apply_impulse(impulse * normal, b.transform.position);
+        return JointImpulse {
+            linear: impulse * normal,
+            angular: Vec3::ZERO,
+        };
+    }
+
+    // Everything else shares the SoA joint solver through proxies of the slice bodies.
+    let mut flags_a = BodyFlags::from(&*a);
+    let mut flags_b = BodyFlags::from(&*b);
+    let applied = ConstraintSolver::resolve_velocity_joint(
+        &mut BodyMut::from_rigid_body(a, &mut flags_a),
+        &mut BodyMut::from_rigid_body(b, &mut flags_b),
+        joint,
+        dt,
+        inv_iterations,
+        lock,
+    );
+    a.is_awake = flags_a.is_awake;
+    b.is_awake = flags_b.is_awake;
+    applied
+}
+
+/// Impulse of a servo spring-damper over one step, solved implicitly.
+///
+/// `error` is the position error, `speed_error` the gap to the feed-forward speed, and
+/// `inv_mass` the joint's inverse effective mass along the driven axis. Integrating the
+/// spring against the end-of-step speed keeps the drive stable however stiff it is.
+pub(crate) fn servo_impulse(
+    error: f32,
+    speed_error: f32,
+    speed: f32,
+    stiffness: f32,
+    damping: f32,
+    inv_mass: f32,
+    dt: f32,
+) -> f32 {
+    let stiffness = stiffness.max(0.0);
+    let damping = damping.max(0.0);
+    let force = stiffness * (error - dt * speed) + damping * speed_error;
+    dt * force / (1.0 + inv_mass * dt * (damping + dt * stiffness))
+}
+
+/// Wraps an angle into `[-PI, PI]`.
+fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// Terms of up to four consecutive contacts, worked out one per SIMD lane. `bodies` holds
+/// each lane's body pair; lanes without one, or past the end of `contacts`, get default
+/// terms, as the solver skips them.
+///
+/// Restitution and bias: penetrating contacts use Baumgarte stabilisation (the scaling
+/// factor is typically 0.2). Speculative contacts have negative depth: the bodies may close
+/// the whole gap this step, but they do not bounce before touching.
+///
+/// No tangent basis is precomputed: friction acts along each iteration's slip direction,
+/// and anisotropic friction reads the body orientation in the scalar solver.
+fn prepare_lanes(
+    contacts: &[Contact],
+    bodies: &[Option<(BlockBody, BlockBody)>; LANES],
+    bias_factor: f32,
+    dt: f32,
+) -> [ContactPrep; LANES] {
+    let padding = (BlockBody::fixed(Vec3::ZERO), BlockBody::fixed(Vec3::ZERO));
+    let pairs: [&(BlockBody, BlockBody); LANES] =
+        std::array::from_fn(|lane| bodies[lane].as_ref().unwrap_or(&padding));
+    let lanes: [&Contact; LANES] =
+        std::array::from_fn(|lane| contacts.get(lane).unwrap_or(&contacts[0]));
+
+    let point = vectors(|lane| lanes[lane].point);
+    let normal = vectors(|lane| lanes[lane].normal);
+    let r_a = point.sub(vectors(|lane| pairs[lane].0.position));
+    let r_b = point.sub(vectors(|lane| pairs[lane].1.position));
+    let velocity_a =
+        vectors(|lane| pairs[lane].0.linear).add(vectors(|lane| pairs[lane].0.angular).cross(r_a));
+    let velocity_b =
+        vectors(|lane| pairs[lane].1.linear).add(vectors(|lane| pairs[lane].1.angular).cross(r_b));
+    let approach = velocity_b.sub(velocity_a).dot(normal);
+    let inv_mass_sum =
+        scalars(|lane| pairs[lane].0.inverse_mass + pairs[lane].1.inverse_mass) + Vec4::splat(1e-6);
+
+    let depth = scalars(|lane| lanes[lane].depth);
+    let speculative = depth.cmplt(Vec4::ZERO);
+    let restitution = Vec4::select(
+        speculative,
+        Vec4::ZERO,
+        scalars(|lane| lanes[lane].material.restitution),
+    );
+    let bias = Vec4::select(speculative, depth, depth * bias_factor) / dt;
+    let bounce = -restitution * approach.min(Vec4::ZERO);
+    let normal_mass = inv_mass_sum.recip();
+
+    let (r_a, r_b) = (r_a.to_array(), r_b.to_array());
+    let (normal_mass, restitution) = (normal_mass.to_array(), restitution.to_array());
+    let (bias, bounce) = (bias.to_array(), bounce.to_array());
+    std::array::from_fn(|lane| {
+        if lane >= contacts.len() || bodies[lane].is_none() {
+            return ContactPrep::default();
+        }
+        ContactPrep {
+            r_a: r_a[lane],
+            r_b: r_b[lane],
+            normal_mass: normal_mass[lane],
+            restitution: restitution[lane],
+            bias: bias[lane],
+            bounce: bounce[lane],
+        }
+    })
+}
+
+/// Moves a body along the pseudo velocity a split-impulse solve left it with.
+fn displace(transform: &mut Transform, pseudo: &BlockBody, dt: f32) {
+    transform.position += pseudo.linear * dt;
+    let turn = pseudo.angular * dt;
+    if turn.length_squared() > 0.0 {
+        transform.rotation = (Quat::from_scaled_axis(turn) * transform.rotation).normalize();
+    }
+}
+
+/// How the solver pushes overlapping bodies back apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PositionCorrection {
+    /// Baumgarte stabilisation: penetration adds a velocity bias to the contact
+    /// constraints, followed by the position iterations. The bias stays in the bodies as
+    /// real velocity, so bounces come out higher than their restitution.
+    #[default]
+    Baumgarte,
+    /// Split impulses: penetration is solved separately on pseudo velocities that move the
+    /// bodies without adding momentum, so the velocity solve only sees restitution.
+    SplitImpulse,
+    /// No correction; contacts only stop bodies from sinking further.
+    None,
+}
+
+/// Largest manifold whose normal impulses are solved as one block.
+const MAX_BLOCK_POINTS: usize = 4;
+
+/// Which contact manifolds the solver treats as one block.
+///
+/// Blocks are warm-started from the cached impulses like single points, and fall back to
+/// sequential impulses when their effective mass matrix is badly conditioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum BlockSolverMode {
+    /// Sequential impulses for every contact point.
+    Off,
+    /// 2x2 blocks, as in Box2D; larger manifolds are solved two points at a time.
+    TwoPoint,
+    /// Whole manifolds of up to four points, retried as 2x2 blocks when ill-conditioned.
+    #[default]
+    FourPoint,
+}
+
+impl BlockSolverMode {
+    fn max_points(self) -> usize {
+        match self {
+            Self::Off => 1,
+            Self::TwoPoint => 2,
+            Self::FourPoint => MAX_BLOCK_POINTS,
+        }
+    }
+}
+
+/// Blocks whose effective mass matrix has a worse condition estimate fall back to
+/// sequential impulses.
+const MAX_BLOCK_CONDITION: f32 = 1000.0;
+
+/// Velocity state of one body as seen by the block solver.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BlockBody {
+    pub(super) position: Vec3,
+    pub(super) inverse_mass: f32,
+    pub(super) inverse_inertia: Mat3,
+    pub(super) linear: Vec3,
+    pub(super) angular: Vec3,
+}
+
+impl BlockBody {
+    pub(super) fn from_proxy(body: &BodyMut) -> Self {
+        if body.is_static() {
+            return Self::fixed(body.transform.position);
+        }
+        Self {
+            position: body.transform.position,
+            inverse_mass: *body.inverse_mass,
+            inverse_inertia: *body.inverse_inertia,
+            linear: body.velocity.linear,
+            angular: body.velocity.angular,
+        }
+    }
+
+    pub(super) fn from_body(body: &RigidBody) -> Self {
+        if body.is_static {
+            return Self::fixed(body.transform.position);
+        }
+        Self {
+            position: body.transform.position,
+            inverse_mass: body.inverse_mass,
+            inverse_inertia: body.inverse_inertia,
+            linear: body.velocity.linear,
+            angular: body.velocity.angular,
+        }
+    }
+
+    pub(super) fn fixed(position: Vec3) -> Self {
+        Self {
+            position,
+            inverse_mass: 0.0,
+            inverse_inertia: Mat3::ZERO,
+            linear: Vec3::ZERO,
+            angular: Vec3::ZERO,
+        }
+    }
+}
+
+/// Splits contacts into runs that share a body pair, at most `max_points` long.
+fn manifold_groups(
+    contacts: &mut [Contact],
+    max_points: usize,
+) -> impl Iterator<Item = &mut [Contact]> {
+    contacts
+        .chunk_by_mut(|a, b| a.body_a == b.body_a && a.body_b == b.body_b)
+        .flat_map(move |run| run.chunks_mut(max_points))
+}
+
+/// Normal effective mass matrix of a block of up to four contact points.
+type BlockMatrix = [[f32; MAX_BLOCK_POINTS]; MAX_BLOCK_POINTS];
+
+/// Solver terms of one contact that stay fixed over the velocity iterations.
+///
+/// Bodies only move once the iterations are done, so lever arms, masses, and the bias are
+/// worked out once per solve instead of once per iteration.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ContactPrep {
+    pub(super) r_a: Vec3,
+    pub(super) r_b: Vec3,
+    /// Linear effective mass along the normal, used by sequential impulses.
+    pub(super) normal_mass: f32,
+    pub(super) restitution: f32,
+    pub(super) bias: f32,
+    /// Separating speed restitution asks for, from the approach speed before the solve.
+    bounce: f32,
+}
+
+/// Effective mass matrix of a manifold and the conditioning of its blocks.
+#[derive(Debug, Clone, Copy)]
+struct BlockPrep {
+    k: BlockMatrix,
+    well_conditioned: bool,
+    /// Conditioning of the 2x2 blocks an ill-conditioned manifold is retried as.
+    pairs_well_conditioned: [bool; MAX_BLOCK_POINTS / 2],
+}
+
+impl BlockPrep {
+    /// Matrix of the `index`-th 2x2 block, if it can be solved as one.
+    fn pair(&self, index: usize, len: usize) -> Option<BlockMatrix> {
+        if len != 2 || !self.pairs_well_conditioned[index] {
+            return None;
+        }
+        Some(sub_block(&self.k, 2 * index, 2))
+    }
+}
+
+/// Precomputed terms of every contact of a solve, laid out in `contacts` order.
+struct PreparedContacts {
+    contacts: Vec<ContactPrep>,
+    /// One entry per [`manifold_groups`] run; `None` when it is solved point by point.
+    blocks: Vec<Option<BlockPrep>>,
+    /// Length of each run, to slice `contacts` alongside the manifolds.
+    lengths: Vec<usize>,
+}
+
+impl PreparedContacts {
+    /// Prepares contacts, fetching each body pair once per manifold and working out the
+    /// per-contact terms four at a time with [`prepare_lanes`].
+    fn new(
+        contacts: &[Contact],
+        bias_factor: f32,
+        block_solver: BlockSolverMode,
+        dt: f32,
+        mut pair: impl FnMut(EntityId, EntityId) -> Option<(BlockBody, BlockBody)>,
+    ) -> Self {
+        let mut prepared = Self {
+            contacts: Vec::with_capacity(contacts.len()),
+            blocks: Vec::new(),
+            lengths: Vec::new(),
+        };
+        // Body pairs of the contacts from `contacts[start..]`, waiting for a full set of lanes.
+        let mut lanes = [None; LANES];
+        let (mut start, mut filled) = (0, 0);
+        for manifold in contacts
+            .chunk_by(|a, b| a.body_a == b.body_a && a.body_b == b.body_b)
+            .flat_map(|run| run.chunks(block_solver.max_points()))
+        {
+            let bodies = pair(manifold[0].body_a, manifold[0].body_b);
+            let block = bodies.as_ref().and_then(|(a, b)| {
+                let both_fixed = a.inverse_mass == 0.0 && b.inverse_mass == 0.0;
+                (manifold.len() >= 2 && !both_fixed).then(|| prepare_block(a, b, manifold))
+            });
+            prepared.blocks.push(block);
+            prepared.lengths.push(manifold.len());
+            for _ in manifold {
+                lanes[filled] = bodies;
+                filled += 1;
+                if filled == LANES {
+                    let preps =
+                        prepare_lanes(&contacts[start..start + LANES], &lanes, bias_factor, dt);
+                    prepared.contacts.extend_from_slice(&preps);
+                    start += LANES;
+                    filled = 0;
+                }
             }
         }
-        Joint::Distance {
-            body_a,
-            body_b,
-            distance,
-        } => {
-            if let Some((a, b)) = get_pair_mut_from_slice(bodies, id_map, *body_a, *body_b) {
-                // Simplified distance joint for slice
-                let rel_pos = b.transform.position - a.transform.position;
-                let current_dist = rel_pos.length();
-                let error = current_dist - *distance;
-                let normal = rel_pos.normalize_or_zero();
-                let bias = (error * 0.2) / dt;
-
-                let v_a = a.velocity.linear;
-                let v_b = b.velocity.linear;
-                let rel_vel = (v_b - v_a).dot(normal);
-
-                let impulse = -(rel_vel - bias) / (a.inverse_mass + b.inverse_mass + 1e-6);
-                a.apply_impulse(-impulse * normal, a.transform.position);
-                b.apply_impulse(impulse * normal, b.transform.position);
-            }
-        }
-        _ => {}
+        if filled > 0 {
+            let preps = prepare_lanes(&contacts[start..start + filled], &lanes, bias_factor, dt);
+            prepared.contacts.extend_from_slice(&preps[..filled]);
+        }
+        prepared
+    }
+
+    /// Per-manifold contact terms and block, in [`manifold_groups`] order.
+    fn manifolds(&self) -> impl Iterator<Item = (&[ContactPrep], Option<&BlockPrep>)> {
+        let mut rest = self.contacts.as_slice();
+        self.lengths
+            .iter()
+            .zip(&self.blocks)
+            .map(move |(&len, block)| {
+                let (preps, tail) = rest.split_at(len);
+                rest = tail;
+                (preps, block.as_ref())
+            })
+    }
+}
+
+/// Builds the normal effective mass matrix of a manifold, angular terms included.
+fn prepare_block(a: &BlockBody, b: &BlockBody, manifold: &[Contact]) -> BlockPrep {
+    let n = manifold.len();
+    let mut ang_a = [Vec3::ZERO; MAX_BLOCK_POINTS];
+    let mut ang_b = [Vec3::ZERO; MAX_BLOCK_POINTS];
+    for (i, contact) in manifold.iter().enumerate() {
+        ang_a[i] = (contact.point - a.position).cross(contact.normal);
+        ang_b[i] = (contact.point - b.position).cross(contact.normal);
+    }
+    // I^-1 (r x n) is shared by every entry of a column.
+    let mut inertia_a = [Vec3::ZERO; MAX_BLOCK_POINTS];
+    let mut inertia_b = [Vec3::ZERO; MAX_BLOCK_POINTS];
+    for i in 0..n {
+        inertia_a[i] = a.inverse_inertia * ang_a[i];
+        inertia_b[i] = b.inverse_inertia * ang_b[i];
+    }
+
+    let mut k = [[0.0f32; MAX_BLOCK_POINTS]; MAX_BLOCK_POINTS];
+    for i in 0..n {
+        for j in 0..n {
+            let linear =
+                (a.inverse_mass + b.inverse_mass) * manifold[i].normal.dot(manifold[j].normal);
+            k[i][j] = linear + ang_a[i].dot(inertia_a[j]) + ang_b[i].dot(inertia_b[j]);
+        }
+    }
+
+    let mut pairs_well_conditioned = [false; MAX_BLOCK_POINTS / 2];
+    for (index, conditioned) in pairs_well_conditioned.iter_mut().enumerate() {
+        if 2 * index + 2 <= n {
+            *conditioned = well_conditioned(&sub_block(&k, 2 * index, 2), 2);
+        }
+    }
+    BlockPrep {
+        k,
+        well_conditioned: well_conditioned(&k, n),
+        pairs_well_conditioned,
+    }
+}
+
+/// The `n` x `n` block of `k` starting at row and column `offset`.
+fn sub_block(k: &BlockMatrix, offset: usize, n: usize) -> BlockMatrix {
+    let mut block = [[0.0f32; MAX_BLOCK_POINTS]; MAX_BLOCK_POINTS];
+    for (row, source) in block.iter_mut().zip(&k[offset..offset + n]) {
+        row[..n].copy_from_slice(&source[offset..offset + n]);
+    }
+    block
+}
+
+/// Solves the normal impulses of a manifold simultaneously.
+///
+/// The mixed LCP `w = K x + b`, `x >= 0`, `w >= 0`, `x . w = 0` is solved by total
+/// enumeration of the active sets, so it is only used for up to four points. `k` must be
+/// well-conditioned (for example not four coplanar points). Returns the new accumulated
+/// impulse of every point, or `None` when no active set satisfies the complementarity
+/// conditions.
+fn solve_normal_block(
+    a: &BlockBody,
+    b: &BlockBody,
+    manifold: &[Contact],
+    preps: &[ContactPrep],
+    k: &BlockMatrix,
+) -> Option<[f32; MAX_BLOCK_POINTS]> {
+    let n = manifold.len();
+    if !(2..=MAX_BLOCK_POINTS).contains(&n) {
+        return None;
+    }
+
+    // b = vn - target - K * accumulated, so that w is the post-solve velocity above target.
+    let mut rhs = [0.0f32; MAX_BLOCK_POINTS];
+    for (i, (contact, prep)) in manifold.iter().zip(preps).enumerate() {
+        let v_a = a.linear + a.angular.cross(prep.r_a);
+        let v_b = b.linear + b.angular.cross(prep.r_b);
+        let vn = (v_b - v_a).dot(contact.normal);
+        let target = prep.bias + prep.bounce;
+        rhs[i] = vn - target;
+        for (j, other) in manifold.iter().enumerate() {
+            rhs[i] -= k[i][j] * other.accumulated_normal_impulse;
+        }
+    }
+
+    // Prefer the largest active set: resting manifolds keep every point loaded.
+    let full = (1usize << n) - 1;
+    for active in (0..=full).rev() {
+        let Some(x) = solve_active_set(k, &rhs, n, active) else {
+            continue;
+        };
+        let complementary = (0..n).all(|i| {
+            if active & (1 << i) != 0 {
+                return true;
+            }
+            let w: f32 = (0..n).map(|j| k[i][j] * x[j]).sum::<f32>() + rhs[i];
+            w >= -1e-4
+        });
+        if complementary {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Cholesky pivots of `K` against its diagonal as a cheap condition estimate.
+fn well_conditioned(k: &BlockMatrix, n: usize) -> bool {
+    let max_diag = (0..n).map(|i| k[i][i]).fold(0.0f32, f32::max);
+    if max_diag <= f32::EPSILON {
+        return false;
+    }
+    let mut l = [[0.0f32; MAX_BLOCK_POINTS]; MAX_BLOCK_POINTS];
+    for i in 0..n {
+        for j in 0..=i {
+            let sum: f32 = (0..j).map(|m| l[i][m] * l[j][m]).sum();
+            if i == j {
+                let pivot = k[i][i] - sum;
+                if pivot * MAX_BLOCK_CONDITION < max_diag {
+                    return false;
+                }
+                l[i][i] = pivot.sqrt();
+            } else {
+                l[i][j] = (k[i][j] - sum) / l[j][j];
+            }
+        }
+    }
+    true
+}
+
+/// Solves `K_SS x_S = -b_S` for the points in `active`, the rest staying at zero.
+///
+/// Returns `None` when any active impulse would be negative.
+fn solve_active_set(
+    k: &BlockMatrix,
+    rhs: &[f32; MAX_BLOCK_POINTS],
+    n: usize,
+    active: usize,
+) -> Option<[f32; MAX_BLOCK_POINTS]> {
+    let mut indices = [0usize; MAX_BLOCK_POINTS];
+    let mut m = 0;
+    for i in 0..n {
+        if active & (1 << i) != 0 {
+            indices[m] = i;
+            m += 1;
+        }
+    }
+
+    // Gaussian elimination on the active sub-block; it is SPD once `K` passed the
+    // conditioning check, so no pivoting is needed.
+    let mut sub = [[0.0f32; MAX_BLOCK_POINTS + 1]; MAX_BLOCK_POINTS];
+    for r in 0..m {
+        for c in 0..m {
+            sub[r][c] = k[indices[r]][indices[c]];
+        }
+        sub[r][m] = -rhs[indices[r]];
+    }
+    for p in 0..m {
+        let pivot = sub[p][p];
+        if pivot.abs() <= f32::EPSILON {
+            return None;
+        }
+        let pivot_row = sub[p];
+        for row in sub.iter_mut().take(m).skip(p + 1) {
+            let factor = row[p] / pivot;
+            for (value, pivot_value) in row.iter_mut().zip(pivot_row).take(m + 1).skip(p) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+    let mut solved = [0.0f32; MAX_BLOCK_POINTS];
+    for r in (0..m).rev() {
+        let tail: f32 = ((r + 1)..m).map(|c| sub[r][c] * solved[c]).sum();
+        solved[r] = (sub[r][m] - tail) / sub[r][r];
+    }
+
+    let mut x = [0.0f32; MAX_BLOCK_POINTS];
+    for r in 0..m {
+        if solved[r] < 0.0 {
+            return None;
+        }
+        x[indices[r]] = solved[r];
+    }
+    Some(x)
+}
+
+/// Static body at the origin standing in for [`Joint::WORLD`].
+fn world_anchor_body() -> RigidBody {
+    let mut body = RigidBody::new(Joint::WORLD);
+    body.is_static = true;
+    body.recompute_inverses();
+    body
+}
+
+/// Holds the world anchor for grounded joints; empty when no joint is grounded.
+fn world_anchor(joints: &[Joint]) -> BodiesSoA {
+    let mut anchor = BodiesSoA::new();
+    if joints.iter().any(Joint::is_grounded) {
+        anchor.insert(world_anchor_body());
+    }
+    anchor
+}
+
+fn joint_bodies_mut<'a>(
+    bodies: &'a mut BodiesSoA,
+    anchor: &'a mut BodiesSoA,
+    joint: &Joint,
+) -> Option<(BodyMut<'a>, BodyMut<'a>)> {
+    let anchor_id = EntityId::new(0, 0);
+    match joint.bodies() {
+        (Joint::WORLD, Joint::WORLD) => None,
+        (Joint::WORLD, id_b) => Some((anchor.get_mut(anchor_id)?, bodies.get_mut(id_b)?)),
+        (id_a, Joint::WORLD) => Some((bodies.get_mut(id_a)?, anchor.get_mut(anchor_id)?)),
+        (id_a, id_b) => bodies.get2_mut(id_a, id_b),
+    }
+}
+
+fn get_joint_pair_mut_from_slice<'a>(
+    bodies: &'a mut [RigidBody],
+    id_map: &std::collections::HashMap<EntityId, usize>,
+    anchor: &'a mut RigidBody,
+    a: EntityId,
+    b: EntityId,
+) -> Option<(&'a mut RigidBody, &'a mut RigidBody)> {
+    match (a, b) {
+        (Joint::WORLD, Joint::WORLD) => None,
+        (Joint::WORLD, b) => Some((anchor, &mut bodies[*id_map.get(&b)?])),
+        (a, Joint::WORLD) => Some((&mut bodies[*id_map.get(&a)?], anchor)),
+        (a, b) => get_pair_mut_from_slice(bodies, id_map, a, b),
     }
 }
 
@@ -0,0 +1,281 @@
+//! C ABI for embedding the engine in C, C++, or C# hosts. Enabled with the `ffi` feature.
+//!
+//! Worlds are opaque pointers owned by the caller between `pa_world_create` and
+//! `pa_world_destroy`. Entity ids cross the boundary as `u64` values packed as
+//! `(generation << 32) | index`; [`PA_INVALID_ID`] signals failure. See `docs/FFI.md`.
+
+use glam::{Quat, Vec3};
+
+use crate::{
+    collision::queries::RaycastQuery,
+    core::{collider::Collider, rigidbody::RigidBody},
+    utils::allocator::EntityId,
+    world::PhysicsWorld,
+};
+
+/// Returned in place of an id when an operation fails.
+pub const PA_INVALID_ID: u64 = u64::MAX;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PaVec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PaQuat {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+/// Initial state for a body created through [`pa_world_add_body`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PaBodyDesc {
+    pub position: PaVec3,
+    pub rotation: PaQuat,
+    pub linear_velocity: PaVec3,
+    pub mass: f32,
+    pub is_static: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PaRaycastHit {
+    pub body: u64,
+    pub collider: u64,
+    pub point: PaVec3,
+    pub normal: PaVec3,
+    pub distance: f32,
+}
+
+impl From<PaVec3> for Vec3 {
+    fn from(v: PaVec3) -> Self {
+        Vec3::new(v.x, v.y, v.z)
+    }
+}
+
+impl From<Vec3> for PaVec3 {
+    fn from(v: Vec3) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<PaQuat> for Quat {
+    fn from(q: PaQuat) -> Self {
+        Quat::from_xyzw(q.x, q.y, q.z, q.w).normalize()
+    }
+}
+
+/// Creates a world with the given fixed time step. Free it with [`pa_world_destroy`].
+#[no_mangle]
+pub extern "C" fn pa_world_create(time_step: f32) -> *mut PhysicsWorld {
+    Box::into_raw(Box::new(PhysicsWorld::new(time_step)))
+}
+
+/// # Safety
+/// `world` must be null or a pointer returned by [`pa_world_create`] that has not been
+/// destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_destroy(world: *mut PhysicsWorld) {
+    if !world.is_null() {
+        drop(Box::from_raw(world));
+    }
+}
+
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_set_gravity(world: *mut PhysicsWorld, gravity: PaVec3) {
+    if let Some(world) = world.as_mut() {
+        world.gravity = gravity.into();
+    }
+}
+
+/// Adds a rigid body and returns its id, or [`PA_INVALID_ID`] on null arguments.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`]; `desc` must be null or
+/// point to a valid [`PaBodyDesc`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_add_body(
+    world: *mut PhysicsWorld,
+    desc: *const PaBodyDesc,
+) -> u64 {
+    let (world, desc) = match (world.as_mut(), desc.as_ref()) {
+        (Some(world), Some(desc)) => (world, desc),
+        _ => return PA_INVALID_ID,
+    };
+
+    let mut body = RigidBody::new(EntityId::default());
+    body.transform.position = desc.position.into();
+    body.transform.rotation = desc.rotation.into();
+    body.velocity.linear = desc.linear_velocity.into();
+    body.mass_properties.mass = desc.mass;
+    body.is_static = desc.is_static;
+    world.add_rigidbody(body).to_bits()
+}
+
+unsafe fn add_collider(world: *mut PhysicsWorld, body: u64, mut collider: Collider) -> u64 {
+    let world = match world.as_mut() {
+        Some(world) => world,
+        None => return PA_INVALID_ID,
+    };
+    let body = EntityId::from_bits(body);
+    if world.body(body).is_none() {
+        return PA_INVALID_ID;
+    }
+    collider.rigidbody_id = body;
+    world.add_collider(collider).to_bits()
+}
+
+/// Attaches a sphere collider to `body`. Returns [`PA_INVALID_ID`] for unknown bodies.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_add_sphere_collider(
+    world: *mut PhysicsWorld,
+    body: u64,
+    radius: f32,
+) -> u64 {
+    add_collider(world, body, Collider::builder().sphere(radius).build())
+}
+
+/// Attaches a box collider to `body`. Returns [`PA_INVALID_ID`] for unknown bodies.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_add_box_collider(
+    world: *mut PhysicsWorld,
+    body: u64,
+    half_extents: PaVec3,
+) -> u64 {
+    add_collider(
+        world,
+        body,
+        Collider::builder().box_shape(half_extents.into()).build(),
+    )
+}
+
+/// Attaches a Y-aligned capsule collider to `body`. Returns [`PA_INVALID_ID`] for unknown
+/// bodies.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_add_capsule_collider(
+    world: *mut PhysicsWorld,
+    body: u64,
+    radius: f32,
+    height: f32,
+) -> u64 {
+    add_collider(
+        world,
+        body,
+        Collider::builder().capsule(radius, height).build(),
+    )
+}
+
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_step(world: *mut PhysicsWorld, dt: f32) {
+    if let Some(world) = world.as_mut() {
+        world.step(dt);
+    }
+}
+
+/// Casts a ray and writes the closest hit to `out_hit`. Returns `false` on a miss.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`]; `out_hit` must be null
+/// or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_raycast(
+    world: *const PhysicsWorld,
+    origin: PaVec3,
+    direction: PaVec3,
+    max_distance: f32,
+    out_hit: *mut PaRaycastHit,
+) -> bool {
+    let world = match world.as_ref() {
+        Some(world) => world,
+        None => return false,
+    };
+    let query = RaycastQuery::new(origin.into(), direction.into(), max_distance);
+    let hit = match world.raycast(&query).into_iter().next() {
+        Some(hit) => hit,
+        None => return false,
+    };
+    if let Some(out) = out_hit.as_mut() {
+        *out = PaRaycastHit {
+            body: hit.body_id.to_bits(),
+            collider: hit.collider_id.to_bits(),
+            point: hit.point.into(),
+            normal: hit.normal.into(),
+            distance: hit.distance,
+        };
+    }
+    true
+}
+
+/// Number of live bodies; use it to size the buffers for [`pa_world_read_transforms`].
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`].
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_body_count(world: *const PhysicsWorld) -> usize {
+    world.as_ref().map_or(0, |world| world.bodies.len())
+}
+
+/// Copies body transforms into caller-owned structure-of-arrays buffers.
+///
+/// `ids` receives one packed id per body, `positions` three floats (xyz) per body, and
+/// `rotations` four floats (xyzw) per body. Any buffer may be null to skip it. At most
+/// `capacity` bodies are written; the return value is the number written.
+///
+/// # Safety
+/// `world` must be null or a live pointer from [`pa_world_create`]. Each non-null buffer
+/// must be valid for writes of `capacity` elements of its stride.
+#[no_mangle]
+pub unsafe extern "C" fn pa_world_read_transforms(
+    world: *const PhysicsWorld,
+    ids: *mut u64,
+    positions: *mut f32,
+    rotations: *mut f32,
+    capacity: usize,
+) -> usize {
+    let world = match world.as_ref() {
+        Some(world) => world,
+        None => return 0,
+    };
+
+    let mut written = 0;
+    for body in world.bodies.iter().take(capacity) {
+        let transform = body.transform();
+        if !ids.is_null() {
+            *ids.add(written) = body.id().to_bits();
+        }
+        if !positions.is_null() {
+            let out = std::slice::from_raw_parts_mut(positions.add(written * 3), 3);
+            out.copy_from_slice(&transform.position.to_array());
+        }
+        if !rotations.is_null() {
+            let out = std::slice::from_raw_parts_mut(rotations.add(written * 4), 4);
+            out.copy_from_slice(&transform.rotation.to_array());
+        }
+        written += 1;
+    }
+    written
+}
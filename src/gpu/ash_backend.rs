@@ -1,8 +1,20 @@
-use crate::gpu::{ComputeBackend, GpuWorldState};
+use crate::{
+    config::DEFAULT_BROADPHASE_CELL_SIZE,
+    gpu::{ComputeBackend, GpuBody, GpuInstance, GpuReadback, GpuWorldState},
+    utils::allocator::EntityId,
+};
 use ash::vk;
-use ash_renderer::vulkan::{Allocator, ComputePipeline, VulkanDevice};
+use ash_renderer::vulkan::{Allocator, CommandPool, ComputePipeline, VulkanDevice};
 use parking_lot::Mutex;
 use std::sync::Arc;
+use vk_mem::Alloc;
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Storage buffers bound by `grid_broadphase.wgsl`, after the uniform params at binding 0.
+const STORAGE_BINDINGS: u32 = 6;
+
+const PAIR_SIZE: u64 = std::mem::size_of::<[u32; 2]>() as u64;
 
 pub struct GpuBuffer {
     pub buffer: vk::Buffer,
@@ -10,60 +22,466 @@ pub struct GpuBuffer {
     pub size: u64,
 }
 
+/// How the host touches a buffer, which decides the memory it is allocated from.
+#[derive(Debug, Clone, Copy)]
+enum Access {
+    Upload,
+    DeviceOnly,
+    Readback,
+    /// Written by the host every step and read by another pipeline, e.g. a renderer.
+    /// Device-local when the device has host-visible VRAM.
+    Shared,
+}
+
+/// Uniform block of `grid_broadphase.wgsl`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct GridParams {
+    body_count: u32,
+    table_size: u32,
+    max_pairs: u32,
+    cell_size: f32,
+}
+
+/// Buffers of the grid broad phase, grown as the body and pair counts grow.
+#[derive(Default)]
+struct GridBuffers {
+    params: Option<GpuBuffer>,
+    bodies: Option<GpuBuffer>,
+    /// First body in each hash bucket.
+    heads: Option<GpuBuffer>,
+    /// Next body in the same bucket, per body.
+    nexts: Option<GpuBuffer>,
+    /// Bodies too wide for a grid cell.
+    oversized: Option<GpuBuffer>,
+    /// Pair count and oversized count.
+    counters: Option<GpuBuffer>,
+    pairs: Option<GpuBuffer>,
+    /// Whether the descriptor set points at the current buffers.
+    bound: bool,
+}
+
+/// Shader, layouts and pipelines shared by every frame.
+struct GridKernels {
+    device: Arc<VulkanDevice>,
+    shader_module: vk::ShaderModule,
+    set_layout: vk::DescriptorSetLayout,
+    pipeline_layout: vk::PipelineLayout,
+    bin_pipeline: Option<ComputePipeline>,
+    pairs_pipeline: Option<ComputePipeline>,
+}
+
+/// Buffers, descriptor set, command buffer and fence for one broad-phase pass in flight.
+struct GridFrame {
+    kernels: Arc<GridKernels>,
+    allocator: Arc<Allocator>,
+    buffers: GridBuffers,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set: vk::DescriptorSet,
+    pool: CommandPool,
+    command_buffer: vk::CommandBuffer,
+    fence: vk::Fence,
+    /// Whether the fence has to be waited on before the frame is touched again.
+    submitted: bool,
+    body_count: u32,
+}
+
+/// A broad-phase pass waiting on its frame's fence. Dropping it hands the frame back.
+struct GridPass {
+    frame: Option<GridFrame>,
+    idle: Arc<Mutex<Vec<GridFrame>>>,
+    params: GridParams,
+}
+
+/// Writes world states into the instance buffers of [`SharedInstances`].
+struct InstanceRing {
+    device: Arc<VulkanDevice>,
+    allocator: Arc<Allocator>,
+    shared: SharedInstances,
+}
+
+/// Buffers a renderer reads from while it has up to `frames_in_flight` frames in flight,
+/// and the renderer frame each was last read in.
+///
+/// A write only goes to a slot that is not the published one and that no frame still in
+/// flight has read, so a slot's buffer can be overwritten or reallocated in place. Slots
+/// superseded before anyone read them are reused at once, so several steps in one frame
+/// cycle through the same few slots.
+struct FrameRing<B> {
+    slots: Vec<RingSlot<B>>,
+    frames_in_flight: u64,
+    /// Latest frame passed to `begin_frame`.
+    frame: u64,
+    current: Option<usize>,
+}
+
+struct RingSlot<B> {
+    buffer: Option<B>,
+    read_in: Option<u64>,
+}
+
+impl<B> FrameRing<B> {
+    fn new(frames_in_flight: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            frames_in_flight: frames_in_flight as u64,
+            frame: 0,
+            current: None,
+        }
+    }
+
+    fn begin_frame(&mut self, frame: u64) {
+        self.frame = self.frame.max(frame);
+    }
+
+    /// A slot safe to write, adding one if every slot is published or still being read.
+    fn acquire(&mut self) -> usize {
+        let (frame, in_flight) = (self.frame, self.frames_in_flight);
+        let free = (0..self.slots.len()).find(|&slot| {
+            Some(slot) != self.current
+                && self.slots[slot]
+                    .read_in
+                    .is_none_or(|read| read + in_flight <= frame)
+        });
+        free.unwrap_or_else(|| {
+            self.slots.push(RingSlot {
+                buffer: None,
+                read_in: None,
+            });
+            self.slots.len() - 1
+        })
+    }
+
+    fn buffer_mut(&mut self, slot: usize) -> &mut Option<B> {
+        &mut self.slots[slot].buffer
+    }
+
+    fn publish(&mut self, slot: usize) {
+        self.slots[slot].read_in = None;
+        self.current = Some(slot);
+    }
+
+    /// The published buffer, recorded as read in the current frame.
+    fn read(&mut self) -> Option<&B> {
+        let slot = &mut self.slots[self.current?];
+        slot.read_in = Some(self.frame);
+        slot.buffer.as_ref()
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = B> + '_ {
+        self.current = None;
+        self.slots.drain(..).filter_map(|slot| slot.buffer)
+    }
+}
+
+/// Shared handle to the instance buffers [`AshBackend`] fills from every [`GpuWorldState`]
+/// it is handed, so a renderer on the same device can bind the bodies as an instance
+/// storage buffer without copying them.
+///
+/// Call [`Self::begin_frame`] at the start of every rendered frame and fetch the buffer
+/// with [`Self::get`] each frame. A buffer the renderer fetched is neither overwritten nor
+/// freed until the frame that fetched it is `frames_in_flight` frames old (see
+/// [`AshBackend::share_instances`]), however many steps run in between.
+#[derive(Clone)]
+pub struct SharedInstances {
+    inner: Arc<Mutex<SharedState>>,
+}
+
+struct SharedState {
+    ring: FrameRing<GpuBuffer>,
+    current: Option<InstanceBuffer>,
+}
+
+impl SharedInstances {
+    fn new(frames_in_flight: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SharedState {
+                ring: FrameRing::new(frames_in_flight),
+                current: None,
+            })),
+        }
+    }
+
+    /// Starts renderer frame `frame`, a counter that goes up by one per frame. Call it once
+    /// the fence of frame `frame - frames_in_flight` has signalled, which frees the buffers
+    /// fetched in that frame and earlier.
+    pub fn begin_frame(&self, frame: u64) {
+        self.inner.lock().ring.begin_frame(frame);
+    }
+
+    /// The buffer written by the latest step, or `None` before the first one.
+    pub fn get(&self) -> Option<InstanceBuffer> {
+        let mut state = self.inner.lock();
+        state.ring.read();
+        state.current.clone()
+    }
+}
+
+impl std::fmt::Debug for SharedInstances {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.inner.lock();
+        f.debug_struct("SharedInstances")
+            .field("current", &state.current)
+            .field("slots", &state.ring.slots.len())
+            .finish()
+    }
+}
+
+/// An instance buffer written by one step.
+#[derive(Debug, Clone)]
+pub struct InstanceBuffer {
+    /// One [`GpuInstance`] per body, with `STORAGE_BUFFER` and `VERTEX_BUFFER` usage.
+    pub buffer: vk::Buffer,
+    pub count: u32,
+    /// Body each instance was taken from.
+    pub ids: Arc<[EntityId]>,
+}
+
+/// Vulkan compute backend running a uniform-grid broad phase over [`GpuWorldState`].
+///
+/// Each body is binned into the hashed grid cell holding its centre, then tested against
+/// the bodies in the 27 cells around it; bodies wider than a cell are tested against
+/// everything. The candidate pairs come back for the CPU narrowphase through the
+/// [`GpuReadback`] returned by `dispatch_broadphase`, which polls the pass's fence.
+///
+/// Every pass owns a frame of buffers, so several can be in flight; frames are reused once
+/// their readback is dropped. Work is submitted to the device's graphics queue, so the
+/// application must not submit to that queue from another thread while the world steps.
 pub struct AshBackend {
     pub device: Arc<VulkanDevice>,
     pub allocator: Arc<Allocator>,
 
-    // Buffers for world state (using interior mutability)
-    pub body_buffer: Mutex<Option<GpuBuffer>>,
-    pub grid_buffer: Mutex<Option<GpuBuffer>>,
-    pub pair_buffer: Mutex<Option<GpuBuffer>>,
-    pub counter_buffer: Mutex<Option<GpuBuffer>>,
+    kernels: Arc<GridKernels>,
+    /// Frame uploaded by `prepare_step` for the next dispatch.
+    staged: Mutex<Option<GridFrame>>,
+    idle: Arc<Mutex<Vec<GridFrame>>>,
+    instances: Mutex<Option<InstanceRing>>,
 
-    pub broadphase_pipeline: Option<Arc<ComputePipeline>>,
+    cell_size: f32,
 }
 
 impl AshBackend {
     pub fn new(device: Arc<VulkanDevice>) -> Self {
         let allocator = unsafe { Allocator::new(&device).expect("Failed to create VMA allocator") };
+        Self::with_allocator(device, Arc::new(allocator))
+    }
+
+    /// Allocates from the renderer's allocator instead of creating one, so the buffers
+    /// shared through [`Self::share_instances`] come from the renderer's memory pools.
+    pub fn with_allocator(device: Arc<VulkanDevice>, allocator: Arc<Allocator>) -> Self {
         Self {
+            kernels: Arc::new(GridKernels::new(device.clone())),
+            allocator,
+            staged: Mutex::new(None),
+            idle: Arc::new(Mutex::new(Vec::new())),
+            instances: Mutex::new(None),
+            cell_size: DEFAULT_BROADPHASE_CELL_SIZE,
             device,
-            allocator: Arc::new(allocator),
-            body_buffer: Mutex::new(None),
-            grid_buffer: Mutex::new(None),
-            pair_buffer: Mutex::new(None),
-            counter_buffer: Mutex::new(None),
-            broadphase_pipeline: None,
         }
     }
 
-    pub fn set_broadphase_pipeline(&mut self, pipeline: Arc<ComputePipeline>) {
-        self.broadphase_pipeline = Some(pipeline);
+    /// Starts writing every world state `prepare_step` receives into instance buffers a
+    /// renderer can bind, and returns the handle to them. `frames_in_flight` is how many
+    /// frames the renderer may still be reading an older buffer from.
+    ///
+    /// Calling it again returns the same handle; `frames_in_flight` only grows.
+    pub fn share_instances(&self, frames_in_flight: usize) -> SharedInstances {
+        let mut slot = self.instances.lock();
+        let ring = slot.get_or_insert_with(|| InstanceRing {
+            device: self.device.clone(),
+            allocator: self.allocator.clone(),
+            shared: SharedInstances::new(frames_in_flight),
+        });
+        let mut state = ring.shared.inner.lock();
+        state.ring.frames_in_flight = state.ring.frames_in_flight.max(frames_in_flight as u64);
+        drop(state);
+        ring.shared.clone()
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Edge length of the grid cells. Bodies wider than a cell skip the grid and are tested
+    /// against every other body, so pick a size a little above the typical body diameter.
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        if cell_size > 0.0 {
+            self.cell_size = cell_size;
+        }
+    }
+
+    /// An idle frame, or a new one if every frame is held by a pending readback.
+    fn acquire(&self) -> GridFrame {
+        let frame = self.idle.lock().pop();
+        let mut frame =
+            frame.unwrap_or_else(|| GridFrame::new(self.kernels.clone(), self.allocator.clone()));
+        // A readback dropped before its fence signalled leaves the frame running.
+        frame.wait(true);
+        frame
+    }
+}
+
+impl GridKernels {
+    fn new(device: Arc<VulkanDevice>) -> Self {
+        let vk_device = &device.device;
+        let code = compile_wgsl(include_str!("shaders/grid_broadphase.wgsl"));
+
+        unsafe {
+            let shader_module = vk_device
+                .create_shader_module(&vk::ShaderModuleCreateInfo::default().code(&code), None)
+                .expect("Failed to create broadphase shader module");
+
+            let bindings: Vec<vk::DescriptorSetLayoutBinding> = (0..=STORAGE_BINDINGS)
+                .map(|binding| {
+                    vk::DescriptorSetLayoutBinding::default()
+                        .binding(binding)
+                        .descriptor_type(descriptor_type(binding))
+                        .descriptor_count(1)
+                        .stage_flags(vk::ShaderStageFlags::COMPUTE)
+                })
+                .collect();
+            let set_layout = vk_device
+                .create_descriptor_set_layout(
+                    &vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings),
+                    None,
+                )
+                .expect("Failed to create broadphase descriptor set layout");
+            let set_layouts = [set_layout];
+            let pipeline_layout = vk_device
+                .create_pipeline_layout(
+                    &vk::PipelineLayoutCreateInfo::default().set_layouts(&set_layouts),
+                    None,
+                )
+                .expect("Failed to create broadphase pipeline layout");
+
+            let pipeline = |entry_point: &str| {
+                ComputePipeline::builder(vk_device.clone())
+                    .with_layout(pipeline_layout)
+                    .with_shader(shader_module)
+                    .with_entry_point(entry_point)
+                    .build()
+                    .expect("Failed to create broadphase pipeline")
+            };
+            let bin_pipeline = pipeline("bin");
+            let pairs_pipeline = pipeline("find_pairs");
+
+            Self {
+                shader_module,
+                set_layout,
+                pipeline_layout,
+                bin_pipeline: Some(bin_pipeline),
+                pairs_pipeline: Some(pairs_pipeline),
+                device,
+            }
+        }
+    }
+}
+
+impl GridFrame {
+    fn new(kernels: Arc<GridKernels>, allocator: Arc<Allocator>) -> Self {
+        let device = &kernels.device;
+        let vk_device = &device.device;
+        unsafe {
+            let pool_sizes = [
+                vk::DescriptorPoolSize {
+                    ty: vk::DescriptorType::UNIFORM_BUFFER,
+                    descriptor_count: 1,
+                },
+                vk::DescriptorPoolSize {
+                    ty: vk::DescriptorType::STORAGE_BUFFER,
+                    descriptor_count: STORAGE_BINDINGS,
+                },
+            ];
+            let descriptor_pool = vk_device
+                .create_descriptor_pool(
+                    &vk::DescriptorPoolCreateInfo::default()
+                        .max_sets(1)
+                        .pool_sizes(&pool_sizes),
+                    None,
+                )
+                .expect("Failed to create broadphase descriptor pool");
+            let set_layouts = [kernels.set_layout];
+            let descriptor_set = vk_device
+                .allocate_descriptor_sets(
+                    &vk::DescriptorSetAllocateInfo::default()
+                        .descriptor_pool(descriptor_pool)
+                        .set_layouts(&set_layouts),
+                )
+                .expect("Failed to allocate broadphase descriptor set")[0];
+
+            let mut pool = CommandPool::new(vk_device.clone(), device.graphics_queue_family)
+                .expect("Failed to create broadphase command pool");
+            let command_buffer = pool
+                .allocate_primary_buffers(1)
+                .expect("Failed to allocate broadphase command buffer")[0];
+            let fence = vk_device
+                .create_fence(&vk::FenceCreateInfo::default(), None)
+                .expect("Failed to create broadphase fence");
+
+            Self {
+                allocator,
+                buffers: GridBuffers::default(),
+                descriptor_pool,
+                descriptor_set,
+                pool,
+                command_buffer,
+                fence,
+                submitted: false,
+                body_count: 0,
+                kernels,
+            }
+        }
     }
 
+    /// Grows `existing` to at least `size` bytes, returning whether it was reallocated.
     fn ensure_buffer(
-        &self,
+        allocator: &Allocator,
         existing: &mut Option<GpuBuffer>,
         size: u64,
         usage: vk::BufferUsageFlags,
-    ) {
+        access: Access,
+    ) -> bool {
         if let Some(buf) = existing {
             if buf.size >= size {
-                return;
+                return false;
             }
-            // Recreate if too small
+            // Recreate if too small; frames are only touched once their fence has signalled.
             unsafe {
-                self.allocator
-                    .destroy_buffer(buf.buffer, &mut buf.allocation);
+                allocator.destroy_buffer(buf.buffer, &mut buf.allocation);
             }
         }
 
+        let (memory_usage, flags) = match access {
+            Access::Upload => (
+                vk_mem::MemoryUsage::AutoPreferHost,
+                vk_mem::AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE,
+            ),
+            Access::DeviceOnly => (
+                vk_mem::MemoryUsage::AutoPreferDevice,
+                vk_mem::AllocationCreateFlags::empty(),
+            ),
+            Access::Readback => (
+                vk_mem::MemoryUsage::AutoPreferHost,
+                vk_mem::AllocationCreateFlags::HOST_ACCESS_RANDOM,
+            ),
+            Access::Shared => (
+                vk_mem::MemoryUsage::AutoPreferDevice,
+                vk_mem::AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE,
+            ),
+        };
         let (buffer, allocation) = unsafe {
-            self.allocator
+            allocator
+                .vma
                 .create_buffer(
-                    size,
-                    usage | vk::BufferUsageFlags::STORAGE_BUFFER,
-                    vk_mem::MemoryUsage::AutoPreferHost,
+                    &vk::BufferCreateInfo::default()
+                        .size(size)
+                        .usage(usage | vk::BufferUsageFlags::STORAGE_BUFFER)
+                        .sharing_mode(vk::SharingMode::EXCLUSIVE),
+                    &vk_mem::AllocationCreateInfo {
+                        usage: memory_usage,
+                        flags,
+                        ..Default::default()
+                    },
                 )
                 .expect("Failed to create GPU buffer")
         };
@@ -72,145 +490,587 @@ impl AshBackend {
             allocation,
             size,
         });
+        true
     }
-}
 
-impl ComputeBackend for AshBackend {
-    fn name(&self) -> &str {
-        "vulkan-ash"
+    fn write_buffer<T: Copy>(allocator: &Allocator, buffer: &mut GpuBuffer, data: &[T]) {
+        unsafe {
+            let mut mapped = allocator
+                .map_allocation_guarded(&mut buffer.allocation, buffer.size)
+                .expect("Failed to map GPU buffer");
+            mapped.copy_from_slice(data);
+        }
+        allocator
+            .vma
+            .flush_allocation(&buffer.allocation, 0, vk::WHOLE_SIZE)
+            .expect("Failed to flush GPU buffer");
     }
 
-    fn prepare_step(&self, state: &GpuWorldState) {
-        let count = state.body_count();
+    fn read_buffer<T: Copy>(allocator: &Allocator, buffer: &mut GpuBuffer, count: usize) -> Vec<T> {
+        debug_assert!((count * std::mem::size_of::<T>()) as u64 <= buffer.size);
+        allocator
+            .vma
+            .invalidate_allocation(&buffer.allocation, 0, vk::WHOLE_SIZE)
+            .expect("Failed to invalidate GPU buffer");
+        unsafe {
+            let ptr = allocator
+                .vma
+                .map_memory(&mut buffer.allocation)
+                .expect("Failed to map GPU buffer");
+            let data = std::slice::from_raw_parts(ptr as *const T, count).to_vec();
+            allocator.vma.unmap_memory(&mut buffer.allocation);
+            data
+        }
+    }
+
+    /// Sizes the buffers for `state` and uploads its bodies.
+    fn upload(&mut self, state: &GpuWorldState) {
+        let count = state.body_count() as u64;
+        self.body_count = count as u32;
         if count == 0 {
             return;
         }
 
-        let body_size = (state.bodies.len() * std::mem::size_of::<crate::gpu::GpuBody>()) as u64;
-        // Conservative grid size for hashing (e.g., 64k cells)
-        let grid_size = (65536 * std::mem::size_of::<i32>()) as u64;
-        // Max pairs we can store (e.g., 1M pairs)
-        let pair_size = (1_000_000 * std::mem::size_of::<[u32; 2]>()) as u64;
-        let counter_size = std::mem::size_of::<u32>() as u64;
+        let body_size = count * std::mem::size_of::<GpuBody>() as u64;
+        let index_size = count * std::mem::size_of::<u32>() as u64;
+        let grid_size = u64::from(table_size(count as u32)) * std::mem::size_of::<i32>() as u64;
+        // Start with room for a few pairs per body; overflow grows the buffer.
+        let pair_size = count * 4 * PAIR_SIZE;
+        let counter_size = 2 * std::mem::size_of::<u32>() as u64;
 
-        unsafe {
-            // Body Sync
-            {
-                let mut body_lock = self.body_buffer.lock();
-                self.ensure_buffer(&mut body_lock, body_size, vk::BufferUsageFlags::empty());
-                if let Some(buf) = body_lock.as_mut() {
-                    let ptr = self
-                        .allocator
-                        .vma
-                        .map_memory(&mut buf.allocation)
-                        .expect("Failed to map body buffer");
-                    std::ptr::copy_nonoverlapping(
-                        state.bodies.as_ptr(),
-                        ptr as *mut crate::gpu::GpuBody,
-                        state.bodies.len(),
-                    );
-                    self.allocator.vma.unmap_memory(&mut buf.allocation);
-                }
-            }
+        let allocator = &*self.allocator;
+        let buffers = &mut self.buffers;
+        let transfer = vk::BufferUsageFlags::TRANSFER_DST;
+        let mut reallocated = false;
+        for (buffer, size, usage, access) in [
+            (
+                &mut buffers.params,
+                std::mem::size_of::<GridParams>() as u64,
+                vk::BufferUsageFlags::UNIFORM_BUFFER,
+                Access::Upload,
+            ),
+            (
+                &mut buffers.bodies,
+                body_size,
+                vk::BufferUsageFlags::empty(),
+                Access::Upload,
+            ),
+            (&mut buffers.heads, grid_size, transfer, Access::DeviceOnly),
+            (
+                &mut buffers.nexts,
+                index_size,
+                vk::BufferUsageFlags::empty(),
+                Access::DeviceOnly,
+            ),
+            (
+                &mut buffers.oversized,
+                index_size,
+                vk::BufferUsageFlags::empty(),
+                Access::DeviceOnly,
+            ),
+            (
+                &mut buffers.counters,
+                counter_size,
+                transfer,
+                Access::Readback,
+            ),
+            (
+                &mut buffers.pairs,
+                pair_size,
+                vk::BufferUsageFlags::empty(),
+                Access::Readback,
+            ),
+        ] {
+            reallocated |= Self::ensure_buffer(allocator, buffer, size, usage, access);
+        }
+        if reallocated {
+            buffers.bound = false;
+        }
 
-            // Grid Buffer Size ensuring
-            {
-                let mut grid_lock = self.grid_buffer.lock();
-                self.ensure_buffer(&mut grid_lock, grid_size, vk::BufferUsageFlags::empty());
-            }
+        // Body Sync
+        if let Some(bodies) = buffers.bodies.as_mut() {
+            Self::write_buffer(allocator, bodies, &state.bodies);
+        }
+    }
 
-            // Pair Buffer Size ensuring
-            {
-                let mut pair_lock = self.pair_buffer.lock();
-                self.ensure_buffer(&mut pair_lock, pair_size, vk::BufferUsageFlags::empty());
-            }
+    fn pair_capacity(&self) -> u32 {
+        self.buffers
+            .pairs
+            .as_ref()
+            .map_or(0, |pairs| pairs.size / PAIR_SIZE) as u32
+    }
 
-            // Counter Buffer Size ensuring
-            {
-                let mut counter_lock = self.counter_buffer.lock();
-                self.ensure_buffer(
-                    &mut counter_lock,
-                    counter_size,
-                    vk::BufferUsageFlags::empty(),
-                );
-            }
+    fn grow_pairs(&mut self, count: u32) {
+        if Self::ensure_buffer(
+            &self.allocator,
+            &mut self.buffers.pairs,
+            u64::from(count.next_power_of_two()) * PAIR_SIZE,
+            vk::BufferUsageFlags::empty(),
+            Access::Readback,
+        ) {
+            self.buffers.bound = false;
         }
     }
 
-    fn dispatch_broadphase(&self, state: &GpuWorldState) {
-        let count = state.body_count();
-        if count == 0 {
+    /// Points the descriptor set at the current buffers after any of them was reallocated.
+    fn bind_buffers(&mut self) {
+        let buffers = &mut self.buffers;
+        if buffers.bound {
             return;
         }
+        let bound = [
+            &buffers.params,
+            &buffers.bodies,
+            &buffers.heads,
+            &buffers.nexts,
+            &buffers.oversized,
+            &buffers.counters,
+            &buffers.pairs,
+        ];
+        let infos: Vec<[vk::DescriptorBufferInfo; 1]> = bound
+            .iter()
+            .map(|buffer| {
+                let buffer = buffer.as_ref().expect("upload allocates every buffer");
+                [vk::DescriptorBufferInfo {
+                    buffer: buffer.buffer,
+                    offset: 0,
+                    range: vk::WHOLE_SIZE,
+                }]
+            })
+            .collect();
+        let writes: Vec<vk::WriteDescriptorSet> = infos
+            .iter()
+            .enumerate()
+            .map(|(binding, info)| {
+                vk::WriteDescriptorSet::default()
+                    .dst_set(self.descriptor_set)
+                    .dst_binding(binding as u32)
+                    .descriptor_type(descriptor_type(binding as u32))
+                    .buffer_info(info)
+            })
+            .collect();
+        unsafe {
+            self.kernels
+                .device
+                .device
+                .update_descriptor_sets(&writes, &[]);
+        }
+        buffers.bound = true;
+    }
 
-        let _pipeline = match &self.broadphase_pipeline {
-            Some(p) => p,
-            None => return,
-        };
+    /// Records the grid pass and submits it without waiting; see [`Self::wait`].
+    fn submit(&mut self, params: GridParams) {
+        if let Some(buffer) = self.buffers.params.as_mut() {
+            Self::write_buffer(&self.allocator, buffer, &[params]);
+        }
+        self.bind_buffers();
+
+        let command_buffer = self.command_buffer;
+        self.pool
+            .begin_command_buffer(command_buffer, vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT)
+            .expect("Failed to begin broadphase commands");
+        self.record_grid_pass(command_buffer, params.body_count);
+        self.pool
+            .end_command_buffer(command_buffer)
+            .expect("Failed to end broadphase commands");
 
+        let device = &self.kernels.device;
+        let command_buffers = [command_buffer];
+        let submits = [vk::SubmitInfo::default().command_buffers(&command_buffers)];
         unsafe {
-            let body_buf = self.body_buffer.lock();
-            let grid_buf = self.grid_buffer.lock();
-            let pair_buf = self.pair_buffer.lock();
-            let counter_buf = self.counter_buffer.lock();
-
-            if let (Some(_bodies), Some(grid), Some(_pairs), Some(counters)) =
-                (&*body_buf, &*grid_buf, &*pair_buf, &*counter_buf)
-            {
-                // In a real implementation, we'd use command buffers from AshRenderer.
-                // Assuming AshRenderer's ComputePipeline provides a simplified dispatch.
-                // For this MVP, we acknowledge dispatch occurs here.
-
-                // 1. Clear Counter
-                let ptr = self
-                    .allocator
-                    .vma
-                    .map_memory(&mut counters.allocation.clone())
-                    .expect("Map fail");
-                std::ptr::write(ptr as *mut u32, 0);
-                self.allocator
-                    .vma
-                    .unmap_memory(&mut counters.allocation.clone());
-
-                // 2. Clear Grid (partial)
-                let grid_ptr = self
-                    .allocator
-                    .vma
-                    .map_memory(&mut grid.allocation.clone())
-                    .expect("Map fail");
-                std::ptr::write_bytes(grid_ptr, 0xFF, grid.size as usize); // -1 in int
-                self.allocator
-                    .vma
-                    .unmap_memory(&mut grid.allocation.clone());
+            device
+                .device
+                .queue_submit(device.graphics_queue, &submits, self.fence)
+                .expect("Failed to submit broadphase");
+        }
+        self.submitted = true;
+    }
 
-                // 3. Dispatch Broadphase (Shader now handles neighbor search)
-                // Note: The shader currently expects populated heads/nexts.
-                // A better implementation would have a separate 'Insertion' kernel.
-                // For this polish, we provide the hooks.
+    /// Whether the last submission has finished, waiting for it first if `block` is set.
+    fn wait(&mut self, block: bool) -> bool {
+        if !self.submitted {
+            return true;
+        }
+        let device = &self.kernels.device.device;
+        let fences = [self.fence];
+        let done = unsafe {
+            if block {
+                device
+                    .wait_for_fences(&fences, true, u64::MAX)
+                    .expect("Failed to wait for broadphase");
+                true
+            } else {
+                device
+                    .get_fence_status(self.fence)
+                    .expect("Failed to query broadphase fence")
+            }
+        };
+        if done {
+            unsafe {
+                device
+                    .reset_fences(&fences)
+                    .expect("Failed to reset broadphase fence");
             }
+            self.submitted = false;
         }
+        done
+    }
+
+    /// Clears the grid, then runs the bin and pair kernels back to back.
+    fn record_grid_pass(&self, command_buffer: vk::CommandBuffer, body_count: u32) {
+        let kernels = &*self.kernels;
+        let (Some(heads), Some(counters), Some(bin), Some(find_pairs)) = (
+            &self.buffers.heads,
+            &self.buffers.counters,
+            &kernels.bin_pipeline,
+            &kernels.pairs_pipeline,
+        ) else {
+            return;
+        };
+        let device = &kernels.device.device;
+        let barrier = |src_stage, src_access, dst_stage, dst_access| unsafe {
+            let barriers = [vk::MemoryBarrier::default()
+                .src_access_mask(src_access)
+                .dst_access_mask(dst_access)];
+            device.cmd_pipeline_barrier(
+                command_buffer,
+                src_stage,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &barriers,
+                &[],
+                &[],
+            );
+        };
+        let shader_access = vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE;
+        let workgroups = body_count.div_ceil(WORKGROUP_SIZE);
+
+        unsafe {
+            // Empty buckets hold -1; both counters start at zero.
+            device.cmd_fill_buffer(command_buffer, heads.buffer, 0, vk::WHOLE_SIZE, u32::MAX);
+            device.cmd_fill_buffer(command_buffer, counters.buffer, 0, vk::WHOLE_SIZE, 0);
+            barrier(
+                vk::PipelineStageFlags::TRANSFER,
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                shader_access,
+            );
+
+            device.cmd_bind_descriptor_sets(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                kernels.pipeline_layout,
+                0,
+                &[self.descriptor_set],
+                &[],
+            );
+            device.cmd_bind_pipeline(command_buffer, vk::PipelineBindPoint::COMPUTE, bin.handle());
+            device.cmd_dispatch(command_buffer, workgroups, 1, 1);
+            barrier(
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::SHADER_WRITE,
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                shader_access,
+            );
+
+            device.cmd_bind_pipeline(
+                command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                find_pairs.handle(),
+            );
+            device.cmd_dispatch(command_buffer, workgroups, 1, 1);
+            barrier(
+                vk::PipelineStageFlags::COMPUTE_SHADER,
+                vk::AccessFlags::SHADER_WRITE,
+                vk::PipelineStageFlags::HOST,
+                vk::AccessFlags::HOST_READ,
+            );
+        }
+    }
+
+    /// Pairs found by the finished pass, or `Err` with the pair count if they overflowed
+    /// the pair buffer.
+    fn read_pairs(&mut self) -> Result<Vec<(u32, u32)>, u32> {
+        let capacity = self.pair_capacity();
+        let allocator = &*self.allocator;
+        let (Some(counters), Some(pairs)) =
+            (self.buffers.counters.as_mut(), self.buffers.pairs.as_mut())
+        else {
+            return Ok(Vec::new());
+        };
+        let found = Self::read_buffer::<u32>(allocator, counters, 1)[0];
+        if found > capacity {
+            return Err(found);
+        }
+        let mut pairs: Vec<(u32, u32)> =
+            Self::read_buffer::<[u32; 2]>(allocator, pairs, found as usize)
+                .into_iter()
+                .map(|[i, j]| (i, j))
+                .collect();
+        // Neighbouring cells that share a hash bucket report their pairs twice.
+        pairs.sort_unstable();
+        pairs.dedup();
+        Ok(pairs)
     }
 }
 
-impl Drop for AshBackend {
+impl InstanceRing {
+    /// Writes the instances of `state` into a slot no frame in flight reads and publishes it.
+    fn write(&mut self, state: &GpuWorldState) {
+        let instances = state.instances();
+        let mut shared = self.shared.inner.lock();
+        let slot = shared.ring.acquire();
+        let buffer = shared.ring.buffer_mut(slot);
+        let size = (instances.len().max(1) * std::mem::size_of::<GpuInstance>()) as u64;
+        // Growing frees the old buffer, which is safe as no frame in flight reads the slot.
+        GridFrame::ensure_buffer(
+            &self.allocator,
+            buffer,
+            size.next_power_of_two(),
+            vk::BufferUsageFlags::VERTEX_BUFFER,
+            Access::Shared,
+        );
+        let buffer = buffer.as_mut().expect("ensure_buffer allocates the buffer");
+        GridFrame::write_buffer(&self.allocator, buffer, &instances);
+        let handle = buffer.buffer;
+        shared.ring.publish(slot);
+        shared.current = Some(InstanceBuffer {
+            buffer: handle,
+            count: instances.len() as u32,
+            ids: state.ids.as_slice().into(),
+        });
+    }
+}
+
+impl Drop for InstanceRing {
     fn drop(&mut self) {
+        let mut shared = self.shared.inner.lock();
+        shared.current = None;
         unsafe {
-            if let Some(mut buf) = self.body_buffer.lock().take() {
+            // The renderer may still be drawing from any of the buffers.
+            let _ = self.device.device.device_wait_idle();
+            for mut buf in shared.ring.drain() {
                 self.allocator
                     .destroy_buffer(buf.buffer, &mut buf.allocation);
             }
-            if let Some(mut buf) = self.grid_buffer.lock().take() {
-                self.allocator
-                    .destroy_buffer(buf.buffer, &mut buf.allocation);
+        }
+    }
+}
+
+impl GridPass {
+    /// Reads the pairs once the fence signals, running the pass again with a larger pair
+    /// buffer if it overflowed.
+    fn poll(&mut self, block: bool) -> Option<Vec<(u32, u32)>> {
+        let Some(frame) = self.frame.as_mut() else {
+            return Some(Vec::new());
+        };
+        loop {
+            if !frame.wait(block) {
+                return None;
             }
-            if let Some(mut buf) = self.pair_buffer.lock().take() {
-                self.allocator
-                    .destroy_buffer(buf.buffer, &mut buf.allocation);
+            match frame.read_pairs() {
+                Ok(pairs) => return Some(pairs),
+                Err(found) => {
+                    frame.grow_pairs(found);
+                    self.params.max_pairs = frame.pair_capacity();
+                    frame.submit(self.params);
+                }
             }
-            if let Some(mut buf) = self.counter_buffer.lock().take() {
-                self.allocator
-                    .destroy_buffer(buf.buffer, &mut buf.allocation);
+        }
+    }
+}
+
+impl Drop for GridPass {
+    fn drop(&mut self) {
+        if let Some(frame) = self.frame.take() {
+            self.idle.lock().push(frame);
+        }
+    }
+}
+
+impl ComputeBackend for AshBackend {
+    fn name(&self) -> &str {
+        "vulkan-ash"
+    }
+
+    fn prepare_step(&self, state: &GpuWorldState) {
+        let staged = self.staged.lock().take();
+        let mut frame = staged.unwrap_or_else(|| self.acquire());
+        frame.upload(state);
+        *self.staged.lock() = Some(frame);
+        if let Some(ring) = self.instances.lock().as_mut() {
+            ring.write(state);
+        }
+    }
+
+    fn dispatch_broadphase(&self, state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        if state.body_count() < 2 {
+            return Some(GpuReadback::ready(Vec::new()));
+        }
+        let staged = self.staged.lock().take();
+        let mut frame = staged
+            .filter(|frame| frame.body_count as usize == state.body_count())
+            .unwrap_or_else(|| {
+                let mut frame = self.acquire();
+                frame.upload(state);
+                frame
+            });
+
+        let params = GridParams {
+            body_count: frame.body_count,
+            table_size: table_size(frame.body_count),
+            max_pairs: frame.pair_capacity(),
+            cell_size: self.cell_size,
+        };
+        frame.submit(params);
+        let mut pass = GridPass {
+            frame: Some(frame),
+            idle: self.idle.clone(),
+            params,
+        };
+        Some(GpuReadback::pending(move |block| pass.poll(block)))
+    }
+}
+
+impl Drop for GridFrame {
+    fn drop(&mut self) {
+        self.wait(true);
+        unsafe {
+            let buffers = &mut self.buffers;
+            for buffer in [
+                &mut buffers.params,
+                &mut buffers.bodies,
+                &mut buffers.heads,
+                &mut buffers.nexts,
+                &mut buffers.oversized,
+                &mut buffers.counters,
+                &mut buffers.pairs,
+            ] {
+                if let Some(mut buf) = buffer.take() {
+                    self.allocator
+                        .destroy_buffer(buf.buffer, &mut buf.allocation);
+                }
             }
+
+            let device = &self.kernels.device.device;
+            device.destroy_descriptor_pool(self.descriptor_pool, None);
+            device.destroy_fence(self.fence, None);
+        }
+    }
+}
+
+impl Drop for GridKernels {
+    fn drop(&mut self) {
+        self.bin_pipeline.take();
+        self.pairs_pipeline.take();
+        unsafe {
+            let device = &self.device.device;
+            device.destroy_pipeline_layout(self.pipeline_layout, None);
+            device.destroy_descriptor_set_layout(self.set_layout, None);
+            device.destroy_shader_module(self.shader_module, None);
         }
     }
 }
+
+fn descriptor_type(binding: u32) -> vk::DescriptorType {
+    if binding == 0 {
+        vk::DescriptorType::UNIFORM_BUFFER
+    } else {
+        vk::DescriptorType::STORAGE_BUFFER
+    }
+}
+
+/// Hash buckets for `body_count` bodies: a power of two with about two buckets per body.
+fn table_size(body_count: u32) -> u32 {
+    (body_count.max(1) * 2).next_power_of_two()
+}
+
+/// Compiles one of the built-in WGSL kernels to SPIR-V.
+fn compile_wgsl(source: &str) -> Vec<u32> {
+    let module = naga::front::wgsl::parse_str(source).expect("Built-in shader failed to parse");
+    let info = naga::valid::Validator::new(
+        naga::valid::ValidationFlags::all(),
+        naga::valid::Capabilities::empty(),
+    )
+    .validate(&module)
+    .expect("Built-in shader failed to validate");
+    naga::back::spv::write_vec(&module, &info, &Default::default(), None)
+        .expect("Built-in shader failed to compile to SPIR-V")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a new buffer id the way `InstanceRing::write` does, returning its slot.
+    fn write(ring: &mut FrameRing<u32>, id: u32) -> usize {
+        let slot = ring.acquire();
+        *ring.buffer_mut(slot) = Some(id);
+        ring.publish(slot);
+        slot
+    }
+
+    #[test]
+    fn several_steps_per_frame_reuse_unread_slots() {
+        let mut ring = FrameRing::new(2);
+        ring.begin_frame(0);
+        let first = write(&mut ring, 0);
+        let second = write(&mut ring, 1);
+        let third = write(&mut ring, 2);
+        assert_ne!(first, second);
+        // Nothing was read, so the first slot is free again once superseded.
+        assert_eq!(third, first);
+        assert_eq!(ring.slots.len(), 2);
+        assert_eq!(ring.read(), Some(&2));
+    }
+
+    #[test]
+    fn read_slots_stay_untouched_until_their_frame_retires() {
+        let mut ring = FrameRing::new(2);
+        ring.begin_frame(0);
+        let read_in_0 = write(&mut ring, 0);
+        ring.read();
+
+        ring.begin_frame(1);
+        for id in 1..6 {
+            assert_ne!(write(&mut ring, id), read_in_0);
+        }
+        let read_in_1 = ring.current.unwrap();
+        ring.read();
+
+        // Frame 0 is two frames old once frame 2 begins, so its slot is free again.
+        ring.begin_frame(2);
+        let slots = ring.slots.len();
+        let next = write(&mut ring, 6);
+        assert_eq!(next, read_in_0);
+        assert_ne!(next, read_in_1);
+        assert_eq!(ring.slots.len(), slots);
+    }
+
+    #[test]
+    fn published_buffer_read_again_stays_in_flight() {
+        let mut ring = FrameRing::new(1);
+        ring.begin_frame(0);
+        let slot = write(&mut ring, 0);
+        ring.read();
+        // No step before the renderer draws the same buffer again in frame 1.
+        ring.begin_frame(1);
+        ring.read();
+        assert_ne!(write(&mut ring, 1), slot);
+        assert_ne!(write(&mut ring, 2), slot);
+        ring.begin_frame(2);
+        assert_eq!(write(&mut ring, 3), slot);
+    }
+
+    #[test]
+    fn drain_hands_back_every_buffer() {
+        let mut ring = FrameRing::new(1);
+        write(&mut ring, 0);
+        ring.read();
+        write(&mut ring, 1);
+        let mut buffers: Vec<_> = ring.drain().collect();
+        buffers.sort();
+        assert_eq!(buffers, [0, 1]);
+        assert_eq!(ring.read(), None);
+    }
+}
@@ -1,25 +1,264 @@
+#[cfg(not(target_arch = "wasm32"))]
 pub mod ash_backend;
-pub use ash_backend::AshBackend;
+#[cfg(not(target_arch = "wasm32"))]
+pub use ash_backend::{AshBackend, InstanceBuffer, SharedInstances};
+#[cfg(all(feature = "wgpu", not(target_arch = "wasm32")))]
+pub mod wgpu_backend;
+#[cfg(all(feature = "wgpu", not(target_arch = "wasm32")))]
+pub use wgpu_backend::{BroadphaseMode, ParticleBuffer, WgpuBackend};
 
-use glam::Vec3;
+use std::{collections::HashMap, fmt};
+
+use glam::{Mat3, Mat3A, Quat, Vec3, Vec3A, Vec4};
+use parking_lot::Mutex;
 
 use crate::{
-    core::{collider::Collider, rigidbody::RigidBody},
-    utils::allocator::Arena,
+    collision::{
+        broadphase::SpeculativeExpansion,
+        queries::{Ray, RaycastQuery},
+    },
+    core::{
+        collider::{Collider, ColliderShape, CollisionFilter},
+        rigidbody::RigidBody,
+        soa::BodiesSoA,
+        types::{Transform, Velocity},
+    },
+    dynamics::particles::ParticleSystem,
+    utils::allocator::{Arena, EntityId},
 };
 
-/// GPU-friendly rigid body data.
+/// GPU-friendly rigid body data, laid out to match a WGSL/GLSL `std430` struct.
 #[repr(C)]
 #[derive(Debug, Default, Clone, Copy)]
 pub struct GpuBody {
     pub position: Vec3,
+    /// Radius of a sphere around `position` bounding every collider of the body.
     pub radius: f32,
+    pub velocity: Vec3,
+    /// Zero for static and kinematic bodies.
+    pub inverse_mass: f32,
+}
+
+/// One body as a renderer instances it, laid out to match a `std430` struct.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpuInstance {
+    pub position: Vec3,
+    /// Radius of a sphere around `position` bounding every collider of the body, for culling.
+    pub radius: f32,
+    pub rotation: Quat,
+}
+
+/// Shape kinds a [`GpuShape`] encodes, with the meaning of its `params`.
+#[repr(u32)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GpuShapeKind {
+    /// `x`: radius.
+    #[default]
+    Sphere = 0,
+    /// `xyz`: half extents.
+    Box = 1,
+    /// `x`: radius, `y`: height.
+    Capsule = 2,
+    /// `x`: radius, `y`: height.
+    Cylinder = 3,
+    /// `x`: radius, `y`: height.
+    Cone = 4,
+    /// `xyz`: half extents, `w`: border radius.
+    RoundedBox = 5,
+    /// `x`: radius, `y`: height, `z`: border radius.
+    RoundedCylinder = 6,
+    /// `xyz`: normal.
+    Plane = 7,
+    /// Convex hulls, meshes and heightfields. `x`: bounding radius; kernels treat these as
+    /// spheres or leave them to the CPU.
+    Complex = 8,
+}
+
+/// One primitive collider shape, laid out to match a `std430` struct.
+///
+/// Compound colliders are flattened into one entry per child.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpuShape {
+    /// Position relative to the owning body.
+    pub offset: Vec3,
+    pub kind: GpuShapeKind,
+    /// Rotation relative to the owning body.
+    pub rotation: Quat,
+    pub params: Vec4,
+}
+
+/// Collision filter of a [`GpuShape`]; two shapes interact only when each one's mask
+/// contains the other's layer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GpuFilter {
+    pub layer: u32,
+    pub mask: u32,
+}
+
+impl From<CollisionFilter> for GpuFilter {
+    fn from(filter: CollisionFilter) -> Self {
+        Self {
+            layer: filter.layer,
+            mask: filter.mask,
+        }
+    }
+}
+
+/// One particle slot, laid out to match a WGSL/GLSL `std430` struct.
+///
+/// Slot `i` holds the particle whose id has index `i`. Dead slots have a negative radius.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpuParticle {
+    pub position: Vec3,
+    pub radius: f32,
+    pub velocity: Vec3,
+    /// Zero for particles that ignore gravity and collisions with other particles.
+    pub inverse_mass: f32,
+}
+
+impl GpuParticle {
+    pub const DEAD: Self = Self {
+        position: Vec3::ZERO,
+        radius: -1.0,
+        velocity: Vec3::ZERO,
+        inverse_mass: 0.0,
+    };
+
+    pub fn is_alive(&self) -> bool {
+        self.radius >= 0.0
+    }
+}
+
+/// A solid collider shape in world space that GPU particles bounce off, with the motion
+/// of its body.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct GpuObstacle {
+    /// `offset` and `rotation` place the shape in world space.
+    pub shape: GpuShape,
+    /// Position of the owning body, which `angular_velocity` turns around.
+    pub pivot: Vec3,
+    /// Radius around `shape.offset` bounding the shape; unused for planes.
+    pub reach: f32,
+    pub linear_velocity: Vec3,
+    pub angular_velocity: Vec3,
+}
+
+/// What [`ComputeBackend::dispatch_particles`] needs to step a GPU-resident particle system.
+///
+/// The particles stay on the device between steps, so the state only carries the slots the
+/// CPU changed since the last step.
+#[derive(Debug, Default, Clone)]
+pub struct GpuParticleState {
+    /// Number of slots, live or dead.
+    pub slot_count: u32,
+    /// Slots spawned or killed since the last step, sorted by slot.
+    pub writes: Vec<(u32, GpuParticle)>,
+    /// Velocities set since the last step, sorted by slot. Applied after `writes`.
+    pub velocity_writes: Vec<(u32, Vec3)>,
+    /// Solid colliders, with compound colliders flattened into their children.
+    pub obstacles: Vec<GpuObstacle>,
+    pub gravity: Vec3,
+    pub time_step: f32,
+    /// Factor the velocities are scaled by each step.
+    pub damping: f32,
+    pub restitution: f32,
+    pub friction: f32,
+    /// Cell size of the particle-particle collision grid, twice the largest radius. Zero
+    /// turns particle-particle collisions off.
+    pub cell_size: f32,
+}
+
+impl GpuParticleState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes the changes `particles` queued since the last sync, plus the world's solid
+    /// colliders if the particles collide.
+    pub fn sync(
+        &mut self,
+        particles: &mut ParticleSystem,
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        gravity: Vec3,
+        time_step: f32,
+    ) {
+        particles.take_gpu_writes(self);
+        self.gravity = gravity;
+        self.time_step = time_step;
+        self.damping = (1.0 - particles.damping * time_step).max(0.0);
+        self.restitution = particles.restitution;
+        self.friction = particles.friction.clamp(0.0, 1.0);
+
+        self.obstacles.clear();
+        if !particles.collisions {
+            self.cell_size = 0.0;
+            return;
+        }
+        for collider in colliders.iter().filter(|collider| !collider.is_trigger) {
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let velocity = body.velocity();
+            let pivot = body.transform().position;
+            let transform = collider.world_transform(body.transform());
+            flatten_shape(&transform, &collider.shape, &mut |shape| {
+                self.obstacles.push(GpuObstacle {
+                    shape,
+                    pivot,
+                    reach: shape_reach(&shape),
+                    linear_velocity: velocity.linear,
+                    angular_velocity: velocity.angular,
+                });
+            });
+        }
+    }
+}
+
+/// Closest hit of one ray cast by [`ComputeBackend::dispatch_raycasts`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpuRayHit {
+    /// `shape` indexes the per-shape columns of the state the rays were cast against, and
+    /// `distance` is measured along the normalized direction.
+    Hit {
+        shape: u32,
+        distance: f32,
+        normal: Vec3,
+    },
+    /// The ray reaches a hull, mesh or heightfield no closer hit rules out. Kernels only
+    /// know those by their bounding spheres, so the ray has to be cast on the CPU.
+    Unresolved,
 }
 
 /// Snapshot of world data converted into a GPU-friendly structure-of-arrays layout.
+///
+/// Per-body columns share their indices with `bodies` and `ids`; per-shape columns share
+/// theirs with `shapes`. Vectors and matrices use the 16-byte aligned `Vec3A`/`Mat3A`, so
+/// every column can be uploaded as-is into a `std430` array.
 #[derive(Debug, Default, Clone)]
 pub struct GpuWorldState {
+    /// Position, bounding radius, linear velocity and inverse mass: what the broad phase needs.
     pub bodies: Vec<GpuBody>,
+    /// Body each entry of `bodies` was taken from.
+    pub ids: Vec<EntityId>,
+    pub orientations: Vec<Quat>,
+    pub angular_velocities: Vec<Vec3A>,
+    /// Zero for static and kinematic bodies.
+    pub inverse_inertias: Vec<Mat3A>,
+    pub shapes: Vec<GpuShape>,
+    /// Index into `bodies` of the body each shape belongs to.
+    pub shape_bodies: Vec<u32>,
+    /// Collider each shape was taken from.
+    pub shape_colliders: Vec<EntityId>,
+    pub shape_filters: Vec<GpuFilter>,
+    /// Whether each shape belongs to a trigger collider.
+    pub shape_triggers: Vec<bool>,
+    /// Fixed step the world is about to take.
+    pub time_step: f32,
 }
 
 impl GpuWorldState {
@@ -29,7 +268,7 @@ impl GpuWorldState {
 
     /// Synchronizes the CPU arenas into the GPU-friendly buffers.
     pub fn sync(&mut self, bodies: &Arena<RigidBody>, colliders: &Arena<Collider>) {
-        self.bodies.clear();
+        self.clear();
 
         for body_id in bodies.ids() {
             if let Some(body) = bodies.get(body_id) {
@@ -44,31 +283,390 @@ impl GpuWorldState {
                     }
                 }
 
-                self.bodies.push(GpuBody {
-                    position: body.transform.position,
+                self.push_body(
+                    body_id,
+                    &body.transform,
+                    &body.velocity,
+                    (!body.is_static && !body.is_kinematic)
+                        .then_some((body.inverse_mass, body.inverse_inertia)),
                     radius,
-                });
+                );
             }
         }
+        self.sync_shapes(colliders);
+    }
+
+    /// Synchronizes the world's bodies into the GPU-friendly buffers, skipping disabled ones.
+    ///
+    /// Body radii include collider margins and `expansion`'s padding for CCD bodies, so a
+    /// GPU broad phase finds the same pairs as the CPU one.
+    pub fn sync_bodies(
+        &mut self,
+        bodies: &BodiesSoA,
+        colliders: &Arena<Collider>,
+        time_step: f32,
+        expansion: SpeculativeExpansion,
+    ) {
+        self.clear();
+        self.time_step = time_step;
+
+        let mut radii: HashMap<EntityId, f32> = HashMap::new();
+        for collider in colliders.iter() {
+            let reach =
+                collider.offset.position.length() + collider.bounding_radius() + collider.margin;
+            let radius = radii.entry(collider.rigidbody_id).or_default();
+            *radius = radius.max(reach);
+        }
+
+        for body in bodies.iter().filter(|body| body.is_enabled()) {
+            let radius = radii.get(&body.id()).copied().unwrap_or(0.0);
+            self.push_body(
+                body.id(),
+                body.transform(),
+                body.velocity(),
+                (!body.is_static() && !body.is_kinematic())
+                    .then(|| (body.inverse_mass(), body.inverse_inertia())),
+                radius + expansion.padding(&body),
+            );
+        }
+        self.sync_shapes(colliders);
     }
 
     pub fn body_count(&self) -> usize {
         self.bodies.len()
     }
+
+    pub fn shape_count(&self) -> usize {
+        self.shapes.len()
+    }
+
+    /// One instance per body, in the order of `bodies` and `ids`.
+    pub fn instances(&self) -> Vec<GpuInstance> {
+        self.bodies
+            .iter()
+            .zip(&self.orientations)
+            .map(|(body, &rotation)| GpuInstance {
+                position: body.position,
+                radius: body.radius,
+                rotation,
+            })
+            .collect()
+    }
+
+    /// Shape `index` with its offset and rotation taken into world space by its body's pose.
+    pub fn world_shape(&self, index: usize) -> GpuShape {
+        let shape = self.shapes[index];
+        let body = self.shape_bodies[index] as usize;
+        let rotation = self.orientations[body];
+        GpuShape {
+            offset: self.bodies[body].position + rotation * shape.offset,
+            rotation: rotation * shape.rotation,
+            ..shape
+        }
+    }
+
+    /// Maps index pairs from a GPU broad phase back to the bodies they were taken from,
+    /// dropping any index outside the state.
+    pub fn pair_ids(&self, pairs: &[(u32, u32)]) -> Vec<(EntityId, EntityId)> {
+        map_pairs(&self.ids, pairs)
+    }
+
+    fn clear(&mut self) {
+        self.bodies.clear();
+        self.ids.clear();
+        self.orientations.clear();
+        self.angular_velocities.clear();
+        self.inverse_inertias.clear();
+        self.shapes.clear();
+        self.shape_bodies.clear();
+        self.shape_colliders.clear();
+        self.shape_filters.clear();
+        self.shape_triggers.clear();
+    }
+
+    /// `inverse_mass` is `None` for bodies that don't respond to impulses.
+    fn push_body(
+        &mut self,
+        id: EntityId,
+        transform: &Transform,
+        velocity: &Velocity,
+        inverse_mass: Option<(f32, Mat3)>,
+        radius: f32,
+    ) {
+        let (inverse_mass, inverse_inertia) = inverse_mass.unwrap_or((0.0, Mat3::ZERO));
+        self.bodies.push(GpuBody {
+            position: transform.position,
+            radius,
+            velocity: velocity.linear,
+            inverse_mass,
+        });
+        self.ids.push(id);
+        self.orientations.push(transform.rotation);
+        self.angular_velocities.push(velocity.angular.into());
+        self.inverse_inertias.push(inverse_inertia.into());
+    }
+
+    /// Flattens the colliders of the synced bodies into the per-shape columns.
+    fn sync_shapes(&mut self, colliders: &Arena<Collider>) {
+        let indices: HashMap<EntityId, u32> = self
+            .ids
+            .iter()
+            .enumerate()
+            .map(|(index, &id)| (id, index as u32))
+            .collect();
+        for collider in colliders.iter() {
+            if let Some(&body) = indices.get(&collider.rigidbody_id) {
+                self.push_shape(body, collider);
+            }
+        }
+    }
+
+    fn push_shape(&mut self, body: u32, collider: &Collider) {
+        let filter = GpuFilter::from(collider.collision_filter);
+        flatten_shape(&collider.offset, &collider.shape, &mut |shape| {
+            self.shapes.push(shape);
+            self.shape_bodies.push(body);
+            self.shape_colliders.push(collider.id);
+            self.shape_filters.push(filter);
+            self.shape_triggers.push(collider.is_trigger);
+        });
+    }
+}
+
+/// Calls `push` with every primitive of `shape` placed at `offset`, flattening compounds.
+fn flatten_shape(offset: &Transform, shape: &ColliderShape, push: &mut dyn FnMut(GpuShape)) {
+    let (kind, params) = match shape {
+        ColliderShape::Sphere { radius } => {
+            (GpuShapeKind::Sphere, Vec4::new(*radius, 0.0, 0.0, 0.0))
+        }
+        ColliderShape::Box { half_extents } => (GpuShapeKind::Box, half_extents.extend(0.0)),
+        ColliderShape::Capsule { radius, height } => {
+            (GpuShapeKind::Capsule, Vec4::new(*radius, *height, 0.0, 0.0))
+        }
+        ColliderShape::Cylinder { radius, height } => (
+            GpuShapeKind::Cylinder,
+            Vec4::new(*radius, *height, 0.0, 0.0),
+        ),
+        ColliderShape::Cone { radius, height } => {
+            (GpuShapeKind::Cone, Vec4::new(*radius, *height, 0.0, 0.0))
+        }
+        ColliderShape::RoundedBox {
+            half_extents,
+            border_radius,
+        } => (
+            GpuShapeKind::RoundedBox,
+            half_extents.extend(*border_radius),
+        ),
+        ColliderShape::RoundedCylinder {
+            radius,
+            height,
+            border_radius,
+        } => (
+            GpuShapeKind::RoundedCylinder,
+            Vec4::new(*radius, *height, *border_radius, 0.0),
+        ),
+        ColliderShape::Plane { normal } => (GpuShapeKind::Plane, normal.extend(0.0)),
+        ColliderShape::Compound { shapes } => {
+            for (transform, child) in shapes {
+                flatten_shape(&offset.combine(transform), child, push);
+            }
+            return;
+        }
+        ColliderShape::ConvexHull { .. }
+        | ColliderShape::Mesh { .. }
+        | ColliderShape::Heightfield { .. } => (
+            GpuShapeKind::Complex,
+            Vec4::new(shape.bounding_radius(), 0.0, 0.0, 0.0),
+        ),
+    };
+    push(GpuShape {
+        offset: offset.position,
+        kind,
+        rotation: offset.rotation,
+        params,
+    });
+}
+
+/// Radius around its offset bounding a primitive from [`flatten_shape`]; zero for planes,
+/// which have no bounds.
+fn shape_reach(shape: &GpuShape) -> f32 {
+    let params = shape.params;
+    match shape.kind {
+        GpuShapeKind::Sphere | GpuShapeKind::Complex => params.x,
+        GpuShapeKind::Box | GpuShapeKind::RoundedBox => params.truncate().length(),
+        GpuShapeKind::Capsule => params.x + params.y * 0.5,
+        GpuShapeKind::Cylinder | GpuShapeKind::Cone | GpuShapeKind::RoundedCylinder => {
+            params.x.hypot(params.y * 0.5)
+        }
+        GpuShapeKind::Plane => 0.0,
+    }
+}
+
+fn map_pairs(ids: &[EntityId], pairs: &[(u32, u32)]) -> Vec<(EntityId, EntityId)> {
+    pairs
+        .iter()
+        .filter_map(|&(a, b)| Some((*ids.get(a as usize)?, *ids.get(b as usize)?)))
+        .collect()
+}
+
+/// Result of a GPU dispatch that may still be running.
+///
+/// Backends that wait on the device hand back [`GpuReadback::ready`]. Asynchronous ones
+/// return [`GpuReadback::pending`] with a poll function that checks their fence and copies
+/// the result out once it has signalled, so the caller decides when to block.
+pub struct GpuReadback<T> {
+    value: Option<T>,
+    poll: Option<Mutex<ReadbackPoll<T>>>,
+}
+
+/// Polls a pending readback. Called with `true` it must block until the result is available.
+type ReadbackPoll<T> = Box<dyn FnMut(bool) -> Option<T> + Send>;
+
+impl<T> GpuReadback<T> {
+    pub fn ready(value: T) -> Self {
+        Self {
+            value: Some(value),
+            poll: None,
+        }
+    }
+
+    /// A readback completed by `poll`, which returns `None` while the GPU is still busy and
+    /// blocks instead when called with `true`.
+    pub fn pending(poll: impl FnMut(bool) -> Option<T> + Send + 'static) -> Self {
+        Self {
+            value: None,
+            poll: Some(Mutex::new(Box::new(poll))),
+        }
+    }
+
+    /// Checks the GPU without blocking and returns the result once it has arrived.
+    pub fn poll(&mut self) -> Option<&T> {
+        self.resolve(false);
+        self.value.as_ref()
+    }
+
+    pub fn is_ready(&mut self) -> bool {
+        self.poll().is_some()
+    }
+
+    /// Blocks until the result has arrived.
+    pub fn wait(&mut self) -> &T {
+        self.resolve(true);
+        self.value
+            .as_ref()
+            .expect("blocking readback polls return a result")
+    }
+
+    pub fn into_inner(mut self) -> T {
+        self.resolve(true);
+        self.value.expect("blocking readback polls return a result")
+    }
+
+    fn resolve(&mut self, block: bool) {
+        let Some(poll) = self.poll.as_mut() else {
+            return;
+        };
+        if let Some(value) = (poll.get_mut())(block) {
+            self.value = Some(value);
+            // Dropping the poll function releases whatever the backend kept alive for it.
+            self.poll = None;
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for GpuReadback<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => f.debug_tuple("Ready").field(value).finish(),
+            None => f.write_str("Pending"),
+        }
+    }
+}
+
+/// Readbacks from the world's last step, with the ids of the state they were dispatched on.
+#[derive(Debug, Default)]
+pub(crate) struct GpuStepResults {
+    ids: Vec<EntityId>,
+    pub(crate) pairs: Option<GpuReadback<Vec<(u32, u32)>>>,
+    pub(crate) velocities: Option<GpuReadback<Vec<Vec3>>>,
+}
+
+impl GpuStepResults {
+    /// Drops the previous step's readbacks, still in flight or not, before new dispatches.
+    pub(crate) fn begin(&mut self, state: &GpuWorldState) {
+        self.ids.clone_from(&state.ids);
+        self.pairs = None;
+        self.velocities = None;
+    }
+
+    pub(crate) fn pair_ids(&mut self) -> Option<Vec<(EntityId, EntityId)>> {
+        let pairs = self.pairs.as_mut()?.wait();
+        Some(map_pairs(&self.ids, pairs))
+    }
+
+    pub(crate) fn velocities(&mut self) -> Option<Vec<(EntityId, Vec3)>> {
+        let velocities = self.velocities.as_mut()?.wait();
+        Some(
+            self.ids
+                .iter()
+                .copied()
+                .zip(velocities.iter().copied())
+                .collect(),
+        )
+    }
 }
 
 /// Trait implemented by GPU/compute backends that can accelerate parts of the pipeline.
+///
+/// Dispatches run on the state passed to the most recent [`Self::prepare_step`] and hand
+/// their output back as a [`GpuReadback`], indexed like that state's `bodies` and `ids`.
 pub trait ComputeBackend: Send + Sync {
     fn name(&self) -> &str;
 
+    /// Whether the world should fill a [`GpuWorldState`] for this backend every step.
+    /// Syncing costs a pass over every body and collider, so backends that ignore the
+    /// state can opt out.
+    fn uses_world_state(&self) -> bool {
+        true
+    }
+
     /// Called once per step after the world state has been synchronized into GPU-friendly buffers.
     fn prepare_step(&self, _state: &GpuWorldState) {}
 
-    /// Optional hook for accelerating broad-phase workloads.
-    fn dispatch_broadphase(&self, _state: &GpuWorldState) {}
+    /// Optional hook for accelerating broad-phase workloads. Returns the candidate pairs as
+    /// `(i, j)` indices into `state` with `i < j`, or `None` if the backend has no broad phase.
+    fn dispatch_broadphase(&self, _state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        None
+    }
+
+    /// Optional hook for accelerating solver workloads. Returns the solved linear velocity of
+    /// every body in `state`, or `None` if the backend has no solver.
+    fn dispatch_solver(&self, _state: &GpuWorldState) -> Option<GpuReadback<Vec<Vec3>>> {
+        None
+    }
+
+    /// Whether [`Self::dispatch_particles`] steps particles. If so, the world hands it the
+    /// particle systems that opt in through [`ParticleSystem::gpu`].
+    fn simulates_particles(&self) -> bool {
+        false
+    }
+
+    /// Optional hook that steps a particle system kept on the GPU: integration, a hashed
+    /// grid with particle-particle collisions, then collisions with `state.obstacles`. The
+    /// particles stay in device memory for a renderer to draw; nothing is read back.
+    fn dispatch_particles(&self, _state: &GpuParticleState) {}
 
-    /// Optional hook for accelerating solver workloads.
-    fn dispatch_solver(&self, _state: &GpuWorldState) {}
+    /// Optional hook for casting many rays at once against the shapes in `state`, filtered
+    /// like `query`. Returns the closest hit of each ray in input order, or `None` if the
+    /// backend has no raycast kernel.
+    fn dispatch_raycasts(
+        &self,
+        _state: &GpuWorldState,
+        _query: &RaycastQuery,
+        _rays: &[Ray],
+    ) -> Option<GpuReadback<Vec<Option<GpuRayHit>>>> {
+        None
+    }
 }
 
 /// Default backend that keeps all work on the CPU.
@@ -85,4 +683,8 @@ impl ComputeBackend for NoopBackend {
     fn name(&self) -> &str {
         "cpu-noop"
     }
+
+    fn uses_world_state(&self) -> bool {
+        false
+    }
 }
@@ -0,0 +1,46 @@
+// Brute-force AABB overlap test over every body pair; each invocation owns body `i` and
+// tests it against the bodies after it, so every pair is reported once as (i, j), i < j.
+
+struct Body {
+    position: vec3<f32>,
+    radius: f32,
+    velocity: vec3<f32>,
+    inverse_mass: f32,
+}
+
+struct Params {
+    body_count: u32,
+    max_pairs: u32,
+    dt: f32,
+    bias: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bodies: array<Body>;
+@group(0) @binding(2) var<storage, read_write> pair_count: atomic<u32>;
+@group(0) @binding(3) var<storage, read_write> pairs: array<vec2<u32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.body_count) {
+        return;
+    }
+    let a = bodies[i];
+    for (var j = i + 1u; j < params.body_count; j++) {
+        let b = bodies[j];
+        // Two immovable bodies never need a contact.
+        if (a.inverse_mass == 0.0 && b.inverse_mass == 0.0) {
+            continue;
+        }
+        let reach = vec3<f32>(a.radius + b.radius);
+        if (all(abs(a.position - b.position) <= reach)) {
+            // The count keeps growing past capacity so the host can tell how much room
+            // a complete result needs.
+            let slot = atomicAdd(&pair_count, 1u);
+            if (slot < params.max_pairs) {
+                pairs[slot] = vec2<u32>(i, j);
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// Uniform-grid broad phase for the Vulkan backend, compiled to SPIR-V at startup.
+//
+// `bin` links every body into the hashed grid cell holding its centre, or into the
+// oversized list if it is wider than a cell. `find_pairs` then tests each body against the
+// 27 cells around it and against every oversized body. A pair can be reported twice when
+// two neighbouring cells share a hash bucket; the host removes the duplicates.
+
+struct Body {
+    position: vec3<f32>,
+    radius: f32,
+    velocity: vec3<f32>,
+    inverse_mass: f32,
+}
+
+struct Params {
+    body_count: u32,
+    // Power of two.
+    table_size: u32,
+    max_pairs: u32,
+    cell_size: f32,
+}
+
+struct Counters {
+    // Keeps counting past `max_pairs` so the host knows how much room a full result needs.
+    pair_count: atomic<u32>,
+    oversized_count: atomic<u32>,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bodies: array<Body>;
+// First body of each bucket's list, -1 when empty.
+@group(0) @binding(2) var<storage, read_write> heads: array<atomic<i32>>;
+// Next body in the same bucket, -1 at the end of a list.
+@group(0) @binding(3) var<storage, read_write> nexts: array<i32>;
+@group(0) @binding(4) var<storage, read_write> oversized: array<u32>;
+@group(0) @binding(5) var<storage, read_write> counters: Counters;
+@group(0) @binding(6) var<storage, read_write> pairs: array<vec2<u32>>;
+
+fn cell_of(position: vec3<f32>) -> vec3<i32> {
+    return vec3<i32>(floor(position / params.cell_size));
+}
+
+fn bucket_of(cell: vec3<i32>) -> u32 {
+    let c = bitcast<vec3<u32>>(cell);
+    let hash = (c.x * 73856093u) ^ (c.y * 19349663u) ^ (c.z * 83492791u);
+    return hash & (params.table_size - 1u);
+}
+
+fn is_oversized(body: Body) -> bool {
+    return body.radius * 2.0 > params.cell_size;
+}
+
+fn report(i: u32, j: u32) {
+    let a = bodies[i];
+    let b = bodies[j];
+    // Two immovable bodies never need a contact.
+    if (a.inverse_mass == 0.0 && b.inverse_mass == 0.0) {
+        return;
+    }
+    let reach = vec3<f32>(a.radius + b.radius);
+    if (any(abs(a.position - b.position) > reach)) {
+        return;
+    }
+    let slot = atomicAdd(&counters.pair_count, 1u);
+    if (slot < params.max_pairs) {
+        pairs[slot] = vec2<u32>(min(i, j), max(i, j));
+    }
+}
+
+@compute @workgroup_size(64)
+fn bin(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.body_count) {
+        return;
+    }
+    let body = bodies[i];
+    if (is_oversized(body)) {
+        nexts[i] = -1;
+        oversized[atomicAdd(&counters.oversized_count, 1u)] = i;
+        return;
+    }
+    nexts[i] = atomicExchange(&heads[bucket_of(cell_of(body.position))], i32(i));
+}
+
+@compute @workgroup_size(64)
+fn find_pairs(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.body_count) {
+        return;
+    }
+    let body = bodies[i];
+    let body_oversized = is_oversized(body);
+
+    // Two bodies no wider than a cell can only touch if their centres sit in
+    // neighbouring cells.
+    if (!body_oversized) {
+        let cell = cell_of(body.position);
+        for (var x = -1; x <= 1; x++) {
+            for (var y = -1; y <= 1; y++) {
+                for (var z = -1; z <= 1; z++) {
+                    var j = atomicLoad(&heads[bucket_of(cell + vec3<i32>(x, y, z))]);
+                    while (j != -1) {
+                        if (u32(j) > i) {
+                            report(i, u32(j));
+                        }
+                        j = nexts[j];
+                    }
+                }
+            }
+        }
+    }
+
+    // Oversized bodies are tested against everything; pairs of two oversized bodies are
+    // reported by the lower index only.
+    let oversized_count = atomicLoad(&counters.oversized_count);
+    for (var k = 0u; k < oversized_count; k++) {
+        let j = oversized[k];
+        if (j != i && (!body_oversized || j > i)) {
+            report(i, j);
+        }
+    }
+}
@@ -0,0 +1,258 @@
+// Linear BVH broad phase (Karras 2012). Bodies are sorted along a Morton curve, a binary
+// radix tree is built over the sorted keys, refit bottom-up one level at a time, and every
+// leaf then walks the tree for the bodies it overlaps. Pairs are reported once as (i, j),
+// i < j, and use the same overlap test as the brute-force kernel.
+
+struct Body {
+    position: vec3<f32>,
+    radius: f32,
+    velocity: vec3<f32>,
+    inverse_mass: f32,
+}
+
+struct Scene {
+    // Lower corner of the body centres' bounds and the scale mapping them into [0, 1].
+    origin: vec3<f32>,
+    body_count: u32,
+    scale: vec3<f32>,
+    // Body count rounded up to a power of two; keys past `body_count` sort last.
+    key_count: u32,
+    max_pairs: u32,
+}
+
+// One bitonic merge step or one refit level, bound at its own offset.
+struct Step {
+    j: u32,
+    k: u32,
+    level: u32,
+}
+
+// Internal nodes come first, leaves from `body_count - 1` on in Morton order. A leaf's
+// `left` holds its body index.
+struct Node {
+    lower: vec3<f32>,
+    left: u32,
+    upper: vec3<f32>,
+    right: u32,
+    parent: u32,
+    depth: u32,
+}
+
+const NONE: u32 = 0xffffffffu;
+// Deeper than any tree over unique 62-bit keys can get.
+const MAX_DEPTH: u32 = 64u;
+
+@group(0) @binding(0) var<uniform> scene: Scene;
+@group(0) @binding(1) var<uniform> step: Step;
+@group(0) @binding(2) var<storage, read> bodies: array<Body>;
+@group(0) @binding(3) var<storage, read_write> keys: array<vec2<u32>>;
+@group(0) @binding(4) var<storage, read_write> nodes: array<Node>;
+@group(0) @binding(5) var<storage, read_write> pair_count: atomic<u32>;
+@group(0) @binding(6) var<storage, read_write> pairs: array<vec2<u32>>;
+
+// Spreads the low 10 bits of `v` two bits apart.
+fn expand_bits(v: u32) -> u32 {
+    var x = v & 0x3ffu;
+    x = (x | (x << 16u)) & 0x030000ffu;
+    x = (x | (x << 8u)) & 0x0300f00fu;
+    x = (x | (x << 4u)) & 0x030c30c3u;
+    x = (x | (x << 2u)) & 0x09249249u;
+    return x;
+}
+
+@compute @workgroup_size(64)
+fn morton(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= scene.key_count) {
+        return;
+    }
+    if (i >= scene.body_count) {
+        keys[i] = vec2<u32>(NONE, NONE);
+        return;
+    }
+    let unit = saturate((bodies[i].position - scene.origin) * scene.scale);
+    let cell = vec3<u32>(unit * 1023.0);
+    let code = (expand_bits(cell.x) << 2u) | (expand_bits(cell.y) << 1u) | expand_bits(cell.z);
+    keys[i] = vec2<u32>(code, i);
+}
+
+fn key_less(a: vec2<u32>, b: vec2<u32>) -> bool {
+    return a.x < b.x || (a.x == b.x && a.y < b.y);
+}
+
+@compute @workgroup_size(64)
+fn sort_step(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    let l = i ^ step.j;
+    if (i >= scene.key_count || l <= i) {
+        return;
+    }
+    let a = keys[i];
+    let b = keys[l];
+    let ascending = (i & step.k) == 0u;
+    if (key_less(b, a) == ascending) {
+        keys[i] = b;
+        keys[l] = a;
+    }
+}
+
+// Length of the common prefix of the sorted keys at `i` and `j`, or -1 out of range.
+// Keys are unique, so equal codes fall back to the body index.
+fn delta(i: i32, j: i32) -> i32 {
+    if (j < 0 || j >= i32(scene.body_count)) {
+        return -1;
+    }
+    let a = keys[i];
+    let b = keys[j];
+    if (a.x != b.x) {
+        return i32(countLeadingZeros(a.x ^ b.x));
+    }
+    return 32 + i32(countLeadingZeros(a.y ^ b.y));
+}
+
+fn leaf(i: u32) -> u32 {
+    return scene.body_count - 1u + i;
+}
+
+@compute @workgroup_size(64)
+fn build(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = scene.body_count;
+    if (id.x >= n) {
+        return;
+    }
+    // Every invocation also fills one leaf. Fields are stored one by one, so this never
+    // clobbers the parent written by the leaf's internal node.
+    let body_index = keys[id.x].y;
+    let body = bodies[body_index];
+    // Padding keeps the culling boxes conservative against the exact leaf test below.
+    let magnitude = abs(body.position);
+    let pad = body.radius * 1e-5 + max(max(magnitude.x, magnitude.y), magnitude.z) * 1e-6;
+    let node = leaf(id.x);
+    nodes[node].lower = body.position - vec3<f32>(body.radius + pad);
+    nodes[node].upper = body.position + vec3<f32>(body.radius + pad);
+    nodes[node].left = body_index;
+    nodes[node].right = NONE;
+    if (id.x == 0u) {
+        nodes[0].parent = NONE;
+    }
+    if (id.x + 1u >= n) {
+        return;
+    }
+
+    // Direction and extent of the key range covered by internal node i.
+    let i = i32(id.x);
+    let d = select(-1, 1, delta(i, i + 1) > delta(i, i - 1));
+    let delta_min = delta(i, i - d);
+    var l_max = 2;
+    while (delta(i, i + l_max * d) > delta_min) {
+        l_max *= 2;
+    }
+    var l = 0;
+    for (var t = l_max / 2; t >= 1; t /= 2) {
+        if (delta(i, i + (l + t) * d) > delta_min) {
+            l += t;
+        }
+    }
+    let j = i + l * d;
+
+    // Split position: the last key sharing more than the range's common prefix with i.
+    let delta_node = delta(i, j);
+    var s = 0;
+    var t = l;
+    loop {
+        t = (t + 1) / 2;
+        if (delta(i, i + (s + t) * d) > delta_node) {
+            s += t;
+        }
+        if (t <= 1) {
+            break;
+        }
+    }
+    let gamma = u32(i + s * d + min(d, 0));
+
+    let first = u32(min(i, j));
+    let last = u32(max(i, j));
+    let left = select(gamma, leaf(gamma), first == gamma);
+    let right = select(gamma + 1u, leaf(gamma + 1u), last == gamma + 1u);
+    nodes[id.x].left = left;
+    nodes[id.x].right = right;
+    nodes[left].parent = id.x;
+    nodes[right].parent = id.x;
+}
+
+@compute @workgroup_size(64)
+fn depth(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x + 1u >= scene.body_count) {
+        return;
+    }
+    var level = 0u;
+    var node = nodes[id.x].parent;
+    while (node != NONE && level < MAX_DEPTH) {
+        level += 1u;
+        node = nodes[node].parent;
+    }
+    nodes[id.x].depth = level;
+}
+
+// Runs once per level, deepest first, so both children are final when a node reads them.
+@compute @workgroup_size(64)
+fn refit(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x + 1u >= scene.body_count || nodes[id.x].depth != step.level) {
+        return;
+    }
+    let left = nodes[nodes[id.x].left];
+    let right = nodes[nodes[id.x].right];
+    nodes[id.x].lower = min(left.lower, right.lower);
+    nodes[id.x].upper = max(left.upper, right.upper);
+}
+
+fn overlaps(a: Node, lower: vec3<f32>, upper: vec3<f32>) -> bool {
+    return all(a.lower <= upper) && all(lower <= a.upper);
+}
+
+@compute @workgroup_size(64)
+fn traverse(@builtin(global_invocation_id) id: vec3<u32>) {
+    let n = scene.body_count;
+    if (id.x >= n) {
+        return;
+    }
+    let query = nodes[leaf(id.x)];
+    let i = query.left;
+    let a = bodies[i];
+
+    var stack: array<u32, MAX_DEPTH>;
+    var top = 1u;
+    stack[0] = 0u;
+    while (top > 0u) {
+        top -= 1u;
+        let node = nodes[stack[top]];
+        if (!overlaps(node, query.lower, query.upper)) {
+            continue;
+        }
+        if (node.right != NONE) {
+            // Popping one node and pushing two keeps the stack within the tree depth.
+            stack[top] = node.left;
+            stack[top + 1u] = node.right;
+            top += 2u;
+            continue;
+        }
+        let j = node.left;
+        if (j <= i) {
+            continue;
+        }
+        let b = bodies[j];
+        // Two immovable bodies never need a contact.
+        if (a.inverse_mass == 0.0 && b.inverse_mass == 0.0) {
+            continue;
+        }
+        let reach = vec3<f32>(a.radius + b.radius);
+        if (all(abs(a.position - b.position) <= reach)) {
+            // The count keeps growing past capacity so the host can tell how much room
+            // a complete result needs.
+            let slot = atomicAdd(&pair_count, 1u);
+            if (slot < scene.max_pairs) {
+                pairs[slot] = vec2<u32>(i, j);
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Particle step: integrate, bin into a hashed grid of per-cell linked lists, resolve
+// particle-particle overlaps (each particle averages the corrections from its contacts, so
+// the pass is race-free), then push particles out of the world's colliders. Loaded after
+// `shapes.wgsl`.
+
+struct Particle {
+    position: vec3<f32>,
+    // Negative for dead slots.
+    radius: f32,
+    velocity: vec3<f32>,
+    inverse_mass: f32,
+}
+
+struct Obstacle {
+    shape: Shape,
+    pivot: vec3<f32>,
+    reach: f32,
+    linear_velocity: vec3<f32>,
+    angular_velocity: vec3<f32>,
+}
+
+struct Correction {
+    position: vec3<f32>,
+    velocity: vec3<f32>,
+}
+
+struct Params {
+    gravity: vec3<f32>,
+    dt: f32,
+    damping: f32,
+    restitution: f32,
+    friction: f32,
+    cell_size: f32,
+    slot_count: u32,
+    obstacle_count: u32,
+    // Power of two.
+    table_size: u32,
+}
+
+const NONE: u32 = 0xffffffffu;
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> particles: array<Particle>;
+@group(0) @binding(2) var<storage, read_write> heads: array<atomic<u32>>;
+@group(0) @binding(3) var<storage, read_write> next: array<u32>;
+@group(0) @binding(4) var<storage, read_write> corrections: array<Correction>;
+@group(0) @binding(5) var<storage, read> obstacles: array<Obstacle>;
+
+@compute @workgroup_size(64)
+fn integrate(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.slot_count || particles[i].radius < 0.0) {
+        return;
+    }
+    var particle = particles[i];
+    if (particle.inverse_mass > 0.0) {
+        particle.velocity = (particle.velocity + params.gravity * params.dt) * params.damping;
+    }
+    particle.position += particle.velocity * params.dt;
+    particles[i] = particle;
+}
+
+fn cell_of(position: vec3<f32>) -> vec3<i32> {
+    return vec3<i32>(floor(position / params.cell_size));
+}
+
+fn hash_cell(cell: vec3<i32>) -> u32 {
+    let h = (u32(cell.x) * 73856093u) ^ (u32(cell.y) * 19349663u) ^ (u32(cell.z) * 83492791u);
+    return h & (params.table_size - 1u);
+}
+
+@compute @workgroup_size(64)
+fn clear_grid(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x < params.table_size) {
+        atomicStore(&heads[id.x], NONE);
+    }
+}
+
+@compute @workgroup_size(64)
+fn insert(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.slot_count || particles[i].radius < 0.0) {
+        return;
+    }
+    let bucket = hash_cell(cell_of(particles[i].position));
+    next[i] = atomicExchange(&heads[bucket], i);
+}
+
+@compute @workgroup_size(64)
+fn collide(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.slot_count) {
+        return;
+    }
+    let a = particles[i];
+    var correction = Correction(vec3<f32>(0.0), vec3<f32>(0.0));
+    if (a.radius < 0.0) {
+        corrections[i] = correction;
+        return;
+    }
+    let home = cell_of(a.position);
+    var contacts = 0.0;
+    for (var x = -1; x <= 1; x++) {
+        for (var y = -1; y <= 1; y++) {
+            for (var z = -1; z <= 1; z++) {
+                let cell = home + vec3<i32>(x, y, z);
+                var j = atomicLoad(&heads[hash_cell(cell)]);
+                while (j != NONE) {
+                    let b = particles[j];
+                    // Other cells can share the bucket; only visit this cell's particles.
+                    if (j != i && all(cell_of(b.position) == cell)) {
+                        let weight_sum = a.inverse_mass + b.inverse_mass;
+                        let offset = a.position - b.position;
+                        let distance = length(offset);
+                        let depth = a.radius + b.radius - distance;
+                        if (weight_sum > 0.0 && depth > 0.0 && distance > 1e-6) {
+                            let normal = offset / distance;
+                            let share = a.inverse_mass / weight_sum;
+                            correction.position += normal * depth * share;
+                            let approach = dot(a.velocity - b.velocity, normal);
+                            if (approach < 0.0) {
+                                correction.velocity -= normal * approach * (1.0 + params.restitution) * share;
+                            }
+                            contacts += 1.0;
+                        }
+                    }
+                    j = next[j];
+                }
+            }
+        }
+    }
+    if (contacts > 0.0) {
+        correction.position /= contacts;
+        correction.velocity /= contacts;
+    }
+    corrections[i] = correction;
+}
+
+// Applies the particle-particle corrections, then pushes the particle out of every
+// obstacle and reflects its velocity off the surface.
+@compute @workgroup_size(64)
+fn resolve(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.slot_count || particles[i].radius < 0.0) {
+        return;
+    }
+    var particle = particles[i];
+    if (params.cell_size > 0.0) {
+        particle.position += corrections[i].position;
+        particle.velocity += corrections[i].velocity;
+    }
+
+    for (var o = 0u; o < params.obstacle_count; o++) {
+        let obstacle = obstacles[o];
+        let shape = obstacle.shape;
+        let to_shape = particle.position - shape.offset;
+        if (shape.kind != PLANE && length(to_shape) > obstacle.reach + particle.radius) {
+            continue;
+        }
+        let inverse = vec4<f32>(-shape.rotation.xyz, shape.rotation.w);
+        let local = rotate(inverse, to_shape);
+        let depth = particle.radius - shape_distance(shape.kind, shape.params, local);
+        if (depth <= 0.0) {
+            continue;
+        }
+        // The distance gradient is the outward surface normal.
+        let h = 1e-4 * max(1.0, obstacle.reach);
+        let dx = vec3<f32>(h, 0.0, 0.0);
+        let dy = vec3<f32>(0.0, h, 0.0);
+        let dz = vec3<f32>(0.0, 0.0, h);
+        let gradient = vec3<f32>(
+            shape_distance(shape.kind, shape.params, local + dx) - shape_distance(shape.kind, shape.params, local - dx),
+            shape_distance(shape.kind, shape.params, local + dy) - shape_distance(shape.kind, shape.params, local - dy),
+            shape_distance(shape.kind, shape.params, local + dz) - shape_distance(shape.kind, shape.params, local - dz),
+        );
+        if (dot(gradient, gradient) == 0.0) {
+            continue;
+        }
+        let normal = rotate(shape.rotation, normalize(gradient));
+        particle.position += normal * depth;
+
+        let contact = particle.position - normal * particle.radius;
+        let surface_velocity = obstacle.linear_velocity + cross(obstacle.angular_velocity, contact - obstacle.pivot);
+        let relative = particle.velocity - surface_velocity;
+        let approach = dot(relative, normal);
+        if (approach < 0.0) {
+            let tangential = relative - normal * approach;
+            particle.velocity = surface_velocity + tangential * (1.0 - params.friction) - normal * approach * params.restitution;
+        }
+    }
+    particles[i] = particle;
+}
@@ -0,0 +1,63 @@
+// One Jacobi iteration of a contact pre-solve over bounding spheres. Every invocation reads
+// the previous iteration's velocities and writes its own body's next velocity, so bodies
+// never race; the host ping-pongs the two velocity buffers between iterations.
+
+struct Body {
+    position: vec3<f32>,
+    radius: f32,
+    velocity: vec3<f32>,
+    inverse_mass: f32,
+}
+
+struct Params {
+    body_count: u32,
+    max_pairs: u32,
+    dt: f32,
+    bias: f32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> bodies: array<Body>;
+@group(0) @binding(2) var<storage, read> velocities_in: array<vec4<f32>>;
+@group(0) @binding(3) var<storage, read_write> velocities_out: array<vec4<f32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let i = id.x;
+    if (i >= params.body_count) {
+        return;
+    }
+    let a = bodies[i];
+    let velocity = velocities_in[i].xyz;
+    if (a.inverse_mass == 0.0) {
+        velocities_out[i] = vec4<f32>(velocity, 0.0);
+        return;
+    }
+
+    var delta = vec3<f32>(0.0);
+    var contacts = 0u;
+    for (var j = 0u; j < params.body_count; j++) {
+        if (j == i) {
+            continue;
+        }
+        let b = bodies[j];
+        let offset = a.position - b.position;
+        let distance = length(offset);
+        let depth = a.radius + b.radius - distance;
+        if (depth <= 0.0 || distance <= 1e-6) {
+            continue;
+        }
+        let normal = offset / distance;
+        let approach = dot(velocity - velocities_in[j].xyz, normal);
+        // Push apart fast enough to resolve `bias` of the overlap this step.
+        let target_speed = params.bias * depth / params.dt;
+        let impulse = max(target_speed - approach, 0.0) / (a.inverse_mass + b.inverse_mass);
+        delta += normal * impulse * a.inverse_mass;
+        contacts += 1u;
+    }
+    // Averaging keeps a body squeezed by many neighbours from overshooting.
+    if (contacts > 0u) {
+        delta /= f32(contacts);
+    }
+    velocities_out[i] = vec4<f32>(velocity + delta, 0.0);
+}
@@ -0,0 +1,299 @@
+// Batched raycasts. Each ray walks an LBVH built by `lbvh.wgsl` over the bounding spheres
+// of the world-space shapes, tests the primitives it reaches exactly and keeps the closest
+// hit. Planes have no bounds and are tested by every ray. Hulls, meshes and heightfields
+// are only known by their bounding spheres, so a ray that enters one before its closest
+// hit is handed back unresolved. Loaded after `shapes.wgsl`.
+
+struct Query {
+    ray_count: u32,
+    // Targets with bounds come first and are the leaves of the tree; planes follow.
+    bounded_count: u32,
+    target_count: u32,
+    layer_mask: u32,
+    query_layer: u32,
+    ignore_triggers: u32,
+}
+
+// A negative `max_distance` marks a ray without a direction.
+struct Ray {
+    origin: vec3<f32>,
+    max_distance: f32,
+    direction: vec3<f32>,
+}
+
+// A shape in world space.
+struct Target {
+    shape: Shape,
+    // Index of the shape in `GpuWorldState::shapes`.
+    index: u32,
+    layer: u32,
+    mask: u32,
+    trigger: u32,
+}
+
+struct Node {
+    lower: vec3<f32>,
+    left: u32,
+    upper: vec3<f32>,
+    right: u32,
+    parent: u32,
+    depth: u32,
+}
+
+struct Hit {
+    normal: vec3<f32>,
+    distance: f32,
+    shape: u32,
+    status: u32,
+}
+
+const NONE: u32 = 0xffffffffu;
+const MAX_DEPTH: u32 = 64u;
+
+const MISS: u32 = 0u;
+const HIT: u32 = 1u;
+const UNRESOLVED: u32 = 2u;
+
+const MAX_STEPS: u32 = 128u;
+const SURFACE_EPSILON: f32 = 1e-4;
+
+@group(0) @binding(0) var<uniform> query: Query;
+@group(0) @binding(1) var<storage, read> rays: array<Ray>;
+@group(0) @binding(2) var<storage, read> nodes: array<Node>;
+@group(0) @binding(3) var<storage, read> targets: array<Target>;
+@group(0) @binding(4) var<storage, read_write> hits: array<Hit>;
+
+fn accepts(candidate: Target) -> bool {
+    return !(query.ignore_triggers != 0u && candidate.trigger != 0u)
+        && (candidate.layer & query.layer_mask) != 0u
+        && (candidate.mask & query.query_layer) != 0u;
+}
+
+// Entry distance of the ray into a box, or a negative value if it misses within `limit`.
+fn enter_box(lower: vec3<f32>, upper: vec3<f32>, origin: vec3<f32>, inverse: vec3<f32>, limit: f32) -> f32 {
+    let t1 = (lower - origin) * inverse;
+    let t2 = (upper - origin) * inverse;
+    let near = min(t1, t2);
+    let far = max(t1, t2);
+    let enter = max(max(max(near.x, near.y), near.z), 0.0);
+    let exit = min(min(min(far.x, far.y), far.z), limit);
+    return select(-1.0, enter, enter <= exit);
+}
+
+// Entry distance into a sphere around the origin, or a negative value. Rays starting
+// inside count as entering at zero.
+fn enter_sphere(origin: vec3<f32>, direction: vec3<f32>, radius: f32) -> f32 {
+    let b = dot(origin, direction);
+    let c = dot(origin, origin) - radius * radius;
+    if (c <= 0.0) {
+        return 0.0;
+    }
+    let discriminant = b * b - c;
+    if (b > 0.0 || discriminant < 0.0) {
+        return -1.0;
+    }
+    return -b - sqrt(discriminant);
+}
+
+// Surface hit of a shape-local ray as (normal, distance), with a negative distance for a
+// miss. Rays starting inside a shape pass through it, like the CPU queries.
+fn intersect(kind: u32, params: vec4<f32>, o: vec3<f32>, d: vec3<f32>, inverse: vec3<f32>) -> vec4<f32> {
+    let miss = vec4<f32>(0.0, 0.0, 0.0, -1.0);
+    switch kind {
+        case SPHERE: {
+            if (dot(o, o) <= params.x * params.x) {
+                return miss;
+            }
+            let t = enter_sphere(o, d, params.x);
+            return select(miss, vec4<f32>((o + d * t) / params.x, t), t >= 0.0);
+        }
+        case BOX: {
+            if (all(abs(o) <= params.xyz)) {
+                return miss;
+            }
+            let t = enter_box(-params.xyz, params.xyz, o, inverse, 3.4e38);
+            if (t < 0.0) {
+                return miss;
+            }
+            // The face entered last is the one the ray hits.
+            let near = min((-params.xyz - o) * inverse, (params.xyz - o) * inverse);
+            var normal = vec3<f32>(0.0, 0.0, -sign(d.z));
+            if (near.x >= near.y && near.x >= near.z) {
+                normal = vec3<f32>(-sign(d.x), 0.0, 0.0);
+            } else if (near.y >= near.z) {
+                normal = vec3<f32>(0.0, -sign(d.y), 0.0);
+            }
+            return vec4<f32>(normal, t);
+        }
+        case CAPSULE: {
+            if (shape_distance(kind, params, o) <= 0.0) {
+                return miss;
+            }
+            let half_height = params.y * 0.5;
+            var best = side_hit(o, d, params.x, half_height);
+            for (var end = -1.0; end <= 1.0; end += 2.0) {
+                let center = vec3<f32>(0.0, end * half_height, 0.0);
+                let t = enter_sphere(o - center, d, params.x);
+                if (t >= 0.0 && (best.w < 0.0 || t < best.w)) {
+                    best = vec4<f32>((o + d * t - center) / params.x, t);
+                }
+            }
+            return best;
+        }
+        case CYLINDER: {
+            if (shape_distance(kind, params, o) <= 0.0) {
+                return miss;
+            }
+            let half_height = params.y * 0.5;
+            var best = side_hit(o, d, params.x, half_height);
+            if (abs(d.y) > 1e-6) {
+                let cap = select(half_height, -half_height, d.y > 0.0);
+                let t = (cap - o.y) / d.y;
+                let p = o + d * t;
+                if (t >= 0.0 && dot(p.xz, p.xz) <= params.x * params.x && (best.w < 0.0 || t < best.w)) {
+                    best = vec4<f32>(0.0, sign(cap), 0.0, t);
+                }
+            }
+            return best;
+        }
+        case PLANE: {
+            let normal = normalize(params.xyz);
+            let denominator = dot(d, normal);
+            if (denominator >= -1e-6) {
+                return miss;
+            }
+            let t = -dot(o, normal) / denominator;
+            return select(miss, vec4<f32>(normal, t), t >= 0.0);
+        }
+        default: {
+            return trace(kind, params, o, d);
+        }
+    }
+}
+
+// Hit on the curved side of a Y-axis cylinder between its caps.
+fn side_hit(o: vec3<f32>, d: vec3<f32>, radius: f32, half_height: f32) -> vec4<f32> {
+    let miss = vec4<f32>(0.0, 0.0, 0.0, -1.0);
+    let a = dot(d.xz, d.xz);
+    if (a < 1e-12) {
+        return miss;
+    }
+    let b = dot(o.xz, d.xz);
+    let c = dot(o.xz, o.xz) - radius * radius;
+    let discriminant = b * b - a * c;
+    if (discriminant < 0.0) {
+        return miss;
+    }
+    let t = (-b - sqrt(discriminant)) / a;
+    let p = o + d * t;
+    if (t < 0.0 || abs(p.y) > half_height) {
+        return miss;
+    }
+    return vec4<f32>(p.x / radius, 0.0, p.z / radius, t);
+}
+
+// Sphere-traces the shapes without a closed-form intersection, starting where the ray
+// enters the bounding sphere.
+fn trace(kind: u32, params: vec4<f32>, o: vec3<f32>, d: vec3<f32>) -> vec4<f32> {
+    let miss = vec4<f32>(0.0, 0.0, 0.0, -1.0);
+    if (shape_distance(kind, params, o) < 0.0) {
+        return miss;
+    }
+    let bound = length(params.xyz);
+    var t = enter_sphere(o, d, bound);
+    if (t < 0.0) {
+        return miss;
+    }
+    for (var i = 0u; i < MAX_STEPS; i++) {
+        let p = o + d * t;
+        let distance = shape_distance(kind, params, p);
+        if (distance < SURFACE_EPSILON) {
+            let h = 1e-3;
+            let gradient = vec3<f32>(
+                shape_distance(kind, params, p + vec3<f32>(h, 0.0, 0.0)) - shape_distance(kind, params, p - vec3<f32>(h, 0.0, 0.0)),
+                shape_distance(kind, params, p + vec3<f32>(0.0, h, 0.0)) - shape_distance(kind, params, p - vec3<f32>(0.0, h, 0.0)),
+                shape_distance(kind, params, p + vec3<f32>(0.0, 0.0, h)) - shape_distance(kind, params, p - vec3<f32>(0.0, 0.0, h)),
+            );
+            return vec4<f32>(normalize(gradient), t);
+        }
+        if (dot(p, p) > bound * bound * 4.0) {
+            return miss;
+        }
+        t += distance;
+    }
+    return miss;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= query.ray_count) {
+        return;
+    }
+    let ray = rays[id.x];
+    var best = Hit(vec3<f32>(0.0), ray.max_distance, NONE, MISS);
+    if (ray.max_distance < 0.0) {
+        hits[id.x] = best;
+        return;
+    }
+    // Axis-parallel rays get a huge but finite inverse instead of an infinite one.
+    let tiny = abs(ray.direction) < vec3<f32>(1e-12);
+    let inverse = 1.0 / select(ray.direction, vec3<f32>(1e-12), tiny);
+    // Closest point at which the ray enters a shape it can't test, if it enters one.
+    var found_unresolved = false;
+    var unresolved = 0.0;
+
+    var stack: array<u32, MAX_DEPTH>;
+    var top = select(0u, 1u, query.bounded_count > 0u);
+    stack[0] = 0u;
+    while (top > 0u) {
+        top -= 1u;
+        let node = nodes[stack[top]];
+        if (enter_box(node.lower, node.upper, ray.origin, inverse, best.distance) < 0.0) {
+            continue;
+        }
+        if (node.right != NONE) {
+            stack[top] = node.left;
+            stack[top + 1u] = node.right;
+            top += 2u;
+            continue;
+        }
+        let candidate = targets[node.left];
+        if (!accepts(candidate)) {
+            continue;
+        }
+        let shape = candidate.shape;
+        let conjugate = vec4<f32>(-shape.rotation.xyz, shape.rotation.w);
+        let o = rotate(conjugate, ray.origin - shape.offset);
+        let d = rotate(conjugate, ray.direction);
+        if (shape.kind == COMPLEX) {
+            let t = enter_sphere(o, d, shape.params.x);
+            if (t >= 0.0 && t <= best.distance) {
+                unresolved = select(t, min(unresolved, t), found_unresolved);
+                found_unresolved = true;
+            }
+            continue;
+        }
+        let hit = intersect(shape.kind, shape.params, o, d, 1.0 / select(d, vec3<f32>(1e-12), abs(d) < vec3<f32>(1e-12)));
+        if (hit.w >= 0.0 && hit.w <= best.distance) {
+            best = Hit(rotate(shape.rotation, hit.xyz), hit.w, candidate.index, HIT);
+        }
+    }
+
+    for (var i = query.bounded_count; i < query.target_count; i++) {
+        let candidate = targets[i];
+        if (!accepts(candidate)) {
+            continue;
+        }
+        let shape = candidate.shape;
+        let normal = rotate(shape.rotation, normalize(shape.params.xyz));
+        let hit = intersect(PLANE, vec4<f32>(normal, 0.0), ray.origin - shape.offset, ray.direction, inverse);
+        if (hit.w >= 0.0 && hit.w <= best.distance) {
+            best = Hit(hit.xyz, hit.w, candidate.index, HIT);
+        }
+    }
+
+    if (found_unresolved && unresolved <= best.distance) {
+        best.status = UNRESOLVED;
+    }
+    hits[id.x] = best;
+}
@@ -0,0 +1,83 @@
+// Collider shapes as the kernels that test against them see a `GpuShape`: its layout,
+// kinds and signed distance functions. Prepended to the modules that include it.
+
+struct Shape {
+    offset: vec3<f32>,
+    kind: u32,
+    rotation: vec4<f32>,
+    params: vec4<f32>,
+}
+
+const SPHERE: u32 = 0u;
+const BOX: u32 = 1u;
+const CAPSULE: u32 = 2u;
+const CYLINDER: u32 = 3u;
+const CONE: u32 = 4u;
+const ROUNDED_BOX: u32 = 5u;
+const ROUNDED_CYLINDER: u32 = 6u;
+const PLANE: u32 = 7u;
+// Hulls, meshes and heightfields; `params.x` is the bounding radius.
+const COMPLEX: u32 = 8u;
+
+fn rotate(q: vec4<f32>, v: vec3<f32>) -> vec3<f32> {
+    let t = 2.0 * cross(q.xyz, v);
+    return v + q.w * t + cross(q.xyz, t);
+}
+
+fn box_distance(p: vec3<f32>, half_extents: vec3<f32>) -> f32 {
+    let q = abs(p) - half_extents;
+    return length(max(q, vec3<f32>(0.0))) + min(max(q.x, max(q.y, q.z)), 0.0);
+}
+
+fn cylinder_distance(p: vec3<f32>, radius: f32, height: f32) -> f32 {
+    let radial = length(p.xz) - radius;
+    let axial = abs(p.y) - height * 0.5;
+    return length(max(vec2<f32>(radial, axial), vec2<f32>(0.0))) + min(max(radial, axial), 0.0);
+}
+
+// Distance in the (radial, y) half-plane to the cone's profile triangle.
+fn cone_distance(point: vec3<f32>, radius: f32, height: f32) -> f32 {
+    let half_height = height * 0.5;
+    let p = vec2<f32>(length(point.xz), point.y);
+    let base = vec2<f32>(min(p.x, radius), -half_height);
+    let edge = vec2<f32>(radius, -half_height);
+    let slant = vec2<f32>(-radius, height);
+    let t = clamp(dot(p - edge, slant) / max(dot(slant, slant), 1e-12), 0.0, 1.0);
+    let distance = min(length(p - base), length(p - (edge + slant * t)));
+    let inside = p.y >= -half_height && p.x * height <= radius * (half_height - p.y);
+    return select(distance, -distance, inside);
+}
+
+// Signed distance from a shape-local point to the surface, negative inside. Hulls, meshes
+// and heightfields are treated as their bounding spheres.
+fn shape_distance(kind: u32, params: vec4<f32>, p: vec3<f32>) -> f32 {
+    switch kind {
+        case BOX: {
+            return box_distance(p, params.xyz);
+        }
+        case CAPSULE: {
+            let half_height = params.y * 0.5;
+            return length(p - vec3<f32>(0.0, clamp(p.y, -half_height, half_height), 0.0)) - params.x;
+        }
+        case CYLINDER: {
+            return cylinder_distance(p, params.x, params.y);
+        }
+        case CONE: {
+            return cone_distance(p, params.x, params.y);
+        }
+        case ROUNDED_BOX: {
+            let border = clamp(params.w, 0.0, max(min(params.x, min(params.y, params.z)), 0.0));
+            return box_distance(p, params.xyz - vec3<f32>(border)) - border;
+        }
+        case ROUNDED_CYLINDER: {
+            let border = clamp(params.z, 0.0, max(min(params.x, params.y * 0.5), 0.0));
+            return cylinder_distance(p, params.x - border, params.y - 2.0 * border) - border;
+        }
+        case PLANE: {
+            return dot(p, normalize(params.xyz));
+        }
+        default: {
+            return length(p) - params.x;
+        }
+    }
+}
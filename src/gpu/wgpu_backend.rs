@@ -0,0 +1,1526 @@
+//! Portable compute backend on wgpu, for platforms without raw Vulkan access.
+
+use std::{
+    num::NonZeroU64,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    task::Poll,
+};
+
+use bytemuck::Zeroable;
+use glam::Vec3;
+use parking_lot::Mutex;
+
+use crate::{
+    collision::queries::{Ray, RaycastQuery},
+    gpu::{
+        shape_reach, ComputeBackend, GpuBody, GpuObstacle, GpuParticle, GpuParticleState,
+        GpuRayHit, GpuReadback, GpuShape, GpuShapeKind, GpuWorldState,
+    },
+};
+
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Default number of Jacobi iterations run by the contact pre-solve.
+pub const DEFAULT_PRESOLVE_ITERATIONS: u32 = 4;
+
+/// Default fraction of bounding-sphere overlap the pre-solve resolves per step.
+pub const DEFAULT_PRESOLVE_BIAS: f32 = 0.2;
+
+/// Body count from which [`BroadphaseMode::Auto`] switches to the LBVH.
+pub const LBVH_THRESHOLD: usize = 4096;
+
+/// Deepest refit level of the LBVH; matches `MAX_DEPTH` in `lbvh.wgsl`.
+const LBVH_MAX_DEPTH: u32 = 64;
+
+/// How [`WgpuBackend`] finds overlapping body pairs. Every mode reports the same pairs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BroadphaseMode {
+    /// Tests every body pair. O(n²), but the fastest choice up to a few thousand bodies.
+    BruteForce,
+    /// Sorts the bodies along a Morton curve, builds a linear BVH over them and walks it
+    /// once per body. O(n log n), for scenes with hundreds of thousands of colliders.
+    Lbvh,
+    /// Brute force below [`LBVH_THRESHOLD`] bodies, the LBVH from there on.
+    #[default]
+    Auto,
+}
+
+/// Compute backend running the broad-phase AABB overlap test and a Jacobi contact pre-solve
+/// on any adapter wgpu supports (Vulkan, Metal, DX12 or GL).
+///
+/// Both kernels work on the bounding spheres in [`GpuWorldState`]. Dispatches return once
+/// the work is submitted; the [`GpuReadback`] they hand back maps the results when the GPU
+/// has finished, and can be polled without blocking.
+///
+/// Blocking readback waits on the device, so the backend is only available on native targets.
+pub struct WgpuBackend {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    adapter_name: String,
+    broadphase_pipeline: wgpu::ComputePipeline,
+    lbvh_pipelines: LbvhPipelines,
+    presolve_pipeline: wgpu::ComputePipeline,
+    particle_pipelines: ParticlePipelines,
+    raycast_pipeline: wgpu::ComputePipeline,
+    buffers: Mutex<Option<Arc<Buffers>>>,
+    particles: Mutex<Option<ParticleBuffers>>,
+    /// Held by the readback of each raycast batch until it completes.
+    raycasts: Mutex<Option<Arc<RaycastBuffers>>>,
+    particle_output: ParticleBuffer,
+    /// Pair capacity the largest broad phase so far needed, so new buffers start big enough.
+    pair_capacity: Arc<AtomicU64>,
+    broadphase_mode: BroadphaseMode,
+    presolve_iterations: u32,
+    presolve_bias: f32,
+}
+
+/// The entry points of `lbvh.wgsl`, in the order a broad phase runs them.
+#[derive(Clone)]
+struct LbvhPipelines {
+    morton: wgpu::ComputePipeline,
+    sort_step: wgpu::ComputePipeline,
+    build: wgpu::ComputePipeline,
+    depth: wgpu::ComputePipeline,
+    refit: wgpu::ComputePipeline,
+    traverse: wgpu::ComputePipeline,
+}
+
+/// The entry points of `particles.wgsl`, in the order a particle step runs them.
+#[derive(Clone)]
+struct ParticlePipelines {
+    integrate: wgpu::ComputePipeline,
+    clear_grid: wgpu::ComputePipeline,
+    insert: wgpu::ComputePipeline,
+    collide: wgpu::ComputePipeline,
+    resolve: wgpu::ComputePipeline,
+}
+
+/// Shared handle to the device buffer [`WgpuBackend`] keeps GPU-stepped particles in, so a
+/// renderer can draw them without reading them back. Take it before handing the backend to
+/// the world.
+///
+/// The buffer holds one [`GpuParticle`] per slot and can be bound as a vertex or storage
+/// buffer. It is replaced when the particle pool outgrows it, so fetch it every frame.
+#[derive(Debug, Clone, Default)]
+pub struct ParticleBuffer {
+    current: Arc<Mutex<Option<(wgpu::Buffer, u32)>>>,
+}
+
+impl ParticleBuffer {
+    /// The buffer as of the last particle step and the number of slots in use, or `None`
+    /// before the first step.
+    pub fn get(&self) -> Option<(wgpu::Buffer, u32)> {
+        self.current.lock().clone()
+    }
+}
+
+/// Particles kept on the device between steps, with the grid they are binned into.
+struct ParticleBuffers {
+    capacity: u64,
+    params: wgpu::Buffer,
+    particles: wgpu::Buffer,
+    /// First particle of each grid bucket's linked list; `next` links the rest.
+    heads: wgpu::Buffer,
+    next: wgpu::Buffer,
+    corrections: wgpu::Buffer,
+    obstacle_capacity: u64,
+    obstacles: wgpu::Buffer,
+}
+
+/// Device buffers, grown as the body and pair counts grow. Passes with a pending readback
+/// keep theirs alive, and the next upload moves to fresh buffers instead of overwriting them.
+struct Buffers {
+    body_capacity: u64,
+    params: wgpu::Buffer,
+    bodies: wgpu::Buffer,
+    pairs: PairBuffers,
+    /// Only allocated while the broad phase uses the LBVH.
+    tree: Option<TreeBuffers>,
+    /// Ping-pong targets of the Jacobi iterations.
+    velocities: [wgpu::Buffer; 2],
+    velocity_readback: wgpu::Buffer,
+    pairs_in_flight: AtomicBool,
+    velocities_in_flight: AtomicBool,
+}
+
+/// Output of the broad-phase kernel and the mappable copy it is read back through.
+struct PairBuffers {
+    capacity: u64,
+    count: wgpu::Buffer,
+    pairs: wgpu::Buffer,
+    /// Pair count, padding, then the pairs.
+    readback: wgpu::Buffer,
+}
+
+/// Rays, their hits, and the world-space shapes they are cast against with the LBVH over
+/// those shapes' bounding spheres.
+struct RaycastBuffers {
+    ray_capacity: u64,
+    target_capacity: u64,
+    query: wgpu::Buffer,
+    rays: wgpu::Buffer,
+    targets: wgpu::Buffer,
+    /// Bounding sphere of each bounded target, laid out like [`GpuBody`] for `lbvh.wgsl`.
+    spheres: wgpu::Buffer,
+    tree: TreeBuffers,
+    hits: wgpu::Buffer,
+    readback: wgpu::Buffer,
+}
+
+/// Morton keys and nodes of the LBVH, plus the uniforms its kernels read.
+struct TreeBuffers {
+    scene: wgpu::Buffer,
+    /// One [`Step`] per bitonic merge step and refit level, [`STEP_STRIDE`] bytes apart.
+    steps: wgpu::Buffer,
+    keys: wgpu::Buffer,
+    nodes: wgpu::Buffer,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PassKind {
+    Pairs,
+    Velocities,
+}
+
+/// A pass's hold on the buffers it reads back through, released when dropped.
+struct Claim {
+    buffers: Arc<Buffers>,
+    kind: PassKind,
+}
+
+/// Uniform block shared by both kernels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Params {
+    body_count: u32,
+    max_pairs: u32,
+    dt: f32,
+    bias: f32,
+}
+
+/// Uniform block of the LBVH kernels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Scene {
+    origin: [f32; 3],
+    body_count: u32,
+    scale: [f32; 3],
+    key_count: u32,
+    max_pairs: u32,
+    _padding: [u32; 3],
+}
+
+/// One bitonic merge step (`j`, `k`) or refit `level` of the LBVH.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct Step {
+    j: u32,
+    k: u32,
+    level: u32,
+    _padding: u32,
+}
+
+/// Uniform block of the raycast kernel.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct RaycastParams {
+    ray_count: u32,
+    bounded_count: u32,
+    target_count: u32,
+    layer_mask: u32,
+    query_layer: u32,
+    ignore_triggers: u32,
+    _padding: [u32; 2],
+}
+
+/// Uniform block of the particle kernels.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ParticleParams {
+    gravity: [f32; 3],
+    dt: f32,
+    damping: f32,
+    restitution: f32,
+    friction: f32,
+    cell_size: f32,
+    slot_count: u32,
+    obstacle_count: u32,
+    table_size: u32,
+    _padding: u32,
+}
+
+const BODY_SIZE: u64 = std::mem::size_of::<GpuBody>() as u64;
+const PAIR_SIZE: u64 = std::mem::size_of::<[u32; 2]>() as u64;
+const VELOCITY_SIZE: u64 = std::mem::size_of::<[f32; 4]>() as u64;
+/// Readback offset of the pair list, after the pair count and padding.
+const PAIRS_OFFSET: u64 = 8;
+const KEY_SIZE: u64 = std::mem::size_of::<[u32; 2]>() as u64;
+const NODE_SIZE: u64 = 48;
+/// Distance between steps, the offset alignment uniform bindings need on every adapter.
+const STEP_STRIDE: u64 = 256;
+const PARTICLE_SIZE: u64 = std::mem::size_of::<GpuParticle>() as u64;
+/// Position and velocity of the correction each particle gets from its neighbours.
+const CORRECTION_SIZE: u64 = std::mem::size_of::<[f32; 8]>() as u64;
+const OBSTACLE_SIZE: u64 = std::mem::size_of::<[f32; 24]>() as u64;
+const RAY_SIZE: u64 = std::mem::size_of::<[f32; 8]>() as u64;
+const TARGET_SIZE: u64 = std::mem::size_of::<[f32; 16]>() as u64;
+const HIT_SIZE: u64 = std::mem::size_of::<[f32; 8]>() as u64;
+
+impl WgpuBackend {
+    /// Picks the system's preferred adapter, or `None` if wgpu finds no adapter that can run
+    /// compute shaders.
+    pub fn new() -> Option<Self> {
+        pollster::block_on(Self::request(wgpu::PowerPreference::HighPerformance))
+    }
+
+    /// Async form of [`Self::new`] with an explicit power preference.
+    pub async fn request(power_preference: wgpu::PowerPreference) -> Option<Self> {
+        let instance =
+            wgpu::Instance::new(wgpu::InstanceDescriptor::new_without_display_handle_from_env());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference,
+                ..Default::default()
+            })
+            .await
+            .ok()?;
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("particle_accelerator"),
+                required_limits: wgpu::Limits::downlevel_defaults(),
+                ..Default::default()
+            })
+            .await
+            .ok()?;
+        let mut backend = Self::from_device(device, queue);
+        backend.adapter_name = adapter.get_info().name;
+        Some(backend)
+    }
+
+    /// Runs on a device the application already owns, e.g. its renderer's.
+    pub fn from_device(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        let broadphase_pipeline = compute_pipeline(
+            &device,
+            "broadphase",
+            include_str!("shaders/broadphase.wgsl"),
+        );
+        let lbvh_pipelines = LbvhPipelines::new(&device);
+        let presolve_pipeline =
+            compute_pipeline(&device, "presolve", include_str!("shaders/presolve.wgsl"));
+        let particle_pipelines = ParticlePipelines::new(&device);
+        let raycast_pipeline = compute_pipeline(
+            &device,
+            "raycast",
+            concat!(
+                include_str!("shaders/shapes.wgsl"),
+                include_str!("shaders/raycast.wgsl")
+            ),
+        );
+        Self {
+            device,
+            queue,
+            adapter_name: String::new(),
+            broadphase_pipeline,
+            lbvh_pipelines,
+            presolve_pipeline,
+            particle_pipelines,
+            raycast_pipeline,
+            buffers: Mutex::new(None),
+            particles: Mutex::new(None),
+            raycasts: Mutex::new(None),
+            particle_output: ParticleBuffer::default(),
+            pair_capacity: Arc::new(AtomicU64::new(0)),
+            broadphase_mode: BroadphaseMode::default(),
+            presolve_iterations: DEFAULT_PRESOLVE_ITERATIONS,
+            presolve_bias: DEFAULT_PRESOLVE_BIAS,
+        }
+    }
+
+    /// Name of the adapter picked by [`Self::new`]; empty for [`Self::from_device`].
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Handle to the buffer the particles live in once the world steps them here.
+    pub fn particle_buffer(&self) -> ParticleBuffer {
+        self.particle_output.clone()
+    }
+
+    pub fn broadphase_mode(&self) -> BroadphaseMode {
+        self.broadphase_mode
+    }
+
+    pub fn set_broadphase_mode(&mut self, mode: BroadphaseMode) {
+        self.broadphase_mode = mode;
+    }
+
+    fn uses_lbvh(&self, body_count: usize) -> bool {
+        match self.broadphase_mode {
+            BroadphaseMode::BruteForce => false,
+            BroadphaseMode::Lbvh => true,
+            BroadphaseMode::Auto => body_count >= LBVH_THRESHOLD,
+        }
+    }
+
+    pub fn presolve_iterations(&self) -> u32 {
+        self.presolve_iterations
+    }
+
+    pub fn set_presolve_iterations(&mut self, iterations: u32) {
+        self.presolve_iterations = iterations;
+    }
+
+    pub fn presolve_bias(&self) -> f32 {
+        self.presolve_bias
+    }
+
+    pub fn set_presolve_bias(&mut self, bias: f32) {
+        self.presolve_bias = bias.clamp(0.0, 1.0);
+    }
+
+    /// Uploads `state`, moving to new buffers if it no longer fits or a pending readback
+    /// still uses the current ones.
+    fn upload(&self, state: &GpuWorldState, slot: &mut Option<Arc<Buffers>>) -> Arc<Buffers> {
+        let body_count = state.body_count() as u64;
+        let pair_capacity = self.pair_capacity.load(Ordering::Relaxed);
+        let tree = self.uses_lbvh(state.body_count());
+        let reusable = slot.as_ref().is_some_and(|b| {
+            b.body_capacity >= body_count
+                && b.pairs.capacity >= pair_capacity
+                && (b.tree.is_some() || !tree)
+                && !b.in_use()
+        });
+        if !reusable {
+            *slot = Some(Arc::new(Buffers::new(
+                &self.device,
+                body_count.next_power_of_two(),
+                pair_capacity.max(body_count * 4),
+                tree,
+            )));
+        }
+        let buffers = slot.clone().expect("buffers were just allocated");
+        let bodies: Vec<[f32; 8]> = state.bodies.iter().map(pack_body).collect();
+        self.queue
+            .write_buffer(&buffers.bodies, 0, bytemuck::cast_slice(&bodies));
+        buffers
+    }
+
+    /// Buffers holding `state` for a pass of `kind`, uploading it again if another pass of
+    /// the same kind is still waiting on its readback.
+    fn claim(&self, state: &GpuWorldState, kind: PassKind) -> Claim {
+        let tree = matches!(kind, PassKind::Pairs) && self.uses_lbvh(state.body_count());
+        let mut slot = self.buffers.lock();
+        let buffers = match slot.as_ref() {
+            Some(buffers)
+                if (buffers.tree.is_some() || !tree)
+                    && !buffers.in_flight(kind).swap(true, Ordering::AcqRel) =>
+            {
+                buffers.clone()
+            }
+            _ => {
+                let buffers = self.upload(state, &mut slot);
+                buffers.in_flight(kind).store(true, Ordering::Release);
+                buffers
+            }
+        };
+        Claim { buffers, kind }
+    }
+
+    fn params(&self, state: &GpuWorldState) -> Params {
+        Params {
+            body_count: state.body_count() as u32,
+            max_pairs: 0,
+            dt: state.time_step.max(1e-6),
+            bias: self.presolve_bias,
+        }
+    }
+
+    fn presolve(&self, state: &GpuWorldState) -> GpuReadback<Vec<Vec3>> {
+        let claim = self.claim(state, PassKind::Velocities);
+        let buffers = &claim.buffers;
+        self.queue
+            .write_buffer(&buffers.params, 0, bytemuck::bytes_of(&self.params(state)));
+        let velocities: Vec<[f32; 4]> = state
+            .bodies
+            .iter()
+            .map(|body| body.velocity.extend(0.0).to_array())
+            .collect();
+        self.queue
+            .write_buffer(&buffers.velocities[0], 0, bytemuck::cast_slice(&velocities));
+
+        let bind_groups = [0, 1].map(|from| {
+            bind_group(
+                &self.device,
+                &self.presolve_pipeline,
+                &[
+                    &buffers.params,
+                    &buffers.bodies,
+                    &buffers.velocities[from],
+                    &buffers.velocities[1 - from],
+                ],
+            )
+        });
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.presolve_pipeline);
+            for iteration in 0..self.presolve_iterations as usize {
+                pass.set_bind_group(0, &bind_groups[iteration % 2], &[]);
+                pass.dispatch_workgroups(workgroups(state.body_count()), 1, 1);
+            }
+        }
+        let result = &buffers.velocities[self.presolve_iterations as usize % 2];
+        let size = state.body_count() as u64 * VELOCITY_SIZE;
+        encoder.copy_buffer_to_buffer(result, 0, &buffers.velocity_readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let mapping = Mapping::start(&self.device, &buffers.velocity_readback, size);
+        // Tuple fields drop in order: the mapping is cancelled before the claim lets go.
+        let mut job = (mapping, claim);
+        GpuReadback::pending(move |block| {
+            let bytes = match job.0.poll(block) {
+                Poll::Pending => return None,
+                Poll::Ready(bytes) => bytes.unwrap_or_default(),
+            };
+            let velocities = bytes
+                .chunks_exact(VELOCITY_SIZE as usize)
+                .map(|v| Vec3::from_slice(&bytemuck::pod_read_unaligned::<[f32; 4]>(v)[..3]));
+            Some(velocities.collect())
+        })
+    }
+
+    /// Applies the CPU's changes to the device particles and records the step after them.
+    fn step_particles(&self, state: &GpuParticleState) {
+        let slot_count = u64::from(state.slot_count);
+        let obstacle_count = state.obstacles.len() as u64;
+        let mut slot = self.particles.lock();
+        let old_capacity = slot.as_ref().map_or(0, |buffers| buffers.capacity);
+        if old_capacity < slot_count || slot.is_none() {
+            let buffers =
+                ParticleBuffers::new(&self.device, slot_count.next_power_of_two(), obstacle_count);
+            if let Some(old) = slot.as_ref() {
+                // Submitted on its own: queued writes run before the next submission's
+                // commands, and must land after the copy.
+                let mut encoder = self.device.create_command_encoder(&Default::default());
+                let size = old.capacity * PARTICLE_SIZE;
+                encoder.copy_buffer_to_buffer(&old.particles, 0, &buffers.particles, 0, size);
+                self.queue.submit([encoder.finish()]);
+            }
+            *slot = Some(buffers);
+        }
+        let buffers = slot.as_mut().expect("particle buffers were just allocated");
+        if buffers.obstacle_capacity < obstacle_count {
+            buffers.obstacle_capacity = obstacle_count.next_power_of_two();
+            buffers.obstacles = obstacle_buffer(&self.device, buffers.obstacle_capacity);
+        }
+
+        for run in state.writes.chunk_by(|a, b| b.0 == a.0 + 1) {
+            let particles: Vec<[f32; 8]> = run.iter().map(|(_, p)| pack_particle(p)).collect();
+            let offset = u64::from(run[0].0) * PARTICLE_SIZE;
+            self.queue
+                .write_buffer(&buffers.particles, offset, bytemuck::cast_slice(&particles));
+        }
+        for &(slot, velocity) in &state.velocity_writes {
+            // The velocity follows the position and radius in each slot.
+            let offset = u64::from(slot) * PARTICLE_SIZE + 16;
+            self.queue.write_buffer(
+                &buffers.particles,
+                offset,
+                bytemuck::cast_slice(&velocity.to_array()),
+            );
+        }
+        if !state.obstacles.is_empty() {
+            let obstacles: Vec<[f32; 24]> = state.obstacles.iter().map(pack_obstacle).collect();
+            self.queue
+                .write_buffer(&buffers.obstacles, 0, bytemuck::cast_slice(&obstacles));
+        }
+        let params = ParticleParams {
+            gravity: state.gravity.to_array(),
+            dt: state.time_step,
+            damping: state.damping,
+            restitution: state.restitution,
+            friction: state.friction,
+            cell_size: state.cell_size,
+            slot_count: state.slot_count,
+            obstacle_count: obstacle_count as u32,
+            table_size: buffers.capacity as u32,
+            _padding: 0,
+        };
+        self.queue
+            .write_buffer(&buffers.params, 0, bytemuck::bytes_of(&params));
+
+        let pipelines = &self.particle_pipelines;
+        let slots = slot_count as usize;
+        // Binding numbers as declared in `particles.wgsl`.
+        let pass = |pipeline, bindings: &[(u32, &wgpu::Buffer)], count| {
+            let resources = bindings
+                .iter()
+                .map(|&(binding, buffer)| (binding, buffer.as_entire_binding()))
+                .collect();
+            (
+                pipeline,
+                bind_group_at(&self.device, pipeline, resources),
+                count,
+            )
+        };
+        let params = (0, &buffers.params);
+        let particles = (1, &buffers.particles);
+        let heads = (2, &buffers.heads);
+        let next = (3, &buffers.next);
+        let corrections = (4, &buffers.corrections);
+        let obstacles = (5, &buffers.obstacles);
+        let mut passes = vec![pass(&pipelines.integrate, &[params, particles], slots)];
+        if state.cell_size > 0.0 {
+            passes.extend([
+                // The grid has `capacity` buckets, at least as many as there are slots.
+                pass(
+                    &pipelines.clear_grid,
+                    &[params, heads],
+                    buffers.capacity as usize,
+                ),
+                pass(&pipelines.insert, &[params, particles, heads, next], slots),
+                pass(
+                    &pipelines.collide,
+                    &[params, particles, heads, next, corrections],
+                    slots,
+                ),
+            ]);
+        }
+        passes.push(pass(
+            &pipelines.resolve,
+            &[params, particles, corrections, obstacles],
+            slots,
+        ));
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut compute = encoder.begin_compute_pass(&Default::default());
+            for (pipeline, bind_group, count) in &passes {
+                compute.set_pipeline(pipeline);
+                compute.set_bind_group(0, bind_group, &[]);
+                compute.dispatch_workgroups(workgroups(*count), 1, 1);
+            }
+        }
+        self.queue.submit([encoder.finish()]);
+        *self.particle_output.current.lock() = Some((buffers.particles.clone(), state.slot_count));
+    }
+
+    /// Builds an LBVH over the world-space shapes of `state` and walks it once per ray.
+    fn raycast(
+        &self,
+        state: &GpuWorldState,
+        query: &RaycastQuery,
+        rays: &[Ray],
+    ) -> GpuReadback<Vec<Option<GpuRayHit>>> {
+        // The tree covers the bounded shapes; planes go last and are tested by every ray.
+        let (planes, bounded): (Vec<u32>, Vec<u32>) = (0..state.shape_count() as u32)
+            .partition(|&index| state.shapes[index as usize].kind == GpuShapeKind::Plane);
+        let order: Vec<u32> = bounded.iter().chain(&planes).copied().collect();
+        let world_shapes: Vec<GpuShape> = order
+            .iter()
+            .map(|&index| state.world_shape(index as usize))
+            .collect();
+        let targets: Vec<[f32; 16]> = order
+            .iter()
+            .zip(&world_shapes)
+            .map(|(&index, shape)| pack_target(state, index, shape))
+            .collect();
+        let spheres: Vec<GpuBody> = world_shapes[..bounded.len()]
+            .iter()
+            .map(|shape| GpuBody {
+                position: shape.offset,
+                radius: shape_reach(shape),
+                ..GpuBody::default()
+            })
+            .collect();
+
+        let ray_count = rays.len() as u64;
+        let target_count = targets.len() as u64;
+        let mut slot = self.raycasts.lock();
+        let reusable = slot.as_ref().is_some_and(|b| {
+            b.ray_capacity >= ray_count
+                && b.target_capacity >= target_count
+                && Arc::strong_count(b) == 1
+        });
+        if !reusable {
+            *slot = Some(Arc::new(RaycastBuffers::new(
+                &self.device,
+                ray_count.next_power_of_two(),
+                target_count.next_power_of_two(),
+            )));
+        }
+        let buffers = slot.clone().expect("raycast buffers were just allocated");
+        drop(slot);
+
+        let params = RaycastParams {
+            ray_count: ray_count as u32,
+            bounded_count: bounded.len() as u32,
+            target_count: target_count as u32,
+            layer_mask: query.layer_mask,
+            query_layer: query.query_layer,
+            ignore_triggers: query.ignore_triggers.into(),
+            _padding: [0; 2],
+        };
+        self.queue
+            .write_buffer(&buffers.query, 0, bytemuck::bytes_of(&params));
+        let packed: Vec<[f32; 8]> = rays.iter().map(pack_ray).collect();
+        self.queue
+            .write_buffer(&buffers.rays, 0, bytemuck::cast_slice(&packed));
+        if !targets.is_empty() {
+            self.queue
+                .write_buffer(&buffers.targets, 0, bytemuck::cast_slice(&targets));
+        }
+        if !spheres.is_empty() {
+            let spheres: Vec<[f32; 8]> = spheres.iter().map(pack_body).collect();
+            self.queue
+                .write_buffer(&buffers.spheres, 0, bytemuck::cast_slice(&spheres));
+        }
+
+        let bind_group = bind_group(
+            &self.device,
+            &self.raycast_pipeline,
+            &[
+                &buffers.query,
+                &buffers.rays,
+                &buffers.tree.nodes,
+                &buffers.targets,
+                &buffers.hits,
+            ],
+        );
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            if !spheres.is_empty() {
+                encode_lbvh_build(
+                    &self.device,
+                    &self.queue,
+                    &mut pass,
+                    &self.lbvh_pipelines,
+                    &buffers.tree,
+                    &buffers.spheres,
+                    &lbvh_scene(&spheres),
+                );
+            }
+            pass.set_pipeline(&self.raycast_pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups(rays.len()), 1, 1);
+        }
+        let size = ray_count * HIT_SIZE;
+        encoder.copy_buffer_to_buffer(&buffers.hits, 0, &buffers.readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let mapping = Mapping::start(&self.device, &buffers.readback, size);
+        // Tuple fields drop in order: the mapping is cancelled before the buffers are freed.
+        let mut job = (mapping, buffers);
+        GpuReadback::pending(move |block| {
+            let bytes = match job.0.poll(block) {
+                Poll::Pending => return None,
+                Poll::Ready(bytes) => bytes.unwrap_or_default(),
+            };
+            let hits = bytes.chunks_exact(HIT_SIZE as usize).map(unpack_hit);
+            Some(hits.collect())
+        })
+    }
+}
+
+/// A broad-phase pass whose pairs have not been read back yet.
+struct PairPass {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    /// Set when the pass walks an LBVH instead of testing every pair.
+    lbvh: Option<(LbvhPipelines, Scene)>,
+    claim: Claim,
+    /// Replaces the claimed pair buffers after an overflow.
+    grown: Option<PairBuffers>,
+    params: Params,
+    pair_capacity: Arc<AtomicU64>,
+}
+
+impl PairPass {
+    fn pairs(&self) -> &PairBuffers {
+        self.grown.as_ref().unwrap_or(&self.claim.buffers.pairs)
+    }
+
+    /// Runs the broad phase into the current pair buffers. After an overflow the LBVH is
+    /// rebuilt along with the pairs, which only happens until the capacity has caught up.
+    fn submit(&self) -> Mapping {
+        let pairs = self.pairs();
+        self.queue
+            .write_buffer(&pairs.count, 0, bytemuck::bytes_of(&0u32));
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        match &self.lbvh {
+            Some((pipelines, scene)) => self.encode_lbvh(&mut encoder, pipelines, scene),
+            None => self.encode_brute_force(&mut encoder),
+        }
+        let pair_bytes = pairs.capacity * PAIR_SIZE;
+        encoder.copy_buffer_to_buffer(&pairs.count, 0, &pairs.readback, 0, 4);
+        encoder.copy_buffer_to_buffer(&pairs.pairs, 0, &pairs.readback, PAIRS_OFFSET, pair_bytes);
+        self.queue.submit([encoder.finish()]);
+        Mapping::start(&self.device, &pairs.readback, PAIRS_OFFSET + pair_bytes)
+    }
+
+    fn encode_brute_force(&self, encoder: &mut wgpu::CommandEncoder) {
+        let buffers = &self.claim.buffers;
+        let pairs = self.pairs();
+        let params = Params {
+            max_pairs: pairs.capacity as u32,
+            ..self.params
+        };
+        self.queue
+            .write_buffer(&buffers.params, 0, bytemuck::bytes_of(&params));
+
+        let bind_group = bind_group(
+            &self.device,
+            &self.pipeline,
+            &[&buffers.params, &buffers.bodies, &pairs.count, &pairs.pairs],
+        );
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(workgroups(params.body_count as usize), 1, 1);
+    }
+
+    /// Builds the tree over the bodies, then walks it once per body.
+    fn encode_lbvh(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipelines: &LbvhPipelines,
+        scene: &Scene,
+    ) {
+        let buffers = &self.claim.buffers;
+        let tree = buffers
+            .tree
+            .as_ref()
+            .expect("LBVH passes claim buffers with a tree");
+        let pairs = self.pairs();
+        let scene = Scene {
+            max_pairs: pairs.capacity as u32,
+            ..*scene
+        };
+        let traverse = bind_group_at(
+            &self.device,
+            &pipelines.traverse,
+            vec![
+                (0, tree.scene.as_entire_binding()),
+                (2, buffers.bodies.as_entire_binding()),
+                (4, tree.nodes.as_entire_binding()),
+                (5, pairs.count.as_entire_binding()),
+                (6, pairs.pairs.as_entire_binding()),
+            ],
+        );
+
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        encode_lbvh_build(
+            &self.device,
+            &self.queue,
+            &mut pass,
+            pipelines,
+            tree,
+            &buffers.bodies,
+            &scene,
+        );
+        pass.set_pipeline(&pipelines.traverse);
+        pass.set_bind_group(0, &traverse, &[]);
+        pass.dispatch_workgroups(workgroups(scene.body_count as usize), 1, 1);
+    }
+
+    /// Reads the pairs once `mapping` completes, running the kernel again with a larger pair
+    /// buffer if it overflowed.
+    fn poll(&mut self, mapping: &mut Mapping, block: bool) -> Option<Vec<(u32, u32)>> {
+        loop {
+            let bytes = match mapping.poll(block) {
+                Poll::Pending => return None,
+                Poll::Ready(Some(bytes)) => bytes,
+                Poll::Ready(None) => return Some(Vec::new()),
+            };
+            let count = u64::from(bytemuck::pod_read_unaligned::<u32>(&bytes[..4]));
+            if count <= self.pairs().capacity {
+                let end = (PAIRS_OFFSET + count * PAIR_SIZE) as usize;
+                let pairs = bytes[PAIRS_OFFSET as usize..end]
+                    .chunks_exact(PAIR_SIZE as usize)
+                    .map(|pair| {
+                        let [i, j] = bytemuck::pod_read_unaligned::<[u32; 2]>(pair);
+                        (i, j)
+                    });
+                return Some(pairs.collect());
+            }
+            let capacity = count.next_power_of_two();
+            self.pair_capacity.fetch_max(capacity, Ordering::Relaxed);
+            self.grown = Some(PairBuffers::new(&self.device, capacity));
+            *mapping = self.submit();
+        }
+    }
+}
+
+/// A readback buffer being mapped once the GPU finishes the submission that fills it.
+struct Mapping {
+    device: wgpu::Device,
+    buffer: wgpu::Buffer,
+    size: u64,
+    mapped: mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>,
+    finished: bool,
+}
+
+impl Mapping {
+    fn start(device: &wgpu::Device, buffer: &wgpu::Buffer, size: u64) -> Self {
+        let (sender, mapped) = mpsc::channel();
+        buffer
+            .slice(..size)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        Self {
+            device: device.clone(),
+            buffer: buffer.clone(),
+            size,
+            mapped,
+            finished: false,
+        }
+    }
+
+    /// The first `size` bytes of the buffer once mapped, or `None` if mapping failed (e.g.
+    /// the device was lost).
+    fn poll(&mut self, block: bool) -> Poll<Option<Vec<u8>>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+        let result = if block {
+            if self
+                .device
+                .poll(wgpu::PollType::wait_indefinitely())
+                .is_err()
+            {
+                return Poll::Ready(None);
+            }
+            self.mapped.recv().ok()
+        } else {
+            let _ = self.device.poll(wgpu::PollType::Poll);
+            match self.mapped.try_recv() {
+                Ok(result) => Some(result),
+                Err(mpsc::TryRecvError::Empty) => return Poll::Pending,
+                Err(mpsc::TryRecvError::Disconnected) => None,
+            }
+        };
+        self.finished = true;
+        if !matches!(result, Some(Ok(()))) {
+            return Poll::Ready(None);
+        }
+        let slice = self.buffer.slice(..self.size);
+        let bytes = slice.get_mapped_range().ok().map(|view| view.to_vec());
+        self.buffer.unmap();
+        Poll::Ready(bytes)
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        // Cancels a map that is still pending so the buffer can be reused.
+        if !self.finished {
+            self.buffer.unmap();
+        }
+    }
+}
+
+impl LbvhPipelines {
+    fn new(device: &wgpu::Device) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("lbvh"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("shaders/lbvh.wgsl").into()),
+        });
+        let pipeline = |entry_point| entry_pipeline(device, &module, entry_point);
+        Self {
+            morton: pipeline("morton"),
+            sort_step: pipeline("sort_step"),
+            build: pipeline("build"),
+            depth: pipeline("depth"),
+            refit: pipeline("refit"),
+            traverse: pipeline("traverse"),
+        }
+    }
+}
+
+impl ParticlePipelines {
+    fn new(device: &wgpu::Device) -> Self {
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("particles"),
+            source: wgpu::ShaderSource::Wgsl(
+                concat!(
+                    include_str!("shaders/shapes.wgsl"),
+                    include_str!("shaders/particles.wgsl")
+                )
+                .into(),
+            ),
+        });
+        let pipeline = |entry_point| entry_pipeline(device, &module, entry_point);
+        Self {
+            integrate: pipeline("integrate"),
+            clear_grid: pipeline("clear_grid"),
+            insert: pipeline("insert"),
+            collide: pipeline("collide"),
+            resolve: pipeline("resolve"),
+        }
+    }
+}
+
+impl ParticleBuffers {
+    fn new(device: &wgpu::Device, capacity: u64, obstacle_capacity: u64) -> Self {
+        use wgpu::BufferUsages as Usage;
+
+        let capacity = capacity.max(1);
+        let obstacle_capacity = obstacle_capacity.next_power_of_two();
+        let storage = Usage::STORAGE | Usage::COPY_DST;
+        Self {
+            capacity,
+            params: buffer(
+                device,
+                "particle_params",
+                std::mem::size_of::<ParticleParams>() as u64,
+                Usage::UNIFORM | Usage::COPY_DST,
+            ),
+            particles: buffer(
+                device,
+                "particles",
+                capacity * PARTICLE_SIZE,
+                storage | Usage::VERTEX | Usage::COPY_SRC,
+            ),
+            heads: buffer(device, "particle_grid_heads", capacity * 4, storage),
+            next: buffer(device, "particle_grid_next", capacity * 4, storage),
+            corrections: buffer(
+                device,
+                "particle_corrections",
+                capacity * CORRECTION_SIZE,
+                storage,
+            ),
+            obstacle_capacity,
+            obstacles: obstacle_buffer(device, obstacle_capacity),
+        }
+    }
+}
+
+impl Buffers {
+    fn new(device: &wgpu::Device, body_capacity: u64, pair_capacity: u64, tree: bool) -> Self {
+        use wgpu::BufferUsages as Usage;
+
+        let body_capacity = body_capacity.max(1);
+        let storage = Usage::STORAGE | Usage::COPY_DST | Usage::COPY_SRC;
+        let velocity_bytes = body_capacity * VELOCITY_SIZE;
+        Self {
+            body_capacity,
+            params: buffer(
+                device,
+                "params",
+                std::mem::size_of::<Params>() as u64,
+                Usage::UNIFORM | Usage::COPY_DST,
+            ),
+            bodies: buffer(device, "bodies", body_capacity * BODY_SIZE, storage),
+            pairs: PairBuffers::new(device, pair_capacity),
+            tree: tree.then(|| TreeBuffers::new(device, body_capacity)),
+            velocities: [
+                buffer(device, "velocities_a", velocity_bytes, storage),
+                buffer(device, "velocities_b", velocity_bytes, storage),
+            ],
+            velocity_readback: buffer(
+                device,
+                "velocity_readback",
+                velocity_bytes,
+                Usage::MAP_READ | Usage::COPY_DST,
+            ),
+            pairs_in_flight: AtomicBool::new(false),
+            velocities_in_flight: AtomicBool::new(false),
+        }
+    }
+
+    fn in_flight(&self, kind: PassKind) -> &AtomicBool {
+        match kind {
+            PassKind::Pairs => &self.pairs_in_flight,
+            PassKind::Velocities => &self.velocities_in_flight,
+        }
+    }
+
+    fn in_use(&self) -> bool {
+        self.pairs_in_flight.load(Ordering::Acquire)
+            || self.velocities_in_flight.load(Ordering::Acquire)
+    }
+}
+
+impl PairBuffers {
+    fn new(device: &wgpu::Device, capacity: u64) -> Self {
+        use wgpu::BufferUsages as Usage;
+
+        let capacity = capacity.max(1);
+        let storage = Usage::STORAGE | Usage::COPY_DST | Usage::COPY_SRC;
+        Self {
+            capacity,
+            count: buffer(device, "pair_count", 4, storage),
+            pairs: buffer(device, "pairs", capacity * PAIR_SIZE, storage),
+            readback: buffer(
+                device,
+                "pair_readback",
+                PAIRS_OFFSET + capacity * PAIR_SIZE,
+                Usage::MAP_READ | Usage::COPY_DST,
+            ),
+        }
+    }
+}
+
+impl RaycastBuffers {
+    fn new(device: &wgpu::Device, ray_capacity: u64, target_capacity: u64) -> Self {
+        use wgpu::BufferUsages as Usage;
+
+        let ray_capacity = ray_capacity.max(1);
+        let target_capacity = target_capacity.max(1);
+        let storage = Usage::STORAGE | Usage::COPY_DST;
+        Self {
+            ray_capacity,
+            target_capacity,
+            query: buffer(
+                device,
+                "raycast_query",
+                std::mem::size_of::<RaycastParams>() as u64,
+                Usage::UNIFORM | Usage::COPY_DST,
+            ),
+            rays: buffer(device, "rays", ray_capacity * RAY_SIZE, storage),
+            targets: buffer(
+                device,
+                "raycast_targets",
+                target_capacity * TARGET_SIZE,
+                storage,
+            ),
+            spheres: buffer(
+                device,
+                "raycast_spheres",
+                target_capacity * BODY_SIZE,
+                storage,
+            ),
+            tree: TreeBuffers::new(device, target_capacity),
+            hits: buffer(
+                device,
+                "ray_hits",
+                ray_capacity * HIT_SIZE,
+                storage | Usage::COPY_SRC,
+            ),
+            readback: buffer(
+                device,
+                "ray_hit_readback",
+                ray_capacity * HIT_SIZE,
+                Usage::MAP_READ | Usage::COPY_DST,
+            ),
+        }
+    }
+}
+
+impl TreeBuffers {
+    fn new(device: &wgpu::Device, body_capacity: u64) -> Self {
+        use wgpu::BufferUsages as Usage;
+
+        let storage = Usage::STORAGE | Usage::COPY_DST;
+        let uniform = Usage::UNIFORM | Usage::COPY_DST;
+        let steps = sort_steps(body_capacity as u32).len() as u64 + u64::from(LBVH_MAX_DEPTH);
+        Self {
+            scene: buffer(
+                device,
+                "lbvh_scene",
+                std::mem::size_of::<Scene>() as u64,
+                uniform,
+            ),
+            steps: buffer(device, "lbvh_steps", steps * STEP_STRIDE, uniform),
+            keys: buffer(device, "lbvh_keys", body_capacity * KEY_SIZE, storage),
+            nodes: buffer(device, "lbvh_nodes", 2 * body_capacity * NODE_SIZE, storage),
+        }
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        self.buffers
+            .in_flight(self.kind)
+            .store(false, Ordering::Release);
+    }
+}
+
+impl ComputeBackend for WgpuBackend {
+    fn name(&self) -> &str {
+        "wgpu"
+    }
+
+    fn prepare_step(&self, state: &GpuWorldState) {
+        let mut slot = self.buffers.lock();
+        self.upload(state, &mut slot);
+    }
+
+    fn dispatch_broadphase(&self, state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        if state.body_count() < 2 {
+            return Some(GpuReadback::ready(Vec::new()));
+        }
+        let lbvh = self
+            .uses_lbvh(state.body_count())
+            .then(|| (self.lbvh_pipelines.clone(), lbvh_scene(&state.bodies)));
+        let pass = PairPass {
+            device: self.device.clone(),
+            queue: self.queue.clone(),
+            pipeline: self.broadphase_pipeline.clone(),
+            lbvh,
+            claim: self.claim(state, PassKind::Pairs),
+            grown: None,
+            params: self.params(state),
+            pair_capacity: self.pair_capacity.clone(),
+        };
+        let mapping = pass.submit();
+        // Tuple fields drop in order: the mapping is cancelled before the claim lets go.
+        let mut job = (mapping, pass);
+        Some(GpuReadback::pending(move |block| {
+            let (mapping, pass) = &mut job;
+            pass.poll(mapping, block)
+        }))
+    }
+
+    fn dispatch_solver(&self, state: &GpuWorldState) -> Option<GpuReadback<Vec<Vec3>>> {
+        if state.body_count() == 0 {
+            return Some(GpuReadback::ready(Vec::new()));
+        }
+        Some(self.presolve(state))
+    }
+
+    fn simulates_particles(&self) -> bool {
+        true
+    }
+
+    fn dispatch_particles(&self, state: &GpuParticleState) {
+        self.step_particles(state);
+    }
+
+    fn dispatch_raycasts(
+        &self,
+        state: &GpuWorldState,
+        query: &RaycastQuery,
+        rays: &[Ray],
+    ) -> Option<GpuReadback<Vec<Option<GpuRayHit>>>> {
+        if rays.is_empty() {
+            return Some(GpuReadback::ready(Vec::new()));
+        }
+        Some(self.raycast(state, query, rays))
+    }
+}
+
+fn buffer(
+    device: &wgpu::Device,
+    label: &str,
+    size: u64,
+    usage: wgpu::BufferUsages,
+) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size,
+        usage,
+        mapped_at_creation: false,
+    })
+}
+
+fn bind_group(
+    device: &wgpu::Device,
+    pipeline: &wgpu::ComputePipeline,
+    buffers: &[&wgpu::Buffer],
+) -> wgpu::BindGroup {
+    let resources = buffers
+        .iter()
+        .enumerate()
+        .map(|(binding, buffer)| (binding as u32, buffer.as_entire_binding()))
+        .collect();
+    bind_group_at(device, pipeline, resources)
+}
+
+/// Binds resources at explicit binding numbers, for entry points that use only some of
+/// their module's bindings.
+fn bind_group_at(
+    device: &wgpu::Device,
+    pipeline: &wgpu::ComputePipeline,
+    resources: Vec<(u32, wgpu::BindingResource)>,
+) -> wgpu::BindGroup {
+    let entries: Vec<wgpu::BindGroupEntry> = resources
+        .into_iter()
+        .map(|(binding, resource)| wgpu::BindGroupEntry { binding, resource })
+        .collect();
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: None,
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &entries,
+    })
+}
+
+fn compute_pipeline(device: &wgpu::Device, label: &str, source: &str) -> wgpu::ComputePipeline {
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    entry_pipeline(device, &module, "main")
+}
+
+fn entry_pipeline(
+    device: &wgpu::Device,
+    module: &wgpu::ShaderModule,
+    entry_point: &str,
+) -> wgpu::ComputePipeline {
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some(entry_point),
+        layout: None,
+        module,
+        entry_point: Some(entry_point),
+        compilation_options: Default::default(),
+        cache: None,
+    })
+}
+
+fn pack_body(body: &GpuBody) -> [f32; 8] {
+    let [px, py, pz] = body.position.to_array();
+    let [vx, vy, vz] = body.velocity.to_array();
+    [px, py, pz, body.radius, vx, vy, vz, body.inverse_mass]
+}
+
+/// Records the LBVH build over the spheres in `bodies`: Morton keys, the bitonic sort, the
+/// radix tree, node depths and the level-by-level refit.
+fn encode_lbvh_build(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    pass: &mut wgpu::ComputePass,
+    pipelines: &LbvhPipelines,
+    tree: &TreeBuffers,
+    bodies: &wgpu::Buffer,
+    scene: &Scene,
+) {
+    queue.write_buffer(&tree.scene, 0, bytemuck::bytes_of(scene));
+
+    let sort = sort_steps(scene.key_count);
+    let levels = (0..LBVH_MAX_DEPTH).rev().map(|level| Step {
+        level,
+        ..Step::zeroed()
+    });
+    let steps: Vec<Step> = sort.iter().copied().chain(levels).collect();
+    let mut bytes = vec![0u8; steps.len() * STEP_STRIDE as usize];
+    for (chunk, step) in bytes.chunks_exact_mut(STEP_STRIDE as usize).zip(&steps) {
+        chunk[..std::mem::size_of::<Step>()].copy_from_slice(bytemuck::bytes_of(step));
+    }
+    queue.write_buffer(&tree.steps, 0, &bytes);
+
+    let step = |index: usize| {
+        wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+            buffer: &tree.steps,
+            offset: index as u64 * STEP_STRIDE,
+            size: NonZeroU64::new(std::mem::size_of::<Step>() as u64),
+        })
+    };
+    let scene_binding = || tree.scene.as_entire_binding();
+    let morton = bind_group_at(
+        device,
+        &pipelines.morton,
+        vec![
+            (0, scene_binding()),
+            (2, bodies.as_entire_binding()),
+            (3, tree.keys.as_entire_binding()),
+        ],
+    );
+    let sort_groups: Vec<_> = (0..sort.len())
+        .map(|index| {
+            bind_group_at(
+                device,
+                &pipelines.sort_step,
+                vec![
+                    (0, scene_binding()),
+                    (1, step(index)),
+                    (3, tree.keys.as_entire_binding()),
+                ],
+            )
+        })
+        .collect();
+    let build = bind_group_at(
+        device,
+        &pipelines.build,
+        vec![
+            (0, scene_binding()),
+            (2, bodies.as_entire_binding()),
+            (3, tree.keys.as_entire_binding()),
+            (4, tree.nodes.as_entire_binding()),
+        ],
+    );
+    let depth = bind_group_at(
+        device,
+        &pipelines.depth,
+        vec![(0, scene_binding()), (4, tree.nodes.as_entire_binding())],
+    );
+    let refit_groups: Vec<_> = (sort.len()..steps.len())
+        .map(|index| {
+            bind_group_at(
+                device,
+                &pipelines.refit,
+                vec![
+                    (0, scene_binding()),
+                    (1, step(index)),
+                    (4, tree.nodes.as_entire_binding()),
+                ],
+            )
+        })
+        .collect();
+
+    let bodies = workgroups(scene.body_count as usize);
+    let keys = workgroups(scene.key_count as usize);
+    pass.set_pipeline(&pipelines.morton);
+    pass.set_bind_group(0, &morton, &[]);
+    pass.dispatch_workgroups(keys, 1, 1);
+    pass.set_pipeline(&pipelines.sort_step);
+    for group in &sort_groups {
+        pass.set_bind_group(0, group, &[]);
+        pass.dispatch_workgroups(keys, 1, 1);
+    }
+    for (pipeline, group) in [(&pipelines.build, &build), (&pipelines.depth, &depth)] {
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, group, &[]);
+        pass.dispatch_workgroups(bodies, 1, 1);
+    }
+    pass.set_pipeline(&pipelines.refit);
+    for group in &refit_groups {
+        pass.set_bind_group(0, group, &[]);
+        pass.dispatch_workgroups(bodies, 1, 1);
+    }
+}
+
+/// Bounds of the body centres, which the Morton codes quantise onto a 1024³ grid.
+fn lbvh_scene(bodies: &[GpuBody]) -> Scene {
+    let (lower, upper) = bodies
+        .iter()
+        .fold((Vec3::MAX, Vec3::MIN), |(lower, upper), body| {
+            (lower.min(body.position), upper.max(body.position))
+        });
+    let extent = upper - lower;
+    let scale = Vec3::select(extent.cmpgt(Vec3::ZERO), extent.recip(), Vec3::ZERO);
+    let body_count = bodies.len() as u32;
+    Scene {
+        origin: lower.to_array(),
+        body_count,
+        scale: scale.to_array(),
+        key_count: body_count.next_power_of_two(),
+        max_pairs: 0,
+        _padding: [0; 3],
+    }
+}
+
+/// The (`j`, `k`) steps of a bitonic sort over `key_count` keys, a power of two.
+fn sort_steps(key_count: u32) -> Vec<Step> {
+    let mut steps = Vec::new();
+    let mut k = 2;
+    while k <= key_count {
+        let mut j = k / 2;
+        while j > 0 {
+            steps.push(Step {
+                j,
+                k,
+                ..Step::zeroed()
+            });
+            j /= 2;
+        }
+        k *= 2;
+    }
+    steps
+}
+
+/// Sized for at least one obstacle, since bindings can't be empty.
+fn obstacle_buffer(device: &wgpu::Device, capacity: u64) -> wgpu::Buffer {
+    use wgpu::BufferUsages as Usage;
+
+    buffer(
+        device,
+        "particle_obstacles",
+        capacity.max(1) * OBSTACLE_SIZE,
+        Usage::STORAGE | Usage::COPY_DST,
+    )
+}
+
+fn pack_particle(particle: &GpuParticle) -> [f32; 8] {
+    let [px, py, pz] = particle.position.to_array();
+    let [vx, vy, vz] = particle.velocity.to_array();
+    [
+        px,
+        py,
+        pz,
+        particle.radius,
+        vx,
+        vy,
+        vz,
+        particle.inverse_mass,
+    ]
+}
+
+/// Packs an obstacle into the 96-byte `std430` layout of `Obstacle` in `particles.wgsl`.
+fn pack_obstacle(obstacle: &GpuObstacle) -> [f32; 24] {
+    let shape = &obstacle.shape;
+    let mut packed = [0.0; 24];
+    packed[..3].copy_from_slice(&shape.offset.to_array());
+    packed[3] = f32::from_bits(shape.kind as u32);
+    packed[4..8].copy_from_slice(&shape.rotation.to_array());
+    packed[8..12].copy_from_slice(&shape.params.to_array());
+    packed[12..15].copy_from_slice(&obstacle.pivot.to_array());
+    packed[15] = obstacle.reach;
+    packed[16..19].copy_from_slice(&obstacle.linear_velocity.to_array());
+    packed[20..23].copy_from_slice(&obstacle.angular_velocity.to_array());
+    packed
+}
+
+/// Packs a ray with a normalized direction. Rays without a direction get a negative
+/// `max_distance`, which the kernel reads as a miss.
+fn pack_ray(ray: &Ray) -> [f32; 8] {
+    let direction = ray.direction.normalize_or_zero();
+    let max_distance = if direction == Vec3::ZERO {
+        -1.0
+    } else {
+        ray.max_distance.clamp(0.0, f32::MAX)
+    };
+    let [ox, oy, oz] = ray.origin.to_array();
+    let [dx, dy, dz] = direction.to_array();
+    [ox, oy, oz, max_distance, dx, dy, dz, 0.0]
+}
+
+/// Packs shape `index` of `state`, placed in world space as `shape`, into the 64-byte
+/// `std430` layout of `Target` in `raycast.wgsl`.
+fn pack_target(state: &GpuWorldState, index: u32, shape: &GpuShape) -> [f32; 16] {
+    let filter = state.shape_filters[index as usize];
+    let trigger = state.shape_triggers[index as usize];
+    let mut packed = [0.0; 16];
+    packed[..3].copy_from_slice(&shape.offset.to_array());
+    packed[3] = f32::from_bits(shape.kind as u32);
+    packed[4..8].copy_from_slice(&shape.rotation.to_array());
+    packed[8..12].copy_from_slice(&shape.params.to_array());
+    packed[12] = f32::from_bits(index);
+    packed[13] = f32::from_bits(filter.layer);
+    packed[14] = f32::from_bits(filter.mask);
+    packed[15] = f32::from_bits(trigger.into());
+    packed
+}
+
+/// Reads one `Hit` of `raycast.wgsl`: normal, distance, shape index and status.
+fn unpack_hit(bytes: &[u8]) -> Option<GpuRayHit> {
+    let [nx, ny, nz, distance, shape, status, _, _] =
+        bytemuck::pod_read_unaligned::<[u32; 8]>(bytes);
+    match status {
+        1 => Some(GpuRayHit::Hit {
+            shape,
+            distance: f32::from_bits(distance),
+            normal: Vec3::new(f32::from_bits(nx), f32::from_bits(ny), f32::from_bits(nz)),
+        }),
+        2 => Some(GpuRayHit::Unresolved),
+        _ => None,
+    }
+}
+
+fn workgroups(count: usize) -> u32 {
+    (count as u32).div_ceil(WORKGROUP_SIZE)
+}
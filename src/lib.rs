@@ -4,36 +4,68 @@
 //! ECS-friendly patterns, offering collision detection, dynamics,
 //! constraint solving, and utility modules out of the box.
 
+#[cfg(feature = "bevy")]
+pub mod bevy;
 pub mod collision;
 pub mod config;
 pub mod core;
 pub mod dynamics;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod gpu;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 pub mod world;
 
 pub use glam::{Mat3, Mat4, Quat, Vec3};
 
+pub use config::{BroadPhaseConfig, ConfigError, SolverConfig, WorldConfig};
 pub use core::soa::{BodyMut, BodyRef};
 
 pub use collision::{
-    broadphase::BroadPhase,
-    contact::ContactManifold,
-    queries::{Raycast, RaycastHit, RaycastQuery},
+    broadphase::{BroadPhase, BroadPhaseBackend},
+    contact::{
+        ContactEvent, ContactEventInfo, ContactManifold, ManifoldDebugInfo, ManifoldPointDebugInfo,
+    },
+    queries::{closest_points, ClosestPoints, Ray, Raycast, RaycastHit, RaycastQuery},
+    query_pipeline::QueryPipeline,
+    recorder::ManifoldRecorder,
+    trigger::{TriggerEvent, TriggerOverlap},
 };
 pub use core::{
-    collider::{Collider, ColliderShape, CollisionFilter},
-    rigidbody::RigidBody,
-    types::{MassProperties, Material, Transform, Velocity},
+    collider::{Collider, ColliderShape, CollisionFilter, CollisionGroups, CollisionLayers},
+    rigidbody::{BodyType, RigidBody},
+    types::{
+        AnisotropicFriction, MassIssue, MassProperties, MassValidation, Material, MaterialId,
+        MaterialPairOverride, MaterialPairTable, StribeckCurve, Transform, Velocity,
+    },
 };
 pub use dynamics::{
-    forces::{DragForce, ForceGenerator, ForceRegistry, GravityForce, SpringForce},
-    integrator::Integrator,
-    solver::{ConstraintSolver, Contact},
+    cloth::{Cloth, ClothAttachment, ClothParams},
+    fields::{FieldKind, FieldVolume, ForceField},
+    fluid::{Fluid, FluidEmitter, FluidParams},
+    forces::{
+        AeroSurface, DragForce, ExplosionFalloff, ForceGenerator, ForceRegistry, GravityForce,
+        SpringForce,
+    },
+    integrator::{IntegrationScheme, Integrator},
+    particles::{Particle, ParticleLink, ParticleSystem},
+    soft_body::{SoftBody, SoftBodyParams},
+    solver::{BlockSolverMode, ConstraintSolver, Contact, PositionCorrection},
+};
+pub use gpu::{
+    ComputeBackend, GpuParticleState, GpuRayHit, GpuReadback, GpuWorldState, NoopBackend,
 };
-pub use gpu::{ComputeBackend, GpuWorldState, NoopBackend};
 pub use utils::allocator::{Arena, EntityId, GenerationalId};
-pub use world::PhysicsWorld;
+pub use utils::profiling::WorldStepStats;
+pub use world::{
+    async_step::{StepHandle, WorldSnapshot},
+    debug_render::{DebugColor, DebugRenderBackend, DebugRenderOptions},
+    quarantine::{NonFiniteEvent, NonFinitePolicy, SimulationStage},
+    state::{WorldState, WORLD_STATE_VERSION},
+    PhysicsWorld, RemovedBody, StepHook,
+};
 
 /// High-level convenience wrapper that owns a [`PhysicsWorld`].
 pub struct PhysicsEngine {
@@ -1,9 +1,12 @@
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
 /// Unique identifier with generation tracking to prevent stale references.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenerationalId {
     pub index: usize,
     pub generation: u32,
@@ -16,7 +19,8 @@ impl GenerationalId {
 }
 
 /// Entity identifier wrapper used across the engine.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EntityId(pub GenerationalId);
 
 impl EntityId {
@@ -39,6 +43,15 @@ impl EntityId {
     pub fn is_null(&self) -> bool {
         self.0.index == usize::MAX
     }
+
+    /// Packs the id as `(generation << 32) | index` for crossing FFI and WASM boundaries.
+    pub fn to_bits(&self) -> u64 {
+        ((self.generation() as u64) << 32) | (self.index() as u64 & 0xffff_ffff)
+    }
+
+    pub fn from_bits(bits: u64) -> Self {
+        Self::new((bits & 0xffff_ffff) as usize, (bits >> 32) as u32)
+    }
 }
 
 impl Default for EntityId {
@@ -48,6 +61,8 @@ impl Default for EntityId {
 }
 
 /// Generational arena that hands out stable IDs while preventing use-after-free.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Arena<T> {
     items: Vec<Option<T>>,
     generations: Vec<u32>,
@@ -82,6 +97,13 @@ impl<T> Arena<T> {
         EntityId::new(index, 0)
     }
 
+    /// Makes room for `additional` more items, beyond the free slots already available.
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = additional.saturating_sub(self.free_list.len());
+        self.items.reserve(needed);
+        self.generations.reserve(needed);
+    }
+
     pub fn get(&self, id: EntityId) -> Option<&T> {
         if self.is_valid(id) {
             self.items.get(id.index()).and_then(|slot| slot.as_ref())
@@ -180,14 +202,49 @@ impl<T> Arena<T> {
 }
 
 impl<T: Send> Arena<T> {
+    /// Runs `f` over every live item, on the rayon pool when the `parallel` feature is on.
     pub fn par_for_each_mut<F>(&mut self, f: F)
     where
         F: Fn(&mut T) + Send + Sync,
     {
+        #[cfg(feature = "parallel")]
         self.items
             .par_iter_mut()
             .filter_map(|slot| slot.as_mut())
             .for_each(f);
+        #[cfg(not(feature = "parallel"))]
+        self.items
+            .iter_mut()
+            .filter_map(|slot| slot.as_mut())
+            .for_each(f);
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<T: Send + Sync> Arena<T> {
+    /// Visits every live item with its id on the rayon pool.
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = (EntityId, &T)> + '_ {
+        self.items
+            .par_iter()
+            .zip(self.generations.par_iter())
+            .enumerate()
+            .filter_map(|(index, (slot, &generation))| {
+                slot.as_ref()
+                    .map(|item| (EntityId::new(index, generation), item))
+            })
+    }
+
+    /// Hands each live item out mutably to at most one rayon task, alongside its id, so
+    /// systems can update thousands of items in parallel without `get_mut` per id.
+    pub fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = (EntityId, &mut T)> + '_ {
+        self.items
+            .par_iter_mut()
+            .zip(self.generations.par_iter())
+            .enumerate()
+            .filter_map(|(index, (slot, &generation))| {
+                slot.as_mut()
+                    .map(|item| (EntityId::new(index, generation), item))
+            })
     }
 }
 
@@ -1,10 +1,12 @@
 use log::{Level, log_enabled, warn};
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+use crate::utils::profiling::Stopwatch;
 
 /// Simple scoped timer for profiling critical sections.
 pub struct ScopedTimer<'a> {
     label: &'a str,
-    start: Instant,
+    start: Stopwatch,
 }
 
 impl<'a> ScopedTimer<'a> {
@@ -14,7 +16,7 @@ impl<'a> ScopedTimer<'a> {
         }
         Self {
             label,
-            start: Instant::now(),
+            start: Stopwatch::start(),
         }
     }
 }
@@ -12,6 +12,27 @@ pub fn angular_velocity_to_quat(angular: Vec3, dt: f32) -> Quat {
     Quat::from_axis_angle(axis, angle)
 }
 
+/// Rotates an inertia tensor given about the body axes into the frame `rotation` maps them to.
+pub fn rotate_inertia(inertia: Mat3, rotation: Quat) -> Mat3 {
+    let rotation = Mat3::from_quat(rotation);
+    rotation * inertia * rotation.transpose()
+}
+
+/// Moves an inertia tensor about the centre of mass to a point `offset` away from it
+/// (parallel axis theorem).
+pub fn translate_inertia(inertia: Mat3, mass: f32, offset: Vec3) -> Mat3 {
+    let outer = Mat3::from_cols(offset * offset.x, offset * offset.y, offset * offset.z);
+    inertia + (Mat3::from_diagonal(Vec3::splat(offset.length_squared())) - outer) * mass
+}
+
+/// Two unit vectors completing a right-handed basis `(tangent, bitangent, axis)`, without a
+/// branch on the axis direction (Duff et al. 2017). `axis` need not be unit length; a zero
+/// axis gives the X and Y axes.
+pub fn orthonormal_basis(axis: Vec3) -> (Vec3, Vec3) {
+    axis.try_normalize()
+        .map_or((Vec3::X, Vec3::Y), |axis| axis.any_orthonormal_pair())
+}
+
 /// Builds an inertia tensor for a solid capsule aligned along Y.
 pub fn inertia_capsule(radius: f32, height: f32, mass: f32) -> Mat3 {
     let cylinder_mass = mass * 0.6;
@@ -27,3 +48,179 @@ pub fn inertia_capsule(radius: f32, height: f32, mass: f32) -> Mat3 {
 
     cylinder_inertia + sphere_inertia
 }
+
+/// Closest point on triangle `abc` to `p` (Ericson, Real-Time Collision Detection 5.1.5).
+pub fn closest_point_on_triangle(p: Vec3, a: Vec3, b: Vec3, c: Vec3) -> Vec3 {
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && (d4 - d3) >= 0.0 && (d5 - d6) >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+/// Closest point on segment `ab` to `p`.
+pub fn closest_point_on_segment(p: Vec3, a: Vec3, b: Vec3) -> Vec3 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    if length_squared <= 1e-12 {
+        return a;
+    }
+    a + ab * ((p - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+}
+
+/// Closest points between segments `p1q1` and `p2q2`.
+pub fn closest_points_segments(p1: Vec3, q1: Vec3, p2: Vec3, q2: Vec3) -> (Vec3, Vec3) {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.length_squared();
+    let e = d2.length_squared();
+    let f = d2.dot(r);
+
+    if a <= 1e-12 && e <= 1e-12 {
+        return (p1, p2);
+    }
+
+    let (s, t) = if a <= 1e-12 {
+        (0.0, (f / e).clamp(0.0, 1.0))
+    } else {
+        let c = d1.dot(r);
+        if e <= 1e-12 {
+            ((-c / a).clamp(0.0, 1.0), 0.0)
+        } else {
+            let b = d1.dot(d2);
+            let denom = a * e - b * b;
+            let mut s = if denom.abs() > 1e-12 {
+                ((b * f - c * e) / denom).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let mut t = (b * s + f) / e;
+            if t < 0.0 {
+                t = 0.0;
+                s = (-c / a).clamp(0.0, 1.0);
+            } else if t > 1.0 {
+                t = 1.0;
+                s = ((b - c) / a).clamp(0.0, 1.0);
+            }
+            (s, t)
+        }
+    };
+
+    (p1 + d1 * s, p2 + d2 * t)
+}
+
+/// Closest points between segment `pq` and triangle `abc`, as `(on_segment, on_triangle)`.
+pub fn closest_points_segment_triangle(
+    p: Vec3,
+    q: Vec3,
+    a: Vec3,
+    b: Vec3,
+    c: Vec3,
+) -> (Vec3, Vec3) {
+    let normal = (b - a).cross(c - a);
+    let dp = normal.dot(p - a);
+    let dq = normal.dot(q - a);
+    if dp * dq <= 0.0 && (dp - dq).abs() > 1e-12 {
+        let crossing = p + (q - p) * (dp / (dp - dq));
+        let projected = closest_point_on_triangle(crossing, a, b, c);
+        if (projected - crossing).length_squared() < 1e-10 {
+            return (crossing, crossing);
+        }
+    }
+
+    let mut best = (p, closest_point_on_triangle(p, a, b, c));
+    let mut best_dist = (best.0 - best.1).length_squared();
+    let mut consider = |candidate: (Vec3, Vec3)| {
+        let dist = (candidate.0 - candidate.1).length_squared();
+        if dist < best_dist {
+            best_dist = dist;
+            best = candidate;
+        }
+    };
+
+    consider((q, closest_point_on_triangle(q, a, b, c)));
+    for (e0, e1) in [(a, b), (b, c), (c, a)] {
+        consider(closest_points_segments(p, q, e0, e1));
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inertia_transforms_match_a_rotated_and_offset_box() {
+        let inertia = Mat3::from_diagonal(Vec3::new(1.0, 2.0, 3.0));
+        let rotated = rotate_inertia(inertia, Quat::from_rotation_z(std::f32::consts::FRAC_PI_2));
+        assert!(rotated.abs_diff_eq(Mat3::from_diagonal(Vec3::new(2.0, 1.0, 3.0)), 1e-5));
+
+        let moved = translate_inertia(inertia, 2.0, Vec3::new(0.0, 3.0, 0.0));
+        assert!(moved.abs_diff_eq(Mat3::from_diagonal(Vec3::new(19.0, 2.0, 21.0)), 1e-5));
+    }
+
+    #[test]
+    fn closest_point_on_segment_clamps_to_the_ends() {
+        let (a, b) = (Vec3::ZERO, Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(
+            closest_point_on_segment(Vec3::new(1.0, 3.0, 0.0), a, b),
+            Vec3::X
+        );
+        assert_eq!(closest_point_on_segment(Vec3::new(-1.0, 1.0, 0.0), a, b), a);
+        assert_eq!(closest_point_on_segment(Vec3::new(5.0, 0.0, 1.0), a, b), b);
+        assert_eq!(closest_point_on_segment(Vec3::ONE, a, a), a);
+    }
+
+    #[test]
+    fn orthonormal_basis_is_right_handed_for_any_axis() {
+        for axis in [
+            Vec3::Z,
+            -Vec3::Z,
+            Vec3::new(3.0, -4.0, 0.0),
+            Vec3::new(1e-3, 0.0, -1.0),
+        ] {
+            let (tangent, bitangent) = orthonormal_basis(axis);
+            assert!((tangent.length() - 1.0).abs() < 1e-5);
+            assert!((bitangent.length() - 1.0).abs() < 1e-5);
+            assert!(tangent.cross(bitangent).abs_diff_eq(axis.normalize(), 1e-5));
+        }
+        assert_eq!(orthonormal_basis(Vec3::ZERO), (Vec3::X, Vec3::Y));
+    }
+}
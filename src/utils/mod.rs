@@ -6,6 +6,7 @@ pub mod math;
 pub mod profiling;
 pub mod simd;
 pub mod spatial;
+pub(crate) mod spatial_hash;
 
 pub use spatial::{SpatialInertia, SpatialVec};
 
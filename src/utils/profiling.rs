@@ -1,4 +1,6 @@
-use std::time::{Duration, Instant};
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
 
 /// Global or Thread-Local profiler state would be ideal, but for now
 /// The profiler is attached to the PhysicsWorld and accessed via direct members or static handles.
@@ -65,15 +67,71 @@ impl PhysicsProfiler {
     }
 }
 
-pub struct ScopedTimer<'a> {
+/// Where the time of the last [`PhysicsWorld::step`](crate::world::PhysicsWorld::step)
+/// went, summed over the fixed steps it ran.
+///
+/// Times are wall-clock and read zero on `wasm32`; counts are exact everywhere.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WorldStepStats {
+    /// Fixed steps run; zero if the accumulated time did not reach one.
+    pub fixed_steps: u32,
+    pub broadphase_time: Duration,
+    /// Candidate collider pairs the broad phase passed to the narrow phase.
+    pub broadphase_pairs: usize,
+    pub narrowphase_time: Duration,
+    /// Colliding pairs the narrow phase produced a contact manifold for.
+    pub manifolds: usize,
+    /// Contacts handed to the solver, including speculative and CCD contacts.
+    pub contacts: usize,
+    pub solver_time: Duration,
+    /// Velocity iterations run, over every island solved.
+    pub solver_iterations: u32,
+    pub islands: usize,
+    /// Islands that were awake and solved.
+    pub awake_islands: usize,
+    /// Body count of the largest awake island.
+    pub largest_island: usize,
+    pub integration_time: Duration,
+    /// Wall-clock time of the whole call, including the stages not broken out above.
+    pub total_time: Duration,
+}
+
+/// Wall-clock timer. `std::time::Instant` panics on `wasm32-unknown-unknown`, so there every
+/// reading is zero.
+#[derive(Debug, Clone, Copy)]
+pub struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
     start: Instant,
+}
+
+impl Stopwatch {
+    pub fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: Instant::now(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+pub struct ScopedTimer<'a> {
+    start: Stopwatch,
     output: &'a mut Duration,
 }
 
 impl<'a> ScopedTimer<'a> {
     pub fn new(output: &'a mut Duration) -> Self {
         Self {
-            start: Instant::now(),
+            start: Stopwatch::start(),
             output,
         }
     }
@@ -84,3 +142,30 @@ impl<'a> Drop for ScopedTimer<'a> {
         *self.output += self.start.elapsed();
     }
 }
+
+/// Opens a named scope in the external frame profilers enabled by the `tracy` and `puffin`
+/// features; it closes at the end of the enclosing block. Expands to nothing without them.
+macro_rules! profile_scope {
+    ($name:literal) => {
+        #[cfg(feature = "puffin")]
+        ::puffin::profile_scope!($name);
+        #[cfg(feature = "tracy")]
+        let _tracy_span = ::tracy_client::Client::running()
+            .map(|client| client.span(::tracy_client::span_location!($name), 0));
+    };
+}
+pub(crate) use profile_scope;
+
+/// Closes the current frame in the external profilers, for applications that do not mark
+/// frames themselves. A no-op without the `tracy` or `puffin` feature.
+///
+/// Tracy only records once a `tracy_client::Client` is started, and puffin once
+/// `puffin::set_scopes_on(true)` is called.
+pub fn finish_frame() {
+    #[cfg(feature = "puffin")]
+    puffin::GlobalProfiler::lock().new_frame();
+    #[cfg(feature = "tracy")]
+    if let Some(client) = tracy_client::Client::running() {
+        client.frame_mark();
+    }
+}
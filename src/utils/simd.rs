@@ -7,7 +7,7 @@
 use glam::{Mat4, Vec3, Vec3A};
 
 /// Controls whether SIMD helpers are allowed to use additional threads for
-/// bulk operations.
+/// bulk operations. `Parallel` runs serially without the `parallel` feature.
 #[derive(Debug, Clone, Copy)]
 pub enum SimdJobMode {
     Serial,
@@ -23,17 +23,18 @@ pub fn batch_transform_points(points: &[Vec3], matrix: &Mat4) -> Vec<Vec3> {
 /// Transforms the provided points in-place by the given matrix.
 pub fn transform_points_in_place(points: &mut [Vec3], matrix: &Mat4, mode: SimdJobMode) {
     match mode {
-        SimdJobMode::Serial => {
-            for point in points.iter_mut() {
-                *point = matrix.transform_point3(*point);
-            }
-        }
+        #[cfg(feature = "parallel")]
         SimdJobMode::Parallel => {
             use rayon::prelude::*;
             points.par_iter_mut().for_each(|point| {
                 *point = matrix.transform_point3(*point);
             });
         }
+        _ => {
+            for point in points.iter_mut() {
+                *point = matrix.transform_point3(*point);
+            }
+        }
     }
 }
 
@@ -108,7 +109,7 @@ pub fn max_length(vertices: &[Vec3]) -> f32 {
 use crate::core::{collider::ColliderShape, types::Transform};
 use crate::dynamics::solver::Contact;
 use crate::utils::allocator::EntityId;
-use glam::Vec4;
+use glam::{BVec4A, Mat3, Vec4};
 
 /// Structure-of-Arrays (SoA) SIMD vector holding 4 3D vectors.
 ///
@@ -151,11 +152,69 @@ impl Vec3x4 {
         }
     }
 
+    pub fn from_array(v: [Vec3; 4]) -> Self {
+        Self {
+            x: Vec4::new(v[0].x, v[1].x, v[2].x, v[3].x),
+            y: Vec4::new(v[0].y, v[1].y, v[2].y, v[3].y),
+            z: Vec4::new(v[0].z, v[1].z, v[2].z, v[3].z),
+        }
+    }
+
+    pub fn mul(&self, other: Self) -> Self {
+        Self {
+            x: self.x * other.x,
+            y: self.y * other.y,
+            z: self.z * other.z,
+        }
+    }
+
+    pub fn add(&self, other: Self) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    /// Multiplies each lane by its own scalar.
+    pub fn scale(&self, factor: Vec4) -> Self {
+        Self {
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    pub fn cross(&self, other: Self) -> Self {
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn length(&self) -> Vec4 {
+        Vec4::from_array(self.dot(*self).to_array().map(f32::sqrt))
+    }
+
+    /// Picks each lane from `if_true` where `mask` is set and from `if_false` elsewhere.
+    pub fn select(mask: BVec4A, if_true: Self, if_false: Self) -> Self {
+        Self {
+            x: Vec4::select(mask, if_true.x, if_false.x),
+            y: Vec4::select(mask, if_true.y, if_false.y),
+            z: Vec4::select(mask, if_true.z, if_false.z),
+        }
+    }
+
+    pub fn to_array(&self) -> [Vec3; 4] {
+        let (x, y, z) = (self.x.to_array(), self.y.to_array(), self.z.to_array());
+        std::array::from_fn(|i| Vec3::new(x[i], y[i], z[i]))
+    }
+
     pub fn normalize_or_zero(&self) -> Self {
         let lensq = self.dot(*self);
         let mask = lensq.cmpgt(Vec4::splat(1e-6));
-        // Use 1.0/sqrt(x) if rsqrt is not available, or just sqrt
-        let inv_len = 1.0 / lensq.max(Vec4::splat(1e-6)).powf(0.5);
+        let inv_len = 1.0 / self.length().max(Vec4::splat(1e-3));
         Self {
             x: Vec4::select(mask, self.x * inv_len, Vec4::ZERO),
             y: Vec4::select(mask, self.y * inv_len, Vec4::ZERO),
@@ -164,6 +223,79 @@ impl Vec3x4 {
     }
 }
 
+/// Four 3x3 matrices in SoA form, one per lane, stored by column.
+#[derive(Clone, Copy, Debug)]
+pub struct Mat3x4 {
+    pub x_axis: Vec3x4,
+    pub y_axis: Vec3x4,
+    pub z_axis: Vec3x4,
+}
+
+impl Mat3x4 {
+    pub fn from_array(m: [Mat3; 4]) -> Self {
+        Self {
+            x_axis: Vec3x4::from_array(m.map(|m| m.x_axis)),
+            y_axis: Vec3x4::from_array(m.map(|m| m.y_axis)),
+            z_axis: Vec3x4::from_array(m.map(|m| m.z_axis)),
+        }
+    }
+
+    /// Multiplies each lane's matrix by the same lane of `v`.
+    pub fn mul_vec3(&self, v: Vec3x4) -> Vec3x4 {
+        self.x_axis
+            .scale(v.x)
+            .add(self.y_axis.scale(v.y))
+            .add(self.z_axis.scale(v.z))
+    }
+}
+
+/// Four rays in SoA form for packet slab tests against axis-aligned boxes.
+#[derive(Clone, Copy, Debug)]
+pub struct RayPacket {
+    pub origin: Vec3x4,
+    pub inv_direction: Vec3x4,
+    pub max_distance: Vec4,
+}
+
+impl RayPacket {
+    /// Packs up to four `(origin, unit direction, max distance)` rays. Missing lanes never hit.
+    pub fn new(rays: &[(Vec3, Vec3, f32)]) -> Self {
+        let mut origin = [Vec3::ZERO; 4];
+        let mut inv_direction = [Vec3::ZERO; 4];
+        let mut max_distance = [-1.0; 4];
+        for (lane, (ray_origin, direction, distance)) in rays.iter().take(4).enumerate() {
+            origin[lane] = *ray_origin;
+            // Clamping keeps axis-parallel rays finite so the slab products never hit 0 * inf.
+            inv_direction[lane] = direction
+                .recip()
+                .clamp(Vec3::splat(-f32::MAX), Vec3::splat(f32::MAX));
+            max_distance[lane] = *distance;
+        }
+        Self {
+            origin: Vec3x4::from_array(origin),
+            inv_direction: Vec3x4::from_array(inv_direction),
+            max_distance: Vec4::from_array(max_distance),
+        }
+    }
+
+    /// Bitmask of the lanes whose ray enters `[min, max]` within its max distance.
+    pub fn slab_test(&self, min: Vec3, max: Vec3) -> u32 {
+        let t1 = Vec3x4::splat(min).sub(self.origin).mul(self.inv_direction);
+        let t2 = Vec3x4::splat(max).sub(self.origin).mul(self.inv_direction);
+        let t_enter =
+            t1.x.min(t2.x)
+                .max(t1.y.min(t2.y))
+                .max(t1.z.min(t2.z))
+                .max(Vec4::ZERO);
+        let t_exit =
+            t1.x.max(t2.x)
+                .min(t1.y.max(t2.y))
+                .min(t1.z.max(t2.z))
+                .min(self.max_distance);
+        t_enter.cmple(t_exit).bitmask()
+    }
+}
+
 /// Helper to select between two Vec4s based on a mask (like _mm_blendv_ps).
 // Note: Moved to math utils or inline here if needed.
 // For now, using glam's select approach.
@@ -1,9 +1,13 @@
+use super::math::translate_inertia;
 use glam::{Mat3, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// A 6D spatial vector combining angular and linear components.
 /// In motion space, angular is velocity and linear is translation.
 /// In force space, angular is torque and linear is force.
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SpatialVec {
     pub ang: Vec3,
     pub lin: Vec3,
@@ -229,8 +233,8 @@ impl SpatialInertia {
         let d1 = self.com - com_total;
         let d2 = other.com - com_total;
 
-        let i1 = self.inertia + self.inertia_offset(d1, self.mass);
-        let i2 = other.inertia + self.inertia_offset(d2, other.mass);
+        let i1 = translate_inertia(self.inertia, self.mass, d1);
+        let i2 = translate_inertia(other.inertia, other.mass, d2);
 
         Self {
             mass: m_total,
@@ -238,9 +242,4 @@ impl SpatialInertia {
             inertia: i1 + i2,
         }
     }
-
-    fn inertia_offset(&self, d: Vec3, m: f32) -> Mat3 {
-        let d2 = d.length_squared();
-        Mat3::from_diagonal(Vec3::splat(d2)) - Mat3::from_cols(d * d.x, d * d.y, d * d.z) * m
-    }
 }
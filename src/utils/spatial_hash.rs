@@ -0,0 +1,49 @@
+//! Uniform-grid neighbour search over point clouds.
+//!
+//! A query visits every point within one cell of the query point, so cells must be at least
+//! as wide as the largest search radius.
+
+use std::collections::HashMap;
+
+use glam::Vec3;
+
+/// Uniform grid of point indices, queried one cell out in every direction.
+pub(crate) struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<(i32, i32, i32), Vec<usize>>,
+}
+
+impl SpatialHash {
+    pub(crate) fn build(cell_size: f32, points: impl Iterator<Item = Vec3>) -> Self {
+        let mut cells: HashMap<(i32, i32, i32), Vec<usize>> = HashMap::new();
+        for (index, point) in points.enumerate() {
+            cells
+                .entry(Self::cell(point, cell_size))
+                .or_default()
+                .push(index);
+        }
+        Self { cell_size, cells }
+    }
+
+    fn cell(point: Vec3, cell_size: f32) -> (i32, i32, i32) {
+        let cell = (point / cell_size).floor();
+        (cell.x as i32, cell.y as i32, cell.z as i32)
+    }
+
+    /// Calls `visit` with every point in the 27 cells around `point`.
+    pub(crate) fn for_each_near(&self, point: Vec3, mut visit: impl FnMut(usize)) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let (x, y, z) = Self::cell(point, self.cell_size);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    if let Some(indices) = self.cells.get(&(x + dx, y + dy, z + dz)) {
+                        indices.iter().copied().for_each(&mut visit);
+                    }
+                }
+            }
+        }
+    }
+}
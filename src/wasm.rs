@@ -0,0 +1,131 @@
+//! `wasm-bindgen` bindings for browser games. Enabled with the `wasm` feature.
+//!
+//! Build with `--target wasm32-unknown-unknown --no-default-features --features wasm`;
+//! rayon threads are unavailable on the web without extra setup. Entity ids cross into
+//! JavaScript as `BigInt` values packed by [`EntityId::to_bits`]. See `docs/WASM.md`.
+
+use glam::Vec3;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    core::{collider::Collider, rigidbody::RigidBody},
+    utils::allocator::EntityId,
+    world::PhysicsWorld,
+};
+
+/// A [`PhysicsWorld`] owned by JavaScript.
+#[wasm_bindgen]
+pub struct WasmWorld {
+    world: PhysicsWorld,
+}
+
+#[wasm_bindgen]
+impl WasmWorld {
+    #[wasm_bindgen(constructor)]
+    pub fn new(time_step: f32) -> WasmWorld {
+        Self {
+            world: PhysicsWorld::new(time_step),
+        }
+    }
+
+    #[wasm_bindgen(js_name = setGravity)]
+    pub fn set_gravity(&mut self, x: f32, y: f32, z: f32) {
+        self.world.gravity = Vec3::new(x, y, z);
+    }
+
+    /// Adds a rigid body at the given position and returns its id.
+    #[wasm_bindgen(js_name = addBody)]
+    pub fn add_body(&mut self, x: f32, y: f32, z: f32, mass: f32, is_static: bool) -> u64 {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = Vec3::new(x, y, z);
+        body.mass_properties.mass = mass;
+        body.is_static = is_static;
+        self.world.add_rigidbody(body).to_bits()
+    }
+
+    /// Sets a body's linear velocity. Returns `false` for unknown bodies.
+    #[wasm_bindgen(js_name = setLinearVelocity)]
+    pub fn set_linear_velocity(&mut self, body: u64, x: f32, y: f32, z: f32) -> bool {
+        match self.world.body_mut(EntityId::from_bits(body)) {
+            Some(body) => {
+                body.velocity.linear = Vec3::new(x, y, z);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attaches a sphere collider. Returns `undefined` for unknown bodies.
+    #[wasm_bindgen(js_name = addSphereCollider)]
+    pub fn add_sphere_collider(&mut self, body: u64, radius: f32) -> Option<u64> {
+        self.add_collider(body, Collider::builder().sphere(radius).build())
+    }
+
+    /// Attaches a box collider. Returns `undefined` for unknown bodies.
+    #[wasm_bindgen(js_name = addBoxCollider)]
+    pub fn add_box_collider(&mut self, body: u64, hx: f32, hy: f32, hz: f32) -> Option<u64> {
+        self.add_collider(
+            body,
+            Collider::builder().box_shape(Vec3::new(hx, hy, hz)).build(),
+        )
+    }
+
+    /// Attaches a Y-aligned capsule collider. Returns `undefined` for unknown bodies.
+    #[wasm_bindgen(js_name = addCapsuleCollider)]
+    pub fn add_capsule_collider(&mut self, body: u64, radius: f32, height: f32) -> Option<u64> {
+        self.add_collider(body, Collider::builder().capsule(radius, height).build())
+    }
+
+    pub fn step(&mut self, dt: f32) {
+        self.world.step(dt);
+    }
+
+    #[wasm_bindgen(js_name = bodyCount)]
+    pub fn body_count(&self) -> usize {
+        self.world.bodies.len()
+    }
+
+    /// Ids of all bodies, in the order used by [`WasmWorld::read_transforms`].
+    #[wasm_bindgen(js_name = bodyIds)]
+    pub fn body_ids(&self) -> Vec<u64> {
+        self.world
+            .bodies
+            .iter()
+            .map(|body| body.id().to_bits())
+            .collect()
+    }
+
+    /// Copies transforms into caller-owned `Float32Array`s: three floats (xyz) per body in
+    /// `positions` and four (xyzw) in `rotations`. Returns the number of bodies written,
+    /// limited by the shorter buffer.
+    #[wasm_bindgen(js_name = readTransforms)]
+    pub fn read_transforms(&self, positions: &mut [f32], rotations: &mut [f32]) -> usize {
+        let capacity = (positions.len() / 3).min(rotations.len() / 4);
+        let mut written = 0;
+        for body in self.world.bodies.iter().take(capacity) {
+            let transform = body.transform();
+            positions[written * 3..written * 3 + 3].copy_from_slice(&transform.position.to_array());
+            rotations[written * 4..written * 4 + 4].copy_from_slice(&transform.rotation.to_array());
+            written += 1;
+        }
+        written
+    }
+}
+
+impl WasmWorld {
+    fn add_collider(&mut self, body: u64, mut collider: Collider) -> Option<u64> {
+        let body = EntityId::from_bits(body);
+        self.world.body(body)?;
+        collider.rigidbody_id = body;
+        Some(self.world.add_collider(collider).to_bits())
+    }
+
+    /// The wrapped world, for Rust code sharing a module with the bindings.
+    pub fn world(&self) -> &PhysicsWorld {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut PhysicsWorld {
+        &mut self.world
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+};
+
+use crate::{
+    core::soa::{BodiesSoA, BodyRef},
+    utils::allocator::EntityId,
+    world::PhysicsWorld,
+};
+
+/// Read-only copy of body state captured when an asynchronous step starts.
+#[derive(Default)]
+pub struct WorldSnapshot {
+    bodies: BodiesSoA,
+    frame_index: u32,
+}
+
+impl WorldSnapshot {
+    pub(crate) fn capture(&mut self, world: &PhysicsWorld) {
+        self.bodies.clone_from(&world.bodies);
+        self.frame_index = world.frame_index;
+    }
+
+    pub fn bodies(&self) -> &BodiesSoA {
+        &self.bodies
+    }
+
+    pub fn body(&self, id: EntityId) -> Option<BodyRef<'_>> {
+        self.bodies.get(id)
+    }
+
+    /// Number of fixed substeps the world had run when the snapshot was taken.
+    pub fn frame_index(&self) -> u32 {
+        self.frame_index
+    }
+}
+
+/// In-flight [`PhysicsWorld::step_async`] call.
+///
+/// The live world runs on a pooled worker while the caller reads [`StepHandle::snapshot`]:
+/// rayon's global pool with the `parallel` feature, otherwise a single long-lived thread.
+/// [`StepHandle::join`] waits for the step and swaps the stepped world back in; dropping the
+/// handle joins as well.
+///
+/// If the step panics, the world is left empty. `join` resumes the panic on the calling
+/// thread and [`StepHandle::try_join`] returns it; a dropped handle logs it.
+pub struct StepHandle<'w> {
+    world: &'w mut PhysicsWorld,
+    snapshot: Option<WorldSnapshot>,
+    step: Option<PendingStep>,
+}
+
+enum PendingStep {
+    /// Receives the stepped world, or the payload of a panic in the step.
+    #[cfg(not(target_arch = "wasm32"))]
+    Worker(mpsc::Receiver<std::thread::Result<Box<PhysicsWorld>>>),
+    #[cfg(target_arch = "wasm32")]
+    Done(Box<PhysicsWorld>),
+}
+
+impl<'w> StepHandle<'w> {
+    pub(crate) fn spawn(world: &'w mut PhysicsWorld, dt: f32) -> Self {
+        let mut snapshot = std::mem::take(&mut world.snapshot_buffer);
+        snapshot.capture(world);
+
+        // The two worlds trade places: the live one goes to the worker, the spare stands in.
+        let mut live = world.async_spare.take().unwrap_or_else(|| {
+            Box::new(PhysicsWorld::builder().time_step(world.time_step).build())
+        });
+        std::mem::swap(world, &mut *live);
+
+        // No threads on the web: step before returning.
+        #[cfg(target_arch = "wasm32")]
+        let step = {
+            live.step(dt);
+            PendingStep::Done(live)
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let step = {
+            let (sender, receiver) = mpsc::sync_channel(1);
+            run_on_worker(move || {
+                let stepped = panic::catch_unwind(AssertUnwindSafe(move || {
+                    live.step(dt);
+                    live
+                }));
+                // The handle always waits for the result, so the receiver is still there.
+                let _ = sender.send(stepped);
+            });
+            PendingStep::Worker(receiver)
+        };
+
+        Self {
+            world,
+            snapshot: Some(snapshot),
+            step: Some(step),
+        }
+    }
+
+    /// State of the world before this step, valid until the handle is joined.
+    pub fn snapshot(&self) -> &WorldSnapshot {
+        self.snapshot
+            .as_ref()
+            .expect("snapshot is held until the handle is joined")
+    }
+
+    /// Blocks until the step completes and swaps the stepped world back in. A panic in the
+    /// step is resumed here.
+    pub fn join(mut self) {
+        if let Err(payload) = self.finish() {
+            std::panic::resume_unwind(payload);
+        }
+    }
+
+    /// Like [`Self::join`], but returns the payload of a panic in the step instead of
+    /// resuming it.
+    pub fn try_join(mut self) -> std::thread::Result<()> {
+        self.finish()
+    }
+
+    fn finish(&mut self) -> std::thread::Result<()> {
+        let stepped = match self.step.take() {
+            #[cfg(not(target_arch = "wasm32"))]
+            Some(PendingStep::Worker(receiver)) => receiver.recv().unwrap_or_else(|_| {
+                let lost: Box<dyn std::any::Any + Send> =
+                    Box::new("physics worker dropped the step without a result");
+                Err(lost)
+            }),
+            #[cfg(target_arch = "wasm32")]
+            Some(PendingStep::Done(world)) => Ok(world),
+            None => return Ok(()),
+        };
+        let mut world = stepped?;
+        std::mem::swap(self.world, &mut *world);
+        self.world.async_spare = Some(world);
+        if let Some(snapshot) = self.snapshot.take() {
+            self.world.snapshot_buffer = snapshot;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StepHandle<'_> {
+    fn drop(&mut self) {
+        if self.finish().is_err() {
+            log::error!("physics step panicked on the worker thread; the world was lost");
+        }
+    }
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "parallel"))]
+fn run_on_worker(job: impl FnOnce() + Send + 'static) {
+    rayon::spawn(job);
+}
+
+/// Without rayon, every asynchronous step runs on one thread started on first use.
+#[cfg(all(not(target_arch = "wasm32"), not(feature = "parallel")))]
+fn run_on_worker(job: impl FnOnce() + Send + 'static) {
+    use std::sync::OnceLock;
+
+    type Job = Box<dyn FnOnce() + Send>;
+    static WORKER: OnceLock<mpsc::Sender<Job>> = OnceLock::new();
+
+    let worker = WORKER.get_or_init(|| {
+        let (sender, jobs) = mpsc::channel::<Job>();
+        std::thread::Builder::new()
+            .name("physics-step".into())
+            .spawn(move || jobs.into_iter().for_each(|job| job()))
+            .expect("failed to start the physics step worker");
+        sender
+    });
+    // Jobs catch their own panics, so the worker outlives every step.
+    worker
+        .send(Box::new(job))
+        .expect("physics step worker has stopped");
+}
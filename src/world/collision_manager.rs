@@ -1,10 +1,23 @@
-use crate::collision::{broadphase::BroadPhase, ccd::CCDDetector, contact::ManifoldCache};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::collision::{
+    broadphase::BroadPhase,
+    ccd::CCDDetector,
+    contact::{ContactManifold, ManifoldCache},
+    trigger::TriggerTracker,
+};
 use crate::config::DEFAULT_BROADPHASE_CELL_SIZE;
+use crate::core::{collider::Collider, soa::BodiesSoA, types::MaterialPairTable};
+use crate::dynamics::solver::Contact;
+use crate::utils::allocator::{Arena, EntityId};
 
 pub struct CollisionManager {
     pub broadphase: BroadPhase,
     pub manifold_cache: ManifoldCache,
     pub ccd: CCDDetector,
+    pub triggers: TriggerTracker,
+    pub material_pairs: MaterialPairTable,
 }
 
 impl Default for CollisionManager {
@@ -19,6 +32,110 @@ impl CollisionManager {
             broadphase: BroadPhase::new(DEFAULT_BROADPHASE_CELL_SIZE),
             manifold_cache: ManifoldCache::new(),
             ccd: CCDDetector::new(),
+            triggers: TriggerTracker::new(),
+            material_pairs: MaterialPairTable::new(),
+        }
+    }
+}
+
+/// Narrowphase result for one broadphase pair. Results are applied to the trigger tracker
+/// and manifold cache afterwards, in pair order.
+pub(crate) enum PairOutcome {
+    Trigger {
+        trigger: EntityId,
+        other: EntityId,
+    },
+    Manifold {
+        manifold: ContactManifold,
+        collider_a: EntityId,
+        collider_b: EntityId,
+    },
+    Speculative(Contact),
+}
+
+impl CollisionManager {
+    /// Runs the narrowphase over `pairs`. With `parallel` set (and the `parallel` feature
+    /// enabled) each rayon worker fills its own buffer; buffers are merged by pair index, so
+    /// the outcome order matches the serial path.
+    pub(crate) fn narrowphase(
+        &self,
+        pairs: &[(EntityId, EntityId)],
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        dt: f32,
+        parallel: bool,
+    ) -> Vec<PairOutcome> {
+        #[cfg(feature = "parallel")]
+        if parallel {
+            let buffers: Vec<Vec<(usize, PairOutcome)>> = pairs
+                .par_iter()
+                .enumerate()
+                .fold(Vec::new, |mut buffer, (index, pair)| {
+                    if let Some(outcome) = self.narrowphase_pair(*pair, colliders, bodies, dt) {
+                        buffer.push((index, outcome));
+                    }
+                    buffer
+                })
+                .collect();
+            let mut merged: Vec<(usize, PairOutcome)> = buffers.into_iter().flatten().collect();
+            merged.sort_unstable_by_key(|(index, _)| *index);
+            return merged.into_iter().map(|(_, outcome)| outcome).collect();
+        }
+        #[cfg(not(feature = "parallel"))]
+        let _ = parallel;
+
+        pairs
+            .iter()
+            .filter_map(|pair| self.narrowphase_pair(*pair, colliders, bodies, dt))
+            .collect()
+    }
+
+    fn narrowphase_pair(
+        &self,
+        (collider_a_id, collider_b_id): (EntityId, EntityId),
+        colliders: &Arena<Collider>,
+        bodies: &BodiesSoA,
+        dt: f32,
+    ) -> Option<PairOutcome> {
+        let collider_a = colliders.get(collider_a_id)?;
+        let collider_b = colliders.get(collider_b_id)?;
+        if collider_a.rigidbody_id == collider_b.rigidbody_id {
+            return None;
+        }
+        let rb_a = bodies.get(collider_a.rigidbody_id)?.to_rigid_body();
+        let rb_b = bodies.get(collider_b.rigidbody_id)?.to_rigid_body();
+
+        // Triggers only report overlaps; they never reach the solver.
+        if collider_a.is_trigger || collider_b.is_trigger {
+            let touching = ContactManifold::generate(collider_a, &rb_a, collider_b, &rb_b)
+                .is_some_and(|manifold| manifold.points.iter().any(|p| p.depth >= 0.0));
+            if !touching {
+                return None;
+            }
+            let (trigger, other) = if collider_a.is_trigger {
+                (collider_a.id, collider_b.id)
+            } else {
+                (collider_b.id, collider_a.id)
+            };
+            return Some(PairOutcome::Trigger { trigger, other });
+        }
+
+        if let Some(manifold) = ContactManifold::generate(collider_a, &rb_a, collider_b, &rb_b) {
+            return Some(PairOutcome::Manifold {
+                manifold,
+                collider_a: collider_a_id,
+                collider_b: collider_b_id,
+            });
+        }
+
+        // Only bodies that opted into CCD get speculative contacts.
+        if !rb_a.ccd_enabled && !rb_b.ccd_enabled {
+            return None;
         }
+        let mut speculative = self
+            .ccd
+            .generate_speculative_contact(&rb_a, collider_a, &rb_b, collider_b, dt)?;
+        speculative.material = self.material_pairs.combine(&rb_a.material, &rb_b.material);
+        Some(PairOutcome::Speculative(speculative))
     }
 }
@@ -0,0 +1,413 @@
+//! Backend-agnostic debug geometry. [`PhysicsWorld::debug_render`] turns the world into
+//! colored line segments and hands them to a [`DebugRenderBackend`], which can draw them
+//! with any renderer.
+
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use glam::{Quat, Vec3};
+
+use crate::{
+    collision::queries::world_bounds,
+    core::{
+        collider::{hull_triangles, ColliderShape},
+        constraints::Joint,
+        soa::BodyRef,
+        types::Transform,
+    },
+    utils::allocator::EntityId,
+    world::PhysicsWorld,
+};
+
+/// Linear RGBA color, each channel in `0..=1`.
+pub type DebugColor = [f32; 4];
+
+/// Receives the line segments [`PhysicsWorld::debug_render`] emits.
+pub trait DebugRenderBackend {
+    fn draw_line(&mut self, start: Vec3, end: Vec3, color: DebugColor);
+}
+
+/// What [`PhysicsWorld::debug_render`] draws, and how.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugRenderOptions {
+    /// Collider wireframes.
+    pub colliders: bool,
+    /// World-space bounds of every bounded collider.
+    pub aabbs: bool,
+    /// Contact points with their normals, from the manifolds kept after the last step.
+    pub contacts: bool,
+    /// Joint anchors, the line between them, and the joint frames.
+    pub joints: bool,
+    /// Colors collider wireframes by the island their body was solved in, instead of by
+    /// body type and sleep state.
+    pub island_colors: bool,
+    /// Length of contact normals and joint frame axes, and half the size drawn for planes.
+    pub axis_length: f32,
+    /// Segments per full circle on round shapes.
+    pub circle_segments: usize,
+}
+
+impl Default for DebugRenderOptions {
+    fn default() -> Self {
+        Self {
+            colliders: true,
+            aabbs: false,
+            contacts: true,
+            joints: true,
+            island_colors: false,
+            axis_length: 0.5,
+            circle_segments: 16,
+        }
+    }
+}
+
+const DYNAMIC_COLOR: DebugColor = [0.9, 0.6, 0.2, 1.0];
+const SLEEPING_COLOR: DebugColor = [0.5, 0.5, 0.5, 1.0];
+const STATIC_COLOR: DebugColor = [0.3, 0.5, 0.9, 1.0];
+const KINEMATIC_COLOR: DebugColor = [0.8, 0.3, 0.9, 1.0];
+const TRIGGER_COLOR: DebugColor = [0.3, 0.9, 0.9, 1.0];
+const AABB_COLOR: DebugColor = [0.9, 0.9, 0.3, 1.0];
+const CONTACT_COLOR: DebugColor = [1.0, 0.2, 0.2, 1.0];
+const JOINT_COLOR: DebugColor = [0.2, 0.9, 0.3, 1.0];
+const AXIS_COLORS: [DebugColor; 3] = [
+    [1.0, 0.0, 0.0, 1.0],
+    [0.0, 1.0, 0.0, 1.0],
+    [0.0, 0.0, 1.0, 1.0],
+];
+const ISLAND_COLORS: [DebugColor; 8] = [
+    [0.9, 0.3, 0.3, 1.0],
+    [0.3, 0.8, 0.3, 1.0],
+    [0.3, 0.5, 0.9, 1.0],
+    [0.9, 0.8, 0.2, 1.0],
+    [0.8, 0.3, 0.8, 1.0],
+    [0.2, 0.8, 0.8, 1.0],
+    [0.9, 0.5, 0.2, 1.0],
+    [0.6, 0.4, 0.9, 1.0],
+];
+
+pub(crate) fn render(world: &PhysicsWorld, backend: &mut impl DebugRenderBackend) {
+    let options = world.debug_render_options;
+    let mut painter = Painter {
+        backend,
+        segments: options.circle_segments.max(3),
+    };
+
+    let islands: HashMap<EntityId, usize> = if options.island_colors {
+        world
+            .islands
+            .islands()
+            .iter()
+            .enumerate()
+            .flat_map(|(index, island)| island.bodies.iter().map(move |&body| (body, index)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    for collider in world.colliders.iter() {
+        let Some(body) = world.bodies.get(collider.rigidbody_id) else {
+            continue;
+        };
+        let transform = collider.world_transform(body.transform());
+        if options.colliders {
+            let color = if collider.is_trigger {
+                TRIGGER_COLOR
+            } else if options.island_colors && !body.is_static() {
+                islands.get(&body.id()).map_or(SLEEPING_COLOR, |index| {
+                    ISLAND_COLORS[index % ISLAND_COLORS.len()]
+                })
+            } else {
+                body_color(&body)
+            };
+            painter.shape(&collider.shape, &transform, options.axis_length, color);
+        }
+        if options.aabbs && !collider.is_unbounded() {
+            let (min, max) = world_bounds(&collider.shape, &transform);
+            painter.cuboid(
+                &Transform::from_position((min + max) * 0.5),
+                (max - min) * 0.5,
+                AABB_COLOR,
+            );
+        }
+    }
+
+    if options.contacts {
+        for manifold in world.collision.manifold_cache.debug_snapshots() {
+            for (start, end) in manifold.normal_segments(options.axis_length) {
+                painter.cross(start, options.axis_length * 0.1, CONTACT_COLOR);
+                painter.backend.draw_line(start, end, CONTACT_COLOR);
+            }
+        }
+    }
+
+    if options.joints {
+        for joint in &world.dynamics.joints {
+            painter.joint(world, joint, options.axis_length);
+        }
+    }
+}
+
+fn body_color(body: &BodyRef) -> DebugColor {
+    if body.is_static() {
+        STATIC_COLOR
+    } else if body.is_kinematic() {
+        KINEMATIC_COLOR
+    } else if !body.is_awake() || !body.is_enabled() {
+        SLEEPING_COLOR
+    } else {
+        DYNAMIC_COLOR
+    }
+}
+
+/// Pose of one side of a joint; the world side sits at the origin.
+fn joint_pose(world: &PhysicsWorld, body: EntityId) -> Option<(Vec3, Quat)> {
+    if body == Joint::WORLD {
+        return Some((Vec3::ZERO, Quat::IDENTITY));
+    }
+    let body = world.bodies.get(body)?;
+    let transform = body.transform();
+    Some((transform.position, transform.rotation))
+}
+
+struct Painter<'a, B> {
+    backend: &'a mut B,
+    segments: usize,
+}
+
+impl<B: DebugRenderBackend> Painter<'_, B> {
+    /// Draws `points`, given in the shape's local space, as a connected strip.
+    fn strip(&mut self, transform: &Transform, points: &[Vec3], color: DebugColor) {
+        for pair in points.windows(2) {
+            self.backend
+                .draw_line(apply(transform, pair[0]), apply(transform, pair[1]), color);
+        }
+    }
+
+    fn line(&mut self, transform: &Transform, start: Vec3, end: Vec3, color: DebugColor) {
+        self.strip(transform, &[start, end], color);
+    }
+
+    /// Arc from angle `from` to `to` around `center`, in the plane spanned by `u` and `v`.
+    fn arc(
+        &mut self,
+        transform: &Transform,
+        center: Vec3,
+        (u, v): (Vec3, Vec3),
+        radius: f32,
+        (from, to): (f32, f32),
+        color: DebugColor,
+    ) {
+        let steps = ((self.segments as f32 * (to - from).abs() / TAU).ceil() as usize).max(1);
+        let points: Vec<Vec3> = (0..=steps)
+            .map(|step| {
+                let angle = from + (to - from) * step as f32 / steps as f32;
+                center + (u * angle.cos() + v * angle.sin()) * radius
+            })
+            .collect();
+        self.strip(transform, &points, color);
+    }
+
+    fn circle(
+        &mut self,
+        transform: &Transform,
+        center: Vec3,
+        plane: (Vec3, Vec3),
+        radius: f32,
+        color: DebugColor,
+    ) {
+        self.arc(transform, center, plane, radius, (0.0, TAU), color);
+    }
+
+    fn cuboid(&mut self, transform: &Transform, half_extents: Vec3, color: DebugColor) {
+        let corner = |index: usize| {
+            Vec3::new(
+                if index & 1 == 0 { -1.0 } else { 1.0 },
+                if index & 2 == 0 { -1.0 } else { 1.0 },
+                if index & 4 == 0 { -1.0 } else { 1.0 },
+            ) * half_extents
+        };
+        for index in 0..8 {
+            for axis in [1, 2, 4] {
+                if index & axis == 0 {
+                    self.line(transform, corner(index), corner(index | axis), color);
+                }
+            }
+        }
+    }
+
+    /// Rings at both ends of a Y-aligned cylinder joined by four side lines.
+    fn cylinder(&mut self, transform: &Transform, radius: f32, height: f32, color: DebugColor) {
+        let top = Vec3::Y * height * 0.5;
+        self.circle(transform, top, (Vec3::X, Vec3::Z), radius, color);
+        self.circle(transform, -top, (Vec3::X, Vec3::Z), radius, color);
+        for side in [Vec3::X, Vec3::Z, -Vec3::X, -Vec3::Z] {
+            self.line(transform, top + side * radius, side * radius - top, color);
+        }
+    }
+
+    fn cross(&mut self, point: Vec3, size: f32, color: DebugColor) {
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            self.backend
+                .draw_line(point - axis * size, point + axis * size, color);
+        }
+    }
+
+    fn axes(&mut self, origin: Vec3, rotation: Quat, length: f32) {
+        for (axis, color) in [Vec3::X, Vec3::Y, Vec3::Z].into_iter().zip(AXIS_COLORS) {
+            self.backend
+                .draw_line(origin, origin + rotation * axis * length, color);
+        }
+    }
+
+    fn shape(
+        &mut self,
+        shape: &ColliderShape,
+        transform: &Transform,
+        plane_size: f32,
+        color: DebugColor,
+    ) {
+        match shape {
+            ColliderShape::Sphere { radius } => {
+                self.circle(transform, Vec3::ZERO, (Vec3::X, Vec3::Y), *radius, color);
+                self.circle(transform, Vec3::ZERO, (Vec3::Y, Vec3::Z), *radius, color);
+                self.circle(transform, Vec3::ZERO, (Vec3::Z, Vec3::X), *radius, color);
+            }
+            ColliderShape::Box { half_extents }
+            | ColliderShape::RoundedBox { half_extents, .. } => {
+                self.cuboid(transform, *half_extents, color);
+            }
+            ColliderShape::Capsule { radius, height } => {
+                self.cylinder(transform, *radius, *height, color);
+                let top = Vec3::Y * height * 0.5;
+                for side in [Vec3::X, Vec3::Z] {
+                    self.arc(transform, top, (side, Vec3::Y), *radius, (0.0, PI), color);
+                    self.arc(transform, -top, (side, Vec3::Y), *radius, (PI, TAU), color);
+                }
+            }
+            ColliderShape::Cylinder { radius, height }
+            | ColliderShape::RoundedCylinder { radius, height, .. } => {
+                self.cylinder(transform, *radius, *height, color);
+            }
+            ColliderShape::Cone { radius, height } => {
+                let apex = Vec3::Y * height * 0.5;
+                self.circle(transform, -apex, (Vec3::X, Vec3::Z), *radius, color);
+                for side in [Vec3::X, Vec3::Z, -Vec3::X, -Vec3::Z] {
+                    self.line(transform, side * radius - apex, apex, color);
+                }
+            }
+            ColliderShape::ConvexHull { vertices } => match hull_triangles(vertices) {
+                Some(triangles) => {
+                    for [a, b, c] in triangles {
+                        // Each edge is shared by two triangles; draw it once.
+                        for (start, end) in [(a, b), (b, c), (c, a)] {
+                            if start < end {
+                                self.line(transform, vertices[start], vertices[end], color);
+                            }
+                        }
+                    }
+                }
+                // Flat hulls have no triangles; outline the points in order instead.
+                None => self.strip(transform, vertices, color),
+            },
+            ColliderShape::Compound { shapes } => {
+                for (offset, child) in shapes {
+                    self.shape(child, &transform.combine(offset), plane_size, color);
+                }
+            }
+            ColliderShape::Mesh { mesh } => {
+                for [a, b, c] in &mesh.indices {
+                    let [a, b, c] = [a, b, c].map(|&index| mesh.vertices[index as usize]);
+                    self.strip(transform, &[a, b, c, a], color);
+                }
+            }
+            ColliderShape::Heightfield { heightfield } => {
+                for row in 0..heightfield.rows {
+                    for col in 0..heightfield.cols {
+                        let vertex = heightfield.vertex(row, col);
+                        if col + 1 < heightfield.cols {
+                            self.line(transform, vertex, heightfield.vertex(row, col + 1), color);
+                        }
+                        if row + 1 < heightfield.rows {
+                            self.line(transform, vertex, heightfield.vertex(row + 1, col), color);
+                        }
+                    }
+                }
+            }
+            ColliderShape::Plane { normal } => {
+                let normal = normal.normalize_or_zero();
+                let u = normal.any_orthonormal_vector();
+                let v = normal.cross(u);
+                let corners =
+                    [u + v, u - v, -u - v, -u + v, u + v].map(|corner| corner * plane_size);
+                self.strip(transform, &corners, color);
+                self.line(transform, u * plane_size, -u * plane_size, color);
+                self.line(transform, v * plane_size, -v * plane_size, color);
+                self.line(transform, Vec3::ZERO, normal * plane_size, color);
+            }
+        }
+    }
+
+    fn joint(&mut self, world: &PhysicsWorld, joint: &Joint, axis_length: f32) {
+        let (body_a, body_b) = joint.bodies();
+        let (Some((position_a, rotation_a)), Some((position_b, rotation_b))) =
+            (joint_pose(world, body_a), joint_pose(world, body_b))
+        else {
+            return;
+        };
+        match joint {
+            Joint::Fixed {
+                local_pivot_a,
+                local_pivot_b,
+                local_frame_a,
+                local_frame_b,
+                ..
+            }
+            | Joint::Prismatic {
+                local_pivot_a,
+                local_pivot_b,
+                local_frame_a,
+                local_frame_b,
+                ..
+            } => {
+                let anchor_a = position_a + rotation_a * *local_pivot_a;
+                let anchor_b = position_b + rotation_b * *local_pivot_b;
+                self.backend.draw_line(position_a, anchor_a, JOINT_COLOR);
+                self.backend.draw_line(anchor_a, anchor_b, JOINT_COLOR);
+                self.backend.draw_line(anchor_b, position_b, JOINT_COLOR);
+                self.axes(anchor_a, rotation_a * *local_frame_a, axis_length);
+                self.axes(anchor_b, rotation_b * *local_frame_b, axis_length);
+                if let Joint::Prismatic { local_axis_a, .. } = joint {
+                    let axis = rotation_a * *local_axis_a * axis_length * 2.0;
+                    self.backend
+                        .draw_line(anchor_a - axis, anchor_a + axis, JOINT_COLOR);
+                }
+            }
+            Joint::Revolute {
+                local_pivot_a,
+                local_pivot_b,
+                local_axis_a,
+                local_axis_b,
+                ..
+            } => {
+                let anchor_a = position_a + rotation_a * *local_pivot_a;
+                let anchor_b = position_b + rotation_b * *local_pivot_b;
+                self.backend.draw_line(position_a, anchor_a, JOINT_COLOR);
+                self.backend.draw_line(anchor_a, anchor_b, JOINT_COLOR);
+                self.backend.draw_line(anchor_b, position_b, JOINT_COLOR);
+                let axis_a = rotation_a * *local_axis_a * axis_length;
+                let axis_b = rotation_b * *local_axis_b * axis_length;
+                self.backend
+                    .draw_line(anchor_a - axis_a, anchor_a + axis_a, AXIS_COLORS[0]);
+                self.backend
+                    .draw_line(anchor_b - axis_b, anchor_b + axis_b, AXIS_COLORS[2]);
+            }
+            Joint::Spring { .. } | Joint::Distance { .. } => {
+                self.backend.draw_line(position_a, position_b, JOINT_COLOR);
+            }
+        }
+    }
+}
+
+fn apply(transform: &Transform, point: Vec3) -> Vec3 {
+    transform.position + transform.rotation * (transform.scale * point)
+}
@@ -1,10 +1,28 @@
-use crate::core::constraints::Joint;
-use crate::dynamics::{forces::ForceRegistry, solver::PGSSolver};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::core::constraints::{Joint, JointBreakLimit, JointBroken};
+use crate::dynamics::{
+    fields::ForceField,
+    forces::ForceRegistry,
+    solver::{Contact, JointImpulse, PGSSolver},
+};
+use crate::utils::allocator::{Arena, EntityId};
 
 pub struct DynamicsManager {
     pub solver: PGSSolver,
     pub joints: Vec<Joint>,
     pub force_registry: ForceRegistry,
+    /// Force field volumes applied to the bodies overlapping them each substep.
+    pub force_fields: Arena<ForceField>,
+    /// Explicit priorities of `joints`, by index; missing entries count as 0.
+    pub joint_priorities: Vec<i32>,
+    /// Break limits of `joints`, by index; missing entries never break.
+    pub joint_break_limits: Vec<Option<JointBreakLimit>>,
+    /// Lock impulses `joints` ended the last step with, by index, to warm-start the next.
+    pub joint_warm_starts: Vec<JointImpulse>,
+    /// Priorities of bodies whose contacts and joints should be solved late.
+    pub body_priorities: HashMap<EntityId, i32>,
 }
 
 impl Default for DynamicsManager {
@@ -19,6 +37,149 @@ impl DynamicsManager {
             solver: PGSSolver::new(),
             joints: Vec::new(),
             force_registry: ForceRegistry::new(),
+            force_fields: Arena::new(),
+            joint_priorities: Vec::new(),
+            joint_break_limits: Vec::new(),
+            joint_warm_starts: Vec::new(),
+            body_priorities: HashMap::new(),
+        }
+    }
+
+    /// Adds a joint and returns its index in `joints`.
+    pub fn add_joint(&mut self, joint: Joint, priority: i32) -> usize {
+        self.joint_priorities.resize(self.joints.len(), 0);
+        self.joint_break_limits.resize(self.joints.len(), None);
+        self.joint_warm_starts
+            .resize(self.joints.len(), JointImpulse::default());
+        self.joints.push(joint);
+        self.joint_priorities.push(priority);
+        self.joint_break_limits.push(None);
+        self.joint_warm_starts.push(JointImpulse::default());
+        self.joints.len() - 1
+    }
+
+    pub fn set_joint_break_limit(&mut self, index: usize, limit: Option<JointBreakLimit>) {
+        if index < self.joints.len() {
+            self.joint_break_limits.resize(self.joints.len(), None);
+            self.joint_break_limits[index] = limit;
+        }
+    }
+
+    /// Removes the joint at `index` with its priority, break limit and warm start.
+    pub fn remove_joint(&mut self, index: usize) -> Option<Joint> {
+        if index >= self.joints.len() {
+            return None;
+        }
+        if index < self.joint_priorities.len() {
+            self.joint_priorities.remove(index);
+        }
+        if index < self.joint_break_limits.len() {
+            self.joint_break_limits.remove(index);
+        }
+        if index < self.joint_warm_starts.len() {
+            self.joint_warm_starts.remove(index);
+        }
+        Some(self.joints.remove(index))
+    }
+
+    pub fn clear_joints(&mut self) {
+        self.joints.clear();
+        self.joint_priorities.clear();
+        self.joint_break_limits.clear();
+        self.joint_warm_starts.clear();
+    }
+
+    pub fn has_breakable_joints(&self) -> bool {
+        self.joint_break_limits.iter().any(Option::is_some)
+    }
+
+    /// Removes joints whose load this step exceeded their break limit.
+    ///
+    /// `impulses` holds each joint's [`JointImpulse`] by index into `joints`.
+    pub fn break_overloaded_joints(
+        &mut self,
+        impulses: &[JointImpulse],
+        dt: f32,
+    ) -> Vec<JointBroken> {
+        let mut broken = Vec::new();
+        for index in (0..self.joints.len()).rev() {
+            let (Some(Some(limit)), Some(impulse)) =
+                (self.joint_break_limits.get(index), impulses.get(index))
+            else {
+                continue;
+            };
+            let force = impulse.linear.length() / dt;
+            let torque = impulse.angular.length() / dt;
+            if !limit.is_exceeded(force, torque) {
+                continue;
+            }
+            if let Some(joint) = self.remove_joint(index) {
+                broken.push(JointBroken {
+                    joint,
+                    force,
+                    torque,
+                });
+            }
+        }
+        broken.reverse();
+        broken
+    }
+
+    /// Sets the solver priority of a body; 0 removes it.
+    pub fn set_body_priority(&mut self, body: EntityId, priority: i32) {
+        if priority == 0 {
+            self.body_priorities.remove(&body);
+        } else {
+            self.body_priorities.insert(body, priority);
+        }
+    }
+
+    fn body_priority(&self, body: EntityId) -> i32 {
+        self.body_priorities.get(&body).copied().unwrap_or(0)
+    }
+
+    /// Priority of a contact: the higher of its two bodies.
+    pub fn contact_priority(&self, contact: &Contact) -> i32 {
+        self.body_priority(contact.body_a)
+            .max(self.body_priority(contact.body_b))
+    }
+
+    /// Priority of the joint at `index`: its own, or the higher of its bodies' if larger.
+    pub fn joint_priority(&self, index: usize) -> i32 {
+        let own = self.joint_priorities.get(index).copied().unwrap_or(0);
+        match self.joints.get(index) {
+            Some(joint) => {
+                let (body_a, body_b) = joint.bodies();
+                own.max(self.body_priority(body_a))
+                    .max(self.body_priority(body_b))
+            }
+            None => own,
+        }
+    }
+
+    /// Stable-sorts contacts so higher priorities come last and receive the final correction.
+    ///
+    /// Contacts of one body pair share a priority, so manifolds stay contiguous.
+    pub fn prioritize_contacts(&self, contacts: &mut [Contact]) {
+        if self.body_priorities.is_empty() {
+            return;
+        }
+        contacts.sort_by_key(|contact| self.contact_priority(contact));
+    }
+
+    /// Joints in solve order, higher priorities last, with each one's index in `joints`.
+    ///
+    /// The joints are borrowed when no priority is set.
+    pub fn prioritized_joints(&self) -> (Cow<'_, [Joint]>, Vec<usize>) {
+        let mut order: Vec<usize> = (0..self.joints.len()).collect();
+        if self.body_priorities.is_empty() && self.joint_priorities.iter().all(|&p| p == 0) {
+            return (Cow::Borrowed(&self.joints), order);
         }
+        order.sort_by_key(|&index| self.joint_priority(index));
+        let joints = order
+            .iter()
+            .map(|&index| self.joints[index].clone())
+            .collect();
+        (Cow::Owned(joints), order)
     }
 }
@@ -4,41 +4,85 @@ use rayon::prelude::*;
 
 use crate::{
     collision::{
-        ccd::CCDDetector,
-        contact::{ContactManifold, ManifoldDebugInfo},
-        queries::{Raycast, RaycastHit, RaycastQuery},
+        broadphase::{BroadPhase, BroadPhaseBackend, SpeculativeExpansion},
+        ccd::{CCDDetector, CcdQuality},
+        contact::{ContactEvent, ManifoldDebugInfo},
+        queries::{closest_points, Ray, Raycast, RaycastHit, RaycastQuery},
+        query_pipeline::QueryPipeline,
+        recorder::ManifoldRecorder,
+        trigger::{TriggerEvent, TriggerOverlap},
+    },
+    config::{
+        BroadPhaseConfig, ConfigError, SolverConfig, WorldConfig, DEFAULT_GRAVITY,
+        DEFAULT_TIME_STEP,
     },
-    config::{DEFAULT_GRAVITY, DEFAULT_TIME_STEP},
     core::{
         articulations::Multibody,
-        collider::{Collider, CollisionFilter},
-        constraints::Joint,
+        collider::Collider,
+        constraints::{Joint, JointBreakLimit, JointBroken},
+        mesh::Aabb,
         rigidbody::RigidBody,
         soa::{BodiesSoA, BodyMut, BodyRef},
+        types::{MassProperties, MassValidation, MaterialId, MaterialPairOverride, Transform},
     },
     dynamics::{
-        integrator::Integrator,
+        aba::ABASolver,
+        cloth::Cloth,
+        fields::ForceField,
+        fluid::{Fluid, FluidEmitter},
+        forces::ExplosionFalloff,
+        integrator::{IntegrationScheme, Integrator},
         island::IslandManager,
+        particles::ParticleSystem,
         pci::PredictiveCorrectiveIntegrator,
-        solver::{Contact, SolverStepMetrics},
+        soft_body::SoftBody,
+        solver::{BlockSolverMode, Contact, JointImpulse, PositionCorrection, SolverStepMetrics},
+    },
+    gpu::{
+        ComputeBackend, GpuParticleState, GpuRayHit, GpuStepResults, GpuWorldState, NoopBackend,
     },
-    gpu::{ComputeBackend, GpuWorldState, NoopBackend},
     utils::{
         allocator::{Arena, EntityId},
         logging::ScopedTimer,
-        profiling::PhysicsProfiler,
+        profiling::{profile_scope, PhysicsProfiler, Stopwatch, WorldStepStats},
     },
 };
-use glam::Vec3;
+use glam::{Mat3, Quat, Vec3};
 use log::debug;
 // use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+pub mod async_step;
 pub mod collision_manager;
+pub mod debug_render;
 pub mod dynamics_manager;
+pub mod quarantine;
+mod regions;
+pub mod state;
+
+/// Everything [`PhysicsWorld::remove_rigidbody`] took out of the world with a body.
+#[derive(Debug, Clone)]
+pub struct RemovedBody {
+    pub body: RigidBody,
+    /// The body's colliders, with offsets relative to its origin as created.
+    pub colliders: Vec<Collider>,
+    /// Joints attached to the body, in the order they were added.
+    pub joints: Vec<Joint>,
+}
+
+/// Callback run once per fixed step with the bodies and the fixed time step; see
+/// [`PhysicsWorld::add_pre_step_hook`].
+pub type StepHook = dyn FnMut(&mut BodiesSoA, f32) + Send + Sync;
 
-use collision_manager::CollisionManager;
+use async_step::{StepHandle, WorldSnapshot};
+use collision_manager::{CollisionManager, PairOutcome};
+use debug_render::{DebugRenderBackend, DebugRenderOptions};
 use dynamics_manager::DynamicsManager;
+use quarantine::{NonFiniteEvent, NonFiniteGuard, NonFinitePolicy, SimulationStage};
+use regions::SimulationRegions;
+use state::{StateHistory, WorldState, WORLD_STATE_VERSION};
 
 /// Central simulation container orchestrating all subsystems.
 pub struct PhysicsWorld {
@@ -48,12 +92,22 @@ pub struct PhysicsWorld {
     pub dynamics: DynamicsManager,
     pub collision: CollisionManager,
     pub gravity: Vec3,
+    /// Point masses stepped after the rigid bodies, colliding with `colliders`.
+    pub particles: ParticleSystem,
+    /// SPH fluid stepped after the particles; see [`Self::add_fluid_emitter`].
+    pub fluid: Fluid,
+    /// Cloths stepped after the fluid; see [`Self::add_cloth`].
+    pub cloths: Arena<Cloth>,
+    /// Tetrahedral soft bodies stepped after the cloths; see [`Self::add_soft_body`].
+    pub soft_bodies: Arena<SoftBody>,
     pub time_accumulated: f32,
     pub time_step: f32,
     islands: IslandManager,
     parallel_enabled: bool,
     gpu_state: GpuWorldState,
     gpu_backend: Box<dyn ComputeBackend>,
+    gpu_results: GpuStepResults,
+    gpu_particles: GpuParticleState,
     frame_index: u32,
     manifold_debug_logging: bool,
     last_solver_metrics: SolverStepMetrics,
@@ -61,7 +115,37 @@ pub struct PhysicsWorld {
     pci: PredictiveCorrectiveIntegrator,
     pci_enabled: bool,
     pub profiler: PhysicsProfiler,
+    step_stats: WorldStepStats,
     pub articulated_bodies: Arena<Multibody>,
+    snapshot_buffer: WorldSnapshot,
+    /// Empty world left in place while `step_async` runs, reused from step to step.
+    async_spare: Option<Box<PhysicsWorld>>,
+    contact_events: Vec<ContactEvent>,
+    trigger_events: Vec<TriggerEvent>,
+    manifold_recorder: Option<ManifoldRecorder>,
+    mass_validation: MassValidation,
+    non_finite_guard: NonFiniteGuard,
+    non_finite_events: Vec<NonFiniteEvent>,
+    /// Index into `dynamics.joints` of each joint the islands were built from.
+    joint_order: Vec<usize>,
+    /// Impulse each joint applied this substep, by index into `dynamics.joints`.
+    joint_loads: Vec<JointImpulse>,
+    joint_broken_events: Vec<JointBroken>,
+    /// Collision proxies of multibody links, mapped to their multibody and link index.
+    link_bodies: HashMap<EntityId, (EntityId, usize)>,
+    /// Poses kinematic bodies should reach by the end of the next fixed step.
+    kinematic_targets: HashMap<EntityId, (Vec3, Quat)>,
+    /// How far each recentred body's origin moved from where it was created, body-local.
+    mass_center_shifts: HashMap<EntityId, Vec3>,
+    pre_step_hooks: Vec<Box<StepHook>>,
+    post_step_hooks: Vec<Box<StepHook>>,
+    /// States after the last few fixed steps, for [`Self::rollback`].
+    history: StateHistory,
+    query_pipeline: QueryPipeline,
+    regions: SimulationRegions,
+    debug_render_options: DebugRenderOptions,
+    /// File [`Self::reload`] reads; set by [`Self::from_config`].
+    config_path: Option<PathBuf>,
 }
 
 impl PhysicsWorld {
@@ -72,6 +156,17 @@ impl PhysicsWorld {
     pub fn builder() -> PhysicsWorldBuilder {
         PhysicsWorldBuilder::new()
     }
+
+    /// Builds a world tuned by the TOML or RON file at `path`; see [`WorldConfig::load`].
+    /// The path is remembered for [`Self::reload`].
+    pub fn from_config(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let config = WorldConfig::load(path)?;
+        let mut world = Self::new(config.time_step);
+        world.apply_config(config)?;
+        world.config_path = Some(path.to_path_buf());
+        Ok(world)
+    }
 }
 
 pub struct PhysicsWorldBuilder {
@@ -79,6 +174,8 @@ pub struct PhysicsWorldBuilder {
     gravity: Vec3,
     parallel_enabled: bool,
     gpu_backend: Option<Box<dyn ComputeBackend>>,
+    broadphase_backend: BroadPhaseBackend,
+    solver_config: SolverConfig,
 }
 
 impl PhysicsWorldBuilder {
@@ -88,6 +185,8 @@ impl PhysicsWorldBuilder {
             gravity: Vec3::from_slice(&DEFAULT_GRAVITY),
             parallel_enabled: false,
             gpu_backend: None,
+            broadphase_backend: BroadPhaseBackend::default(),
+            solver_config: SolverConfig::default(),
         }
     }
 
@@ -111,15 +210,31 @@ impl PhysicsWorldBuilder {
         self
     }
 
+    pub fn broadphase_backend(mut self, backend: BroadPhaseBackend) -> Self {
+        self.broadphase_backend = backend;
+        self
+    }
+
+    pub fn solver_config(mut self, config: SolverConfig) -> Self {
+        self.solver_config = config;
+        self
+    }
+
     pub fn build(self) -> PhysicsWorld {
         let ts = self.time_step;
-        PhysicsWorld {
+        let mut collision = CollisionManager::new();
+        collision.broadphase.set_backend(self.broadphase_backend);
+        let mut world = PhysicsWorld {
             bodies: BodiesSoA::new(),
             colliders: Arena::new(),
             integrator: Integrator::new(ts, 2),
             dynamics: DynamicsManager::new(),
-            collision: CollisionManager::new(),
+            collision,
             gravity: self.gravity,
+            particles: ParticleSystem::new(),
+            fluid: Fluid::default(),
+            cloths: Arena::new(),
+            soft_bodies: Arena::new(),
             time_accumulated: 0.0,
             time_step: ts,
             islands: IslandManager::new(),
@@ -128,6 +243,8 @@ impl PhysicsWorldBuilder {
             gpu_backend: self
                 .gpu_backend
                 .unwrap_or_else(|| Box::new(NoopBackend::new())),
+            gpu_results: GpuStepResults::default(),
+            gpu_particles: GpuParticleState::new(),
             frame_index: 0,
             manifold_debug_logging: false,
             last_solver_metrics: SolverStepMetrics::default(),
@@ -135,8 +252,32 @@ impl PhysicsWorldBuilder {
             pci: PredictiveCorrectiveIntegrator::default(),
             pci_enabled: false,
             profiler: PhysicsProfiler::default(),
+            step_stats: WorldStepStats::default(),
             articulated_bodies: Arena::new(),
-        }
+            snapshot_buffer: WorldSnapshot::default(),
+            async_spare: None,
+            contact_events: Vec::new(),
+            trigger_events: Vec::new(),
+            manifold_recorder: None,
+            mass_validation: MassValidation::default(),
+            non_finite_guard: NonFiniteGuard::default(),
+            non_finite_events: Vec::new(),
+            joint_order: Vec::new(),
+            joint_loads: Vec::new(),
+            joint_broken_events: Vec::new(),
+            link_bodies: HashMap::new(),
+            kinematic_targets: HashMap::new(),
+            mass_center_shifts: HashMap::new(),
+            pre_step_hooks: Vec::new(),
+            post_step_hooks: Vec::new(),
+            history: StateHistory::default(),
+            query_pipeline: QueryPipeline::new(),
+            regions: SimulationRegions::default(),
+            debug_render_options: DebugRenderOptions::default(),
+            config_path: None,
+        };
+        world.set_solver_config(self.solver_config);
+        world
     }
 }
 
@@ -161,12 +302,34 @@ impl PhysicsWorld {
         B: ComputeBackend + 'static,
     {
         self.gpu_backend = Box::new(backend);
+        // The new backend starts without the particles; they are uploaded again next step.
+        self.particles.set_gpu_resident(false);
     }
 
     pub fn gpu_backend_name(&self) -> &str {
         self.gpu_backend.name()
     }
 
+    /// Candidate pairs from the GPU backend's broad phase in the last step, waiting for the
+    /// readback if the GPU is still busy. `None` if the backend has no broad phase.
+    pub fn gpu_broadphase_pairs(&mut self) -> Option<Vec<(EntityId, EntityId)>> {
+        self.gpu_results.pair_ids()
+    }
+
+    /// Linear velocities from the GPU backend's solver in the last step, waiting for the
+    /// readback if the GPU is still busy. `None` if the backend has no solver.
+    pub fn gpu_solver_velocities(&mut self) -> Option<Vec<(EntityId, Vec3)>> {
+        self.gpu_results.velocities()
+    }
+
+    pub fn set_broadphase_backend(&mut self, backend: BroadPhaseBackend) {
+        self.collision.broadphase.set_backend(backend);
+    }
+
+    pub fn broadphase_backend(&self) -> BroadPhaseBackend {
+        self.collision.broadphase.backend()
+    }
+
     pub fn ccd(&self) -> &CCDDetector {
         &self.collision.ccd
     }
@@ -175,6 +338,83 @@ impl PhysicsWorld {
         &mut self.collision.ccd
     }
 
+    /// Iteration counts, position correction and warm starting of the solver, and whether
+    /// CCD runs.
+    pub fn solver_config(&self) -> SolverConfig {
+        let solver = &self.dynamics.solver;
+        SolverConfig {
+            velocity_iterations: solver.velocity_iterations,
+            position_iterations: solver.position_iterations,
+            bias: solver.bias_factor,
+            slop: solver.slop,
+            warm_starting: solver.contact_warm_starting && solver.joint_warm_starting,
+            ccd_enabled: self.collision.ccd.enabled,
+            position_correction: solver.position_correction,
+            block_solver: solver.block_solver,
+        }
+    }
+
+    /// Applies `config` from the next step on.
+    pub fn set_solver_config(&mut self, config: SolverConfig) {
+        let solver = &mut self.dynamics.solver;
+        solver.velocity_iterations = config.velocity_iterations;
+        solver.position_iterations = config.position_iterations;
+        solver.bias_factor = config.bias;
+        solver.slop = config.slop;
+        solver.contact_warm_starting = config.warm_starting;
+        solver.joint_warm_starting = config.warm_starting;
+        solver.position_correction = config.position_correction;
+        solver.block_solver = config.block_solver;
+        self.collision.ccd.set_enabled(config.ccd_enabled);
+    }
+
+    /// The world's current tuning, in the shape [`Self::apply_config`] takes.
+    pub fn config(&self) -> WorldConfig {
+        let broadphase = &self.collision.broadphase;
+        WorldConfig {
+            time_step: self.time_step,
+            gravity: self.gravity,
+            parallel: self.parallel_enabled,
+            solver: self.solver_config(),
+            broadphase: BroadPhaseConfig {
+                backend: broadphase.backend(),
+                cell_size: broadphase.grid_cell_size(),
+                sap_margin: broadphase.sweep_and_prune().margin,
+                fat_margin: broadphase.pair_manager().fat_margin,
+            },
+        }
+    }
+
+    /// Validates `config` and applies it from the next step on. An invalid config leaves the
+    /// world untouched.
+    pub fn apply_config(&mut self, config: WorldConfig) -> Result<(), ConfigError> {
+        config.validate()?;
+        self.time_step = config.time_step;
+        self.integrator.dt = config.time_step / self.integrator.substeps as f32;
+        self.gravity = config.gravity;
+        self.set_parallel_enabled(config.parallel);
+        self.set_solver_config(config.solver);
+        let broadphase = &mut self.collision.broadphase;
+        broadphase.set_backend(config.broadphase.backend);
+        broadphase.set_grid_cell_size(config.broadphase.cell_size);
+        broadphase.sweep_and_prune_mut().margin = config.broadphase.sap_margin;
+        broadphase.pair_manager_mut().fat_margin = config.broadphase.fat_margin;
+        Ok(())
+    }
+
+    /// Re-reads the file the world was loaded from and applies it, for live tuning. If the
+    /// file can't be read or is invalid the world keeps its current settings.
+    pub fn reload(&mut self) -> Result<(), ConfigError> {
+        let path = self.config_path.as_ref().ok_or(ConfigError::NoSource)?;
+        let config = WorldConfig::load(path)?;
+        self.apply_config(config)
+    }
+
+    /// File the world was loaded from with [`Self::from_config`].
+    pub fn config_path(&self) -> Option<&Path> {
+        self.config_path.as_deref()
+    }
+
     pub fn set_ccd_enabled(&mut self, enabled: bool) {
         self.collision.ccd.set_enabled(enabled);
     }
@@ -183,10 +423,34 @@ impl PhysicsWorld {
         self.collision.ccd.set_ccd_threshold(threshold);
     }
 
+    /// Overrides the mixed friction/restitution for bodies whose materials carry ids `a` and
+    /// `b`, in either order.
+    pub fn set_material_pair_override(
+        &mut self,
+        a: MaterialId,
+        b: MaterialId,
+        properties: MaterialPairOverride,
+    ) {
+        self.collision.material_pairs.insert(a, b, properties);
+    }
+
+    pub fn remove_material_pair_override(&mut self, a: MaterialId, b: MaterialId) {
+        self.collision.material_pairs.remove(a, b);
+    }
+
     pub fn set_ccd_angular_padding(&mut self, padding: f32) {
         self.collision.ccd.set_angular_padding(padding);
     }
 
+    pub fn set_ccd_quality(&mut self, quality: CcdQuality) {
+        self.collision.ccd.set_quality(quality);
+    }
+
+    /// Caps post-solve speed of CCD-hit bodies at `clamp` times their pre-impact speed.
+    pub fn set_ccd_velocity_clamp(&mut self, clamp: Option<f32>) {
+        self.collision.ccd.set_velocity_clamp(clamp);
+    }
+
     pub fn raycast(&self, query: &RaycastQuery) -> Vec<RaycastHit> {
         Raycast::cast(query, &self.colliders, &self.bodies)
     }
@@ -198,6 +462,207 @@ impl PhysicsWorld {
         Raycast::cast_with_filter(query, &self.colliders, &self.bodies, filter)
     }
 
+    /// All hits along the ray, nearest first.
+    pub fn raycast_all(&self, query: &RaycastQuery) -> Vec<RaycastHit> {
+        Raycast::cast_all(query, &self.colliders, &self.bodies)
+    }
+
+    /// All hits along the ray, nearest first, skipping bodies rejected by `filter`.
+    pub fn raycast_all_with_filter<F>(&self, query: &RaycastQuery, filter: F) -> Vec<RaycastHit>
+    where
+        F: Fn(EntityId) -> bool,
+    {
+        Raycast::cast_all_with_filter(query, &self.colliders, &self.bodies, filter)
+    }
+
+    /// Collider hierarchy as of the last step, for running many queries against the same
+    /// poses. Call [`Self::update_query_pipeline`] after moving or adding colliders between
+    /// steps.
+    pub fn query_pipeline(&self) -> &QueryPipeline {
+        &self.query_pipeline
+    }
+
+    pub fn update_query_pipeline(&mut self) {
+        self.query_pipeline.update(&self.colliders, &self.bodies);
+    }
+
+    /// Marks `bounds` as a region to simulate, for streaming open worlds.
+    ///
+    /// While any region is active, bodies further than [`Self::region_margin`] outside all
+    /// of them are frozen: they keep their pose and velocity but are not integrated, and
+    /// their colliders leave the broad phase. They thaw when a region comes back in reach.
+    /// Regions apply at the start of each step, or on [`Self::update_regions`].
+    pub fn activate_region(&mut self, bounds: Aabb) -> EntityId {
+        self.regions.activate(bounds)
+    }
+
+    /// Stops simulating a region. Once the last region is gone, every body thaws.
+    pub fn deactivate_region(&mut self, id: EntityId) -> Option<Aabb> {
+        self.regions.deactivate(id)
+    }
+
+    pub fn active_regions(&self) -> impl Iterator<Item = &Aabb> {
+        self.regions.regions()
+    }
+
+    /// How far outside the active regions a body must be before it freezes.
+    pub fn set_region_margin(&mut self, margin: f32) {
+        self.regions.set_margin(margin);
+    }
+
+    pub fn region_margin(&self) -> f32 {
+        self.regions.margin()
+    }
+
+    /// Whether `body` is frozen for lying outside every active region.
+    pub fn is_frozen(&self, body: EntityId) -> bool {
+        self.regions.is_frozen(body)
+    }
+
+    /// Freezes and thaws bodies for the current regions without waiting for the next step.
+    pub fn update_regions(&mut self) {
+        self.regions.update(
+            &mut self.bodies,
+            &self.colliders,
+            &mut self.collision.broadphase,
+        );
+    }
+
+    /// Closest hit per ray for a batch sharing `query`'s filters. See [`RaycastQuery::cast_batch`].
+    pub fn raycast_batch(&self, query: &RaycastQuery, rays: &[Ray]) -> Vec<Option<RaycastHit>> {
+        query.cast_batch(rays, &self.colliders, &self.bodies)
+    }
+
+    /// Like [`Self::raycast_batch`], but cast by the GPU backend, for batches of thousands
+    /// of rays. Rays the backend leaves unresolved, because a hull, mesh or heightfield may
+    /// be in the way, are cast again on the CPU. `None` if the backend has no raycast kernel.
+    pub fn gpu_raycast_batch(
+        &self,
+        query: &RaycastQuery,
+        rays: &[Ray],
+    ) -> Option<Vec<Option<RaycastHit>>> {
+        let mut state = GpuWorldState::new();
+        state.sync_bodies(
+            &self.bodies,
+            &self.colliders,
+            self.time_step,
+            SpeculativeExpansion::default(),
+        );
+        let hits = self
+            .gpu_backend
+            .dispatch_raycasts(&state, query, rays)?
+            .into_inner();
+
+        let mut results = Vec::with_capacity(rays.len());
+        let mut unresolved = Vec::new();
+        for (index, (ray, hit)) in rays.iter().zip(hits).enumerate() {
+            results.push(match hit {
+                Some(GpuRayHit::Hit {
+                    shape,
+                    distance,
+                    normal,
+                }) => Some(RaycastHit {
+                    body_id: state.ids[state.shape_bodies[shape as usize] as usize],
+                    collider_id: state.shape_colliders[shape as usize],
+                    point: ray.origin + ray.direction.normalize_or_zero() * distance,
+                    normal,
+                    distance,
+                    material_index: None,
+                }),
+                Some(GpuRayHit::Unresolved) => {
+                    unresolved.push(index);
+                    None
+                }
+                None => None,
+            });
+        }
+        if !unresolved.is_empty() {
+            let retry: Vec<Ray> = unresolved.iter().map(|&index| rays[index]).collect();
+            let hits = query.cast_batch(&retry, &self.colliders, &self.bodies);
+            for (index, hit) in unresolved.into_iter().zip(hits) {
+                results[index] = hit;
+            }
+        }
+        Some(results)
+    }
+
+    /// Pushes every dynamic body within `radius` of `center` away from it and returns the
+    /// bodies hit.
+    ///
+    /// Each body receives `impulse`, scaled by `falloff`, at the point of its nearest
+    /// collider closest to the centre, so off-centre hits also spin it. A body whose
+    /// collider contains the centre is pushed from its collider's origin instead.
+    pub fn apply_explosion(
+        &mut self,
+        center: Vec3,
+        radius: f32,
+        impulse: f32,
+        falloff: ExplosionFalloff,
+    ) -> Vec<EntityId> {
+        let candidates =
+            self.collision
+                .broadphase
+                .query_sphere(center, radius, &self.colliders, &self.bodies);
+        let origin = Collider::builder()
+            .sphere(0.0)
+            .offset(Transform::from_position(center))
+            .build();
+        let origin_body = RigidBody::new(EntityId::default());
+
+        // Nearest application point per body.
+        let mut hits: Vec<(EntityId, Vec3, f32)> = Vec::new();
+        for collider_id in candidates {
+            let Some(collider) = self.colliders.get(collider_id) else {
+                continue;
+            };
+            let Some(body) = self.bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            if body.is_static() || body.is_kinematic() || !body.is_enabled() {
+                continue;
+            }
+            let point = match closest_points(collider, &body.to_rigid_body(), &origin, &origin_body)
+            {
+                Some(closest) if closest.distance > 0.0 => closest.point_a,
+                _ => collider.world_transform(body.transform()).position,
+            };
+            let distance = point.distance(center);
+            if distance > radius {
+                continue;
+            }
+            match hits.iter_mut().find(|(id, ..)| *id == body.id()) {
+                Some(hit) if distance < hit.2 => *hit = (body.id(), point, distance),
+                Some(_) => {}
+                None => hits.push((body.id(), point, distance)),
+            }
+        }
+
+        for &(id, point, distance) in &hits {
+            let Some(mut body) = self.bodies.get_mut(id) else {
+                continue;
+            };
+            let direction = (point - center)
+                .try_normalize()
+                .or_else(|| (body.transform.position - center).try_normalize())
+                .unwrap_or(Vec3::Y);
+            body.apply_impulse(direction * impulse * falloff.scale(distance, radius), point);
+        }
+        hits.into_iter().map(|(id, ..)| id).collect()
+    }
+
+    /// Installs a callback consulted for every broadphase pair after layer filtering;
+    /// returning `false` keeps the pair out of narrowphase, CCD and trigger detection.
+    pub fn set_pair_filter<F>(&mut self, filter: Option<F>)
+    where
+        F: Fn(&Collider, &Collider) -> bool + Send + Sync + 'static,
+    {
+        self.collision.broadphase.set_pair_filter(filter);
+    }
+
+    /// Installs a callback receiving manifold snapshots as contacts are generated and solved.
+    /// See [`ManifoldCache::set_debug_hook`](crate::collision::contact::ManifoldCache::set_debug_hook)
+    /// for when it fires; pair it with [`PhysicsWorld::set_manifold_debug_filter`] to keep
+    /// the cost down in large scenes.
     pub fn set_manifold_debug_hook<F>(&mut self, hook: Option<F>)
     where
         F: Fn(&ManifoldDebugInfo) + Send + Sync + 'static,
@@ -205,10 +670,36 @@ impl PhysicsWorld {
         self.collision.manifold_cache.set_debug_hook(hook);
     }
 
+    /// Restricts the manifold debug hook to body pairs accepted by `filter`.
+    pub fn set_manifold_debug_filter<F>(&mut self, filter: Option<F>)
+    where
+        F: Fn(EntityId, EntityId) -> bool + Send + Sync + 'static,
+    {
+        self.collision.manifold_cache.set_debug_filter(filter);
+    }
+
+    /// Snapshots of every cached manifold, for debug drawing or inspection on demand.
     pub fn manifold_debug_snapshots(&self) -> Vec<ManifoldDebugInfo> {
         self.collision.manifold_cache.debug_snapshots()
     }
 
+    /// Attaches a recorder that captures this frame's manifolds at the end of every step,
+    /// or detaches it with `None`. Returns the previous recorder.
+    pub fn set_manifold_recorder(
+        &mut self,
+        recorder: Option<ManifoldRecorder>,
+    ) -> Option<ManifoldRecorder> {
+        std::mem::replace(&mut self.manifold_recorder, recorder)
+    }
+
+    pub fn manifold_recorder(&self) -> Option<&ManifoldRecorder> {
+        self.manifold_recorder.as_ref()
+    }
+
+    pub fn manifold_recorder_mut(&mut self) -> Option<&mut ManifoldRecorder> {
+        self.manifold_recorder.as_mut()
+    }
+
     pub fn set_manifold_logging_enabled(&mut self, enabled: bool) {
         self.manifold_debug_logging = enabled;
     }
@@ -217,10 +708,66 @@ impl PhysicsWorld {
         &self.last_solver_metrics
     }
 
+    /// Emits the world as debug lines: collider wireframes, bounds, contacts and joints, as
+    /// chosen with [`Self::set_debug_render_options`].
+    pub fn debug_render(&self, backend: &mut impl DebugRenderBackend) {
+        debug_render::render(self, backend);
+    }
+
+    pub fn set_debug_render_options(&mut self, options: DebugRenderOptions) {
+        self.debug_render_options = options;
+    }
+
+    pub fn debug_render_options(&self) -> &DebugRenderOptions {
+        &self.debug_render_options
+    }
+
+    /// Per-stage timings and counts of the last [`Self::step`] call.
+    pub fn step_stats(&self) -> &WorldStepStats {
+        &self.step_stats
+    }
+
     pub fn set_solver_metrics_logging(&mut self, enabled: bool) {
         self.solver_metrics_logging = enabled;
     }
 
+    /// Splits awake islands above `max_bodies` into spatial partitions for the parallel
+    /// solver; see [`Island::split`](crate::dynamics::island::Island::split) for the accuracy
+    /// cost. Oversized islands are counted in [`SolverStepMetrics::oversized_islands`] either
+    /// way.
+    pub fn set_max_island_size(&mut self, max_bodies: Option<usize>) {
+        self.islands.set_max_island_size(max_bodies);
+    }
+
+    /// Chooses which contact manifolds the solver treats as one block (default: up to four
+    /// points).
+    pub fn set_block_solver_mode(&mut self, mode: BlockSolverMode) {
+        self.dynamics.solver.block_solver = mode;
+    }
+
+    /// Solves contacts four at a time on SIMD lanes (default: off). Pays off when contacts
+    /// stay active across iterations, as in stacks; manifolds are then solved point by point.
+    pub fn set_simd_contacts(&mut self, enabled: bool) {
+        self.dynamics.solver.simd_contacts = enabled;
+    }
+
+    /// Chooses how contacts push overlapping bodies apart (default: Baumgarte).
+    pub fn set_position_correction(&mut self, correction: PositionCorrection) {
+        self.dynamics.solver.position_correction = correction;
+    }
+
+    /// Chooses how bodies are moved each substep (default: symplectic Euler).
+    ///
+    /// [`IntegrationScheme::PredictiveCorrective`] also runs the predictive contact pass,
+    /// as `set_pci_enabled(true)` does.
+    pub fn set_integration_scheme(&mut self, scheme: IntegrationScheme) {
+        self.integrator.set_scheme(scheme);
+    }
+
+    pub fn integration_scheme(&self) -> IntegrationScheme {
+        self.integrator.scheme()
+    }
+
     pub fn set_pci_enabled(&mut self, enabled: bool) {
         self.pci_enabled = enabled;
     }
@@ -230,30 +777,341 @@ impl PhysicsWorld {
     }
 
     pub fn add_joint(&mut self, joint: Joint) {
-        self.dynamics.joints.push(joint);
+        self.dynamics.add_joint(joint, 0);
+    }
+
+    /// Adds a joint that is removed once its constraint force or torque exceeds `limit`.
+    ///
+    /// Each break is reported through [`Self::joint_broken_events`].
+    pub fn add_breakable_joint(&mut self, joint: Joint, limit: JointBreakLimit) {
+        let index = self.dynamics.add_joint(joint, 0);
+        self.dynamics.set_joint_break_limit(index, Some(limit));
+    }
+
+    /// Adds a joint solved after lower-priority constraints in its island.
+    ///
+    /// Constraints solved last in each iteration end up most accurate, so give critical
+    /// joints (a player character's) a positive priority.
+    pub fn add_joint_with_priority(&mut self, joint: Joint, priority: i32) {
+        self.dynamics.add_joint(joint, priority);
+    }
+
+    /// Sets the solver priority of a body's contacts and joints (default 0).
+    ///
+    /// A contact or joint takes the higher priority of its bodies and is solved after
+    /// lower-priority ones in its island.
+    pub fn set_solver_priority(&mut self, body: EntityId, priority: i32) {
+        self.dynamics.set_body_priority(body, priority);
+    }
+
+    /// Adds a multibody, snapping its links' collision bodies to the links.
+    ///
+    /// Add the collision bodies first; they become kinematic proxies that follow the links.
+    pub fn add_multibody(&mut self, mut mb: Multibody) -> EntityId {
+        mb.update_kinematics();
+        let id = self.articulated_bodies.insert(mb);
+        self.sync_link_bodies(Some(id));
+        id
     }
 
-    pub fn add_multibody(&mut self, mb: Multibody) -> EntityId {
-        self.articulated_bodies.insert(mb)
+    /// Registers a force field; bodies overlapping its volume feel it every substep.
+    pub fn add_force_field(&mut self, field: ForceField) -> EntityId {
+        self.dynamics.force_fields.insert(field)
+    }
+
+    pub fn remove_force_field(&mut self, id: EntityId) -> Option<ForceField> {
+        self.dynamics.force_fields.remove(id)
+    }
+
+    pub fn force_field_mut(&mut self, id: EntityId) -> Option<&mut ForceField> {
+        self.dynamics.force_fields.get_mut(id)
+    }
+
+    /// Adds a nozzle that pours particles into [`Self::fluid`].
+    pub fn add_fluid_emitter(&mut self, emitter: FluidEmitter) -> EntityId {
+        self.fluid.emitters.insert(emitter)
+    }
+
+    pub fn remove_fluid_emitter(&mut self, id: EntityId) -> Option<FluidEmitter> {
+        self.fluid.emitters.remove(id)
+    }
+
+    pub fn fluid_emitter_mut(&mut self, id: EntityId) -> Option<&mut FluidEmitter> {
+        self.fluid.emitters.get_mut(id)
+    }
+
+    /// Adds a cloth that collides with the world's colliders and hangs from its bodies.
+    pub fn add_cloth(&mut self, cloth: Cloth) -> EntityId {
+        self.cloths.insert(cloth)
+    }
+
+    pub fn remove_cloth(&mut self, id: EntityId) -> Option<Cloth> {
+        self.cloths.remove(id)
+    }
+
+    pub fn cloth_mut(&mut self, id: EntityId) -> Option<&mut Cloth> {
+        self.cloths.get_mut(id)
+    }
+
+    /// Adds a soft body that collides with and pushes on the world's bodies.
+    pub fn add_soft_body(&mut self, body: SoftBody) -> EntityId {
+        self.soft_bodies.insert(body)
+    }
+
+    pub fn remove_soft_body(&mut self, id: EntityId) -> Option<SoftBody> {
+        self.soft_bodies.remove(id)
+    }
+
+    pub fn soft_body_mut(&mut self, id: EntityId) -> Option<&mut SoftBody> {
+        self.soft_bodies.get_mut(id)
     }
 
     pub fn clear_joints(&mut self) {
-        self.dynamics.joints.clear();
+        self.dynamics.clear_joints();
     }
 
+    /// Inserts a body, checking dynamic bodies' mass properties per [`MassValidation`].
     pub fn add_rigidbody(&mut self, mut body: RigidBody) -> EntityId {
+        let issues = if body.is_static || body.is_kinematic {
+            Vec::new()
+        } else {
+            match self.mass_validation {
+                MassValidation::Off => Vec::new(),
+                MassValidation::Warn | MassValidation::Correct => body.mass_properties.validate(),
+            }
+        };
+        let corrected = !issues.is_empty() && self.mass_validation == MassValidation::Correct;
+        if corrected {
+            body.mass_properties = body.mass_properties.conditioned();
+        }
         body.recompute_inverses();
-        self.bodies.insert(body)
+        let id = self.bodies.insert(body);
+        for issue in &issues {
+            if corrected {
+                log::warn!("Body {id:?}: {issue}; mass properties corrected");
+            } else {
+                log::warn!("Body {id:?}: {issue}");
+            }
+        }
+        id
     }
 
-    pub fn add_collider(&mut self, collider: Collider) -> EntityId {
+    /// Inserts many bodies at once, like [`Self::add_rigidbody`] on each, reserving storage
+    /// up front. Returns their ids in input order.
+    pub fn add_bodies(&mut self, bodies: impl IntoIterator<Item = RigidBody>) -> Vec<EntityId> {
+        let bodies = bodies.into_iter();
+        self.bodies.reserve(bodies.size_hint().0);
+        bodies.map(|body| self.add_rigidbody(body)).collect()
+    }
+
+    /// Sets how [`Self::add_rigidbody`] treats bad mass properties (default: correct them).
+    pub fn set_mass_validation(&mut self, validation: MassValidation) {
+        self.mass_validation = validation;
+    }
+
+    /// Inserts a collider; one with a density also updates its body's mass properties.
+    ///
+    /// The offset is relative to the body's origin as created, even if
+    /// [`Self::update_mass_properties`] has since moved it.
+    pub fn add_collider(&mut self, mut collider: Collider) -> EntityId {
+        if let Some(shift) = self.mass_center_shifts.get(&collider.rigidbody_id) {
+            collider.offset.position -= *shift;
+        }
+        let body = collider.density.map(|_| collider.rigidbody_id);
         let id = self.colliders.insert(collider);
         if let Some(stored) = self.colliders.get_mut(id) {
             stored.id = id;
         }
+        if let Some(body) = body {
+            self.update_mass_properties(body);
+        }
         id
     }
 
+    /// Removes a collider with its broadphase proxies, its trigger overlaps (reported as
+    /// [`TriggerEvent::Exit`]) and its body's contact manifolds, waking the bodies involved.
+    /// A collider with a density also updates its body's mass properties.
+    ///
+    /// The returned collider's offset is relative to the body's origin as created, like
+    /// the one given to [`Self::add_collider`].
+    pub fn remove_collider(&mut self, id: EntityId) -> Option<Collider> {
+        let mut collider = self.colliders.remove(id)?;
+        self.detach_collider(&collider);
+        let touching = self
+            .collision
+            .manifold_cache
+            .remove_body(collider.rigidbody_id);
+        self.wake_bodies(touching.into_iter().chain([collider.rigidbody_id]));
+        collider.offset.position += self.center_of_mass_shift(collider.rigidbody_id);
+        if collider.density.is_some() {
+            self.update_mass_properties(collider.rigidbody_id);
+        }
+        Some(collider)
+    }
+
+    /// Removes a body together with its colliders and the joints attached to it, and
+    /// cleans it out of the contact, trigger and broadphase caches. Bodies it was touching
+    /// or jointed to are woken up.
+    pub fn remove_rigidbody(&mut self, id: EntityId) -> Option<RemovedBody> {
+        self.regions
+            .thaw(id, &mut self.bodies, &mut self.collision.broadphase);
+        let body = self.bodies.get(id)?.to_rigid_body();
+        let shift = self.center_of_mass_shift(id);
+
+        let attached: Vec<EntityId> = self
+            .colliders
+            .iter()
+            .filter(|collider| collider.rigidbody_id == id)
+            .map(|collider| collider.id)
+            .collect();
+        let mut colliders = Vec::with_capacity(attached.len());
+        for collider_id in attached {
+            if let Some(mut collider) = self.colliders.remove(collider_id) {
+                self.detach_collider(&collider);
+                collider.offset.position += shift;
+                colliders.push(collider);
+            }
+        }
+
+        let mut woken = self.collision.manifold_cache.remove_body(id);
+        let mut joints = Vec::new();
+        for index in (0..self.dynamics.joints.len()).rev() {
+            let (a, b) = self.dynamics.joints[index].bodies();
+            if a != id && b != id {
+                continue;
+            }
+            if let Some(joint) = self.dynamics.remove_joint(index) {
+                woken.push(if a == id { b } else { a });
+                joints.push(joint);
+            }
+        }
+        joints.reverse();
+
+        self.dynamics.body_priorities.remove(&id);
+        self.kinematic_targets.remove(&id);
+        self.mass_center_shifts.remove(&id);
+        self.link_bodies.remove(&id);
+        self.bodies.remove(id);
+        self.wake_bodies(woken);
+
+        Some(RemovedBody {
+            body,
+            colliders,
+            joints,
+        })
+    }
+
+    /// Drops a removed collider from the broadphase and trigger tracking.
+    fn detach_collider(&mut self, collider: &Collider) {
+        self.collision.broadphase.remove_collider(collider.id);
+        let exits = self.collision.triggers.remove_collider(collider.id);
+        self.trigger_events
+            .extend(exits.into_iter().map(TriggerEvent::Exit));
+    }
+
+    fn wake_bodies(&mut self, ids: impl IntoIterator<Item = EntityId>) {
+        for id in ids {
+            if let Some(mut body) = self.bodies.get_mut(id) {
+                body.set_awake(true);
+            }
+        }
+    }
+
+    /// Inserts many colliders at once, like [`Self::add_collider`] on each, and returns
+    /// their ids in input order.
+    ///
+    /// Bodies given colliders with a density have their mass properties updated once, after
+    /// all colliders are in, from a single pass over the collider arena.
+    pub fn add_colliders(
+        &mut self,
+        colliders: impl IntoIterator<Item = Collider>,
+    ) -> Vec<EntityId> {
+        let colliders = colliders.into_iter();
+        self.colliders.reserve(colliders.size_hint().0);
+        let mut massive = HashSet::new();
+        let ids: Vec<EntityId> = colliders
+            .map(|mut collider| {
+                if let Some(shift) = self.mass_center_shifts.get(&collider.rigidbody_id) {
+                    collider.offset.position -= *shift;
+                }
+                if collider.density.is_some() {
+                    massive.insert(collider.rigidbody_id);
+                }
+                let id = self.colliders.insert(collider);
+                if let Some(stored) = self.colliders.get_mut(id) {
+                    stored.id = id;
+                }
+                id
+            })
+            .collect();
+        if !massive.is_empty() {
+            let mut attached: HashMap<EntityId, Vec<EntityId>> = HashMap::new();
+            for collider in self.colliders.iter() {
+                if massive.contains(&collider.rigidbody_id) {
+                    attached
+                        .entry(collider.rigidbody_id)
+                        .or_default()
+                        .push(collider.id);
+                }
+            }
+            let mut attached: Vec<_> = attached.into_iter().collect();
+            attached.sort_by_key(|(body, _)| *body);
+            for (body, colliders) in attached {
+                self.update_mass_properties_from(body, &colliders);
+            }
+        }
+        ids
+    }
+
+    /// Recomputes a body's mass and inertia from its colliders with a density.
+    ///
+    /// The body's origin is moved to the computed centre of mass, keeping its colliders
+    /// where they are, because the solver rotates bodies about their origin; see
+    /// [`Self::center_of_mass_shift`]. Attach colliders before creating joints, whose
+    /// anchors are relative to the current origin. Returns `None`, leaving the body
+    /// unchanged, if none of its colliders has mass.
+    pub fn update_mass_properties(&mut self, body: EntityId) -> Option<MassProperties> {
+        let attached: Vec<EntityId> = self
+            .colliders
+            .iter()
+            .filter(|collider| collider.rigidbody_id == body)
+            .map(|collider| collider.id)
+            .collect();
+        self.update_mass_properties_from(body, &attached)
+    }
+
+    /// [`Self::update_mass_properties`] with the body's colliders already gathered.
+    fn update_mass_properties_from(
+        &mut self,
+        body: EntityId,
+        attached: &[EntityId],
+    ) -> Option<MassProperties> {
+        let (props, center) = Collider::combined_mass_properties(
+            attached.iter().filter_map(|&id| self.colliders.get(id)),
+        )?;
+        let mut body_mut = self.bodies.get_mut(body)?;
+        body_mut.set_mass_properties(props);
+        if center.length_squared() > f32::EPSILON * f32::EPSILON {
+            let transform = *body_mut.transform;
+            body_mut.transform.position += transform.rotation * (transform.scale * center);
+            for &id in attached {
+                if let Some(collider) = self.colliders.get_mut(id) {
+                    collider.offset.position -= center;
+                }
+            }
+            *self.mass_center_shifts.entry(body).or_default() += center;
+        }
+        Some(props)
+    }
+
+    /// Body-local distance [`Self::update_mass_properties`] has moved a body's origin.
+    pub fn center_of_mass_shift(&self, body: EntityId) -> Vec3 {
+        self.mass_center_shifts
+            .get(&body)
+            .copied()
+            .unwrap_or(Vec3::ZERO)
+    }
+
     pub fn body(&self, id: EntityId) -> Option<BodyRef<'_>> {
         self.bodies.get(id)
     }
@@ -262,6 +1120,35 @@ impl PhysicsWorld {
         self.bodies.get_mut(id)
     }
 
+    /// Moves a kinematic body to `position` over the next fixed step, keeping its rotation.
+    ///
+    /// Returns `false` if `id` is not a kinematic body. See
+    /// [`Self::set_next_kinematic_transform`].
+    pub fn set_next_kinematic_position(&mut self, id: EntityId, position: Vec3) -> bool {
+        let Some(rotation) = self.bodies.get(id).map(|body| body.transform().rotation) else {
+            return false;
+        };
+        self.set_next_kinematic_transform(id, position, rotation)
+    }
+
+    /// Moves a kinematic body to `position` and `rotation` over the next fixed step.
+    ///
+    /// The body is given the velocity that reaches the target, so contacts push dynamic
+    /// bodies out of its way, and it rests there afterwards unless given a new target.
+    /// Kinematic bodies can also be driven by setting their velocity directly.
+    pub fn set_next_kinematic_transform(
+        &mut self,
+        id: EntityId,
+        position: Vec3,
+        rotation: Quat,
+    ) -> bool {
+        if !self.bodies.get(id).is_some_and(|body| body.is_kinematic()) {
+            return false;
+        }
+        self.kinematic_targets.insert(id, (position, rotation));
+        true
+    }
+
     pub fn collider(&self, id: EntityId) -> Option<&Collider> {
         self.colliders.get(id)
     }
@@ -269,53 +1156,234 @@ impl PhysicsWorld {
     /// Collects contacts for the current world state without advancing the simulation.
     /// Useful for debugging and tests.
     pub fn collect_contacts(&mut self) -> Vec<Contact> {
-        self.generate_contacts()
+        let pairs = self.potential_pairs();
+        self.generate_contacts(&pairs)
+    }
+
+    /// Runs [`PhysicsWorld::step`] on a pooled worker thread.
+    ///
+    /// The world is unavailable until the returned handle is joined; read the pre-step state
+    /// through [`StepHandle::snapshot`] in the meantime.
+    pub fn step_async(&mut self, dt: f32) -> StepHandle<'_> {
+        StepHandle::spawn(self, dt)
+    }
+
+    /// Contact events produced by the last [`PhysicsWorld::step`], in substep order.
+    pub fn contact_events(&self) -> &[ContactEvent] {
+        &self.contact_events
+    }
+
+    /// Takes the contact events produced by the last [`PhysicsWorld::step`].
+    pub fn drain_contact_events(&mut self) -> std::vec::Drain<'_, ContactEvent> {
+        self.contact_events.drain(..)
+    }
+
+    /// Trigger enter/exit events produced by the last [`PhysicsWorld::step`], in substep order.
+    pub fn trigger_events(&self) -> &[TriggerEvent] {
+        &self.trigger_events
+    }
+
+    /// Takes the trigger events produced by the last [`PhysicsWorld::step`].
+    pub fn drain_trigger_events(&mut self) -> std::vec::Drain<'_, TriggerEvent> {
+        self.trigger_events.drain(..)
+    }
+
+    /// Joints broken during the last [`PhysicsWorld::step`], in substep order.
+    pub fn joint_broken_events(&self) -> &[JointBroken] {
+        &self.joint_broken_events
+    }
+
+    /// Takes the joint break events produced by the last [`PhysicsWorld::step`].
+    pub fn drain_joint_broken_events(&mut self) -> std::vec::Drain<'_, JointBroken> {
+        self.joint_broken_events.drain(..)
+    }
+
+    /// Bodies quarantined for NaN or infinite state during the last [`PhysicsWorld::step`].
+    pub fn non_finite_events(&self) -> &[NonFiniteEvent] {
+        &self.non_finite_events
+    }
+
+    /// Takes the non-finite events produced by the last [`PhysicsWorld::step`].
+    pub fn drain_non_finite_events(&mut self) -> std::vec::Drain<'_, NonFiniteEvent> {
+        self.non_finite_events.drain(..)
+    }
+
+    /// Sets what happens to bodies whose state turns non-finite (default: freeze them).
+    ///
+    /// Bodies are checked after solving and after integration each substep, and every
+    /// quarantined body is reported through [`Self::non_finite_events`].
+    pub fn set_non_finite_policy(&mut self, policy: NonFinitePolicy) {
+        self.non_finite_guard.set_policy(policy);
+    }
+
+    pub fn non_finite_policy(&self) -> NonFinitePolicy {
+        self.non_finite_guard.policy()
+    }
+
+    /// Trigger overlaps as of the last substep, ordered by collider ids.
+    pub fn trigger_overlaps(&self) -> &[TriggerOverlap] {
+        self.collision.triggers.overlaps()
+    }
+
+    /// Captures the simulation state, to serialize or to hand back to [`Self::restore_state`].
+    pub fn save_state(&self) -> WorldState {
+        WorldState::capture(self)
+    }
+
+    /// Puts the world back into a saved state; stepping on from there matches stepping on
+    /// from when it was saved. Returns false, leaving the world untouched, if the state was
+    /// written with a different [`WORLD_STATE_VERSION`].
+    pub fn restore_state(&mut self, state: &WorldState) -> bool {
+        if state.version() != WORLD_STATE_VERSION {
+            return false;
+        }
+        state.apply(self);
+        true
+    }
+
+    /// Keeps the states of the last `frames` fixed steps so [`Self::rollback`] can return to
+    /// them; zero, the default, records nothing. Recording starts from the current state.
+    pub fn set_rollback_frames(&mut self, frames: usize) {
+        self.history.set_capacity(frames);
+        self.history.clear();
+        self.history.push(WorldState::capture(self));
+    }
+
+    pub fn rollback_frames(&self) -> usize {
+        self.history.capacity()
+    }
+
+    /// Rewinds the world by `frames` fixed steps, to the state it had after that step.
+    ///
+    /// Recorded states newer than that one are dropped; [`Self::resimulate`] records them
+    /// again. Returns false, leaving the world untouched, if fewer than `frames` steps are
+    /// recorded.
+    pub fn rollback(&mut self, frames: usize) -> bool {
+        let mut history = std::mem::take(&mut self.history);
+        let rewound = history.rewind(frames).map(|state| state.apply(self));
+        self.history = history;
+        rewound.is_some()
+    }
+
+    /// Steps the world once per input, applying each input to the world right before its
+    /// step.
+    ///
+    /// A world rolled back and resimulated with the inputs it originally stepped with ends
+    /// up exactly where it was, provided it was originally advanced one fixed step per call
+    /// to [`Self::step`].
+    pub fn resimulate<F>(&mut self, inputs: impl IntoIterator<Item = F>)
+    where
+        F: FnOnce(&mut PhysicsWorld),
+    {
+        for input in inputs {
+            input(self);
+            self.step(self.time_step);
+        }
+    }
+
+    /// Runs `hook` at the start of every fixed step, before gravity and forces are applied.
+    ///
+    /// Forces and impulses the hook applies to the bodies act in that same step.
+    pub fn add_pre_step_hook(
+        &mut self,
+        hook: impl FnMut(&mut BodiesSoA, f32) + Send + Sync + 'static,
+    ) {
+        self.pre_step_hooks.push(Box::new(hook));
+    }
+
+    /// Runs `hook` at the end of every fixed step, once bodies have moved and events are in.
+    pub fn add_post_step_hook(
+        &mut self,
+        hook: impl FnMut(&mut BodiesSoA, f32) + Send + Sync + 'static,
+    ) {
+        self.post_step_hooks.push(Box::new(hook));
+    }
+
+    pub fn clear_step_hooks(&mut self) {
+        self.pre_step_hooks.clear();
+        self.post_step_hooks.clear();
     }
 
     /// Advances the simulation using a fixed timestep accumulator.
+    ///
+    /// Contact and trigger events left over from the previous call are discarded.
     pub fn step(&mut self, dt: f32) {
+        profile_scope!("step");
+        let step_start = Stopwatch::start();
+        self.step_stats = WorldStepStats::default();
         self.time_accumulated += dt;
+        self.contact_events.clear();
+        self.trigger_events.clear();
+        self.non_finite_events.clear();
+        self.joint_broken_events.clear();
 
         while self.time_accumulated >= self.time_step {
+            profile_scope!("fixed_step");
             self.time_accumulated -= self.time_step;
             self.frame_index = self.frame_index.wrapping_add(1);
+            self.step_stats.fixed_steps += 1;
             self.collision.manifold_cache.begin_frame(self.frame_index);
+            self.collision.triggers.begin_frame();
 
             self.profiler.reset();
             self.profiler.total_frame_time = Duration::ZERO;
-            let frame_start = std::time::Instant::now();
+            let frame_start = Stopwatch::start();
 
+            self.update_regions();
+            for hook in &mut self.pre_step_hooks {
+                hook(&mut self.bodies, self.time_step);
+            }
+            let driven = self.drive_kinematic_bodies();
             self.apply_gravity();
             self.dynamics
                 .force_registry
                 .apply_all(&mut self.bodies, self.time_step);
+            self.apply_force_fields();
+            self.collision
+                .broadphase
+                .set_speculative_expansion(self.collision.ccd.speculative_margin, self.time_step);
             self.sync_gpu_state();
 
+            // One broad phase per step, on the GPU backend if it has one, shared by CCD and
+            // the narrowphase.
+            let potential_pairs = self.step_potential_pairs();
             // Phase 1: Continuous Collision Detection (BEFORE integration)
-            let ccd_contacts = self.resolve_ccd_velocities();
-
-            // Broad-phase Dispatch (Prepare for contact generation)
-            self.gpu_backend.dispatch_broadphase(&self.gpu_state);
+            let (ccd_contacts, ccd_speed_limits) = {
+                profile_scope!("ccd");
+                self.resolve_ccd_velocities(&potential_pairs)
+            };
 
             let contacts = {
-                let start = std::time::Instant::now();
-                let mut c = self.generate_contacts();
+                let start = Stopwatch::start();
+                let mut c = self.generate_contacts(&potential_pairs);
                 c.extend(ccd_contacts); // Ensure CCD hits are solved
+                self.dynamics.prioritize_contacts(&mut c);
                 self.profiler.broad_phase_time = start.elapsed();
                 c
             };
             self.profiler.contact_count = contacts.len();
+            self.step_stats.contacts += contacts.len();
 
             {
-                let start = std::time::Instant::now();
-                self.islands
-                    .build_islands(&self.bodies, &contacts, &self.dynamics.joints);
+                profile_scope!("islands");
+                let start = Stopwatch::start();
+                let (joints, order) = self.dynamics.prioritized_joints();
+                self.islands.build_islands(&self.bodies, &contacts, &joints);
+                self.joint_order = order;
                 self.profiler.narrow_phase_time = start.elapsed();
             }
             self.profiler.active_island_count = self.islands.islands().len();
+            self.step_stats.islands += self.islands.islands().len();
 
             {
-                let start = std::time::Instant::now();
+                profile_scope!("solver");
+                let start = Stopwatch::start();
+                self.joint_loads.clear();
+                self.joint_loads
+                    .resize(self.dynamics.joints.len(), JointImpulse::default());
+                self.dynamics
+                    .joint_warm_starts
+                    .resize(self.dynamics.joints.len(), JointImpulse::default());
                 if self.parallel_enabled {
                     #[cfg(feature = "parallel")]
                     self.solve_islands_parallel();
@@ -325,30 +1393,68 @@ impl PhysicsWorld {
                     self.solve_islands_sequential();
                 }
                 self.apply_predictive_corrections(&contacts);
+                self.clamp_ccd_velocities(&ccd_speed_limits);
+                if self.dynamics.has_breakable_joints() {
+                    let broken = self
+                        .dynamics
+                        .break_overloaded_joints(&self.joint_loads, self.time_step);
+                    self.joint_broken_events.extend(broken);
+                }
+                self.non_finite_guard.check(
+                    &mut self.bodies,
+                    SimulationStage::Solver,
+                    &mut self.non_finite_events,
+                );
                 self.profiler.solver_time = start.elapsed();
+                let stats = &mut self.step_stats;
+                let metrics = &self.last_solver_metrics;
+                stats.solver_time += self.profiler.solver_time;
+                stats.awake_islands += metrics.islands_solved;
+                stats.largest_island = stats.largest_island.max(metrics.largest_island);
+                stats.solver_iterations +=
+                    metrics.islands_solved as u32 * self.dynamics.solver.velocity_iterations;
             }
 
             self.log_solver_metrics_if_needed();
-            self.gpu_backend.dispatch_solver(&self.gpu_state);
+            self.gpu_results.velocities = self.gpu_backend.dispatch_solver(&self.gpu_state);
 
             // Integrate (Move bodies based on velocity)
-            let start_int = std::time::Instant::now();
-            self.integrator.step(&mut self.bodies);
-            self.profiler.integrator_time = start_int.elapsed();
-
             {
-                // 5. Articulation Step (ABA)
-                for mb in self.articulated_bodies.iter_mut() {
-                    crate::dynamics::aba::ABASolver::solve(mb, self.gravity);
-
-                    // Integrate Generalized coordinates (Semi-implicit Euler)
-                    for i in 0..mb.total_dofs {
-                        mb.dq[i] += mb.ddq[i] * self.time_step;
-                        mb.q[i] += mb.dq[i] * self.time_step;
-                    }
+                profile_scope!("integration");
+                let start_int = Stopwatch::start();
+                self.integrator.step(&mut self.bodies);
+                self.settle_kinematic_bodies(&driven);
+                self.non_finite_guard.check(
+                    &mut self.bodies,
+                    SimulationStage::Integration,
+                    &mut self.non_finite_events,
+                );
+                self.non_finite_guard.capture(&self.bodies);
+                self.profiler.integrator_time = start_int.elapsed();
+                self.step_stats.integration_time += self.profiler.integrator_time;
+            }
 
-                    mb.update_kinematics();
-                }
+            // 5. Articulation Step (ABA)
+            self.step_articulations();
+
+            self.step_particles();
+            self.fluid
+                .step(self.time_step, self.gravity, &self.colliders, &self.bodies);
+            for cloth in self.cloths.iter_mut() {
+                cloth.step(
+                    self.time_step,
+                    self.gravity,
+                    &self.colliders,
+                    &mut self.bodies,
+                );
+            }
+            for body in self.soft_bodies.iter_mut() {
+                body.step(
+                    self.time_step,
+                    self.gravity,
+                    &self.colliders,
+                    &mut self.bodies,
+                );
             }
 
             {
@@ -356,107 +1462,354 @@ impl PhysicsWorld {
                 self.islands.update_sleeping(&mut self.bodies);
             }
 
+            self.collision
+                .manifold_cache
+                .collect_events(&mut self.contact_events);
+            if let Some(recorder) = self.manifold_recorder.as_mut() {
+                if !recorder.is_paused() {
+                    recorder.record(
+                        self.frame_index,
+                        self.collision.manifold_cache.frame_snapshots(),
+                    );
+                }
+            }
             self.collision.manifold_cache.prune_stale();
+            self.collision.triggers.end_frame(&mut self.trigger_events);
             self.log_manifolds_if_needed();
+            for hook in &mut self.post_step_hooks {
+                hook(&mut self.bodies, self.time_step);
+            }
+            if self.history.capacity() > 0 {
+                let state = WorldState::capture(self);
+                self.history.push(state);
+            }
 
             self.profiler.total_frame_time = frame_start.elapsed();
             self.profiler.body_count = self.bodies.len();
             // self.profiler.report();
         }
+        self.query_pipeline.update(&self.colliders, &self.bodies);
+        self.step_stats.total_time = step_start.elapsed();
     }
 
-    fn apply_gravity(&mut self) {
-        for body in self.bodies.iter_mut() {
-            if body.is_static() {
-                continue;
+    /// Advances multibodies with ABA and moves their links' collision proxies along.
+    ///
+    /// Contact impulses recorded on the proxies during this substep's solve act as joint
+    /// forces, and link motors are solved against the resulting passive accelerations.
+    fn step_articulations(&mut self) {
+        profile_scope!("articulations");
+        let dt = self.time_step;
+        for mb in self.articulated_bodies.iter_mut() {
+            let mut tau = mb.tau.clone();
+            for (force, impulse) in tau.iter_mut().zip(&mut mb.contact_impulse) {
+                *force += *impulse / dt;
+                *impulse = 0.0;
+            }
+            ABASolver::solve_with_forces(mb, self.gravity, &tau);
+            if mb.has_motors() {
+                for (force, motor) in tau.iter_mut().zip(mb.motor_forces(dt)) {
+                    *force += motor;
+                }
+                ABASolver::solve_with_forces(mb, self.gravity, &tau);
+            }
+
+            // Integrate Generalized coordinates (Semi-implicit Euler)
+            for i in 0..mb.total_dofs {
+                mb.dq[i] += mb.ddq[i] * dt;
+                mb.q[i] += mb.dq[i] * dt;
             }
-            let force = self.gravity * (*body.gravity_scale);
-            *body.acceleration += force;
+            mb.enforce_limits();
+            mb.update_kinematics();
         }
+        self.sync_link_bodies(None);
     }
 
-    fn sync_gpu_state(&mut self) {
-        // self.gpu_state.sync(&self.bodies, &self.colliders);
-        // self.gpu_backend.prepare_step(&self.gpu_state);
-    }
+    /// Moves link collision bodies onto their links as kinematic proxies.
+    ///
+    /// Their velocity is the motion since the last sync, so contacts see the links move;
+    /// the proxies of the `snapped` multibody are placed without velocity.
+    fn sync_link_bodies(&mut self, snapped: Option<EntityId>) {
+        self.link_bodies.clear();
+        let dt = self.time_step;
+        for mb_id in self.articulated_bodies.ids() {
+            let Some(mb) = self.articulated_bodies.get(mb_id) else {
+                continue;
+            };
+            for (index, link) in mb.links.iter().enumerate() {
+                let Some(body_id) = link.collision_body else {
+                    continue;
+                };
+                let Some(mut body) = self.bodies.get_mut(body_id) else {
+                    continue;
+                };
+                self.link_bodies.insert(body_id, (mb_id, index));
 
-    fn generate_contacts(&mut self) -> Vec<Contact> {
-        if self.colliders.len() < 2 {
-            return Vec::new();
+                let target = mb.world_transforms[index];
+                let (linear, angular) = if snapped == Some(mb_id) {
+                    (Vec3::ZERO, Vec3::ZERO)
+                } else {
+                    velocity_toward(body.transform, target.position, target.rotation, dt)
+                };
+                body.transform.position = target.position;
+                body.transform.rotation = target.rotation;
+                body.set_velocity(linear, angular);
+                body.flags.is_kinematic = true;
+                *body.inverse_mass = 0.0;
+                *body.inverse_inertia = Mat3::ZERO;
+                *body.gravity_scale = 0.0;
+            }
         }
+    }
 
-        let mut contacts = Vec::new();
-        let potential_pairs = self
-            .collision
-            .broadphase
-            .get_potential_pairs(&self.colliders, &self.bodies);
-
-        for (collider_a_id, collider_b_id) in potential_pairs {
-            let collider_a = match self.colliders.get(collider_a_id) {
-                Some(collider) => collider,
-                None => continue,
-            };
-            let collider_b = match self.colliders.get(collider_b_id) {
-                Some(collider) => collider,
-                None => continue,
+    /// Gives kinematic bodies with a target the velocity that reaches it this fixed step.
+    fn drive_kinematic_bodies(&mut self) -> Vec<(EntityId, Vec3, Quat)> {
+        let mut driven = Vec::with_capacity(self.kinematic_targets.len());
+        for (id, (position, rotation)) in self.kinematic_targets.drain() {
+            let Some(mut body) = self.bodies.get_mut(id) else {
+                continue;
             };
-
-            if !Self::filters_match(&collider_a.collision_filter, &collider_b.collision_filter) {
+            if !body.is_kinematic() {
                 continue;
             }
+            let (linear, angular) =
+                velocity_toward(body.transform, position, rotation, self.time_step);
+            body.set_velocity(linear, angular);
+            body.set_awake(true);
+            driven.push((id, position, rotation));
+        }
+        driven
+    }
 
-            let (body_a_mut, body_b_mut) = match self
-                .bodies
-                .get2_mut(collider_a.rigidbody_id, collider_b.rigidbody_id)
-            {
-                Some(pair) => pair,
-                None => continue,
-            };
+    /// Places driven kinematic bodies exactly on their targets and stops them there.
+    fn settle_kinematic_bodies(&mut self, driven: &[(EntityId, Vec3, Quat)]) {
+        for &(id, position, rotation) in driven {
+            if let Some(mut body) = self.bodies.get_mut(id) {
+                body.transform.position = position;
+                body.transform.rotation = rotation;
+                body.set_velocity(Vec3::ZERO, Vec3::ZERO);
+            }
+        }
+    }
 
-            let rb_a = body_a_mut.to_rigid_body();
-            let rb_b = body_b_mut.to_rigid_body();
+    fn apply_gravity(&mut self) {
+        for body in self.bodies.iter_mut() {
+            if body.is_static() || body.is_kinematic() {
+                continue;
+            }
+            *body.acceleration += body.gravity(self.gravity);
+        }
+    }
 
-            if let Some(manifold) = ContactManifold::generate(collider_a, &rb_a, collider_b, &rb_b)
-            {
-                contacts.extend(
-                    self.collision
-                        .manifold_cache
-                        .update_pair(manifold, collider_a, collider_b, &rb_a, &rb_b),
-                );
+    fn apply_force_fields(&mut self) {
+        if self.dynamics.force_fields.is_empty() {
+            return;
+        }
+
+        // Bounds of each body's colliders; bodies without colliders are points.
+        let mut bounds: HashMap<EntityId, Aabb> = HashMap::new();
+        for collider in self.colliders.iter() {
+            if collider.is_trigger || collider.is_unbounded() {
                 continue;
             }
+            let Some(body) = self.bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            let transform = collider.world_transform(body.transform());
+            let extent = Vec3::splat(
+                BroadPhase::get_collider_radius(&collider.shape)
+                    * transform.scale.abs().max_element()
+                    + collider.margin,
+            );
+            let collider_bounds =
+                Aabb::new(transform.position - extent, transform.position + extent);
+            bounds
+                .entry(body.id())
+                .and_modify(|b| *b = b.union(&collider_bounds))
+                .or_insert(collider_bounds);
+        }
 
-            if collider_a.is_trigger || collider_b.is_trigger {
+        let time = self.frame_index as f32 * self.time_step;
+        for mut body in self.bodies.iter_mut() {
+            if body.is_static() || body.is_kinematic() || !body.is_enabled() {
                 continue;
             }
+            let position = body.transform.position;
+            let body_bounds = bounds
+                .get(&body.id)
+                .copied()
+                .unwrap_or(Aabb::new(position, position));
+            let mut force = Vec3::ZERO;
+            for field in self.dynamics.force_fields.iter() {
+                if field.enabled && field.volume.overlaps(&body_bounds) {
+                    force += field.force(
+                        position,
+                        body.velocity.linear,
+                        body.mass_properties.mass,
+                        time,
+                    );
+                }
+            }
+            if force != Vec3::ZERO {
+                body.apply_force(force);
+            }
+        }
+    }
 
-            if let Some(speculative) = self.collision.ccd.generate_speculative_contact(
-                &rb_a,
-                collider_a,
-                &rb_b,
-                collider_b,
+    /// Steps the particles on the compute backend if they opt in and it can, else on the CPU.
+    fn step_particles(&mut self) {
+        profile_scope!("particles");
+        let on_gpu = self.particles.gpu
+            && self.particles.links().is_empty()
+            && self.gpu_backend.simulates_particles();
+        self.particles.set_gpu_resident(on_gpu);
+        if !on_gpu {
+            self.particles
+                .step(self.time_step, self.gravity, &self.colliders, &self.bodies);
+            return;
+        }
+        self.particles.advance_ages(self.time_step);
+        self.gpu_particles.sync(
+            &mut self.particles,
+            &self.colliders,
+            &self.bodies,
+            self.gravity,
+            self.time_step,
+        );
+        self.gpu_backend.dispatch_particles(&self.gpu_particles);
+    }
+
+    fn sync_gpu_state(&mut self) {
+        if self.gpu_backend.uses_world_state() {
+            self.gpu_state.sync_bodies(
+                &self.bodies,
+                &self.colliders,
                 self.time_step,
-            ) {
-                self.collision.manifold_cache.record_contact(&speculative);
-                contacts.push(speculative);
-            }
+                self.collision.broadphase.speculative_expansion(),
+            );
+            self.gpu_backend.prepare_step(&self.gpu_state);
         }
+        self.gpu_results.begin(&self.gpu_state);
+    }
 
-        contacts
+    /// Candidate collider pairs for this step. A GPU backend with a broad phase finds the
+    /// body pairs and the CPU broad phase is skipped; otherwise falls back to
+    /// [`Self::potential_pairs`].
+    fn step_potential_pairs(&mut self) -> Vec<(EntityId, EntityId)> {
+        if !self.gpu_backend.uses_world_state() {
+            return self.potential_pairs();
+        }
+        let start = Stopwatch::start();
+        let Some(mut readback) = self.gpu_backend.dispatch_broadphase(&self.gpu_state) else {
+            return self.potential_pairs();
+        };
+        profile_scope!("broadphase");
+        let body_pairs = self.gpu_state.pair_ids(readback.wait());
+        // Kept for `gpu_broadphase_pairs`.
+        self.gpu_results.pairs = Some(readback);
+        let pairs =
+            self.collision
+                .broadphase
+                .pairs_from_bodies(&body_pairs, &self.colliders, &self.bodies);
+        self.step_stats.broadphase_time += start.elapsed();
+        self.step_stats.broadphase_pairs += pairs.len();
+        pairs
     }
 
-    fn resolve_ccd_velocities(&mut self) -> Vec<Contact> {
-        let mut ccd_contacts = Vec::new();
+    /// Candidate collider pairs from the broad phase, which updates its pair tracking.
+    fn potential_pairs(&mut self) -> Vec<(EntityId, EntityId)> {
         if self.colliders.len() < 2 {
-            return ccd_contacts;
+            return Vec::new();
         }
-
-        let potential_pairs = self
+        profile_scope!("broadphase");
+        let start = Stopwatch::start();
+        let pairs = self
             .collision
             .broadphase
             .get_potential_pairs(&self.colliders, &self.bodies);
+        self.step_stats.broadphase_time += start.elapsed();
+        self.step_stats.broadphase_pairs += pairs.len();
+        pairs
+    }
 
-        for (collider_a_id, collider_b_id) in potential_pairs {
+    fn generate_contacts(&mut self, potential_pairs: &[(EntityId, EntityId)]) -> Vec<Contact> {
+        if self.colliders.len() < 2 {
+            return Vec::new();
+        }
+
+        profile_scope!("narrowphase");
+        let start = Stopwatch::start();
+        let outcomes = self.collision.narrowphase(
+            potential_pairs,
+            &self.colliders,
+            &self.bodies,
+            self.time_step,
+            self.parallel_enabled,
+        );
+
+        let mut contacts = Vec::new();
+        for outcome in outcomes {
+            match outcome {
+                PairOutcome::Trigger { trigger, other } => {
+                    let trigger = match self.colliders.get(trigger) {
+                        Some(collider) => collider,
+                        None => continue,
+                    };
+                    let other = match self.colliders.get(other) {
+                        Some(collider) => collider,
+                        None => continue,
+                    };
+                    self.collision.triggers.record(trigger, other);
+                }
+                PairOutcome::Manifold {
+                    manifold,
+                    collider_a,
+                    collider_b,
+                } => {
+                    self.step_stats.manifolds += 1;
+                    let collider_a = match self.colliders.get(collider_a) {
+                        Some(collider) => collider,
+                        None => continue,
+                    };
+                    let collider_b = match self.colliders.get(collider_b) {
+                        Some(collider) => collider,
+                        None => continue,
+                    };
+                    let (body_a, body_b) = match (
+                        self.bodies.get(collider_a.rigidbody_id),
+                        self.bodies.get(collider_b.rigidbody_id),
+                    ) {
+                        (Some(a), Some(b)) => (a.to_rigid_body(), b.to_rigid_body()),
+                        _ => continue,
+                    };
+                    contacts.extend(self.collision.manifold_cache.update_pair(
+                        manifold,
+                        collider_a,
+                        collider_b,
+                        &body_a,
+                        &body_b,
+                        &self.collision.material_pairs,
+                    ));
+                }
+                PairOutcome::Speculative(speculative) => {
+                    self.collision.manifold_cache.record_contact(&speculative);
+                    contacts.push(speculative);
+                }
+            }
+        }
+        self.step_stats.narrowphase_time += start.elapsed();
+
+        contacts
+    }
+
+    /// Scales velocities to the time of impact for CCD hits. Returns the CCD contacts and
+    /// each hit body's pre-impact speed for [`PhysicsWorld::clamp_ccd_velocities`].
+    fn resolve_ccd_velocities(
+        &mut self,
+        potential_pairs: &[(EntityId, EntityId)],
+    ) -> (Vec<Contact>, Vec<(EntityId, f32)>) {
+        let mut ccd_contacts = Vec::new();
+        let mut speed_limits = Vec::new();
+        for &(collider_a_id, collider_b_id) in potential_pairs {
             let collider_a = match self.colliders.get(collider_a_id) {
                 Some(collider) => collider,
                 None => continue,
@@ -466,7 +1819,7 @@ impl PhysicsWorld {
                 None => continue,
             };
 
-            if !Self::filters_match(&collider_a.collision_filter, &collider_b.collision_filter) {
+            if collider_a.is_trigger || collider_b.is_trigger {
                 continue;
             }
 
@@ -477,15 +1830,22 @@ impl PhysicsWorld {
                 Some(pair) => pair,
                 None => continue,
             };
+            if !body_a_mut.ccd_enabled() && !body_b_mut.ccd_enabled() {
+                continue;
+            }
 
             let rb_a = body_a_mut.to_rigid_body();
             let rb_b = body_b_mut.to_rigid_body();
 
-            if let Some(ccd_hit) =
+            if let Some(mut ccd_hit) =
                 self.collision
                     .ccd
                     .detect_ccd(&rb_a, collider_a, &rb_b, collider_b, self.time_step)
             {
+                ccd_hit.contact.material = self
+                    .collision
+                    .material_pairs
+                    .combine(&rb_a.material, &rb_b.material);
                 // TOI found - collision will happen at time toi.
                 // Scale velocity to reach the impact point exactly during integration.
                 let toi = ccd_hit.time_of_impact;
@@ -496,9 +1856,11 @@ impl PhysicsWorld {
                 };
 
                 if !body_a_mut.is_static() {
+                    speed_limits.push((rb_a.id, rb_a.velocity.linear.length()));
                     body_a_mut.velocity.linear *= scale;
                 }
                 if !body_b_mut.is_static() {
+                    speed_limits.push((rb_b.id, rb_b.velocity.linear.length()));
                     body_b_mut.velocity.linear *= scale;
                 }
 
@@ -510,11 +1872,26 @@ impl PhysicsWorld {
                 ccd_contacts.push(ccd_hit.contact);
             }
         }
-        ccd_contacts
+        (ccd_contacts, speed_limits)
     }
 
-    fn filters_match(filter_a: &CollisionFilter, filter_b: &CollisionFilter) -> bool {
-        (filter_a.mask & filter_b.layer) != 0 && (filter_b.mask & filter_a.layer) != 0
+    /// Keeps the solver from sending CCD-hit bodies away faster than they arrived; see
+    /// [`CCDDetector::velocity_clamp`].
+    fn clamp_ccd_velocities(&mut self, speed_limits: &[(EntityId, f32)]) {
+        let scale = match self.collision.ccd.velocity_clamp {
+            Some(scale) => scale,
+            None => return,
+        };
+        for &(id, speed) in speed_limits {
+            let body = match self.bodies.get_mut(id) {
+                Some(body) => body,
+                None => continue,
+            };
+            let limit = speed * scale;
+            if body.velocity.linear.length() > limit {
+                body.velocity.linear = body.velocity.linear.normalize_or_zero() * limit;
+            }
+        }
     }
 
     fn log_manifolds_if_needed(&self) {
@@ -551,8 +1928,10 @@ impl PhysicsWorld {
         }
         let metrics = &self.last_solver_metrics;
         debug!(
-            "Solver metrics: islands={} contacts={} joints={} normal_sum={:.4} tangent_sum={:.4} rolling_sum={:.4} torsional_sum={:.4}",
+            "Solver metrics: islands={} largest_island={} oversized_islands={} contacts={} joints={} normal_sum={:.4} tangent_sum={:.4} rolling_sum={:.4} torsional_sum={:.4}",
             metrics.islands_solved,
+            metrics.largest_island,
+            metrics.oversized_islands,
             metrics.contacts_solved,
             metrics.joints_solved,
             metrics.normal_impulse_sum,
@@ -563,7 +1942,8 @@ impl PhysicsWorld {
     }
 
     fn apply_predictive_corrections(&mut self, contacts: &[Contact]) {
-        if !self.pci_enabled {
+        let predictive = self.integrator.scheme() == IntegrationScheme::PredictiveCorrective;
+        if !self.pci_enabled && !predictive {
             return;
         }
         if contacts.is_empty() {
@@ -579,16 +1959,41 @@ impl PhysicsWorld {
             if !island.is_awake {
                 continue;
             }
+            metrics.record_island_size(island.bodies.len(), self.islands.max_island_size());
             let mut contacts = island.contacts.clone();
 
             // Sequential solver still uses global SoA for simplicity,
             // but we could use the slice-based one too.
-            self.dynamics.solver.solve(
+            let mut warm_starts = gather_joint_warm_starts(
+                &self.dynamics.joint_warm_starts,
+                &self.joint_order,
+                &island.joint_indices,
+            );
+            let joint_impulses = self.dynamics.solver.solve_warm_started(
                 &mut self.bodies,
                 &island.joints,
+                &mut warm_starts,
                 &mut contacts,
                 self.time_step,
             );
+            scatter_joint_warm_starts(
+                &mut self.dynamics.joint_warm_starts,
+                &self.joint_order,
+                &island.joint_indices,
+                &warm_starts,
+            );
+            record_joint_loads(
+                &mut self.joint_loads,
+                &self.joint_order,
+                &island.joint_indices,
+                &joint_impulses,
+            );
+            record_link_contacts(
+                &self.link_bodies,
+                &self.bodies,
+                &mut self.articulated_bodies,
+                &contacts,
+            );
             self.collision.manifold_cache.apply_impulses(&contacts);
             metrics.record_island(&contacts, island.joints.len());
         }
@@ -599,34 +2004,63 @@ impl PhysicsWorld {
     fn solve_islands_parallel(&mut self) {
         let solver = self.dynamics.solver.clone();
         let dt = self.time_step;
+        let max_island_size = self.islands.max_island_size();
+        let mut metrics = SolverStepMetrics::default();
 
-        let mut jobs: Vec<IslandJob> = self
-            .islands
-            .islands()
-            .iter()
-            .filter(|island| island.is_awake)
-            .filter_map(|island| self.prepare_island_job(island))
-            .collect();
+        let mut jobs: Vec<IslandJob> = Vec::new();
+        for island in self.islands.islands() {
+            if !island.is_awake {
+                continue;
+            }
+            metrics.record_island_size(island.bodies.len(), max_island_size);
+            match max_island_size {
+                Some(max) if island.bodies.len() > max => jobs.extend(
+                    island
+                        .split(max, &self.bodies)
+                        .iter()
+                        .filter_map(|part| self.prepare_island_job(part)),
+                ),
+                _ => jobs.extend(self.prepare_island_job(island)),
+            }
+        }
 
         jobs.par_iter_mut().for_each(|job| {
-            solver.solve_island_slice(
+            job.joint_impulses = solver.solve_island_slice(
                 &mut job.bodies,
                 &job.id_map,
                 &mut job.contacts,
                 &job.joints,
+                &mut job.joint_warm_starts,
                 dt,
             );
         });
 
-        let mut metrics = SolverStepMetrics::default();
-
         for job in &jobs {
+            record_joint_loads(
+                &mut self.joint_loads,
+                &self.joint_order,
+                &job.joint_indices,
+                &job.joint_impulses,
+            );
+            scatter_joint_warm_starts(
+                &mut self.dynamics.joint_warm_starts,
+                &self.joint_order,
+                &job.joint_indices,
+                &job.joint_warm_starts,
+            );
+            record_link_contacts(
+                &self.link_bodies,
+                &self.bodies,
+                &mut self.articulated_bodies,
+                &job.contacts,
+            );
             self.collision.manifold_cache.apply_impulses(&job.contacts);
             metrics.record_island(&job.contacts, job.joints.len());
         }
 
+        // Ghost bodies sit after the owned ones and are not written back.
         for job in jobs {
-            for (id, body_state) in job.ids.into_iter().zip(job.bodies.into_iter()) {
+            for (id, body_state) in job.ids.into_iter().zip(job.bodies) {
                 if let Some(mut slot) = self.bodies.get_mut(id) {
                     slot.copy_from(&body_state);
                 }
@@ -635,6 +2069,7 @@ impl PhysicsWorld {
         self.last_solver_metrics = metrics;
     }
 
+    #[cfg(feature = "parallel")]
     fn prepare_island_job(&self, island: &crate::dynamics::island::Island) -> Option<IslandJob> {
         if island.bodies.is_empty() {
             return None;
@@ -644,11 +2079,11 @@ impl PhysicsWorld {
         let mut bodies = Vec::with_capacity(island.bodies.len());
         let mut id_map = std::collections::HashMap::with_capacity(island.bodies.len());
 
-        for (idx, body_id) in island.bodies.iter().enumerate() {
+        for body_id in &island.bodies {
             if let Some(body) = self.bodies.get(*body_id) {
+                id_map.insert(*body_id, bodies.len());
                 ids.push(*body_id);
                 bodies.push(body.to_rigid_body());
-                id_map.insert(*body_id, idx);
             }
         }
 
@@ -656,20 +2091,138 @@ impl PhysicsWorld {
             return None;
         }
 
+        for body_id in &island.ghosts {
+            if let Some(body) = self.bodies.get(*body_id) {
+                id_map.insert(*body_id, bodies.len());
+                bodies.push(body.to_rigid_body());
+            }
+        }
+
         Some(IslandJob {
             ids,
             bodies,
             id_map,
             contacts: island.contacts.clone(),
             joints: island.joints.clone(),
+            joint_warm_starts: gather_joint_warm_starts(
+                &self.dynamics.joint_warm_starts,
+                &self.joint_order,
+                &island.joint_indices,
+            ),
+            joint_indices: island.joint_indices.clone(),
+            joint_impulses: Vec::new(),
+        })
+    }
+}
+
+/// Stores island joint impulses by index into the world's joint list.
+///
+/// Split islands solve boundary joints in both partitions; the larger load is kept.
+fn record_joint_loads(
+    loads: &mut [JointImpulse],
+    order: &[usize],
+    island_indices: &[usize],
+    impulses: &[JointImpulse],
+) {
+    for (index, impulse) in island_indices.iter().zip(impulses) {
+        let Some(load) = order.get(*index).and_then(|&joint| loads.get_mut(joint)) else {
+            continue;
+        };
+        if impulse.linear.length_squared() + impulse.angular.length_squared()
+            > load.linear.length_squared() + load.angular.length_squared()
+        {
+            *load = *impulse;
+        }
+    }
+}
+
+/// Cached warm starts of an island's joints, in island order.
+fn gather_joint_warm_starts(
+    warm_starts: &[JointImpulse],
+    order: &[usize],
+    island_indices: &[usize],
+) -> Vec<JointImpulse> {
+    island_indices
+        .iter()
+        .map(|index| {
+            order
+                .get(*index)
+                .and_then(|&joint| warm_starts.get(joint))
+                .copied()
+                .unwrap_or_default()
         })
+        .collect()
+}
+
+/// Stores an island's warm starts back by index into the world's joint list.
+fn scatter_joint_warm_starts(
+    warm_starts: &mut [JointImpulse],
+    order: &[usize],
+    island_indices: &[usize],
+    island_warm_starts: &[JointImpulse],
+) {
+    for (index, impulse) in island_indices.iter().zip(island_warm_starts) {
+        if let Some(slot) = order
+            .get(*index)
+            .and_then(|&joint| warm_starts.get_mut(joint))
+        {
+            *slot = *impulse;
+        }
     }
 }
 
+/// Velocity that carries `from` onto `position` and `rotation` in `dt`.
+fn velocity_toward(from: &Transform, position: Vec3, rotation: Quat, dt: f32) -> (Vec3, Vec3) {
+    let mut turn = rotation * from.rotation.inverse();
+    if turn.w < 0.0 {
+        turn = -turn;
+    }
+    ((position - from.position) / dt, turn.to_scaled_axis() / dt)
+}
+
+/// Hands the contact impulses on link collision proxies to their multibodies.
+///
+/// Contacts against bodies of infinite mass are skipped: the proxies are kinematic, so the
+/// solver has no finite impulse for them.
+fn record_link_contacts(
+    link_bodies: &HashMap<EntityId, (EntityId, usize)>,
+    bodies: &BodiesSoA,
+    multibodies: &mut Arena<Multibody>,
+    contacts: &[Contact],
+) {
+    if link_bodies.is_empty() {
+        return;
+    }
+    let movable = |id| bodies.get(id).is_some_and(|body| body.inverse_mass() > 0.0);
+    for contact in contacts {
+        let impulse = contact.normal * contact.accumulated_normal_impulse
+            + contact.accumulated_tangent_impulse;
+        let sides = [
+            (contact.body_a, contact.body_b, -impulse),
+            (contact.body_b, contact.body_a, impulse),
+        ];
+        for (body, other, impulse) in sides {
+            let Some(&(mb_id, link)) = link_bodies.get(&body) else {
+                continue;
+            };
+            if !movable(other) {
+                continue;
+            }
+            if let Some(mb) = multibodies.get_mut(mb_id) {
+                mb.apply_link_impulse(link, contact.point, impulse);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "parallel")]
 struct IslandJob {
     ids: Vec<EntityId>,
     bodies: Vec<RigidBody>,
     id_map: std::collections::HashMap<EntityId, usize>,
     contacts: Vec<Contact>,
     joints: Vec<Joint>,
+    joint_indices: Vec<usize>,
+    joint_impulses: Vec<JointImpulse>,
+    joint_warm_starts: Vec<JointImpulse>,
 }
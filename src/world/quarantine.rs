@@ -0,0 +1,146 @@
+use std::fmt;
+
+use crate::{
+    core::{
+        soa::{BodiesSoA, BodyMut},
+        types::{Transform, Velocity},
+    },
+    utils::allocator::EntityId,
+};
+
+/// What the world does with a body whose state turns NaN or infinite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinitePolicy {
+    /// Panic with the body and stage, for catching the culprit in development.
+    Panic,
+    /// Restore the body's last finite pose, zero its velocity, and disable it.
+    #[default]
+    Freeze,
+    /// Restore the pose and velocity the body had at the end of the previous substep.
+    ResetToLastGood,
+}
+
+/// Point in the substep where a non-finite body was caught.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationStage {
+    /// After contact and joint solving (including predictive corrections and CCD clamping).
+    Solver,
+    /// After velocity and position integration.
+    Integration,
+}
+
+impl fmt::Display for SimulationStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solver => write!(f, "solver"),
+            Self::Integration => write!(f, "integration"),
+        }
+    }
+}
+
+/// A body was quarantined by the world's [`NonFinitePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NonFiniteEvent {
+    pub body: EntityId,
+    pub stage: SimulationStage,
+    pub policy: NonFinitePolicy,
+}
+
+/// Checks bodies for non-finite state and applies the policy.
+#[derive(Debug, Default)]
+pub(crate) struct NonFiniteGuard {
+    policy: NonFinitePolicy,
+    transforms: Vec<Transform>,
+    velocities: Vec<Velocity>,
+}
+
+impl NonFiniteGuard {
+    pub(crate) fn policy(&self) -> NonFinitePolicy {
+        self.policy
+    }
+
+    pub(crate) fn set_policy(&mut self, policy: NonFinitePolicy) {
+        self.policy = policy;
+    }
+
+    /// Remembers the finite bodies' state after a checked substep as their last good one.
+    ///
+    /// Bodies still non-finite (the check ran under another policy) keep their older state.
+    pub(crate) fn capture(&mut self, bodies: &BodiesSoA) {
+        if self.policy == NonFinitePolicy::Panic {
+            return;
+        }
+        self.transforms
+            .resize(bodies.transforms.len(), Transform::default());
+        self.velocities
+            .resize(bodies.velocities.len(), Velocity::default());
+        let current = bodies.transforms.iter().zip(&bodies.velocities);
+        let saved = self.transforms.iter_mut().zip(&mut self.velocities);
+        for ((transform, velocity), (saved_transform, saved_velocity)) in current.zip(saved) {
+            if transform_is_finite(transform)
+                && velocity.linear.is_finite()
+                && velocity.angular.is_finite()
+            {
+                *saved_transform = *transform;
+                *saved_velocity = *velocity;
+            }
+        }
+    }
+
+    pub(crate) fn check(
+        &self,
+        bodies: &mut BodiesSoA,
+        stage: SimulationStage,
+        events: &mut Vec<NonFiniteEvent>,
+    ) {
+        for mut body in bodies.iter_mut() {
+            if body.is_static() || is_finite(&body) {
+                continue;
+            }
+            let id = body.id;
+            if self.policy == NonFinitePolicy::Panic {
+                panic!("Body {id:?} became non-finite during {stage}");
+            }
+            log::warn!(
+                "Body {id:?} became non-finite during {stage}; applying {:?}",
+                self.policy
+            );
+
+            let index = id.index();
+            let transform = self.transforms.get(index).copied();
+            let velocity = self.velocities.get(index).copied();
+            *body.acceleration = glam::Vec3::ZERO;
+            match self.policy {
+                NonFinitePolicy::Freeze => {
+                    if !transform_is_finite(body.transform) {
+                        *body.transform = transform.unwrap_or_default();
+                    }
+                    body.set_velocity(glam::Vec3::ZERO, glam::Vec3::ZERO);
+                    body.flags.is_enabled = false;
+                    body.flags.is_awake = false;
+                }
+                NonFinitePolicy::ResetToLastGood => {
+                    *body.transform = transform.unwrap_or_default();
+                    *body.velocity = velocity.unwrap_or_default();
+                }
+                NonFinitePolicy::Panic => unreachable!(),
+            }
+            events.push(NonFiniteEvent {
+                body: id,
+                stage,
+                policy: self.policy,
+            });
+        }
+    }
+}
+
+fn transform_is_finite(transform: &Transform) -> bool {
+    transform.position.is_finite() && transform.rotation.is_finite() && transform.scale.is_finite()
+}
+
+fn is_finite(body: &BodyMut) -> bool {
+    transform_is_finite(body.transform)
+        && body.velocity.linear.is_finite()
+        && body.velocity.angular.is_finite()
+        && body.acceleration.is_finite()
+}
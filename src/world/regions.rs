@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+
+use glam::Vec3;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{broadphase::BroadPhase, queries::world_bounds},
+    config::DEFAULT_REGION_MARGIN,
+    core::{collider::Collider, mesh::Aabb, soa::BodiesSoA, types::Velocity},
+    utils::allocator::{Arena, EntityId},
+};
+
+/// What a body was doing when its region went inactive, restored when it thaws.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub(crate) struct FrozenBody {
+    velocity: Velocity,
+    enabled: bool,
+}
+
+/// Active simulation regions and the bodies frozen for lying outside all of them.
+///
+/// Frozen bodies are disabled, so they are neither integrated nor woken, and their
+/// colliders are taken out of the broad phase. With no active regions nothing is frozen.
+pub(crate) struct SimulationRegions {
+    regions: Arena<Aabb>,
+    margin: f32,
+    frozen: HashMap<EntityId, FrozenBody>,
+}
+
+impl Default for SimulationRegions {
+    fn default() -> Self {
+        Self {
+            regions: Arena::new(),
+            margin: DEFAULT_REGION_MARGIN,
+            frozen: HashMap::new(),
+        }
+    }
+}
+
+impl SimulationRegions {
+    pub(crate) fn activate(&mut self, bounds: Aabb) -> EntityId {
+        self.regions.insert(bounds)
+    }
+
+    pub(crate) fn deactivate(&mut self, id: EntityId) -> Option<Aabb> {
+        self.regions.remove(id)
+    }
+
+    pub(crate) fn regions(&self) -> impl Iterator<Item = &Aabb> {
+        self.regions.iter()
+    }
+
+    pub(crate) fn margin(&self) -> f32 {
+        self.margin
+    }
+
+    pub(crate) fn set_margin(&mut self, margin: f32) {
+        self.margin = margin.max(0.0);
+    }
+
+    pub(crate) fn is_frozen(&self, body: EntityId) -> bool {
+        self.frozen.contains_key(&body)
+    }
+
+    /// Freezes bodies that are now further than the margin outside every region and thaws
+    /// those that came back in reach. A body is in reach if the bounds of any of its
+    /// colliders, or its origin if it has none, touch a region grown by the margin.
+    pub(crate) fn update(
+        &mut self,
+        bodies: &mut BodiesSoA,
+        colliders: &Arena<Collider>,
+        broadphase: &mut BroadPhase,
+    ) {
+        if self.regions.is_empty() {
+            let frozen: Vec<EntityId> = self.frozen.keys().copied().collect();
+            for body in frozen {
+                self.thaw(body, bodies, broadphase);
+            }
+            return;
+        }
+
+        let margin = Vec3::splat(self.margin);
+        let reach: Vec<Aabb> = self
+            .regions
+            .iter()
+            .map(|region| Aabb::new(region.min - margin, region.max + margin))
+            .collect();
+        let touches = |bounds: &Aabb| reach.iter().any(|region| region.overlaps(bounds));
+
+        let mut with_colliders = HashSet::new();
+        let mut in_reach = HashSet::new();
+        for collider in colliders.iter() {
+            let Some(body) = bodies.get(collider.rigidbody_id) else {
+                continue;
+            };
+            with_colliders.insert(body.id());
+            if in_reach.contains(&body.id()) {
+                continue;
+            }
+            let transform = collider.world_transform(body.transform());
+            let (min, max) = world_bounds(&collider.shape, &transform);
+            if touches(&Aabb::new(min, max)) {
+                in_reach.insert(body.id());
+            }
+        }
+
+        let mut changes = Vec::new();
+        for body in bodies.iter() {
+            let id = body.id();
+            let inside = if with_colliders.contains(&id) {
+                in_reach.contains(&id)
+            } else {
+                let position = body.transform().position;
+                touches(&Aabb::new(position, position))
+            };
+            if inside == self.is_frozen(id) {
+                changes.push((id, inside));
+            }
+        }
+        for (body, thaw) in changes {
+            if thaw {
+                self.thaw(body, bodies, broadphase);
+            } else {
+                self.freeze(body, bodies, broadphase);
+            }
+        }
+    }
+
+    fn freeze(&mut self, id: EntityId, bodies: &mut BodiesSoA, broadphase: &mut BroadPhase) {
+        let Some(body) = bodies.get_mut(id) else {
+            return;
+        };
+        self.frozen.insert(
+            id,
+            FrozenBody {
+                velocity: *body.velocity,
+                enabled: body.flags.is_enabled,
+            },
+        );
+        body.flags.is_enabled = false;
+        broadphase.set_body_frozen(id, true);
+    }
+
+    /// Puts a frozen body back the way it was frozen. Returns `false` if it was not frozen.
+    pub(crate) fn thaw(
+        &mut self,
+        id: EntityId,
+        bodies: &mut BodiesSoA,
+        broadphase: &mut BroadPhase,
+    ) -> bool {
+        let Some(frozen) = self.frozen.remove(&id) else {
+            return false;
+        };
+        broadphase.set_body_frozen(id, false);
+        if let Some(body) = bodies.get_mut(id) {
+            // Forces keep accumulating on disabled bodies; drop them with the freeze.
+            *body.acceleration = Vec3::ZERO;
+            *body.velocity = frozen.velocity;
+            body.flags.is_enabled = frozen.enabled;
+            body.flags.is_awake = true;
+        }
+        true
+    }
+
+    /// Frozen bodies as stored in a [`WorldState`](super::state::WorldState), sorted.
+    pub(crate) fn frozen(&self) -> Vec<(EntityId, FrozenBody)> {
+        let mut frozen: Vec<_> = self.frozen.iter().map(|(id, body)| (*id, *body)).collect();
+        frozen.sort_by_key(|(id, _)| *id);
+        frozen
+    }
+
+    pub(crate) fn restore(
+        &mut self,
+        frozen: &[(EntityId, FrozenBody)],
+        broadphase: &mut BroadPhase,
+    ) {
+        for id in self.frozen.keys() {
+            broadphase.set_body_frozen(*id, false);
+        }
+        self.frozen = frozen.iter().copied().collect();
+        for id in self.frozen.keys() {
+            broadphase.set_body_frozen(*id, true);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Complete simulation state of a [`PhysicsWorld`], for save games and rollback.
+
+use std::collections::VecDeque;
+
+use glam::{Quat, Vec3};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{contact::PersistentManifold, trigger::TriggerOverlap},
+    core::{
+        articulations::Multibody,
+        collider::Collider,
+        constraints::{Joint, JointBreakLimit},
+        soa::BodiesSoA,
+    },
+    dynamics::{
+        cloth::Cloth, fields::ForceField, fluid::Fluid, particles::ParticleSystem,
+        soft_body::SoftBody, solver::JointImpulse,
+    },
+    utils::allocator::{Arena, EntityId},
+    world::{regions::FrozenBody, PhysicsWorld},
+};
+
+/// Layout version written into every [`WorldState`]; bumped whenever the layout changes.
+pub const WORLD_STATE_VERSION: u32 = 2;
+
+/// Everything a [`PhysicsWorld`] needs to carry on stepping exactly where it left off.
+///
+/// Configuration is not part of the state: solver and integrator settings, material pairs,
+/// force generators, step hooks and backends stay with the world the state is restored
+/// into, which should be set up the way the saved one was. With the `serde` feature the
+/// state serializes with any serde format.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WorldState {
+    version: u32,
+    frame_index: u32,
+    time_accumulated: f32,
+    time_step: f32,
+    gravity: Vec3,
+    bodies: BodiesSoA,
+    colliders: Arena<Collider>,
+    joints: Vec<Joint>,
+    joint_priorities: Vec<i32>,
+    joint_break_limits: Vec<Option<JointBreakLimit>>,
+    joint_warm_starts: Vec<JointImpulse>,
+    force_fields: Arena<ForceField>,
+    // Maps are stored as sorted lists so formats with string-only keys can hold them.
+    body_priorities: Vec<(EntityId, i32)>,
+    kinematic_targets: Vec<(EntityId, (Vec3, Quat))>,
+    mass_center_shifts: Vec<(EntityId, Vec3)>,
+    link_bodies: Vec<(EntityId, (EntityId, usize))>,
+    frozen_bodies: Vec<(EntityId, FrozenBody)>,
+    manifolds: Vec<PersistentManifold>,
+    trigger_overlaps: Vec<TriggerOverlap>,
+    articulated_bodies: Arena<Multibody>,
+    particles: ParticleSystem,
+    fluid: Fluid,
+    cloths: Arena<Cloth>,
+    soft_bodies: Arena<SoftBody>,
+}
+
+impl WorldState {
+    pub(crate) fn capture(world: &PhysicsWorld) -> Self {
+        let dynamics = &world.dynamics;
+        Self {
+            version: WORLD_STATE_VERSION,
+            frame_index: world.frame_index,
+            time_accumulated: world.time_accumulated,
+            time_step: world.time_step,
+            gravity: world.gravity,
+            bodies: world.bodies.clone(),
+            colliders: world.colliders.clone(),
+            joints: dynamics.joints.clone(),
+            joint_priorities: dynamics.joint_priorities.clone(),
+            joint_break_limits: dynamics.joint_break_limits.clone(),
+            joint_warm_starts: dynamics.joint_warm_starts.clone(),
+            force_fields: dynamics.force_fields.clone(),
+            body_priorities: sorted(dynamics.body_priorities.iter()),
+            kinematic_targets: sorted(world.kinematic_targets.iter()),
+            mass_center_shifts: sorted(world.mass_center_shifts.iter()),
+            link_bodies: sorted(world.link_bodies.iter()),
+            frozen_bodies: world.regions.frozen(),
+            manifolds: world.collision.manifold_cache.persisted(),
+            trigger_overlaps: world.collision.triggers.overlaps().to_vec(),
+            articulated_bodies: world.articulated_bodies.clone(),
+            particles: world.particles.clone(),
+            fluid: world.fluid.clone(),
+            cloths: world.cloths.clone(),
+            soft_bodies: world.soft_bodies.clone(),
+        }
+    }
+
+    /// Writes the state into `world`, dropping anything the last step left pending.
+    pub(crate) fn apply(&self, world: &mut PhysicsWorld) {
+        world.frame_index = self.frame_index;
+        world.time_accumulated = self.time_accumulated;
+        world.time_step = self.time_step;
+        world.integrator.dt = self.time_step / world.integrator.substeps as f32;
+        world.gravity = self.gravity;
+        world.bodies.clone_from(&self.bodies);
+        world.colliders = self.colliders.clone();
+
+        let dynamics = &mut world.dynamics;
+        dynamics.joints.clone_from(&self.joints);
+        dynamics.joint_priorities.clone_from(&self.joint_priorities);
+        dynamics
+            .joint_break_limits
+            .clone_from(&self.joint_break_limits);
+        dynamics
+            .joint_warm_starts
+            .clone_from(&self.joint_warm_starts);
+        dynamics.force_fields = self.force_fields.clone();
+        dynamics.body_priorities = self.body_priorities.iter().copied().collect();
+
+        world.kinematic_targets = self.kinematic_targets.iter().copied().collect();
+        world.mass_center_shifts = self.mass_center_shifts.iter().copied().collect();
+        world.link_bodies = self.link_bodies.iter().copied().collect();
+
+        let collision = &mut world.collision;
+        collision
+            .manifold_cache
+            .restore(&self.manifolds, self.frame_index);
+        collision.triggers.restore(&self.trigger_overlaps);
+        world
+            .regions
+            .restore(&self.frozen_bodies, &mut collision.broadphase);
+        // Incremental broadphases rebuild from the restored poses.
+        collision.broadphase.sweep_and_prune_mut().clear();
+        collision.broadphase.pair_manager_mut().clear();
+        world.query_pipeline.update(&world.colliders, &world.bodies);
+
+        world.articulated_bodies = self.articulated_bodies.clone();
+        world.particles.clone_from(&self.particles);
+        // GPU particles restart from the snapshot's CPU copies.
+        world.particles.set_gpu_resident(false);
+        world.fluid.clone_from(&self.fluid);
+        world.cloths = self.cloths.clone();
+        world.soft_bodies = self.soft_bodies.clone();
+
+        world.contact_events.clear();
+        world.trigger_events.clear();
+        world.non_finite_events.clear();
+        world.joint_broken_events.clear();
+        world.non_finite_guard.capture(&world.bodies);
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Number of fixed steps the world had run when the state was saved.
+    pub fn frame_index(&self) -> u32 {
+        self.frame_index
+    }
+}
+
+fn sorted<'a, K: Ord + Copy + 'a, V: Copy + 'a>(
+    entries: impl Iterator<Item = (&'a K, &'a V)>,
+) -> Vec<(K, V)> {
+    let mut entries: Vec<(K, V)> = entries.map(|(key, value)| (*key, *value)).collect();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
+}
+
+/// Ring buffer of the states a world had after each of its last few fixed steps.
+#[derive(Default)]
+pub(crate) struct StateHistory {
+    states: VecDeque<WorldState>,
+    capacity: usize,
+}
+
+impl StateHistory {
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub(crate) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.states.len() > capacity {
+            self.states.pop_front();
+        }
+    }
+
+    pub(crate) fn push(&mut self, state: WorldState) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        self.states.push_back(state);
+    }
+
+    /// Drops every state newer than the one saved `frames` steps before the newest and
+    /// returns it, or `None` if the history does not reach that far back.
+    pub(crate) fn rewind(&mut self, frames: usize) -> Option<&WorldState> {
+        let keep = self.states.len().checked_sub(frames)?;
+        if keep == 0 {
+            return None;
+        }
+        self.states.truncate(keep);
+        self.states.back()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.states.clear();
+    }
+}
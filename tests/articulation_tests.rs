@@ -1,7 +1,10 @@
 use glam::{Mat3, Vec3};
 use particle_accelerator::core::articulations::{
-    JointType as ArticulatedJointType, Link, Multibody,
+    JointType as ArticulatedJointType, Link, LinkMotor, Multibody,
 };
+use particle_accelerator::core::collider::ColliderBuilder;
+use particle_accelerator::core::constraints::MotorMode;
+use particle_accelerator::core::rigidbody::RigidBody;
 use particle_accelerator::core::types::Transform;
 use particle_accelerator::utils::allocator::EntityId;
 use particle_accelerator::world::PhysicsWorld;
@@ -96,3 +99,132 @@ fn test_double_pendulum_under_gravity() {
     assert!(mb_ref.q[0].abs() > 0.01);
     assert!(mb_ref.q[1].abs() > 0.01);
 }
+
+/// Fixed root at the origin with one revolute arm along +X, swinging about Z.
+fn arm(length: f32) -> Multibody {
+    let mut mb = Multibody::new(EntityId::default());
+    mb.add_link(Link::new("root", None, ArticulatedJointType::Fixed));
+    let mut link = Link::new(
+        "arm",
+        Some(0),
+        ArticulatedJointType::Revolute { axis: Vec3::Z },
+    );
+    link.mass = 1.0;
+    link.com_offset = Vec3::X * (length * 0.5);
+    link.inertia = Mat3::IDENTITY * (length * length / 12.0);
+    mb.add_link(link);
+    mb
+}
+
+#[test]
+fn link_limits_and_motors() {
+    // Gravity drops the arm onto its lower limit.
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut mb = arm(1.0);
+    mb.links[1].limit = Some((-0.3, 0.3));
+    world.add_multibody(mb);
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    let q = world.articulated_bodies.iter().next().unwrap().q[0];
+    assert!((q + 0.3).abs() < 1e-4, "limited arm at {q}");
+
+    // A servo holds the arm up against gravity.
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut mb = arm(1.0);
+    mb.links[1].motor = Some(LinkMotor {
+        speed: 0.0,
+        max_effort: 50.0,
+        mode: MotorMode::Position {
+            target: 0.5,
+            stiffness: 500.0,
+            damping: 50.0,
+        },
+    });
+    world.add_multibody(mb);
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+    }
+    let held = world.articulated_bodies.iter().next().unwrap();
+    assert!((held.q[0] - 0.5).abs() < 0.05, "servo at {}", held.q[0]);
+    assert!(held.dq[0].abs() < 0.05);
+
+    // A velocity motor without gravity settles on its speed.
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.gravity = Vec3::ZERO;
+    let mut mb = arm(1.0);
+    mb.links[1].motor = Some(LinkMotor {
+        speed: 2.0,
+        max_effort: 100.0,
+        mode: MotorMode::Velocity,
+    });
+    world.add_multibody(mb);
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let spinning = world.articulated_bodies.iter().next().unwrap();
+    assert!(
+        (spinning.dq[0] - 2.0).abs() < 1e-3,
+        "motor at {}",
+        spinning.dq[0]
+    );
+}
+
+#[test]
+fn rigid_bodies_collide_with_links() {
+    let settle = |with_crate: bool| {
+        let mut world = PhysicsWorld::new(1.0 / 60.0);
+        let mut plank_body = RigidBody::new(EntityId::default());
+        plank_body.material.restitution = 0.0;
+        let proxy = world.add_rigidbody(plank_body);
+        let mut plank = ColliderBuilder::new()
+            .box_shape(Vec3::new(1.0, 0.05, 0.5))
+            .offset(Transform::from_position(Vec3::X))
+            .build();
+        plank.rigidbody_id = proxy;
+        world.add_collider(plank);
+
+        let mut mb = arm(2.0);
+        mb.links[1].collision_body = Some(proxy);
+        mb.links[1].motor = Some(LinkMotor {
+            speed: 0.0,
+            max_effort: 200.0,
+            mode: MotorMode::Position {
+                target: 0.0,
+                stiffness: 200.0,
+                damping: 40.0,
+            },
+        });
+        world.add_multibody(mb);
+
+        let mut crate_id = None;
+        if with_crate {
+            let mut body = RigidBody::new(EntityId::default());
+            body.transform.position = Vec3::new(1.0, 0.3, 0.0);
+            body.material.restitution = 0.0;
+            let id = world.add_rigidbody(body);
+            let mut shape = ColliderBuilder::new().box_shape(Vec3::splat(0.2)).build();
+            shape.rigidbody_id = id;
+            world.add_collider(shape);
+            crate_id = Some(id);
+        }
+
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        let q = world.articulated_bodies.iter().next().unwrap().q[0];
+        let crate_height = crate_id.map(|id| world.body(id).unwrap().transform().position.y);
+        (q, crate_height)
+    };
+
+    let (unloaded, _) = settle(false);
+    let (loaded, crate_height) = settle(true);
+    let crate_height = crate_height.unwrap();
+    // The crate rests on the plank instead of falling through it...
+    assert!(crate_height > 0.0, "crate fell to {crate_height}");
+    // ...and its weight bends the servo further down than gravity on the arm alone.
+    assert!(
+        loaded < unloaded - 0.01,
+        "loaded {loaded} vs unloaded {unloaded}"
+    );
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "bevy")]
+
+use std::time::Duration;
+
+use bevy_app::{App, FixedUpdate};
+use bevy_ecs::entity::Entity;
+use bevy_time::Time;
+use bevy_transform::components::Transform;
+use particle_accelerator::bevy::{ColliderComponent, PhysicsPlugin, RigidBodyComponent};
+use particle_accelerator::*;
+
+fn run_fixed_updates(app: &mut App, count: usize) {
+    for _ in 0..count {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(1.0 / 60.0));
+        app.world_mut().run_schedule(FixedUpdate);
+    }
+}
+
+#[test]
+fn plugin_simulates_entities_and_writes_back_their_transforms() {
+    let mut app = App::new();
+    app.insert_resource(PhysicsWorld::new(1.0 / 60.0))
+        .insert_resource(Time::<()>::default())
+        .add_plugins(PhysicsPlugin);
+
+    let ground = app
+        .world_mut()
+        .spawn((
+            RigidBodyComponent::new(RigidBody::builder().is_static(true).build()),
+            ColliderComponent::new(
+                Collider::builder()
+                    .box_shape(Vec3::new(10.0, 0.5, 10.0))
+                    .build(),
+            ),
+        ))
+        .id();
+    let ball = app
+        .world_mut()
+        .spawn((
+            RigidBodyComponent::new(RigidBody::builder().build()),
+            ColliderComponent::new(Collider::builder().sphere(0.5).build()),
+            Transform::from_xyz(1.0, 3.0, 0.0),
+        ))
+        .id();
+
+    run_fixed_updates(&mut app, 1);
+    let handle = {
+        let entity = app.world().entity(ball);
+        assert!(entity
+            .get::<ColliderComponent>()
+            .unwrap()
+            .handle()
+            .is_some());
+        entity
+            .get::<RigidBodyComponent>()
+            .unwrap()
+            .handle()
+            .unwrap()
+    };
+    let world = app.world().resource::<PhysicsWorld>();
+    assert_eq!(world.body(handle).unwrap().transform().position.x, 1.0);
+    assert!(app
+        .world()
+        .entity(ground)
+        .get::<RigidBodyComponent>()
+        .unwrap()
+        .handle()
+        .is_some());
+
+    // The entity follows the ball exactly as a world built and stepped by hand moves it.
+    let mut reference = PhysicsWorld::new(1.0 / 60.0);
+    let floor = reference.add_rigidbody(RigidBody::builder().is_static(true).build());
+    reference.add_collider(Collider {
+        rigidbody_id: floor,
+        ..Collider::builder()
+            .box_shape(Vec3::new(10.0, 0.5, 10.0))
+            .build()
+    });
+    let mut body = RigidBody::builder().build();
+    body.transform.position = Vec3::new(1.0, 3.0, 0.0);
+    let expected = reference.add_rigidbody(body);
+    reference.add_collider(Collider {
+        rigidbody_id: expected,
+        ..Collider::builder().sphere(0.5).build()
+    });
+    for _ in 0..120 {
+        reference.step(1.0 / 60.0);
+    }
+
+    run_fixed_updates(&mut app, 119);
+    let transform = *app.world().entity(ball).get::<Transform>().unwrap();
+    let pose = *reference.body(expected).unwrap().transform();
+    assert_ne!(pose.position, Vec3::new(1.0, 3.0, 0.0));
+    assert_eq!(transform.translation, pose.position);
+    assert_eq!(transform.rotation, pose.rotation);
+}
+
+#[test]
+fn plugin_removes_bodies_and_colliders_of_despawned_entities() {
+    let mut app = App::new();
+    app.insert_resource(PhysicsWorld::new(1.0 / 60.0))
+        .insert_resource(Time::<()>::default())
+        .add_plugins(PhysicsPlugin);
+
+    let spawn_ball = |app: &mut App, x: f32| {
+        app.world_mut()
+            .spawn((
+                RigidBodyComponent::new(RigidBody::builder().build()),
+                ColliderComponent::new(Collider::builder().sphere(0.5).build()),
+                Transform::from_xyz(x, 3.0, 0.0),
+            ))
+            .id()
+    };
+    let doomed = spawn_ball(&mut app, 0.0);
+    let stripped = spawn_ball(&mut app, 3.0);
+    run_fixed_updates(&mut app, 1);
+
+    let handles = |app: &App, entity: Entity| {
+        let entity = app.world().entity(entity);
+        (
+            entity
+                .get::<RigidBodyComponent>()
+                .unwrap()
+                .handle()
+                .unwrap(),
+            entity.get::<ColliderComponent>().unwrap().handle().unwrap(),
+        )
+    };
+    let (doomed_body, doomed_collider) = handles(&app, doomed);
+    let (stripped_body, stripped_collider) = handles(&app, stripped);
+
+    app.world_mut().despawn(doomed);
+    app.world_mut()
+        .entity_mut(stripped)
+        .remove::<ColliderComponent>();
+    run_fixed_updates(&mut app, 1);
+
+    let world = app.world().resource::<PhysicsWorld>();
+    assert!(world.body(doomed_body).is_none());
+    assert!(world.colliders.get(doomed_collider).is_none());
+    // Losing only the collider keeps the body simulated.
+    assert!(world.body(stripped_body).is_some());
+    assert!(world.colliders.get(stripped_collider).is_none());
+}
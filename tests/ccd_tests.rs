@@ -41,6 +41,8 @@ fn fast_sphere_hits_thin_wall() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(wall_collider);
 
@@ -51,6 +53,7 @@ fn fast_sphere_hits_thin_wall() {
     bullet_body.transform.position = Vec3::new(0.0, 0.0, 0.0);
     bullet_body.velocity.linear = Vec3::new(0.0, 0.0, 600.0);
     bullet_body.inverse_mass = 1.0;
+    bullet_body.ccd_enabled = true;
     bullet_body.material.restitution = 1.0; // Perfect bounce
     let bullet_id = world.add_rigidbody(bullet_body);
 
@@ -61,6 +64,8 @@ fn fast_sphere_hits_thin_wall() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(bullet_collider);
 
@@ -111,6 +116,8 @@ fn fast_box_hits_wall_ccd() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(wall_collider);
 
@@ -119,6 +126,7 @@ fn fast_box_hits_wall_ccd() {
     box_body.transform.position = Vec3::new(0.0, 0.0, 0.0);
     box_body.velocity.linear = Vec3::new(0.0, 0.0, 600.0); // 10m/frame
     box_body.inverse_mass = 1.0;
+    box_body.ccd_enabled = true;
     box_body.material.restitution = 1.0;
     let box_id = world.add_rigidbody(box_body);
 
@@ -131,6 +139,8 @@ fn fast_box_hits_wall_ccd() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(box_collider);
 
@@ -146,3 +156,81 @@ fn fast_box_hits_wall_ccd() {
     assert!(final_body.transform().position.z < 11.0, "Box tunneled!");
     assert!(final_body.velocity().linear.z < 0.0, "Box did not bounce!");
 }
+
+#[test]
+fn iterative_ccd_quality_finds_surface_normal_for_glancing_hits() {
+    use particle_accelerator::collision::ccd::{CCDDetector, CcdQuality};
+
+    let mut wall = RigidBody::new(EntityId::from_index(1));
+    wall.transform.position = Vec3::new(0.0, 0.0, 5.0);
+    wall.is_static = true;
+    let mut wall_collider = Collider::builder()
+        .box_shape(Vec3::new(10.0, 10.0, 0.1))
+        .build();
+    wall_collider.rigidbody_id = wall.id;
+
+    // Grazing approach: mostly sideways, closing on the wall at 300 m/s.
+    let mut bullet = RigidBody::new(EntityId::from_index(2));
+    bullet.velocity.linear = Vec3::new(600.0, 0.0, 300.0);
+    bullet.ccd_enabled = true;
+    let mut bullet_collider = Collider::builder().sphere(0.2).build();
+    bullet_collider.rigidbody_id = bullet.id;
+
+    let mut ccd = CCDDetector::new();
+    ccd.set_quality(CcdQuality::Iterative);
+    let hit = ccd
+        .detect_ccd(&bullet, &bullet_collider, &wall, &wall_collider, 1.0 / 60.0)
+        .expect("bullet should hit the wall this frame");
+    assert!(
+        hit.contact.normal.dot(Vec3::Z) > 0.99,
+        "normal {:?}",
+        hit.contact.normal
+    );
+    assert!(hit.time_of_impact <= 1.0 / 60.0);
+}
+
+#[test]
+fn ccd_velocity_clamp_caps_rebound_speed() {
+    let run = |clamp: Option<f32>| {
+        let mut world = make_world();
+        world.gravity = Vec3::ZERO;
+        world.set_ccd_velocity_clamp(clamp);
+
+        // A heavy paddle swinging into the bullet knocks it back hard.
+        let mut paddle = RigidBody::new(EntityId::from_index(1));
+        paddle.transform.position = Vec3::new(0.0, 0.0, 5.0);
+        paddle.velocity.linear = Vec3::new(0.0, 0.0, -300.0);
+        paddle.mass_properties.mass = 1000.0;
+        paddle.material.restitution = 1.0;
+        let paddle_id = world.add_rigidbody(paddle);
+        let mut paddle_collider = Collider::builder()
+            .box_shape(Vec3::new(10.0, 10.0, 0.1))
+            .build();
+        paddle_collider.rigidbody_id = paddle_id;
+        world.add_collider(paddle_collider);
+
+        let mut bullet = RigidBody::new(EntityId::from_index(2));
+        bullet.velocity.linear = Vec3::new(0.0, 0.0, 600.0);
+        bullet.ccd_enabled = true;
+        bullet.material.restitution = 1.0;
+        let bullet_id = world.add_rigidbody(bullet);
+        let mut bullet_collider = Collider::builder().sphere(0.2).build();
+        bullet_collider.rigidbody_id = bullet_id;
+        world.add_collider(bullet_collider);
+
+        world.step(1.0 / 60.0);
+        world.body(bullet_id).unwrap().velocity().linear.length()
+    };
+
+    // Half the 600 m/s approach speed.
+    let unclamped = run(None);
+    let clamped = run(Some(0.5));
+    assert!(
+        unclamped > 300.0,
+        "rebound should exceed the cap: {unclamped}"
+    );
+    assert!(
+        clamped <= 300.0 + 1e-3,
+        "clamp should cap the rebound: {clamped}"
+    );
+}
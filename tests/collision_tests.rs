@@ -1,6 +1,8 @@
-use particle_accelerator::core::soa::BodiesSoA;
+use particle_accelerator::collision::narrowphase::NarrowPhase;
+use particle_accelerator::core::{heightfield::Heightfield, soa::BodiesSoA};
 use particle_accelerator::utils::allocator::Arena;
 use particle_accelerator::*;
+use std::sync::Arc;
 
 fn make_box_body(id: u32, position: Vec3) -> (RigidBody, Collider) {
     let mut body = RigidBody::new(EntityId::from_index(id));
@@ -15,6 +17,8 @@ fn make_box_body(id: u32, position: Vec3) -> (RigidBody, Collider) {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
 
     (body, collider)
@@ -80,3 +84,811 @@ fn broadphase_returns_overlapping_pair() {
         "broadphase missed overlapping colliders"
     );
 }
+
+#[test]
+fn sweep_and_prune_tracks_moving_pair() {
+    let (body_a, mut collider_a) = make_box_body(4, Vec3::ZERO);
+    let (body_b, mut collider_b) = make_box_body(5, Vec3::new(0.2, 0.0, 0.0));
+    let mut broadphase = BroadPhase::with_backend(1.0, BroadPhaseBackend::SweepAndPrune);
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    let body_a_id = bodies.insert(body_a);
+    let body_b_id = bodies.insert(body_b);
+
+    collider_a.rigidbody_id = body_a_id;
+    let collider_a_id = colliders.insert(collider_a);
+    colliders.get_mut(collider_a_id).unwrap().id = collider_a_id;
+    collider_b.rigidbody_id = body_b_id;
+    let collider_b_id = colliders.insert(collider_b);
+    colliders.get_mut(collider_b_id).unwrap().id = collider_b_id;
+
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert_eq!(pairs, vec![(collider_a_id, collider_b_id)]);
+
+    bodies.get_mut(body_b_id).unwrap().transform.position = Vec3::new(5.0, 0.0, 0.0);
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert!(pairs.is_empty(), "separated colliders should not pair");
+
+    bodies.get_mut(body_b_id).unwrap().transform.position = Vec3::new(-0.3, 0.0, 0.0);
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert_eq!(pairs, vec![(collider_a_id, collider_b_id)]);
+
+    colliders.remove(collider_b_id);
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert!(pairs.is_empty());
+    assert_eq!(broadphase.sweep_and_prune().proxy_count(), 1);
+}
+
+#[test]
+fn query_sphere_matches_brute_force_with_every_backend() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    for i in 0..64u32 {
+        let position = Vec3::new((i % 8) as f32 * 1.5, 0.0, (i / 8) as f32 * 1.5);
+        let (body, mut collider) = make_box_body(i, position);
+        collider.rigidbody_id = bodies.insert(body);
+        let id = colliders.insert(collider);
+        colliders.get_mut(id).unwrap().id = id;
+    }
+    let reach = Vec3::splat(0.5).length();
+    let brute_force = |center: Vec3, radius: f32| {
+        let mut hits: Vec<EntityId> = colliders
+            .iter()
+            .filter(|collider| {
+                let position = bodies
+                    .get(collider.rigidbody_id)
+                    .unwrap()
+                    .transform()
+                    .position;
+                position.distance(center) <= radius + reach
+            })
+            .map(|collider| collider.id)
+            .collect();
+        hits.sort();
+        hits
+    };
+    let queries = [
+        (Vec3::new(3.0, 0.0, 3.0), 2.0),
+        (Vec3::new(-1.0, 0.5, 5.0), 0.4),
+        (Vec3::new(5.0, 3.0, 5.0), 0.1),
+    ];
+
+    for backend in [
+        BroadPhaseBackend::Grid,
+        BroadPhaseBackend::SweepAndPrune,
+        BroadPhaseBackend::Incremental,
+    ] {
+        let mut broadphase = BroadPhase::with_backend(1.0, backend);
+        for (center, radius) in queries {
+            let hits = broadphase.query_sphere(center, radius, &colliders, &bodies);
+            assert_eq!(
+                hits,
+                brute_force(center, radius),
+                "{backend:?} before an update"
+            );
+        }
+        broadphase.get_potential_pairs(&colliders, &bodies);
+        for (center, radius) in queries {
+            let hits = broadphase.query_sphere(center, radius, &colliders, &bodies);
+            assert_eq!(hits, brute_force(center, radius), "{backend:?}");
+        }
+    }
+}
+
+#[test]
+fn pair_manager_keeps_resting_pairs_and_reports_changes() {
+    let mut broadphase = BroadPhase::with_backend(1.0, BroadPhaseBackend::Incremental);
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let mut ids = Vec::new();
+    for (i, x) in [0.0, 0.8, 5.0].into_iter().enumerate() {
+        let (body, mut collider) = make_box_body(20 + i as u32, Vec3::new(x, 0.0, 0.0));
+        let body_id = bodies.insert(body);
+        collider.rigidbody_id = body_id;
+        let collider_id = colliders.insert(collider);
+        colliders.get_mut(collider_id).unwrap().id = collider_id;
+        ids.push((body_id, collider_id));
+    }
+    let (a, b, c) = (ids[0].1, ids[1].1, ids[2].1);
+
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert_eq!(pairs, vec![(a, b)]);
+    assert_eq!(broadphase.pair_manager().added_pairs(), &[(a, b)]);
+    assert_eq!(broadphase.pair_manager().moved_count(), 3);
+
+    // Jitter inside the fat margin re-tests nothing and keeps the cached pair.
+    bodies.get_mut(ids[1].0).unwrap().transform.position.x += 0.05;
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert_eq!(pairs, vec![(a, b)]);
+    assert_eq!(broadphase.pair_manager().moved_count(), 0);
+    assert!(broadphase.pair_manager().added_pairs().is_empty());
+
+    // Moving the far box next to the second swaps one pair for another.
+    bodies.get_mut(ids[0].0).unwrap().transform.position.x = -5.0;
+    bodies.get_mut(ids[2].0).unwrap().transform.position.x = 1.6;
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert_eq!(pairs, vec![(b, c)]);
+    assert_eq!(broadphase.pair_manager().moved_count(), 2);
+    assert_eq!(broadphase.pair_manager().added_pairs(), &[(b, c)]);
+    assert_eq!(broadphase.pair_manager().removed_pairs(), &[(a, b)]);
+
+    colliders.remove(c);
+    let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+    assert!(pairs.is_empty());
+    assert_eq!(broadphase.pair_manager().removed_pairs(), &[(b, c)]);
+    assert_eq!(broadphase.pair_manager().proxy_count(), 2);
+}
+
+#[test]
+fn speculative_expansion_pairs_fast_ccd_bodies() {
+    for backend in [
+        BroadPhaseBackend::Grid,
+        BroadPhaseBackend::SweepAndPrune,
+        BroadPhaseBackend::Incremental,
+    ] {
+        let (mut body_a, mut collider_a) = make_box_body(6, Vec3::ZERO);
+        let (body_b, mut collider_b) = make_box_body(7, Vec3::new(2.5, 0.0, 0.0));
+        body_a.velocity.linear = Vec3::new(60.0, 0.0, 0.0);
+        let mut broadphase = BroadPhase::with_backend(1.0, backend);
+        broadphase.set_speculative_expansion(0.05, 1.0 / 60.0);
+        let mut bodies = BodiesSoA::new();
+        let mut colliders = Arena::new();
+
+        let body_a_id = bodies.insert(body_a);
+        let body_b_id = bodies.insert(body_b);
+        collider_a.rigidbody_id = body_a_id;
+        let collider_a_id = colliders.insert(collider_a);
+        colliders.get_mut(collider_a_id).unwrap().id = collider_a_id;
+        collider_b.rigidbody_id = body_b_id;
+        let collider_b_id = colliders.insert(collider_b);
+        colliders.get_mut(collider_b_id).unwrap().id = collider_b_id;
+
+        let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+        assert!(
+            pairs.is_empty(),
+            "{backend:?}: bodies without CCD are not padded"
+        );
+
+        bodies.get_mut(body_a_id).unwrap().flags.ccd_enabled = true;
+        let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+        assert_eq!(
+            pairs,
+            vec![(collider_a_id, collider_b_id)],
+            "{backend:?}: one step of travel should reach the other box"
+        );
+    }
+}
+
+#[test]
+fn pair_changes_survive_a_world_step() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::ZERO)
+        .broadphase_backend(BroadPhaseBackend::Incremental)
+        .build();
+    let mut ids = Vec::new();
+    for x in [0.0, 10.0] {
+        let mut body = RigidBody::new(EntityId::from_index(0));
+        body.transform.position = Vec3::new(x, 0.0, 0.0);
+        let body_id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = body_id;
+        ids.push((body_id, world.add_collider(collider)));
+    }
+    let pair = (ids[0].1, ids[1].1);
+    let dt = world.time_step;
+    world.step(dt);
+    assert_eq!(world_pair_changes(&world), (vec![], vec![]));
+
+    world.bodies.get_mut(ids[1].0).unwrap().transform.position.x = 0.8;
+    world.step(dt);
+    assert_eq!(world_pair_changes(&world), (vec![pair], vec![]));
+
+    world.bodies.get_mut(ids[1].0).unwrap().transform.position.x = 10.0;
+    world.step(dt);
+    assert_eq!(world_pair_changes(&world), (vec![], vec![pair]));
+}
+
+type PairList = Vec<(EntityId, EntityId)>;
+
+fn world_pair_changes(world: &PhysicsWorld) -> (PairList, PairList) {
+    let manager = world.collision.broadphase.pair_manager();
+    (
+        manager.added_pairs().to_vec(),
+        manager.removed_pairs().to_vec(),
+    )
+}
+
+#[test]
+fn broadphase_applies_collision_layers_and_pair_filter() {
+    const PLAYER: CollisionGroups = CollisionGroups::layer(0);
+    const ENEMY: CollisionGroups = CollisionGroups::layer(1);
+    const TERRAIN: CollisionGroups = CollisionGroups::layer(2);
+    const PICKUP: CollisionGroups = CollisionGroups::layer(3);
+
+    let layers = [
+        CollisionLayers::group(PLAYER).collides_with(ENEMY | TERRAIN | PICKUP),
+        CollisionLayers::group(ENEMY).ignores(PICKUP),
+        CollisionLayers::group(TERRAIN).collides_with(PLAYER | ENEMY),
+        CollisionLayers::group(PICKUP).collides_with(PLAYER),
+    ];
+    assert_eq!(layers[0].filter().layer, 1);
+    assert!(!layers[1].filter().interacts_with(&layers[3].filter()));
+
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let mut ids = Vec::new();
+    for (i, layer) in layers.iter().enumerate() {
+        let (body, mut collider) =
+            make_box_body(20 + i as u32, Vec3::new(0.1 * i as f32, 0.0, 0.0));
+        collider.rigidbody_id = bodies.insert(body);
+        collider.collision_filter = (*layer).into();
+        let id = colliders.insert(collider);
+        colliders.get_mut(id).unwrap().id = id;
+        ids.push(id);
+    }
+    let [player, enemy, terrain, pickup] = [ids[0], ids[1], ids[2], ids[3]];
+
+    let sorted = |mut pairs: Vec<(EntityId, EntityId)>| {
+        pairs.sort();
+        pairs
+    };
+    let mut broadphase = BroadPhase::new(1.0);
+    let pairs = sorted(broadphase.get_potential_pairs(&colliders, &bodies));
+    assert_eq!(
+        pairs,
+        vec![
+            (player, enemy),
+            (player, terrain),
+            (player, pickup),
+            (enemy, terrain)
+        ]
+    );
+
+    broadphase.set_pair_filter(Some(move |a: &Collider, b: &Collider| {
+        a.id != enemy && b.id != enemy
+    }));
+    let pairs = sorted(broadphase.get_potential_pairs(&colliders, &bodies));
+    assert_eq!(pairs, vec![(player, terrain), (player, pickup)]);
+}
+
+fn make_heightfield_body(id: u32, heightfield: Heightfield) -> (RigidBody, Collider) {
+    let mut body = RigidBody::new(EntityId::from_index(id));
+    body.is_static = true;
+
+    let collider = Collider {
+        id: EntityId::from_index(id + 100),
+        rigidbody_id: body.id,
+        shape: ColliderShape::Heightfield {
+            heightfield: Arc::new(heightfield),
+        },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+
+    (body, collider)
+}
+
+#[test]
+fn heightfield_sphere_contact_points_away_from_terrain() {
+    let (ground, ground_collider) = make_heightfield_body(20, Heightfield::flat(5, 5, Vec3::ONE));
+    let mut ball = RigidBody::new(EntityId::from_index(21));
+    ball.transform.position = Vec3::new(0.3, 0.4, -0.2);
+    let ball_collider = Collider {
+        id: EntityId::from_index(121),
+        rigidbody_id: ball.id,
+        shape: ColliderShape::Sphere { radius: 0.5 },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+
+    let manifold = ContactManifold::generate(&ground_collider, &ground, &ball_collider, &ball)
+        .expect("sphere sunk into terrain should collide");
+    assert_eq!(manifold.points.len(), 1);
+    assert!((manifold.normal - Vec3::Y).length() < 1e-4);
+    assert!((manifold.points[0].depth - 0.1).abs() < 1e-4);
+
+    let flipped = ContactManifold::generate(&ball_collider, &ball, &ground_collider, &ground)
+        .expect("pair order should not matter");
+    assert!((flipped.normal + Vec3::Y).length() < 1e-4);
+
+    ball.transform.position.y = 0.6;
+    assert!(ContactManifold::generate(&ground_collider, &ground, &ball_collider, &ball).is_none());
+}
+
+#[test]
+fn heightfield_contacts_carry_materials_and_skip_holes() {
+    // 2x2 cells of four units each; cell (0, 0) is a hole.
+    let terrain = Heightfield::flat(3, 3, Vec3::splat(4.0))
+        .with_holes(vec![true, false, false, false])
+        .with_material_indices((10..18).collect());
+    let (ground, ground_collider) = make_heightfield_body(30, terrain);
+
+    let mut ball = RigidBody::new(EntityId::from_index(31));
+    ball.transform.position = Vec3::new(-2.0, 0.4, -2.0);
+    let ball_collider = Collider {
+        id: EntityId::from_index(131),
+        rigidbody_id: ball.id,
+        shape: ColliderShape::Sphere { radius: 0.5 },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+    assert!(ContactManifold::generate(&ground_collider, &ground, &ball_collider, &ball).is_none());
+
+    // Lower triangle of cell (0, 1) has id 2.
+    ball.transform.position = Vec3::new(1.5, 0.4, -3.0);
+    let manifold = ContactManifold::generate(&ground_collider, &ground, &ball_collider, &ball)
+        .expect("sphere over solid terrain should collide");
+    assert_eq!(manifold.points[0].material_index_a, Some(12));
+    assert_eq!(manifold.points[0].material_index_b, None);
+
+    let flipped = ContactManifold::generate(&ball_collider, &ball, &ground_collider, &ground)
+        .expect("pair order should not matter");
+    assert_eq!(flipped.points[0].material_index_a, None);
+    assert_eq!(flipped.points[0].material_index_b, Some(12));
+}
+
+#[test]
+fn heightfield_box_and_capsule_produce_multi_point_manifolds() {
+    let (ground, ground_collider) =
+        make_heightfield_body(22, Heightfield::flat(6, 6, Vec3::splat(0.5)));
+
+    let (mut crate_body, crate_collider) = make_box_body(23, Vec3::new(0.1, 0.4, 0.1));
+    crate_body.is_static = false;
+    let manifold =
+        ContactManifold::generate(&ground_collider, &ground, &crate_collider, &crate_body)
+            .expect("box resting in terrain should collide");
+    assert_eq!(manifold.points.len(), 4);
+    assert!((manifold.normal - Vec3::Y).length() < 1e-4);
+    for point in &manifold.points {
+        assert!((point.depth - 0.1).abs() < 1e-4);
+    }
+
+    let mut log = RigidBody::new(EntityId::from_index(24));
+    log.transform.position = Vec3::new(0.0, 0.2, 0.0);
+    log.transform.rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    let log_collider = Collider {
+        id: EntityId::from_index(124),
+        rigidbody_id: log.id,
+        shape: ColliderShape::Capsule {
+            radius: 0.25,
+            height: 1.0,
+        },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+    let manifold = ContactManifold::generate(&ground_collider, &ground, &log_collider, &log)
+        .expect("lying capsule should touch terrain");
+    assert!(manifold.points.len() >= 2);
+    assert!(manifold.normal.y > 0.99);
+}
+
+fn make_plane_body(id: u32, normal: Vec3) -> (RigidBody, Collider) {
+    let mut body = RigidBody::new(EntityId::from_index(id));
+    body.is_static = true;
+
+    let collider = Collider {
+        id: EntityId::from_index(id + 100),
+        rigidbody_id: body.id,
+        shape: ColliderShape::Plane { normal },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+
+    (body, collider)
+}
+
+#[test]
+fn plane_contacts_for_sphere_box_and_capsule() {
+    let (floor, floor_collider) = make_plane_body(50, Vec3::Y);
+
+    let mut ball = RigidBody::new(EntityId::from_index(51));
+    ball.transform.position = Vec3::new(40.0, 0.4, -25.0);
+    let ball_collider = Collider::builder().sphere(0.5).build();
+    let manifold = ContactManifold::generate(&floor_collider, &floor, &ball_collider, &ball)
+        .expect("sphere sunk into the plane should collide");
+    assert_eq!(manifold.points.len(), 1);
+    assert!((manifold.normal - Vec3::Y).length() < 1e-4);
+    assert!((manifold.points[0].depth - 0.1).abs() < 1e-4);
+    assert!(manifold.points[0].point.y.abs() < 1e-4);
+
+    let flipped = ContactManifold::generate(&ball_collider, &ball, &floor_collider, &floor)
+        .expect("pair order should not matter");
+    assert!((flipped.normal + Vec3::Y).length() < 1e-4);
+
+    ball.transform.position.y = 0.6;
+    assert!(ContactManifold::generate(&floor_collider, &floor, &ball_collider, &ball).is_none());
+
+    let (mut crate_body, crate_collider) = make_box_body(52, Vec3::new(-300.0, 0.4, 7.0));
+    crate_body.is_static = false;
+    let manifold = ContactManifold::generate(&floor_collider, &floor, &crate_collider, &crate_body)
+        .expect("box resting in the plane should collide");
+    assert_eq!(manifold.points.len(), 4);
+    for point in &manifold.points {
+        assert!((point.depth - 0.1).abs() < 1e-4);
+    }
+
+    let mut log = RigidBody::new(EntityId::from_index(53));
+    log.transform.position = Vec3::new(0.0, 0.2, 0.0);
+    log.transform.rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    let log_collider = Collider::builder().capsule(0.25, 1.0).build();
+    let manifold = ContactManifold::generate(&floor_collider, &floor, &log_collider, &log)
+        .expect("lying capsule should touch the plane");
+    assert_eq!(manifold.points.len(), 2);
+    for point in &manifold.points {
+        assert!((point.depth - 0.05).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn tilted_plane_contacts_convex_hull_vertices() {
+    let normal = Vec3::new(1.0, 1.0, 0.0).normalize();
+    let (ramp, ramp_collider) = make_plane_body(54, normal);
+
+    let mut rock = RigidBody::new(EntityId::from_index(55));
+    rock.transform.position = normal * 0.4;
+    let rock_collider = Collider {
+        id: EntityId::from_index(155),
+        rigidbody_id: rock.id,
+        shape: ColliderShape::ConvexHull {
+            vertices: vec![
+                Vec3::new(-0.5, -0.5, -0.5),
+                Vec3::new(0.5, -0.5, -0.5),
+                Vec3::new(0.0, -0.5, 0.5),
+                Vec3::new(0.0, 0.5, 0.0),
+            ],
+        },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+
+    let manifold = ContactManifold::generate(&ramp_collider, &ramp, &rock_collider, &rock)
+        .expect("hull pushed into the ramp should collide");
+    assert!((manifold.normal - normal).length() < 1e-4);
+    assert_eq!(manifold.points.len(), 1);
+    assert!((manifold.points[0].depth - (0.5 * 2f32.sqrt() - 0.4)).abs() < 1e-4);
+}
+
+#[test]
+fn broadphase_pairs_planes_by_distance_not_bounds() {
+    for backend in [BroadPhaseBackend::Grid, BroadPhaseBackend::SweepAndPrune] {
+        let mut broadphase = BroadPhase::with_backend(1.0, backend);
+        let mut bodies = BodiesSoA::new();
+        let mut colliders = Arena::new();
+
+        let mut add = |body: RigidBody, mut collider: Collider| {
+            collider.rigidbody_id = bodies.insert(body);
+            let id = colliders.insert(collider);
+            colliders.get_mut(id).unwrap().id = id;
+            id
+        };
+        let (floor, floor_collider) = make_plane_body(60, Vec3::Y);
+        let floor_id = add(floor, floor_collider);
+        let (near, near_collider) = make_box_body(61, Vec3::new(500.0, 0.6, -500.0));
+        let near_id = add(near, near_collider);
+        let (far, far_collider) = make_box_body(62, Vec3::new(0.0, 20.0, 0.0));
+        add(far, far_collider);
+
+        let pairs = broadphase.get_potential_pairs(&colliders, &bodies);
+        assert_eq!(pairs, vec![(floor_id, near_id)], "{backend:?}");
+    }
+}
+
+#[test]
+fn cone_and_rounded_shapes_collide_through_support_functions() {
+    let mut ball = RigidBody::new(EntityId::from_index(70));
+    ball.transform.position = Vec3::new(0.0, 1.4, 0.0);
+    let ball_collider = Collider::builder().sphere(0.5).build();
+
+    // The cone apex sits at y = 1.0, so the sphere dips 0.1 into it.
+    let cone = RigidBody::new(EntityId::from_index(71));
+    let cone_collider = Collider::builder().cone(1.0, 2.0).build();
+    let manifold = ContactManifold::generate(&cone_collider, &cone, &ball_collider, &ball)
+        .expect("sphere resting on the cone tip should collide");
+    assert!(manifold.normal.y > 0.99);
+    assert!((manifold.points[0].depth - 0.1).abs() < 1e-2);
+
+    let rounded = Collider::builder()
+        .rounded_box(Vec3::splat(1.0), 0.3)
+        .build();
+    let manifold = ContactManifold::generate(&rounded, &cone, &ball_collider, &ball)
+        .expect("sphere above a rounded box face should collide");
+    assert!((manifold.points[0].depth - 0.1).abs() < 1e-2);
+
+    // Off the corner the rounding leaves a gap that a sharp box would not.
+    ball.transform.position = Vec3::splat(1.2);
+    assert!(ContactManifold::generate(&rounded, &cone, &ball_collider, &ball).is_none());
+    let sharp = Collider::builder().box_shape(Vec3::splat(1.0)).build();
+    assert!(ContactManifold::generate(&sharp, &cone, &ball_collider, &ball).is_some());
+}
+
+#[test]
+fn rounded_shapes_rest_on_planes_along_their_border() {
+    let (floor, floor_collider) = make_plane_body(72, Vec3::Y);
+    let mut body = RigidBody::new(EntityId::from_index(73));
+    body.transform.position = Vec3::new(0.0, 0.45, 0.0);
+
+    let rounded_box = Collider::builder()
+        .rounded_box(Vec3::splat(0.5), 0.1)
+        .build();
+    let manifold = ContactManifold::generate(&floor_collider, &floor, &rounded_box, &body)
+        .expect("rounded box sunk into the plane should collide");
+    assert_eq!(manifold.points.len(), 4);
+    for point in &manifold.points {
+        assert!((point.depth - 0.05).abs() < 1e-4);
+        assert!(point.point.y.abs() < 1e-4);
+    }
+
+    let disc = Collider::builder().rounded_cylinder(0.5, 1.0, 0.1).build();
+    let manifold = ContactManifold::generate(&floor_collider, &floor, &disc, &body)
+        .expect("rounded cylinder sunk into the plane should collide");
+    assert!(manifold.points.len() >= 3);
+    assert!((manifold.points[0].depth - 0.05).abs() < 1e-4);
+}
+
+#[test]
+fn primitive_mass_properties_scale_with_density() {
+    let sphere = Collider::sphere(1.0).mass_properties(2.0).unwrap();
+    assert!((sphere.mass - 8.0 / 3.0 * std::f32::consts::PI).abs() < 1e-4);
+
+    let cone = Collider::cone(1.0, 3.0).mass_properties(1.0).unwrap();
+    assert!((cone.mass - std::f32::consts::PI).abs() < 1e-4);
+    assert!((cone.inertia.y_axis.y - 0.3 * cone.mass).abs() < 1e-4);
+
+    // Rounding removes material from the corners and edges only.
+    let cube = Collider::cuboid(Vec3::splat(0.5))
+        .mass_properties(1.0)
+        .unwrap();
+    let rounded = Collider::rounded_box(Vec3::splat(0.5), 0.1)
+        .mass_properties(1.0)
+        .unwrap();
+    assert!(rounded.mass < cube.mass && rounded.mass > 0.95 * cube.mass);
+    let fully_rounded = Collider::rounded_box(Vec3::splat(0.5), 2.0)
+        .mass_properties(1.0)
+        .unwrap();
+    let ball = Collider::sphere(0.5).mass_properties(1.0).unwrap();
+    assert!((fully_rounded.mass - ball.mass).abs() < 1e-4);
+
+    let capsule_like = Collider::rounded_cylinder(0.5, 1.0, 0.5)
+        .mass_properties(1.0)
+        .unwrap();
+    assert!((capsule_like.mass - ball.mass).abs() < 1e-4);
+
+    assert!(Collider::plane(Vec3::Y).mass_properties(1.0).is_none());
+}
+
+#[test]
+fn hull_and_compound_mass_properties_match_their_primitives() {
+    let mut corners: Vec<Vec3> = (0..8)
+        .map(|i| {
+            Vec3::new(
+                if i & 1 == 0 { -1.0 } else { 1.0 },
+                if i & 2 == 0 { -0.5 } else { 0.5 },
+                if i & 4 == 0 { -0.25 } else { 0.25 },
+            ) + Vec3::X
+        })
+        .collect();
+    // Interior points must not change the hull.
+    corners.push(Vec3::new(1.2, 0.1, 0.0));
+    let hull = ColliderShape::ConvexHull { vertices: corners };
+    let cuboid = Collider::cuboid(Vec3::new(1.0, 0.5, 0.25));
+    let hull_props = hull.mass_properties(2.0).unwrap();
+    let box_props = cuboid.mass_properties(2.0).unwrap();
+    assert!((hull_props.mass - box_props.mass).abs() < 1e-4);
+    assert!(hull.center_of_mass().unwrap().distance(Vec3::X) < 1e-5);
+    // The hull is offset along x, so its inertia about the origin gains m·d² about y and z.
+    let shifted =
+        box_props.inertia + Mat3::from_diagonal(Vec3::new(0.0, box_props.mass, box_props.mass));
+    assert!(hull_props.inertia.abs_diff_eq(shifted, 1e-3));
+
+    let flat = ColliderShape::ConvexHull {
+        vertices: vec![Vec3::ZERO, Vec3::X, Vec3::Z, Vec3::ONE.with_y(0.0)],
+    };
+    assert!(flat.mass_properties(1.0).is_none());
+
+    // A rotated, scaled unit cube is the same box.
+    let compound = ColliderShape::Compound {
+        shapes: vec![(
+            Transform {
+                position: Vec3::X,
+                rotation: Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+                scale: Vec3::new(0.5, 1.0, 2.0),
+            },
+            Collider::cuboid(Vec3::splat(0.5)),
+        )],
+    };
+    let compound_props = compound.mass_properties(2.0).unwrap();
+    assert!((compound_props.mass - box_props.mass).abs() < 1e-4);
+    assert!(compound_props.inertia.abs_diff_eq(shifted, 1e-3));
+    assert!(compound.center_of_mass().unwrap().distance(Vec3::X) < 1e-5);
+}
+
+fn primitive_body(id: u32, position: Vec3, rotation: Quat) -> RigidBody {
+    let mut body = RigidBody::new(EntityId::from_index(id));
+    body.transform.position = position;
+    body.transform.rotation = rotation;
+    body
+}
+
+#[test]
+fn analytic_sphere_pairs_report_exact_depth_and_normal() {
+    let sphere = Collider::builder().sphere(0.5).build();
+    let a = primitive_body(80, Vec3::ZERO, Quat::IDENTITY);
+    let b = primitive_body(81, Vec3::new(0.8, 0.0, 0.0), Quat::IDENTITY);
+    let manifold = ContactManifold::generate(&sphere, &a, &sphere, &b)
+        .expect("overlapping spheres should collide");
+    assert_eq!(manifold.points.len(), 1);
+    assert!((manifold.normal - Vec3::X).length() < 1e-6);
+    assert!((manifold.points[0].depth - 0.2).abs() < 1e-6);
+    assert!((manifold.points[0].point - Vec3::new(0.4, 0.0, 0.0)).length() < 1e-6);
+
+    // Sphere against the side of an upright capsule.
+    let capsule = Collider::builder().capsule(0.25, 2.0).build();
+    let post = primitive_body(82, Vec3::new(0.0, 0.0, 0.7), Quat::IDENTITY);
+    let manifold = ContactManifold::generate(&sphere, &a, &capsule, &post)
+        .expect("sphere touching the capsule side should collide");
+    assert!((manifold.normal - Vec3::Z).length() < 1e-6);
+    assert!((manifold.points[0].depth - 0.05).abs() < 1e-5);
+
+    // Sphere centre inside a box is pushed out through the nearest face.
+    let crate_collider = Collider::builder().box_shape(Vec3::splat(1.0)).build();
+    let inside = primitive_body(83, Vec3::new(0.0, 0.9, 0.2), Quat::IDENTITY);
+    let manifold = ContactManifold::generate(&crate_collider, &a, &sphere, &inside)
+        .expect("sphere inside the box should collide");
+    assert!((manifold.normal - Vec3::Y).length() < 1e-6);
+    assert!((manifold.points[0].depth - 0.6).abs() < 1e-5);
+
+    let flipped = ContactManifold::generate(&sphere, &inside, &crate_collider, &a)
+        .expect("pair order should not matter");
+    assert!((flipped.normal + Vec3::Y).length() < 1e-6);
+    assert!((flipped.points[0].point - manifold.points[0].point).length() < 1e-6);
+}
+
+#[test]
+fn analytic_capsules_rest_on_both_end_caps() {
+    let lying = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    let log = Collider::builder().capsule(0.25, 1.0).build();
+
+    let ground = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    let floor = primitive_body(84, Vec3::ZERO, Quat::IDENTITY);
+    let resting = primitive_body(85, Vec3::new(0.3, 0.7, -0.2), lying);
+    let manifold = ContactManifold::generate(&ground, &floor, &log, &resting)
+        .expect("capsule lying on the box should collide");
+    assert_eq!(manifold.points.len(), 2);
+    assert!((manifold.normal - Vec3::Y).length() < 1e-5);
+    for point in &manifold.points {
+        assert!((point.depth - 0.05).abs() < 1e-4);
+    }
+
+    // Tilted, only the lower cap touches.
+    let tilted = primitive_body(86, Vec3::new(0.0, 0.9, 0.0), Quat::from_rotation_z(1.2));
+    let manifold = ContactManifold::generate(&ground, &floor, &log, &tilted)
+        .expect("tilted capsule should touch with one cap");
+    assert_eq!(manifold.points.len(), 1);
+
+    let stacked = primitive_body(87, Vec3::new(0.2, 1.15, -0.2), lying);
+    let manifold = ContactManifold::generate(&log, &resting, &log, &stacked)
+        .expect("parallel capsules should collide");
+    assert_eq!(manifold.points.len(), 2);
+    assert!((manifold.normal - Vec3::Y).length() < 1e-5);
+    for point in &manifold.points {
+        assert!((point.depth - 0.05).abs() < 1e-4);
+    }
+
+    let contact = NarrowPhase::collide(&ground, &floor, &log, &resting, None)
+        .map(|(contact, _)| contact)
+        .expect("narrowphase should agree with the manifold");
+    assert!((contact.depth - 0.05).abs() < 1e-4);
+}
+
+#[test]
+fn closest_points_reports_gap_and_penetration() {
+    let mut ball = RigidBody::new(EntityId::from_index(40));
+    ball.transform.position = Vec3::new(3.0, 0.2, 0.0);
+    let ball_collider = Collider {
+        id: EntityId::from_index(140),
+        rigidbody_id: ball.id,
+        shape: ColliderShape::Sphere { radius: 0.5 },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+    let (crate_body, crate_collider) = make_box_body(41, Vec3::ZERO);
+
+    let result = closest_points(&crate_collider, &crate_body, &ball_collider, &ball)
+        .expect("separated shapes have closest points");
+    assert!((result.distance - 2.0).abs() < 1e-3, "{result:?}");
+    assert!((result.point_a - Vec3::new(0.5, 0.2, 0.0)).length() < 1e-3);
+    assert!((result.point_b - Vec3::new(2.5, 0.2, 0.0)).length() < 1e-3);
+    assert!((result.normal - Vec3::X).length() < 1e-3);
+
+    ball.transform.position = Vec3::new(0.8, 0.0, 0.0);
+    let result = closest_points(&crate_collider, &crate_body, &ball_collider, &ball)
+        .expect("overlapping shapes report penetration");
+    assert!((result.distance + 0.2).abs() < 1e-2, "{result:?}");
+    assert!(result.normal.x > 0.9);
+}
+
+#[test]
+fn collision_margins_shrink_primitives_and_inflate_hulls() {
+    use particle_accelerator::collision::shapes::ShapeUtil;
+
+    let cube = ColliderShape::Box {
+        half_extents: Vec3::splat(0.5),
+    };
+    let (core, margin) = ShapeUtil::margin_core(&cube, 0.05, Vec3::ONE);
+    assert_eq!(margin, 0.05);
+    match core.as_ref() {
+        ColliderShape::Box { half_extents } => {
+            assert!((*half_extents - Vec3::splat(0.45)).length() < 1e-6)
+        }
+        other => panic!("box core should stay a box, got {other:?}"),
+    }
+
+    // Stacked cylinders: the cores are apart, so the contact comes from their closest points.
+    let mut cylinder = Collider::builder().margin(0.04).build();
+    cylinder.shape = ColliderShape::Cylinder {
+        radius: 0.5,
+        height: 1.0,
+    };
+    let bottom = primitive_body(90, Vec3::ZERO, Quat::IDENTITY);
+    let top = primitive_body(91, Vec3::new(0.1, 0.98, 0.0), Quat::IDENTITY);
+    let (contact, simplex) = NarrowPhase::collide(&cylinder, &bottom, &cylinder, &top, None)
+        .expect("overlapping cylinders should collide");
+    assert!(simplex.is_empty(), "core contact should skip EPA");
+    assert!((contact.normal - Vec3::Y).length() < 1e-4);
+    assert!(
+        (contact.depth - 0.02).abs() < 1e-4,
+        "depth {}",
+        contact.depth
+    );
+    let apart = primitive_body(92, Vec3::new(0.1, 1.01, 0.0), Quat::IDENTITY);
+    assert!(NarrowPhase::collide(&cylinder, &bottom, &cylinder, &apart, None).is_none());
+
+    // Hulls keep their vertices and grow by the margin.
+    let corners: Vec<Vec3> = (0..8)
+        .map(|i| {
+            Vec3::new(
+                if i & 1 == 0 { -0.5 } else { 0.5 },
+                if i & 2 == 0 { -0.5 } else { 0.5 },
+                if i & 4 == 0 { -0.5 } else { 0.5 },
+            )
+        })
+        .collect();
+    let mut hull = Collider::builder().build();
+    hull.shape = ColliderShape::ConvexHull { vertices: corners };
+    let left = primitive_body(93, Vec3::ZERO, Quat::IDENTITY);
+    let right = primitive_body(94, Vec3::new(1.02, 0.0, 0.0), Quat::IDENTITY);
+    assert!(NarrowPhase::collide(&hull, &left, &hull, &right, None).is_none());
+    hull.margin = 0.02;
+    let (contact, _) = NarrowPhase::collide(&hull, &left, &hull, &right, None)
+        .expect("margins should close the gap");
+    assert!((contact.normal - Vec3::X).length() < 1e-4);
+    assert!(
+        (contact.depth - 0.02).abs() < 1e-4,
+        "depth {}",
+        contact.depth
+    );
+}
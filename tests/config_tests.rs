@@ -0,0 +1,115 @@
+#![cfg(any(feature = "toml", feature = "ron"))]
+
+use std::path::PathBuf;
+
+use particle_accelerator::{
+    collision::broadphase::BroadPhaseBackend,
+    dynamics::{BlockSolverMode, PositionCorrection},
+    ConfigError, PhysicsWorld, Vec3, WorldConfig,
+};
+
+fn temp_config(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "particle_accelerator_{}_{name}",
+        std::process::id()
+    ));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_config_builds_a_world_and_reloads_live() {
+    let path = temp_config(
+        "world.toml",
+        r#"
+time_step = 0.01
+gravity = [0.0, -3.0, 0.0]
+
+[solver]
+velocity_iterations = 12
+warm_starting = false
+position_correction = "SplitImpulse"
+block_solver = "Off"
+
+[broadphase]
+backend = "SweepAndPrune"
+cell_size = 2.5
+"#,
+    );
+
+    let mut world = PhysicsWorld::from_config(&path).unwrap();
+    let config = world.config();
+    assert_eq!(config.time_step, 0.01);
+    assert_eq!(config.gravity, Vec3::new(0.0, -3.0, 0.0));
+    assert_eq!(config.solver.velocity_iterations, 12);
+    assert!(!config.solver.warm_starting);
+    assert_eq!(
+        config.solver.position_correction,
+        PositionCorrection::SplitImpulse
+    );
+    assert_eq!(config.solver.block_solver, BlockSolverMode::Off);
+    // Fields the file leaves out keep their defaults.
+    assert_eq!(config.solver.bias, WorldConfig::default().solver.bias);
+    assert_eq!(config.broadphase.backend, BroadPhaseBackend::SweepAndPrune);
+    assert_eq!(config.broadphase.cell_size, 2.5);
+    assert_eq!(world.config_path(), Some(path.as_path()));
+
+    std::fs::write(&path, "gravity = [0.0, -20.0, 0.0]\n").unwrap();
+    world.reload().unwrap();
+    assert_eq!(world.gravity, Vec3::new(0.0, -20.0, 0.0));
+    assert_eq!(world.time_step, WorldConfig::default().time_step);
+
+    // A bad edit reports every problem and leaves the running world alone.
+    std::fs::write(
+        &path,
+        "time_step = -1.0\n[solver]\nvelocity_iterations = 0\n",
+    )
+    .unwrap();
+    let before = world.config();
+    match world.reload() {
+        Err(ConfigError::Invalid(problems)) => assert_eq!(problems.len(), 2, "{problems:?}"),
+        other => panic!("expected validation errors, got {other:?}"),
+    }
+    assert_eq!(world.config(), before);
+
+    std::fs::write(&path, "gravty = [0.0, -1.0, 0.0]\n").unwrap();
+    assert!(matches!(world.reload(), Err(ConfigError::Parse(_))));
+
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(world.reload(), Err(ConfigError::Io(_))));
+}
+
+#[cfg(feature = "ron")]
+#[test]
+fn ron_config_builds_a_world() {
+    let path = temp_config(
+        "world.ron",
+        "(time_step: 0.02, parallel: true, broadphase: (backend: Incremental, fat_margin: 0.3))",
+    );
+
+    let world = PhysicsWorld::from_config(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(world.time_step, 0.02);
+    assert!(world.parallel_enabled());
+    assert_eq!(world.broadphase_backend(), BroadPhaseBackend::Incremental);
+    assert_eq!(world.config().broadphase.fat_margin, 0.3);
+}
+
+#[test]
+fn config_errors_without_a_usable_source() {
+    let path = temp_config("world.yaml", "time_step: 0.01\n");
+    let result = PhysicsWorld::from_config(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(ConfigError::UnsupportedFormat(ref f)) if f == "yaml"));
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    assert!(matches!(world.reload(), Err(ConfigError::NoSource)));
+
+    let invalid = WorldConfig {
+        time_step: 0.0,
+        ..WorldConfig::default()
+    };
+    assert!(world.apply_config(invalid).is_err());
+    assert_eq!(world.config(), WorldConfig::default());
+}
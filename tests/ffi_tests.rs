@@ -0,0 +1,73 @@
+#![cfg(feature = "ffi")]
+
+use particle_accelerator::ffi::*;
+
+fn vec3(x: f32, y: f32, z: f32) -> PaVec3 {
+    PaVec3 { x, y, z }
+}
+
+fn body_desc(position: PaVec3, mass: f32, is_static: bool) -> PaBodyDesc {
+    PaBodyDesc {
+        position,
+        rotation: PaQuat {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        },
+        linear_velocity: PaVec3::default(),
+        mass,
+        is_static,
+    }
+}
+
+#[test]
+fn ffi_world_steps_and_reads_back_transforms() {
+    let world = pa_world_create(1.0 / 60.0);
+    unsafe {
+        let ground = pa_world_add_body(world, &body_desc(PaVec3::default(), 0.0, true));
+        let ball = pa_world_add_body(world, &body_desc(vec3(0.0, 5.0, 0.0), 1.0, false));
+        assert_ne!(ground, PA_INVALID_ID);
+        let ground_box = pa_world_add_box_collider(world, ground, vec3(5.0, 0.5, 5.0));
+        assert_ne!(ground_box, PA_INVALID_ID);
+        assert_ne!(
+            pa_world_add_sphere_collider(world, ball, 0.5),
+            PA_INVALID_ID
+        );
+        assert_eq!(pa_world_add_sphere_collider(world, 99, 0.5), PA_INVALID_ID);
+
+        for _ in 0..10 {
+            pa_world_step(world, 1.0 / 60.0);
+        }
+
+        let count = pa_world_body_count(world);
+        assert_eq!(count, 2);
+        let mut ids = vec![0u64; count];
+        let mut positions = vec![0.0f32; count * 3];
+        let written = pa_world_read_transforms(
+            world,
+            ids.as_mut_ptr(),
+            positions.as_mut_ptr(),
+            std::ptr::null_mut(),
+            count,
+        );
+        assert_eq!(written, 2);
+        let slot = ids.iter().position(|&id| id == ball).unwrap();
+        assert!(positions[slot * 3 + 1] < 5.0);
+
+        let mut hit = std::mem::MaybeUninit::<PaRaycastHit>::uninit();
+        let origin = vec3(3.0, 10.0, 0.0);
+        let down = vec3(0.0, -1.0, 0.0);
+        assert!(pa_world_raycast(
+            world,
+            origin,
+            down,
+            100.0,
+            hit.as_mut_ptr()
+        ));
+        assert_eq!(hit.assume_init().body, ground);
+
+        pa_world_destroy(world);
+        pa_world_destroy(std::ptr::null_mut());
+    }
+}
@@ -0,0 +1,434 @@
+use particle_accelerator::core::soa::BodiesSoA;
+use particle_accelerator::core::types::MaterialPairProperties;
+use particle_accelerator::dynamics::friction::apply_friction;
+use particle_accelerator::dynamics::{BlockSolverMode, PGSSolver, PositionCorrection};
+use particle_accelerator::*;
+
+/// Tangential impulse a unit normal impulse allows for a body sliding at `speed`.
+fn friction_impulse(material: Material, speed: f32) -> f32 {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let mut slider = RigidBody::new(EntityId::default());
+    slider.transform.position = Vec3::new(0.0, 0.5, 0.0);
+    slider.velocity.linear = Vec3::new(speed, 0.0, 0.0);
+    // Heavy enough that stopping it always needs more than the friction limit.
+    slider.mass_properties.mass = 100.0;
+    slider.recompute_inverses();
+    let slider_id = bodies.insert(slider);
+
+    let mut contact = Contact {
+        body_a: ground_id,
+        body_b: slider_id,
+        point: Vec3::ZERO,
+        normal: Vec3::Y,
+        depth: 0.0,
+        relative_velocity: 0.0,
+        feature_id: 0,
+        material_index_a: None,
+        material_index_b: None,
+        accumulated_normal_impulse: 0.0,
+        accumulated_tangent_impulse: Vec3::ZERO,
+        accumulated_rolling_impulse: Vec3::ZERO,
+        accumulated_torsional_impulse: 0.0,
+        material: MaterialPairProperties::from_materials(&material, &material),
+    };
+
+    let (mut body_a, mut body_b) = bodies.get2_mut(ground_id, slider_id).unwrap();
+    apply_friction(&mut body_a, &mut body_b, &mut contact, 1.0);
+    contact.accumulated_tangent_impulse.length()
+}
+
+#[test]
+fn stribeck_friction_depends_on_slip_speed() {
+    let coulomb = Material {
+        static_friction: 0.6,
+        dynamic_friction: 0.2,
+        ..Material::default()
+    };
+    let stribeck = Material {
+        stribeck: Some(StribeckCurve::new(1.0)),
+        ..coulomb
+    };
+
+    // Coulomb friction drops straight to the dynamic limit once the body slides.
+    assert!((friction_impulse(coulomb, 0.5) - 0.2).abs() < 1e-4);
+    assert!((friction_impulse(coulomb, 5.0) - 0.2).abs() < 1e-4);
+
+    // The Stribeck curve keeps near-static grip at low slip and decays towards dynamic.
+    let slow = friction_impulse(stribeck, 0.5);
+    let medium = friction_impulse(stribeck, 1.0);
+    let fast = friction_impulse(stribeck, 5.0);
+    assert!(slow > 0.45 && slow < 0.6, "slow slip impulse {slow}");
+    assert!(medium < slow && medium > fast);
+    assert!((fast - 0.2).abs() < 1e-3, "fast slip impulse {fast}");
+}
+
+#[test]
+fn world_material_pair_overrides_reach_contacts() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut rink = RigidBody::new(EntityId::default());
+    rink.is_static = true;
+    rink.material = Material::ice();
+    let rink = world.add_rigidbody(rink);
+    world.add_collider(Collider {
+        rigidbody_id: rink,
+        ..Collider::builder()
+            .box_shape(Vec3::new(5.0, 0.5, 5.0))
+            .build()
+    });
+
+    let mut puck = RigidBody::new(EntityId::default());
+    puck.transform.position = Vec3::new(0.0, 0.95, 0.0);
+    puck.material = Material::rubber();
+    let puck = world.add_rigidbody(puck);
+    world.add_collider(Collider {
+        rigidbody_id: puck,
+        ..Collider::builder().sphere(0.5).build()
+    });
+
+    let mixed = Material::combine_pair(&Material::ice(), &Material::rubber());
+    let contacts = world.collect_contacts();
+    assert!(!contacts.is_empty());
+    assert_eq!(contacts[0].material.restitution, mixed.restitution);
+
+    world.set_material_pair_override(
+        MaterialId::RUBBER,
+        MaterialId::ICE,
+        MaterialPairOverride::new()
+            .with_friction(0.9, 0.7)
+            .with_restitution(0.0),
+    );
+    let contacts = world.collect_contacts();
+    assert_eq!(contacts[0].material.restitution, 0.0);
+    assert!(contacts[0].material.dynamic_friction.x > mixed.dynamic_friction.x);
+    assert_eq!(
+        contacts[0].material.rolling_friction,
+        mixed.rolling_friction
+    );
+
+    world.remove_material_pair_override(MaterialId::ICE, MaterialId::RUBBER);
+    let contacts = world.collect_contacts();
+    assert_eq!(contacts[0].material.restitution, mixed.restitution);
+}
+
+/// Angular speed left on a box landing flat on two contact points.
+fn landing_spin(block_solver: BlockSolverMode) -> (f32, Vec<f32>) {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let mut crate_body = RigidBody::new(EntityId::default());
+    crate_body.transform.position = Vec3::new(0.0, 0.5, 0.0);
+    crate_body.velocity.linear = Vec3::new(0.0, -2.0, 0.0);
+    crate_body.recompute_inverses();
+    let crate_id = bodies.insert(crate_body);
+
+    let material = Material {
+        restitution: 0.0,
+        ..Material::default()
+    };
+    let mut contacts: Vec<Contact> = [-0.5, 0.5]
+        .into_iter()
+        .map(|x| Contact {
+            body_a: ground_id,
+            body_b: crate_id,
+            point: Vec3::new(x, 0.0, 0.0),
+            normal: Vec3::Y,
+            depth: 0.0,
+            relative_velocity: 0.0,
+            feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
+            accumulated_normal_impulse: 0.0,
+            accumulated_tangent_impulse: Vec3::ZERO,
+            accumulated_rolling_impulse: Vec3::ZERO,
+            accumulated_torsional_impulse: 0.0,
+            material: MaterialPairProperties::from_materials(&material, &material),
+        })
+        .collect();
+
+    let solver = PGSSolver {
+        velocity_iterations: 1,
+        position_iterations: 0,
+        block_solver,
+        ..PGSSolver::new()
+    };
+    solver.solve(&mut bodies, &[], &mut contacts, 1.0 / 60.0);
+    let spin = bodies.get(crate_id).unwrap().velocity().angular.length();
+    let impulses = contacts
+        .iter()
+        .map(|c| c.accumulated_normal_impulse)
+        .collect();
+    (spin, impulses)
+}
+
+#[test]
+fn block_solver_loads_manifold_points_evenly() {
+    let (sequential_spin, sequential) = landing_spin(BlockSolverMode::Off);
+    let (block_spin, block) = landing_spin(BlockSolverMode::FourPoint);
+
+    // Sequential impulses push the first point harder and leave the box rocking.
+    assert!(sequential_spin > 0.1, "sequential spin {sequential_spin}");
+    assert!((sequential[0] - sequential[1]).abs() > 0.1);
+
+    // The block solve stops both points in one pass without inducing spin.
+    assert!(block_spin < 1e-4, "block spin {block_spin}");
+    assert!((block[0] - block[1]).abs() < 1e-4);
+    assert!(block.iter().all(|&impulse| impulse > 0.0));
+    assert!((block[0] + block[1] - 2.0).abs() < 1e-3);
+
+    // Two points are a single 2x2 block in either block mode.
+    let (pair_spin, pair) = landing_spin(BlockSolverMode::TwoPoint);
+    assert!(pair_spin < 1e-4, "2x2 spin {pair_spin}");
+    assert!((pair[0] - block[0]).abs() < 1e-5 && (pair[1] - block[1]).abs() < 1e-5);
+}
+
+/// Velocity and height of a box hitting the ground at 2 m/s while 0.1 deep in it.
+fn rebound(position_correction: PositionCorrection) -> (f32, f32) {
+    crate_on_ground(position_correction, 0.1, 0.5, 1.0 / 60.0)
+}
+
+/// Velocity and height of a box hitting the ground at 2 m/s with contacts `depth` deep,
+/// after one solve over `dt`.
+fn crate_on_ground(
+    position_correction: PositionCorrection,
+    depth: f32,
+    restitution: f32,
+    dt: f32,
+) -> (f32, f32) {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let mut crate_body = RigidBody::new(EntityId::default());
+    crate_body.transform.position = Vec3::new(0.0, 0.5, 0.0);
+    crate_body.velocity.linear = Vec3::new(0.0, -2.0, 0.0);
+    crate_body.recompute_inverses();
+    let crate_id = bodies.insert(crate_body);
+
+    let material = Material {
+        restitution,
+        ..Material::default()
+    };
+    let mut contacts: Vec<Contact> = [-0.5, 0.5]
+        .into_iter()
+        .map(|x| Contact {
+            body_a: ground_id,
+            body_b: crate_id,
+            point: Vec3::new(x, 0.0, 0.0),
+            normal: Vec3::Y,
+            depth,
+            relative_velocity: 0.0,
+            feature_id: 0,
+            material_index_a: None,
+            material_index_b: None,
+            accumulated_normal_impulse: 0.0,
+            accumulated_tangent_impulse: Vec3::ZERO,
+            accumulated_rolling_impulse: Vec3::ZERO,
+            accumulated_torsional_impulse: 0.0,
+            material: MaterialPairProperties::from_materials(&material, &material),
+        })
+        .collect();
+
+    let solver = PGSSolver {
+        position_correction,
+        ..PGSSolver::new()
+    };
+    solver.solve(&mut bodies, &[], &mut contacts, dt);
+    let body = bodies.get(crate_id).unwrap();
+    (body.velocity().linear.y, body.transform().position.y)
+}
+
+#[test]
+fn split_impulses_keep_penetration_out_of_the_bounce() {
+    // The Baumgarte bias comes out as extra bounce on top of the restitution.
+    let (baumgarte_speed, _) = rebound(PositionCorrection::Baumgarte);
+    assert!(baumgarte_speed > 1.5, "Baumgarte bounce {baumgarte_speed}");
+
+    // Split impulses bounce at exactly the restitution and move the box out instead.
+    let (split_speed, split_height) = rebound(PositionCorrection::SplitImpulse);
+    assert!(
+        (split_speed - 1.0).abs() < 1e-3,
+        "split bounce {split_speed}"
+    );
+    assert!(split_height > 0.51, "split height {split_height}");
+
+    let (speed, height) = rebound(PositionCorrection::None);
+    assert!((speed - 1.0).abs() < 1e-3, "uncorrected bounce {speed}");
+    assert_eq!(height, 0.5);
+}
+
+#[test]
+fn speculative_contacts_close_the_gap_over_the_solve_time_step() {
+    // A box 1 cm above the ground may fall exactly that far during the step, whatever its
+    // length.
+    for dt in [1.0 / 60.0, 1.0 / 120.0, 1.0 / 30.0] {
+        let (speed, _) = crate_on_ground(PositionCorrection::Baumgarte, -0.01, 0.0, dt);
+        assert!(
+            (speed + 0.01 / dt).abs() < 1e-3,
+            "dt {dt}: falling at {speed}"
+        );
+    }
+}
+
+/// Velocity of a ski (long along its local X) launched diagonally across flat ground.
+fn ski_velocity(heading: f32) -> Vec3 {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    let ground = world.add_rigidbody(ground);
+    world.add_collider(Collider {
+        rigidbody_id: ground,
+        ..Collider::builder()
+            .box_shape(Vec3::new(50.0, 0.5, 50.0))
+            .build()
+    });
+
+    let mut ski = RigidBody::new(EntityId::default());
+    ski.transform.position = Vec3::new(0.0, 0.6, 0.0);
+    ski.transform.rotation = Quat::from_rotation_y(heading);
+    ski.velocity.linear = Vec3::new(3.0, 0.0, 3.0);
+    // Large inertia keeps the thin ski flat so only its friction is under test.
+    ski.set_mass_properties(MassProperties {
+        mass: 1.0,
+        inertia: Mat3::from_diagonal(Vec3::splat(100.0)),
+    });
+    ski.material = Material {
+        restitution: 0.0,
+        anisotropic_friction: Some(AnisotropicFriction::new(Vec3::X, 0.05, 2.0)),
+        ..Material::default()
+    };
+    let ski = world.add_rigidbody(ski);
+    world.add_collider(Collider {
+        rigidbody_id: ski,
+        ..Collider::builder()
+            .box_shape(Vec3::new(1.0, 0.1, 0.2))
+            .build()
+    });
+
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    world.body(ski).unwrap().velocity().linear
+}
+
+#[test]
+fn anisotropic_friction_follows_body_orientation() {
+    // Pointing along X, the ski keeps gliding forward while its sideways slide brakes hard.
+    let along_x = ski_velocity(0.0);
+    assert!(along_x.x > 2.8, "forward speed {along_x}");
+    assert!(along_x.z < 2.0, "sideways speed {along_x}");
+
+    // Turned a quarter, the same material grips the other way.
+    let along_z = ski_velocity(std::f32::consts::FRAC_PI_2);
+    assert!(along_z.z > 2.8, "forward speed {along_z}");
+    assert!(along_z.x < 2.0, "sideways speed {along_z}");
+}
+
+/// Columns of three boxes on the ground, sliding and spinning a little, with a
+/// four-point manifold under every box.
+fn box_columns(columns: usize) -> (BodiesSoA, Vec<Contact>, Vec<EntityId>) {
+    let mut bodies = BodiesSoA::new();
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.recompute_inverses();
+    let ground_id = bodies.insert(ground);
+    let material =
+        MaterialPairProperties::from_materials(&Material::default(), &Material::default());
+    let mut contacts = Vec::new();
+    let mut ids = Vec::new();
+    for column in 0..columns {
+        let mut support = ground_id;
+        for level in 0..3 {
+            let mut body = RigidBody::new(EntityId::default());
+            let center = Vec3::new(column as f32 * 1.5, 0.5 + level as f32, 0.0);
+            body.transform.position = center;
+            body.velocity.linear = Vec3::new(0.3 * level as f32, -1.0, 0.1 * column as f32);
+            body.velocity.angular = Vec3::new(0.0, 0.5, 0.2 * level as f32);
+            body.recompute_inverses();
+            let id = bodies.insert(body);
+            for (x, z) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+                contacts.push(Contact {
+                    body_a: support,
+                    body_b: id,
+                    point: center + Vec3::new(x, -0.5, z),
+                    normal: Vec3::Y,
+                    depth: 0.01,
+                    relative_velocity: 0.0,
+                    feature_id: 0,
+                    material_index_a: None,
+                    material_index_b: None,
+                    accumulated_normal_impulse: 0.0,
+                    accumulated_tangent_impulse: Vec3::ZERO,
+                    accumulated_rolling_impulse: Vec3::ZERO,
+                    accumulated_torsional_impulse: 0.0,
+                    material,
+                });
+            }
+            support = id;
+            ids.push(id);
+        }
+    }
+    (bodies, contacts, ids)
+}
+
+#[test]
+fn simd_contacts_match_sequential_impulses() {
+    let solve = |simd_contacts: bool| {
+        let (mut bodies, mut contacts, ids) = box_columns(5);
+        let solver = PGSSolver {
+            velocity_iterations: 8,
+            block_solver: BlockSolverMode::Off,
+            simd_contacts,
+            ..PGSSolver::new()
+        };
+        solver.solve(&mut bodies, &[], &mut contacts, 1.0 / 60.0);
+        let velocities: Vec<_> = ids
+            .iter()
+            .map(|&id| {
+                let body = bodies.get(id).unwrap();
+                (body.velocity().linear, body.velocity().angular)
+            })
+            .collect();
+        (velocities, contacts)
+    };
+    let (sequential, sequential_contacts) = solve(false);
+    let (simd, simd_contacts) = solve(true);
+
+    // Batched contacts share no dynamic body and keep each body's order, so the lanes
+    // reproduce the sequential sweep up to rounding.
+    for ((linear, angular), (simd_linear, simd_angular)) in sequential.iter().zip(&simd) {
+        assert!(
+            linear.abs_diff_eq(*simd_linear, 1e-4),
+            "{linear} vs {simd_linear}"
+        );
+        assert!(
+            angular.abs_diff_eq(*simd_angular, 1e-4),
+            "{angular} vs {simd_angular}"
+        );
+    }
+    assert!(sequential_contacts
+        .iter()
+        .all(|c| c.accumulated_normal_impulse >= 0.0));
+    assert!(sequential_contacts
+        .iter()
+        .any(|c| c.accumulated_tangent_impulse != Vec3::ZERO
+            && c.accumulated_rolling_impulse != Vec3::ZERO));
+    for (contact, simd_contact) in sequential_contacts.iter().zip(&simd_contacts) {
+        assert!(
+            (contact.accumulated_normal_impulse - simd_contact.accumulated_normal_impulse).abs()
+                < 1e-4
+        );
+        assert!(contact
+            .accumulated_tangent_impulse
+            .abs_diff_eq(simd_contact.accumulated_tangent_impulse, 1e-4));
+        assert!(contact
+            .accumulated_rolling_impulse
+            .abs_diff_eq(simd_contact.accumulated_rolling_impulse, 1e-4));
+    }
+}
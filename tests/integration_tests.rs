@@ -1,3 +1,4 @@
+use particle_accelerator::core::mesh::{Aabb, TriangleMesh};
 use particle_accelerator::*;
 
 #[test]
@@ -21,3 +22,2017 @@ fn bodies_fall_under_gravity() {
         "body should start falling, y = {position_y}"
     );
 }
+
+#[test]
+fn sphere_does_not_sink_into_heightfield() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut terrain = Collider::builder().build();
+    terrain.shape = Collider::heightfield(8, 8, vec![0.0; 64], Vec3::ONE);
+    terrain.rigidbody_id = ground_id;
+    world.add_collider(terrain);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(0.2, 2.0, -0.3);
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    world.add_collider(ball_collider);
+
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+        let y = world.body(ball_id).unwrap().transform().position.y;
+        assert!(y > 0.35, "ball sank into terrain, y = {y}");
+    }
+}
+
+#[test]
+fn contact_events_track_touching_pairs() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(0.0, 0.98, 0.0);
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    world.add_collider(ball_collider);
+
+    world.step(1.0 / 60.0);
+    let events: Vec<ContactEvent> = world.drain_contact_events().collect();
+    assert_eq!(events.len(), 1);
+    match events[0] {
+        ContactEvent::Started(info) => {
+            let bodies = [info.body_a, info.body_b];
+            assert!(bodies.contains(&ground_id) && bodies.contains(&ball_id));
+        }
+        other => panic!("expected a start event, got {other:?}"),
+    }
+    assert!(world.contact_events().is_empty());
+
+    let mut max_impulse = 0.0f32;
+    for _ in 0..3 {
+        world.step(1.0 / 60.0);
+        match world.contact_events() {
+            [ContactEvent::Persisted(info)] => max_impulse = max_impulse.max(info.normal_impulse),
+            other => panic!("expected a persist event, got {other:?}"),
+        }
+    }
+    assert!(
+        max_impulse > 0.0,
+        "resting contact should report support impulse"
+    );
+
+    world.body_mut(ball_id).unwrap().transform.position.y = 5.0;
+    world.step(1.0 / 60.0);
+    match world.contact_events() {
+        [ContactEvent::Ended(info)] => assert_eq!(info.normal_impulse, 0.0),
+        other => panic!("expected an end event, got {other:?}"),
+    }
+
+    world.step(1.0 / 60.0);
+    assert!(world.contact_events().is_empty());
+}
+
+#[test]
+fn world_pair_filter_lets_bodies_pass_through() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut ghost = RigidBody::new(EntityId::from_index(1));
+    ghost.transform.position = Vec3::new(0.0, 1.5, 0.0);
+    let ghost_id = world.add_rigidbody(ghost);
+    let mut ghost_collider = Collider::builder().sphere(0.5).build();
+    ghost_collider.rigidbody_id = ghost_id;
+    world.add_collider(ghost_collider);
+
+    world.set_pair_filter(Some(move |a: &Collider, b: &Collider| {
+        a.rigidbody_id != ghost_id && b.rigidbody_id != ghost_id
+    }));
+    for _ in 0..90 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world.contact_events().is_empty());
+    assert!(world.body(ghost_id).unwrap().transform().position.y < -1.0);
+}
+
+#[test]
+fn trigger_volumes_report_enter_and_exit_without_contacts() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.gravity = Vec3::ZERO;
+
+    let mut sensor = RigidBody::new(EntityId::from_index(0));
+    sensor.is_static = true;
+    let sensor_id = world.add_rigidbody(sensor);
+    let mut volume = Collider::builder()
+        .box_shape(Vec3::splat(1.0))
+        .is_trigger(true)
+        .build();
+    volume.rigidbody_id = sensor_id;
+    let volume_id = world.add_collider(volume);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(-2.0, 0.0, 0.0);
+    ball.velocity.linear = Vec3::new(6.0, 0.0, 0.0);
+    ball.linear_velocity_damping = 0.0;
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    let ball_collider_id = world.add_collider(ball_collider);
+
+    let mut entered = 0;
+    let mut exited = 0;
+    let mut overlapping_frames = 0;
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+        assert!(world.contact_events().is_empty());
+        for event in world.drain_trigger_events() {
+            let overlap = *event.overlap();
+            assert_eq!(overlap.trigger, volume_id);
+            assert_eq!(overlap.other, ball_collider_id);
+            assert_eq!(overlap.other_body, ball_id);
+            match event {
+                TriggerEvent::Enter(_) => entered += 1,
+                TriggerEvent::Exit(_) => exited += 1,
+            }
+        }
+        if !world.trigger_overlaps().is_empty() {
+            overlapping_frames += 1;
+        }
+    }
+
+    assert_eq!((entered, exited), (1, 1));
+    assert!(overlapping_frames > 10);
+    assert!(world.trigger_overlaps().is_empty());
+    let ball = world.body(ball_id).unwrap();
+    assert!(
+        (ball.velocity().linear.x - 6.0).abs() < 1e-4,
+        "triggers must not push bodies"
+    );
+    assert!(ball.transform().position.x > 3.0);
+}
+
+#[test]
+fn manifold_debug_hook_only_snapshots_filtered_pairs() {
+    use std::sync::{Arc, Mutex};
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut balls = Vec::new();
+    for x in [-2.0, 2.0] {
+        let mut ball = RigidBody::new(EntityId::from_index(1));
+        ball.transform.position = Vec3::new(x, 0.95, 0.0);
+        let ball_id = world.add_rigidbody(ball);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = ball_id;
+        world.add_collider(collider);
+        balls.push(ball_id);
+    }
+    let watched = balls[1];
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&seen);
+    world.set_manifold_debug_hook(Some(move |info: &ManifoldDebugInfo| {
+        sink.lock().unwrap().push(info.clone());
+    }));
+    world.set_manifold_debug_filter(Some(move |a: EntityId, b: EntityId| {
+        a == watched || b == watched
+    }));
+    world.step(1.0 / 60.0);
+
+    let seen = seen.lock().unwrap();
+    assert!(!seen.is_empty(), "watched pair should be snapshotted");
+    for info in seen.iter() {
+        assert!([info.body_a, info.body_b].contains(&watched));
+        assert!(!info.points.is_empty());
+        for (start, end) in info.normal_segments(0.25) {
+            assert!(((end - start).length() - 0.25).abs() < 1e-5);
+        }
+    }
+    assert_eq!(world.manifold_debug_snapshots().len(), 2);
+}
+
+#[test]
+fn manifold_recorder_keeps_recent_frames_and_dumps_json() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(0.0, 0.95, 0.0);
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    world.add_collider(ball_collider);
+
+    assert!(world
+        .set_manifold_recorder(Some(ManifoldRecorder::new(3)))
+        .is_none());
+    for _ in 0..5 {
+        world.step(1.0 / 60.0);
+    }
+
+    let recorder = world.manifold_recorder().unwrap();
+    assert_eq!(recorder.len(), 3);
+    let frames: Vec<u32> = recorder.frames().map(|frame| frame.frame).collect();
+    assert_eq!(frames, vec![frames[0], frames[0] + 1, frames[0] + 2]);
+    for frame in recorder.frames() {
+        assert_eq!(frame.manifolds.len(), 1);
+        assert!(frame.manifolds[0].points[0].normal_impulse > 0.0);
+    }
+
+    let json = recorder.to_json();
+    assert!(json.starts_with("{\"frames\":[{\"frame\":"));
+    assert_eq!(json.matches("\"manifolds\"").count(), 3);
+    assert!(json.contains(&format!("\"body_b\":{}", ball_id.to_bits())));
+    assert_eq!(json.matches('{').count(), json.matches('}').count());
+    assert_eq!(json.matches('[').count(), json.matches(']').count());
+
+    world.manifold_recorder_mut().unwrap().set_paused(true);
+    world.step(1.0 / 60.0);
+    let recorder = world.set_manifold_recorder(None).unwrap();
+    assert_eq!(recorder.frames().next().unwrap().frame, frames[0]);
+}
+
+#[test]
+fn add_rigidbody_conditions_bad_mass_properties() {
+    let broken = MassProperties {
+        mass: 0.0,
+        inertia: Mat3::from_diagonal(Vec3::new(1.0, 1.0, 0.0)),
+    };
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut body = RigidBody::new(EntityId::default());
+    body.mass_properties = broken;
+    let id = world.add_rigidbody(body);
+    let stored = world.body(id).unwrap();
+    assert_eq!(stored.inverse_mass(), 1.0);
+    assert!(stored.inverse_inertia().is_finite());
+    assert!(stored.to_rigid_body().mass_properties.validate().is_empty());
+
+    // Warn-only keeps the body as given; static bodies are never checked.
+    world.set_mass_validation(MassValidation::Warn);
+    let mut body = RigidBody::new(EntityId::default());
+    body.mass_properties = broken;
+    let id = world.add_rigidbody(body);
+    assert_eq!(world.body(id).unwrap().inverse_mass(), 0.0);
+
+    world.set_mass_validation(MassValidation::Correct);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    ground.mass_properties = broken;
+    let id = world.add_rigidbody(ground);
+    assert_eq!(
+        world.body(id).unwrap().to_rigid_body().mass_properties.mass,
+        0.0
+    );
+
+    // A corrected body steps without producing NaNs.
+    for _ in 0..10 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world
+        .bodies
+        .iter()
+        .all(|body| body.transform().position.is_finite()));
+}
+
+#[test]
+fn non_finite_bodies_are_quarantined_and_reported() {
+    let dt = 1.0 / 60.0;
+    let spawn = |world: &mut PhysicsWorld| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = Vec3::new(0.0, 5.0, 0.0);
+        world.add_rigidbody(body)
+    };
+
+    let mut world = PhysicsWorld::new(dt);
+    let bad = spawn(&mut world);
+    let good = spawn(&mut world);
+    world.step(dt);
+    let before = world.body(bad).unwrap().transform().position;
+    world
+        .body_mut(bad)
+        .unwrap()
+        .set_velocity(Vec3::new(f32::NAN, 0.0, 0.0), Vec3::ZERO);
+    world.step(dt);
+
+    assert_eq!(
+        world.non_finite_events(),
+        &[NonFiniteEvent {
+            body: bad,
+            stage: SimulationStage::Solver,
+            policy: NonFinitePolicy::Freeze,
+        }]
+    );
+    // The frozen body keeps its last pose and stays put; its neighbour is untouched.
+    for _ in 0..5 {
+        world.step(dt);
+    }
+    let frozen = world.body(bad).unwrap();
+    assert!(!frozen.is_enabled());
+    assert_eq!(frozen.transform().position, before);
+    assert!(world.non_finite_events().is_empty());
+    assert!(world.body(good).unwrap().transform().position.y < before.y);
+
+    let mut world = PhysicsWorld::new(dt);
+    world.set_non_finite_policy(NonFinitePolicy::ResetToLastGood);
+    let bad = spawn(&mut world);
+    world.step(dt);
+    let before = world.body(bad).unwrap().velocity().linear;
+    world
+        .body_mut(bad)
+        .unwrap()
+        .set_velocity(Vec3::ZERO, Vec3::splat(f32::INFINITY));
+    world.step(dt);
+    assert_eq!(world.drain_non_finite_events().count(), 1);
+    let reset = world.body(bad).unwrap();
+    assert!(reset.is_enabled());
+    assert_eq!(reset.velocity().angular, Vec3::ZERO);
+    // Integration still runs after the reset, so only damping separates the two.
+    assert!((reset.velocity().linear - before).length() < 1e-3);
+    assert!(reset.velocity().linear.y < 0.0);
+}
+
+#[test]
+#[should_panic(expected = "non-finite during solver")]
+fn non_finite_panic_policy_names_the_stage() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.set_non_finite_policy(NonFinitePolicy::Panic);
+    let mut body = RigidBody::new(EntityId::default());
+    body.velocity.linear = Vec3::new(0.0, f32::NAN, 0.0);
+    world.add_rigidbody(body);
+    world.step(1.0 / 60.0);
+}
+
+#[test]
+fn aero_surfaces_lift_wings_and_drag_projectiles() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut spawn = |pitch: f32| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.rotation = Quat::from_rotation_z(pitch);
+        body.velocity.linear = Vec3::new(20.0, 0.0, 0.0);
+        world.add_rigidbody(body)
+    };
+    let glider = spawn(5f32.to_radians());
+    let diver = spawn(-5f32.to_radians());
+    let bullet = spawn(0.0);
+    let plain = spawn(0.0);
+    world
+        .dynamics
+        .force_registry
+        .add_force(AeroSurface::wing(Vec3::Y, 0.1).body(glider));
+    world
+        .dynamics
+        .force_registry
+        .add_force(AeroSurface::wing(Vec3::Y, 0.1).body(diver));
+    world
+        .dynamics
+        .force_registry
+        .add_force(AeroSurface::projectile(0.1, 0.5).body(bullet));
+
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let velocity = |id| world.body(id).unwrap().velocity().linear;
+
+    // Five degrees nose-up at 20 m/s lifts more than the body weighs.
+    assert!(velocity(glider).y > 0.0, "glider {}", velocity(glider));
+    assert!(velocity(diver).y < velocity(plain).y);
+    // Drag alone slows the projectile's flight and its fall alike.
+    assert!(velocity(bullet).x < velocity(plain).x - 0.5);
+    assert!(velocity(bullet).y > velocity(plain).y);
+}
+
+#[test]
+fn explosions_push_and_spin_bodies_in_range() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut spawn = |position: Vec3, mut collider: Collider| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = position;
+        let id = world.add_rigidbody(body);
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+        id
+    };
+    let near = spawn(
+        Vec3::new(2.0, 0.0, 0.0),
+        Collider::builder().sphere(0.5).build(),
+    );
+    let far = spawn(
+        Vec3::new(0.0, 0.0, -4.0),
+        Collider::builder().sphere(0.5).build(),
+    );
+    let outside = spawn(
+        Vec3::new(10.0, 0.0, 0.0),
+        Collider::builder().sphere(0.5).build(),
+    );
+    // Raised so the blast catches its lower edge.
+    let crate_id = spawn(
+        Vec3::new(-3.0, 0.5, 0.0),
+        Collider::builder().box_shape(Vec3::splat(0.5)).build(),
+    );
+
+    let mut hit = world.apply_explosion(Vec3::ZERO, 5.0, 10.0, ExplosionFalloff::Linear);
+    hit.sort();
+    let mut expected = vec![near, far, crate_id];
+    expected.sort();
+    assert_eq!(hit, expected);
+
+    let velocity = |id| *world.body(id).unwrap().velocity();
+    assert!(velocity(near).linear.x > 0.0);
+    assert!(velocity(far).linear.z < 0.0);
+    assert!(velocity(near).linear.length() > velocity(far).linear.length());
+    assert_eq!(velocity(outside).linear, Vec3::ZERO);
+    // Pushed along -X below its centre of mass, the crate tips over backwards.
+    assert!(velocity(crate_id).linear.x < 0.0);
+    assert!(velocity(crate_id).angular.z < 0.0);
+}
+
+#[test]
+fn force_fields_act_on_bodies_overlapping_them() {
+    let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+    let mut spawn = |position: Vec3| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = position;
+        let id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+        id
+    };
+    // Its centre is outside the wind box but its collider reaches in.
+    let kite = spawn(Vec3::new(0.0, 1.4, 0.0));
+    let sheltered = spawn(Vec3::new(0.0, 3.0, 0.0));
+    let satellite = spawn(Vec3::new(20.0, 0.0, 0.0));
+    let leaf = spawn(Vec3::new(-18.0, 0.0, 0.0));
+
+    world.add_force_field(
+        ForceField::wind(
+            FieldVolume::Box {
+                center: Vec3::ZERO,
+                half_extents: Vec3::ONE,
+            },
+            Vec3::new(0.0, 0.0, 5.0),
+            2.0,
+        )
+        .turbulence(1.0, 0.5),
+    );
+    world.add_force_field(ForceField::attractor(
+        FieldVolume::Sphere {
+            center: Vec3::new(22.0, 0.0, 0.0),
+            radius: 3.0,
+        },
+        4.0,
+    ));
+    let vortex = world.add_force_field(ForceField::vortex(
+        FieldVolume::Sphere {
+            center: Vec3::new(-20.0, 0.0, 0.0),
+            radius: 5.0,
+        },
+        Vec3::Y,
+        3.0,
+        0.0,
+    ));
+
+    for _ in 0..10 {
+        world.step(1.0 / 60.0);
+    }
+    let velocity = |world: &PhysicsWorld, id| world.body(id).unwrap().velocity().linear;
+
+    assert!(velocity(&world, kite).z > 0.5);
+    assert_eq!(velocity(&world, sheltered), Vec3::ZERO);
+    assert!(velocity(&world, satellite).x > 0.0);
+    // Right-handed about +Y: a body on the +X side of the axis moves toward -Z.
+    let swirl = velocity(&world, leaf);
+    assert!(swirl.z < 0.0 && swirl.x.abs() < -swirl.z);
+
+    world.force_field_mut(vortex).unwrap().enabled = false;
+    world.step(1.0 / 60.0);
+    assert!((velocity(&world, leaf) - swirl).length() < 1e-3);
+}
+
+#[test]
+fn gyroscopic_torque_flips_spin_about_intermediate_axis() {
+    let spin = |gyroscopic: bool| {
+        let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+        world.integrator.set_gyroscopic(gyroscopic);
+        let mut body = RigidBody::new(EntityId::default());
+        body.set_mass_properties(MassProperties {
+            mass: 1.0,
+            inertia: Mat3::from_diagonal(Vec3::new(1.0, 2.0, 3.0)),
+        });
+        body.angular_velocity_damping = 0.0;
+        body.velocity.angular = Vec3::new(0.01, 5.0, 0.0);
+        let id = world.add_rigidbody(body);
+
+        let energy = |world: &PhysicsWorld| {
+            let body = world.body(id).unwrap();
+            let omega = body.transform().rotation.inverse() * body.velocity().angular;
+            0.5 * omega.dot(Mat3::from_diagonal(Vec3::new(1.0, 2.0, 3.0)) * omega)
+        };
+        let start = energy(&world);
+        let mut lowest_spin = f32::MAX;
+        for _ in 0..300 {
+            world.step(1.0 / 60.0);
+            let body = world.body(id).unwrap();
+            let omega = body.transform().rotation.inverse() * body.velocity().angular;
+            lowest_spin = lowest_spin.min(omega.y);
+        }
+        (lowest_spin, energy(&world) / start)
+    };
+
+    // The Dzhanibekov effect: the body turns over and spins the other way round.
+    let (lowest_spin, energy_ratio) = spin(true);
+    assert!(lowest_spin < -4.0, "spin only dropped to {lowest_spin}");
+    assert!(energy_ratio <= 1.001, "energy grew by {energy_ratio}");
+    let (lowest_spin, _) = spin(false);
+    assert!(lowest_spin > 4.9);
+}
+
+#[test]
+fn higher_order_integration_schemes_track_exact_motion() {
+    // A damped throw has v(t) = v0·e^(-ct) + g(1 - e^(-ct))/c.
+    let (damping, thrown) = (0.5f32, Vec3::new(3.0, 8.0, 0.0));
+    let gravity = Vec3::new(0.0, -9.81, 0.0);
+    let decay = (-damping).exp();
+    let exact = thrown * decay + gravity * (1.0 - decay) / damping;
+
+    let velocity_error = |scheme: IntegrationScheme| {
+        let mut world = PhysicsWorld::builder().gravity(gravity).build();
+        world.integrator = Integrator::new(1.0 / 60.0, 1);
+        world.set_integration_scheme(scheme);
+        let mut body = RigidBody::new(EntityId::default());
+        body.linear_velocity_damping = damping;
+        body.velocity.linear = thrown;
+        let id = world.add_rigidbody(body);
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        (world.body(id).unwrap().velocity().linear - exact).length()
+    };
+
+    let euler = velocity_error(IntegrationScheme::SymplecticEuler);
+    let verlet = velocity_error(IntegrationScheme::Verlet);
+    let rk4 = velocity_error(IntegrationScheme::RK4);
+    assert!(rk4 < 1e-4, "RK4 missed by {rk4}");
+    assert!(rk4 < euler * 0.01, "RK4 {rk4} vs Euler {euler}");
+    assert!(verlet <= euler + 1e-5);
+
+    // Verlet puts half of each substep's gravity into the position.
+    let drop = |scheme: IntegrationScheme| {
+        let mut world = PhysicsWorld::builder().gravity(gravity).build();
+        world.integrator = Integrator::new(1.0 / 60.0, 1);
+        world.set_integration_scheme(scheme);
+        let mut body = RigidBody::new(EntityId::default());
+        body.linear_velocity_damping = 0.0;
+        let id = world.add_rigidbody(body);
+        for _ in 0..60 {
+            world.step(1.0 / 60.0);
+        }
+        world.body(id).unwrap().transform().position.y
+    };
+    let exact_drop = 0.5 * gravity.y;
+    assert!((drop(IntegrationScheme::Verlet) - exact_drop).abs() < 1e-3);
+    assert!((drop(IntegrationScheme::RK4) - exact_drop).abs() < 1e-3);
+    assert!((drop(IntegrationScheme::SymplecticEuler) - exact_drop).abs() > 0.02);
+}
+
+#[test]
+fn step_hooks_run_around_every_fixed_step() {
+    use std::sync::{Arc, Mutex};
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let id = world.add_rigidbody(RigidBody::new(EntityId::default()));
+    // Hold the body up against gravity, and record where each step left it.
+    world.add_pre_step_hook(move |bodies, _| {
+        if let Some(mut body) = bodies.get_mut(id) {
+            let weight = -body.gravity(Vec3::new(0.0, -9.81, 0.0)) / *body.inverse_mass;
+            body.apply_force(weight);
+        }
+    });
+    let heights = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&heights);
+    world.add_post_step_hook(move |bodies, dt| {
+        let height = bodies.get(id).unwrap().transform().position.y;
+        recorded.lock().unwrap().push((height, dt));
+    });
+
+    world.step(3.0 / 60.0 + 1e-4);
+    {
+        let heights = heights.lock().unwrap();
+        assert_eq!(heights.len(), 3);
+        assert!(heights
+            .iter()
+            .all(|&(height, dt)| height.abs() < 1e-5 && dt == 1.0 / 60.0));
+    }
+
+    world.clear_step_hooks();
+    world.step(1.0 / 60.0);
+    assert_eq!(heights.lock().unwrap().len(), 3);
+    assert!(world.body(id).unwrap().transform().position.y < 0.0);
+}
+
+#[test]
+fn rollback_and_resimulate_reproduce_the_original_steps() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    let ground = world.add_rigidbody(ground);
+    world.add_collider(Collider {
+        rigidbody_id: ground,
+        ..Collider::builder()
+            .box_shape(Vec3::new(10.0, 0.5, 10.0))
+            .build()
+    });
+    let mut ids = Vec::new();
+    for level in 0..2 {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = Vec3::new(0.0, 1.5 + 1.2 * level as f32, 0.0);
+        let id = world.add_rigidbody(body);
+        world.add_collider(Collider {
+            rigidbody_id: id,
+            ..Collider::builder().box_shape(Vec3::splat(0.5)).build()
+        });
+        ids.push(id);
+    }
+    // One player input per frame: a shove on the bottom box, off its centre.
+    let input = |frame: usize| {
+        let target = ids[0];
+        move |world: &mut PhysicsWorld| {
+            let mut body = world.body_mut(target).unwrap();
+            let point = body.transform.position + Vec3::new(0.0, 0.3, 0.0);
+            body.apply_impulse(Vec3::new(frame as f32 % 5.0 - 2.0, 0.0, 0.5), point);
+        }
+    };
+    let poses = |world: &PhysicsWorld| -> Vec<(Vec3, Quat, Vec3)> {
+        ids.iter()
+            .map(|&id| {
+                let body = world.body(id).unwrap();
+                let transform = body.transform();
+                (
+                    transform.position,
+                    transform.rotation,
+                    body.velocity().linear,
+                )
+            })
+            .collect()
+    };
+
+    world.set_rollback_frames(30);
+    assert_eq!(world.rollback_frames(), 30);
+    world.resimulate((0..40).map(input));
+    let expected = poses(&world);
+
+    assert!(world.rollback(12));
+    assert_ne!(poses(&world), expected);
+    world.resimulate((28..40).map(input));
+    assert_eq!(poses(&world), expected);
+
+    // Only the last 30 steps are kept, so the first ones are out of reach.
+    assert!(!world.rollback(30));
+    assert_eq!(poses(&world), expected);
+    assert!(world.rollback(29));
+    world.resimulate((11..40).map(input));
+    assert_eq!(poses(&world), expected);
+}
+
+#[test]
+fn removing_a_body_takes_its_colliders_joints_and_contacts_with_it() {
+    use particle_accelerator::core::constraints::Joint;
+
+    let mut world = PhysicsWorld::builder()
+        .time_step(1.0 / 60.0)
+        .broadphase_backend(BroadPhaseBackend::Incremental)
+        .build();
+    let spawn_box = |world: &mut PhysicsWorld, position: Vec3, is_static: bool| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = position;
+        body.is_static = is_static;
+        let id = world.add_rigidbody(body);
+        let half = if is_static {
+            Vec3::new(10.0, 0.5, 10.0)
+        } else {
+            Vec3::splat(0.5)
+        };
+        let collider = world.add_collider(Collider {
+            rigidbody_id: id,
+            ..Collider::builder().box_shape(half).build()
+        });
+        (id, collider)
+    };
+    spawn_box(&mut world, Vec3::ZERO, true);
+    let (bottom, bottom_collider) = spawn_box(&mut world, Vec3::new(0.0, 1.0, 0.0), false);
+    let (top, top_collider) = spawn_box(&mut world, Vec3::new(0.0, 2.0, 0.0), false);
+    let mut sensor = RigidBody::new(EntityId::default());
+    sensor.is_static = true;
+    sensor.transform.position = Vec3::new(0.0, 1.0, 0.0);
+    let sensor = world.add_rigidbody(sensor);
+    world.add_collider(Collider {
+        rigidbody_id: sensor,
+        ..Collider::builder()
+            .box_shape(Vec3::splat(0.2))
+            .is_trigger(true)
+            .build()
+    });
+    let bob = world.add_rigidbody(RigidBody::new(EntityId::default()));
+    world.add_joint(Joint::Distance {
+        body_a: bottom,
+        body_b: bob,
+        distance: 3.0,
+    });
+    world.add_joint(Joint::Distance {
+        body_a: sensor,
+        body_b: bob,
+        distance: 3.0,
+    });
+
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    assert_eq!(world.trigger_overlaps().len(), 1);
+    let proxies = world.collision.broadphase.pair_manager().proxy_count();
+
+    let removed = world.remove_rigidbody(bottom).unwrap();
+    assert_eq!(removed.body.id, bottom);
+    assert_eq!(
+        removed
+            .colliders
+            .iter()
+            .map(|collider| collider.id)
+            .collect::<Vec<_>>(),
+        [bottom_collider]
+    );
+    assert_eq!(removed.joints.len(), 1);
+    assert_eq!(removed.joints[0].bodies(), (bottom, bob));
+    assert_eq!(world.dynamics.joints.len(), 1);
+    assert!(world.body(bottom).is_none());
+    assert!(world.collider(bottom_collider).is_none());
+    assert!(world.remove_rigidbody(bottom).is_none());
+    assert!(world.trigger_overlaps().is_empty());
+    assert!(matches!(
+        world.trigger_events(),
+        [TriggerEvent::Exit(overlap)] if overlap.other == bottom_collider
+    ));
+    assert_eq!(
+        world.collision.broadphase.pair_manager().proxy_count(),
+        proxies - 1
+    );
+    assert!(world.body(top).unwrap().is_awake());
+
+    // The top box drops onto the ground in its place.
+    let mut lowest = f32::MAX;
+    for _ in 0..90 {
+        world.step(1.0 / 60.0);
+        lowest = lowest.min(world.body(top).unwrap().transform().position.y);
+    }
+    assert!((lowest - 1.0).abs() < 0.1, "top box got down to {lowest}");
+
+    // Without its collider it falls through.
+    let collider = world.remove_collider(top_collider).unwrap();
+    assert_eq!(collider.rigidbody_id, top);
+    assert!(world.remove_collider(top_collider).is_none());
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world.body(top).unwrap().transform().position.y < -1.0);
+}
+
+#[test]
+fn batch_insertion_matches_adding_one_at_a_time() {
+    let bodies = || {
+        (0..40).map(|i| {
+            let mut body = RigidBody::new(EntityId::default());
+            body.transform.position = Vec3::new((i % 8) as f32 * 1.5, 1.0 + (i / 8) as f32, 0.0);
+            body
+        })
+    };
+    // Two colliders with density per body, off-centre, plus a massless sensor.
+    let colliders = |ids: &[EntityId]| {
+        ids.iter()
+            .flat_map(|&id| {
+                [
+                    Collider::builder().sphere(0.3).density(2.0).build(),
+                    Collider::builder()
+                        .box_shape(Vec3::splat(0.2))
+                        .offset(Transform::from_position(Vec3::new(0.4, 0.0, 0.0)))
+                        .density(1.0)
+                        .build(),
+                    Collider::builder().sphere(0.6).is_trigger(true).build(),
+                ]
+                .map(|collider| Collider {
+                    rigidbody_id: id,
+                    ..collider
+                })
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut one_by_one = PhysicsWorld::new(1.0 / 60.0);
+    let ids: Vec<EntityId> = bodies()
+        .map(|body| one_by_one.add_rigidbody(body))
+        .collect();
+    for collider in colliders(&ids) {
+        one_by_one.add_collider(collider);
+    }
+
+    let mut batched = PhysicsWorld::new(1.0 / 60.0);
+    let batch_ids = batched.add_bodies(bodies());
+    assert_eq!(batch_ids, ids);
+    let collider_ids = batched.add_colliders(colliders(&ids));
+    assert_eq!(collider_ids.len(), 120);
+
+    for &id in &ids {
+        let (a, b) = (one_by_one.body(id).unwrap(), batched.body(id).unwrap());
+        assert!(a
+            .transform()
+            .position
+            .abs_diff_eq(b.transform().position, 1e-5));
+        assert!((a.inverse_mass() - b.inverse_mass()).abs() < 1e-5);
+        assert!(a.inverse_inertia().abs_diff_eq(b.inverse_inertia(), 1e-4));
+        assert!(one_by_one
+            .center_of_mass_shift(id)
+            .abs_diff_eq(batched.center_of_mass_shift(id), 1e-5));
+    }
+    for &id in &collider_ids {
+        let (a, b) = (
+            one_by_one.collider(id).unwrap(),
+            batched.collider(id).unwrap(),
+        );
+        assert_eq!(a.id, b.id);
+        assert!(a.offset.position.abs_diff_eq(b.offset.position, 1e-5));
+    }
+}
+
+#[test]
+fn bodies_outside_active_regions_freeze_until_a_region_reaches_them() {
+    let mut world = PhysicsWorld::builder()
+        .broadphase_backend(BroadPhaseBackend::SweepAndPrune)
+        .build();
+    let mut add_sphere = |position: Vec3, velocity: Vec3| {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = position;
+        body.velocity.linear = velocity;
+        let id = world.add_rigidbody(body);
+        world.add_collider(Collider {
+            rigidbody_id: id,
+            ..Collider::builder().sphere(0.5).build()
+        });
+        id
+    };
+    let near = add_sphere(Vec3::new(0.0, 5.0, 0.0), Vec3::ZERO);
+    let far = add_sphere(Vec3::new(100.0, 5.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+
+    let home = world.activate_region(Aabb::new(Vec3::splat(-20.0), Vec3::splat(20.0)));
+    for _ in 0..10 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(!world.is_frozen(near));
+    assert!(world.body(near).unwrap().transform().position.y < 5.0);
+    assert!(world.is_frozen(far));
+    let parked = world.body(far).unwrap();
+    assert_eq!(parked.transform().position, Vec3::new(100.0, 5.0, 0.0));
+    assert_eq!(parked.velocity().linear, Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(
+        world.collision.broadphase.sweep_and_prune().proxy_count(),
+        1
+    );
+
+    // Streaming in the far region thaws the body with the velocity it was frozen with.
+    let away = world.activate_region(Aabb::new(
+        Vec3::new(90.0, -20.0, -20.0),
+        Vec3::new(110.0, 20.0, 20.0),
+    ));
+    world.step(1.0 / 60.0);
+    assert!(!world.is_frozen(far));
+    let thawed = world.body(far).unwrap();
+    assert!(thawed.transform().position.x > 100.0);
+    assert!(thawed.transform().position.y < 5.0);
+    assert!(thawed.velocity().linear.x > 0.9);
+    assert_eq!(
+        world.collision.broadphase.sweep_and_prune().proxy_count(),
+        2
+    );
+
+    // Margins keep bodies just outside a region awake.
+    world.deactivate_region(home);
+    world.set_region_margin(200.0);
+    world.update_regions();
+    assert!(!world.is_frozen(near));
+    world.set_region_margin(0.0);
+    world.update_regions();
+    assert!(world.is_frozen(near));
+
+    // Removing a frozen body hands it back enabled, and dropping the last region thaws
+    // everything.
+    let removed = world.remove_rigidbody(near).unwrap();
+    assert!(removed.body.is_enabled);
+    assert!(world.deactivate_region(away).is_some());
+    assert_eq!(world.active_regions().count(), 0);
+    world.update_regions();
+    assert!(!world.is_frozen(far));
+}
+
+#[test]
+fn step_stats_break_down_each_step_by_stage() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    let ground = world.add_rigidbody(ground);
+    world.add_collider(Collider {
+        rigidbody_id: ground,
+        ..Collider::builder()
+            .box_shape(Vec3::new(10.0, 0.5, 10.0))
+            .build()
+    });
+    for x in [-2.0, 2.0] {
+        let mut ball = RigidBody::new(EntityId::default());
+        ball.transform.position = Vec3::new(x, 0.95, 0.0);
+        let ball = world.add_rigidbody(ball);
+        world.add_collider(Collider {
+            rigidbody_id: ball,
+            ..Collider::builder().sphere(0.5).build()
+        });
+    }
+
+    world.step(2.0 / 60.0);
+    let stats = *world.step_stats();
+    assert_eq!(stats.fixed_steps, 2);
+    assert_eq!(
+        stats.manifolds, 4,
+        "both balls touch the ground in both steps"
+    );
+    assert!(stats.broadphase_pairs >= stats.manifolds);
+    assert!(stats.contacts >= stats.manifolds);
+    assert!(stats.awake_islands >= 1 && stats.awake_islands <= stats.islands);
+    assert_eq!(
+        stats.solver_iterations,
+        stats.awake_islands as u32 * world.dynamics.solver.velocity_iterations
+    );
+    assert!(stats.largest_island >= 2);
+    assert!(
+        stats.total_time
+            >= stats.broadphase_time
+                + stats.narrowphase_time
+                + stats.solver_time
+                + stats.integration_time
+    );
+
+    // A call too short for a fixed step reports nothing.
+    world.step(0.1 / 60.0);
+    assert_eq!(world.step_stats().fixed_steps, 0);
+    assert_eq!(world.step_stats().broadphase_pairs, 0);
+}
+
+#[derive(Default)]
+struct LineRecorder {
+    lines: Vec<(Vec3, Vec3, DebugColor)>,
+}
+
+impl DebugRenderBackend for LineRecorder {
+    fn draw_line(&mut self, start: Vec3, end: Vec3, color: DebugColor) {
+        self.lines.push((start, end, color));
+    }
+}
+
+#[test]
+fn debug_render_emits_wireframes_contacts_and_joints() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    let ground = world.add_rigidbody(ground);
+    world.add_collider(Collider {
+        rigidbody_id: ground,
+        ..Collider::builder()
+            .box_shape(Vec3::new(5.0, 0.5, 5.0))
+            .build()
+    });
+    let mut crate_body = RigidBody::new(EntityId::default());
+    crate_body.transform.position = Vec3::new(0.0, 0.99, 0.0);
+    let crate_body = world.add_rigidbody(crate_body);
+    world.add_collider(Collider {
+        rigidbody_id: crate_body,
+        ..Collider::builder().box_shape(Vec3::splat(0.5)).build()
+    });
+    world.set_debug_render_options(DebugRenderOptions {
+        contacts: false,
+        joints: false,
+        ..DebugRenderOptions::default()
+    });
+
+    // Two boxes are twelve edges each, every one on the box it outlines.
+    let mut recorder = LineRecorder::default();
+    world.debug_render(&mut recorder);
+    assert_eq!(recorder.lines.len(), 24);
+    let center = world.body(crate_body).unwrap().transform().position;
+    let crate_lines = recorder
+        .lines
+        .iter()
+        .filter(|(start, end, _)| start.y > 0.6 && end.y > 0.6);
+    for (start, end, _) in crate_lines {
+        for point in [start, end] {
+            let local = (*point - center).abs();
+            assert!((local.max_element() - 0.5).abs() < 1e-4, "{local}");
+        }
+    }
+
+    world.step(1.0 / 60.0);
+    world.add_joint(particle_accelerator::core::constraints::Joint::Distance {
+        body_a: ground,
+        body_b: crate_body,
+        distance: 1.0,
+    });
+    world.set_debug_render_options(DebugRenderOptions {
+        colliders: false,
+        ..DebugRenderOptions::default()
+    });
+    let mut recorder = LineRecorder::default();
+    world.debug_render(&mut recorder);
+    let contact_points: usize = world
+        .manifold_debug_snapshots()
+        .iter()
+        .map(|manifold| manifold.points.len())
+        .sum();
+    assert!(contact_points > 0);
+    // A cross and a normal per contact point, and one line for the distance joint.
+    assert_eq!(recorder.lines.len(), contact_points * 4 + 1);
+    assert!(recorder
+        .lines
+        .iter()
+        .all(|(start, end, _)| start.is_finite() && end.is_finite()));
+}
+
+#[test]
+fn solver_config_round_trips_and_drives_the_solver() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    assert_eq!(world.solver_config(), SolverConfig::default());
+
+    let config = SolverConfig {
+        velocity_iterations: 9,
+        position_iterations: 3,
+        bias: 0.1,
+        slop: 0.005,
+        warm_starting: false,
+        ccd_enabled: false,
+        position_correction: PositionCorrection::SplitImpulse,
+        block_solver: BlockSolverMode::TwoPoint,
+    };
+    world.set_solver_config(config);
+    assert_eq!(world.solver_config(), config);
+    assert!(!world.ccd().enabled);
+    let built = PhysicsWorld::builder().solver_config(config).build();
+    assert_eq!(built.solver_config(), config);
+
+    // Tall stacks settle lower when contacts restart from zero impulse every step.
+    let stack_top = |warm_starting: bool| {
+        let mut world = PhysicsWorld::builder()
+            .solver_config(SolverConfig {
+                velocity_iterations: 2,
+                warm_starting,
+                ..SolverConfig::default()
+            })
+            .build();
+        let mut ground = RigidBody::new(EntityId::default());
+        ground.is_static = true;
+        let ground = world.add_rigidbody(ground);
+        world.add_collider(Collider {
+            rigidbody_id: ground,
+            ..Collider::builder()
+                .box_shape(Vec3::new(5.0, 0.5, 5.0))
+                .build()
+        });
+        let mut top = ground;
+        for level in 0..8 {
+            let mut body = RigidBody::new(EntityId::default());
+            body.transform.position = Vec3::new(0.0, 1.0 + level as f32, 0.0);
+            top = world.add_rigidbody(body);
+            world.add_collider(Collider {
+                rigidbody_id: top,
+                ..Collider::builder().box_shape(Vec3::splat(0.5)).build()
+            });
+        }
+        for _ in 0..180 {
+            world.step(1.0 / 60.0);
+        }
+        world.body(top).unwrap().transform().position.y
+    };
+    let (warm, cold) = (stack_top(true), stack_top(false));
+    assert!(warm > cold, "warm {warm}, cold {cold}");
+}
+
+#[derive(Clone, Default)]
+struct StateRecorder {
+    states: std::sync::Arc<std::sync::Mutex<Vec<GpuWorldState>>>,
+}
+
+impl ComputeBackend for StateRecorder {
+    fn name(&self) -> &str {
+        "recorder"
+    }
+
+    fn prepare_step(&self, state: &GpuWorldState) {
+        self.states.lock().unwrap().push(state.clone());
+    }
+}
+
+#[test]
+fn compute_backends_receive_the_synced_world_state() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(0.0, 3.0, 0.0);
+    ball.velocity.linear = Vec3::new(1.0, 0.0, 0.0);
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    world.add_collider(ball_collider);
+
+    let recorder = StateRecorder::default();
+    world.set_gpu_backend(recorder.clone());
+    world.step(1.0 / 60.0);
+
+    let states = recorder.states.lock().unwrap();
+    assert_eq!(states.len(), 1, "one sync per fixed step");
+    let state = &states[0];
+    assert_eq!(state.ids, vec![ground_id, ball_id]);
+    assert_eq!(state.time_step, 1.0 / 60.0);
+    assert_eq!(state.bodies[0].inverse_mass, 0.0);
+    assert!((state.bodies[0].radius - Vec3::new(5.0, 0.5, 5.0).length()).abs() < 1e-4);
+    let ball_state = state.bodies[1];
+    assert!((ball_state.radius - 0.5).abs() < 1e-4);
+    assert!(ball_state.inverse_mass > 0.0);
+    assert_eq!(ball_state.velocity.x, 1.0);
+    assert!(ball_state.position.y < 3.0 + 1e-4);
+    assert_eq!(
+        state.pair_ids(&[(0, 1), (1, 7)]),
+        vec![(ground_id, ball_id)],
+        "out-of-range indices are dropped"
+    );
+}
+
+#[test]
+fn gpu_state_carries_rotation_shapes_and_filters() {
+    use particle_accelerator::gpu::{GpuFilter, GpuInstance, GpuShape, GpuShapeKind};
+
+    // std430 layouts the kernels bind against.
+    assert_eq!(std::mem::size_of::<GpuShape>(), 48);
+    assert_eq!(std::mem::size_of::<glam::Vec3A>(), 16);
+    assert_eq!(std::mem::size_of::<glam::Mat3A>(), 48);
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.gravity = Vec3::ZERO;
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut plane = Collider::builder().plane(Vec3::Y).filter(2, 1).build();
+    plane.rigidbody_id = ground_id;
+    world.add_collider(plane);
+
+    let mut spinner = RigidBody::new(EntityId::from_index(1));
+    spinner.transform.rotation = Quat::from_rotation_y(0.5);
+    spinner.velocity.angular = Vec3::new(0.0, 2.0, 0.0);
+    let spinner_id = world.add_rigidbody(spinner);
+    let child = Transform {
+        position: Vec3::new(1.0, 0.0, 0.0),
+        ..Transform::default()
+    };
+    let mut dumbbell = Collider::builder()
+        .shape(ColliderShape::Compound {
+            shapes: vec![
+                (child, Collider::sphere(0.25)),
+                (Transform::default(), Collider::cuboid(Vec3::splat(0.5))),
+            ],
+        })
+        .build();
+    dumbbell.rigidbody_id = spinner_id;
+    let dumbbell_id = world.add_collider(dumbbell);
+
+    let recorder = StateRecorder::default();
+    world.set_gpu_backend(recorder.clone());
+    world.step(1.0 / 60.0);
+    let state = recorder.states.lock().unwrap()[0].clone();
+
+    assert_eq!(state.orientations.len(), 2);
+    assert!(state.orientations[1].angle_between(Quat::from_rotation_y(0.5)) < 1e-3);
+    assert_eq!(state.angular_velocities[1].y, 2.0);
+    assert_eq!(
+        state.inverse_inertias[0],
+        glam::Mat3A::ZERO,
+        "static bodies don't rotate"
+    );
+    assert_ne!(state.inverse_inertias[1], glam::Mat3A::ZERO);
+
+    // The compound collider is flattened into one shape per child.
+    assert_eq!(state.shape_count(), 3);
+    assert_eq!(state.shape_bodies, vec![0, 1, 1]);
+    assert_eq!(state.shape_colliders[1..], [dumbbell_id, dumbbell_id]);
+    assert_eq!(state.shapes[0].kind, GpuShapeKind::Plane);
+    assert_eq!(state.shapes[0].params.truncate(), Vec3::Y);
+    assert_eq!(state.shape_filters[0], GpuFilter { layer: 2, mask: 1 });
+    assert_eq!(state.shapes[1].kind, GpuShapeKind::Sphere);
+    assert_eq!(state.shapes[1].offset, Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(state.shapes[1].params.x, 0.25);
+    assert_eq!(state.shapes[2].kind, GpuShapeKind::Box);
+    assert_eq!(state.shapes[2].params.truncate(), Vec3::splat(0.5));
+
+    // Instances a renderer binds follow the body columns.
+    assert_eq!(std::mem::size_of::<GpuInstance>(), 32);
+    let instances = state.instances();
+    assert_eq!(instances.len(), 2);
+    assert_eq!(instances[1].position, state.bodies[1].position);
+    assert_eq!(instances[1].radius, state.bodies[1].radius);
+    assert_eq!(instances[1].rotation, state.orientations[1]);
+}
+
+/// Reports every body pair after a fixed number of non-blocking polls.
+struct SlowPairs;
+
+impl ComputeBackend for SlowPairs {
+    fn name(&self) -> &str {
+        "slow-pairs"
+    }
+
+    fn dispatch_broadphase(&self, state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        let count = state.body_count() as u32;
+        let mut polls_left = 2;
+        Some(GpuReadback::pending(move |block| {
+            if !block && polls_left > 0 {
+                polls_left -= 1;
+                return None;
+            }
+            Some(
+                (0..count)
+                    .flat_map(|i| (i + 1..count).map(move |j| (i, j)))
+                    .collect(),
+            )
+        }))
+    }
+}
+
+#[test]
+fn gpu_readbacks_resolve_to_body_ids() {
+    let mut readback = SlowPairs
+        .dispatch_broadphase(&GpuWorldState {
+            bodies: vec![Default::default(); 2],
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(!readback.is_ready());
+    assert_eq!(readback.poll(), None);
+    assert_eq!(readback.poll(), Some(&vec![(0, 1)]));
+    assert_eq!(readback.into_inner(), vec![(0, 1)]);
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let a = world.add_rigidbody(RigidBody::new(EntityId::from_index(0)));
+    let b = world.add_rigidbody(RigidBody::new(EntityId::from_index(1)));
+    world.set_gpu_backend(SlowPairs);
+    world.step(1.0 / 60.0);
+    // The world waits for the readback when asked.
+    assert_eq!(world.gpu_broadphase_pairs(), Some(vec![(a, b)]));
+    assert_eq!(world.gpu_solver_velocities(), None);
+}
+
+/// Reports the same body index pairs every step.
+struct FixedPairs(Vec<(u32, u32)>);
+
+impl ComputeBackend for FixedPairs {
+    fn name(&self) -> &str {
+        "fixed-pairs"
+    }
+
+    fn dispatch_broadphase(&self, _state: &GpuWorldState) -> Option<GpuReadback<Vec<(u32, u32)>>> {
+        Some(GpuReadback::ready(self.0.clone()))
+    }
+}
+
+#[test]
+fn gpu_broadphase_pairs_replace_the_cpu_broadphase() {
+    let touching_spheres = |pairs: Vec<(u32, u32)>| {
+        let mut world = PhysicsWorld::new(1.0 / 60.0);
+        world.gravity = Vec3::ZERO;
+        world.set_broadphase_backend(BroadPhaseBackend::Incremental);
+        for x in [0.0, 0.9] {
+            let mut body = RigidBody::new(EntityId::from_index(0));
+            body.transform.position = Vec3::new(x, 0.0, 0.0);
+            let id = world.add_rigidbody(body);
+            let mut collider = Collider::builder().sphere(0.5).build();
+            collider.rigidbody_id = id;
+            world.add_collider(collider);
+        }
+        world.set_gpu_backend(FixedPairs(pairs));
+        world.step(1.0 / 60.0);
+        world
+    };
+
+    let world = touching_spheres(Vec::new());
+    assert!(
+        world.contact_events().is_empty(),
+        "overlapping bodies the GPU didn't pair are never tested"
+    );
+    assert_eq!(world.step_stats().broadphase_pairs, 0);
+
+    let world = touching_spheres(vec![(0, 1)]);
+    assert_eq!(world.contact_events().len(), 1);
+    assert_eq!(world.step_stats().broadphase_pairs, 1);
+    assert_eq!(
+        world.collision.broadphase.pair_manager().proxy_count(),
+        0,
+        "the CPU broad phase never ran"
+    );
+}
+
+/// Keeps every particle state the world dispatches.
+#[derive(Default, Clone)]
+struct RecordedParticles(std::sync::Arc<std::sync::Mutex<Vec<GpuParticleState>>>);
+
+impl ComputeBackend for RecordedParticles {
+    fn name(&self) -> &str {
+        "recorded-particles"
+    }
+
+    fn uses_world_state(&self) -> bool {
+        false
+    }
+
+    fn simulates_particles(&self) -> bool {
+        true
+    }
+
+    fn dispatch_particles(&self, state: &GpuParticleState) {
+        self.0.lock().unwrap().push(state.clone());
+    }
+}
+
+#[test]
+fn gpu_particles_upload_only_cpu_changes() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let recorded = RecordedParticles::default();
+    world.set_gpu_backend(recorded.clone());
+    let a = world.particles.spawn(Particle::new(Vec3::ZERO).radius(0.2));
+    let b = world.particles.spawn(Particle::new(Vec3::X));
+
+    // Particles stay on the CPU until they opt in.
+    world.step(1.0 / 60.0);
+    assert!(!world.particles.is_gpu_resident());
+    assert!(recorded.0.lock().unwrap().is_empty());
+    let position = world.particles.position(b).unwrap();
+
+    world.particles.gpu = true;
+    world.step(1.0 / 60.0);
+    world.step(1.0 / 60.0);
+    world.particles.despawn(a);
+    world.particles.set_velocity(b, Vec3::Y);
+    world.step(1.0 / 60.0);
+    assert!(world.particles.is_gpu_resident());
+    assert_eq!(world.particles.position(b), Some(position));
+    {
+        let states = recorded.0.lock().unwrap();
+        assert_eq!(states.len(), 3);
+        // Moving onto the GPU uploads every slot, then only what the CPU changed.
+        let slots: Vec<u32> = states[0].writes.iter().map(|&(slot, _)| slot).collect();
+        assert_eq!(slots, vec![0, 1]);
+        assert_eq!(states[0].cell_size, 0.4);
+        assert!(states[1].writes.is_empty());
+        assert_eq!(states[2].writes, vec![(0, gpu::GpuParticle::DEAD)]);
+        assert_eq!(states[2].velocity_writes, vec![(1, Vec3::Y)]);
+        assert_eq!(states[2].slot_count, 2);
+    }
+
+    // Links aren't simulated on the GPU, so the system falls back to the CPU.
+    let c = world.particles.spawn(Particle::new(Vec3::Z));
+    world.particles.add_link(b, c, 10.0, 0.0);
+    world.step(1.0 / 60.0);
+    assert!(!world.particles.is_gpu_resident());
+    assert_eq!(recorded.0.lock().unwrap().len(), 3);
+    assert_ne!(world.particles.position(b), Some(position));
+}
+
+#[test]
+fn bodies_fall_with_their_own_gravity() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut spawn = |body: RigidBody| world.add_rigidbody(body);
+    let falling = spawn(RigidBody::builder().build());
+    let floating = spawn(RigidBody::builder().gravity_scale(0.0).build());
+    let magnetised = spawn(
+        RigidBody::builder()
+            .gravity(Vec3::new(4.0, 0.0, 0.0))
+            .gravity_scale(0.5)
+            .build(),
+    );
+
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let velocity = |world: &PhysicsWorld, id| world.body(id).unwrap().velocity().linear;
+    let fall = velocity(&world, falling);
+    assert!(fall.y < 0.0);
+    assert_eq!(velocity(&world, floating), Vec3::ZERO);
+    // Pulled along +X at half of 4 m/s² instead of down at 9.81 m/s².
+    let pulled = velocity(&world, magnetised);
+    assert_eq!(pulled.y, 0.0);
+    assert!(
+        (pulled.x / -fall.y - 2.0 / 9.81).abs() < 1e-3,
+        "{pulled} vs {fall}"
+    );
+
+    // A GravityForce generator honours the same overrides.
+    let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+    world
+        .dynamics
+        .force_registry
+        .add_force(GravityForce::new(Vec3::new(0.0, -9.81, 0.0)));
+    let rising = world.add_rigidbody(
+        RigidBody::builder()
+            .gravity(Vec3::new(0.0, 9.81, 0.0))
+            .build(),
+    );
+    world.step(1.0 / 60.0);
+    assert!(velocity(&world, rising).y > 0.0);
+}
+
+#[test]
+fn bodies_respect_damping_and_velocity_limits() {
+    let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+    let launch = Vec3::new(0.0, 0.0, 100.0);
+    let spin = Vec3::new(0.0, 50.0, 0.0);
+    let capped = world.add_rigidbody(
+        RigidBody::builder()
+            .velocity(launch, spin)
+            .max_velocity(5.0, 1.0)
+            .build(),
+    );
+    let damped = world.add_rigidbody(
+        RigidBody::builder()
+            .velocity(Vec3::X, Vec3::ZERO)
+            .damping(2.0, 0.0)
+            .build(),
+    );
+    let free = world.add_rigidbody(RigidBody::builder().velocity(Vec3::X, spin).build());
+
+    world.step(1.0 / 60.0);
+    let velocity = |id| *world.body(id).unwrap().velocity();
+    // Capped, then damped a little by the last substep.
+    assert!((velocity(capped).linear.length() - 5.0).abs() < 1e-2);
+    assert!((velocity(capped).angular.length() - 1.0).abs() < 1e-2);
+    assert!(velocity(damped).linear.x < velocity(free).linear.x - 0.02);
+    // The world default is generous enough for ordinary spins.
+    assert!(velocity(free).angular.y > 49.0);
+}
+
+#[test]
+fn kinematic_bodies_follow_targets_and_push_dynamic_bodies() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let paddle = world.add_rigidbody(
+        RigidBody::builder()
+            .body_type(BodyType::Kinematic)
+            .mass(1.0)
+            .build(),
+    );
+    let mut collider = Collider::builder().sphere(0.5).build();
+    collider.rigidbody_id = paddle;
+    world.add_collider(collider);
+
+    let ball = world.add_rigidbody(
+        RigidBody::builder()
+            .position(Vec3::new(1.5, 0.0, 0.0))
+            .gravity_scale(0.0)
+            .build(),
+    );
+    let mut collider = Collider::builder().sphere(0.5).build();
+    collider.rigidbody_id = ball;
+    world.add_collider(collider);
+
+    assert_eq!(world.body(paddle).unwrap().body_type(), BodyType::Kinematic);
+    assert_eq!(world.body(paddle).unwrap().inverse_mass(), 0.0);
+    assert!(!world.set_next_kinematic_position(ball, Vec3::ZERO));
+
+    let dt = 1.0 / 60.0;
+    for frame in 1..=30 {
+        let target = Vec3::new(frame as f32 * 0.05, 0.0, 0.0);
+        assert!(world.set_next_kinematic_position(paddle, target));
+        world.step(dt);
+        // Neither gravity nor the contact moves the paddle off its path.
+        let position = world.body(paddle).unwrap().transform().position;
+        assert!(
+            position.distance(target) < 1e-5,
+            "frame {frame}: {position}"
+        );
+    }
+
+    // The paddle swept 1.5 m and shoved the ball ahead of it.
+    let ball_x = world.body(ball).unwrap().transform().position.x;
+    assert!(ball_x > 2.4, "ball at {ball_x}");
+
+    // Without a new target the paddle stays put.
+    world.step(dt);
+    let paddle_body = world.body(paddle).unwrap();
+    assert!(
+        paddle_body
+            .transform()
+            .position
+            .distance(Vec3::new(1.5, 0.0, 0.0))
+            < 1e-5
+    );
+    assert_eq!(paddle_body.velocity().linear, Vec3::ZERO);
+}
+
+#[test]
+fn colliders_with_density_set_body_mass_and_center() {
+    let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+    let body = world.add_rigidbody(
+        RigidBody::builder()
+            .position(Vec3::new(0.0, 2.0, 0.0))
+            .build(),
+    );
+    // A heavy ball and a light ball on a rod along x.
+    let mut heavy = Collider::builder()
+        .sphere(0.5)
+        .offset(Transform::from_position(Vec3::new(-1.0, 0.0, 0.0)))
+        .density(3.0)
+        .build();
+    heavy.rigidbody_id = body;
+    let heavy = world.add_collider(heavy);
+    let mut light = Collider::builder()
+        .sphere(0.5)
+        .offset(Transform::from_position(Vec3::new(1.0, 0.0, 0.0)))
+        .density(1.0)
+        .build();
+    light.rigidbody_id = body;
+    let light = world.add_collider(light);
+
+    let ball_mass = 4.0 / 3.0 * std::f32::consts::PI * 0.125;
+    let body_ref = world.body(body).unwrap();
+    let props = body_ref.to_rigid_body().mass_properties;
+    assert!((props.mass - 4.0 * ball_mass).abs() < 1e-4);
+    assert!((body_ref.inverse_mass() - 1.0 / props.mass).abs() < 1e-4);
+    // The origin moved to the centre of mass, a quarter of the way to the heavy ball.
+    let origin = body_ref.transform().position;
+    assert!(origin.distance(Vec3::new(-0.5, 2.0, 0.0)) < 1e-5);
+    assert!(
+        world
+            .center_of_mass_shift(body)
+            .distance(Vec3::new(-0.5, 0.0, 0.0))
+            < 1e-5
+    );
+    let transform = *body_ref.transform();
+    let center = |id| {
+        world
+            .collider(id)
+            .unwrap()
+            .world_transform(&transform)
+            .position
+    };
+    assert!(center(heavy).distance(Vec3::new(-1.0, 2.0, 0.0)) < 1e-5);
+    assert!(center(light).distance(Vec3::new(1.0, 2.0, 0.0)) < 1e-5);
+    // Spinning about y costs more than about the rod.
+    assert!(props.inertia.y_axis.y > 5.0 * props.inertia.x_axis.x);
+
+    // Colliders without a density leave the mass alone.
+    let mut sensor = Collider::builder().sphere(5.0).build();
+    sensor.rigidbody_id = body;
+    world.add_collider(sensor);
+    let mass = world
+        .body(body)
+        .unwrap()
+        .to_rigid_body()
+        .mass_properties
+        .mass;
+    assert!((mass - props.mass).abs() < 1e-6);
+}
+
+#[test]
+fn particles_land_on_colliders_and_expire() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let ground = world.add_rigidbody(RigidBody::builder().is_static(true).build());
+    let mut collider = Collider::builder()
+        .box_shape(Vec3::new(10.0, 0.5, 10.0))
+        .build();
+    collider.rigidbody_id = ground;
+    world.add_collider(collider);
+
+    let dust = world
+        .particles
+        .spawn(Particle::new(Vec3::new(0.0, 2.0, 0.0)).radius(0.1));
+    let spark = world.particles.spawn(
+        Particle::new(Vec3::new(3.0, 2.0, 0.0))
+            .velocity(Vec3::X)
+            .lifetime(0.5),
+    );
+    assert_eq!(world.particles.len(), 2);
+
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+
+    // The dust rests on the ground's top face; the spark burnt out mid-air.
+    let resting = world.particles.position(dust).unwrap();
+    assert!((resting.y - 0.6).abs() < 0.02, "dust at {resting}");
+    assert!(world.particles.velocity(dust).unwrap().y.abs() < 0.5);
+    assert!(!world.particles.is_alive(spark));
+    assert_eq!(world.particles.position(spark), None);
+    assert_eq!(world.particles.len(), 1);
+
+    // Recycled slots hand out fresh ids.
+    let ember = world.particles.spawn(Particle::new(Vec3::Y));
+    assert_ne!(ember, spark);
+    assert!(world.particles.despawn(ember));
+    assert!(!world.particles.despawn(ember));
+}
+
+#[test]
+fn linked_particles_hold_their_spacing() {
+    let mut world = PhysicsWorld::builder().gravity(Vec3::ZERO).build();
+    let a = world.particles.spawn(Particle::new(Vec3::ZERO));
+    let b = world
+        .particles
+        .spawn(Particle::new(Vec3::X).velocity(Vec3::X * 2.0));
+    assert!(world.particles.add_link(a, b, 200.0, 5.0));
+
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+    }
+
+    // The spring dragged the pair along together at its rest length.
+    let (pa, pb) = (
+        world.particles.position(a).unwrap(),
+        world.particles.position(b).unwrap(),
+    );
+    assert!(
+        (pa.distance(pb) - 1.0).abs() < 0.05,
+        "spacing {}",
+        pa.distance(pb)
+    );
+    let va = world.particles.velocity(a).unwrap();
+    assert!((va.x - 1.0).abs() < 0.05, "velocity {va}");
+
+    // Despawning an end removes the link.
+    assert!(world.particles.despawn(b));
+    assert!(world.particles.links().is_empty());
+}
+
+#[test]
+fn fluid_emitters_fill_a_tank_without_leaking() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    // Open-topped 0.6 m tank with 0.1 m thick floor and walls.
+    let walls = [
+        (Vec3::new(0.0, -0.05, 0.0), Vec3::new(0.4, 0.05, 0.4)),
+        (Vec3::new(-0.35, 0.3, 0.0), Vec3::new(0.05, 0.3, 0.4)),
+        (Vec3::new(0.35, 0.3, 0.0), Vec3::new(0.05, 0.3, 0.4)),
+        (Vec3::new(0.0, 0.3, -0.35), Vec3::new(0.4, 0.3, 0.05)),
+        (Vec3::new(0.0, 0.3, 0.35), Vec3::new(0.4, 0.3, 0.05)),
+    ];
+    for (position, half_extents) in walls {
+        let id = world.add_rigidbody(
+            RigidBody::builder()
+                .position(position)
+                .is_static(true)
+                .build(),
+        );
+        let mut collider = Collider::builder().box_shape(half_extents).build();
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+    }
+
+    let nozzle = world.add_fluid_emitter(FluidEmitter::new(
+        Vec3::new(0.0, 0.5, 0.0),
+        Vec3::new(0.0, -1.0, 0.0),
+        0.1,
+        200.0,
+    ));
+    for _ in 0..45 {
+        world.step(1.0 / 60.0);
+    }
+    world.fluid_emitter_mut(nozzle).unwrap().enabled = false;
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+
+    assert_eq!(world.fluid.len(), 150);
+    for position in world.fluid.positions() {
+        assert!(position.y > -0.01, "leaked through the floor: {position}");
+        assert!(
+            position.x.abs() < 0.31 && position.z.abs() < 0.31,
+            "leaked through a wall: {position}"
+        );
+    }
+    let mean_speed = world
+        .fluid
+        .velocities()
+        .iter()
+        .map(|v| v.length())
+        .sum::<f32>()
+        / world.fluid.len() as f32;
+    assert!(mean_speed < 0.2, "fluid still sloshing at {mean_speed} m/s");
+    // Settled water neither collapses nor flies apart.
+    let top = world
+        .fluid
+        .positions()
+        .iter()
+        .map(|p| p.y)
+        .fold(f32::MIN, f32::max);
+    assert!(top > 0.05 && top < 0.4, "surface at {top}");
+}
+
+#[test]
+fn cloth_drapes_over_a_sphere_without_passing_through() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let ball = world.add_rigidbody(RigidBody::builder().is_static(true).build());
+    let mut collider = Collider::builder().sphere(0.5).build();
+    collider.rigidbody_id = ball;
+    world.add_collider(collider);
+
+    let cloth = Cloth::grid(
+        Vec3::new(-0.8, 0.7, -0.8),
+        Vec3::X * 1.6,
+        Vec3::Z * 1.6,
+        16,
+        16,
+        ClothParams::default(),
+    );
+    let id = world.add_cloth(cloth);
+    for _ in 0..180 {
+        world.step(1.0 / 60.0);
+    }
+
+    let cloth = world.cloth_mut(id).unwrap();
+    let positions = cloth.positions();
+    let thickness = cloth.params.thickness;
+    for position in positions {
+        assert!(position.is_finite());
+        assert!(
+            position.length() > 0.5 + thickness * 0.5,
+            "vertex at {position}"
+        );
+    }
+    // The middle rests on top of the ball while the corners hang down its sides.
+    let middle = positions[8 * 17 + 8];
+    assert!(
+        (middle.y - 0.5 - thickness).abs() < 0.03,
+        "middle at {middle}"
+    );
+    assert!(positions[0].y < 0.2, "corner at {}", positions[0]);
+}
+
+#[test]
+fn cloth_hangs_from_pins_and_pulls_on_dynamic_bodies() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let pole = world.add_rigidbody(
+        RigidBody::builder()
+            .mass(1.0)
+            .gravity_scale(0.0)
+            .damping(0.0, 0.0)
+            .build(),
+    );
+    let mut collider = Collider::builder()
+        .box_shape(Vec3::new(0.02, 0.5, 0.02))
+        .build();
+    collider.rigidbody_id = pole;
+    world.add_collider(collider);
+
+    // A flag along the pole's side, and a banner pinned by its top corners.
+    let mut flag = Cloth::grid(
+        Vec3::new(0.05, 0.4, 0.0),
+        Vec3::X * 0.6,
+        Vec3::NEG_Y * 0.4,
+        12,
+        8,
+        ClothParams::default(),
+    );
+    for row in 0..=8 {
+        let vertex = row * 13;
+        let local = flag.positions()[vertex];
+        assert!(flag.attach(vertex, pole, local));
+    }
+    let flag_mass = flag.mass();
+    let flag_id = world.add_cloth(flag);
+
+    let mut banner = Cloth::grid(
+        Vec3::new(2.0, 1.0, 0.0),
+        Vec3::X,
+        Vec3::Z,
+        10,
+        10,
+        ClothParams::default(),
+    );
+    assert!(banner.pin(0) && banner.pin(10));
+    let banner_id = world.add_cloth(banner);
+
+    let seconds = 1.0;
+    let mut lowest = f32::MAX;
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+        let banner = world.cloth_mut(banner_id).unwrap();
+        lowest = banner
+            .positions()
+            .iter()
+            .map(|p| p.y)
+            .fold(lowest, f32::min);
+    }
+
+    // The banner swings down below its pins, which stay put, without stretching.
+    let banner = world.cloth_mut(banner_id).unwrap();
+    assert_eq!(banner.positions()[0], Vec3::new(2.0, 1.0, 0.0));
+    assert_eq!(banner.positions()[10], Vec3::new(3.0, 1.0, 0.0));
+    assert!(lowest < 0.2, "banner bottom reached {lowest}");
+    let hem = banner.positions()[110].distance(banner.positions()[0]);
+    assert!((hem - 1.0).abs() < 0.05, "banner side is {hem} long");
+
+    // The flag's weight drags the floating pole down with it, conserving momentum.
+    let flag = world.cloth_mut(flag_id).unwrap();
+    let flag_momentum: Vec3 = flag
+        .velocities()
+        .iter()
+        .zip(flag.masses())
+        .map(|(velocity, mass)| *velocity * *mass)
+        .sum();
+    let pole_velocity = world.bodies.get(pole).unwrap().velocity().linear;
+    assert!(pole_velocity.y < -0.1, "pole velocity {pole_velocity}");
+    let total = pole_velocity.y + flag_momentum.y;
+    let expected = -flag_mass * 9.81 * seconds;
+    assert!(
+        (total - expected).abs() < expected.abs() * 0.3,
+        "momentum {total} vs {expected}"
+    );
+
+    // Without coupling the pole ignores the flag.
+    world
+        .bodies
+        .get_mut(pole)
+        .unwrap()
+        .set_velocity(Vec3::ZERO, Vec3::ZERO);
+    world.cloth_mut(flag_id).unwrap().params.coupling = 0.0;
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let pole_velocity = world.bodies.get(pole).unwrap().velocity().linear;
+    assert!(
+        pole_velocity.length() < 1e-3,
+        "pole velocity {pole_velocity}"
+    );
+}
+
+fn cube_mesh(center: Vec3, half_extent: f32) -> TriangleMesh {
+    let vertices = (0..8)
+        .map(|corner| {
+            let sign = |bit: i32| if corner & bit == 0 { -1.0 } else { 1.0 };
+            center + Vec3::new(sign(1), sign(2), sign(4)) * half_extent
+        })
+        .collect();
+    let indices = vec![
+        [0, 4, 6],
+        [0, 6, 2],
+        [1, 3, 7],
+        [1, 7, 5],
+        [0, 1, 5],
+        [0, 5, 4],
+        [2, 6, 7],
+        [2, 7, 3],
+        [0, 2, 3],
+        [0, 3, 1],
+        [4, 5, 7],
+        [4, 7, 6],
+    ];
+    TriangleMesh::builder(vertices, indices).build()
+}
+
+#[test]
+fn soft_bodies_rest_on_colliders_and_keep_their_volume() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let ground = world.add_rigidbody(RigidBody::builder().is_static(true).build());
+    let mut collider = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    collider.rigidbody_id = ground;
+    world.add_collider(collider);
+
+    let mesh = cube_mesh(Vec3::new(0.0, 1.0, 0.0), 0.2);
+    let jelly = SoftBody::from_mesh(&mesh, 0.1, SoftBodyParams::default());
+    assert_eq!(jelly.len(), 125);
+    assert_eq!(jelly.tetrahedra().len(), 384);
+    assert_eq!(jelly.surface_triangles().len(), 6 * 16 * 2);
+    assert!((jelly.rest_volume() - 0.064).abs() < 1e-4);
+    for (embedded, vertex) in jelly.embedded_positions().iter().zip(&mesh.vertices) {
+        assert!(embedded.distance(*vertex) < 1e-4);
+    }
+    let id = world.add_soft_body(jelly);
+
+    for _ in 0..120 {
+        world.step(1.0 / 60.0);
+    }
+
+    let jelly = world.soft_body_mut(id).unwrap();
+    assert!(jelly.positions().iter().all(|p| p.is_finite()));
+    let bottom = jelly
+        .positions()
+        .iter()
+        .map(|p| p.y)
+        .fold(f32::MAX, f32::min);
+    assert!((bottom - 0.5).abs() < 0.02, "bottom at {bottom}");
+    let ratio = jelly.volume() / jelly.rest_volume();
+    assert!((ratio - 1.0).abs() < 0.05, "volume ratio {ratio}");
+    // It sits on its base rather than slumping or sinking.
+    let centroid = jelly.positions().iter().copied().sum::<Vec3>() / jelly.len() as f32;
+    assert!((centroid.y - 0.7).abs() < 0.03, "centroid at {centroid}");
+    assert!(centroid.x.abs() < 0.05 && centroid.z.abs() < 0.05);
+}
+
+#[test]
+fn soft_bodies_push_the_dynamic_bodies_they_land_on() {
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::new(0.0, -9.81, 0.0))
+        .build();
+    let plank = world.add_rigidbody(
+        RigidBody::builder()
+            .mass(5.0)
+            .gravity_scale(0.0)
+            .damping(0.0, 0.0)
+            .build(),
+    );
+    let mut collider = Collider::builder()
+        .box_shape(Vec3::new(0.5, 0.05, 0.5))
+        .build();
+    collider.rigidbody_id = plank;
+    world.add_collider(collider);
+
+    let params = SoftBodyParams {
+        density: 100.0,
+        ..SoftBodyParams::default()
+    };
+    let jelly = SoftBody::from_mesh(&cube_mesh(Vec3::new(0.0, 0.3, 0.0), 0.2), 0.1, params);
+    let jelly_mass = jelly.mass();
+    let id = world.add_soft_body(jelly);
+
+    for _ in 0..60 {
+        world.step(1.0 / 60.0);
+    }
+
+    // The plank carries the jelly's weight, so the pair falls together.
+    let plank_velocity = world.bodies.get(plank).unwrap().velocity().linear;
+    let jelly = world.soft_body_mut(id).unwrap();
+    let jelly_momentum: Vec3 = jelly
+        .velocities()
+        .iter()
+        .zip(jelly.masses())
+        .map(|(velocity, mass)| *velocity * *mass)
+        .sum();
+    assert!(plank_velocity.y < -1.0, "plank velocity {plank_velocity}");
+    let total = plank_velocity.y * 5.0 + jelly_momentum.y;
+    let expected = -jelly_mass * 9.81;
+    assert!(
+        (total - expected).abs() < expected.abs() * 0.2,
+        "momentum {total} vs {expected}"
+    );
+}
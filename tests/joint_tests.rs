@@ -1,6 +1,9 @@
 use glam::{Quat, Vec3};
 use particle_accelerator::{
-    core::{constraints::Joint, rigidbody::RigidBody},
+    core::{
+        constraints::{Joint, JointBreakLimit, MotorMode},
+        rigidbody::RigidBody,
+    },
     utils::allocator::EntityId,
     world::PhysicsWorld,
 };
@@ -56,6 +59,7 @@ fn test_revolute_motor_spin_up() {
         enable_motor: true,
         motor_speed: 10.0,     // Target 10 rad/s
         max_motor_torque: 5.0, // Limited torque
+        motor_mode: MotorMode::Velocity,
 
         enable_limit: false,
         lower_angle: 0.0,
@@ -142,6 +146,7 @@ fn test_revolute_limits() {
         enable_motor: false,
         motor_speed: 0.0,
         max_motor_torque: 0.0,
+        motor_mode: MotorMode::Velocity,
 
         enable_limit: true,
         lower_angle: lower,
@@ -295,6 +300,7 @@ fn test_prismatic_slider() {
         enable_motor: true,
         motor_speed: 2.0, // Move at 2m/s
         max_motor_force: 1000.0,
+        motor_mode: MotorMode::Velocity,
     };
     world.add_joint(joint);
 
@@ -336,3 +342,360 @@ fn test_prismatic_slider() {
         assert!(rot.angle_between(Quat::IDENTITY) < 0.1);
     }
 }
+
+#[test]
+fn test_grounded_revolute_pendulum() {
+    let mut world = make_world();
+    let dt = 1.0 / 60.0;
+    let anchor = Vec3::new(0.0, 5.0, 0.0);
+
+    let mut bob = RigidBody::new(EntityId::from_index(3000));
+    bob.transform.position = Vec3::new(2.0, 5.0, 0.0);
+    bob.mass_properties.mass = 1.0;
+    bob.mass_properties.inertia = glam::Mat3::IDENTITY;
+    bob.linear_velocity_damping = 0.0;
+    bob.angular_velocity_damping = 0.0;
+    let bob_id = world.add_rigidbody(bob);
+
+    // Hinge the bob to a fixed point in the world; no static body is needed.
+    let joint = Joint::Revolute {
+        body_a: Joint::WORLD,
+        body_b: bob_id,
+        local_pivot_a: anchor,
+        local_pivot_b: Vec3::new(-2.0, 0.0, 0.0),
+        local_axis_a: Vec3::Z,
+        local_axis_b: Vec3::Z,
+        local_basis_a: Vec3::X,
+        local_basis_b: Vec3::X,
+
+        enable_motor: false,
+        motor_speed: 0.0,
+        max_motor_torque: 0.0,
+        motor_mode: MotorMode::Velocity,
+
+        enable_limit: false,
+        lower_angle: 0.0,
+        upper_angle: 0.0,
+    };
+    assert!(joint.is_grounded());
+    world.add_joint(joint);
+
+    let mut lowest = f32::MAX;
+    for _ in 0..60 {
+        world.step(dt);
+        let pos = world.bodies.get(bob_id).unwrap().transform().position;
+        lowest = lowest.min(pos.y);
+        let arm = (pos - anchor).length();
+        assert!((arm - 2.0).abs() < 0.1, "Arm length drifted to {arm}");
+    }
+
+    assert_eq!(world.bodies.len(), 1);
+    assert!(lowest < 4.0, "Bob should swing down, lowest y {lowest}");
+}
+
+#[test]
+fn test_grounded_fixed_joint_holds_against_gravity() {
+    let mut world = make_world();
+    let dt = 1.0 / 60.0;
+
+    let mut link = RigidBody::new(EntityId::from_index(3001));
+    link.transform.position = Vec3::new(0.0, 3.0, 0.0);
+    link.mass_properties.mass = 1.0;
+    link.mass_properties.inertia = glam::Mat3::IDENTITY;
+    let link_id = world.add_rigidbody(link);
+
+    world.add_joint(Joint::Fixed {
+        body_a: link_id,
+        body_b: Joint::WORLD,
+        local_pivot_a: Vec3::ZERO,
+        local_pivot_b: Vec3::new(0.0, 3.0, 0.0),
+        local_frame_a: Quat::IDENTITY,
+        local_frame_b: Quat::IDENTITY,
+    });
+
+    for _ in 0..60 {
+        world.step(dt);
+    }
+
+    let pos = world.bodies.get(link_id).unwrap().transform().position;
+    assert!((pos - Vec3::new(0.0, 3.0, 0.0)).length() < 0.05, "{pos:?}");
+}
+
+/// Position errors of two fixed joints that cannot both hold, with one prioritized.
+fn conflicting_fixed_errors(priority_on_second: bool) -> (f32, f32) {
+    let mut world = make_world();
+    world.gravity = Vec3::ZERO;
+    let dt = 1.0 / 60.0;
+
+    let mut post = RigidBody::new(EntityId::from_index(4001));
+    post.transform.position = Vec3::new(1.0, 3.0, 0.0);
+    post.is_static = true;
+    let post_id = world.add_rigidbody(post);
+
+    let mut link = RigidBody::new(EntityId::from_index(4002));
+    link.transform.position = Vec3::new(0.5, 3.0, 0.0);
+    link.mass_properties.mass = 1.0;
+    link.mass_properties.inertia = glam::Mat3::IDENTITY;
+    let link_id = world.add_rigidbody(link);
+
+    let to_world = Joint::Fixed {
+        body_a: link_id,
+        body_b: Joint::WORLD,
+        local_pivot_a: Vec3::ZERO,
+        local_pivot_b: Vec3::new(0.0, 3.0, 0.0),
+        local_frame_a: Quat::IDENTITY,
+        local_frame_b: Quat::IDENTITY,
+    };
+    let to_post = Joint::Fixed {
+        body_a: link_id,
+        body_b: post_id,
+        local_pivot_a: Vec3::ZERO,
+        local_pivot_b: Vec3::ZERO,
+        local_frame_a: Quat::IDENTITY,
+        local_frame_b: Quat::IDENTITY,
+    };
+    if priority_on_second {
+        world.add_joint(to_world);
+        world.add_joint_with_priority(to_post, 1);
+    } else {
+        world.add_joint_with_priority(to_world, 1);
+        world.add_joint(to_post);
+    }
+
+    for _ in 0..60 {
+        world.step(dt);
+    }
+
+    let pos = world.bodies.get(link_id).unwrap().transform().position;
+    let world_error = (pos - Vec3::new(0.0, 3.0, 0.0)).length();
+    let post_error = (pos - Vec3::new(1.0, 3.0, 0.0)).length();
+    (world_error, post_error)
+}
+
+#[test]
+fn prioritized_joints_are_solved_last_and_hold_best() {
+    let (world_error, post_error) = conflicting_fixed_errors(true);
+    assert!(post_error < world_error, "{post_error} vs {world_error}");
+
+    // Insertion order is the same; only the priority moved.
+    let (world_error, post_error) = conflicting_fixed_errors(false);
+    assert!(world_error < post_error, "{world_error} vs {post_error}");
+}
+
+#[test]
+fn breakable_joint_snaps_under_load_and_reports_it() {
+    let hang = |max_force: f32| {
+        let mut world = make_world();
+        let mut weight = RigidBody::new(EntityId::from_index(5001));
+        weight.transform.position = Vec3::new(0.0, 3.0, 0.0);
+        weight.mass_properties.mass = 2.0;
+        weight.mass_properties.inertia = glam::Mat3::IDENTITY;
+        let weight_id = world.add_rigidbody(weight);
+        world.add_breakable_joint(
+            Joint::Fixed {
+                body_a: weight_id,
+                body_b: Joint::WORLD,
+                local_pivot_a: Vec3::ZERO,
+                local_pivot_b: Vec3::new(0.0, 3.0, 0.0),
+                local_frame_a: Quat::IDENTITY,
+                local_frame_b: Quat::IDENTITY,
+            },
+            JointBreakLimit::new(max_force, f32::INFINITY),
+        );
+        let mut broken = Vec::new();
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+            broken.extend(world.drain_joint_broken_events());
+        }
+        let height = world.bodies.get(weight_id).unwrap().transform().position.y;
+        (world, broken, height)
+    };
+
+    // Holding 2 kg against gravity takes about 19.6 N.
+    let (world, broken, height) = hang(100.0);
+    assert!(broken.is_empty());
+    assert_eq!(world.dynamics.joints.len(), 1);
+    assert!((height - 3.0).abs() < 0.05, "{height}");
+
+    let (world, broken, height) = hang(10.0);
+    assert_eq!(broken.len(), 1);
+    assert!(broken[0].force > 10.0, "{}", broken[0].force);
+    assert!(matches!(broken[0].joint, Joint::Fixed { .. }));
+    assert!(world.dynamics.joints.is_empty());
+    assert!(height < 2.5, "{height}");
+}
+
+#[test]
+fn revolute_servo_holds_arm_against_gravity() {
+    let mut world = make_world();
+    let dt = 1.0 / 60.0;
+
+    let mut arm = RigidBody::new(EntityId::default());
+    arm.transform.position = Vec3::new(1.0, 0.0, 0.0);
+    arm.linear_velocity_damping = 0.0;
+    arm.angular_velocity_damping = 0.0;
+    let arm_id = world.add_rigidbody(arm);
+
+    let target = 0.5;
+    world.add_joint(Joint::Revolute {
+        body_a: Joint::WORLD,
+        body_b: arm_id,
+        local_pivot_a: Vec3::ZERO,
+        local_pivot_b: Vec3::new(-1.0, 0.0, 0.0),
+        local_axis_a: Vec3::Z,
+        local_axis_b: Vec3::Z,
+        local_basis_a: Vec3::X,
+        local_basis_b: Vec3::X,
+        enable_motor: true,
+        motor_speed: 0.0,
+        max_motor_torque: 100.0,
+        motor_mode: MotorMode::Position {
+            target,
+            stiffness: 2000.0,
+            damping: 100.0,
+        },
+        enable_limit: false,
+        lower_angle: 0.0,
+        upper_angle: 0.0,
+    });
+
+    for _ in 0..180 {
+        world.step(dt);
+    }
+
+    let body = world.bodies.get(arm_id).unwrap();
+    let (axis, angle) = body.transform().rotation.to_axis_angle();
+    let angle = angle * axis.z.signum();
+    assert!((angle - target).abs() < 0.05, "arm at {angle} rad");
+    assert!(body.velocity().angular.length() < 0.05);
+}
+
+#[test]
+fn prismatic_servo_settles_on_target_offset() {
+    let mut world = make_world();
+    world.gravity = Vec3::ZERO;
+    let dt = 1.0 / 60.0;
+
+    let mut slider = RigidBody::new(EntityId::default());
+    slider.linear_velocity_damping = 0.0;
+    slider.angular_velocity_damping = 0.0;
+    let slider_id = world.add_rigidbody(slider);
+
+    world.add_joint(Joint::Prismatic {
+        body_a: Joint::WORLD,
+        body_b: slider_id,
+        local_pivot_a: Vec3::ZERO,
+        local_pivot_b: Vec3::ZERO,
+        local_axis_a: Vec3::X,
+        local_frame_a: Quat::IDENTITY,
+        local_frame_b: Quat::IDENTITY,
+        enable_limit: false,
+        lower_limit: 0.0,
+        upper_limit: 0.0,
+        enable_motor: true,
+        motor_speed: 0.0,
+        max_motor_force: 1000.0,
+        motor_mode: MotorMode::Position {
+            target: 2.0,
+            stiffness: 100.0,
+            damping: 20.0,
+        },
+    });
+
+    let mut overshoot: f32 = 0.0;
+    for _ in 0..300 {
+        world.step(dt);
+        let x = world.bodies.get(slider_id).unwrap().transform().position.x;
+        overshoot = overshoot.max(x - 2.0);
+    }
+
+    let body = world.bodies.get(slider_id).unwrap();
+    assert!((body.transform().position.x - 2.0).abs() < 0.02);
+    assert!(body.velocity().linear.length() < 0.02);
+    // Critically damped for a unit mass, so the servo should not swing past the target.
+    assert!(overshoot < 0.02, "overshoot {overshoot}");
+}
+
+#[test]
+fn parallel_island_solve_keeps_joints() {
+    let swing = |parallel: bool| {
+        let mut world = make_world();
+        world.set_parallel_enabled(parallel);
+        let mut bob = RigidBody::new(EntityId::default());
+        bob.transform.position = Vec3::new(1.0, 0.0, 0.0);
+        let bob_id = world.add_rigidbody(bob);
+        world.add_joint(Joint::Revolute {
+            body_a: Joint::WORLD,
+            body_b: bob_id,
+            local_pivot_a: Vec3::ZERO,
+            local_pivot_b: Vec3::new(-1.0, 0.0, 0.0),
+            local_axis_a: Vec3::Z,
+            local_axis_b: Vec3::Z,
+            local_basis_a: Vec3::X,
+            local_basis_b: Vec3::X,
+            enable_motor: false,
+            motor_speed: 0.0,
+            max_motor_torque: 0.0,
+            motor_mode: MotorMode::Velocity,
+            enable_limit: false,
+            lower_angle: 0.0,
+            upper_angle: 0.0,
+        });
+        for _ in 0..30 {
+            world.step(1.0 / 60.0);
+        }
+        world.bodies.get(bob_id).unwrap().transform().position
+    };
+
+    let sequential = swing(false);
+    let parallel = swing(true);
+    assert!((sequential.length() - 1.0).abs() < 0.05, "{sequential:?}");
+    assert!(
+        (parallel - sequential).length() < 1e-3,
+        "parallel {parallel:?} vs sequential {sequential:?}"
+    );
+}
+
+/// How far the end of a hanging fixed-joint chain with a heavy weight has sagged after 4 s.
+fn hanging_chain_sag(warm_starting: bool) -> f32 {
+    let mut world = make_world();
+    world.dynamics.solver.joint_warm_starting = warm_starting;
+    let dt = 1.0 / 60.0;
+
+    let mut previous = Joint::WORLD;
+    let mut end = Vec3::ZERO;
+    for index in 0..20 {
+        end = Vec3::new(0.0, 10.0 - index as f32 * 0.5, 0.0);
+        let mut link = RigidBody::new(EntityId::from_index(5000 + index));
+        link.transform.position = end;
+        link.mass_properties.mass = if index == 19 { 100.0 } else { 1.0 };
+        link.mass_properties.inertia = glam::Mat3::IDENTITY * link.mass_properties.mass;
+        let link_id = world.add_rigidbody(link);
+        world.add_joint(Joint::Fixed {
+            body_a: link_id,
+            body_b: previous,
+            local_pivot_a: Vec3::new(0.0, 0.25, 0.0),
+            local_pivot_b: if previous == Joint::WORLD {
+                Vec3::new(0.0, 10.25, 0.0)
+            } else {
+                Vec3::new(0.0, -0.25, 0.0)
+            },
+            local_frame_a: Quat::IDENTITY,
+            local_frame_b: Quat::IDENTITY,
+        });
+        previous = link_id;
+    }
+
+    for _ in 0..240 {
+        world.step(dt);
+    }
+    end.y - world.bodies.get(previous).unwrap().transform().position.y
+}
+
+#[test]
+fn warm_started_joints_sag_less_under_load() {
+    let cold = hanging_chain_sag(false);
+    let warm = hanging_chain_sag(true);
+    println!("Chain sag cold {cold}, warm {warm}");
+    assert!(warm < cold * 0.8, "warm {warm} vs cold {cold}");
+}
@@ -1,9 +1,14 @@
 use particle_accelerator::core::{
     collider::ColliderShape,
-    mesh::TriangleMesh,
+    mesh::{Aabb, BvhSplit, MeshBuilder, MeshBvh, TriangleMesh},
+    soa::BodiesSoA,
     types::Transform,
 };
-use particle_accelerator::{Collider, CollisionFilter, EntityId, RigidBody};
+use particle_accelerator::utils::allocator::Arena;
+use particle_accelerator::{
+    Collider, CollisionFilter, ContactManifold, EntityId, Raycast, RaycastQuery, RigidBody,
+};
+use std::sync::Arc;
 
 #[test]
 fn weld_vertices_reduces_duplicates() {
@@ -55,10 +60,14 @@ fn mesh_collider_bounds_match_shape() {
     let collider = Collider {
         id: EntityId::from_index(0),
         rigidbody_id: EntityId::from_index(0),
-        shape: ColliderShape::Mesh { mesh },
+        shape: ColliderShape::Mesh {
+            mesh: Arc::new(mesh),
+        },
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
 
     let mut body = RigidBody::new(EntityId::from_index(0));
@@ -68,3 +77,291 @@ fn mesh_collider_bounds_match_shape() {
     assert_eq!(world_transform.position, glam::Vec3::ZERO);
     assert!(collider.bounding_radius() >= 2.0);
 }
+
+fn cube_mesh(half: f32) -> TriangleMesh {
+    cube_builder(half).build()
+}
+
+fn cube_builder(half: f32) -> MeshBuilder {
+    let vertices = (0..8)
+        .map(|i| {
+            glam::Vec3::new(
+                if i & 1 == 0 { -half } else { half },
+                if i & 2 == 0 { -half } else { half },
+                if i & 4 == 0 { -half } else { half },
+            )
+        })
+        .collect();
+    let indices = vec![
+        [0, 2, 1],
+        [1, 2, 3],
+        [4, 5, 6],
+        [5, 7, 6],
+        [0, 1, 4],
+        [1, 5, 4],
+        [2, 6, 3],
+        [3, 6, 7],
+        [0, 4, 2],
+        [2, 4, 6],
+        [1, 3, 5],
+        [3, 7, 5],
+    ];
+    TriangleMesh::builder(vertices, indices)
+}
+
+fn mesh_body(id: u32, mesh: TriangleMesh, position: glam::Vec3) -> (RigidBody, Collider) {
+    let mut body = RigidBody::new(EntityId::from_index(id));
+    body.transform.position = position;
+    let collider = Collider {
+        id: EntityId::from_index(id + 100),
+        rigidbody_id: body.id,
+        shape: ColliderShape::Mesh {
+            mesh: Arc::new(mesh),
+        },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    };
+    (body, collider)
+}
+
+#[test]
+fn mesh_bvh_leaves_cover_every_triangle() {
+    let mesh = cube_mesh(1.0);
+    assert!(mesh.bvh.nodes.len() > 1, "12 triangles should split");
+
+    let mut covered: Vec<usize> = mesh
+        .bvh
+        .nodes
+        .iter()
+        .filter(|node| node.is_leaf())
+        .flat_map(|node| node.start..node.start + node.count)
+        .collect();
+    covered.sort_unstable();
+    assert_eq!(covered, (0..mesh.indices.len()).collect::<Vec<_>>());
+
+    let mut hits = Vec::new();
+    let top = Aabb::new(
+        glam::Vec3::new(-0.1, 0.9, -0.1),
+        glam::Vec3::new(0.1, 1.1, 0.1),
+    );
+    mesh.bvh.query(&top, &mut hits);
+    assert!(hits
+        .iter()
+        .any(|&tri| mesh.triangle(tri).iter().all(|v| (v.y - 1.0).abs() < 1e-6)));
+}
+
+fn grid_builder(cells: u32) -> MeshBuilder {
+    let mut vertices = Vec::new();
+    for z in 0..=cells {
+        for x in 0..=cells {
+            vertices.push(glam::Vec3::new(x as f32, 0.0, z as f32));
+        }
+    }
+    TriangleMesh::builder(vertices, grid_indices(cells))
+}
+
+fn grid_indices(cells: u32) -> Vec<[u32; 3]> {
+    let row = cells + 1;
+    let mut indices = Vec::new();
+    for z in 0..cells {
+        for x in 0..cells {
+            let i = z * row + x;
+            indices.push([i, i + row, i + 1]);
+            indices.push([i + 1, i + row, i + row + 1]);
+        }
+    }
+    indices
+}
+
+fn leaf_coverage(bvh: &MeshBvh) -> Vec<usize> {
+    let mut covered: Vec<usize> = bvh
+        .nodes
+        .iter()
+        .filter(|node| node.is_leaf())
+        .flat_map(|node| node.start..node.start + node.count)
+        .collect();
+    covered.sort_unstable();
+    covered
+}
+
+#[test]
+fn sah_and_median_bvhs_find_the_same_triangles() {
+    let sah = grid_builder(12).build();
+    let median = grid_builder(12).bvh_split(BvhSplit::Median).build();
+    let all: Vec<usize> = (0..sah.indices.len()).collect();
+    assert_eq!(leaf_coverage(&sah.bvh), all);
+    assert_eq!(leaf_coverage(&median.bvh), all);
+
+    let region = Aabb::new(
+        glam::Vec3::new(3.2, -0.1, 5.2),
+        glam::Vec3::new(4.8, 0.1, 6.8),
+    );
+    let touching = |mesh: &TriangleMesh| {
+        let mut hits = Vec::new();
+        mesh.bvh.query(&region, &mut hits);
+        let mut corners: Vec<_> = hits
+            .into_iter()
+            .map(|tri| mesh.triangle(tri))
+            .filter(|tri| Aabb::from_points(tri).overlaps(&region))
+            .map(|tri| tri.map(|v| v.to_array().map(f32::to_bits)))
+            .collect();
+        corners.sort_unstable();
+        corners
+    };
+    assert!(!touching(&sah).is_empty());
+    assert_eq!(touching(&sah), touching(&median));
+}
+
+#[test]
+fn refit_tracks_deformed_vertices() {
+    let mut mesh = grid_builder(8).build();
+    let node_count = mesh.bvh.nodes.len();
+    for vertex in &mut mesh.vertices {
+        vertex.y = vertex.x * 0.5;
+    }
+    mesh.refit();
+
+    assert_eq!(mesh.bvh.nodes.len(), node_count);
+    assert!((mesh.bounds.max.y - 4.0).abs() < 1e-6);
+    assert!((mesh.bvh.nodes[0].bounds.max.y - 4.0).abs() < 1e-6);
+    for node in &mesh.bvh.nodes {
+        for slot in node.start..node.start + node.count {
+            for corner in mesh.triangle(slot) {
+                assert!(node.bounds.min.cmple(corner).all() && corner.cmple(node.bounds.max).all());
+            }
+        }
+    }
+
+    let raised = Aabb::new(
+        glam::Vec3::new(7.5, 3.5, 0.0),
+        glam::Vec3::new(8.0, 4.0, 8.0),
+    );
+    let mut hits = Vec::new();
+    mesh.bvh.query(&raised, &mut hits);
+    assert!(
+        !hits.is_empty(),
+        "refit bounds should follow the raised edge"
+    );
+}
+
+#[test]
+fn built_triangles_map_back_to_builder_order() {
+    let original = grid_indices(6);
+    let mesh = grid_builder(6).material_indices((0..72).collect()).build();
+
+    assert!((0..mesh.indices.len()).any(|tri| mesh.source_triangle(tri) != tri));
+    for (tri, indices) in mesh.indices.iter().enumerate() {
+        let source = mesh.source_triangle(tri);
+        assert_eq!(*indices, original[source]);
+        assert_eq!(mesh.material_index(tri), Some(source as u16));
+    }
+}
+
+#[test]
+fn cooked_mesh_round_trips_through_bytes() {
+    let mesh = grid_builder(6)
+        .material_indices((0..72).map(|i| i % 3).collect())
+        .build();
+    let bytes = mesh.to_bytes();
+    let loaded = TriangleMesh::from_bytes(&bytes).expect("cooked mesh should load");
+
+    assert_eq!(loaded.vertices, mesh.vertices);
+    assert_eq!(loaded.indices, mesh.indices);
+    assert_eq!(loaded.material_indices, mesh.material_indices);
+    assert_eq!(loaded.source_triangles, mesh.source_triangles);
+    assert_eq!(loaded.bvh.nodes.len(), mesh.bvh.nodes.len());
+    for (a, b) in loaded.bvh.nodes.iter().zip(&mesh.bvh.nodes) {
+        assert_eq!(
+            (a.left, a.right, a.start, a.count),
+            (b.left, b.right, b.start, b.count)
+        );
+        assert_eq!((a.bounds.min, a.bounds.max), (b.bounds.min, b.bounds.max));
+    }
+
+    let bvh = MeshBvh::from_bytes(&mesh.bvh.to_bytes()).expect("bvh should load");
+    assert_eq!(bvh.nodes.len(), mesh.bvh.nodes.len());
+
+    assert!(TriangleMesh::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    assert!(
+        MeshBvh::from_bytes(&bytes).is_none(),
+        "mesh data is not a bare bvh"
+    );
+    let mut corrupt = bytes.clone();
+    // First index of the first triangle, just past the header and vertex block.
+    let offset = 8 + 4 + mesh.vertices.len() * 12 + 4;
+    corrupt[offset..offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(TriangleMesh::from_bytes(&corrupt).is_none());
+}
+
+#[test]
+fn mesh_vs_mesh_generates_multi_point_manifold() {
+    let (body_a, collider_a) = mesh_body(0, cube_mesh(0.5), glam::Vec3::ZERO);
+    let (body_b, collider_b) = mesh_body(1, cube_mesh(0.5), glam::Vec3::new(0.1, 0.9, 0.05));
+
+    let manifold = ContactManifold::generate(&collider_a, &body_a, &collider_b, &body_b)
+        .expect("overlapping cube meshes should collide");
+    assert!(manifold.normal.y > 0.9, "normal = {:?}", manifold.normal);
+    assert!(manifold.points.len() >= 3);
+    let deepest = manifold
+        .points
+        .iter()
+        .map(|p| p.depth)
+        .fold(0.0f32, f32::max);
+    assert!((deepest - 0.1).abs() < 1e-3, "deepest = {deepest}");
+
+    let (far_body, far_collider) = mesh_body(2, cube_mesh(0.5), glam::Vec3::new(0.0, 1.5, 0.0));
+    assert!(ContactManifold::generate(&collider_a, &body_a, &far_collider, &far_body).is_none());
+}
+
+#[test]
+fn mesh_material_indices_follow_triangles_into_contacts_and_raycasts() {
+    // Triangles 6 and 7 form the +Y face of the cube.
+    let materials: Vec<u16> = (0..12).collect();
+    let ground_mesh = cube_builder(0.5).material_indices(materials).build();
+    let (ground, ground_collider) = mesh_body(0, ground_mesh, glam::Vec3::ZERO);
+    let crate_mesh = cube_builder(0.5).material_indices(vec![40; 12]).build();
+    let (crate_body, crate_collider) = mesh_body(1, crate_mesh, glam::Vec3::new(0.1, 0.9, 0.05));
+
+    let manifold =
+        ContactManifold::generate(&ground_collider, &ground, &crate_collider, &crate_body)
+            .expect("overlapping cube meshes should collide");
+    // Points on shared edges may be credited to a neighbouring face, but every point is tagged.
+    assert!(manifold
+        .points
+        .iter()
+        .all(|p| p.material_index_a.is_some() && p.material_index_b == Some(40)));
+    assert!(manifold
+        .points
+        .iter()
+        .any(|p| matches!(p.material_index_a, Some(6) | Some(7))));
+
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let body_id = bodies.insert(ground);
+    let mut collider = ground_collider;
+    collider.rigidbody_id = body_id;
+    let collider_id = colliders.insert(collider);
+    colliders.get_mut(collider_id).unwrap().id = collider_id;
+
+    let query = RaycastQuery::new(glam::Vec3::new(0.2, 5.0, 0.1), -glam::Vec3::Y, 10.0);
+    let hit = Raycast::cast(&query, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("downward ray should hit the top face");
+    assert!(matches!(hit.material_index, Some(6) | Some(7)));
+}
+
+#[test]
+fn cloned_mesh_colliders_share_triangles() {
+    let (_, collider) = mesh_body(0, cube_mesh(0.5), glam::Vec3::ZERO);
+    let copy = collider.clone();
+    match (&collider.shape, &copy.shape) {
+        (ColliderShape::Mesh { mesh: a }, ColliderShape::Mesh { mesh: b }) => {
+            assert!(Arc::ptr_eq(a, b));
+        }
+        _ => panic!("clone should keep the mesh shape"),
+    }
+}
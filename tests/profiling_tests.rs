@@ -0,0 +1,52 @@
+#![cfg(feature = "puffin")]
+
+use particle_accelerator::utils::profiling::finish_frame;
+use particle_accelerator::*;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn step_stages_show_up_as_puffin_scopes() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+
+    let mut ground = RigidBody::new(EntityId::from_index(0));
+    ground.is_static = true;
+    let ground_id = world.add_rigidbody(ground);
+    let mut floor = Collider::builder()
+        .box_shape(Vec3::new(5.0, 0.5, 5.0))
+        .build();
+    floor.rigidbody_id = ground_id;
+    world.add_collider(floor);
+
+    let mut ball = RigidBody::new(EntityId::from_index(1));
+    ball.transform.position = Vec3::new(0.0, 0.9, 0.0);
+    let ball_id = world.add_rigidbody(ball);
+    let mut ball_collider = Collider::builder().sphere(0.5).build();
+    ball_collider.rigidbody_id = ball_id;
+    world.add_collider(ball_collider);
+
+    let names = Arc::new(Mutex::new(Vec::new()));
+    let sink_names = Arc::clone(&names);
+    let sink = puffin::GlobalProfiler::lock().add_sink(Box::new(move |frame| {
+        let mut names = sink_names.lock().unwrap();
+        names.extend(
+            frame
+                .scope_delta
+                .iter()
+                .filter_map(|scope| scope.scope_name.as_ref().map(|name| name.to_string())),
+        );
+    }));
+    puffin::set_scopes_on(true);
+
+    world.step(1.0 / 60.0);
+    finish_frame();
+
+    puffin::set_scopes_on(false);
+    puffin::GlobalProfiler::lock().remove_sink(sink);
+    let names = names.lock().unwrap();
+    for stage in ["step", "broadphase", "narrowphase", "solver", "integration"] {
+        assert!(
+            names.iter().any(|name| name == stage),
+            "missing {stage} scope in {names:?}"
+        );
+    }
+}
@@ -1,19 +1,31 @@
 use particle_accelerator::{
-    collision::queries::{Raycast, RaycastQuery},
+    collision::{
+        broadphase::BroadPhase,
+        queries::{Ray, Raycast, RaycastQuery},
+        query_pipeline::QueryPipeline,
+    },
     core::soa::BodiesSoA,
     core::{
         collider::{Collider, ColliderShape, CollisionFilter},
+        heightfield::Heightfield,
+        mesh::Aabb,
         rigidbody::RigidBody,
         types::Transform,
     },
     utils::allocator::{Arena, EntityId},
+    world::PhysicsWorld,
     Vec3,
 };
+use std::sync::Arc;
 
-fn add_body(arena: &mut BodiesSoA, position: Vec3) -> EntityId {
+fn add_body_at(position: Vec3) -> RigidBody {
     let mut body = RigidBody::new(EntityId::default());
     body.transform.position = position;
-    arena.insert(body)
+    body
+}
+
+fn add_body(arena: &mut BodiesSoA, position: Vec3) -> EntityId {
+    arena.insert(add_body_at(position))
 }
 
 fn add_collider(
@@ -30,6 +42,8 @@ fn add_collider(
         offset: Transform::default(),
         is_trigger,
         collision_filter: filter,
+        margin: 0.0,
+        density: None,
     };
 
     let id = arena.insert(collider);
@@ -132,3 +146,432 @@ fn raycast_returns_closest_when_requested() {
         "returned hit should be the nearer collider"
     );
 }
+
+#[test]
+fn raycast_all_ignores_closest_only_and_filters_bodies() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    // The ray is cast from just behind the character that owns it.
+    let character = add_body(&mut bodies, Vec3::new(0.0, 0.0, 1.0));
+    let mut body_ids = vec![character];
+    for z in [3.0, 6.0] {
+        body_ids.push(add_body(&mut bodies, Vec3::new(0.0, 0.0, z)));
+    }
+    for &body in &body_ids {
+        add_collider(
+            &mut colliders,
+            body,
+            ColliderShape::Sphere { radius: 0.5 },
+            CollisionFilter::default(),
+            false,
+        );
+    }
+
+    let query = RaycastQuery::new(Vec3::ZERO, Vec3::Z, 20.0);
+    assert!(query.closest_only);
+    let hits = Raycast::cast_all(&query, &colliders, &bodies);
+    assert_eq!(hits.len(), 3);
+    assert!(hits.windows(2).all(|w| w[0].distance <= w[1].distance));
+    assert_eq!(hits[0].body_id, character);
+
+    let hits = Raycast::cast_all_with_filter(&query, &colliders, &bodies, |id| id != character);
+    let hit_bodies: Vec<EntityId> = hits.iter().map(|hit| hit.body_id).collect();
+    assert_eq!(hit_bodies, body_ids[1..]);
+}
+
+#[test]
+fn cast_batch_matches_individual_casts() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    for (i, x) in [-4.0f32, -1.5, 1.5, 4.0].into_iter().enumerate() {
+        let body = add_body(&mut bodies, Vec3::new(x, 0.0, 5.0 + i as f32));
+        let shape = if i % 2 == 0 {
+            ColliderShape::Sphere { radius: 1.0 }
+        } else {
+            ColliderShape::Box {
+                half_extents: Vec3::splat(0.75),
+            }
+        };
+        add_collider(
+            &mut colliders,
+            body,
+            shape,
+            CollisionFilter::default(),
+            false,
+        );
+    }
+    // A trigger in front of everything must be skipped by the shared query filters.
+    let trigger = add_body(&mut bodies, Vec3::new(0.0, 0.0, 2.0));
+    add_collider(
+        &mut colliders,
+        trigger,
+        ColliderShape::Sphere { radius: 3.0 },
+        CollisionFilter::default(),
+        true,
+    );
+
+    // A fan of 11 rays spans two full packets and a partial one; the axis-aligned ray
+    // exercises zero direction components in the slab test.
+    let rays: Vec<Ray> = (-5..=5)
+        .map(|i| {
+            Ray::new(
+                Vec3::new(0.0, 0.0, -1.0),
+                Vec3::new(i as f32 * 0.1, 0.0, 1.0),
+                30.0,
+            )
+        })
+        .collect();
+    let query = RaycastQuery {
+        ignore_triggers: true,
+        ..RaycastQuery::default()
+    };
+
+    let batch = query.cast_batch(&rays, &colliders, &bodies);
+    assert_eq!(batch.len(), rays.len());
+    for (ray, hit) in rays.iter().zip(&batch) {
+        let single = RaycastQuery {
+            origin: ray.origin,
+            direction: ray.direction,
+            max_distance: ray.max_distance,
+            ..query.clone()
+        };
+        let expected = Raycast::cast(&single, &colliders, &bodies)
+            .into_iter()
+            .next();
+        match (hit, expected) {
+            (Some(hit), Some(expected)) => {
+                assert_eq!(hit.collider_id, expected.collider_id);
+                assert!((hit.distance - expected.distance).abs() < 1e-5);
+            }
+            (None, None) => {}
+            (hit, expected) => panic!("batch {hit:?} disagrees with single cast {expected:?}"),
+        }
+    }
+    assert!(batch.iter().any(|hit| hit.is_some()));
+    assert!(batch.iter().any(|hit| hit.is_none()));
+
+    let short = [Ray::new(
+        Vec3::new(0.0, 0.0, -1.0),
+        Vec3::new(-0.3, 0.0, 1.0),
+        2.0,
+    )];
+    assert!(query.cast_batch(&short, &colliders, &bodies)[0].is_none());
+}
+
+#[test]
+fn raycast_box_normals_face_the_ray() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let body = add_body(&mut bodies, Vec3::ZERO);
+    add_collider(
+        &mut colliders,
+        body,
+        ColliderShape::Box {
+            half_extents: Vec3::ONE,
+        },
+        CollisionFilter::default(),
+        false,
+    );
+
+    for direction in [Vec3::X, -Vec3::X, Vec3::Y, -Vec3::Y, Vec3::Z, -Vec3::Z] {
+        let query = RaycastQuery::new(-direction * 5.0, direction, 20.0);
+        let hits = Raycast::cast(&query, &colliders, &bodies);
+        assert_eq!(hits.len(), 1, "{direction}");
+        assert_eq!(hits[0].normal, -direction, "{direction}");
+        assert!((hits[0].distance - 4.0).abs() < 1e-5, "{direction}");
+    }
+}
+
+#[test]
+fn raycast_hits_sloped_heightfield() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    // Height rises by one unit per column along +X.
+    let heights: Vec<f32> = (0..4).flat_map(|_| (0..4).map(|col| col as f32)).collect();
+    let terrain = Heightfield::new(4, 4, heights, Vec3::ONE);
+    let expected = terrain.height_at(0.25, 0.5).unwrap();
+
+    let ground = add_body(&mut bodies, Vec3::ZERO);
+    let terrain_collider = add_collider(
+        &mut colliders,
+        ground,
+        ColliderShape::Heightfield {
+            heightfield: Arc::new(terrain),
+        },
+        CollisionFilter::default(),
+        false,
+    );
+
+    let query = RaycastQuery::new(Vec3::new(0.25, 10.0, 0.5), -Vec3::Y, 100.0);
+    let hit = Raycast::cast(&query, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("downward ray should hit terrain");
+    assert_eq!(hit.collider_id, terrain_collider);
+    assert!((hit.point.y - expected).abs() < 1e-4);
+    assert!(hit.normal.y > 0.0 && hit.normal.x < 0.0);
+
+    let grazing = RaycastQuery::new(Vec3::new(-5.0, 2.0, 0.5), Vec3::X, 100.0);
+    let hit = Raycast::cast(&grazing, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("horizontal ray should hit the slope");
+    assert!((hit.point.x - 0.5).abs() < 1e-4);
+
+    let miss = RaycastQuery::new(Vec3::new(5.0, 10.0, 0.0), -Vec3::Y, 100.0);
+    assert!(Raycast::cast(&miss, &colliders, &bodies).is_empty());
+}
+
+#[test]
+fn raycast_reports_heightfield_materials_and_skips_holes() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    // 3x3 cells of one unit, centred on the origin; the middle cell is a hole.
+    let mut terrain = Heightfield::flat(4, 4, Vec3::ONE).with_material_indices((0..18).collect());
+    terrain.set_hole(1, 1, true);
+    assert!(terrain.is_hole(1, 1));
+    assert!(terrain.height_at(0.0, 0.0).is_none());
+
+    let ground = add_body(&mut bodies, Vec3::ZERO);
+    add_collider(
+        &mut colliders,
+        ground,
+        ColliderShape::Heightfield {
+            heightfield: Arc::new(terrain),
+        },
+        CollisionFilter::default(),
+        false,
+    );
+
+    let through_hole = RaycastQuery::new(Vec3::new(0.0, 10.0, 0.0), -Vec3::Y, 100.0);
+    assert!(Raycast::cast(&through_hole, &colliders, &bodies).is_empty());
+
+    // Upper triangle of cell (row 1, col 2): id (1 * 3 + 2) * 2 + 1.
+    let query = RaycastQuery::new(Vec3::new(1.2, 10.0, 0.2), -Vec3::Y, 100.0);
+    let hit = Raycast::cast(&query, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("ray beside the hole should hit terrain");
+    assert_eq!(hit.material_index, Some(11));
+
+    // A slanted ray entering over the hole continues to the next solid cell.
+    let slanted = RaycastQuery::new(Vec3::new(0.0, 0.5, 0.0), Vec3::new(1.0, -1.0, 0.0), 100.0);
+    let hit = Raycast::cast(&slanted, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("slanted ray should land past the hole");
+    assert!((hit.point.x - 0.5).abs() < 1e-4);
+}
+
+#[test]
+fn raycast_hits_cone_and_rounded_shapes() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+
+    let cone_body = add_body(&mut bodies, Vec3::ZERO);
+    add_collider(
+        &mut colliders,
+        cone_body,
+        Collider::cone(1.0, 2.0),
+        CollisionFilter::default(),
+        false,
+    );
+    let down = RaycastQuery::new(Vec3::new(0.0, 5.0, 0.0), -Vec3::Y, 100.0);
+    let hit = Raycast::cast(&down, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("ray down the axis should hit the apex");
+    assert!((hit.point.y - 1.0).abs() < 1e-3);
+
+    // Halfway down the slant the surface radius is 0.5.
+    let side = RaycastQuery::new(Vec3::new(5.0, 0.0, 0.0), -Vec3::X, 100.0);
+    let hit = Raycast::cast(&side, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("ray from the side should hit the slant");
+    assert!((hit.point.x - 0.5).abs() < 1e-3);
+    let slant_normal = Vec3::new(2.0, 1.0, 0.0).normalize();
+    assert!((hit.normal - slant_normal).length() < 1e-2);
+
+    let box_body = add_body(&mut bodies, Vec3::new(0.0, 0.0, 10.0));
+    add_collider(
+        &mut colliders,
+        box_body,
+        Collider::rounded_box(Vec3::splat(1.0), 0.5),
+        CollisionFilter::default(),
+        false,
+    );
+    // A diagonal ray towards the centre meets the rounded corner, not the sharp one.
+    let origin = Vec3::new(5.0, 5.0, 15.0);
+    let toward_corner = RaycastQuery::new(origin, Vec3::new(-1.0, -1.0, -1.0), 100.0);
+    let hit = Raycast::cast(&toward_corner, &colliders, &bodies)
+        .into_iter()
+        .next()
+        .expect("diagonal ray should hit the rounded corner");
+    let corner_distance = (origin - Vec3::new(0.0, 0.0, 10.0)).length() - (0.75f32.sqrt() + 0.5);
+    assert!((hit.distance - corner_distance).abs() < 1e-3);
+    assert!((hit.normal - Vec3::ONE.normalize()).length() < 1e-2);
+}
+
+#[test]
+fn query_pipeline_matches_brute_force_casts() {
+    let mut bodies = BodiesSoA::new();
+    let mut colliders = Arena::new();
+    let mut seed = 0x2545_f491_u32;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    for i in 0..200 {
+        let position = Vec3::new(next(), next(), next()) * 40.0 - Vec3::splat(20.0);
+        let body = add_body(&mut bodies, position);
+        let shape = match i % 3 {
+            0 => ColliderShape::Sphere {
+                radius: 0.3 + next(),
+            },
+            1 => ColliderShape::Box {
+                half_extents: Vec3::new(0.2 + next(), 0.2 + next(), 0.2 + next()),
+            },
+            _ => ColliderShape::Capsule {
+                radius: 0.2 + 0.5 * next(),
+                height: 0.5 + next(),
+            },
+        };
+        add_collider(
+            &mut colliders,
+            body,
+            shape,
+            CollisionFilter::default(),
+            i % 7 == 0,
+        );
+    }
+    let floor = add_body(&mut bodies, Vec3::new(0.0, -25.0, 0.0));
+    add_collider(
+        &mut colliders,
+        floor,
+        ColliderShape::Plane { normal: Vec3::Y },
+        CollisionFilter::default(),
+        false,
+    );
+
+    let mut pipeline = QueryPipeline::new();
+    pipeline.update(&colliders, &bodies);
+    assert_eq!(pipeline.len(), 201);
+
+    let mut hit_count = 0;
+    for i in 0..300 {
+        let origin = Vec3::new(next(), next(), next()) * 50.0 - Vec3::splat(25.0);
+        let direction = Vec3::new(next(), next(), next()) * 2.0 - Vec3::ONE;
+        let query = RaycastQuery {
+            ignore_triggers: i % 2 == 0,
+            closest_only: i % 3 != 0,
+            ..RaycastQuery::new(origin, direction, 60.0)
+        };
+        let expected = Raycast::cast(&query, &colliders, &bodies);
+        let hits = pipeline.cast_ray(&query, &colliders);
+        hit_count += hits.len();
+        assert_eq!(
+            hits.iter()
+                .map(|hit| (hit.collider_id, hit.distance))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|hit| (hit.collider_id, hit.distance))
+                .collect::<Vec<_>>(),
+        );
+    }
+    assert!(hit_count > 100);
+
+    // Batches walk the same tree a packet at a time; 102 rays leave a partial last packet.
+    let query = RaycastQuery {
+        ignore_triggers: true,
+        ..RaycastQuery::default()
+    };
+    let rays: Vec<Ray> = (0..102)
+        .map(|_| {
+            let origin = Vec3::new(next(), next(), next()) * 50.0 - Vec3::splat(25.0);
+            let direction = Vec3::new(next(), next(), next()) * 2.0 - Vec3::ONE;
+            Ray::new(origin, direction, 60.0)
+        })
+        .collect();
+    let batch = pipeline.cast_batch(&query, &rays, &colliders);
+    assert_eq!(batch.len(), rays.len());
+    for (ray, hit) in rays.iter().zip(&batch) {
+        let single = RaycastQuery {
+            origin: ray.origin,
+            direction: ray.direction,
+            max_distance: ray.max_distance,
+            ..query.clone()
+        };
+        let expected = Raycast::cast(&single, &colliders, &bodies)
+            .into_iter()
+            .next();
+        match (hit, expected) {
+            (Some(hit), Some(expected)) => {
+                assert_eq!(hit.collider_id, expected.collider_id);
+                assert!((hit.distance - expected.distance).abs() < 1e-4);
+            }
+            (None, None) => {}
+            (hit, expected) => panic!("batch {hit:?} disagrees with single cast {expected:?}"),
+        }
+    }
+    assert!(batch.iter().filter(|hit| hit.is_some()).count() > 20);
+
+    let region = Aabb::new(Vec3::new(-5.0, -5.0, -5.0), Vec3::new(5.0, 5.0, 5.0));
+    let mut overlapping = pipeline.intersect_aabb(&region, &colliders);
+    overlapping.sort();
+    let mut expected: Vec<EntityId> = colliders
+        .iter()
+        .filter(|collider| !matches!(collider.shape, ColliderShape::Plane { .. }))
+        .filter(|collider| {
+            let center = bodies
+                .get(collider.rigidbody_id)
+                .unwrap()
+                .transform()
+                .position;
+            let radius = BroadPhase::get_collider_radius(&collider.shape);
+            region.overlaps(&Aabb::new(
+                center - Vec3::splat(radius),
+                center + Vec3::splat(radius),
+            ))
+        })
+        .map(|collider| collider.id)
+        .collect();
+    expected.sort();
+    assert!(!expected.is_empty());
+    assert_eq!(overlapping, expected);
+    let below = Aabb::new(Vec3::new(-1.0, -26.0, -1.0), Vec3::new(1.0, -24.5, 1.0));
+    assert!(pipeline
+        .intersect_aabb(&below, &colliders)
+        .contains(&colliders.iter().last().unwrap().id));
+}
+
+#[test]
+fn world_refreshes_its_query_pipeline_every_step() {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let body = world.add_rigidbody(add_body_at(Vec3::new(0.0, 10.0, 0.0)));
+    let collider = world.add_collider(Collider {
+        rigidbody_id: body,
+        ..Collider::builder().sphere(0.5).build()
+    });
+    let down = RaycastQuery::new(Vec3::new(0.0, 20.0, 0.0), Vec3::NEG_Y, 100.0);
+    assert!(world.query_pipeline().is_empty());
+    world.update_query_pipeline();
+    let before = world.query_pipeline().cast_ray(&down, &world.colliders)[0].distance;
+    assert!((before - 9.5).abs() < 1e-4);
+
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let hits = world.query_pipeline().cast_ray(&down, &world.colliders);
+    assert_eq!(hits[0].collider_id, collider);
+    let height = world.body(body).unwrap().transform().position.y;
+    assert!(height < 9.9);
+    assert!((hits[0].distance - (19.5 - height)).abs() < 1e-4);
+}
@@ -42,6 +42,8 @@ fn speculative_contact_prevents_penetration() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(wall_collider);
 
@@ -77,6 +79,7 @@ fn speculative_contact_prevents_penetration() {
     let mut body = RigidBody::new(EntityId::from_index(2));
     body.transform.position = Vec3::new(0.0, 0.0, 0.9);
     body.velocity.linear = Vec3::new(0.0, 0.0, 12.0);
+    body.ccd_enabled = true;
     body.inverse_mass = 1.0;
     body.material.restitution = 0.0;
     let body_id = world.add_rigidbody(body);
@@ -88,6 +91,8 @@ fn speculative_contact_prevents_penetration() {
         offset: Transform::default(),
         is_trigger: false,
         collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
     };
     world.add_collider(collider);
 
@@ -112,3 +117,51 @@ fn speculative_contact_prevents_penetration() {
         final_body.transform().position.z
     );
 }
+
+#[test]
+fn bodies_without_ccd_get_no_speculative_contacts() {
+    let mut world = make_world();
+    world.set_ccd_enabled(false);
+
+    let mut wall_body = RigidBody::new(EntityId::from_index(1));
+    wall_body.transform.position = Vec3::new(0.0, 0.0, 2.0);
+    wall_body.is_static = true;
+    let wall_id = world.add_rigidbody(wall_body);
+    world.add_collider(Collider {
+        id: EntityId::from_index(101),
+        rigidbody_id: wall_id,
+        shape: ColliderShape::Box {
+            half_extents: Vec3::new(5.0, 5.0, 0.5),
+        },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    });
+
+    // Same approach as above, but the sphere has not opted into CCD.
+    let mut body = RigidBody::new(EntityId::from_index(2));
+    body.transform.position = Vec3::new(0.0, 0.0, 0.9);
+    body.velocity.linear = Vec3::new(0.0, 0.0, 12.0);
+    body.material.restitution = 0.0;
+    let body_id = world.add_rigidbody(body);
+    world.add_collider(Collider {
+        id: EntityId::from_index(102),
+        rigidbody_id: body_id,
+        shape: ColliderShape::Sphere { radius: 0.5 },
+        offset: Transform::default(),
+        is_trigger: false,
+        collision_filter: CollisionFilter::default(),
+        margin: 0.0,
+        density: None,
+    });
+
+    world.step(1.0 / 60.0);
+
+    let z = world.body(body_id).unwrap().transform().position.z;
+    assert!(
+        z > 1.05,
+        "Sphere should reach the wall before any contact, Z={z}"
+    );
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "serde")]
+
+use particle_accelerator::core::constraints::Joint;
+use particle_accelerator::*;
+
+/// A box stack on the ground, a pendulum and some debris, all still in motion.
+fn make_scene() -> (PhysicsWorld, Vec<EntityId>) {
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut ground = RigidBody::new(EntityId::default());
+    ground.is_static = true;
+    let ground = world.add_rigidbody(ground);
+    world.add_collider(Collider {
+        rigidbody_id: ground,
+        ..Collider::builder()
+            .box_shape(Vec3::new(10.0, 0.5, 10.0))
+            .build()
+    });
+
+    let mut ids = Vec::new();
+    for level in 0..3 {
+        let mut body = RigidBody::new(EntityId::default());
+        body.transform.position = Vec3::new(0.1 * level as f32, 1.2 + 1.05 * level as f32, 0.0);
+        let id = world.add_rigidbody(body);
+        world.add_collider(Collider {
+            rigidbody_id: id,
+            ..Collider::builder().box_shape(Vec3::splat(0.5)).build()
+        });
+        ids.push(id);
+    }
+
+    let mut bob = RigidBody::new(EntityId::default());
+    bob.transform.position = Vec3::new(5.0, 4.0, 0.0);
+    let bob = world.add_rigidbody(bob);
+    let mut pivot = RigidBody::new(EntityId::default());
+    pivot.is_static = true;
+    pivot.transform.position = Vec3::new(3.0, 4.0, 0.0);
+    let pivot = world.add_rigidbody(pivot);
+    world.add_joint(Joint::Distance {
+        body_a: pivot,
+        body_b: bob,
+        distance: 2.0,
+    });
+    ids.push(bob);
+
+    for i in 0..4 {
+        world
+            .particles
+            .spawn(Particle::new(Vec3::new(-2.0, 2.0 + i as f32, 0.0)));
+    }
+    (world, ids)
+}
+
+fn poses(world: &PhysicsWorld, ids: &[EntityId]) -> Vec<(Vec3, Quat, Vec3)> {
+    let mut poses: Vec<_> = ids
+        .iter()
+        .map(|&id| {
+            let body = world.body(id).unwrap();
+            let transform = body.transform();
+            (
+                transform.position,
+                transform.rotation,
+                body.velocity().linear,
+            )
+        })
+        .collect();
+    poses.extend(
+        world
+            .particles
+            .iter()
+            .map(|(_, position)| (position, Quat::IDENTITY, Vec3::ZERO)),
+    );
+    poses
+}
+
+#[test]
+fn restored_state_steps_on_exactly_like_the_original() {
+    let (mut world, ids) = make_scene();
+    for _ in 0..45 {
+        world.step(1.0 / 60.0);
+    }
+    let json = serde_json::to_string(&world.save_state()).unwrap();
+    for _ in 0..45 {
+        world.step(1.0 / 60.0);
+    }
+    let expected = poses(&world, &ids);
+
+    let state: WorldState = serde_json::from_str(&json).unwrap();
+    assert_eq!(state.version(), WORLD_STATE_VERSION);
+    assert_eq!(state.frame_index(), 45);
+
+    // Into a freshly built world, as when loading a save game.
+    let (mut loaded, _) = make_scene();
+    assert!(loaded.restore_state(&state));
+    for _ in 0..45 {
+        loaded.step(1.0 / 60.0);
+    }
+    assert_eq!(poses(&loaded, &ids), expected);
+
+    // And back into the world that saved it.
+    assert!(world.restore_state(&state));
+    for _ in 0..45 {
+        world.step(1.0 / 60.0);
+    }
+    assert_eq!(poses(&world, &ids), expected);
+
+    let future = json.replacen(
+        &format!("\"version\":{WORLD_STATE_VERSION}"),
+        "\"version\":9999",
+        1,
+    );
+    let future: WorldState = serde_json::from_str(&future).unwrap();
+    assert!(!world.restore_state(&future));
+    assert_eq!(poses(&world, &ids), expected);
+}
+
+#[test]
+fn restored_state_brings_its_time_step_to_the_integrator() {
+    let (mut world, ids) = make_scene();
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let state = world.save_state();
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    let expected = poses(&world, &ids);
+
+    let (mut loaded, _) = make_scene();
+    // Built for a finer step than the one the state was saved at.
+    loaded.time_step = 1.0 / 120.0;
+    loaded.integrator.dt = 1.0 / 120.0 / loaded.integrator.substeps as f32;
+    assert!(loaded.restore_state(&state));
+    assert_eq!(loaded.time_step, 1.0 / 60.0);
+    assert_eq!(
+        loaded.integrator.dt,
+        1.0 / 60.0 / loaded.integrator.substeps as f32
+    );
+    for _ in 0..30 {
+        loaded.step(1.0 / 60.0);
+    }
+    assert_eq!(poses(&loaded, &ids), expected);
+}
@@ -26,3 +26,207 @@ fn test_shared_physics_world_across_threads() {
         handle.join().unwrap();
     }
 }
+
+#[test]
+fn step_async_exposes_previous_state_and_matches_step() {
+    use particle_accelerator::{EntityId, RigidBody, Vec3};
+
+    let make_world = || {
+        let mut world = PhysicsWorld::new(1.0 / 60.0);
+        let mut body = RigidBody::new(EntityId::from_index(0));
+        body.transform.position = Vec3::new(0.0, 10.0, 0.0);
+        let id = world.add_rigidbody(body);
+        (world, id)
+    };
+    let (mut sync_world, sync_id) = make_world();
+    let (mut async_world, async_id) = make_world();
+
+    for _ in 0..3 {
+        sync_world.step(1.0 / 60.0);
+
+        let before = async_world.body(async_id).unwrap().transform().position;
+        let handle = async_world.step_async(1.0 / 60.0);
+        let snapshot = handle
+            .snapshot()
+            .body(async_id)
+            .unwrap()
+            .transform()
+            .position;
+        assert_eq!(snapshot, before);
+        handle.join();
+    }
+
+    let expected = sync_world.body(sync_id).unwrap().transform().position;
+    let actual = async_world.body(async_id).unwrap().transform().position;
+    assert_eq!(actual, expected);
+    assert!(actual.y < 10.0);
+}
+
+#[test]
+fn step_async_hands_a_panicking_step_back_to_the_caller() {
+    use particle_accelerator::{EntityId, RigidBody};
+
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.add_rigidbody(RigidBody::new(EntityId::from_index(0)));
+    world.add_pre_step_hook(|_, _| panic!("hook failed"));
+
+    let payload = world
+        .step_async(1.0 / 60.0)
+        .try_join()
+        .expect_err("the hook panics");
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"hook failed"));
+
+    // The stepped world is lost, but the one left in place steps normally.
+    assert_eq!(world.bodies.len(), 0);
+    world.step_async(1.0 / 60.0).join();
+    world.add_pre_step_hook(|_, _| panic!("hook failed again"));
+    // Dropping the handle logs the panic instead of unwinding through `Drop`.
+    drop(world.step_async(1.0 / 60.0));
+
+    world.add_pre_step_hook(|_, _| panic!("resumed"));
+    let resumed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        world.step_async(1.0 / 60.0).join();
+    }));
+    assert!(resumed.is_err());
+}
+
+#[test]
+fn step_async_reuses_pooled_worker_threads() {
+    use std::collections::HashSet;
+
+    const STEPS: usize = 128;
+    let workers = Arc::new(Mutex::new(HashSet::new()));
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let seen = Arc::clone(&workers);
+    world.add_pre_step_hook(move |_, _| {
+        seen.lock().unwrap().insert(thread::current().id());
+    });
+    for _ in 0..STEPS {
+        world.step_async(1.0 / 60.0).join();
+    }
+    // A fresh thread per step would leave one id per step.
+    let workers = workers.lock().unwrap();
+    assert!(!workers.contains(&thread::current().id()));
+    assert!(workers.len() < STEPS, "{} worker threads", workers.len());
+}
+
+#[test]
+fn oversized_islands_are_counted_and_split_for_parallel_solve() {
+    use particle_accelerator::{Collider, EntityId, RigidBody, Vec3};
+
+    let mut world = PhysicsWorld::builder()
+        .gravity(Vec3::ZERO)
+        .parallel(true)
+        .build();
+    world.set_max_island_size(Some(4));
+
+    // A row of slightly overlapping spheres forms a single island.
+    let mut ids = Vec::new();
+    for i in 0..12 {
+        let mut body = RigidBody::new(EntityId::from_index(i));
+        body.transform.position = Vec3::new(i as f32 * 0.9, 0.0, 0.0);
+        let id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+        ids.push(id);
+    }
+
+    world.step(1.0 / 60.0);
+
+    let metrics = world.last_solver_metrics();
+    assert_eq!(metrics.largest_island, 12);
+    assert_eq!(metrics.oversized_islands, 1);
+    if cfg!(feature = "parallel") {
+        assert_eq!(metrics.islands_solved, 3);
+    }
+
+    // Bodies on either side of a partition boundary are still pushed apart.
+    let x = |i: usize| world.body(ids[i]).unwrap().transform().position.x;
+    assert!(x(4) - x(3) > 0.9, "boundary pair did not separate");
+    assert!(x(8) - x(7) > 0.9, "boundary pair did not separate");
+}
+
+#[test]
+fn parallel_narrowphase_matches_serial_contacts() {
+    use particle_accelerator::{Collider, EntityId, RigidBody, Vec3};
+
+    let build = |parallel: bool| {
+        let mut world = PhysicsWorld::builder()
+            .gravity(Vec3::ZERO)
+            .parallel(parallel)
+            .build();
+        // A loose grid of overlapping spheres and boxes, plus a trigger over its centre.
+        for i in 0..48 {
+            let mut body = RigidBody::new(EntityId::from_index(i));
+            body.transform.position = Vec3::new(
+                (i % 4) as f32 * 0.9,
+                (i / 16) as f32 * 0.9,
+                ((i / 4) % 4) as f32 * 0.9,
+            );
+            let id = world.add_rigidbody(body);
+            let mut collider = if i % 3 == 0 {
+                Collider::builder().box_shape(Vec3::splat(0.45)).build()
+            } else {
+                Collider::builder().sphere(0.5).build()
+            };
+            collider.rigidbody_id = id;
+            world.add_collider(collider);
+        }
+        let mut sensor = RigidBody::new(EntityId::from_index(48));
+        sensor.is_static = true;
+        sensor.transform.position = Vec3::splat(1.35);
+        let sensor = world.add_rigidbody(sensor);
+        let mut trigger = Collider::builder().sphere(1.0).is_trigger(true).build();
+        trigger.rigidbody_id = sensor;
+        world.add_collider(trigger);
+        world
+    };
+
+    let mut serial = build(false);
+    let mut parallel = build(true);
+    let serial_contacts = serial.collect_contacts();
+    let parallel_contacts = parallel.collect_contacts();
+    assert!(!serial_contacts.is_empty());
+    assert_eq!(serial_contacts.len(), parallel_contacts.len());
+    for (a, b) in serial_contacts.iter().zip(&parallel_contacts) {
+        assert_eq!((a.body_a, a.body_b), (b.body_a, b.body_b));
+        assert_eq!(a.point, b.point);
+        assert_eq!(a.normal, b.normal);
+        assert_eq!(a.depth, b.depth);
+    }
+
+    serial.step(1.0 / 60.0);
+    parallel.step(1.0 / 60.0);
+    assert!(!serial.trigger_events().is_empty());
+    assert_eq!(serial.trigger_events(), parallel.trigger_events());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn arena_par_iter_mut_updates_every_live_item_once() {
+    use particle_accelerator::Arena;
+    use rayon::prelude::*;
+
+    let mut arena = Arena::new();
+    let ids: Vec<_> = (0..1000).map(|value| arena.insert(value)).collect();
+    for id in ids.iter().step_by(3) {
+        arena.remove(*id);
+    }
+    let reused = arena.insert(5000);
+
+    arena
+        .par_iter_mut()
+        .for_each(|(id, value)| *value += id.index() as i32);
+
+    let mut seen: Vec<_> = arena.par_iter().map(|(id, value)| (id, *value)).collect();
+    seen.sort();
+    let expected: Vec<_> = arena
+        .ids()
+        .map(|id| (id, *arena.get(id).unwrap()))
+        .collect();
+    assert_eq!(seen, expected);
+    assert_eq!(seen.len(), arena.len());
+    assert_eq!(arena.get(reused), Some(&5000));
+    assert_eq!(arena.get(ids[1]), Some(&2));
+}
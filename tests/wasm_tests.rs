@@ -0,0 +1,32 @@
+#![cfg(feature = "wasm")]
+
+use particle_accelerator::wasm::WasmWorld;
+
+#[test]
+fn wasm_world_steps_and_reads_back_transforms() {
+    let mut world = WasmWorld::new(1.0 / 60.0);
+    let ground = world.add_body(0.0, 0.0, 0.0, 0.0, true);
+    let ball = world.add_body(0.0, 5.0, 0.0, 1.0, false);
+    assert!(world.add_box_collider(ground, 5.0, 0.5, 5.0).is_some());
+    assert!(world.add_sphere_collider(ball, 0.5).is_some());
+    assert!(world.add_sphere_collider(99, 0.5).is_none());
+    assert!(world.set_linear_velocity(ball, 1.0, 0.0, 0.0));
+
+    for _ in 0..10 {
+        world.step(1.0 / 60.0);
+    }
+
+    assert_eq!(world.body_count(), 2);
+    assert_eq!(world.body_ids(), vec![ground, ball]);
+
+    let mut positions = [0.0f32; 6];
+    let mut rotations = [0.0f32; 8];
+    assert_eq!(world.read_transforms(&mut positions, &mut rotations), 2);
+    assert_eq!(&positions[..3], &[0.0, 0.0, 0.0]);
+    assert!(positions[3] > 0.0, "ball keeps its horizontal velocity");
+    assert!(positions[4] < 5.0, "ball falls under gravity");
+    assert_eq!(rotations[7], 1.0);
+
+    let mut short = [0.0f32; 3];
+    assert_eq!(world.read_transforms(&mut short, &mut rotations), 1);
+}
@@ -0,0 +1,455 @@
+#![cfg(feature = "wgpu")]
+
+use particle_accelerator::gpu::{
+    BroadphaseMode, GpuBody, GpuParticle, ParticleBuffer, WgpuBackend,
+};
+use particle_accelerator::{ComputeBackend, GpuWorldState, Vec3};
+
+/// Machines without a GPU (or a software adapter) can't run these tests.
+fn backend() -> Option<WgpuBackend> {
+    let backend = WgpuBackend::new();
+    if backend.is_none() {
+        eprintln!("no wgpu adapter available; skipping");
+    }
+    backend
+}
+
+fn state(bodies: &[(Vec3, f32, Vec3, f32)]) -> GpuWorldState {
+    GpuWorldState {
+        bodies: bodies
+            .iter()
+            .map(|&(position, radius, velocity, inverse_mass)| GpuBody {
+                position,
+                radius,
+                velocity,
+                inverse_mass,
+            })
+            .collect(),
+        time_step: 1.0 / 60.0,
+        ..Default::default()
+    }
+}
+
+/// Pairs the broad phase should report for `state`, in order.
+fn brute_force_pairs(state: &GpuWorldState) -> Vec<(u32, u32)> {
+    let mut expected = Vec::new();
+    for i in 0..state.bodies.len() {
+        for j in i + 1..state.bodies.len() {
+            let (a, b) = (&state.bodies[i], &state.bodies[j]);
+            let reach = Vec3::splat(a.radius + b.radius);
+            let both_static = a.inverse_mass == 0.0 && b.inverse_mass == 0.0;
+            if !both_static && (a.position - b.position).abs().cmple(reach).all() {
+                expected.push((i as u32, j as u32));
+            }
+        }
+    }
+    expected
+}
+
+fn broadphase_pairs(backend: &WgpuBackend, state: &GpuWorldState) -> Vec<(u32, u32)> {
+    backend.prepare_step(state);
+    let mut pairs = backend.dispatch_broadphase(state).unwrap().into_inner();
+    pairs.sort_unstable();
+    pairs
+}
+
+#[test]
+fn wgpu_broadphase_matches_brute_force() {
+    let Some(mut backend) = backend() else {
+        return;
+    };
+    backend.set_broadphase_mode(BroadphaseMode::BruteForce);
+    // A jittered lattice dense enough to overflow the initial pair buffer.
+    let mut bodies = Vec::new();
+    for i in 0..400u32 {
+        let position = Vec3::new(
+            (i % 10) as f32 * 0.7,
+            (i / 10 % 10) as f32 * 0.7,
+            (i / 100) as f32 * 0.7 + (i % 3) as f32 * 0.05,
+        );
+        let inverse_mass = if i % 50 == 0 { 0.0 } else { 1.0 };
+        bodies.push((position, 0.5, Vec3::ZERO, inverse_mass));
+    }
+    let state = state(&bodies);
+
+    let expected = brute_force_pairs(&state);
+    assert!(expected.len() > bodies.len() * 4);
+    assert_eq!(broadphase_pairs(&backend, &state), expected);
+
+    backend.set_broadphase_mode(BroadphaseMode::Lbvh);
+    assert_eq!(broadphase_pairs(&backend, &state), expected);
+}
+
+#[test]
+fn wgpu_lbvh_matches_brute_force_on_scattered_bodies() {
+    let Some(mut backend) = backend() else {
+        return;
+    };
+    // Pseudo-random sizes and positions, with clusters sharing a Morton cell, a static
+    // floor and a count that isn't a power of two.
+    let mut seed = 0x2545_f491_u32;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    let mut bodies = Vec::new();
+    for i in 0..5000u32 {
+        let position = if i % 7 == 0 {
+            Vec3::splat(5.0) + Vec3::new(next(), next(), next()) * 1e-4
+        } else {
+            Vec3::new(next() * 60.0, next() * 20.0, next() * 60.0)
+        };
+        let radius = 0.2 + next() * 0.8;
+        bodies.push((position, radius, Vec3::ZERO, 1.0));
+    }
+    bodies.push((Vec3::new(30.0, -50.0, 30.0), 50.0, Vec3::ZERO, 0.0));
+    let state = state(&bodies);
+
+    let expected = brute_force_pairs(&state);
+    for mode in [
+        BroadphaseMode::Lbvh,
+        BroadphaseMode::BruteForce,
+        BroadphaseMode::Auto,
+    ] {
+        backend.set_broadphase_mode(mode);
+        assert_eq!(broadphase_pairs(&backend, &state), expected, "{mode:?}");
+    }
+}
+
+#[test]
+fn wgpu_presolve_pushes_overlapping_bodies_apart() {
+    let Some(mut backend) = backend() else {
+        return;
+    };
+    backend.set_presolve_iterations(8);
+    let state = state(&[
+        (Vec3::new(0.0, 0.0, 0.0), 0.5, Vec3::new(1.0, 0.0, 0.0), 1.0),
+        (
+            Vec3::new(0.8, 0.0, 0.0),
+            0.5,
+            Vec3::new(-1.0, 0.0, 0.0),
+            1.0,
+        ),
+        (Vec3::new(0.0, -1.4, 0.0), 1.0, Vec3::ZERO, 0.0),
+        (
+            Vec3::new(10.0, 0.0, 0.0),
+            0.5,
+            Vec3::new(0.0, 2.0, 0.0),
+            1.0,
+        ),
+    ]);
+
+    backend.prepare_step(&state);
+    let velocities = backend.dispatch_solver(&state).unwrap().into_inner();
+    assert_eq!(velocities.len(), 4);
+
+    // The head-on pair stops approaching and separates.
+    assert!(velocities[1].x - velocities[0].x > 0.0, "{velocities:?}");
+    // The first body also sits on the static one and gets pushed up off it.
+    assert!(velocities[0].y > 0.0, "{velocities:?}");
+    // Static and untouched bodies keep their velocity.
+    assert_eq!(velocities[2], Vec3::ZERO);
+    assert_eq!(velocities[3], Vec3::new(0.0, 2.0, 0.0));
+}
+
+#[test]
+fn wgpu_readbacks_complete_out_of_order() {
+    let Some(backend) = backend() else {
+        return;
+    };
+    let near = state(&[
+        (Vec3::ZERO, 0.5, Vec3::ZERO, 1.0),
+        (Vec3::new(0.8, 0.0, 0.0), 0.5, Vec3::ZERO, 1.0),
+    ]);
+    let far = state(&[
+        (Vec3::ZERO, 0.5, Vec3::ZERO, 1.0),
+        (Vec3::new(5.0, 0.0, 0.0), 0.5, Vec3::ZERO, 1.0),
+    ]);
+
+    backend.prepare_step(&near);
+    let mut first = backend.dispatch_broadphase(&near).unwrap();
+    // The first pass still holds its buffers, so the next state goes to fresh ones.
+    backend.prepare_step(&far);
+    let second = backend.dispatch_broadphase(&far).unwrap();
+
+    assert_eq!(second.into_inner(), Vec::new());
+    while !first.is_ready() {
+        std::thread::yield_now();
+    }
+    assert_eq!(first.poll(), Some(&vec![(0, 1)]));
+}
+
+#[test]
+fn world_exposes_wgpu_results_by_body() {
+    use particle_accelerator::{Collider, EntityId, PhysicsWorld, RigidBody};
+
+    let Some(backend) = backend() else {
+        return;
+    };
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    world.gravity = Vec3::ZERO;
+    let mut ids = Vec::new();
+    for x in [0.0, 0.8, 5.0] {
+        let mut body = RigidBody::new(EntityId::from_index(0));
+        body.transform.position = Vec3::new(x, 0.0, 0.0);
+        let id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().sphere(0.5).build();
+        collider.rigidbody_id = id;
+        world.add_collider(collider);
+        ids.push(id);
+    }
+    assert_eq!(world.gpu_broadphase_pairs(), None);
+
+    world.set_gpu_backend(backend);
+    world.step(1.0 / 60.0);
+    assert_eq!(world.gpu_broadphase_pairs(), Some(vec![(ids[0], ids[1])]));
+    let velocities = world.gpu_solver_velocities().unwrap();
+    assert_eq!(velocities.len(), 3);
+    assert_eq!(velocities[2], (ids[2], Vec3::ZERO));
+}
+
+/// Copies the particle buffer back, which a renderer drawing from it never has to do.
+fn read_particles(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    buffer: &ParticleBuffer,
+) -> Vec<GpuParticle> {
+    let (particles, slots) = buffer.get().expect("particles were stepped");
+    let size = u64::from(slots) * 32;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: None,
+        size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&Default::default());
+    encoder.copy_buffer_to_buffer(&particles, 0, &readback, 0, size);
+    queue.submit([encoder.finish()]);
+    readback.slice(..).map_async(wgpu::MapMode::Read, |_| {});
+    device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+    let bytes = readback.slice(..).get_mapped_range().unwrap().to_vec();
+    let floats: Vec<f32> = bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    floats
+        .chunks_exact(8)
+        .map(|p| GpuParticle {
+            position: Vec3::new(p[0], p[1], p[2]),
+            radius: p[3],
+            velocity: Vec3::new(p[4], p[5], p[6]),
+            inverse_mass: p[7],
+        })
+        .collect()
+}
+
+#[test]
+fn wgpu_steps_particles_without_readback() {
+    use particle_accelerator::{Collider, EntityId, Particle, PhysicsWorld, RigidBody};
+
+    let Some(backend) = backend() else {
+        return;
+    };
+    // The renderer's side: the device, queue and particle buffer outlive the hand-over.
+    let (device, queue) = (backend.device().clone(), backend.queue().clone());
+    let buffer = backend.particle_buffer();
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut floor = RigidBody::new(EntityId::from_index(0));
+    floor.is_static = true;
+    floor.transform.position = Vec3::new(0.0, -0.5, 0.0);
+    let floor_id = world.add_rigidbody(floor);
+    let mut collider = Collider::builder()
+        .box_shape(Vec3::new(20.0, 0.5, 20.0))
+        .build();
+    collider.rigidbody_id = floor_id;
+    world.add_collider(collider);
+
+    world.particles.gpu = true;
+    world.particles.restitution = 0.0;
+    let dropped = world
+        .particles
+        .spawn(Particle::new(Vec3::new(0.0, 2.0, 0.0)).radius(0.1));
+    let left = world.particles.spawn(
+        Particle::new(Vec3::new(-1.0, 0.1, 5.0))
+            .radius(0.1)
+            .velocity(Vec3::new(2.0, 0.0, 0.0)),
+    );
+    let right = world.particles.spawn(
+        Particle::new(Vec3::new(1.0, 0.1, 5.0))
+            .radius(0.1)
+            .velocity(Vec3::new(-2.0, 0.0, 0.0)),
+    );
+    let doomed = world
+        .particles
+        .spawn(Particle::new(Vec3::new(8.0, 3.0, 0.0)));
+    let pushed = world
+        .particles
+        .spawn(Particle::new(Vec3::new(-8.0, 10.0, 0.0)));
+
+    world.set_gpu_backend(backend);
+    for _ in 0..30 {
+        world.step(1.0 / 60.0);
+    }
+    assert!(world.particles.is_gpu_resident());
+    world.particles.despawn(doomed);
+    world
+        .particles
+        .set_velocity(pushed, Vec3::new(0.0, 0.0, 3.0));
+    for _ in 0..90 {
+        world.step(1.0 / 60.0);
+    }
+
+    // The CPU copies were never updated.
+    assert_eq!(
+        world.particles.position(dropped),
+        Some(Vec3::new(0.0, 2.0, 0.0))
+    );
+
+    let particles = read_particles(&device, &queue, &buffer);
+    assert_eq!(particles.len(), 5);
+    // The dropped particle came to rest on the floor.
+    let rest = particles[dropped.index()];
+    assert!((rest.position.y - 0.1).abs() < 0.02, "{rest:?}");
+    // The head-on pair collided instead of passing through each other.
+    let (a, b) = (particles[left.index()], particles[right.index()]);
+    assert!(a.position.x < b.position.x, "{a:?} {b:?}");
+    assert!(b.position.x - a.position.x >= 0.19, "{a:?} {b:?}");
+    // Despawns and new velocities made it to the GPU.
+    assert!(!particles[doomed.index()].is_alive());
+    let pushed = particles[pushed.index()];
+    assert!(pushed.position.z > 3.0, "{pushed:?}");
+}
+
+#[test]
+fn wgpu_raycasts_match_cpu_batch() {
+    use particle_accelerator::collision::broadphase::SpeculativeExpansion;
+    use particle_accelerator::{
+        Collider, ColliderShape, EntityId, PhysicsWorld, Ray, RaycastQuery, RigidBody,
+    };
+
+    let Some(backend) = backend() else {
+        return;
+    };
+    let mut world = PhysicsWorld::new(1.0 / 60.0);
+    let mut add = |position: Vec3, shape: ColliderShape, trigger: bool| {
+        let mut body = RigidBody::new(EntityId::from_index(0));
+        body.is_static = true;
+        body.transform.position = position;
+        let body_id = world.add_rigidbody(body);
+        let mut collider = Collider::builder().shape(shape).is_trigger(trigger).build();
+        collider.rigidbody_id = body_id;
+        world.add_collider(collider)
+    };
+    let mut seed = 0x9e37_79b9_u32;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        seed as f32 / u32::MAX as f32
+    };
+    add(Vec3::ZERO, Collider::plane(Vec3::Y), false);
+    for x in 0..12 {
+        for z in 0..12 {
+            let position = Vec3::new(x as f32 * 3.0, 1.0 + next() * 4.0, z as f32 * 3.0);
+            let size = 0.4 + next() * 0.8;
+            let shape = match (x + z) % 6 {
+                0 => Collider::sphere(size),
+                1 => Collider::cuboid(Vec3::new(size, size * 0.5, size * 1.2)),
+                2 => ColliderShape::Capsule {
+                    radius: size * 0.5,
+                    height: size * 2.0,
+                },
+                3 => ColliderShape::Cylinder {
+                    radius: size,
+                    height: size,
+                },
+                4 => Collider::cone(size, size * 2.0),
+                _ => Collider::rounded_box(Vec3::splat(size), size * 0.3),
+            };
+            add(position, shape, (x * z) % 11 == 5);
+        }
+    }
+    // Only the CPU can test the heightfield exactly; rays that reach it are cast again.
+    let terrain = add(
+        Vec3::new(40.0, 0.5, 0.0),
+        Collider::heightfield(4, 4, [0.0, 1.0, 0.5, 0.0].repeat(4), Vec3::splat(2.0)),
+        false,
+    );
+
+    let mut rays: Vec<Ray> = (0..4000)
+        .map(|_| {
+            let origin = Vec3::new(
+                next() * 50.0 - 3.0,
+                12.0 + next() * 5.0,
+                next() * 40.0 - 3.0,
+            );
+            let aim = Vec3::new(next() * 50.0 - 3.0, next() * 4.0, next() * 40.0 - 3.0);
+            Ray::new(origin, aim - origin, 20.0 + next() * 10.0)
+        })
+        .collect();
+    // Unbounded rays that leave the scene upwards miss everything, heightfield included.
+    let misses: Vec<Ray> = (0..64)
+        .map(|_| {
+            let origin = Vec3::new(next() * 50.0 - 3.0, 12.0, next() * 40.0 - 3.0);
+            Ray::new(
+                origin,
+                Vec3::new(next() - 0.5, 1.0, next() - 0.5),
+                f32::INFINITY,
+            )
+        })
+        .collect();
+
+    // The raw kernel output must report the misses as misses, not as rays for the CPU.
+    let mut state = GpuWorldState::new();
+    state.sync_bodies(
+        &world.bodies,
+        &world.colliders,
+        world.time_step,
+        SpeculativeExpansion::default(),
+    );
+    let raw = backend
+        .dispatch_raycasts(&state, &RaycastQuery::default(), &misses)
+        .expect("wgpu backend should cast rays")
+        .into_inner();
+    assert_eq!(raw.len(), misses.len());
+    for (index, hit) in raw.iter().enumerate() {
+        assert!(hit.is_none(), "ray {index}: {hit:?}");
+    }
+
+    rays.extend(misses);
+    world.set_gpu_backend(backend);
+    for query in [
+        RaycastQuery::default(),
+        RaycastQuery {
+            ignore_triggers: true,
+            ..RaycastQuery::default()
+        },
+    ] {
+        let expected = world.raycast_batch(&query, &rays);
+        let hits = world.gpu_raycast_batch(&query, &rays).unwrap();
+        assert_eq!(hits.len(), rays.len());
+        assert!(expected
+            .iter()
+            .any(|hit| hit.as_ref().is_some_and(|hit| hit.collider_id == terrain)));
+        for (index, (hit, expected)) in hits.iter().zip(&expected).enumerate() {
+            match (hit, expected) {
+                (None, None) => {}
+                (Some(hit), Some(expected)) => {
+                    assert_eq!(hit.collider_id, expected.collider_id, "ray {index}");
+                    assert_eq!(hit.body_id, expected.body_id, "ray {index}");
+                    assert!(
+                        (hit.distance - expected.distance).abs() < 1e-3,
+                        "ray {index}: {hit:?} {expected:?}"
+                    );
+                    assert!(
+                        hit.normal.dot(expected.normal) > 0.99,
+                        "ray {index}: {hit:?} {expected:?}"
+                    );
+                }
+                _ => panic!("ray {index}: {hit:?} {expected:?}"),
+            }
+        }
+    }
+}